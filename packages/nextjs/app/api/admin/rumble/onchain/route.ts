@@ -166,7 +166,7 @@ export async function POST(request: Request) {
         const fighterWallets = parseWalletList(body?.fighter_wallets ?? body?.fighterWallets);
         const moveCommitmentAccounts =
           turn > 0
-            ? fighterWallets.map((wallet) => deriveMoveCommitmentPda(rumbleId, wallet, turn)[0])
+            ? fighterWallets.map((wallet) => deriveMoveCommitmentPda(rumbleId, wallet)[0])
             : [];
         signature = await resolveTurnOnChain(rumbleId, moveCommitmentAccounts);
         break;
@@ -120,6 +120,7 @@ export async function POST(req: Request) {
     if (
       existing &&
       !existing.revoked &&
+      existing.expiresAtSlot === 0n &&
       existing.authority.equals(targetAuthority)
     ) {
       return NextResponse.json({
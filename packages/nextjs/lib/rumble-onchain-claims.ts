@@ -4,10 +4,12 @@ import { utils as anchorUtils } from "@coral-xyz/anchor";
 import { getBettingConnection, getBettingRpcEndpoint, getCachedBalance } from "./solana-connection";
 import {
   RUMBLE_ENGINE_ID_MAINNET,
+  bettorFighterSlots,
   deriveBettorPdaMainnet,
   readRumbleAccountState,
   deriveRumblePdaMainnet,
   deriveVaultPdaMainnet,
+  rumbleFighterSlots,
 } from "./solana-programs";
 import { freshSupabase } from "./supabase";
 import { parseOnchainRumbleIdNumber } from "./rumble-id";
@@ -139,7 +141,7 @@ function decodeBettorAccountData(data: Buffer): BettorAccountDecoded | null {
   }
 
   if (data.length >= offset + 8 * 16) {
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < bettorFighterSlots(data.length); i++) {
       fighterDeploymentsLamports.push(readU64LE(data, offset));
       offset += 8;
     }
@@ -405,24 +407,26 @@ async function batchReadRumbleStates(
         }
 
         const data = info.data;
-        // Rumble account layout (must match on-chain struct exactly):
-        // discriminator(8) + id(8) + state(1) + fighters(32*16=512) +
-        // fighter_count(1) + betting_pools(8*16=128) + total_deployed(8) +
-        // admin_fee_collected(8) + sponsorship_paid(8) + placements(16) +
+        // Rumble account layout (must match on-chain struct exactly), with
+        // slots = 16, or 32 on v2 rumbles:
+        // discriminator(8) + id(8) + state(1) + fighters(32*slots) +
+        // fighter_count(1) + betting_pools(8*slots) + total_deployed(8) +
+        // admin_fee_collected(8) + sponsorship_paid(8) + placements(slots) +
         // winner_index(1) + betting_deadline(8) + combat_started_at(8) +
-        // completed_at(8) + bump(1) = 724
+        // completed_at(8) + bump(1) = 724 for 16 slots (offsets below are for 16)
         const stateVal = data[16]; // state (u8)
         const state = STATE_NAMES[stateVal] ?? "betting";
 
+        const slots = rumbleFighterSlots(data.length);
         const fightersOffset = 8 + 8 + 1; // 17
-        const fighterCountOffset = fightersOffset + 32 * 16; // 529
+        const fighterCountOffset = fightersOffset + 32 * slots; // 529
         const fighterCount = data[fighterCountOffset];
         const bettingPoolsOffset = fighterCountOffset + 1; // 530
-        const totalDeployedOffset = bettingPoolsOffset + 8 * 16; // 658
+        const totalDeployedOffset = bettingPoolsOffset + 8 * slots; // 658
         const adminFeeCollectedOffset = totalDeployedOffset + 8; // 666
         const sponsorshipPaidOffset = adminFeeCollectedOffset + 8; // 674
         const placementsOffset = sponsorshipPaidOffset + 8; // 682
-        const winnerIndexOffset = placementsOffset + 16; // 698
+        const winnerIndexOffset = placementsOffset + slots; // 698
 
         const winnerIndex = data[winnerIndexOffset];
 
@@ -433,10 +437,10 @@ async function batchReadRumbleStates(
         const sponsorshipPaidLamports = data.length >= sponsorshipPaidOffset + 8
           ? readU64LE(data, sponsorshipPaidOffset) : 0n;
 
-        // Read betting pools (16 x u64)
+        // Read betting pools (slots x u64)
         const bettingPools: bigint[] = [];
-        if (data.length >= bettingPoolsOffset + 8 * 16) {
-          for (let k = 0; k < 16; k++) {
+        if (data.length >= bettingPoolsOffset + 8 * slots) {
+          for (let k = 0; k < slots; k++) {
             bettingPools.push(readU64LE(data, bettingPoolsOffset + k * 8));
           }
         }
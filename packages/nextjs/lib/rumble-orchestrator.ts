@@ -79,6 +79,7 @@ import {
   finalizeRumbleOnChain as finalizeRumbleOnChainTx,
   openTurn as openTurnOnChain,
   readRumbleCombatState,
+  migrateCombatState,
   readFighterDelegateState,
   startCombat as startCombatOnChain,
   resolveTurnOnChain,
@@ -96,7 +97,6 @@ import {
   getServerAdminKeypair,
   type RumbleCombatAccountState,
   readShowerRequest,
-  RUMBLE_ENGINE_ID,
   invalidateReadCache,
   closeMoveCommitmentOnChain,
   readRumbleFighters,
@@ -2532,7 +2532,7 @@ export class RumbleOrchestrator {
     // NOTE: Auto-sweep disabled — sweep_treasury is admin-only now.
     // Users can claim payouts indefinitely; admin can sweep manually via admin panel.
 
-    // 3) Reclaim rent from MoveCommitment PDAs (~1.46 SOL per rumble, fire-and-forget)
+    // 3) Reclaim rent from MoveCommitment PDAs (one per fighter, fire-and-forget)
     try {
       const [fighters, combat] = await Promise.all([
         readRumbleFighters(entry.rumbleIdNum),
@@ -2540,16 +2540,14 @@ export class RumbleOrchestrator {
       ]);
       const totalTurns = combat?.currentTurn ?? 0;
       if (fighters.length > 0 && totalTurns > 0) {
-        for (let turn = 1; turn <= totalTurns; turn++) {
-          for (const fighter of fighters) {
-            // Return rent to the fighter, not the admin
-            closeMoveCommitmentOnChain(entry.rumbleIdNum, fighter, turn, fighter).catch((err: any) => {
-              this.logCloseMoveCommitmentCleanupError(err);
-            });
-          }
+        for (const fighter of fighters) {
+          // Return rent to the fighter, not the admin
+          closeMoveCommitmentOnChain(entry.rumbleIdNum, fighter, fighter).catch((err: any) => {
+            this.logCloseMoveCommitmentCleanupError(err);
+          });
         }
         console.log(
-          `[ONCHAIN-CLOSE-MOVE] Queued ${fighters.length * totalTurns} MoveCommitment closures for rumble ${entry.rumbleId} (rent → fighters)`,
+          `[ONCHAIN-CLOSE-MOVE] Queued ${fighters.length} MoveCommitment closures for rumble ${entry.rumbleId} (rent → fighters)`,
         );
       }
     } catch (e) {
@@ -4389,6 +4387,16 @@ export class RumbleOrchestrator {
       }
     }
     if (!combat) return;
+    if (combat.legacyLayout) {
+      // Combat states from before the zero-copy layout must be migrated on L1
+      // before any combat instruction accepts them.
+      if (!state.erDelegated) {
+        await migrateCombatState(rumbleIdNum).catch((err) =>
+          console.warn(`[OnChain] migrateCombatState failed for rumble ${rumbleIdNum}:`, err),
+        );
+      }
+      return;
+    }
     if (combat.currentTurn > 0) {
       this.pruneTurnDecisionsBefore(slot.id, state, combat.currentTurn);
     }
@@ -4501,16 +4509,6 @@ export class RumbleOrchestrator {
         );
       }
 
-      // Close MoveCommitment PDAs for the resolved turn (fire-and-forget).
-      // Returns rent to each fighter so wallets don't drain over many rumbles.
-      for (const fid of slot.fighters) {
-        const wallet = state.fighterWallets.get(fid);
-        if (!wallet) continue;
-        closeMoveCommitmentOnChain(rumbleIdNum, wallet, turnNum, wallet, slotConn).catch((err: any) => {
-          this.logCloseMoveCommitmentCleanupError(err);
-        });
-      }
-
       for (const eliminatedId of sync.newEliminations) {
         this.emit("fighter_eliminated", {
           slotIndex: idx,
@@ -4622,11 +4620,7 @@ export class RumbleOrchestrator {
         if (RESOLUTION_MODE === "hybrid") {
           await this.resolveAndPostTurnResult(slot, state, combat, rumbleIdNum);
         } else {
-          const commitmentAccounts = await this.collectExistingMoveCommitments(
-            state,
-            rumbleIdNum,
-            combat.currentTurn,
-          );
+          const commitmentAccounts = this.collectMoveCommitments(state, rumbleIdNum);
           const sig = await resolveTurnOnChain(rumbleIdNum, commitmentAccounts, slotConn);
           if (sig) {
             state.lastResolveSubmittedTurn = combat.currentTurn;
@@ -4730,16 +4724,6 @@ export class RumbleOrchestrator {
           );
         }
 
-        // Close MoveCommitment PDAs for the resolved turn (fire-and-forget).
-        // Returns rent to each fighter so wallets don't drain over many rumbles.
-        for (const fid of slot.fighters) {
-          const wallet = state.fighterWallets.get(fid);
-          if (!wallet) continue;
-          closeMoveCommitmentOnChain(rumbleIdNum, wallet, turnNumPost, wallet, slotConn).catch((err: any) => {
-            this.logCloseMoveCommitmentCleanupError(err);
-          });
-        }
-
         for (const eliminatedId of syncAfterResolve.newEliminations) {
           this.emit("fighter_eliminated", {
             slotIndex: idx,
@@ -5703,23 +5687,15 @@ export class RumbleOrchestrator {
     }
   }
 
-  private async collectExistingMoveCommitments(
-    state: SlotCombatState,
-    rumbleIdNum: number,
-    turn: number,
-  ): Promise<PublicKey[]> {
-    const wallets = [...state.fighterWallets.values()];
-    if (wallets.length === 0) return [];
-    const pdas = wallets.map((wallet) => deriveMoveCommitmentPda(rumbleIdNum, wallet, turn)[0]);
-    const infos = await this.getConnectionForState(state).getMultipleAccountsInfo(pdas, "processed");
-    const resolved: PublicKey[] = [];
-    for (let i = 0; i < pdas.length; i++) {
-      const info = infos[i];
-      if (!info) continue;
-      if (!info.owner.equals(RUMBLE_ENGINE_ID)) continue;
-      resolved.push(pdas[i]);
-    }
-    return resolved;
+  /**
+   * Every fighter's MoveCommitment PDA. The program requires each dueling
+   * fighter's PDA, including ones never created, so missed reveals are always
+   * penalized; stale commits from earlier turns are ignored on-chain.
+   */
+  private collectMoveCommitments(state: SlotCombatState, rumbleIdNum: number): PublicKey[] {
+    return [...state.fighterWallets.values()].map(
+      (wallet) => deriveMoveCommitmentPda(rumbleIdNum, wallet)[0],
+    );
   }
 
   /**
@@ -5766,10 +5742,11 @@ export class RumbleOrchestrator {
     // Read revealed moves from MoveCommitment PDAs
     const movesByIdx = new Map<number, number>();
     for (const idx of aliveIndices) {
-      const [pda] = deriveMoveCommitmentPda(rumbleIdNum, fighters[idx], turn);
+      const [pda] = deriveMoveCommitmentPda(rumbleIdNum, fighters[idx]);
       try {
         const data = await readMoveCommitmentData(pda);
-        if (data && data.revealedMove !== null) {
+        // The PDA is reused across turns; an older commit means no move this turn.
+        if (data && data.turn === turn && data.revealedMove !== null) {
           movesByIdx.set(idx, data.revealedMove);
         }
       } catch {
@@ -5881,6 +5858,7 @@ export class RumbleOrchestrator {
       rumbleIdNum,
       duelResults,
       byeIdx,
+      aliveIndices.map((idx) => deriveMoveCommitmentPda(rumbleIdNum, fighters[idx])[0]),
       this.getConnectionForState(state),
     );
     if (sig) {
@@ -130,20 +130,61 @@ export function isMainnetConfigured(): boolean {
 const ARENA_SEED = Buffer.from("arena_config");
 const DISTRIBUTION_VAULT_SEED = Buffer.from("distribution_vault");
 const SHOWER_REQUEST_SEED = Buffer.from("shower_request");
+const SHOWER_HISTORY_SEED = Buffer.from("shower_history");
+const SHOWER_CAP_SEED = Buffer.from("shower_cap");
 const ENTROPY_CONFIG_SEED = Buffer.from("entropy_config");
 const PENDING_ADMIN_SEED = Buffer.from("pending_admin");
+const STAKE_SEED = Buffer.from("stake");
+const STAKE_VAULT_SEED = Buffer.from("stake_vault");
+const SEASON_CONFIG_SEED = Buffer.from("season_config");
 const ENTROPY_VAR_SEED = Buffer.from("var");
 const REGISTRY_SEED = Buffer.from("registry_config");
 const CONFIG_SEED = Buffer.from("rumble_config");
 const FIGHTER_SEED = Buffer.from("fighter");
 const WALLET_STATE_SEED = Buffer.from("wallet_state");
+const FIGHTER_NAME_SEED = Buffer.from("fname");
+const HALL_OF_FAME_SEED = Buffer.from("hall_of_fame");
+const HEAD_TO_HEAD_SEED = Buffer.from("h2h");
 const RUMBLE_SEED = Buffer.from("rumble");
 const VAULT_SEED = Buffer.from("vault");
 const BETTOR_SEED = Buffer.from("bettor");
 const SPONSORSHIP_SEED = Buffer.from("sponsorship");
+const SPONSORSHIP_LEDGER_SEED = Buffer.from("sponsorship_ledger");
+const REFERRAL_SEED = Buffer.from("referral");
+const WHITELIST_SEED = Buffer.from("whitelist");
+const EXACTA_TICKET_SEED = Buffer.from("exacta_ticket");
+const EXACTA_COMBO_SEED = Buffer.from("exacta_combo");
+const BETTOR_INDEX_SEED = Buffer.from("bettor_index");
+const GLOBAL_STATS_SEED = Buffer.from("global_stats");
+const BETTOR_STATS_SEED = Buffer.from("bettor_stats");
+const FEE_EXEMPT_SEED = Buffer.from("fee_exempt");
+const RATE_LIMIT_SEED = Buffer.from("rate_limit");
+/** place_bet calls one wallet may land on one rumble per slot (MAX_BETS_PER_SLOT). */
+const MAX_BETS_PER_SLOT = 3;
+/** Rumble accounts at least this long are on the v2 layout (32 fighter slots). */
+const RUMBLE_V2_ACCOUNT_LEN = 2222;
+/** BettorAccount size for v2 rumbles; 16-slot rumbles' bettor accounts are 352. */
+const BETTOR_V2_ACCOUNT_LEN = 609;
+/** Length of per-fighter instruction args such as `audience_boosts`, whatever the layout. */
+const PROGRAM_MAX_FIGHTERS = 32;
+
+/** Entries per fighter array in a rumble account `dataLength` bytes long. */
+export function rumbleFighterSlots(dataLength: number): number {
+  return dataLength >= RUMBLE_V2_ACCOUNT_LEN ? 32 : 16;
+}
+
+/** Entries per fighter array in a bettor account `dataLength` bytes long. */
+export function bettorFighterSlots(dataLength: number): number {
+  return dataLength >= BETTOR_V2_ACCOUNT_LEN ? 32 : 16;
+}
+
+const DISPUTE_SEED = Buffer.from("dispute");
+const ADMIN_LOG_SEED = Buffer.from("admin_log");
+const COMBAT_RULESET_SEED = Buffer.from("combat_ruleset");
 const MOVE_COMMIT_SEED = Buffer.from("move_commit");
 const FIGHTER_DELEGATE_SEED = Buffer.from("fighter_delegate");
 const COMBAT_STATE_SEED = Buffer.from("combat_state");
+const COMBAT_LOG_SEED = Buffer.from("combat_log");
 const DELEGATION_BUFFER_SEED = Buffer.from("buffer");
 const DELEGATION_RECORD_SEED = Buffer.from("delegation");
 const DELEGATION_METADATA_SEED = Buffer.from("delegation-metadata");
@@ -509,6 +550,17 @@ export function deriveShowerRequestPda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([SHOWER_REQUEST_SEED], ICHOR_TOKEN_ID);
 }
 
+export function deriveShowerHistoryPda(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SHOWER_HISTORY_SEED], ICHOR_TOKEN_ID);
+}
+
+export function deriveShowerCapPda(recipientTokenAccount: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SHOWER_CAP_SEED, recipientTokenAccount.toBuffer()],
+    ICHOR_TOKEN_ID,
+  );
+}
+
 export function deriveEntropyConfigPda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([ENTROPY_CONFIG_SEED], ICHOR_TOKEN_ID);
 }
@@ -517,6 +569,21 @@ export function deriveDistributionVaultPda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([DISTRIBUTION_VAULT_SEED], ICHOR_TOKEN_ID);
 }
 
+export function deriveIchorStakePda(staker: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([STAKE_SEED, staker.toBuffer()], ICHOR_TOKEN_ID);
+}
+
+export function deriveStakeVaultPda(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([STAKE_VAULT_SEED], ICHOR_TOKEN_ID);
+}
+
+export function deriveSeasonConfigPda(seasonId: bigint | number): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEASON_CONFIG_SEED, u64Buffer(BigInt(seasonId))],
+    ICHOR_TOKEN_ID,
+  );
+}
+
 export function deriveRegistryConfigPda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([REGISTRY_SEED], FIGHTER_REGISTRY_ID);
 }
@@ -546,6 +613,41 @@ export function deriveWalletStatePda(authority: PublicKey): [PublicKey, number]
   );
 }
 
+export function deriveFighterNamePda(nameBytes: Uint8Array): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [FIGHTER_NAME_SEED, Buffer.from(nameBytes)],
+    FIGHTER_REGISTRY_ID
+  );
+}
+
+export function deriveHallOfFamePda(fighterPubkey: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [HALL_OF_FAME_SEED, fighterPubkey.toBuffer()],
+    FIGHTER_REGISTRY_ID
+  );
+}
+
+/** Sort a fighter pair into the on-chain (fighter_a, fighter_b) order. */
+export function sortHeadToHeadPair(a: PublicKey, b: PublicKey): [PublicKey, PublicKey] {
+  return Buffer.compare(a.toBuffer(), b.toBuffer()) < 0 ? [a, b] : [b, a];
+}
+
+export function deriveHeadToHeadPda(a: PublicKey, b: PublicKey): [PublicKey, number] {
+  const [fighterA, fighterB] = sortHeadToHeadPair(a, b);
+  return PublicKey.findProgramAddressSync(
+    [HEAD_TO_HEAD_SEED, fighterA.toBuffer(), fighterB.toBuffer()],
+    FIGHTER_REGISTRY_ID
+  );
+}
+
+/** Encode a fighter name as the on-chain zero-padded [u8; 32]. */
+function encodeFighterName(name: string): Uint8Array {
+  const nameBytes = new Uint8Array(32);
+  const encoded = new TextEncoder().encode(name.slice(0, 32));
+  nameBytes.set(encoded.slice(0, 32));
+  return nameBytes;
+}
+
 export function deriveRumblePda(rumbleId: bigint | number): [PublicKey, number] {
   const buf = Buffer.alloc(8);
   buf.writeBigUInt64LE(BigInt(rumbleId));
@@ -585,9 +687,124 @@ export function deriveSponsorshipPda(
   );
 }
 
+export function deriveCombatRulesetPda(rulesetId: number): [PublicKey, number] {
+  const buf = Buffer.alloc(4);
+  buf.writeUInt32LE(rulesetId);
+  return PublicKey.findProgramAddressSync([COMBAT_RULESET_SEED, buf], RUMBLE_ENGINE_ID);
+}
+
+export function deriveWhitelistPda(rumbleId: bigint | number): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync([WHITELIST_SEED, buf], RUMBLE_ENGINE_ID);
+}
+
+export function deriveReferralPda(
+  referrer: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [REFERRAL_SEED, referrer.toBuffer()],
+    RUMBLE_ENGINE_ID
+  );
+}
+
+export function deriveSponsorshipLedgerPda(
+  fighterPubkey: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SPONSORSHIP_LEDGER_SEED, fighterPubkey.toBuffer()],
+    RUMBLE_ENGINE_ID
+  );
+}
+
+/** A wallet's BettorIndex: the last 32 rumbles it bet on. */
+export function deriveBettorIndexPda(wallet: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([BETTOR_INDEX_SEED, wallet.toBuffer()], RUMBLE_ENGINE_ID);
+}
+
+/** A wallet's lifetime BettorStats across every rumble. */
+export function deriveBettorStatsPda(wallet: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([BETTOR_STATS_SEED, wallet.toBuffer()], RUMBLE_ENGINE_ID);
+}
+
+/** A promo wallet's FeeExemption from the place_bet admin fee. */
+export function deriveFeeExemptionPda(wallet: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([FEE_EXEMPT_SEED, wallet.toBuffer()], RUMBLE_ENGINE_ID);
+}
+
+/** A bettor's per-slot bet counter on one rumble; place_bet opens it. */
+export function deriveRateLimitPda(
+  bettor: PublicKey,
+  rumbleId: bigint | number,
+): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync([RATE_LIMIT_SEED, bettor.toBuffer(), buf], RUMBLE_ENGINE_ID);
+}
+
+/** The one DisputeRecord a rumble's admin-set result can carry. */
+export function deriveDisputePda(rumbleId: bigint | number): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync([DISPUTE_SEED, buf], RUMBLE_ENGINE_ID);
+}
+
+/** The rumble engine's AdminLog ring buffer of privileged calls. */
+export function deriveAdminLogPda(): [PublicKey, number] {
+  const [configPda] = deriveRumbleConfigPda();
+  return PublicKey.findProgramAddressSync([ADMIN_LOG_SEED, configPda.toBuffer()], RUMBLE_ENGINE_ID);
+}
+
+export function deriveGlobalStatsPda(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([GLOBAL_STATS_SEED], RUMBLE_ENGINE_ID);
+}
+
+/** A bettor's exacta ticket on one ordered (first, second) pair. */
+export function deriveExactaTicketPda(
+  rumbleId: bigint | number,
+  bettor: PublicKey,
+  firstIndex: number,
+  secondIndex: number
+): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync(
+    [EXACTA_TICKET_SEED, buf, bettor.toBuffer(), Buffer.from([firstIndex]), Buffer.from([secondIndex])],
+    RUMBLE_ENGINE_ID
+  );
+}
+
+/** Total exacta stake on one ordered (first, second) pair. */
+export function deriveExactaComboPda(
+  rumbleId: bigint | number,
+  firstIndex: number,
+  secondIndex: number
+): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync(
+    [EXACTA_COMBO_SEED, buf, Buffer.from([firstIndex]), Buffer.from([secondIndex])],
+    RUMBLE_ENGINE_ID
+  );
+}
+
+/** A fighter's MoveCommitment PDA for a rumble, reused across turns. */
 export function deriveMoveCommitmentPda(
   rumbleId: bigint | number,
   fighter: PublicKey,
+): [PublicKey, number] {
+  const rumbleBuf = Buffer.alloc(8);
+  rumbleBuf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync(
+    [MOVE_COMMIT_SEED, rumbleBuf, fighter.toBuffer()],
+    RUMBLE_ENGINE_ID,
+  );
+}
+
+/** Per-turn MoveCommitment PDA from before commitments were reused across turns. */
+export function deriveLegacyMoveCommitmentPda(
+  rumbleId: bigint | number,
+  fighter: PublicKey,
   turn: number,
 ): [PublicKey, number] {
   const rumbleBuf = Buffer.alloc(8);
@@ -622,6 +839,18 @@ export function deriveCombatStatePda(
   );
 }
 
+export function deriveCombatLogPda(
+  rumbleId: bigint | number,
+  programId: PublicKey = RUMBLE_ENGINE_ID,
+): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync(
+    [COMBAT_LOG_SEED, buf],
+    programId,
+  );
+}
+
 function deriveDelegationBufferPda(
   combatStatePda: PublicKey,
   ownerProgramId: PublicKey,
@@ -654,6 +883,11 @@ export function deriveRumbleConfigPdaMainnet(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([CONFIG_SEED], RUMBLE_ENGINE_ID_MAINNET);
 }
 
+export function deriveAdminLogPdaMainnet(): [PublicKey, number] {
+  const [configPda] = deriveRumbleConfigPdaMainnet();
+  return PublicKey.findProgramAddressSync([ADMIN_LOG_SEED, configPda.toBuffer()], RUMBLE_ENGINE_ID_MAINNET);
+}
+
 export function deriveRumblePdaMainnet(rumbleId: bigint | number): [PublicKey, number] {
   const buf = Buffer.alloc(8);
   buf.writeBigUInt64LE(BigInt(rumbleId));
@@ -693,6 +927,42 @@ export function deriveSponsorshipPdaMainnet(
   );
 }
 
+export function deriveRateLimitPdaMainnet(
+  bettor: PublicKey,
+  rumbleId: bigint | number,
+): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync(
+    [RATE_LIMIT_SEED, bettor.toBuffer(), buf],
+    RUMBLE_ENGINE_ID_MAINNET,
+  );
+}
+
+export function deriveWhitelistPdaMainnet(rumbleId: bigint | number): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync([WHITELIST_SEED, buf], RUMBLE_ENGINE_ID_MAINNET);
+}
+
+export function deriveReferralPdaMainnet(
+  referrer: PublicKey,
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [REFERRAL_SEED, referrer.toBuffer()],
+    RUMBLE_ENGINE_ID_MAINNET,
+  );
+}
+
+export function deriveSponsorshipLedgerPdaMainnet(
+  fighterPubkey: PublicKey,
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SPONSORSHIP_LEDGER_SEED, fighterPubkey.toBuffer()],
+    RUMBLE_ENGINE_ID_MAINNET,
+  );
+}
+
 function readU64LE(data: Uint8Array, offset: number): bigint {
   const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
   return view.getBigUint64(offset, true);
@@ -739,7 +1009,47 @@ export interface RumbleAccountState {
   totalDeployedLamports: bigint;
   adminFeeCollectedLamports: bigint;
   sponsorshipPaidLamports: bigint;
+  /** Per-fighter sponsorship for this rumble; empty for rumbles created before it was tracked. */
+  sponsorshipByFighter: bigint[];
+  /** On-chain `betting_close_slot`; falls back to the legacy deadline for older rumbles. */
   bettingCloseSlot: bigint;
+  /** Distinct open bettor accounts, and the minimum combat needs (0 = none / older rumble). */
+  uniqueBettorCount: number;
+  minBettors: number;
+  /** Organizer-funded bonus on top of the losers' pool (0 for older rumbles). */
+  prizePoolLamports: bigint;
+  /** `CombatRuleset` the rumble fights under; null = built-in constants. */
+  ruleset: PublicKey | null;
+  /** ICHOR the creator had to hold to create the rumble (0 = ungated). */
+  tokenGate: bigint;
+  /** Net exacta stakes, kept apart from `bettingPools` (0 for older rumbles). */
+  exactaPoolLamports: bigint;
+  /** Whether `settle_exacta_pool` has run for this rumble. */
+  exactaSettled: boolean;
+  /** Lamports owed to winning bettors, fixed by the first winning claim (0 until then). */
+  winnerPoolSnapshotLamports: bigint;
+  /** Lamports winning bettors have claimed so far (stake bonuses excluded). */
+  totalClaimedLamports: bigint;
+  /** Winning stake whose payout has been claimed. */
+  winnerStakeClaimedLamports: bigint;
+  /** A bettor's dispute of the result awaits the admin; claims are on hold. */
+  disputeOpen: boolean;
+  /** Lamports `sweep_treasury` has moved to the treasury so far. */
+  sweptLamports: bigint;
+  /** Seconds after completion before the rumble can close (legacy: 86_400). */
+  claimWindowSeconds: number;
+  /** verify_vault_integrity found the vault short; claims wait for the admin. */
+  vaultHalted: boolean;
+  /** Final elimination ranks from finalize_rumble (1 = first out, 0 = never out). */
+  eliminationRank: number[];
+  /** Insurance premiums paid into the vault. */
+  insurancePoolLamports: bigint;
+  /** Insurance paid out to losing bettors so far. */
+  insurancePaidLamports: bigint;
+  /** Share of insured net stake refunded on a loss (0 = no insurance). */
+  insurancePayoutBps: number;
+  /** Wallet that funded the prize pool; null if nobody has. */
+  prizeDepositor: PublicKey | null;
   // Legacy compatibility field: this account offset previously represented
   // unix timestamp and is now used as on-chain slot close.
   bettingDeadlineTs: bigint;
@@ -765,6 +1075,8 @@ export interface RumbleCombatAccountState {
   totalDamageTaken: bigint[];
   vrfSeed: Uint8Array;
   bump: number;
+  /** Pre-zero-copy layout; combat instructions fail until `migrateCombatState`. */
+  legacyLayout: boolean;
 }
 
 export interface FighterDelegateAccountState {
@@ -774,6 +1086,8 @@ export interface FighterDelegateAccountState {
   authorizedSlot: bigint;
   revoked: boolean;
   bump: number;
+  /** Last slot the delegate may act; 0 = until revoked (always 0 for pre-expiry accounts). */
+  expiresAtSlot: bigint;
 }
 
 function getConnectionCacheKey(connection: Connection): string {
@@ -814,18 +1128,35 @@ export async function readRumbleAccountState(
     const state = ONCHAIN_RUMBLE_STATES[rawState];
     if (!state) return null;
 
+    const slots = rumbleFighterSlots(data.length);
     const fightersOffset = 8 + 8 + 1;
-    const fighterCountOffset = fightersOffset + 32 * 16;
-    const fighterCount = Math.min(data[fighterCountOffset] ?? 0, 16);
+    const fighterCountOffset = fightersOffset + 32 * slots;
+    const fighterCount = Math.min(data[fighterCountOffset] ?? 0, slots);
     const bettingPoolsOffset = fighterCountOffset + 1;
-    const totalDeployedOffset = bettingPoolsOffset + 8 * 16;
+    const totalDeployedOffset = bettingPoolsOffset + 8 * slots;
     const adminFeeCollectedOffset = totalDeployedOffset + 8;
     const sponsorshipPaidOffset = adminFeeCollectedOffset + 8;
     const placementsOffset = sponsorshipPaidOffset + 8;
-    const winnerIndexOffset = placementsOffset + 16;
+    const winnerIndexOffset = placementsOffset + slots;
     const bettingDeadlineOffset = winnerIndexOffset + 1;
     const combatStartedAtOffset = bettingDeadlineOffset + 8;
     const completedAtOffset = combatStartedAtOffset + 8;
+    // bump(1) cancelled_at(8) place bps(24) max_bet(8) min_pool(8) underfunded(1)
+    // deadline_kind(1) vault_rent_reserve(8) result_source(1) record_bets(1)
+    const sponsorshipByFighterOffset = completedAtOffset + 8 + 61;
+    const bettingCloseSlotOffset = sponsorshipByFighterOffset + 8 * slots;
+    const uniqueBettorCountOffset = bettingCloseSlotOffset + 8;
+    const minBettorsOffset = uniqueBettorCountOffset + 2;
+    const prizePoolOffset = minBettorsOffset + 2;
+    // prize_pool(8) whitelist_enabled(1) stake_bonus_reserve(8) combat_history_hash(32)
+    // keeper_budget(8)
+    const rulesetOffset = prizePoolOffset + 57;
+    // ruleset(32) crits_enabled(1)
+    const tokenGateOffset = rulesetOffset + 33;
+    const exactaPoolOffset = tokenGateOffset + 8;
+    // exacta_pool(8) exacta_settled(1) exacta_winning_stake(8)
+    // exacta_distributable(8) exacta_claimed_stake(8)
+    const winnerPoolSnapshotOffset = exactaPoolOffset + 33;
 
     // Extract fighter public keys from the same data (avoids separate RPC call)
     const fighters: PublicKey[] = [];
@@ -841,11 +1172,11 @@ export async function readRumbleAccountState(
 
     const winnerIndexRaw = data.length > winnerIndexOffset ? data[winnerIndexOffset] : undefined;
     const winnerIndex =
-      typeof winnerIndexRaw === "number" && winnerIndexRaw < 16 ? winnerIndexRaw : null;
+      typeof winnerIndexRaw === "number" && winnerIndexRaw < slots ? winnerIndexRaw : null;
 
     const bettingPools: bigint[] = [];
-    if (data.length >= bettingPoolsOffset + 8 * 16) {
-      for (let i = 0; i < 16; i++) {
+    if (data.length >= bettingPoolsOffset + 8 * slots) {
+      for (let i = 0; i < slots; i++) {
         bettingPools.push(readU64LE(data, bettingPoolsOffset + i * 8));
       }
     }
@@ -854,14 +1185,72 @@ export async function readRumbleAccountState(
     const sponsorshipPaidLamports = data.length >= sponsorshipPaidOffset + 8 ? readU64LE(data, sponsorshipPaidOffset) : 0n;
 
     const placements: number[] = [];
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       const offset = placementsOffset + i;
       placements.push(offset < data.length ? data[offset] ?? 0 : 0);
     }
     const bettingDeadlineRaw = data.length >= bettingDeadlineOffset + 8 ? readI64LE(data, bettingDeadlineOffset) : 0n;
-    const bettingCloseSlot = bettingDeadlineRaw > 0n ? bettingDeadlineRaw : 0n;
+    const explicitCloseSlot =
+      data.length >= bettingCloseSlotOffset + 8 ? readU64LE(data, bettingCloseSlotOffset) : 0n;
+    const bettingCloseSlot =
+      explicitCloseSlot > 0n ? explicitCloseSlot : bettingDeadlineRaw > 0n ? bettingDeadlineRaw : 0n;
     const combatStartedAtTs = data.length >= combatStartedAtOffset + 8 ? readI64LE(data, combatStartedAtOffset) : 0n;
     const completedAtTs = data.length >= completedAtOffset + 8 ? readI64LE(data, completedAtOffset) : 0n;
+    const uniqueBettorCount =
+      data.length >= uniqueBettorCountOffset + 2 ? data.readUInt16LE(uniqueBettorCountOffset) : 0;
+    const minBettors = data.length >= minBettorsOffset + 2 ? data.readUInt16LE(minBettorsOffset) : 0;
+    const prizePoolLamports = data.length >= prizePoolOffset + 8 ? readU64LE(data, prizePoolOffset) : 0n;
+    const rulesetKey =
+      data.length >= rulesetOffset + 32
+        ? new PublicKey(data.subarray(rulesetOffset, rulesetOffset + 32))
+        : PublicKey.default;
+    const ruleset = rulesetKey.equals(PublicKey.default) ? null : rulesetKey;
+    const tokenGate = data.length >= tokenGateOffset + 8 ? readU64LE(data, tokenGateOffset) : 0n;
+    const exactaPoolLamports =
+      data.length >= exactaPoolOffset + 8 ? readU64LE(data, exactaPoolOffset) : 0n;
+    const exactaSettled = data.length > exactaPoolOffset + 8 && data[exactaPoolOffset + 8] === 1;
+    const readWinnerClaimField = (index: number) => {
+      const offset = winnerPoolSnapshotOffset + index * 8;
+      return data.length >= offset + 8 ? readU64LE(data, offset) : 0n;
+    };
+    const winnerPoolSnapshotLamports = readWinnerClaimField(0);
+    const totalClaimedLamports = readWinnerClaimField(1);
+    const winnerStakeClaimedLamports = readWinnerClaimField(2);
+    const disputeOpenOffset = winnerPoolSnapshotOffset + 24;
+    const disputeOpen = data.length > disputeOpenOffset && data[disputeOpenOffset] === 1;
+    const sweptLamports =
+      data.length >= disputeOpenOffset + 9 ? readU64LE(data, disputeOpenOffset + 1) : 0n;
+    const claimWindowRaw =
+      data.length >= disputeOpenOffset + 17 ? readI64LE(data, disputeOpenOffset + 9) : 0n;
+    const claimWindowSeconds = claimWindowRaw > 0n ? Number(claimWindowRaw) : 86_400;
+    const vaultHalted = data.length > disputeOpenOffset + 17 && data[disputeOpenOffset + 17] === 1;
+    const eliminationRankOffset = disputeOpenOffset + 18;
+    const eliminationRank: number[] = [];
+    for (let i = 0; i < slots; i++) {
+      const offset = eliminationRankOffset + i;
+      eliminationRank.push(offset < data.length ? data[offset] ?? 0 : 0);
+    }
+    const insurancePoolOffset = eliminationRankOffset + slots;
+    const insurancePoolLamports =
+      data.length >= insurancePoolOffset + 8 ? readU64LE(data, insurancePoolOffset) : 0n;
+    const insurancePaidLamports =
+      data.length >= insurancePoolOffset + 16 ? readU64LE(data, insurancePoolOffset + 8) : 0n;
+    // insured_stake (one u64 per slot) sits between the paid total and the payout rate.
+    const insurancePayoutBpsOffset = insurancePoolOffset + 16 + 8 * slots;
+    const insurancePayoutBps =
+      data.length >= insurancePayoutBpsOffset + 2 ? data.readUInt16LE(insurancePayoutBpsOffset) : 0;
+    const prizeDepositorOffset = insurancePayoutBpsOffset + 2;
+    const prizeDepositorKey =
+      data.length >= prizeDepositorOffset + 32
+        ? new PublicKey(data.subarray(prizeDepositorOffset, prizeDepositorOffset + 32))
+        : PublicKey.default;
+    const prizeDepositor = prizeDepositorKey.equals(PublicKey.default) ? null : prizeDepositorKey;
+    const sponsorshipByFighter: bigint[] = [];
+    if (data.length >= sponsorshipByFighterOffset + 8 * slots) {
+      for (let i = 0; i < slots; i++) {
+        sponsorshipByFighter.push(readU64LE(data, sponsorshipByFighterOffset + i * 8));
+      }
+    }
 
     return {
       address: rumblePda,
@@ -875,7 +1264,27 @@ export async function readRumbleAccountState(
       totalDeployedLamports,
       adminFeeCollectedLamports,
       sponsorshipPaidLamports,
+      sponsorshipByFighter,
       bettingCloseSlot,
+      uniqueBettorCount,
+      minBettors,
+      prizePoolLamports,
+      ruleset,
+      tokenGate,
+      exactaPoolLamports,
+      exactaSettled,
+      winnerPoolSnapshotLamports,
+      totalClaimedLamports,
+      winnerStakeClaimedLamports,
+      disputeOpen,
+      sweptLamports,
+      claimWindowSeconds,
+      vaultHalted,
+      eliminationRank,
+      insurancePoolLamports,
+      insurancePaidLamports,
+      insurancePayoutBps,
+      prizeDepositor,
       bettingDeadlineTs: bettingDeadlineRaw,
       combatStartedAtTs,
       completedAtTs,
@@ -923,7 +1332,7 @@ export async function readMainnetRumbleAccountStateResilient(
 }
 
 /**
- * Read the raw betting_pools[16] (u64 lamport values) from the on-chain
+ * Read the raw betting_pools (u64 lamport values, one per fighter slot) from the on-chain
  * rumble account. Delegates to cached readRumbleAccountState to avoid
  * duplicate RPC calls (saves 1 credit per call).
  */
@@ -950,27 +1359,73 @@ export async function readRumbleCombatState(
   return cachedRead(key, 5_000, async () => {
     const [combatStatePda] = deriveCombatStatePda(rumbleId);
     const MIN_COMBAT_ACCOUNT_LEN = 401;
+    const ZERO_COPY_COMBAT_ACCOUNT_LEN = 1872;
     const info = await conn.getAccountInfo(combatStatePda, "processed");
     if (!info || info.data.length < MIN_COMBAT_ACCOUNT_LEN) return null;
 
     const data = info.data;
     const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
 
-    const fighterCountOffset = 8 + 8;
-    const currentTurnOffset = fighterCountOffset + 1;
-    const turnOpenSlotOffset = currentTurnOffset + 4;
-    const commitCloseSlotOffset = turnOpenSlotOffset + 8;
-    const revealCloseSlotOffset = commitCloseSlotOffset + 8;
-    const turnResolvedOffset = revealCloseSlotOffset + 8;
-    const remainingFightersOffset = turnResolvedOffset + 1;
-    const winnerIndexOffset = remainingFightersOffset + 1;
-    const hpOffset = winnerIndexOffset + 1;
-    const meterOffset = hpOffset + 16 * 2;
-    const eliminationRankOffset = meterOffset + 16;
-    const totalDamageDealtOffset = eliminationRankOffset + 16;
-    const totalDamageTakenOffset = totalDamageDealtOffset + 16 * 8;
-    const vrfSeedOffset = totalDamageTakenOffset + 16 * 8;
-    const bumpOffset = vrfSeedOffset + 32;
+    // Zero-copy layout (field order by alignment); combat states created
+    // before it keep the borsh order until `migrate_combat_state` runs.
+    const zeroCopy = data.length >= ZERO_COPY_COMBAT_ACCOUNT_LEN;
+    let fighterCountOffset: number;
+    let currentTurnOffset: number;
+    let turnOpenSlotOffset: number;
+    let commitCloseSlotOffset: number;
+    let revealCloseSlotOffset: number;
+    let turnResolvedOffset: number;
+    let remainingFightersOffset: number;
+    let winnerIndexOffset: number;
+    let hpOffset: number;
+    let meterOffset: number;
+    let eliminationRankOffset: number;
+    let totalDamageDealtOffset: number;
+    let totalDamageTakenOffset: number;
+    let vrfSeedOffset: number;
+    let bumpOffset: number;
+    // Fighters the per-fighter arrays cover: 16, or 32 for v2 rumbles.
+    let slots = 16;
+    if (zeroCopy) {
+      // Zero-copy arrays are always 32 wide; `fighter_slots` says how many are used.
+      const width = 32;
+      turnOpenSlotOffset = 8 + 8;
+      commitCloseSlotOffset = turnOpenSlotOffset + 8;
+      revealCloseSlotOffset = commitCloseSlotOffset + 8;
+      totalDamageDealtOffset = 8 + 7 * 8;
+      totalDamageTakenOffset = totalDamageDealtOffset + width * 8;
+      const fighterAttributesOffset = totalDamageTakenOffset + width * 8;
+      currentTurnOffset = fighterAttributesOffset + width * 24;
+      hpOffset = currentTurnOffset + 4;
+      const pairsResolvedOffset = hpOffset + 3 * width * 2;
+      vrfSeedOffset = pairsResolvedOffset + 2;
+      fighterCountOffset = vrfSeedOffset + 2 * 32;
+      turnResolvedOffset = fighterCountOffset + 1;
+      remainingFightersOffset = turnResolvedOffset + 1;
+      winnerIndexOffset = remainingFightersOffset + 1;
+      bumpOffset = winnerIndexOffset + 1;
+      // bump(1) turn_chunked(1) fighter_slots(1)
+      const fighterSlotsOffset = bumpOffset + 2;
+      slots = data[fighterSlotsOffset] === 32 ? 32 : 16;
+      meterOffset = fighterSlotsOffset + 1;
+      eliminationRankOffset = meterOffset + width;
+    } else {
+      fighterCountOffset = 8 + 8;
+      currentTurnOffset = fighterCountOffset + 1;
+      turnOpenSlotOffset = currentTurnOffset + 4;
+      commitCloseSlotOffset = turnOpenSlotOffset + 8;
+      revealCloseSlotOffset = commitCloseSlotOffset + 8;
+      turnResolvedOffset = revealCloseSlotOffset + 8;
+      remainingFightersOffset = turnResolvedOffset + 1;
+      winnerIndexOffset = remainingFightersOffset + 1;
+      hpOffset = winnerIndexOffset + 1;
+      meterOffset = hpOffset + 16 * 2;
+      eliminationRankOffset = meterOffset + 16;
+      totalDamageDealtOffset = eliminationRankOffset + 16;
+      totalDamageTakenOffset = totalDamageDealtOffset + 16 * 8;
+      vrfSeedOffset = totalDamageTakenOffset + 16 * 8;
+      bumpOffset = vrfSeedOffset + 32;
+    }
 
     const parsedRumbleId = view.getBigUint64(8, true);
     const fighterCount = data[fighterCountOffset] ?? 0;
@@ -983,33 +1438,33 @@ export async function readRumbleCombatState(
     const winnerIndexRaw = data[winnerIndexOffset] ?? 255;
 
     const hp: number[] = [];
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       hp.push(view.getUint16(hpOffset + i * 2, true));
     }
 
     const meter: number[] = [];
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       meter.push(data[meterOffset + i] ?? 0);
     }
 
     const eliminationRank: number[] = [];
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       eliminationRank.push(data[eliminationRankOffset + i] ?? 0);
     }
 
     const totalDamageDealt: bigint[] = [];
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       totalDamageDealt.push(view.getBigUint64(totalDamageDealtOffset + i * 8, true));
     }
 
     const totalDamageTaken: bigint[] = [];
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       totalDamageTaken.push(view.getBigUint64(totalDamageTakenOffset + i * 8, true));
     }
 
     const vrfSeed = data.slice(vrfSeedOffset, vrfSeedOffset + 32);
     const bump = data[bumpOffset] ?? 0;
-    const winnerIndex = winnerIndexRaw < 16 ? winnerIndexRaw : null;
+    const winnerIndex = winnerIndexRaw < slots ? winnerIndexRaw : null;
 
     return {
       address: combatStatePda,
@@ -1029,6 +1484,7 @@ export async function readRumbleCombatState(
       totalDamageTaken,
       vrfSeed,
       bump,
+      legacyLayout: !zeroCopy,
     };
   });
 }
@@ -1053,6 +1509,7 @@ export async function readFighterDelegateState(
   const authorizedSlotOffset = authorityOffset + 32;
   const revokedOffset = authorizedSlotOffset + 8;
   const bumpOffset = revokedOffset + 1;
+  const expiresAtSlotOffset = bumpOffset + 1;
 
   return {
     address: fighterDelegatePda,
@@ -1061,9 +1518,142 @@ export async function readFighterDelegateState(
     authorizedSlot: view.getBigUint64(authorizedSlotOffset, true),
     revoked: data[revokedOffset] === 1,
     bump: data[bumpOffset] ?? 0,
+    expiresAtSlot:
+      data.length >= expiresAtSlotOffset + 8 ? view.getBigUint64(expiresAtSlotOffset, true) : 0n,
+  };
+}
+
+/**
+ * Read the rumble-engine's lifetime totals from its GlobalStats PDA; null if
+ * this deployment has none yet. `totalBettors` counts one per wallet per rumble.
+ */
+export async function readGlobalStats(connection?: Connection): Promise<{
+  totalRumbles: bigint;
+  totalBetsLamports: bigint;
+  totalBettors: bigint;
+  totalFightersEntered: bigint;
+  totalSponsorshipLamports: bigint;
+  completedRumbles: bigint;
+} | null> {
+  const conn = connection ?? getConnection();
+  const [pda] = deriveGlobalStatsPda();
+  const info = await conn.getAccountInfo(pda, "confirmed");
+  if (!info || info.data.length < 8 + 6 * 8) return null;
+
+  const d = info.data;
+  return {
+    totalRumbles: readU64LE(d, 8),
+    totalBetsLamports: readU64LE(d, 16),
+    totalBettors: readU64LE(d, 24),
+    totalFightersEntered: readU64LE(d, 32),
+    totalSponsorshipLamports: readU64LE(d, 40),
+    completedRumbles: readU64LE(d, 48),
+  };
+}
+
+/**
+ * Read a wallet's lifetime BettorStats; null until its first place_bet that
+ * passes the account, or after it was closed.
+ */
+export async function readBettorStats(
+  wallet: PublicKey,
+  connection?: Connection
+): Promise<{
+  totalBets: bigint;
+  totalDeployedLamports: bigint;
+  totalClaimedLamports: bigint;
+  wins: number;
+  losses: number;
+  rumblesEntered: number;
+} | null> {
+  const conn = connection ?? getConnection();
+  const [pda] = deriveBettorStatsPda(wallet);
+  const info = await conn.getAccountInfo(pda, "confirmed");
+  if (!info || info.data.length < 8 + 32 + 3 * 8 + 3 * 4) return null;
+
+  const d = info.data;
+  const offset = 8 + 32; // discriminator, wallet
+  return {
+    totalBets: readU64LE(d, offset),
+    totalDeployedLamports: readU64LE(d, offset + 8),
+    totalClaimedLamports: readU64LE(d, offset + 16),
+    wins: d.readUInt32LE(offset + 24),
+    losses: d.readUInt32LE(offset + 28),
+    rumblesEntered: d.readUInt32LE(offset + 32),
   };
 }
 
+/**
+ * Read the rumble engine's AdminLog, newest entry first. `instructionTag` is
+ * the hex Anchor discriminator of the logged instruction. Empty until
+ * `initialize_admin_log` has run.
+ */
+export async function readAdminLog(connection?: Connection): Promise<Array<{
+  instructionTag: string;
+  actor: PublicKey;
+  atSlot: bigint;
+  atTs: bigint;
+}>> {
+  const conn = connection ?? getConnection();
+  const [pda] = deriveAdminLogPda();
+  const info = await conn.getAccountInfo(pda, "confirmed");
+  if (!info) return [];
+
+  const d = info.data;
+  const LOG_LEN = 128;
+  const ENTRY_LEN = 56;
+  const entriesOffset = 8 + 32 + 8; // discriminator, config, total_entries
+  const headOffset = entriesOffset + LOG_LEN * ENTRY_LEN;
+  if (d.length < headOffset + 2) return [];
+  const head = d[headOffset] ?? 0;
+  const count = Math.min(d[headOffset + 1] ?? 0, LOG_LEN);
+
+  const entries = [];
+  for (let i = 1; i <= count; i++) {
+    const offset = entriesOffset + ((head - i + LOG_LEN) % LOG_LEN) * ENTRY_LEN;
+    entries.push({
+      instructionTag: d.subarray(offset, offset + 8).toString("hex"),
+      actor: new PublicKey(d.subarray(offset + 8, offset + 40)),
+      atSlot: readU64LE(d, offset + 40),
+      atTs: readI64LE(d, offset + 48),
+    });
+  }
+  return entries;
+}
+
+/**
+ * Read the last 32 rumbles a wallet bet on from its BettorIndex, newest
+ * first. Empty until the wallet's first place_bet that passes the index.
+ */
+export async function readBettorIndex(
+  wallet: PublicKey,
+  connection?: Connection
+): Promise<Array<{ rumbleId: bigint; claimed: boolean }>> {
+  const conn = connection ?? getConnection();
+  const [pda] = deriveBettorIndexPda(wallet);
+  const info = await conn.getAccountInfo(pda, "confirmed");
+  if (!info) return [];
+
+  const d = info.data;
+  const INDEX_LEN = 32;
+  const headOffset = 8 + 32; // discriminator, wallet
+  const head = d[headOffset] ?? 0;
+  const count = Math.min(d[headOffset + 1] ?? 0, INDEX_LEN);
+  const rumbleIdsOffset = headOffset + 3; // head, count, bump
+  const claimedOffset = rumbleIdsOffset + 8 * INDEX_LEN;
+  if (d.length < claimedOffset + INDEX_LEN) return [];
+
+  const entries = [];
+  for (let i = 1; i <= count; i++) {
+    const idx = (head - i + INDEX_LEN) % INDEX_LEN;
+    entries.push({
+      rumbleId: readU64LE(d, rumbleIdsOffset + idx * 8),
+      claimed: d[claimedOffset + idx] === 1,
+    });
+  }
+  return entries;
+}
+
 /**
  * Read a bettor account directly from chain.
  * Works with both old and new layouts; missing new fields default to zero.
@@ -1122,10 +1712,11 @@ export async function readBettorAccount(
     offset += 1;
   }
 
-  // New layout appends fighter_deployments: [u64; 16] after bump.
+  // New layout appends fighter_deployments (one u64 per fighter slot) after bump.
+  const slots = bettorFighterSlots(data.length);
   if (data.length >= offset + 8 * 16) {
     // bump byte is consumed above; deployments start at current offset
-    for (let i = 0; i < 16; i++) {
+    for (let i = 0; i < slots; i++) {
       fighterDeploymentsLamports.push(readU64LE(data, offset));
       offset += 8;
     }
@@ -1407,15 +1998,14 @@ export async function registerFighter(
 
   const [fighterPda] = deriveFighterPda(authority, fighterIndex);
 
-  // Encode name as [u8; 32]
-  const nameBytes = new Uint8Array(32);
-  const encoded = new TextEncoder().encode(name.slice(0, 32));
-  nameBytes.set(encoded);
+  const nameBytes = encodeFighterName(name);
+  const [fighterNamePda] = deriveFighterNamePda(nameBytes);
 
   const accounts: Record<string, PublicKey> = {
     authority,
     walletState: walletStatePda,
     fighter: fighterPda,
+    fighterName: fighterNamePda,
     registryConfig: registryConfigPda,
     systemProgram: SystemProgram.programId,
   };
@@ -1446,35 +2036,160 @@ export async function registerFighter(
   return tx;
 }
 
-// ---------------------------------------------------------------------------
-// ICHOR Token Functions
-// ---------------------------------------------------------------------------
+/**
+ * Grow a fighter account created before the attribute fields to the current
+ * layout. Anyone may pay; returns the transaction for `payer` to sign.
+ */
+export async function migrateFighter(
+  payer: PublicKey,
+  fighterPubkey: PublicKey,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getFighterRegistryProgram(provider);
+  const conn = connection ?? getConnection();
 
-type EntropySettings = {
-  programId: PublicKey;
-  varAccount: PublicKey;
-  provider: PublicKey;
-  varAuthority: PublicKey;
-};
+  const tx = await (program.methods as any)
+    .migrateFighter()
+    .accounts({
+      payer,
+      fighter: fighterPubkey,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
 
-let _entropyConfigCacheKey: string | null = null;
-let _runtimeEntropySettings: EntropySettings | null = null;
-let _entropyRotationInFlight: Promise<EntropySettings> | null = null;
+  tx.feePayer = payer;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
 
-function entropyCacheKey(settings: EntropySettings): string {
-  return `${settings.programId.toBase58()}:${settings.varAccount.toBase58()}:${settings.provider.toBase58()}:${settings.varAuthority.toBase58()}`;
+  return tx;
 }
 
-function isTruthyEnv(value: string | undefined): boolean {
-  if (!value) return false;
-  const normalized = value.trim().toLowerCase();
-  return normalized === "1" || normalized === "true" || normalized === "yes" || normalized === "on";
-}
+/**
+ * Rename a fighter (burns 1 ICHOR). Returns the transaction for the user to sign.
+ */
+export async function renameFighter(
+  authority: PublicKey,
+  fighterPubkey: PublicKey,
+  newName: string,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getFighterRegistryProgram(provider);
+  const conn = connection ?? getConnection();
 
-function getEntropySettingsFromEnv(): EntropySettings | null {
-  if (!isTruthyEnv(process.env.ICHOR_ENTROPY_ENABLED)) {
-    return null;
-  }
+  const fighterInfo = await conn.getAccountInfo(fighterPubkey, "confirmed");
+  if (!fighterInfo) throw new Error("Fighter account not found");
+  // Fighter: discriminator(8) + authority(32) + name(32)
+  const [oldFighterNamePda] = deriveFighterNamePda(fighterInfo.data.subarray(40, 72));
+
+  const newNameBytes = encodeFighterName(newName);
+  const [newFighterNamePda] = deriveFighterNamePda(newNameBytes);
+
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ata = await getAssociatedTokenAddress(ichorMint, authority);
+
+  const tx = await (program.methods as any)
+    .renameFighter(Array.from(newNameBytes))
+    .accounts({
+      authority,
+      fighter: fighterPubkey,
+      oldFighterName: oldFighterNamePda,
+      newFighterName: newFighterNamePda,
+      ichorMint,
+      ichorTokenAccount: ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = authority;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Retire a fighter into the Hall of Fame (burns 5 ICHOR). Returns the
+ * transaction for the user to sign.
+ */
+export async function retireFighter(
+  authority: PublicKey,
+  fighterPubkey: PublicKey,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getFighterRegistryProgram(provider);
+  const conn = connection ?? getConnection();
+
+  const [hallOfFamePda] = deriveHallOfFamePda(fighterPubkey);
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ata = await getAssociatedTokenAddress(ichorMint, authority);
+
+  const tx = await (program.methods as any)
+    .retireFighter()
+    .accounts({
+      authority,
+      fighter: fighterPubkey,
+      hallOfFame: hallOfFamePda,
+      ichorMint,
+      ichorTokenAccount: ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = authority;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+// ---------------------------------------------------------------------------
+// ICHOR Token Functions
+// ---------------------------------------------------------------------------
+
+type EntropySettings = {
+  programId: PublicKey;
+  varAccount: PublicKey;
+  provider: PublicKey;
+  varAuthority: PublicKey;
+};
+
+let _entropyConfigCacheKey: string | null = null;
+let _runtimeEntropySettings: EntropySettings | null = null;
+let _entropyRotationInFlight: Promise<EntropySettings> | null = null;
+
+function entropyCacheKey(settings: EntropySettings): string {
+  return `${settings.programId.toBase58()}:${settings.varAccount.toBase58()}:${settings.provider.toBase58()}:${settings.varAuthority.toBase58()}`;
+}
+
+function isTruthyEnv(value: string | undefined): boolean {
+  if (!value) return false;
+  const normalized = value.trim().toLowerCase();
+  return normalized === "1" || normalized === "true" || normalized === "yes" || normalized === "on";
+}
+
+function getEntropySettingsFromEnv(): EntropySettings | null {
+  if (!isTruthyEnv(process.env.ICHOR_ENTROPY_ENABLED)) {
+    return null;
+  }
 
   const programIdRaw = process.env.ICHOR_ENTROPY_PROGRAM_ID;
   const varRaw = process.env.ICHOR_ENTROPY_VAR;
@@ -1832,6 +2547,20 @@ export async function initializeWithMint(
   return tx;
 }
 
+/**
+ * Season config accounts for distribute_reward: the current season
+ * (ICHOR_SEASON_ID, default 1) and the next one once it has been created.
+ */
+async function resolveSeasonConfigAccounts(
+  connection: Connection
+): Promise<{ seasonConfig: PublicKey; nextSeasonConfig: PublicKey | null }> {
+  const seasonId = BigInt(process.env.ICHOR_SEASON_ID?.trim() || "1");
+  const [seasonConfig] = deriveSeasonConfigPda(seasonId);
+  const [nextPda] = deriveSeasonConfigPda(seasonId + 1n);
+  const nextInfo = await connection.getAccountInfo(nextPda, "confirmed");
+  return { seasonConfig, nextSeasonConfig: nextInfo ? nextPda : null };
+}
+
 /**
  * Distribute rumble reward from vault to the winner (admin/server-side).
  * Returns tx signature on success, null if admin keypair unavailable.
@@ -1852,6 +2581,7 @@ export async function distributeReward(
   const [arenaConfigPda] = deriveArenaConfigPda();
   const [distributionVaultPda] = deriveDistributionVaultPda();
   const ichorMint = getIchorMint();
+  const seasonAccounts = await resolveSeasonConfigAccounts(connection ?? getConnection());
 
   const method = (program.methods as any)
     .distributeReward()
@@ -1863,14 +2593,194 @@ export async function distributeReward(
       winnerTokenAccount,
       showerVault,
       tokenProgram: TOKEN_PROGRAM_ID,
+      ...seasonAccounts,
     });
 
   return await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
 }
 
+/**
+ * batch_distribute_reward: distributeReward for up to 8 completed rumbles in
+ * one transaction. `rewards[i].winnerTokenAccount` is passed as the i-th
+ * remaining account.
+ */
+export async function batchDistributeReward(
+  rewards: { rumbleId: bigint | number; winnerTokenAccount: PublicKey }[],
+  showerVault: PublicKey,
+  connection?: Connection
+): Promise<string | null> {
+  if (rewards.length === 0 || rewards.length > 8) {
+    throw new Error("batchDistributeReward takes 1 to 8 rumbles");
+  }
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping batchDistributeReward");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [distributionVaultPda] = deriveDistributionVaultPda();
+  const ichorMint = getIchorMint();
+  const seasonAccounts = await resolveSeasonConfigAccounts(connection ?? getConnection());
+
+  const method = (program.methods as any)
+    .batchDistributeReward(
+      rewards.map((r) => new anchor.BN(r.rumbleId.toString())),
+      rewards.map((r) => r.winnerTokenAccount)
+    )
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+      distributionVault: distributionVaultPda,
+      ichorMint,
+      showerVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      ...seasonAccounts,
+    })
+    .remainingAccounts(
+      rewards.map((r) => ({ pubkey: r.winnerTokenAccount, isSigner: false, isWritable: true }))
+    );
+
+  return await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
+}
+
 /** @deprecated Use distributeReward instead */
 export const mintRumbleReward = distributeReward;
 
+/**
+ * Build a stake_ichor transaction: locks `amount` ICHOR (base units) for
+ * `lockPeriodSeconds` (7 to 365 days). Longer locks earn a bigger bonus on
+ * betting winnings while the lock runs.
+ */
+export async function buildStakeIchorTx(
+  staker: PublicKey,
+  amount: bigint | number,
+  lockPeriodSeconds: number,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getIchorTokenProgram(provider);
+  const conn = connection ?? getConnection();
+
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ata = await getAssociatedTokenAddress(ichorMint, staker);
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [ichorStakePda] = deriveIchorStakePda(staker);
+  const [stakeVaultPda] = deriveStakeVaultPda();
+
+  const tx = await (program.methods as any)
+    .stakeIchor(new anchor.BN(amount.toString()), new anchor.BN(lockPeriodSeconds))
+    .accounts({
+      staker,
+      arenaConfig: arenaConfigPda,
+      ichorMint,
+      stakerTokenAccount: ata,
+      ichorStake: ichorStakePda,
+      stakeVault: stakeVaultPda,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .transaction();
+
+  tx.feePayer = staker;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Build an unstake_ichor transaction. Only succeeds once the stake's lock
+ * period has elapsed; closes the stake account.
+ */
+export async function buildUnstakeIchorTx(
+  staker: PublicKey,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getIchorTokenProgram(provider);
+  const conn = connection ?? getConnection();
+
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ata = await getAssociatedTokenAddress(ichorMint, staker);
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [ichorStakePda] = deriveIchorStakePda(staker);
+  const [stakeVaultPda] = deriveStakeVaultPda();
+
+  const tx = await (program.methods as any)
+    .unstakeIchor()
+    .accounts({
+      staker,
+      arenaConfig: arenaConfigPda,
+      ichorMint,
+      stakerTokenAccount: ata,
+      ichorStake: ichorStakePda,
+      stakeVault: stakeVaultPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .transaction();
+
+  tx.feePayer = staker;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Build a top_up_shower_pool transaction donating `amount` ICHOR base units
+ * from the contributor's ATA to the shower vault. Permissionless.
+ */
+export async function buildTopUpShowerPoolTx(
+  contributor: PublicKey,
+  amount: bigint | number,
+  showerVault: PublicKey,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getIchorTokenProgram(provider);
+  const conn = connection ?? getConnection();
+
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ata = await getAssociatedTokenAddress(ichorMint, contributor);
+  const [arenaConfigPda] = deriveArenaConfigPda();
+
+  const tx = await (program.methods as any)
+    .topUpShowerPool(new anchor.BN(amount.toString()))
+    .accounts({
+      contributor,
+      arenaConfig: arenaConfigPda,
+      ichorMint,
+      contributorTokenAccount: ata,
+      showerVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .transaction();
+
+  tx.feePayer = contributor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
 /**
  * Check for ichor shower trigger (admin/server-side).
  * Returns tx signature on success, null if admin keypair unavailable.
@@ -1890,6 +2800,7 @@ export async function checkIchorShower(
 
   const [arenaConfigPda] = deriveArenaConfigPda();
   const [showerRequestPda] = deriveShowerRequestPda();
+  const [showerHistoryPda] = deriveShowerHistoryPda();
   const [entropyConfigPda] = deriveEntropyConfigPda();
   const ichorMint = getIchorMint();
   const slotHashesSysvar = SLOT_HASHES_SYSVAR_ID;
@@ -1906,8 +2817,10 @@ export async function checkIchorShower(
     authority: admin.publicKey,
     arenaConfig: arenaConfigPda,
     showerRequest: showerRequestPda,
+    showerHistory: showerHistoryPda,
     ichorMint,
     recipientTokenAccount,
+    showerCap: deriveShowerCapPda(recipientTokenAccount)[0],
     showerVault,
     slotHashes: slotHashesSysvar,
     systemProgram: SystemProgram.programId,
@@ -1960,6 +2873,7 @@ export async function transferAdmin(
       arenaConfig: arenaConfigPda,
       pendingAdmin: pendingAdminPda,
       systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPda()[0],
     })
     .rpc();
 
@@ -1990,6 +2904,38 @@ export async function acceptAdmin(
       newAdmin: newAdminKeypair.publicKey,
       arenaConfig: arenaConfigPda,
       pendingAdmin: pendingAdminPda,
+      adminLog: deriveAdminLogPda()[0],
+    })
+    .rpc();
+
+  return tx;
+}
+
+/**
+ * Withdraw a pending admin transfer for the ICHOR program (current admin only).
+ * Proposals also lapse on their own ~7 days after transferAdmin().
+ */
+export async function revokePendingAdmin(
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping revokePendingAdmin");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [pendingAdminPda] = derivePendingAdminPda();
+
+  const tx = await (program.methods as any)
+    .revokePendingAdmin()
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+      pendingAdmin: pendingAdminPda,
+      adminLog: deriveAdminLogPda()[0],
     })
     .rpc();
 
@@ -2034,22 +2980,65 @@ export async function adminDistribute(
 }
 
 /**
- * Admin: update the on-chain season reward (flat ICHOR per rumble).
- * Returns tx signature on success, null if admin keypair unavailable.
+ * Build a fund_distribution_vault transaction moving `amount` ICHOR base units
+ * from the funder's ATA into the distribution vault. Open to any funder unless
+ * the admin has made refills admin-only.
  */
-export async function updateSeasonReward(
-  newSeasonReward: bigint | number,
+export async function buildFundDistributionVaultTx(
+  funder: PublicKey,
+  amount: bigint | number,
   connection?: Connection
-): Promise<string | null> {
-  const provider = getAdminProvider(connection);
-  if (!provider) {
-    console.warn("[solana-programs] No admin keypair, skipping updateSeasonReward");
-    return null;
-  }
+): Promise<Transaction> {
+  const provider = getProvider(connection);
   const program = getIchorTokenProgram(provider);
-  const admin = getAdminKeypair()!;
-  const [arenaConfigPda] = deriveArenaConfigPda();
-  const value = BigInt(newSeasonReward);
+  const conn = connection ?? getConnection();
+
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ata = await getAssociatedTokenAddress(ichorMint, funder);
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [distributionVaultPda] = deriveDistributionVaultPda();
+
+  const tx = await (program.methods as any)
+    .fundDistributionVault(new anchor.BN(amount.toString()))
+    .accounts({
+      funder,
+      arenaConfig: arenaConfigPda,
+      ichorMint,
+      sourceTokenAccount: ata,
+      distributionVault: distributionVaultPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .transaction();
+
+  tx.feePayer = funder;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Admin: update the on-chain season reward (flat ICHOR per rumble).
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function updateSeasonReward(
+  newSeasonReward: bigint | number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateSeasonReward");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const value = BigInt(newSeasonReward);
   const method = (program.methods as any)?.updateSeasonReward;
 
   // Some local IDLs are stale and may not expose updateSeasonReward yet.
@@ -2079,7 +3068,186 @@ export async function updateSeasonReward(
 }
 
 /**
- * Admin: migrate legacy ArenaConfig account to V2 layout and set season reward.
+ * Admin: create the SeasonConfig for `seasonId` (ids start at 1). The three
+ * reward shares must sum to 10000 bps; `fighterFirstShareBps` is a share of
+ * the fighter pool. Season N > 1 must start at or after season N-1 ends.
+ */
+export async function startNewSeason(
+  seasonId: bigint | number,
+  params: {
+    startRumble: bigint | number;
+    endRumble: bigint | number;
+    bettorShareBps: number;
+    fighterShareBps: number;
+    showerShareBps: number;
+    fighterFirstShareBps: number;
+  },
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping startNewSeason");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const id = BigInt(seasonId);
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [seasonConfigPda] = deriveSeasonConfigPda(id);
+
+  return await (program.methods as any)
+    .startNewSeason(new anchor.BN(id.toString()), {
+      startRumble: new anchor.BN(params.startRumble.toString()),
+      endRumble: new anchor.BN(params.endRumble.toString()),
+      bettorShareBps: params.bettorShareBps,
+      fighterShareBps: params.fighterShareBps,
+      showerShareBps: params.showerShareBps,
+      fighterFirstShareBps: params.fighterFirstShareBps,
+    })
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+      seasonConfig: seasonConfigPda,
+      previousSeasonConfig: id > 1n ? deriveSeasonConfigPda(id - 1n)[0] : null,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+/**
+ * Admin: update the Ichor Shower trigger chance (1 in `newChance`, 50-5000).
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function updateShowerChance(
+  newChance: bigint | number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateShowerChance");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+
+  return await (program.methods as any)
+    .updateShowerChance(new anchor.BN(newChance.toString()))
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+    })
+    .rpc();
+}
+
+/**
+ * Admin: set the share of each triggered Ichor Shower that is burned, in bps
+ * (at most 5000). The recipient receives the rest.
+ */
+export async function updateShowerBurnBps(
+  newBurnBps: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateShowerBurnBps");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+
+  return await (program.methods as any)
+    .updateShowerBurnBps(newBurnBps)
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+    })
+    .rpc();
+}
+
+/**
+ * Admin: set how many slots must pass after a shower settles before a new
+ * shower can be requested (at most 216,000).
+ */
+export async function updateShowerCooldown(
+  newCooldownSlots: bigint | number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateShowerCooldown");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+
+  return await (program.methods as any)
+    .updateShowerCooldown(new anchor.BN(newCooldownSlots.toString()))
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+    })
+    .rpc();
+}
+
+/**
+ * Admin: set the most shower payout (raw units) one recipient can receive per
+ * day; anything above it is burned.
+ */
+export async function updateMaxShowerPerDay(
+  newMax: bigint | number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateMaxShowerPerDay");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+
+  return await (program.methods as any)
+    .updateMaxShowerPerDay(new anchor.BN(newMax.toString()))
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+    })
+    .rpc();
+}
+
+/**
+ * Admin: restrict fundDistributionVault refills to the admin, or reopen them.
+ */
+export async function updateRefillRequireAdmin(
+  requireAdmin: boolean,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateRefillRequireAdmin");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+
+  return await (program.methods as any)
+    .updateRefillRequireAdmin(requireAdmin)
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+    })
+    .rpc();
+}
+
+/**
+ * Admin: migrate a legacy ArenaConfig account to the current layout and set
+ * season reward (shower chance defaults to 1 in 500, shower burn to 1000 bps,
+ * shower cooldown to 1000 slots and the daily shower cap to 100k ICHOR if
+ * missing).
  * Use when on-chain account fails to deserialize on updateSeasonReward.
  */
 export async function migrateArenaConfigV2(
@@ -2158,6 +3326,34 @@ export async function revokeMintAuthority(
 // Rumble Engine Functions
 // ---------------------------------------------------------------------------
 
+/** Fighters a create_rumble transaction carries; the rest go in append_rumble_fighters. */
+const CREATE_RUMBLE_FIGHTERS_PER_TX = 16;
+
+/**
+ * create_rumble args after `betting_deadline`, all at the program defaults:
+ * default payout split, no bet cap or pool minimum, no BetRecords, no
+ * registry check, open betting, no crits, no ICHOR gate, 24-hour claim window.
+ * `fighterSlots` = 32 puts the rumble on the v2 layout; null keeps 16 slots.
+ */
+function defaultCreateRumbleArgs(
+  deadlineMode: "slot" | "unix",
+  fighterSlots: number | null = null,
+): unknown[] {
+  return [
+    null,
+    new anchor.BN(0),
+    new anchor.BN(0),
+    deadlineMode === "unix" ? { unixTimestamp: {} } : { slot: {} },
+    false,
+    false,
+    false,
+    false,
+    null,
+    null,
+    fighterSlots,
+  ];
+}
+
 /**
  * Create a new rumble (admin/server-side).
  * Returns tx signature on success, null if admin keypair unavailable.
@@ -2178,6 +3374,11 @@ export async function createRumble(
 
   const [rumbleConfigPda] = deriveRumbleConfigPda();
   const [rumblePda] = deriveRumblePda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+  // Optional balance ruleset; unset keeps the program's built-in combat constants.
+  const rulesetIdRaw = Number(process.env.RUMBLE_COMBAT_RULESET_ID ?? "");
+  const rulesetPda =
+    Number.isInteger(rulesetIdRaw) && rulesetIdRaw > 0 ? deriveCombatRulesetPda(rulesetIdRaw)[0] : null;
   const nowUnix = Math.floor(Date.now() / 1000);
   const deadlineModeRaw = (process.env.RUMBLE_CREATE_DEADLINE_MODE ?? "slot").trim().toLowerCase();
   const prefersUnixDeadline = deadlineModeRaw === "unix";
@@ -2211,6 +3412,12 @@ export async function createRumble(
   );
   const bettingCloseUnix = BigInt(Math.max(bettingDeadlineUnix, nowUnix + minCloseSeconds));
 
+  // More than 16 fighters need the 32-slot v2 layout, and more pubkeys than
+  // fit in one transaction: the rest are appended once the rumble exists.
+  const fighterSlots = fighters.length > 16 ? 32 : null;
+  const createFighters = fighters.slice(0, CREATE_RUMBLE_FIGHTERS_PER_TX);
+  const appendFighters = fighters.slice(CREATE_RUMBLE_FIGHTERS_PER_TX);
+
   console.log(`[ONCHAIN-CREATE] Sending createRumble for rumble ${rumbleId} (${fighters.length} fighters)...`);
   const createWithDeadline = async (
     closeValue: bigint,
@@ -2219,20 +3426,43 @@ export async function createRumble(
     const method = (program.methods as any)
       .createRumble(
         new anchor.BN(rumbleId),
-        fighters,
-        new anchor.BN(closeValue.toString())
+        createFighters,
+        new anchor.BN(closeValue.toString()),
+        ...defaultCreateRumbleArgs(effectiveMode, fighterSlots),
       )
       .accounts({
         admin: admin.publicKey,
         config: rumbleConfigPda,
         rumble: rumblePda,
+        ruleset: rulesetPda,
+        vault: vaultPda,
         systemProgram: SystemProgram.programId,
+        globalStats: await findGlobalStats(provider.connection),
+        adminLog: deriveAdminLogPda()[0],
       })
 ;
 
     try {
-      const sig = await sendAdminTxFireAndForget(method, admin, provider.connection);
+      if (appendFighters.length === 0) {
+        const sig = await sendAdminTxFireAndForget(method, admin, provider.connection);
+        console.log(`[ONCHAIN-CREATE] createRumble confirmed for rumble ${rumbleId}: ${sig}`);
+        return sig;
+      }
+      const { signature: sig } = await sendAdminTxWithConfirmation(method, admin, provider.connection);
       console.log(`[ONCHAIN-CREATE] createRumble confirmed for rumble ${rumbleId}: ${sig}`);
+      const appendMethod = (program.methods as any)
+        .appendRumbleFighters(new anchor.BN(rumbleId), appendFighters)
+        .accounts({
+          admin: admin.publicKey,
+          config: rumbleConfigPda,
+          rumble: rumblePda,
+          globalStats: await findGlobalStats(provider.connection),
+          adminLog: deriveAdminLogPda()[0],
+        });
+      const appendSig = await sendAdminTxFireAndForget(appendMethod, admin, provider.connection);
+      console.log(
+        `[ONCHAIN-CREATE] appendRumbleFighters sent for rumble ${rumbleId} (${appendFighters.length} fighters): ${appendSig}`,
+      );
       return sig;
     } catch (err: unknown) {
       const context =
@@ -2314,12 +3544,14 @@ export function computeMoveCommitmentHash(
 /**
  * Build a fighter-signed authorize_fighter_delegate transaction.
  * The fighter signs once to bind a persistent delegate authority that can
- * submit commit/reveal turns across future rumbles.
+ * submit commit/reveal turns across future rumbles, until revoked or, if
+ * `expiresAtSlot` is set, until that slot passes.
  */
 export async function buildAuthorizeFighterDelegateTx(
   fighter: PublicKey,
   authority: PublicKey,
   connection?: Connection,
+  expiresAtSlot: bigint | number = 0,
 ): Promise<Transaction> {
   const provider = getProvider(connection);
   const program = getRumbleEngineProgram(provider);
@@ -2331,7 +3563,7 @@ export async function buildAuthorizeFighterDelegateTx(
   }
 
   const tx = await (program.methods as any)
-    .authorizeFighterDelegate(authority)
+    .authorizeFighterDelegate(authority, new anchor.BN(expiresAtSlot.toString()))
     .accounts({
       fighter,
       fighterDelegate: fighterDelegatePda,
@@ -2340,6 +3572,21 @@ export async function buildAuthorizeFighterDelegateTx(
     })
     .transaction();
 
+  // Delegate accounts from before expiry support (82 bytes) must be grown first.
+  const existing = await conn.getAccountInfo(fighterDelegatePda, "processed");
+  if (existing && existing.data.length === 82) {
+    const migrateIx = await (program.methods as any)
+      .migrateFighterDelegate()
+      .accounts({
+        payer: sponsor.publicKey,
+        fighter,
+        fighterDelegate: fighterDelegatePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+    tx.instructions.unshift(migrateIx);
+  }
+
   tx.feePayer = sponsor.publicKey;
   const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "confirmed");
   tx.recentBlockhash = blockhash;
@@ -2412,7 +3659,7 @@ export async function buildCommitMoveTx(
   const program = getRumbleEngineProgram(provider);
   const [rumblePda] = deriveRumblePda(rumbleId);
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
-  const [moveCommitmentPda] = deriveMoveCommitmentPda(rumbleId, fighter, turn);
+  const [moveCommitmentPda] = deriveMoveCommitmentPda(rumbleId, fighter);
   const [fighterDelegatePda] = deriveFighterDelegatePda(fighter);
   const conn = connection ?? getConnection();
   const authority = options?.authority ?? fighter;
@@ -2428,6 +3675,7 @@ export async function buildCommitMoveTx(
       authority,
       fighter,
       payer,
+      config: deriveRumbleConfigPda()[0],
       rumble: rumblePda,
       combatState: combatStatePda,
       moveCommitment: moveCommitmentPda,
@@ -2473,7 +3721,7 @@ export async function buildRevealMoveTx(
   const program = getRumbleEngineProgram(provider);
   const [rumblePda] = deriveRumblePda(rumbleId);
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
-  const [moveCommitmentPda] = deriveMoveCommitmentPda(rumbleId, fighter, turn);
+  const [moveCommitmentPda] = deriveMoveCommitmentPda(rumbleId, fighter);
   const [fighterDelegatePda] = deriveFighterDelegatePda(fighter);
   const conn = connection ?? getConnection();
   const authority = options?.authority ?? fighter;
@@ -2488,6 +3736,7 @@ export async function buildRevealMoveTx(
     .accounts({
       authority,
       fighter,
+      config: deriveRumbleConfigPda()[0],
       rumble: rumblePda,
       combatState: combatStatePda,
       moveCommitment: moveCommitmentPda,
@@ -2504,6 +3753,8 @@ export async function buildRevealMoveTx(
 
 /**
  * Build a place_bet transaction for the user to sign.
+ * An optional `referrer` receives part of the admin fee in its referral PDA.
+ * `insured` buys loss insurance; the config's premium is charged on top of `lamports`.
  */
 export async function buildPlaceBetTx(
   bettor: PublicKey,
@@ -2512,6 +3763,8 @@ export async function buildPlaceBetTx(
   lamports: number,
   connection?: Connection,
   programId?: PublicKey,
+  referrer?: PublicKey,
+  insured = false,
 ): Promise<Transaction> {
   const provider = getProvider(connection);
   const program = getRumbleEngineProgram(provider, programId);
@@ -2524,7 +3777,10 @@ export async function buildPlaceBetTx(
     treasury,
     fighterPubkeys,
     fighterCount,
+    whitelistPda,
+    ichorBurnPerBet,
   } = await loadRumbleBetContext(rumbleId, conn, programId);
+  const burnAccounts = await ichorBurnAccounts(bettor, ichorBurnPerBet);
   if (fighterIndex >= fighterCount) {
     throw new Error("Invalid fighter index");
   }
@@ -2533,14 +3789,24 @@ export async function buildPlaceBetTx(
     throw new Error(`Invalid fighter index ${fighterIndex} for rumble ${rumbleId}`);
   }
   const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
+  const globalStats = await findGlobalStats(conn, useMainnet);
+  const feeExemption = await findFeeExemption(bettor, conn, useMainnet);
   const [sponsorshipPda] = useMainnet ? deriveSponsorshipPdaMainnet(fighterPubkey) : deriveSponsorshipPda(fighterPubkey);
+  const [sponsorshipLedgerPda] = useMainnet
+    ? deriveSponsorshipLedgerPdaMainnet(fighterPubkey)
+    : deriveSponsorshipLedgerPda(fighterPubkey);
   const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+  const [rateLimitPda] = useMainnet ? deriveRateLimitPdaMainnet(bettor, rumbleId) : deriveRateLimitPda(bettor, rumbleId);
+  const referralPda = referrer
+    ? (useMainnet ? deriveReferralPdaMainnet(referrer) : deriveReferralPda(referrer))[0]
+    : null;
 
   const tx = await (program.methods as any)
     .placeBet(
       new anchor.BN(rumbleId),
       fighterIndex,
-      new anchor.BN(lamports)
+      new anchor.BN(lamports),
+      insured
     )
     .accounts({
       bettor,
@@ -2549,8 +3815,19 @@ export async function buildPlaceBetTx(
       treasury,
       config: rumbleConfigPda,
       sponsorshipAccount: sponsorshipPda,
+      sponsorshipLedger: sponsorshipLedgerPda,
       bettorAccount: bettorAccountPda,
+      betRecord: null,
+      referrer: referrer ?? null,
+      referralAccount: referralPda,
+      whitelist: whitelistPda,
+      ...burnAccounts,
       systemProgram: SystemProgram.programId,
+      bettorIndex: useMainnet ? null : deriveBettorIndexPda(bettor)[0],
+      globalStats,
+      bettorStats: useMainnet ? null : deriveBettorStatsPda(bettor)[0],
+      feeExemption,
+      rateLimit: rateLimitPda,
     })
     .transaction();
 
@@ -2572,18 +3849,25 @@ export async function buildPlaceBetTx(
 export async function buildPlaceBetBatchTx(
   bettor: PublicKey,
   rumbleId: number,
-  bets: Array<{ fighterIndex: number; lamports: number }>,
+  bets: Array<{ fighterIndex: number; lamports: number; insured?: boolean }>,
   connection?: Connection,
   programId?: PublicKey,
+  referrer?: PublicKey,
 ): Promise<Transaction> {
   if (!Array.isArray(bets) || bets.length === 0) {
     throw new Error("At least one bet is required for batch place_bet");
   }
+  // Every leg lands in the same slot, so the program's per-slot cap applies.
+  if (bets.length > MAX_BETS_PER_SLOT) {
+    throw new Error(`Batch place_bet is limited to ${MAX_BETS_PER_SLOT} bets`);
+  }
 
   const provider = getProvider(connection);
   const program = getRumbleEngineProgram(provider, programId);
   const conn = connection ?? getConnection();
   const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
+  const globalStats = await findGlobalStats(conn, useMainnet);
+  const feeExemption = await findFeeExemption(bettor, conn, useMainnet);
 
   const {
     rumbleConfigPda,
@@ -2592,8 +3876,15 @@ export async function buildPlaceBetBatchTx(
     treasury,
     fighterPubkeys,
     fighterCount,
+    whitelistPda,
+    ichorBurnPerBet,
   } = await loadRumbleBetContext(rumbleId, conn, programId);
+  const burnAccounts = await ichorBurnAccounts(bettor, ichorBurnPerBet);
   const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+  const [rateLimitPda] = useMainnet ? deriveRateLimitPdaMainnet(bettor, rumbleId) : deriveRateLimitPda(bettor, rumbleId);
+  const referralPda = referrer
+    ? (useMainnet ? deriveReferralPdaMainnet(referrer) : deriveReferralPda(referrer))[0]
+    : null;
 
   const tx = new Transaction();
 
@@ -2613,12 +3904,16 @@ export async function buildPlaceBetBatchTx(
       throw new Error(`Fighter index ${leg.fighterIndex} not found in rumble ${rumbleId}`);
     }
     const [sponsorshipPda] = useMainnet ? deriveSponsorshipPdaMainnet(fighterPubkey) : deriveSponsorshipPda(fighterPubkey);
+    const [sponsorshipLedgerPda] = useMainnet
+      ? deriveSponsorshipLedgerPdaMainnet(fighterPubkey)
+      : deriveSponsorshipLedgerPda(fighterPubkey);
 
     const ix = await (program.methods as any)
       .placeBet(
         new anchor.BN(rumbleId),
         leg.fighterIndex,
         new anchor.BN(leg.lamports),
+        leg.insured ?? false,
       )
       .accounts({
         bettor,
@@ -2627,8 +3922,19 @@ export async function buildPlaceBetBatchTx(
         treasury,
         config: rumbleConfigPda,
         sponsorshipAccount: sponsorshipPda,
+        sponsorshipLedger: sponsorshipLedgerPda,
         bettorAccount: bettorAccountPda,
+        betRecord: null,
+        referrer: referrer ?? null,
+        referralAccount: referralPda,
+        whitelist: whitelistPda,
+        ...burnAccounts,
         systemProgram: SystemProgram.programId,
+        bettorIndex: useMainnet ? null : deriveBettorIndexPda(bettor)[0],
+        globalStats,
+        bettorStats: useMainnet ? null : deriveBettorStatsPda(bettor)[0],
+        feeExemption,
+        rateLimit: rateLimitPda,
       })
       .instruction();
 
@@ -2653,21 +3959,27 @@ async function loadRumbleBetContext(
   treasury: PublicKey;
   fighterPubkeys: PublicKey[];
   fighterCount: number;
+  /** Set only for invite-only rumbles, whose whitelist PDA exists. */
+  whitelistPda: PublicKey | null;
+  /** ICHOR (base units) place_bet burns from the bettor; 0 = no burn. */
+  ichorBurnPerBet: bigint;
 }> {
   const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
   const [rumbleConfigPda] = useMainnet ? deriveRumbleConfigPdaMainnet() : deriveRumbleConfigPda();
   const [rumblePda] = useMainnet ? deriveRumblePdaMainnet(rumbleId) : deriveRumblePda(rumbleId);
   const [vaultPda] = useMainnet ? deriveVaultPdaMainnet(rumbleId) : deriveVaultPda(rumbleId);
+  const [whitelistPda] = useMainnet ? deriveWhitelistPdaMainnet(rumbleId) : deriveWhitelistPda(rumbleId);
 
-  const [rumbleInfo, configInfo] = await Promise.all([
+  const [rumbleInfo, configInfo, whitelistInfo] = await Promise.all([
     conn.getAccountInfo(rumblePda),
     conn.getAccountInfo(rumbleConfigPda),
+    conn.getAccountInfo(whitelistPda),
   ]);
   if (!rumbleInfo) throw new Error(`Rumble account not found: ${rumblePda}`);
   if (!configInfo) throw new Error("Rumble config not found");
 
   const fighterOffsetBase = 8 + 8 + 1;
-  const fighterCountOffset = fighterOffsetBase + 32 * 16;
+  const fighterCountOffset = fighterOffsetBase + 32 * rumbleFighterSlots(rumbleInfo.data.length);
   const fighterCount = rumbleInfo.data[fighterCountOffset] ?? 0;
   const fighterPubkeys: PublicKey[] = [];
   for (let i = 0; i < fighterCount; i++) {
@@ -2679,6 +3991,10 @@ async function loadRumbleBetContext(
 
   // RumbleConfig: discriminator(8) + admin(32) + treasury(32)
   const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
+  // RumbleConfig.ichor_burn_per_bet: last field, absent on unmigrated configs
+  const ichorBurnPerBet = configInfo.data.length >= 136
+    ? configInfo.data.readBigUInt64LE(128)
+    : 0n;
 
   return {
     rumbleConfigPda,
@@ -2687,11 +4003,34 @@ async function loadRumbleBetContext(
     treasury,
     fighterPubkeys,
     fighterCount,
+    whitelistPda: whitelistInfo ? whitelistPda : null,
+    ichorBurnPerBet,
   };
 }
 
 /**
- * Start combat for a rumble (admin/server-side).
+ * Optional place_bet accounts for the ICHOR burn gate; all null while the
+ * config's `ichor_burn_per_bet` is 0.
+ */
+async function ichorBurnAccounts(
+  bettor: PublicKey,
+  ichorBurnPerBet: bigint,
+): Promise<{
+  ichorTokenAccount: PublicKey | null;
+  ichorMint: PublicKey | null;
+  tokenProgram: PublicKey | null;
+}> {
+  if (ichorBurnPerBet === 0n) {
+    return { ichorTokenAccount: null, ichorMint: null, tokenProgram: null };
+  }
+  const ichorMint = getIchorMint();
+  const { getAssociatedTokenAddress } = await import("@solana/spl-token");
+  const ichorTokenAccount = await getAssociatedTokenAddress(ichorMint, bettor);
+  return { ichorTokenAccount, ichorMint, tokenProgram: TOKEN_PROGRAM_ID };
+}
+
+/**
+ * Start combat for a rumble (server-side keeper; permissionless after the betting deadline).
  * Returns tx signature on success, null if admin keypair unavailable.
  */
 export async function startCombat(
@@ -2709,15 +4048,33 @@ export async function startCombat(
   const [rumbleConfigPda] = deriveRumbleConfigPda();
   const [rumblePda] = deriveRumblePda(rumbleId);
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const rumbleState = await readRumbleAccountState(rumbleId, connection);
+  // Registry accounts that exist: combat snapshots their attributes, and
+  // fighters without one (Supabase-only) start on base stats.
+  const fighters = rumbleState?.fighters ?? [];
+  const fighterInfos = fighters.length
+    ? await (connection ?? getConnection()).getMultipleAccountsInfo(fighters)
+    : [];
+  const registeredFighters = fighters.filter((_, i) =>
+    fighterInfos[i]?.owner.equals(FIGHTER_REGISTRY_ID),
+  );
 
   console.log(`[ONCHAIN-START] Sending startCombat for rumble ${rumbleId}...`);
   const method = (program.methods as any)
     .startCombat()
+    .remainingAccounts(
+      registeredFighters.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
+    )
     .accounts({
-      admin: admin.publicKey,
+      keeper: admin.publicKey,
       config: rumbleConfigPda,
       rumble: rumblePda,
       combatState: combatStatePda,
+      ruleset: rumbleState?.ruleset ?? null,
       systemProgram: SystemProgram.programId,
     });
 
@@ -2727,55 +4084,319 @@ export async function startCombat(
 }
 
 /**
- * Send an admin-signed transaction without waiting for confirmation.
- * The on-chain state polling loop will pick up results on the next tick.
+ * Move a combat state created before the zero-copy layout onto it (server-side
+ * keeper; permissionless). Returns tx signature on success, null if admin
+ * keypair unavailable.
  */
-async function sendAdminTxFireAndForget(
-  method: any,
-  admin: Keypair,
-  connection?: Connection,
-): Promise<string> {
-  const conn = connection ?? getConnection();
-  const isEr = (conn as any)._rpcEndpoint?.includes("magicblock") ||
-    conn.rpcEndpoint?.includes("magicblock");
-  const routerConn = conn as ErRouterLikeConnection;
-  const shouldRetryBlockhash = (err: unknown): boolean => {
-    const msg = err instanceof Error ? err.message : String(err);
-    return /blockhash not found/i.test(msg);
-  };
+export async function migrateCombatState(
+  rumbleId: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping migrateCombatState");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
 
-  const assignBlockhash = async (tx: Transaction): Promise<void> => {
-    if (isEr && typeof routerConn.getLatestBlockhashForTransaction === "function") {
-      try {
-        const { blockhash, lastValidBlockHeight } = await routerConn.getLatestBlockhashForTransaction(tx, {
-          commitment: "processed",
-        });
-        tx.recentBlockhash = blockhash;
-        tx.lastValidBlockHeight = lastValidBlockHeight;
-        return;
-      } catch (error) {
-        console.warn("[ER] getLatestBlockhashForTransaction failed; falling back to getLatestBlockhash:", error);
-      }
-    }
-    const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "processed");
-    tx.recentBlockhash = blockhash;
-    tx.lastValidBlockHeight = lastValidBlockHeight;
-  };
+  const method = (program.methods as any)
+    .migrateCombatState(new anchor.BN(rumbleId))
+    .accounts({
+      payer: admin.publicKey,
+      combatState: deriveCombatStatePda(rumbleId)[0],
+      systemProgram: SystemProgram.programId,
+    });
 
-  const enrichSendError = async (error: unknown): Promise<unknown> => {
-    if (!(error instanceof SendTransactionError)) return error;
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
 
-    let logs = Array.isArray(error.logs)
-      ? error.logs.filter((line): line is string => typeof line === "string" && line.trim().length > 0)
-      : [];
-    if (logs.length === 0) {
-      try {
-        const fetchedLogs = await error.getLogs(conn);
-        if (Array.isArray(fetchedLogs)) {
-          logs = fetchedLogs.filter((line): line is string => typeof line === "string" && line.trim().length > 0);
-        }
-      } catch {
-        // Ignore secondary log-fetch failures and preserve the original error.
+/**
+ * Add or remove bettors on an invite-only rumble's whitelist (admin/server-side).
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function updateRumbleWhitelist(
+  rumbleId: number,
+  pubkeys: PublicKey[],
+  action: "add" | "remove",
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping updateRumbleWhitelist");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [whitelistPda] = deriveWhitelistPda(rumbleId);
+
+  const instruction = action === "add" ? "addToWhitelist" : "removeFromWhitelist";
+  const method = (program.methods as any)[instruction](new anchor.BN(rumbleId), pubkeys).accounts({
+    admin: admin.publicKey,
+    config: rumbleConfigPda,
+    whitelist: whitelistPda,
+    adminLog: deriveAdminLogPda()[0],
+  });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Grant or extend a promo wallet's admin-fee exemption until `expiresSlot`
+ * (admin/server-side). Returns tx signature on success, null if admin keypair
+ * unavailable.
+ */
+export async function grantFeeExemption(
+  wallet: PublicKey,
+  expiresSlot: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping grantFeeExemption");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const method = (program.methods as any)
+    .grantFeeExemption(wallet, new anchor.BN(expiresSlot))
+    .accounts({
+      admin: admin.publicKey,
+      config: deriveRumbleConfigPda()[0],
+      feeExemption: deriveFeeExemptionPda(wallet)[0],
+      systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPda()[0],
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Close a wallet's admin-fee exemption (admin/server-side).
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function revokeFeeExemption(
+  wallet: PublicKey,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping revokeFeeExemption");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const method = (program.methods as any).revokeFeeExemption(wallet).accounts({
+    admin: admin.publicKey,
+    config: deriveRumbleConfigPda()[0],
+    feeExemption: deriveFeeExemptionPda(wallet)[0],
+    adminLog: deriveAdminLogPda()[0],
+  });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Resolve a rumble's open dispute (admin/server-side). With `uphold` the
+ * result stands; otherwise `correction` replaces it, and any drop in the
+ * treasury cut is topped up into the vault from the admin wallet.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function resolveDispute(
+  rumbleId: number,
+  uphold: boolean,
+  correction?: { placements: number[]; winnerIndex: number },
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping resolveDispute");
+    return null;
+  }
+  if (!uphold && !correction) {
+    throw new Error("A corrected result is required when the dispute is not upheld");
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const conn = connection ?? getConnection();
+
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const configInfo = await conn.getAccountInfo(rumbleConfigPda);
+  if (!configInfo) throw new Error("Rumble config not found");
+  const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
+
+  const method = (program.methods as any)
+    .resolveDispute(
+      uphold,
+      Buffer.from(correction?.placements ?? []),
+      correction?.winnerIndex ?? 0
+    )
+    .accounts({
+      admin: admin.publicKey,
+      config: rumbleConfigPda,
+      rumble: deriveRumblePda(rumbleId)[0],
+      disputeRecord: deriveDisputePda(rumbleId)[0],
+      vault: deriveVaultPda(rumbleId)[0],
+      treasury,
+      systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPda()[0],
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, conn);
+  console.log(`[ONCHAIN] resolveDispute confirmed for rumble ${rumbleId} (upheld: ${uphold}): ${signature}`);
+  return signature;
+}
+
+/**
+ * Cancel a rumble that closed betting with fewer distinct bettors than its minimum
+ * (server-side keeper; permissionless). Bettors then use claim_refund.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function cancelUnderfilledRumble(
+  rumbleId: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping cancelUnderfilledRumble");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const [rumblePda] = deriveRumblePda(rumbleId);
+
+  const method = (program.methods as any)
+    .cancelUnderfilledRumble()
+    .accounts({
+      caller: admin.publicKey,
+      rumble: rumblePda,
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Move a rumble from Payout to Complete once its 24h claim window has passed
+ * (server-side keeper; permissionless finalize_complete). Fails with
+ * InvalidStateTransition if complete_rumble already ran.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function finalizeComplete(
+  rumbleId: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping finalizeComplete");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const conn = connection ?? getConnection();
+
+  const method = (program.methods as any)
+    .finalizeComplete()
+    .accounts({
+      caller: admin.publicKey,
+      config: deriveRumbleConfigPda()[0],
+      rumble: deriveRumblePda(rumbleId)[0],
+      globalStats: await findGlobalStats(conn),
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, conn);
+  return signature;
+}
+
+/**
+ * Check a settled rumble's vault against what it still owes bettors
+ * (permissionless verify_vault_integrity). A short vault halts its claims
+ * until adminLiftVaultHalt; a healthy one is left as is.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function verifyVaultIntegrity(
+  rumbleId: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping verifyVaultIntegrity");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const method = (program.methods as any)
+    .verifyVaultIntegrity()
+    .accounts({
+      caller: admin.publicKey,
+      rumble: deriveRumblePda(rumbleId)[0],
+      vault: deriveVaultPda(rumbleId)[0],
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Send an admin-signed transaction without waiting for confirmation.
+ * The on-chain state polling loop will pick up results on the next tick.
+ */
+async function sendAdminTxFireAndForget(
+  method: any,
+  admin: Keypair,
+  connection?: Connection,
+): Promise<string> {
+  const conn = connection ?? getConnection();
+  const isEr = (conn as any)._rpcEndpoint?.includes("magicblock") ||
+    conn.rpcEndpoint?.includes("magicblock");
+  const routerConn = conn as ErRouterLikeConnection;
+  const shouldRetryBlockhash = (err: unknown): boolean => {
+    const msg = err instanceof Error ? err.message : String(err);
+    return /blockhash not found/i.test(msg);
+  };
+
+  const assignBlockhash = async (tx: Transaction): Promise<void> => {
+    if (isEr && typeof routerConn.getLatestBlockhashForTransaction === "function") {
+      try {
+        const { blockhash, lastValidBlockHeight } = await routerConn.getLatestBlockhashForTransaction(tx, {
+          commitment: "processed",
+        });
+        tx.recentBlockhash = blockhash;
+        tx.lastValidBlockHeight = lastValidBlockHeight;
+        return;
+      } catch (error) {
+        console.warn("[ER] getLatestBlockhashForTransaction failed; falling back to getLatestBlockhash:", error);
+      }
+    }
+    const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "processed");
+    tx.recentBlockhash = blockhash;
+    tx.lastValidBlockHeight = lastValidBlockHeight;
+  };
+
+  const enrichSendError = async (error: unknown): Promise<unknown> => {
+    if (!(error instanceof SendTransactionError)) return error;
+
+    let logs = Array.isArray(error.logs)
+      ? error.logs.filter((line): line is string => typeof line === "string" && line.trim().length > 0)
+      : [];
+    if (logs.length === 0) {
+      try {
+        const fetchedLogs = await error.getLogs(conn);
+        if (Array.isArray(fetchedLogs)) {
+          logs = fetchedLogs.filter((line): line is string => typeof line === "string" && line.trim().length > 0);
+        }
+      } catch {
+        // Ignore secondary log-fetch failures and preserve the original error.
       }
     }
 
@@ -2884,15 +4505,22 @@ export async function openTurn(
   const program = getRumbleEngineProgram(provider);
   const admin = getAdminKeypair()!;
   const [rumblePda] = deriveRumblePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
 
   console.log(`[ONCHAIN-OPEN-TURN] Sending openTurn for rumble ${rumbleId}...`);
   const method = (program.methods as any)
     .openTurn()
     .accounts({
       keeper: admin.publicKey,
+      config: rumbleConfigPda,
       rumble: rumblePda,
       combatState: combatStatePda,
+      combatLog: combatLogPda,
+      // Seeds the turn's crits on rumbles created with crits enabled.
+      slotHashes: SLOT_HASHES_SYSVAR_ID,
+      systemProgram: SystemProgram.programId,
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
@@ -2917,7 +4545,11 @@ export async function resolveTurnOnChain(
   const program = getRumbleEngineProgram(provider);
   const admin = getAdminKeypair()!;
   const [rumblePda] = deriveRumblePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+  const rumbleState = await readRumbleAccountState(rumbleId, connection);
 
   console.log(`[ONCHAIN-RESOLVE] Sending resolveTurn for rumble ${rumbleId} (${moveCommitmentAccounts.length} move commitments)...`);
   let method = (program.methods as any).resolveTurn();
@@ -2934,8 +4566,13 @@ export async function resolveTurnOnChain(
   method = method
     .accounts({
       keeper: admin.publicKey,
+      config: rumbleConfigPda,
       rumble: rumblePda,
       combatState: combatStatePda,
+      combatLog: combatLogPda,
+      ruleset: rumbleState?.ruleset ?? null,
+      vault: vaultPda,
+      systemProgram: SystemProgram.programId,
     })
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: HEAVY_ADMIN_TX_COMPUTE_LIMIT }),
@@ -2947,67 +4584,216 @@ export async function resolveTurnOnChain(
 }
 
 /**
- * Advance to the next on-chain turn window.
+ * Resolve a turn no keeper resolved in time (permissionless). Only succeeds
+ * 300 slots after the turn's reveal window closed; unrevealed moves fall back.
  */
-export async function advanceTurnOnChain(
+export async function forceResolveStaleTurnOnChain(
   rumbleId: number,
+  moveCommitmentAccounts: PublicKey[] = [],
   connection?: Connection,
 ): Promise<string | null> {
   const provider = getAdminProvider(connection);
   if (!provider) {
-    console.warn("[solana-programs] No admin keypair, skipping advanceTurnOnChain");
+    console.warn("[solana-programs] No admin keypair, skipping forceResolveStaleTurnOnChain");
     return null;
   }
   const program = getRumbleEngineProgram(provider);
   const admin = getAdminKeypair()!;
   const [rumblePda] = deriveRumblePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+  const rumbleState = await readRumbleAccountState(rumbleId, connection);
 
-  console.log(`[ONCHAIN-ADVANCE] Sending advanceTurn for rumble ${rumbleId}...`);
+  console.log(`[ONCHAIN-FORCE-RESOLVE] Sending forceResolveStaleTurn for rumble ${rumbleId}...`);
   const method = (program.methods as any)
-    .advanceTurn()
+    .forceResolveStaleTurn()
+    .remainingAccounts(
+      moveCommitmentAccounts.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
+    )
     .accounts({
       keeper: admin.publicKey,
+      config: rumbleConfigPda,
       rumble: rumblePda,
       combatState: combatStatePda,
-    });
+      combatLog: combatLogPda,
+      ruleset: rumbleState?.ruleset ?? null,
+      vault: vaultPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: HEAVY_ADMIN_TX_COMPUTE_LIMIT }),
+    ]);
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
-  console.log(`[ONCHAIN-ADVANCE] advanceTurn confirmed for rumble ${rumbleId}: ${signature}`);
+  console.log(`[ONCHAIN-FORCE-RESOLVE] forceResolveStaleTurn confirmed for rumble ${rumbleId}: ${signature}`);
   return signature;
 }
 
 /**
- * Finalize rumble result from on-chain combat state.
+ * Resolve one pair of the open turn (permissionless). Pairs must go in order:
+ * index 0, 1, ... by the lower fighter index of each stored pairing. Pass the
+ * pair's MoveCommitment PDAs; finish with sealTurnOnChain.
  */
-export async function finalizeRumbleOnChain(
+export async function resolveTurnPairOnChain(
   rumbleId: number,
+  pairIndex: number,
+  moveCommitmentAccounts: PublicKey[] = [],
   connection?: Connection,
 ): Promise<string | null> {
   const provider = getAdminProvider(connection);
   if (!provider) {
-    console.warn("[solana-programs] No admin keypair, skipping finalizeRumbleOnChain");
+    console.warn("[solana-programs] No admin keypair, skipping resolveTurnPairOnChain");
     return null;
   }
   const program = getRumbleEngineProgram(provider);
   const admin = getAdminKeypair()!;
   const [rumblePda] = deriveRumblePda(rumbleId);
-  const [combatStatePda] = deriveCombatStatePda(rumbleId);
   const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
   const [vaultPda] = deriveVaultPda(rumbleId);
-  const conn = connection ?? getConnection();
-  const configInfo = await conn.getAccountInfo(rumbleConfigPda);
-  if (!configInfo) throw new Error("Rumble config not found");
-  const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
+  const rumbleState = await readRumbleAccountState(rumbleId, connection);
 
-  console.log(`[ONCHAIN-FINALIZE] Sending finalizeRumble for rumble ${rumbleId}...`);
   const method = (program.methods as any)
-    .finalizeRumble()
-    .accounts({
-      keeper: admin.publicKey,
-      config: rumbleConfigPda,
+    .resolveTurnPair(pairIndex)
+    .remainingAccounts(
+      moveCommitmentAccounts.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
+    )
+    .accounts({
+      keeper: admin.publicKey,
+      config: rumbleConfigPda,
+      rumble: rumblePda,
+      combatState: combatStatePda,
+      combatLog: combatLogPda,
+      ruleset: rumbleState?.ruleset ?? null,
+      vault: vaultPda,
+      systemProgram: SystemProgram.programId,
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Finish a turn resolved pair by pair with resolveTurnPairOnChain.
+ */
+export async function sealTurnOnChain(
+  rumbleId: number,
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping sealTurnOnChain");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [rumblePda] = deriveRumblePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+  const rumbleState = await readRumbleAccountState(rumbleId, connection);
+
+  const method = (program.methods as any).sealTurn().accounts({
+    keeper: admin.publicKey,
+    config: rumbleConfigPda,
+    rumble: rumblePda,
+    combatState: combatStatePda,
+    combatLog: combatLogPda,
+    ruleset: rumbleState?.ruleset ?? null,
+    vault: vaultPda,
+    systemProgram: SystemProgram.programId,
+  });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Advance to the next on-chain turn window.
+ */
+export async function advanceTurnOnChain(
+  rumbleId: number,
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping advanceTurnOnChain");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [rumblePda] = deriveRumblePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+
+  console.log(`[ONCHAIN-ADVANCE] Sending advanceTurn for rumble ${rumbleId}...`);
+  const method = (program.methods as any)
+    .advanceTurn()
+    .accounts({
+      keeper: admin.publicKey,
+      config: rumbleConfigPda,
       rumble: rumblePda,
       combatState: combatStatePda,
+      combatLog: combatLogPda,
+      vault: vaultPda,
+      slotHashes: SLOT_HASHES_SYSVAR_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  console.log(`[ONCHAIN-ADVANCE] advanceTurn confirmed for rumble ${rumbleId}: ${signature}`);
+  return signature;
+}
+
+/**
+ * Finalize rumble result from on-chain combat state.
+ */
+export async function finalizeRumbleOnChain(
+  rumbleId: number,
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping finalizeRumbleOnChain");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [rumblePda] = deriveRumblePda(rumbleId);
+  const [combatStatePda] = deriveCombatStatePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [vaultPda] = deriveVaultPda(rumbleId);
+  const [combatLogPda] = deriveCombatLogPda(rumbleId);
+  const conn = connection ?? getConnection();
+  const configInfo = await conn.getAccountInfo(rumbleConfigPda);
+  if (!configInfo) throw new Error("Rumble config not found");
+  const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
+  // Seal the combat log when one exists (rumbles opened before it was added have none).
+  const combatLogInfo = await conn.getAccountInfo(combatLogPda);
+
+  console.log(`[ONCHAIN-FINALIZE] Sending finalizeRumble for rumble ${rumbleId}...`);
+  const method = (program.methods as any)
+    .finalizeRumble()
+    .accounts({
+      keeper: admin.publicKey,
+      config: rumbleConfigPda,
+      rumble: rumblePda,
+      combatState: combatStatePda,
+      combatLog: combatLogInfo ? combatLogPda : null,
       vault: vaultPda,
       treasury,
       systemProgram: SystemProgram.programId,
@@ -3022,13 +4808,12 @@ export async function finalizeRumbleOnChain(
 }
 
 /**
- * Close a move commitment PDA and reclaim rent (admin/server-side).
+ * Close a fighter's move commitment PDA and reclaim rent (admin/server-side).
  * Returns tx signature on success, null if admin keypair unavailable.
  */
 export async function closeMoveCommitmentOnChain(
   rumbleId: number,
   fighter: PublicKey,
-  turn: number,
   destination?: PublicKey,
   connection?: Connection,
 ): Promise<string | null> {
@@ -3041,12 +4826,12 @@ export async function closeMoveCommitmentOnChain(
   const admin = getAdminKeypair()!;
   const [rumblePda] = deriveRumblePda(rumbleId);
   const [rumbleConfigPda] = deriveRumbleConfigPda();
-  const [moveCommitmentPda] = deriveMoveCommitmentPda(rumbleId, fighter, turn);
+  const [moveCommitmentPda] = deriveMoveCommitmentPda(rumbleId, fighter);
   const dest = destination ?? admin.publicKey;
 
-  console.log(`[ONCHAIN-CLOSE-MOVE] Sending closeMoveCommitment for rumble ${rumbleId} fighter ${fighter.toBase58().slice(0, 8)}... turn ${turn}...`);
+  console.log(`[ONCHAIN-CLOSE-MOVE] Sending closeMoveCommitment for rumble ${rumbleId} fighter ${fighter.toBase58().slice(0, 8)}...`);
   const method = (program.methods as any)
-    .closeMoveCommitment(new anchor.BN(rumbleId), turn)
+    .closeMoveCommitment(new anchor.BN(rumbleId))
     .accounts({
       admin: admin.publicKey,
       config: rumbleConfigPda,
@@ -3054,13 +4839,86 @@ export async function closeMoveCommitmentOnChain(
       moveCommitment: moveCommitmentPda,
       fighter: fighter,
       destination: dest,
+      adminLog: deriveAdminLogPda()[0],
     });
 
   const sig = await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
-  console.log(`[ONCHAIN-CLOSE-MOVE] closeMoveCommitment confirmed for rumble ${rumbleId} turn ${turn}: ${sig}`);
+  console.log(`[ONCHAIN-CLOSE-MOVE] closeMoveCommitment confirmed for rumble ${rumbleId}: ${sig}`);
   return sig;
 }
 
+/**
+ * Close a legacy per-turn move commitment PDA left over from before
+ * commitments were reused across turns (admin/server-side).
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function closeLegacyMoveCommitmentOnChain(
+  rumbleId: number,
+  fighter: PublicKey,
+  turn: number,
+  destination?: PublicKey,
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping closeLegacyMoveCommitmentOnChain");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [rumblePda] = deriveRumblePda(rumbleId);
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [moveCommitmentPda] = deriveLegacyMoveCommitmentPda(rumbleId, fighter, turn);
+
+  const method = (program.methods as any)
+    .closeLegacyMoveCommitment(new anchor.BN(rumbleId), turn)
+    .accounts({
+      admin: admin.publicKey,
+      config: rumbleConfigPda,
+      rumble: rumblePda,
+      moveCommitment: moveCommitmentPda,
+      fighter: fighter,
+      destination: destination ?? admin.publicKey,
+      adminLog: deriveAdminLogPda()[0],
+    });
+
+  return sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
+}
+
+/**
+ * Close finished-rumble move commitments via the permissionless keeper crank,
+ * refunding rent to each fighter. `turn` is the turn each PDA last stored.
+ * Returns tx signature on success, null if no keeper keypair is available.
+ */
+export async function crankCloseMoveCommitmentsOnChain(
+  rumbleId: number,
+  commitments: Array<{ fighter: PublicKey; turn: number }>,
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping crankCloseMoveCommitmentsOnChain");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [rumblePda] = deriveRumblePda(rumbleId);
+
+  const remainingAccounts = commitments.flatMap(({ fighter }) => [
+    { pubkey: deriveMoveCommitmentPda(rumbleId, fighter)[0], isWritable: true, isSigner: false },
+    { pubkey: fighter, isWritable: true, isSigner: false },
+  ]);
+  const method = (program.methods as any)
+    .crankCloseMoveCommitments(new anchor.BN(rumbleId), commitments)
+    .accounts({
+      caller: admin.publicKey,
+      rumble: rumblePda,
+    })
+    .remainingAccounts(remainingAccounts);
+
+  return sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
+}
+
 /**
  * Report rumble result with placements (admin/server-side).
  * Returns tx signature on success, null if admin keypair unavailable.
@@ -3091,38 +4949,449 @@ export async function reportResult(
       rumble: rumblePda,
     });
 
-  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
-  console.log(`[ONCHAIN-REPORT] reportResult confirmed for rumble ${rumbleId}: ${signature}`);
-  return signature;
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  console.log(`[ONCHAIN-REPORT] reportResult confirmed for rumble ${rumbleId}: ${signature}`);
+  return signature;
+}
+
+/**
+ * The bettor's IchorStake PDA if it exists, so claims pick up the staking
+ * bonus; null otherwise (the account is optional on claim_payout).
+ */
+async function findIchorStake(
+  bettor: PublicKey,
+  connection: Connection,
+): Promise<PublicKey | null> {
+  const [stakePda] = deriveIchorStakePda(bettor);
+  const info = await connection.getAccountInfo(stakePda, "confirmed");
+  return info && info.owner.equals(ICHOR_TOKEN_ID) ? stakePda : null;
+}
+
+/**
+ * The bettor's BettorIndex PDA if it exists, so claims flag the rumble as
+ * claimed there; null otherwise (optional on the claim instructions).
+ */
+async function findBettorIndex(
+  bettor: PublicKey,
+  connection: Connection,
+  useMainnet?: boolean,
+): Promise<PublicKey | null> {
+  if (useMainnet) return null;
+  const [indexPda] = deriveBettorIndexPda(bettor);
+  const info = await connection.getAccountInfo(indexPda, "confirmed");
+  return info && info.owner.equals(RUMBLE_ENGINE_ID) ? indexPda : null;
+}
+
+/**
+ * The bettor's BettorStats PDA if it exists, so claims are credited to it;
+ * null otherwise (optional on the claim instructions).
+ */
+async function findBettorStats(
+  bettor: PublicKey,
+  connection: Connection,
+  useMainnet?: boolean,
+): Promise<PublicKey | null> {
+  if (useMainnet) return null;
+  const [statsPda] = deriveBettorStatsPda(bettor);
+  const info = await connection.getAccountInfo(statsPda, "confirmed");
+  return info && info.owner.equals(RUMBLE_ENGINE_ID) ? statsPda : null;
+}
+
+/**
+ * The bettor's BettorRateLimit PDA for a rumble if it is still open, so claims
+ * close it and return its rent; null otherwise (optional on the claim
+ * instructions).
+ */
+async function findRateLimit(
+  bettor: PublicKey,
+  rumbleId: number,
+  connection: Connection,
+  useMainnet?: boolean,
+): Promise<PublicKey | null> {
+  if (useMainnet) return null;
+  const [rateLimitPda] = deriveRateLimitPda(bettor, rumbleId);
+  const info = await connection.getAccountInfo(rateLimitPda, "confirmed");
+  return info && info.owner.equals(RUMBLE_ENGINE_ID) ? rateLimitPda : null;
+}
+
+/**
+ * The bettor's FeeExemption PDA if one was granted, so place_bet can waive the
+ * admin fee; null otherwise. Expired exemptions are ignored on-chain.
+ */
+async function findFeeExemption(
+  bettor: PublicKey,
+  connection: Connection,
+  useMainnet?: boolean,
+): Promise<PublicKey | null> {
+  if (useMainnet) return null;
+  const [exemptionPda] = deriveFeeExemptionPda(bettor);
+  const info = await connection.getAccountInfo(exemptionPda, "confirmed");
+  return info && info.owner.equals(RUMBLE_ENGINE_ID) ? exemptionPda : null;
+}
+
+/**
+ * The GlobalStats PDA if this deployment has one; null otherwise (optional on
+ * create_rumble, place_bet and complete_rumble).
+ */
+async function findGlobalStats(
+  connection: Connection,
+  useMainnet?: boolean,
+): Promise<PublicKey | null> {
+  if (useMainnet) return null;
+  const [statsPda] = deriveGlobalStatsPda();
+  const info = await connection.getAccountInfo(statsPda, "confirmed");
+  return info && info.owner.equals(RUMBLE_ENGINE_ID) ? statsPda : null;
+}
+
+/**
+ * Build a claim_payout transaction for the bettor to sign.
+ */
+export async function buildClaimPayoutTx(
+  bettor: PublicKey,
+  rumbleId: number,
+  connection?: Connection,
+  programId?: PublicKey,
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider, programId);
+  const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
+
+  const [configPda] = useMainnet ? deriveRumbleConfigPdaMainnet() : deriveRumbleConfigPda();
+  const [rumblePda] = useMainnet ? deriveRumblePdaMainnet(rumbleId) : deriveRumblePda(rumbleId);
+  const [vaultPda] = useMainnet ? deriveVaultPdaMainnet(rumbleId) : deriveVaultPda(rumbleId);
+  const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+
+  const conn = connection ?? getConnection();
+  const ichorStake = await findIchorStake(bettor, conn);
+  const bettorIndex = await findBettorIndex(bettor, conn, useMainnet);
+  const bettorStats = await findBettorStats(bettor, conn, useMainnet);
+  const rateLimit = await findRateLimit(bettor, rumbleId, conn, useMainnet);
+
+  const tx = await (program.methods as any)
+    .claimPayout()
+    .accounts({
+      bettor,
+      config: configPda,
+      rumble: rumblePda,
+      vault: vaultPda,
+      bettorAccount: bettorAccountPda,
+      systemProgram: SystemProgram.programId,
+      ichorStake,
+      bettorIndex,
+      bettorStats,
+      rateLimit,
+    })
+    .transaction();
+
+  tx.feePayer = bettor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Build a claim_insurance transaction for an insured bettor whose fighters
+ * missed the money.
+ */
+export async function buildClaimInsuranceTx(
+  bettor: PublicKey,
+  rumbleId: number,
+  connection?: Connection,
+  programId?: PublicKey,
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider, programId);
+  const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
+
+  const [configPda] = useMainnet ? deriveRumbleConfigPdaMainnet() : deriveRumbleConfigPda();
+  const [rumblePda] = useMainnet ? deriveRumblePdaMainnet(rumbleId) : deriveRumblePda(rumbleId);
+  const [vaultPda] = useMainnet ? deriveVaultPdaMainnet(rumbleId) : deriveVaultPda(rumbleId);
+  const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+
+  const conn = connection ?? getConnection();
+  const rateLimit = await findRateLimit(bettor, rumbleId, conn, useMainnet);
+  const tx = await (program.methods as any)
+    .claimInsurance()
+    .accounts({
+      bettor,
+      config: configPda,
+      rumble: rumblePda,
+      vault: vaultPda,
+      bettorAccount: bettorAccountPda,
+      systemProgram: SystemProgram.programId,
+      rateLimit,
+    })
+    .transaction();
+
+  tx.feePayer = bettor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Build one transaction with multiple claim_payout instructions so a wallet
+ * can claim all ready rumble wins in a single signature.
+ */
+export async function buildClaimPayoutBatchTx(
+  bettor: PublicKey,
+  rumbleIds: number[],
+  connection?: Connection,
+  programId?: PublicKey,
+): Promise<Transaction> {
+  if (!Array.isArray(rumbleIds) || rumbleIds.length === 0) {
+    throw new Error("At least one rumble id is required for batch claim");
+  }
+
+  const uniqueRumbleIds = [...new Set(
+    rumbleIds.filter((id) => Number.isInteger(id) && id > 0),
+  )];
+  if (uniqueRumbleIds.length === 0) {
+    throw new Error("No valid rumble ids provided for batch claim");
+  }
+
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider, programId);
+  const conn = connection ?? getConnection();
+  const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
+  const tx = new Transaction();
+  const [configPda] = useMainnet ? deriveRumbleConfigPdaMainnet() : deriveRumbleConfigPda();
+  const ichorStake = await findIchorStake(bettor, conn);
+  const bettorIndex = await findBettorIndex(bettor, conn, useMainnet);
+  const bettorStats = await findBettorStats(bettor, conn, useMainnet);
+
+  // Batch claim instructions can exceed the default compute cap.
+  tx.add(ComputeBudgetProgram.setComputeUnitLimit({ units: 900_000 }));
+
+  for (const rumbleId of uniqueRumbleIds) {
+    const [rumblePda] = useMainnet ? deriveRumblePdaMainnet(rumbleId) : deriveRumblePda(rumbleId);
+    const [vaultPda] = useMainnet ? deriveVaultPdaMainnet(rumbleId) : deriveVaultPda(rumbleId);
+    const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+    const rateLimit = await findRateLimit(bettor, rumbleId, conn, useMainnet);
+
+    const ix = await (program.methods as any)
+      .claimPayout()
+      .accounts({
+        bettor,
+        config: configPda,
+        rumble: rumblePda,
+        vault: vaultPda,
+        bettorAccount: bettorAccountPda,
+        systemProgram: SystemProgram.programId,
+        ichorStake,
+        bettorIndex,
+        bettorStats,
+        rateLimit,
+      })
+      .instruction();
+    tx.add(ix);
+  }
+
+  tx.feePayer = bettor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "confirmed");
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+  return tx;
+}
+
+/**
+ * Build a deposit_prize_pool transaction for an organizer to sign while the
+ * rumble is still in Betting.
+ */
+export async function buildDepositPrizePoolTx(
+  depositor: PublicKey,
+  rumbleId: number,
+  lamports: number,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider);
+
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [rumblePda] = deriveRumblePda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+
+  const conn = connection ?? getConnection();
+
+  const tx = await (program.methods as any)
+    .depositPrizePool(new anchor.BN(rumbleId), new anchor.BN(lamports))
+    .accounts({
+      depositor,
+      config: rumbleConfigPda,
+      rumble: rumblePda,
+      vault: vaultPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = depositor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Build a refund_prize_pool transaction, returning the prize pool to its
+ * depositor once the rumble is cancelled or refunds every bettor. Anyone can
+ * sign it; `payer` only pays the fee.
+ */
+export async function buildRefundPrizePoolTx(
+  payer: PublicKey,
+  rumbleId: number,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider);
+
+  const conn = connection ?? getConnection();
+  const rumbleState = await readRumbleAccountState(rumbleId, conn);
+  if (!rumbleState?.prizeDepositor) {
+    throw new Error(`Rumble ${rumbleId} has no prize depositor`);
+  }
+
+  const tx = await (program.methods as any)
+    .refundPrizePool()
+    .accounts({
+      caller: payer,
+      rumble: deriveRumblePda(rumbleId)[0],
+      depositor: rumbleState.prizeDepositor,
+      vault: deriveVaultPda(rumbleId)[0],
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = payer;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+
+  return tx;
+}
+
+/**
+ * Build a place_exacta_bet transaction: `firstIndex` to win and
+ * `secondIndex` to finish second, in that order.
+ */
+export async function buildPlaceExactaBetTx(
+  bettor: PublicKey,
+  rumbleId: number,
+  firstIndex: number,
+  secondIndex: number,
+  lamports: number,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider);
+  const conn = connection ?? getConnection();
+
+  const { rumbleConfigPda, rumblePda, vaultPda, treasury, fighterCount, whitelistPda } =
+    await loadRumbleBetContext(rumbleId, conn);
+  if (firstIndex >= fighterCount || secondIndex >= fighterCount || firstIndex === secondIndex) {
+    throw new Error("Exacta picks must be two different fighters in the rumble");
+  }
+  const [exactaTicketPda] = deriveExactaTicketPda(rumbleId, bettor, firstIndex, secondIndex);
+  const [exactaComboPda] = deriveExactaComboPda(rumbleId, firstIndex, secondIndex);
+
+  const tx = await (program.methods as any)
+    .placeExactaBet(new anchor.BN(rumbleId), firstIndex, secondIndex, new anchor.BN(lamports))
+    .accounts({
+      bettor,
+      rumble: rumblePda,
+      vault: vaultPda,
+      treasury,
+      config: rumbleConfigPda,
+      exactaTicket: exactaTicketPda,
+      exactaCombo: exactaComboPda,
+      whitelist: whitelistPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = bettor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "confirmed");
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+  return tx;
+}
+
+/**
+ * Build a claim_exacta_payout transaction for one of the bettor's exacta
+ * tickets. Closes the ticket whether or not it hit.
+ */
+export async function buildClaimExactaPayoutTx(
+  bettor: PublicKey,
+  rumbleId: number,
+  firstIndex: number,
+  secondIndex: number,
+  connection?: Connection
+): Promise<Transaction> {
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider);
+
+  const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const [rumblePda] = deriveRumblePda(rumbleId);
+  const [vaultPda] = deriveVaultPda(rumbleId);
+  const [exactaTicketPda] = deriveExactaTicketPda(rumbleId, bettor, firstIndex, secondIndex);
+
+  const conn = connection ?? getConnection();
+
+  const tx = await (program.methods as any)
+    .claimExactaPayout()
+    .accounts({
+      bettor,
+      config: rumbleConfigPda,
+      rumble: rumblePda,
+      vault: vaultPda,
+      exactaTicket: exactaTicketPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = bettor;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "confirmed");
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
+  return tx;
 }
 
 /**
- * Build a claim_payout transaction for the bettor to sign.
+ * Build a close_bettor_stats transaction: wipes the wallet's lifetime
+ * BettorStats and returns its rent.
  */
-export async function buildClaimPayoutTx(
+export async function buildCloseBettorStatsTx(
   bettor: PublicKey,
-  rumbleId: number,
-  connection?: Connection,
-  programId?: PublicKey,
+  connection?: Connection
 ): Promise<Transaction> {
   const provider = getProvider(connection);
-  const program = getRumbleEngineProgram(provider, programId);
-  const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
+  const program = getRumbleEngineProgram(provider);
 
-  const [rumblePda] = useMainnet ? deriveRumblePdaMainnet(rumbleId) : deriveRumblePda(rumbleId);
-  const [vaultPda] = useMainnet ? deriveVaultPdaMainnet(rumbleId) : deriveVaultPda(rumbleId);
-  const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+  const [bettorStatsPda] = deriveBettorStatsPda(bettor);
 
   const conn = connection ?? getConnection();
 
   const tx = await (program.methods as any)
-    .claimPayout()
+    .closeBettorStats()
     .accounts({
       bettor,
-      rumble: rumblePda,
-      vault: vaultPda,
-      bettorAccount: bettorAccountPda,
-      systemProgram: SystemProgram.programId,
+      bettorStats: bettorStatsPda,
     })
     .transaction();
 
@@ -3138,57 +5407,73 @@ export async function buildClaimPayoutTx(
 }
 
 /**
- * Build one transaction with multiple claim_payout instructions so a wallet
- * can claim all ready rumble wins in a single signature.
+ * Build a file_dispute transaction for a bettor challenging an admin-set
+ * result, within 12 hours of it. `evidenceUri` is at most 128 bytes UTF-8.
  */
-export async function buildClaimPayoutBatchTx(
-  bettor: PublicKey,
-  rumbleIds: number[],
-  connection?: Connection,
-  programId?: PublicKey,
+export async function buildFileDisputeTx(
+  disputer: PublicKey,
+  rumbleId: number,
+  evidenceUri: string,
+  connection?: Connection
 ): Promise<Transaction> {
-  if (!Array.isArray(rumbleIds) || rumbleIds.length === 0) {
-    throw new Error("At least one rumble id is required for batch claim");
-  }
+  const provider = getProvider(connection);
+  const program = getRumbleEngineProgram(provider);
 
-  const uniqueRumbleIds = [...new Set(
-    rumbleIds.filter((id) => Number.isInteger(id) && id > 0),
-  )];
-  if (uniqueRumbleIds.length === 0) {
-    throw new Error("No valid rumble ids provided for batch claim");
-  }
+  const conn = connection ?? getConnection();
+
+  const tx = await (program.methods as any)
+    .fileDispute(evidenceUri)
+    .accounts({
+      disputer,
+      rumble: deriveRumblePda(rumbleId)[0],
+      bettorAccount: deriveBettorPda(rumbleId, disputer)[0],
+      disputeRecord: deriveDisputePda(rumbleId)[0],
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
+
+  tx.feePayer = disputer;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
+  tx.recentBlockhash = blockhash;
+  tx.lastValidBlockHeight = lastValidBlockHeight;
 
+  return tx;
+}
+
+/**
+ * Build a claim_referral_revenue transaction for the referrer.
+ */
+export async function buildClaimReferralTx(
+  referrer: PublicKey,
+  connection?: Connection
+): Promise<Transaction> {
   const provider = getProvider(connection);
-  const program = getRumbleEngineProgram(provider, programId);
-  const conn = connection ?? getConnection();
-  const useMainnet = programId && !programId.equals(RUMBLE_ENGINE_ID);
-  const tx = new Transaction();
+  const program = getRumbleEngineProgram(provider);
 
-  // Batch claim instructions can exceed the default compute cap.
-  tx.add(ComputeBudgetProgram.setComputeUnitLimit({ units: 900_000 }));
+  const [referralPda] = deriveReferralPda(referrer);
 
-  for (const rumbleId of uniqueRumbleIds) {
-    const [rumblePda] = useMainnet ? deriveRumblePdaMainnet(rumbleId) : deriveRumblePda(rumbleId);
-    const [vaultPda] = useMainnet ? deriveVaultPdaMainnet(rumbleId) : deriveVaultPda(rumbleId);
-    const [bettorAccountPda] = useMainnet ? deriveBettorPdaMainnet(rumbleId, bettor) : deriveBettorPda(rumbleId, bettor);
+  const conn = connection ?? getConnection();
 
-    const ix = await (program.methods as any)
-      .claimPayout()
-      .accounts({
-        bettor,
-        rumble: rumblePda,
-        vault: vaultPda,
-        bettorAccount: bettorAccountPda,
-        systemProgram: SystemProgram.programId,
-      })
-      .instruction();
-    tx.add(ix);
-  }
+  const tx = await (program.methods as any)
+    .claimReferralRevenue()
+    .accounts({
+      referrer,
+      referralAccount: referralPda,
+      systemProgram: SystemProgram.programId,
+    })
+    .transaction();
 
-  tx.feePayer = bettor;
-  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(conn, "confirmed");
+  tx.feePayer = referrer;
+  const { blockhash, lastValidBlockHeight } = await getLatestBlockhashCached(
+    conn,
+    "confirmed"
+  );
   tx.recentBlockhash = blockhash;
   tx.lastValidBlockHeight = lastValidBlockHeight;
+
   return tx;
 }
 
@@ -3204,6 +5489,7 @@ export async function buildClaimSponsorshipTx(
   const program = getRumbleEngineProgram(provider);
 
   const [sponsorshipPda] = deriveSponsorshipPda(fighterPubkey);
+  const [sponsorshipLedgerPda] = deriveSponsorshipLedgerPda(fighterPubkey);
 
   const conn = connection ?? getConnection();
 
@@ -3213,6 +5499,7 @@ export async function buildClaimSponsorshipTx(
       fighterOwner,
       fighter: fighterPubkey,
       sponsorshipAccount: sponsorshipPda,
+      sponsorshipLedger: sponsorshipLedgerPda,
       systemProgram: SystemProgram.programId,
     })
     .transaction();
@@ -3244,8 +5531,10 @@ export async function readFighterAuthority(
 }
 
 /**
- * Read currently claimable sponsorship lamports for a fighter's sponsorship PDA.
- * Returns max(vault_lamports - rent_exempt_minimum, 0).
+ * Read currently claimable sponsorship lamports for a fighter.
+ * Returns the ledger's total_accrued - total_claimed; before the ledger exists,
+ * max(sponsorship_lamports - rent_exempt_minimum, 0), which the first claim or
+ * bet carries into the ledger.
  */
 export async function readSponsorshipClaimableLamports(
   fighterPubkey: PublicKey,
@@ -3253,10 +5542,18 @@ export async function readSponsorshipClaimableLamports(
 ): Promise<bigint> {
   const conn = connection ?? getConnection();
   const [sponsorshipPda] = deriveSponsorshipPda(fighterPubkey);
-  const [info, rentMin] = await Promise.all([
+  const [sponsorshipLedgerPda] = deriveSponsorshipLedgerPda(fighterPubkey);
+  const [ledgerInfo, info, rentMin] = await Promise.all([
+    conn.getAccountInfo(sponsorshipLedgerPda, "confirmed"),
     conn.getAccountInfo(sponsorshipPda, "confirmed"),
     conn.getMinimumBalanceForRentExemption(0, "confirmed"),
   ]);
+  // SponsorshipLedger: discriminator(8) + fighter(32) + total_accrued(8) + total_claimed(8)
+  if (ledgerInfo && ledgerInfo.data.length >= 56) {
+    const accrued = readU64LE(ledgerInfo.data, 40);
+    const claimed = readU64LE(ledgerInfo.data, 48);
+    return accrued > claimed ? accrued - claimed : 0n;
+  }
   if (!info) return 0n;
   const available = BigInt(info.lamports) - BigInt(rentMin);
   return available > 0n ? available : 0n;
@@ -3288,6 +5585,8 @@ export async function completeRumble(
       admin: admin.publicKey,
       config: rumbleConfigPda,
       rumble: rumblePda,
+      globalStats: await findGlobalStats(connection ?? getConnection()),
+      adminLog: deriveAdminLogPda()[0],
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
@@ -3295,6 +5594,38 @@ export async function completeRumble(
   return signature;
 }
 
+/**
+ * Resume claims on a rumble halted by verify_vault_integrity (admin-only),
+ * once the vault shortfall has been investigated.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function adminLiftVaultHalt(
+  rumbleId: number,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping adminLiftVaultHalt");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+  const conn = connection ?? getConnection();
+
+  const method = (program.methods as any)
+    .adminLiftVaultHalt()
+    .accounts({
+      admin: admin.publicKey,
+      config: deriveRumbleConfigPda()[0],
+      rumble: deriveRumblePda(rumbleId)[0],
+      globalStats: await findGlobalStats(conn),
+      adminLog: deriveAdminLogPda()[0],
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, conn);
+  return signature;
+}
+
 // ---------------------------------------------------------------------------
 // Mainnet Admin Functions (betting network)
 // ---------------------------------------------------------------------------
@@ -3323,6 +5654,7 @@ export async function createRumbleMainnet(
 
   const [rumbleConfigPda] = deriveRumbleConfigPdaMainnet();
   const [rumblePda] = deriveRumblePdaMainnet(rumbleId);
+  const [vaultPda] = deriveVaultPdaMainnet(rumbleId);
 
   const conn = getBettingConnection();
   const nowUnix = Math.floor(Date.now() / 1000);
@@ -3356,12 +5688,15 @@ export async function createRumbleMainnet(
       new anchor.BN(rumbleId),
       fighters,
       new anchor.BN(bettingCloseSlot.toString()),
+      ...defaultCreateRumbleArgs("slot"),
     )
     .accounts({
       admin: admin.publicKey,
       config: rumbleConfigPda,
       rumble: rumblePda,
+      vault: vaultPda,
       systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPdaMainnet()[0],
     });
 
   return await sendAdminTxFireAndForget(method, admin, conn);
@@ -3398,7 +5733,7 @@ export async function reportResultMainnet(
   const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
 
   const method = (program.methods as any)
-    .adminSetResult(Buffer.from(placements), winnerIndex)
+    .adminSetResult(Buffer.from(placements), winnerIndex, false)
     .accounts({
       admin: admin.publicKey,
       config: rumbleConfigPda,
@@ -3406,6 +5741,7 @@ export async function reportResultMainnet(
       vault: vaultPda,
       treasury,
       systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPdaMainnet()[0],
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, conn);
@@ -3440,6 +5776,7 @@ export async function completeRumbleMainnet(
       admin: admin.publicKey,
       config: rumbleConfigPda,
       rumble: rumblePda,
+      adminLog: deriveAdminLogPdaMainnet()[0],
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, getBettingConnection());
@@ -3474,6 +5811,7 @@ export async function closeRumbleMainnet(
       vault: vaultPda,
       treasury: new PublicKey(getMainnetTreasuryAddress()),
       systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPdaMainnet()[0],
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, getBettingConnection());
@@ -3661,7 +5999,7 @@ export async function reclaimMainnetRumbleRent(): Promise<{ completed: number; c
         if (!treasury) throw new Error("treasury unavailable");
         const [vaultPda] = deriveVaultPdaMainnet(rumbleId);
         const method = (program.methods as any)
-          .adminSetResult(dummyPlacements, 0)
+          .adminSetResult(dummyPlacements, 0, false)
           .accounts({
             admin: admin.publicKey,
             config: configPda,
@@ -3669,6 +6007,7 @@ export async function reclaimMainnetRumbleRent(): Promise<{ completed: number; c
             vault: vaultPda,
             treasury,
             systemProgram: SystemProgram.programId,
+            adminLog: deriveAdminLogPdaMainnet()[0],
           });
         const sig = await sendAdminTxFireAndForget(method, admin, conn);
         if (sig) {
@@ -3693,6 +6032,7 @@ export async function reclaimMainnetRumbleRent(): Promise<{ completed: number; c
         await noteMutationAttempt();
         const method = (program.methods as any).completeRumble().accounts({
           admin: admin.publicKey, config: configPda, rumble: rumblePda,
+          adminLog: deriveAdminLogPdaMainnet()[0],
         });
         const sig = await sendAdminTxFireAndForget(method, admin, conn);
         if (sig) {
@@ -3746,7 +6086,7 @@ export async function reclaimMainnetRumbleRent(): Promise<{ completed: number; c
         try {
           await noteMutationAttempt();
           const method = (program.methods as any)
-            .sweepTreasury()
+            .sweepTreasury(false)
             .accounts({
               admin: admin.publicKey,
               config: configPda,
@@ -3754,6 +6094,7 @@ export async function reclaimMainnetRumbleRent(): Promise<{ completed: number; c
               vault: vaultPda,
               treasury,
               systemProgram: SystemProgram.programId,
+              adminLog: deriveAdminLogPdaMainnet()[0],
             });
           const sig = await sendAdminTxFireAndForget(method, admin, conn);
           if (sig) {
@@ -3780,6 +6121,7 @@ export async function reclaimMainnetRumbleRent(): Promise<{ completed: number; c
             vault: vaultPda,
             treasury: new PublicKey(getMainnetTreasuryAddress()),
             systemProgram: SystemProgram.programId,
+            adminLog: deriveAdminLogPdaMainnet()[0],
           });
           const { signature } = await sendAdminTxWithConfirmation(method, admin, conn);
           if (signature) {
@@ -3824,7 +6166,8 @@ export async function readRumbleFighters(
  */
 export async function sweepTreasury(
   rumbleId: number,
-  connection?: Connection
+  connection?: Connection,
+  force = false,
 ): Promise<string | null> {
   const provider = getAdminProvider(connection);
   if (!provider) {
@@ -3838,11 +6181,11 @@ export async function sweepTreasury(
   const [rumblePda] = deriveRumblePda(rumbleId);
   const [vaultPda] = deriveVaultPda(rumbleId);
   const rumbleState = await readRumbleAccountState(rumbleId, connection ?? getConnection());
-  if (rumbleState?.winnerIndex !== null && rumbleState?.winnerIndex !== undefined) {
-    const winnerPool = rumbleState.bettingPools[rumbleState.winnerIndex] ?? 0n;
-    if (winnerPool > 0n) {
-      throw new Error("OutstandingWinnerClaims: winner rumbles remain claimable and cannot be swept");
-    }
+  if (rumbleState && rumbleState.winnerPoolSnapshotLamports > rumbleState.totalClaimedLamports) {
+    // The program rejects the sweep (UnclaimedPayoutsRemain) unless forced
+    // after the claim window; log what a forced sweep would strand.
+    const liability = rumbleState.winnerPoolSnapshotLamports - rumbleState.totalClaimedLamports;
+    console.log(`[solana-programs] sweepTreasury ${rumbleId}: ${liability} lamports of winner payouts unclaimed`);
   }
 
   // Read treasury from config
@@ -3852,7 +6195,7 @@ export async function sweepTreasury(
   const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
 
   const method = (program.methods as any)
-    .sweepTreasury()
+    .sweepTreasury(force)
     .accounts({
       admin: admin.publicKey,
       config: rumbleConfigPda,
@@ -3860,6 +6203,40 @@ export async function sweepTreasury(
       vault: vaultPda,
       treasury,
       systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPda()[0],
+    });
+
+  const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
+  return signature;
+}
+
+/**
+ * Admin: grow a Rumble created under an older, shorter layout to the current
+ * one. The admin pays the extra rent and the vault's rent reserve. Rumbles in
+ * Combat can't be migrated.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function migrateRumble(
+  rumbleId: number,
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping migrateRumble");
+    return null;
+  }
+  const program = getRumbleEngineProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const method = (program.methods as any)
+    .migrateRumble(new anchor.BN(rumbleId))
+    .accounts({
+      admin: admin.publicKey,
+      config: deriveRumbleConfigPda()[0],
+      rumble: deriveRumblePda(rumbleId)[0],
+      vault: deriveVaultPda(rumbleId)[0],
+      systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPda()[0],
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, connection ?? getConnection());
@@ -3909,7 +6286,7 @@ export async function sweepTreasuryMainnet(
   const treasury = new PublicKey(configInfo.data.subarray(8 + 32, 8 + 32 + 32));
 
   const method = (program.methods as any)
-    .sweepTreasury()
+    .sweepTreasury(false)
     .accounts({
       admin: admin.publicKey,
       config: rumbleConfigPda,
@@ -3917,6 +6294,7 @@ export async function sweepTreasuryMainnet(
       vault: vaultPda,
       treasury,
       systemProgram: SystemProgram.programId,
+      adminLog: deriveAdminLogPdaMainnet()[0],
     });
 
   const { signature } = await sendAdminTxWithConfirmation(method, admin, conn);
@@ -3958,7 +6336,9 @@ export async function updateFighterRecord(
       new anchor.BN(damageDealt),
       new anchor.BN(damageTaken),
       new anchor.BN(ichorMined),
-      new anchor.BN(rumbleId)
+      new anchor.BN(rumbleId),
+      // damage_taken_this_rumble: drives the registry's injury flag
+      new anchor.BN(damageTaken)
     )
     .accounts({
       authority: admin.publicKey,
@@ -3969,6 +6349,93 @@ export async function updateFighterRecord(
   return await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
 }
 
+/**
+ * Record the winner between two fighters in their head-to-head PDA.
+ * Fighters may be passed in any order. Returns null if admin keypair unavailable.
+ */
+export async function recordHeadToHead(
+  rumbleId: number,
+  winner: PublicKey,
+  loser: PublicKey,
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping recordHeadToHead");
+    return null;
+  }
+  const program = getFighterRegistryProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const [registryConfigPda] = deriveRegistryConfigPda();
+  const [fighterA, fighterB] = sortHeadToHeadPair(winner, loser);
+  const [headToHeadPda] = deriveHeadToHeadPda(fighterA, fighterB);
+
+  const method = (program.methods as any)
+    .recordH2h(new anchor.BN(rumbleId), fighterA, fighterB, fighterA.equals(winner) ? 0 : 1)
+    .accounts({
+      admin: admin.publicKey,
+      registryConfig: registryConfigPda,
+      headToHead: headToHeadPda,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
+}
+
+export interface FighterRecordUpdate {
+  fighter: PublicKey;
+  wins: number;
+  losses: number;
+  damageDealt: number;
+  damageTaken: number;
+  ichorMined: number;
+  rumbleId: number;
+}
+
+/**
+ * Update up to 16 fighters' records in one transaction after a Rumble.
+ * Returns tx signature on success, null if admin keypair unavailable.
+ */
+export async function batchUpdateFighterRecords(
+  updates: FighterRecordUpdate[],
+  connection?: Connection
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping batchUpdateFighterRecords");
+    return null;
+  }
+  const program = getFighterRegistryProgram(provider);
+  const admin = getAdminKeypair()!;
+
+  const [registryConfigPda] = deriveRegistryConfigPda();
+
+  const method = (program.methods as any)
+    .batchUpdateRecord(
+      updates.map((u) => ({
+        fighterKey: u.fighter,
+        wins: new anchor.BN(u.wins),
+        losses: new anchor.BN(u.losses),
+        damageDealt: new anchor.BN(u.damageDealt),
+        damageTaken: new anchor.BN(u.damageTaken),
+        ichorMined: new anchor.BN(u.ichorMined),
+        rumbleId: new anchor.BN(u.rumbleId),
+        // damage_taken_this_rumble: drives the registry's injury flag
+        damageTakenThisRumble: new anchor.BN(u.damageTaken),
+      }))
+    )
+    .accounts({
+      authority: admin.publicKey,
+      registryConfig: registryConfigPda,
+    })
+    .remainingAccounts(
+      updates.map((u) => ({ pubkey: u.fighter, isSigner: false, isWritable: true }))
+    );
+
+  return await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
+}
+
 // ---------------------------------------------------------------------------
 // ATA Helper — ensures a token account exists before minting
 // ---------------------------------------------------------------------------
@@ -4049,6 +6516,18 @@ export async function readArenaConfig(
   baseReward: bigint;
   seasonReward: bigint;
   effectiveReward: bigint;
+  /** Shower triggers 1 in `showerChance` (500 for accounts that predate the field). */
+  showerChance: bigint;
+  /** Share of a triggered shower burned, in bps (1000 for accounts that predate the field). */
+  showerBurnBps: number;
+  /** Slot the last shower request settled (0 if none or for older accounts). */
+  showerLastSettledSlot: bigint;
+  /** Slots after a settlement before a new shower can be requested (1000 by default). */
+  showerCooldownSlots: bigint;
+  /** Most shower payout one recipient can receive per day (100k ICHOR by default). */
+  maxShowerPerDay: bigint;
+  /** Whether only the admin may refill the distribution vault (false for older accounts). */
+  refillRequireAdmin: boolean;
   ichorShowerPool: bigint;
   treasuryVault: bigint;
   bump: number;
@@ -4086,6 +6565,29 @@ export async function readArenaConfig(
     const seasonReward =
       d.length >= seasonRewardOffset + 8 ? d.readBigUInt64LE(seasonRewardOffset) : 0n;
     const effectiveReward = seasonReward > 0n ? seasonReward : baseReward;
+    const showerChanceOffset = seasonRewardOffset + 8;
+    const storedShowerChance =
+      d.length >= showerChanceOffset + 8 ? d.readBigUInt64LE(showerChanceOffset) : 0n;
+    const showerChance = storedShowerChance > 0n ? storedShowerChance : 500n;
+    const showerBurnBpsOffset = showerChanceOffset + 8;
+    const showerBurnBps =
+      d.length >= showerBurnBpsOffset + 2 ? d.readUInt16LE(showerBurnBpsOffset) : 1000;
+    const showerLastSettledOffset = showerBurnBpsOffset + 2;
+    const hasShowerCooldown = d.length >= showerLastSettledOffset + 16;
+    const showerLastSettledSlot = hasShowerCooldown
+      ? d.readBigUInt64LE(showerLastSettledOffset)
+      : 0n;
+    const showerCooldownSlots = hasShowerCooldown
+      ? d.readBigUInt64LE(showerLastSettledOffset + 8)
+      : 1000n;
+    const maxShowerPerDayOffset = showerLastSettledOffset + 16;
+    const maxShowerPerDay =
+      d.length >= maxShowerPerDayOffset + 8
+        ? d.readBigUInt64LE(maxShowerPerDayOffset)
+        : 100_000n * 1_000_000_000n;
+    const refillRequireAdminOffset = maxShowerPerDayOffset + 8;
+    const refillRequireAdmin =
+      d.length > refillRequireAdminOffset && d[refillRequireAdminOffset] !== 0;
 
     return {
       admin,
@@ -4096,6 +6598,12 @@ export async function readArenaConfig(
       baseReward,
       seasonReward,
       effectiveReward,
+      showerChance,
+      showerBurnBps,
+      showerLastSettledSlot,
+      showerCooldownSlots,
+      maxShowerPerDay,
+      refillRequireAdmin,
       ichorShowerPool,
       treasuryVault,
       bump,
@@ -4158,6 +6666,48 @@ export async function readShowerRequest(
   };
 }
 
+/**
+ * Read the last 16 settled Ichor Showers from the ShowerHistory ring buffer,
+ * newest first. Empty until the first shower call creates the account.
+ */
+export async function readShowerHistory(
+  connection?: Connection
+): Promise<Array<{
+  slot: bigint;
+  triggered: boolean;
+  recipient: string;
+  amount: bigint;
+  rngValue: bigint;
+}>> {
+  const conn = connection ?? getConnection();
+  const [pda] = deriveShowerHistoryPda();
+  const info = await conn.getAccountInfo(pda);
+  if (!info) return [];
+
+  const d = info.data;
+  const HISTORY_LEN = 16;
+  const ENTRY_LEN = 8 + 1 + 32 + 8 + 8;
+  const head = d[8];
+  const entriesOffset = 8 + 1 + 1; // discriminator, head, bump
+  const entries = [];
+  for (let i = 1; i <= HISTORY_LEN; i++) {
+    const idx = (head - i + HISTORY_LEN) % HISTORY_LEN;
+    let offset = entriesOffset + idx * ENTRY_LEN;
+    const slot = d.readBigUInt64LE(offset);
+    offset += 8;
+    if (slot === 0n) continue; // never written
+    const triggered = d[offset] !== 0;
+    offset += 1;
+    const recipient = new PublicKey(d.subarray(offset, offset + 32)).toBase58();
+    offset += 32;
+    const amount = d.readBigUInt64LE(offset);
+    offset += 8;
+    const rngValue = d.readBigUInt64LE(offset);
+    entries.push({ slot, triggered, recipient, amount, rngValue });
+  }
+  return entries;
+}
+
 /**
  * Read RumbleConfig state from chain.
  */
@@ -4219,7 +6769,7 @@ export async function readRegistryConfig(
 export async function readMoveCommitmentData(
   pda: PublicKey,
   connection?: Connection,
-): Promise<{ revealedMove: number | null; fighter: PublicKey } | null> {
+): Promise<{ revealedMove: number | null; fighter: PublicKey; turn: number } | null> {
   const conn = connection ?? getConnection();
   const info = await conn.getAccountInfo(pda);
   if (!info || !info.data || info.data.length < 8 + 8 + 32 + 4 + 32 + 1) return null;
@@ -4237,14 +6787,15 @@ export async function readMoveCommitmentData(
   offset += 8; // skip rumble_id
   const fighter = new PublicKey(info.data.subarray(offset, offset + 32));
   offset += 32; // skip fighter
-  offset += 4; // skip turn
+  const turn = info.data.readUInt32LE(offset);
+  offset += 4;
   offset += 32; // skip commitment
 
   const hasMove = info.data[offset] === 1;
   offset += 1;
   const revealedMove = hasMove ? info.data[offset] : null;
 
-  return { revealedMove, fighter };
+  return { revealedMove, fighter, turn };
 }
 
 /**
@@ -4259,8 +6810,12 @@ export async function postTurnResultOnChain(
     moveB: number;
     damageToA: number;
     damageToB: number;
+    /** Crit rolls from the turn's `crit_seed`; only set when the rumble has crits enabled. */
+    critA?: boolean;
+    critB?: boolean;
   }>,
   byeFighterIdx: number | null,
+  moveCommitmentAccounts: PublicKey[],
   connection?: Connection,
 ): Promise<string | null> {
   const provider = getAdminProvider(connection);
@@ -4273,6 +6828,12 @@ export async function postTurnResultOnChain(
   const [rumblePda] = deriveRumblePda(rumbleId);
   const [combatStatePda] = deriveCombatStatePda(rumbleId);
   const [rumbleConfigPda] = deriveRumbleConfigPda();
+  const rumbleState = await readRumbleAccountState(rumbleId, connection);
+  // Must mirror the program's `audience_boost`: +1 per SOL backing a fighter, capped at 5.
+  const audienceBoosts = Array.from({ length: PROGRAM_MAX_FIGHTERS }, (_, idx) => {
+    const pool = rumbleState?.bettingPools[idx] ?? 0n;
+    return Number(pool / BigInt(LAMPORTS_PER_SOL) > 5n ? 5n : pool / BigInt(LAMPORTS_PER_SOL));
+  });
 
   console.log(`[ONCHAIN-POST-TURN] Sending postTurnResult for rumble ${rumbleId} (${duelResults.length} duels, bye=${byeFighterIdx})...`);
   const method = (program.methods as any)
@@ -4284,14 +6845,25 @@ export async function postTurnResultOnChain(
         moveB: d.moveB,
         damageToA: d.damageToA,
         damageToB: d.damageToB,
+        critA: d.critA ?? false,
+        critB: d.critB ?? false,
       })),
       byeFighterIdx,
+      audienceBoosts,
+    )
+    .remainingAccounts(
+      moveCommitmentAccounts.map((pubkey) => ({
+        pubkey,
+        isWritable: false,
+        isSigner: false,
+      })),
     )
     .accounts({
       keeper: admin.publicKey,
       rumble: rumblePda,
       combatState: combatStatePda,
       config: rumbleConfigPda,
+      ruleset: rumbleState?.ruleset ?? null,
     })
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
@@ -4648,8 +7220,10 @@ export async function requestIchorShowerVrf(
       payer: admin.publicKey,
       arenaConfig: arenaConfigPda,
       showerRequest: showerRequestPda,
+      showerHistory: deriveShowerHistoryPda()[0],
       ichorMint,
       recipientTokenAccount,
+      showerCap: deriveShowerCapPda(recipientTokenAccount)[0],
       showerVault,
       oracleQueue: VRF_DEFAULT_QUEUE,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -4661,3 +7235,31 @@ export async function requestIchorShowerVrf(
 
   return await sendAdminTxFireAndForget(method, admin, conn);
 }
+
+/**
+ * Admin: cancel a VRF shower request the oracle never answered. Only succeeds
+ * 10,000 slots after the request; the shower pool is left as is.
+ */
+export async function cancelVrfShowerRequest(
+  connection?: Connection,
+): Promise<string | null> {
+  const provider = getAdminProvider(connection);
+  if (!provider) {
+    console.warn("[solana-programs] No admin keypair, skipping cancelVrfShowerRequest");
+    return null;
+  }
+  const program = getIchorTokenProgram(provider);
+  const admin = getAdminKeypair()!;
+  const [arenaConfigPda] = deriveArenaConfigPda();
+  const [showerRequestPda] = deriveShowerRequestPda();
+
+  const method = (program.methods as any)
+    .cancelVrfShowerRequest()
+    .accounts({
+      authority: admin.publicKey,
+      arenaConfig: arenaConfigPda,
+      showerRequest: showerRequestPda,
+    });
+
+  return await sendAdminTxFireAndForget(method, admin, connection ?? getConnection());
+}
@@ -34,6 +34,7 @@ const TX_DELAY_MS = 400; // delay between txs to avoid rate limits
 const CONFIG_SEED = Buffer.from("rumble_config");
 const RUMBLE_SEED = Buffer.from("rumble");
 const VAULT_SEED = Buffer.from("vault");
+const COMBAT_STATE_SEED = Buffer.from("combat_state");
 const RUMBLE_DISCRIMINATOR = crypto
   .createHash("sha256")
   .update("account:Rumble")
@@ -64,6 +65,12 @@ function deriveVaultPda(rumbleId: number): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([VAULT_SEED, buf], PROGRAM_ID);
 }
 
+function deriveCombatStatePda(rumbleId: number): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync([COMBAT_STATE_SEED, buf], PROGRAM_ID);
+}
+
 const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
 
 async function sendTx(
@@ -359,13 +366,14 @@ type Candidate = {
           Array.from({ length: c.fighterCount }, (_, i) => i + 1),
         );
         method = (program.methods as any)
-          .adminSetResult(placements, 0)
+          .adminSetResult(placements, 0, false)
           .accounts({
             admin: admin.publicKey,
             config: configPda,
             rumble: rumblePda,
             vault: vaultPda,
             treasury,
+            combatState: deriveCombatStatePda(c.rumbleId)[0],
             systemProgram: SystemProgram.programId,
           });
       } else if (step === "completeRumble") {
@@ -373,7 +381,7 @@ type Candidate = {
           .completeRumble()
           .accounts({ admin: admin.publicKey, config: configPda, rumble: rumblePda });
       } else if (step === "sweepTreasury") {
-        method = (program.methods as any).sweepTreasury().accounts({
+        method = (program.methods as any).sweepTreasury(false).accounts({
           admin: admin.publicKey,
           config: configPda,
           rumble: rumblePda,
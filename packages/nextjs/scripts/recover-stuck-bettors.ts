@@ -498,8 +498,10 @@ async function main() {
         Array.from({ length: info.fighterCount }, (_, i) => i + 1),
       );
 
+      // `force` lets a rumble stuck mid-combat be overridden once the
+      // program's force timeout has passed; it is ignored before combat.
       const method = (program.methods as any)
-        .adminSetResult(placements, 0)
+        .adminSetResult(placements, 0, true)
         .accounts({
           admin: adminKeypair.publicKey,
           config: configPda,
@@ -55,6 +55,7 @@ const ADMIN_KEYPAIR_PATH = `${process.env.HOME}/.config/solana/mainnet-admin.jso
 const CONFIG_SEED = Buffer.from("rumble_config");
 const RUMBLE_SEED = Buffer.from("rumble");
 const VAULT_SEED = Buffer.from("vault");
+const COMBAT_STATE_SEED = Buffer.from("combat_state");
 
 // On-chain state indices
 const STATE_BETTING = 0;
@@ -94,6 +95,15 @@ function deriveVaultPda(rumbleId: number | bigint, programId: PublicKey = PROGRA
   return PublicKey.findProgramAddressSync([VAULT_SEED, buf], programId);
 }
 
+function deriveCombatStatePda(
+  rumbleId: number | bigint,
+  programId: PublicKey = PROGRAM_ID,
+): [PublicKey, number] {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(rumbleId));
+  return PublicKey.findProgramAddressSync([COMBAT_STATE_SEED, buf], programId);
+}
+
 function deriveConfigPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([CONFIG_SEED], programId);
 }
@@ -298,13 +308,14 @@ async function main() {
       const [vaultPda] = deriveVaultPda(rumbleId);
 
       const method = (program.methods as any)
-        .adminSetResult(Buffer.from(devPlacements), devWinnerIndex)
+        .adminSetResult(Buffer.from(devPlacements), devWinnerIndex, false)
         .accounts({
           admin: admin.publicKey,
           config: configPda,
           rumble: rumblePda,
           vault: vaultPda,
           treasury,
+          combatState: deriveCombatStatePda(rumbleId)[0],
           systemProgram: SystemProgram.programId,
         });
 
@@ -535,7 +535,7 @@ async function main() {
         const vaultBalance = await conn.getBalance(vaultPda);
         if (vaultBalance > 890_880) {
           const method = (program.methods as any)
-            .sweepTreasury()
+            .sweepTreasury(false)
             .accounts({
               admin: adminKeypair.publicKey,
               config: configPda,
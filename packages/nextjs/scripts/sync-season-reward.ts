@@ -29,8 +29,8 @@ async function main() {
     effectiveReward: before.effectiveReward.toString(),
   });
 
-  // Legacy accounts (len 145) cannot deserialize in newer handlers until migrated.
-  if (before.accountDataLen < 153) {
+  // Legacy accounts (len 145 / 153 / 161 / 163 / 179 / 187) cannot deserialize in newer handlers until migrated.
+  if (before.accountDataLen < 188) {
     console.log("[SeasonSync] Legacy ArenaConfig detected. Migrating...");
     const migrateSig = await migrateArenaConfigV2(TARGET_SEASON_REWARD, connection);
     console.log("[SeasonSync] migrateArenaConfigV2 sig:", migrateSig);
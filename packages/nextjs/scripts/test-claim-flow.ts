@@ -84,14 +84,16 @@ async function main() {
     if (rumbleInfo) {
       const rData = rumbleInfo.data;
       const state = ["betting", "combat", "payout", "complete"][rData[16]!] ?? "unknown";
-      const fighterCountOffset = 8 + 8 + 1 + 32 * 16;
+      // 32 fighter slots on v2 rumbles (2222+ bytes), 16 before.
+      const slots = rData.length >= 2222 ? 32 : 16;
+      const fighterCountOffset = 8 + 8 + 1 + 32 * slots;
       const fighterCount = rData[fighterCountOffset]!;
       const poolsOffset = fighterCountOffset + 1;
-      const totalDeployedOffset = poolsOffset + 8 * 16;
+      const totalDeployedOffset = poolsOffset + 8 * slots;
       const adminFeeOffset = totalDeployedOffset + 8;
       const sponsorOffset = adminFeeOffset + 8;
       const placementsOffset = sponsorOffset + 8;
-      const winnerIndexOffset = placementsOffset + 16;
+      const winnerIndexOffset = placementsOffset + slots;
       const winnerIndex = rData[winnerIndexOffset]!;
 
       console.log(`  State: ${state}`);
@@ -103,7 +105,7 @@ async function main() {
       // Check winner deployment
       const wOffset = 83; // in bettor data: skip 8+32+8+1+8+8+8+8+1+1 = 83
       const fighterDeployments: bigint[] = [];
-      for (let i = 0; i < 16; i++) {
+      for (let i = 0; i < slots; i++) {
         if (wOffset + i * 8 + 8 <= data.length) {
           const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
           fighterDeployments.push(view.getBigUint64(wOffset + i * 8, true));
@@ -49,7 +49,9 @@ const FINAL_DUEL_SUDDEN_DEATH_CHIP = 20;
 const METER_PER_TURN = 20;
 const SPECIAL_METER_COST = 100;
 const START_HP = 100;
-const MAX_FIGHTERS = 16;
+const MAX_FIGHTERS = 32;
+/** Fighter slots of rumbles (and combat states) from before the v2 layout. */
+const MAX_FIGHTERS_V1 = 16;
 
 const MOVE_NAMES = [
   "HIGH_STRIKE",
@@ -75,6 +77,11 @@ const DISC_RUMBLE = Buffer.from([121, 136, 74, 188, 164, 146, 171, 5]);
 const DISC_COMBAT_STATE = Buffer.from([81, 24, 234, 237, 157, 188, 177, 99]);
 const DISC_MOVE_COMMITMENT = Buffer.from([86, 55, 88, 157, 101, 93, 13, 220]);
 
+// Combat states shorter than this predate the zero-copy layout.
+const COMBAT_STATE_ZERO_COPY_LEN = 1872;
+// Rumble accounts this long or longer are on the 32-slot v2 layout.
+const RUMBLE_V2_LEN = 2222;
+
 // ─── Borsh enum: RumbleState ────────────────────────────────────────────────
 
 const RUMBLE_STATE_BETTING = 0;
@@ -189,13 +196,13 @@ function deriveCombatStatePda(rumbleId: bigint): PublicKey {
   return pda;
 }
 
+/** One MoveCommitment PDA per fighter per rumble, overwritten every turn. */
 function deriveMoveCommitmentPda(
   rumbleId: bigint,
   fighter: PublicKey,
-  turn: number,
 ): PublicKey {
   const [pda] = PublicKey.findProgramAddressSync(
-    [SEED_MOVE_COMMIT, u64LeBuffer(rumbleId), fighter.toBuffer(), u32LeBuffer(turn)],
+    [SEED_MOVE_COMMIT, u64LeBuffer(rumbleId), fighter.toBuffer()],
     PROGRAM_ID,
   );
   return pda;
@@ -216,8 +223,9 @@ function parseRumbleAccount(data: Buffer): RumbleAccount {
   const state = data.readUInt8(offset);
   offset += 1;
 
+  const slots = data.length >= RUMBLE_V2_LEN ? MAX_FIGHTERS : MAX_FIGHTERS_V1;
   const fighters: PublicKey[] = [];
-  for (let i = 0; i < MAX_FIGHTERS; i++) {
+  for (let i = 0; i < slots; i++) {
     fighters.push(new PublicKey(data.subarray(offset, offset + 32)));
     offset += 32;
   }
@@ -231,6 +239,7 @@ function parseCombatStateAccount(data: Buffer): CombatStateAccount {
   if (!data.subarray(0, 8).equals(DISC_COMBAT_STATE)) {
     throw new Error("Invalid CombatState account discriminator");
   }
+  if (data.length >= COMBAT_STATE_ZERO_COPY_LEN) return parseZeroCopyCombatState(data);
   let offset = 8;
 
   const rumbleId = data.readBigUInt64LE(offset); offset += 8;
@@ -244,27 +253,27 @@ function parseCombatStateAccount(data: Buffer): CombatStateAccount {
   const winnerIndex = data.readUInt8(offset); offset += 1;
 
   const hp: number[] = [];
-  for (let i = 0; i < MAX_FIGHTERS; i++) {
+  for (let i = 0; i < MAX_FIGHTERS_V1; i++) {
     hp.push(data.readUInt16LE(offset)); offset += 2;
   }
 
   const meter: number[] = [];
-  for (let i = 0; i < MAX_FIGHTERS; i++) {
+  for (let i = 0; i < MAX_FIGHTERS_V1; i++) {
     meter.push(data.readUInt8(offset)); offset += 1;
   }
 
   const eliminationRank: number[] = [];
-  for (let i = 0; i < MAX_FIGHTERS; i++) {
+  for (let i = 0; i < MAX_FIGHTERS_V1; i++) {
     eliminationRank.push(data.readUInt8(offset)); offset += 1;
   }
 
   const totalDamageDealt: bigint[] = [];
-  for (let i = 0; i < MAX_FIGHTERS; i++) {
+  for (let i = 0; i < MAX_FIGHTERS_V1; i++) {
     totalDamageDealt.push(data.readBigUInt64LE(offset)); offset += 8;
   }
 
   const totalDamageTaken: bigint[] = [];
-  for (let i = 0; i < MAX_FIGHTERS; i++) {
+  for (let i = 0; i < MAX_FIGHTERS_V1; i++) {
     totalDamageTaken.push(data.readBigUInt64LE(offset)); offset += 8;
   }
 
@@ -277,6 +286,46 @@ function parseCombatStateAccount(data: Buffer): CombatStateAccount {
   };
 }
 
+/** Zero-copy RumbleCombatState: fields ordered by alignment, not declaration. */
+function parseZeroCopyCombatState(data: Buffer): CombatStateAccount {
+  const u64Array = (offset: number) =>
+    Array.from({ length: MAX_FIGHTERS }, (_, i) => data.readBigUInt64LE(offset + i * 8));
+  const u8Array = (offset: number) =>
+    Array.from({ length: MAX_FIGHTERS }, (_, i) => data.readUInt8(offset + i));
+
+  const rumbleId = data.readBigUInt64LE(8);
+  const turnOpenSlot = data.readBigUInt64LE(16);
+  const commitCloseSlot = data.readBigUInt64LE(24);
+  const revealCloseSlot = data.readBigUInt64LE(32);
+  const totalDamageDealtOffset = 8 + 7 * 8;
+  const totalDamageTakenOffset = totalDamageDealtOffset + MAX_FIGHTERS * 8;
+  const currentTurnOffset = totalDamageTakenOffset + MAX_FIGHTERS * 8 + MAX_FIGHTERS * 24;
+  const hpOffset = currentTurnOffset + 4;
+  // hp, hp_overrides, turn_start_hp, pairs_resolved, vrf_seed, history_hash
+  const flagsOffset = hpOffset + 3 * MAX_FIGHTERS * 2 + 2 + 2 * 32;
+  // fighter_count, turn_resolved, remaining_fighters, winner_index, bump,
+  // turn_chunked, fighter_slots
+  const meterOffset = flagsOffset + 7;
+
+  return {
+    rumbleId,
+    fighterCount: data.readUInt8(flagsOffset),
+    currentTurn: data.readUInt32LE(currentTurnOffset),
+    turnOpenSlot,
+    commitCloseSlot,
+    revealCloseSlot,
+    turnResolved: data.readUInt8(flagsOffset + 1) !== 0,
+    remainingFighters: data.readUInt8(flagsOffset + 2),
+    winnerIndex: data.readUInt8(flagsOffset + 3),
+    hp: Array.from({ length: MAX_FIGHTERS }, (_, i) => data.readUInt16LE(hpOffset + i * 2)),
+    meter: u8Array(meterOffset),
+    eliminationRank: u8Array(meterOffset + MAX_FIGHTERS),
+    totalDamageDealt: u64Array(totalDamageDealtOffset),
+    totalDamageTaken: u64Array(totalDamageTakenOffset),
+    bump: data.readUInt8(flagsOffset + 4),
+  };
+}
+
 function parseMoveCommitmentAccount(data: Buffer): MoveCommitmentAccount | null {
   if (data.length < 103) return null;
   if (!data.subarray(0, 8).equals(DISC_MOVE_COMMITMENT)) return null;
@@ -477,15 +526,17 @@ async function fetchMoveCommitment(
   fighter: PublicKey,
   turn: number,
 ): Promise<MoveCommitmentAccount | null> {
-  const pda = deriveMoveCommitmentPda(rumbleId, fighter, turn);
+  const pda = deriveMoveCommitmentPda(rumbleId, fighter);
   const data = await fetchAccountData(conn, pda);
   if (!data) return null;
-  return parseMoveCommitmentAccount(data);
+  const parsed = parseMoveCommitmentAccount(data);
+  return parsed && parsed.turn === turn ? parsed : null;
 }
 
 /**
  * Batch-fetch all MoveCommitment PDAs for a set of fighters in a single
- * getMultipleAccountsInfo call.
+ * getMultipleAccountsInfo call. Each PDA only holds the fighter's latest
+ * commit, so earlier turns come back without it.
  */
 async function batchFetchMoveCommitments(
   conn: Connection,
@@ -495,7 +546,7 @@ async function batchFetchMoveCommitments(
   turn: number,
 ): Promise<Map<number, MoveCommitmentAccount>> {
   const pdas = indices.map((idx) =>
-    deriveMoveCommitmentPda(rumbleId, fighters[idx], turn),
+    deriveMoveCommitmentPda(rumbleId, fighters[idx]),
   );
   const accounts = await conn.getMultipleAccountsInfo(pdas, "confirmed");
 
@@ -504,7 +555,7 @@ async function batchFetchMoveCommitments(
     const accInfo = accounts[i];
     if (!accInfo || !accInfo.data) continue;
     const parsed = parseMoveCommitmentAccount(Buffer.from(accInfo.data));
-    if (parsed && parsed.revealed) {
+    if (parsed && parsed.revealed && parsed.turn === turn) {
       result.set(indices[i], parsed);
     }
   }
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::pubkey;
+use anchor_lang::system_program;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 
 declare_id!("2hA6Jvj1yjP2Uj3qrJcsBeYA2R9xPM95mDKw1ncKVExa");
@@ -13,13 +14,49 @@ const ADDITIONAL_FIGHTER_COST: u64 = 10 * ONE_ICHOR;
 /// Transfer fee: 5% of 1 ICHOR (burned)
 const TRANSFER_FEE: u64 = ONE_ICHOR / 20;
 
+/// Class-change fee for picking a specialization: 5 ICHOR (burned)
+const SPECIALIZATION_FEE: u64 = 5 * ONE_ICHOR;
+
+/// Rename fee: 1 ICHOR (burned)
+const RENAME_FEE: u64 = ONE_ICHOR;
+
+/// Retirement ceremony fee: 5 ICHOR (burned)
+const RETIREMENT_FEE: u64 = 5 * ONE_ICHOR;
+
+/// Wins required before a fighter can pick a specialization
+const SPECIALIZATION_MIN_WINS: u64 = 10;
+
 /// Maximum fighters per wallet
 const MAX_FIGHTERS_PER_WALLET: u8 = 5;
 
+/// Maximum combined strength + speed + defense per fighter
+const MAX_ATTRIBUTE_POINTS: u16 = 15;
+
+/// Damage taken in one rumble above which a fighter sits out the next one
+const INJURY_DAMAGE_THRESHOLD: u64 = 80;
+
+/// XP granted per win and per loss
+const XP_PER_WIN: u64 = 10;
+const XP_PER_LOSS: u64 = 2;
+
+/// XP needed to gain a level
+const XP_PER_LEVEL: u64 = 100;
+
+/// Cap on the starting-HP bonus a fighter's level grants
+const MAX_LEVEL_HP_BONUS: u16 = 10;
+
+/// Most fighters `batch_update_record` touches in one call (a full rumble).
+const MAX_RECORD_BATCH: usize = 16;
+
 /// PDA seeds
 const FIGHTER_SEED: &[u8] = b"fighter";
 const WALLET_STATE_SEED: &[u8] = b"wallet_state";
 const REGISTRY_SEED: &[u8] = b"registry_config";
+const FIGHTER_NAME_SEED: &[u8] = b"fname";
+const HALL_OF_FAME_SEED: &[u8] = b"hall_of_fame";
+const HEAD_TO_HEAD_SEED: &[u8] = b"h2h";
+/// Seed of the rumble-engine PDA that signs engine CPIs into the registry
+const ENGINE_AUTHORITY_SEED: &[u8] = b"registry_authority";
 
 /// Canonical ICHOR mint address — prevents fake token bypass on registration/transfer fees
 const EXPECTED_ICHOR_MINT: Pubkey = pubkey!("4amdLk5Ue4pbM1CXRZeUn3ZBAf8QTXXGu4HqH5dQv3qM");
@@ -34,6 +71,7 @@ pub mod fighter_registry {
         config.admin = ctx.accounts.admin.key();
         config.total_fighters = 0;
         config.bump = ctx.bumps.registry_config;
+        config.engine_program = Pubkey::default();
 
         msg!("Fighter registry initialized");
         Ok(())
@@ -41,7 +79,10 @@ pub mod fighter_registry {
 
     /// Register a new fighter for the calling wallet.
     /// First fighter per wallet is free; additional fighters cost 10 ICHOR (burned).
+    /// The name is claimed via its `FighterNameRegistry` PDA, so a taken name fails.
     pub fn register_fighter(ctx: Context<RegisterFighter>, name: [u8; 32]) -> Result<()> {
+        require!(is_valid_name(&name), RegistryError::InvalidName);
+
         let wallet_state = &mut ctx.accounts.wallet_state;
         let fighter = &mut ctx.accounts.fighter;
         let config = &mut ctx.accounts.registry_config;
@@ -119,6 +160,20 @@ pub mod fighter_registry {
         fighter.in_rumble = false;
         fighter.fighter_index = fighter_index;
         fighter.bump = ctx.bumps.fighter;
+        fighter.strength = 0;
+        fighter.speed = 0;
+        fighter.defense = 0;
+        fighter.specialization = None;
+        fighter.injured_until_rumble = 0;
+        fighter.xp = 0;
+        fighter.level = 0;
+        fighter.retired = false;
+        fighter.delegate = None;
+
+        let fighter_name = &mut ctx.accounts.fighter_name;
+        fighter_name.fighter = fighter.key();
+        fighter_name.created_at = clock.unix_timestamp;
+        fighter_name.bump = ctx.bumps.fighter_name;
 
         // Update wallet and global state
         wallet_state.fighter_count = fighter_index
@@ -139,6 +194,9 @@ pub mod fighter_registry {
     }
 
     /// Update a fighter's combat record after a Rumble. Admin/engine only.
+    /// Each rumble counts once: a repeat of the fighter's `last_rumble_id` is
+    /// rejected. Taking more than 80 damage injures the fighter for the next rumble.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_record(
         ctx: Context<UpdateRecord>,
         wins: u64,
@@ -147,84 +205,203 @@ pub mod fighter_registry {
         damage_taken: u64,
         ichor_mined: u64,
         rumble_id: u64,
+        damage_taken_this_rumble: u64,
     ) -> Result<()> {
         let fighter = &mut ctx.accounts.fighter;
-        let clock = Clock::get()?;
+        let update = RecordUpdate {
+            fighter_key: fighter.key(),
+            wins,
+            losses,
+            damage_dealt,
+            damage_taken,
+            ichor_mined,
+            rumble_id,
+            damage_taken_this_rumble,
+        };
+        apply_record_update(fighter, &update, Clock::get()?.unix_timestamp)?;
 
-        fighter.wins = fighter
-            .wins
-            .checked_add(wins)
-            .ok_or(RegistryError::MathOverflow)?;
-        fighter.losses = fighter
-            .losses
-            .checked_add(losses)
-            .ok_or(RegistryError::MathOverflow)?;
-        fighter.total_damage_dealt = fighter
-            .total_damage_dealt
-            .checked_add(damage_dealt)
-            .ok_or(RegistryError::MathOverflow)?;
-        fighter.total_damage_taken = fighter
-            .total_damage_taken
-            .checked_add(damage_taken)
-            .ok_or(RegistryError::MathOverflow)?;
-        fighter.total_rumbles = fighter
-            .total_rumbles
-            .checked_add(1)
-            .ok_or(RegistryError::MathOverflow)?;
-        fighter.total_ichor_mined = fighter
-            .total_ichor_mined
-            .checked_add(ichor_mined)
-            .ok_or(RegistryError::MathOverflow)?;
+        msg!(
+            "Fighter record updated: {}W-{}L, streak: {}, level {} ({} XP), rumble #{}",
+            fighter.wins,
+            fighter.losses,
+            fighter.current_streak,
+            fighter.level,
+            fighter.xp,
+            rumble_id
+        );
+        Ok(())
+    }
 
-        // Update streak
-        if wins > 0 {
-            // Won this rumble
-            if fighter.current_streak >= 0 {
-                fighter.current_streak = fighter
-                    .current_streak
-                    .checked_add(1)
-                    .ok_or(RegistryError::MathOverflow)?;
-            } else {
-                fighter.current_streak = 1;
-            }
-            // Update best streak
-            let streak_unsigned = fighter.current_streak as u64;
-            if streak_unsigned > fighter.best_streak {
-                fighter.best_streak = streak_unsigned;
-            }
-        } else if losses > 0 {
-            // Lost this rumble
-            if fighter.current_streak <= 0 {
-                fighter.current_streak = fighter
-                    .current_streak
-                    .checked_sub(1)
-                    .ok_or(RegistryError::MathOverflow)?;
-            } else {
-                fighter.current_streak = -1;
-            }
+    /// `update_record` for a whole rumble in one transaction. Admin/engine only.
+    /// Each update's `Fighter` account is passed writable in `remaining_accounts`;
+    /// at most `MAX_RECORD_BATCH` distinct fighters per call.
+    pub fn batch_update_record(
+        ctx: Context<UpdateRecordBatch>,
+        updates: Vec<RecordUpdate>,
+    ) -> Result<()> {
+        require!(
+            !updates.is_empty() && updates.len() <= MAX_RECORD_BATCH,
+            RegistryError::InvalidRecordBatch
+        );
+        let mut seen = std::collections::BTreeSet::new();
+        for update in updates.iter() {
+            require!(
+                seen.insert(update.fighter_key),
+                RegistryError::InvalidRecordBatch
+            );
         }
 
-        fighter.last_rumble_id = rumble_id;
-        fighter.last_rumble_at = clock.unix_timestamp;
+        let now = Clock::get()?.unix_timestamp;
+        for update in updates.iter() {
+            let info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|acc| *acc.key == update.fighter_key)
+                .ok_or(RegistryError::FighterAccountMissing)?;
+            // Owner + discriminator, as `Account<Fighter>` checks for `update_record`.
+            // Seeds aren't re-derived: transfers rewrite the authority/index they use.
+            require!(
+                *info.owner == crate::ID && info.is_writable,
+                RegistryError::FighterAccountMissing
+            );
+
+            let mut data = info.try_borrow_mut_data()?;
+            let mut fighter = Fighter::try_deserialize(&mut &data[..])?;
+            apply_record_update(&mut fighter, update, now)?;
+            fighter.try_serialize(&mut &mut data[..])?;
+        }
+
+        msg!("Fighter records updated: {} fighters", updates.len());
+        emit!(FighterRecordBatchUpdatedEvent {
+            fighters: updates.iter().map(|u| u.fighter_key).collect(),
+            rumble_ids: updates.iter().map(|u| u.rumble_id).collect(),
+        });
+        Ok(())
+    }
+
+    /// Admin: record the winner of a rumble between two fighters in their
+    /// head-to-head PDA, creating it on first meeting. `fighter_a` must sort
+    /// before `fighter_b` so each pair has one PDA; `winner_idx` 0 = a, 1 = b.
+    pub fn record_h2h(
+        ctx: Context<RecordHeadToHead>,
+        rumble_id: u64,
+        fighter_a: Pubkey,
+        fighter_b: Pubkey,
+        winner_idx: u8,
+    ) -> Result<()> {
+        require!(fighter_a < fighter_b, RegistryError::InvalidHeadToHead);
+        require!(winner_idx <= 1, RegistryError::InvalidHeadToHead);
+
+        let h2h = &mut ctx.accounts.head_to_head;
+        if h2h.fighter_a == Pubkey::default() {
+            h2h.fighter_a = fighter_a;
+            h2h.fighter_b = fighter_b;
+            h2h.bump = ctx.bumps.head_to_head;
+        } else {
+            require!(
+                h2h.last_rumble_id != rumble_id,
+                RegistryError::RecordAlreadyUpdated
+            );
+        }
+
+        if winner_idx == 0 {
+            h2h.wins_a = h2h.wins_a.checked_add(1).ok_or(RegistryError::MathOverflow)?;
+        } else {
+            h2h.wins_b = h2h.wins_b.checked_add(1).ok_or(RegistryError::MathOverflow)?;
+        }
+        h2h.last_rumble_id = rumble_id;
 
         msg!(
-            "Fighter record updated: {}W-{}L, streak: {}, rumble #{}",
-            fighter.wins,
-            fighter.losses,
-            fighter.current_streak,
+            "Head-to-head {} vs {}: {}-{} after rumble #{}",
+            fighter_a,
+            fighter_b,
+            h2h.wins_a,
+            h2h.wins_b,
             rumble_id
         );
         Ok(())
     }
 
-    /// Fighter joins the Rumble queue.
+    /// Set a fighter's combat attributes. Admin only; total points are capped.
+    pub fn set_attributes(
+        ctx: Context<SetAttributes>,
+        strength: u8,
+        speed: u8,
+        defense: u8,
+    ) -> Result<()> {
+        let fighter = &mut ctx.accounts.fighter;
+
+        require!(!fighter.retired, RegistryError::FighterRetired);
+        require!(!fighter.in_rumble, RegistryError::InRumble);
+        let total_points = strength as u16 + speed as u16 + defense as u16;
+        require!(
+            total_points <= MAX_ATTRIBUTE_POINTS,
+            RegistryError::AttributeCapExceeded
+        );
+
+        fighter.strength = strength;
+        fighter.speed = speed;
+        fighter.defense = defense;
+
+        msg!(
+            "Fighter attributes set: STR {}, SPD {}, DEF {}",
+            strength,
+            speed,
+            defense
+        );
+        Ok(())
+    }
+
+    /// Pick or change a fighter's specialization class once it has 10+ wins.
+    /// Callable by the fighter's authority or admin; burns 5 ICHOR from the signer.
+    pub fn set_specialization(
+        ctx: Context<SetSpecialization>,
+        class: Specialization,
+    ) -> Result<()> {
+        let fighter = &mut ctx.accounts.fighter;
+
+        require!(!fighter.retired, RegistryError::FighterRetired);
+        require!(!fighter.in_rumble, RegistryError::InRumble);
+        require!(
+            fighter.wins >= SPECIALIZATION_MIN_WINS,
+            RegistryError::NotEnoughWins
+        );
+        require!(
+            ctx.accounts.ichor_token_account.amount >= SPECIALIZATION_FEE,
+            RegistryError::InsufficientIchorForSpecialization
+        );
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.ichor_mint.to_account_info(),
+                    from: ctx.accounts.ichor_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            SPECIALIZATION_FEE,
+        )?;
+
+        fighter.specialization = Some(class);
+
+        msg!(
+            "Fighter specialization set to {:?}. Fee: {} ICHOR burned",
+            class,
+            SPECIALIZATION_FEE
+        );
+        Ok(())
+    }
+
+    /// Fighter joins the Rumble queue. Callable by the authority or its delegate.
     pub fn join_queue(
-        ctx: Context<JoinQueue>,
+        ctx: Context<DelegatedAction>,
         queue_position: u64,
         auto_requeue: bool,
     ) -> Result<()> {
         let fighter = &mut ctx.accounts.fighter;
 
+        require!(!fighter.retired, RegistryError::FighterRetired);
         require!(
             fighter.queue_position.is_none(),
             RegistryError::AlreadyQueued
@@ -242,8 +419,8 @@ pub mod fighter_registry {
         Ok(())
     }
 
-    /// Fighter leaves the Rumble queue.
-    pub fn leave_queue(ctx: Context<LeaveQueue>) -> Result<()> {
+    /// Fighter leaves the Rumble queue. Callable by the authority or its delegate.
+    pub fn leave_queue(ctx: Context<DelegatedAction>) -> Result<()> {
         let fighter = &mut ctx.accounts.fighter;
 
         require!(fighter.queue_position.is_some(), RegistryError::NotInQueue);
@@ -256,6 +433,131 @@ pub mod fighter_registry {
         Ok(())
     }
 
+    /// Rename a fighter, releasing its old name and claiming `new_name`.
+    /// Callable by the fighter's authority; burns 1 ICHOR.
+    pub fn rename_fighter(ctx: Context<RenameFighter>, new_name: [u8; 32]) -> Result<()> {
+        require!(is_valid_name(&new_name), RegistryError::InvalidName);
+        require!(
+            ctx.accounts.ichor_token_account.amount >= RENAME_FEE,
+            RegistryError::InsufficientIchorForRename
+        );
+
+        // Fighters registered before name uniqueness have no old name PDA to release
+        let old_name = &ctx.accounts.old_fighter_name;
+        if !old_name.data_is_empty() {
+            let record = FighterNameRegistry::try_deserialize(&mut &old_name.data.borrow()[..])?;
+            require_keys_eq!(
+                record.fighter,
+                ctx.accounts.fighter.key(),
+                RegistryError::NameNotOwned
+            );
+            close_program_account(
+                &old_name.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+            )?;
+        }
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.ichor_mint.to_account_info(),
+                    from: ctx.accounts.ichor_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            RENAME_FEE,
+        )?;
+
+        let fighter = &mut ctx.accounts.fighter;
+        fighter.name = new_name;
+
+        let new_fighter_name = &mut ctx.accounts.new_fighter_name;
+        new_fighter_name.fighter = fighter.key();
+        new_fighter_name.created_at = Clock::get()?.unix_timestamp;
+        new_fighter_name.bump = ctx.bumps.new_fighter_name;
+
+        msg!(
+            "Fighter {} renamed. Fee: {} ICHOR burned",
+            fighter.key(),
+            RENAME_FEE
+        );
+        Ok(())
+    }
+
+    /// Let a hot wallet manage the fighter's queue entries on the authority's
+    /// behalf. Replaces any existing delegate. Authority only.
+    pub fn delegate_fighter(ctx: Context<FighterOwnerAction>, delegate: Pubkey) -> Result<()> {
+        require!(
+            delegate != Pubkey::default() && delegate != ctx.accounts.fighter.authority,
+            RegistryError::InvalidDelegate
+        );
+        ctx.accounts.fighter.delegate = Some(delegate);
+
+        msg!("Fighter delegate set to {}", delegate);
+        Ok(())
+    }
+
+    /// Clear the fighter's delegate. Authority only.
+    pub fn revoke_delegation(ctx: Context<FighterOwnerAction>) -> Result<()> {
+        ctx.accounts.fighter.delegate = None;
+
+        msg!("Fighter delegate revoked");
+        Ok(())
+    }
+
+    /// Retire a fighter for good, locking its record and snapshotting it into a
+    /// `HallOfFameEntry`. The fighter must be out of the queue and any rumble.
+    /// Callable by the fighter's authority; burns 5 ICHOR.
+    pub fn retire_fighter(ctx: Context<RetireFighter>) -> Result<()> {
+        let fighter = &mut ctx.accounts.fighter;
+
+        require!(!fighter.retired, RegistryError::FighterRetired);
+        require!(!fighter.in_rumble, RegistryError::InRumble);
+        require!(
+            fighter.queue_position.is_none(),
+            RegistryError::MustLeaveQueueFirst
+        );
+        require!(
+            ctx.accounts.ichor_token_account.amount >= RETIREMENT_FEE,
+            RegistryError::InsufficientIchorForRetirement
+        );
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.ichor_mint.to_account_info(),
+                    from: ctx.accounts.ichor_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            RETIREMENT_FEE,
+        )?;
+
+        fighter.retired = true;
+
+        let entry = &mut ctx.accounts.hall_of_fame;
+        entry.fighter = fighter.key();
+        entry.authority = fighter.authority;
+        entry.name = fighter.name;
+        entry.wins = fighter.wins;
+        entry.losses = fighter.losses;
+        entry.best_streak = fighter.best_streak;
+        entry.total_ichor_mined = fighter.total_ichor_mined;
+        entry.retired_at = Clock::get()?.unix_timestamp;
+        entry.bump = ctx.bumps.hall_of_fame;
+
+        msg!(
+            "Fighter {} retired at {}W-{}L. Fee: {} ICHOR burned",
+            entry.fighter,
+            entry.wins,
+            entry.losses,
+            RETIREMENT_FEE
+        );
+        Ok(())
+    }
+
     /// Transfer a fighter's authority to a new wallet. Requires burning a 5% ICHOR fee.
     pub fn transfer_fighter(ctx: Context<TransferFighter>) -> Result<()> {
         let fighter = &mut ctx.accounts.fighter;
@@ -302,9 +604,10 @@ pub mod fighter_registry {
             .checked_add(1)
             .ok_or(RegistryError::MathOverflow)?;
 
-        // Transfer authority
+        // Transfer authority; the old owner's delegate doesn't carry over
         let old_key = fighter.authority;
         fighter.authority = ctx.accounts.new_authority.key();
+        fighter.delegate = None;
         fighter.fighter_index = new_wallet
             .fighter_count
             .checked_sub(1)
@@ -319,6 +622,131 @@ pub mod fighter_registry {
         Ok(())
     }
 
+    /// Grow a `Fighter` created before the attribute fields to the current
+    /// layout. The payer covers the extra rent and every byte after the
+    /// original record is zeroed, so the new fields read as their defaults.
+    /// Such fighters fail to load anywhere else until migrated. Permissionless:
+    /// no recorded value changes.
+    pub fn migrate_fighter(ctx: Context<MigrateFighter>) -> Result<()> {
+        const FIGHTER_LEN: usize = 8 + Fighter::INIT_SPACE;
+
+        let fighter_info = ctx.accounts.fighter.to_account_info();
+        require!(
+            fighter_info.data_len() < FIGHTER_LEN,
+            RegistryError::FighterAlreadyMigrated
+        );
+        let record_end = {
+            let data = fighter_info.try_borrow_data()?;
+            require!(
+                data.get(..8) == Some(Fighter::DISCRIMINATOR),
+                RegistryError::InvalidFighterAccount
+            );
+            legacy_fighter_record_end(&data).ok_or(RegistryError::InvalidFighterAccount)?
+        };
+
+        let rent = Rent::get()?;
+        let topup = rent
+            .minimum_balance(FIGHTER_LEN)
+            .saturating_sub(fighter_info.lamports());
+        if topup > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: fighter_info.clone(),
+                    },
+                ),
+                topup,
+            )?;
+        }
+        fighter_info.resize(FIGHTER_LEN)?;
+
+        // A shorter re-serialization (queue_position Some -> None) leaves stale
+        // bytes past the record; clear them along with the new space.
+        let mut data = fighter_info.try_borrow_mut_data()?;
+        data[record_end..].fill(0);
+
+        msg!(
+            "Fighter {} migrated to the current layout",
+            fighter_info.key()
+        );
+        Ok(())
+    }
+
+    /// Flag a fighter as entered in (or released from) a rumble. Admin or the
+    /// rumble-engine authority PDA only. A fighter can only be in one rumble,
+    /// and a retired fighter can't enter any.
+    pub fn set_in_rumble(ctx: Context<SetInRumble>, in_rumble: bool) -> Result<()> {
+        let fighter = &mut ctx.accounts.fighter;
+
+        if in_rumble {
+            require!(!fighter.retired, RegistryError::FighterRetired);
+            require!(!fighter.in_rumble, RegistryError::InRumble);
+        }
+        fighter.in_rumble = in_rumble;
+
+        msg!("Fighter in_rumble set to {}", in_rumble);
+        Ok(())
+    }
+
+    /// Admin: set the rumble-engine program allowed to call in through its
+    /// `registry_authority` PDA. Also migrates a registry config created before
+    /// `engine_program` existed, so run this once after upgrading.
+    pub fn set_engine_program(
+        ctx: Context<SetEngineProgram>,
+        engine_program: Pubkey,
+    ) -> Result<()> {
+        const CONFIG_V1_LEN: usize = 8 + 32 + 8 + 1; // 49
+        const CONFIG_LEN: usize = 8 + RegistryConfig::INIT_SPACE; // 81
+
+        let config_info = ctx.accounts.registry_config.to_account_info();
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                data.len() >= CONFIG_V1_LEN,
+                RegistryError::InvalidRegistryConfig
+            );
+            require!(
+                &data[..8] == RegistryConfig::DISCRIMINATOR,
+                RegistryError::InvalidRegistryConfig
+            );
+            let admin_bytes: [u8; 32] = data[8..40]
+                .try_into()
+                .map_err(|_| error!(RegistryError::InvalidRegistryConfig))?;
+            require!(
+                Pubkey::new_from_array(admin_bytes) == ctx.accounts.authority.key(),
+                RegistryError::Unauthorized
+            );
+        }
+
+        if config_info.data_len() < CONFIG_LEN {
+            let rent = Rent::get()?;
+            let topup = rent
+                .minimum_balance(CONFIG_LEN)
+                .saturating_sub(config_info.lamports());
+            if topup > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.authority.to_account_info(),
+                            to: config_info.clone(),
+                        },
+                    ),
+                    topup,
+                )?;
+            }
+            config_info.resize(CONFIG_LEN)?;
+        }
+
+        let mut data = config_info.try_borrow_mut_data()?;
+        data[CONFIG_V1_LEN..CONFIG_LEN].copy_from_slice(engine_program.as_ref());
+
+        msg!("Engine program set to {}", engine_program);
+        Ok(())
+    }
+
     /// Admin: update the admin key in registry config.
     pub fn update_admin(ctx: Context<AdminOnly>, new_admin: Pubkey) -> Result<()> {
         let config = &mut ctx.accounts.registry_config;
@@ -350,6 +778,7 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(name: [u8; 32])]
 pub struct RegisterFighter<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -372,6 +801,16 @@ pub struct RegisterFighter<'info> {
     )]
     pub fighter: Account<'info, Fighter>,
 
+    /// Fails with `AccountAlreadyInitialized` if another fighter holds the name.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FighterNameRegistry::INIT_SPACE,
+        seeds = [FIGHTER_NAME_SEED, name.as_ref()],
+        bump
+    )]
+    pub fighter_name: Account<'info, FighterNameRegistry>,
+
     #[account(
         mut,
         seeds = [REGISTRY_SEED],
@@ -396,7 +835,122 @@ pub struct RegisterFighter<'info> {
 
 #[derive(Accounts)]
 pub struct UpdateRecord<'info> {
-    /// Only admin/engine can update records.
+    /// Only admin, or rumble-engine signing with its `registry_authority` PDA.
+    #[account(
+        constraint = authority.key() == registry_config.admin
+            || is_engine_authority(&registry_config, &authority.key())
+            @ RegistryError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry_config.bump,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(mut)]
+    pub fighter: Account<'info, Fighter>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateFighter<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: `Fighter` in the pre-attribute layout, which no longer
+    /// deserializes. Owner is checked here; discriminator in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub fighter: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecordBatch<'info> {
+    /// Only admin, or rumble-engine signing with its `registry_authority` PDA.
+    #[account(
+        constraint = authority.key() == registry_config.admin
+            || is_engine_authority(&registry_config, &authority.key())
+            @ RegistryError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry_config.bump,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, fighter_a: Pubkey, fighter_b: Pubkey)]
+pub struct RecordHeadToHead<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == registry_config.admin @ RegistryError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry_config.bump,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + HeadToHead::INIT_SPACE,
+        seeds = [HEAD_TO_HEAD_SEED, fighter_a.as_ref(), fighter_b.as_ref()],
+        bump
+    )]
+    pub head_to_head: Account<'info, HeadToHead>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetInRumble<'info> {
+    /// Admin, or rumble-engine signing with its `registry_authority` PDA.
+    #[account(
+        constraint = authority.key() == registry_config.admin
+            || is_engine_authority(&registry_config, &authority.key())
+            @ RegistryError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry_config.bump,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(mut)]
+    pub fighter: Account<'info, Fighter>,
+}
+
+#[derive(Accounts)]
+pub struct SetEngineProgram<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Registry config PDA, possibly in the pre-`engine_program` layout.
+    /// Seeds and owner are checked here; admin and discriminator in the handler.
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump,
+        owner = crate::ID,
+    )]
+    pub registry_config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttributes<'info> {
+    /// Only admin can assign attributes.
     #[account(
         constraint = authority.key() == registry_config.admin @ RegistryError::Unauthorized,
     )]
@@ -413,8 +967,53 @@ pub struct UpdateRecord<'info> {
 }
 
 #[derive(Accounts)]
-pub struct JoinQueue<'info> {
-    /// Fighter's current authority must sign.
+pub struct SetSpecialization<'info> {
+    /// Fighter's authority or admin; pays the ICHOR fee.
+    #[account(
+        constraint = authority.key() == fighter.authority
+            || authority.key() == registry_config.admin @ RegistryError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry_config.bump,
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(mut)]
+    pub fighter: Account<'info, Fighter>,
+
+    #[account(mut, address = EXPECTED_ICHOR_MINT)]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = authority,
+    )]
+    pub ichor_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DelegatedAction<'info> {
+    /// Fighter's current authority or its delegate must sign.
+    #[account(
+        constraint = authority.key() == fighter.authority
+            || authority.key() == fighter.delegate.unwrap_or_default()
+            @ RegistryError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub fighter: Account<'info, Fighter>,
+}
+
+#[derive(Accounts)]
+pub struct FighterOwnerAction<'info> {
+    /// Fighter's current authority must sign; a delegate can't.
     #[account(
         constraint = authority.key() == fighter.authority @ RegistryError::Unauthorized,
     )]
@@ -425,15 +1024,83 @@ pub struct JoinQueue<'info> {
 }
 
 #[derive(Accounts)]
-pub struct LeaveQueue<'info> {
-    /// Fighter's current authority must sign.
+#[instruction(new_name: [u8; 32])]
+pub struct RenameFighter<'info> {
+    /// Fighter's current authority must sign; pays the ICHOR fee and new name rent.
     #[account(
+        mut,
         constraint = authority.key() == fighter.authority @ RegistryError::Unauthorized,
     )]
     pub authority: Signer<'info>,
 
     #[account(mut)]
     pub fighter: Account<'info, Fighter>,
+
+    /// CHECK: Name PDA of the fighter's current name; empty for fighters registered
+    /// before names were unique. Ownership of a live record is checked in the handler.
+    #[account(
+        mut,
+        seeds = [FIGHTER_NAME_SEED, fighter.name.as_ref()],
+        bump,
+    )]
+    pub old_fighter_name: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FighterNameRegistry::INIT_SPACE,
+        seeds = [FIGHTER_NAME_SEED, new_name.as_ref()],
+        bump
+    )]
+    pub new_fighter_name: Account<'info, FighterNameRegistry>,
+
+    #[account(mut, address = EXPECTED_ICHOR_MINT)]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = authority,
+    )]
+    pub ichor_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RetireFighter<'info> {
+    /// Fighter's current authority must sign; pays the ICHOR fee and entry rent.
+    #[account(
+        mut,
+        constraint = authority.key() == fighter.authority @ RegistryError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub fighter: Account<'info, Fighter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HallOfFameEntry::INIT_SPACE,
+        seeds = [HALL_OF_FAME_SEED, fighter.key().as_ref()],
+        bump
+    )]
+    pub hall_of_fame: Account<'info, HallOfFameEntry>,
+
+    #[account(mut, address = EXPECTED_ICHOR_MINT)]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = authority,
+    )]
+    pub ichor_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -507,6 +1174,35 @@ pub struct RegistryConfig {
     pub admin: Pubkey,       // 32
     pub total_fighters: u64, // 8
     pub bump: u8,            // 1
+    /// rumble-engine program whose `registry_authority` PDA may call in;
+    /// default = none.
+    pub engine_program: Pubkey, // 32
+}
+
+/// Whether `key` is the configured rumble-engine's registry authority PDA.
+fn is_engine_authority(config: &RegistryConfig, key: &Pubkey) -> bool {
+    if config.engine_program == Pubkey::default() {
+        return false;
+    }
+    let (expected, _bump) =
+        Pubkey::find_program_address(&[ENGINE_AUTHORITY_SEED], &config.engine_program);
+    *key == expected
+}
+
+/// Offset of `Fighter.queue_position`, the first variable-length field.
+const FIGHTER_QUEUE_POSITION_OFFSET: usize = 8 + 32 + 32 + 8 + 7 * 8 + 3 * 8;
+
+/// End of a pre-attribute `Fighter` record, which stops at `bump`: the
+/// `queue_position` option, then auto_requeue, in_rumble, last_rumble_id,
+/// last_rumble_at, fighter_index and bump.
+fn legacy_fighter_record_end(data: &[u8]) -> Option<usize> {
+    let option_len = match *data.get(FIGHTER_QUEUE_POSITION_OFFSET)? {
+        0 => 1,
+        1 => 9,
+        _ => return None,
+    };
+    let end = FIGHTER_QUEUE_POSITION_OFFSET + option_len + 1 + 1 + 8 + 8 + 1 + 1;
+    (end <= data.len()).then_some(end)
 }
 
 #[account]
@@ -544,6 +1240,199 @@ pub struct Fighter {
     pub last_rumble_at: i64, // 8
     pub fighter_index: u8,   // 1
     pub bump: u8,            // 1
+    // Attributes (strength + speed + defense <= MAX_ATTRIBUTE_POINTS).
+    // rumble-engine parses these right after `bump`; append new fields below them.
+    pub strength: u8, // 1
+    pub speed: u8,    // 1
+    pub defense: u8,  // 1
+    pub specialization: Option<Specialization>, // 1 + 1 = 2
+    // Injury: sits out every rumble up to and including this id (see `is_healthy`).
+    pub injured_until_rumble: u64, // 8
+    // Progression: XP_PER_LEVEL xp gains a level (see `level_up_bonus`).
+    // rumble-engine reads `level` at a fixed distance after `specialization`.
+    pub xp: u32,   // 4
+    pub level: u8, // 1
+    // Set once by `retire_fighter`; the record is frozen from then on.
+    pub retired: bool, // 1
+    // Hot wallet allowed to join/leave the queue; cleared on transfer.
+    pub delegate: Option<Pubkey>, // 1 + 32 = 33
+}
+
+/// Claim on a fighter name, seeded by the raw name bytes.
+#[account]
+#[derive(InitSpace)]
+pub struct FighterNameRegistry {
+    pub fighter: Pubkey, // 32
+    pub created_at: i64, // 8
+    pub bump: u8,        // 1
+}
+
+/// Snapshot of a retired fighter's record, seeded by the fighter's key.
+#[account]
+#[derive(InitSpace)]
+pub struct HallOfFameEntry {
+    pub fighter: Pubkey,        // 32
+    pub authority: Pubkey,      // 32
+    pub name: [u8; 32],         // 32
+    pub wins: u64,              // 8
+    pub losses: u64,            // 8
+    pub best_streak: u64,       // 8
+    pub total_ichor_mined: u64, // 8
+    pub retired_at: i64,        // 8
+    pub bump: u8,               // 1
+}
+
+/// Running score between two fighters, seeded by both keys in sorted order.
+#[account]
+#[derive(InitSpace)]
+pub struct HeadToHead {
+    pub fighter_a: Pubkey,   // 32 (sorts before fighter_b)
+    pub fighter_b: Pubkey,   // 32
+    pub wins_a: u32,         // 4
+    pub wins_b: u32,         // 4
+    pub last_rumble_id: u64, // 8
+    pub bump: u8,            // 1
+}
+
+/// Names must have at least one non-zero byte.
+fn is_valid_name(name: &[u8; 32]) -> bool {
+    name.iter().any(|&b| b != 0)
+}
+
+/// Close a program-owned account held as a raw `AccountInfo`, returning rent to `destination`.
+fn close_program_account(info: &AccountInfo<'_>, destination: &AccountInfo<'_>) -> Result<()> {
+    let lamports = info.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(RegistryError::MathOverflow)?;
+    **info.try_borrow_mut_lamports()? = 0;
+
+    info.assign(&system_program::ID);
+    info.resize(0)?;
+    Ok(())
+}
+
+/// One fighter's results from a rumble, as taken by `update_record`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RecordUpdate {
+    pub fighter_key: Pubkey,
+    pub wins: u64,
+    pub losses: u64,
+    pub damage_dealt: u64,
+    pub damage_taken: u64,
+    pub ichor_mined: u64,
+    pub rumble_id: u64,
+    pub damage_taken_this_rumble: u64,
+}
+
+/// Fold one rumble's results into a fighter's record: totals, streak, injury
+/// and XP. Shared by `update_record` and `batch_update_record`.
+fn apply_record_update(fighter: &mut Fighter, update: &RecordUpdate, now: i64) -> Result<()> {
+    require!(!fighter.retired, RegistryError::FighterRetired);
+    require!(
+        fighter.total_rumbles == 0 || fighter.last_rumble_id != update.rumble_id,
+        RegistryError::RecordAlreadyUpdated
+    );
+
+    fighter.wins = fighter
+        .wins
+        .checked_add(update.wins)
+        .ok_or(RegistryError::MathOverflow)?;
+    fighter.losses = fighter
+        .losses
+        .checked_add(update.losses)
+        .ok_or(RegistryError::MathOverflow)?;
+    fighter.total_damage_dealt = fighter
+        .total_damage_dealt
+        .checked_add(update.damage_dealt)
+        .ok_or(RegistryError::MathOverflow)?;
+    fighter.total_damage_taken = fighter
+        .total_damage_taken
+        .checked_add(update.damage_taken)
+        .ok_or(RegistryError::MathOverflow)?;
+    fighter.total_rumbles = fighter
+        .total_rumbles
+        .checked_add(1)
+        .ok_or(RegistryError::MathOverflow)?;
+    fighter.total_ichor_mined = fighter
+        .total_ichor_mined
+        .checked_add(update.ichor_mined)
+        .ok_or(RegistryError::MathOverflow)?;
+
+    // Update streak
+    if update.wins > 0 {
+        // Won this rumble
+        if fighter.current_streak >= 0 {
+            fighter.current_streak = fighter
+                .current_streak
+                .checked_add(1)
+                .ok_or(RegistryError::MathOverflow)?;
+        } else {
+            fighter.current_streak = 1;
+        }
+        // Update best streak
+        let streak_unsigned = fighter.current_streak as u64;
+        if streak_unsigned > fighter.best_streak {
+            fighter.best_streak = streak_unsigned;
+        }
+    } else if update.losses > 0 {
+        // Lost this rumble
+        if fighter.current_streak <= 0 {
+            fighter.current_streak = fighter
+                .current_streak
+                .checked_sub(1)
+                .ok_or(RegistryError::MathOverflow)?;
+        } else {
+            fighter.current_streak = -1;
+        }
+    }
+
+    fighter.last_rumble_id = update.rumble_id;
+    fighter.last_rumble_at = now;
+    if update.damage_taken_this_rumble > INJURY_DAMAGE_THRESHOLD {
+        fighter.injured_until_rumble = update
+            .rumble_id
+            .checked_add(1)
+            .ok_or(RegistryError::MathOverflow)?;
+    }
+
+    // XP: a level up resets xp to 0
+    let xp_gained = update
+        .wins
+        .checked_mul(XP_PER_WIN)
+        .and_then(|xp| xp.checked_add(update.losses.checked_mul(XP_PER_LOSS)?))
+        .ok_or(RegistryError::MathOverflow)?;
+    let xp = (fighter.xp as u64)
+        .checked_add(xp_gained)
+        .ok_or(RegistryError::MathOverflow)?;
+    if xp >= XP_PER_LEVEL {
+        fighter.level = fighter.level.saturating_add(1);
+        fighter.xp = 0;
+    } else {
+        fighter.xp = xp as u32;
+    }
+    Ok(())
+}
+
+/// Extra starting HP a fighter's level is worth in rumble-engine combat.
+pub fn level_up_bonus(level: u8) -> u16 {
+    (level as u16).min(MAX_LEVEL_HP_BONUS)
+}
+
+/// Whether a fighter can enter `rumble_id`, i.e. is not sitting out an injury.
+pub fn is_healthy(fighter: &Fighter, rumble_id: u64) -> bool {
+    fighter.injured_until_rumble < rumble_id
+}
+
+/// Combat class unlocked at 10 wins. Variant order is part of the account
+/// layout read by rumble-engine; append new classes at the end.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Specialization {
+    Brawler,
+    Duelist,
+    Guardian,
+    Trickster,
 }
 
 // ---------------------------------------------------------------------------
@@ -564,6 +1453,12 @@ pub struct FighterTransferred {
     pub fee_burned: u64,
 }
 
+#[event]
+pub struct FighterRecordBatchUpdatedEvent {
+    pub fighters: Vec<Pubkey>,
+    pub rumble_ids: Vec<u64>,
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -596,4 +1491,52 @@ pub enum RegistryError {
 
     #[msg("Math overflow")]
     MathOverflow,
+
+    #[msg("Attribute points exceed the per-fighter cap of 15")]
+    AttributeCapExceeded,
+
+    #[msg("Fighter account already uses the current layout")]
+    FighterAlreadyMigrated,
+
+    #[msg("Not a fighter-registry Fighter account")]
+    InvalidFighterAccount,
+
+    #[msg("Fighter needs at least 10 wins to pick a specialization")]
+    NotEnoughWins,
+
+    #[msg("Insufficient ICHOR to change specialization (5 ICHOR required)")]
+    InsufficientIchorForSpecialization,
+
+    #[msg("Invalid registry config account")]
+    InvalidRegistryConfig,
+
+    #[msg("Fighter record already updated for this rumble")]
+    RecordAlreadyUpdated,
+
+    #[msg("Fighter name must not be empty")]
+    InvalidName,
+
+    #[msg("Insufficient ICHOR to rename fighter (1 ICHOR required)")]
+    InsufficientIchorForRename,
+
+    #[msg("Name record does not belong to this fighter")]
+    NameNotOwned,
+
+    #[msg("Fighter is retired")]
+    FighterRetired,
+
+    #[msg("Insufficient ICHOR to retire fighter (5 ICHOR required)")]
+    InsufficientIchorForRetirement,
+
+    #[msg("Delegate must be a non-default key other than the fighter's authority")]
+    InvalidDelegate,
+
+    #[msg("Record batch must hold 1-16 updates for distinct fighters")]
+    InvalidRecordBatch,
+
+    #[msg("Fighter account missing or not writable for a record update")]
+    FighterAccountMissing,
+
+    #[msg("Head-to-head needs two fighters in sorted order and a winner index of 0 or 1")]
+    InvalidHeadToHead,
 }
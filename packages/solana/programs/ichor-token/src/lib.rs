@@ -25,14 +25,35 @@ const SHOWER_BONUS_EMISSION: u64 = 200_000_000;
 /// Ichor Shower pool contribution from reward: 0.1 ICHOR
 const SHOWER_POOL_CUT: u64 = 100_000_000;
 
-/// Ichor Shower trigger chance: 1 in 500
-const SHOWER_CHANCE: u64 = 500;
-
-/// Seasonal split model (matches current betting.ts season math).
+/// Default Ichor Shower trigger chance: 1 in 500
+const DEFAULT_SHOWER_CHANCE: u64 = 500;
+/// Bounds for the admin-configured `shower_chance` (1 in 50 to 1 in 5,000).
+const MIN_SHOWER_CHANCE: u64 = 50;
+const MAX_SHOWER_CHANCE: u64 = 5_000;
+
+/// Default share of a triggered shower that is burned (10%); the rest goes to
+/// the recipient. Admin-configurable up to MAX_SHOWER_BURN_BPS.
+const DEFAULT_SHOWER_BURN_BPS: u16 = 1_000;
+const MAX_SHOWER_BURN_BPS: u16 = 5_000;
+
+/// Slots after a shower settles before a new request can be opened; a
+/// triggered shower drains the pool, so an immediate retry is pointless.
+const DEFAULT_SHOWER_COOLDOWN_SLOTS: u64 = 1_000;
+const MAX_SHOWER_COOLDOWN_SLOTS: u64 = 216_000; // ~1 day
+
+/// Most shower payout one recipient token account can receive per window;
+/// anything above the cap is burned.
+const DEFAULT_MAX_SHOWER_PER_DAY: u64 = 100_000 * ONE_ICHOR;
+const SHOWER_CAP_WINDOW_SECONDS: i64 = 86_400;
+
+/// Default seasonal split model (matches current betting.ts season math).
+/// Used when no SeasonConfig covers the current rumble.
 const BETTOR_SHARE_BPS: u64 = 1_000; // 10%
 const FIGHTER_SHARE_BPS: u64 = 8_000; // 80%
 const SHOWER_SHARE_BPS: u64 = 1_000; // 10%
 const FIGHTER_FIRST_SHARE_BPS: u64 = 4_000; // 40% of fighter share => 32% of total reward
+/// Rumbles one `batch_distribute_reward` may pay out (compute budget).
+const MAX_REWARD_BATCH: usize = 8;
 
 /// Halving schedule boundaries (by rumble count)
 const HALVING_1: u64 = 2_100_000;
@@ -45,15 +66,44 @@ const ARENA_SEED: &[u8] = b"arena_config";
 const DISTRIBUTION_VAULT_SEED: &[u8] = b"distribution_vault";
 /// Shower request PDA seed
 const SHOWER_REQUEST_SEED: &[u8] = b"shower_request";
+/// Shower history PDA seed
+const SHOWER_HISTORY_SEED: &[u8] = b"shower_history";
+/// Settled showers kept in the ShowerHistory ring buffer.
+const SHOWER_HISTORY_LEN: usize = 16;
+/// Per-recipient shower cap PDA seed (plus the recipient token account)
+const SHOWER_CAP_SEED: &[u8] = b"shower_cap";
 /// Entropy config PDA seed
 const ENTROPY_CONFIG_SEED: &[u8] = b"entropy_config";
 /// Pending admin transfer PDA seed
 const PENDING_ADMIN_SEED: &[u8] = b"pending_admin";
+/// How long a proposed admin has to accept before the proposal lapses (~7 days of slots).
+const PENDING_ADMIN_EXPIRY_SLOTS: u64 = 1_512_000;
+/// Per-staker ICHOR stake PDA seed
+const STAKE_SEED: &[u8] = b"stake";
+/// Stake vault PDA seed (token account holding every locked stake)
+const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+/// Season config PDA seed (plus the season id, little-endian)
+const SEASON_CONFIG_SEED: &[u8] = b"season_config";
+
+/// Allowed stake lock periods: 7 days to 1 year.
+const MIN_STAKE_LOCK_SECONDS: i64 = 7 * 86_400;
+const MAX_STAKE_LOCK_SECONDS: i64 = 365 * 86_400;
+/// (minimum lock seconds, yield bps) tiers, longest lock first. rumble-engine
+/// adds the yield to a staker's betting winnings while the lock runs.
+const STAKE_YIELD_TIERS: [(i64, u16); 3] = [
+    (90 * 86_400, 200), // 2%
+    (30 * 86_400, 100), // 1%
+    (7 * 86_400, 50),   // 0.5%
+];
 
 /// Delayed-slot entropy schedule (must settle before slot hash eviction window).
 const SHOWER_DELAY_SLOT_A: u64 = 8;
 const SHOWER_DELAY_SLOT_B: u64 = 24;
 
+/// Slots after which an unanswered VRF shower request can be cancelled by the
+/// admin (oracle outage); see `cancel_vrf_shower_request`.
+const SHOWER_VRF_EXPIRY_SLOTS: u64 = 10_000;
+
 /// entropy_api::state::Var payload size (without account discriminator).
 const ENTROPY_VAR_LEN: usize = 232;
 
@@ -88,6 +138,12 @@ pub mod ichor_token {
         arena.treasury_vault = 0;
         arena.bump = bump;
         arena.season_reward = default_season_reward;
+        arena.shower_chance = DEFAULT_SHOWER_CHANCE;
+        arena.shower_burn_bps = DEFAULT_SHOWER_BURN_BPS;
+        arena.shower_last_settled_slot = 0;
+        arena.shower_cooldown_slots = DEFAULT_SHOWER_COOLDOWN_SLOTS;
+        arena.max_shower_per_day = DEFAULT_MAX_SHOWER_PER_DAY;
+        arena.refill_require_admin = false;
 
         // Mint the full 1B supply to the distribution vault
         // (use to_account_info() to avoid borrow conflicts)
@@ -125,46 +181,24 @@ pub mod ichor_token {
     ///
     /// Remaining seasonal splits (winner bettors + non-1st fighters) are sent
     /// on-chain by orchestrator via `admin_distribute`.
+    ///
+    /// Split bps come from `season_config`; once its season has ended they
+    /// come from `next_season_config` if given, else the default constants.
     pub fn distribute_reward(ctx: Context<DistributeReward>) -> Result<()> {
         let arena_info = ctx.accounts.arena_config.to_account_info();
         let arena = &mut ctx.accounts.arena_config;
 
-        // Calculate reward (season-based flat reward, no halving)
-        let reward = calculate_reward(
+        let shares = season_shares(
+            arena.total_rumbles_completed,
+            &ctx.accounts.season_config,
+            ctx.accounts.next_season_config.as_deref(),
+        )?;
+        let (winner_amount, shower_addition) = core_reward_emission(
             arena.base_reward,
             arena.total_rumbles_completed,
             arena.season_reward,
-        );
-
-        let _bettor_pool = reward
-            .checked_mul(BETTOR_SHARE_BPS)
-            .ok_or(IchorError::MathOverflow)?
-            .checked_div(10_000)
-            .ok_or(IchorError::MathOverflow)?;
-
-        let fighter_pool = reward
-            .checked_mul(FIGHTER_SHARE_BPS)
-            .ok_or(IchorError::MathOverflow)?
-            .checked_div(10_000)
-            .ok_or(IchorError::MathOverflow)?;
-
-        let winner_amount = fighter_pool
-            .checked_mul(FIGHTER_FIRST_SHARE_BPS)
-            .ok_or(IchorError::MathOverflow)?
-            .checked_div(10_000)
-            .ok_or(IchorError::MathOverflow)?;
-
-        let shower_from_reward = reward
-            .checked_mul(SHOWER_SHARE_BPS)
-            .ok_or(IchorError::MathOverflow)?
-            .checked_div(10_000)
-            .ok_or(IchorError::MathOverflow)?;
-
-        let shower_addition = shower_from_reward
-            .checked_add(SHOWER_BONUS_EMISSION)
-            .ok_or(IchorError::MathOverflow)?;
-
-        // This instruction emits only the core on-chain portion.
+            &shares,
+        )?;
         let total_emission = winner_amount
             .checked_add(shower_addition)
             .ok_or(IchorError::MathOverflow)?;
@@ -175,64 +209,105 @@ pub mod ichor_token {
             IchorError::VaultInsufficientBalance
         );
 
-        // Build PDA signer seeds
-        let bump = &[arena.bump];
-        let seeds: &[&[u8]] = &[ARENA_SEED, bump];
-        let signer_seeds = &[seeds];
+        pay_core_reward(
+            arena,
+            arena_info,
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.distribution_vault.to_account_info(),
+            ctx.accounts.winner_token_account.to_account_info(),
+            ctx.accounts.shower_vault.to_account_info(),
+            None,
+            winner_amount,
+            shower_addition,
+        )
+    }
 
-        // Transfer winner's share from vault to their token account
-        if winner_amount > 0 {
-            token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.distribution_vault.to_account_info(),
-                        to: ctx.accounts.winner_token_account.to_account_info(),
-                        authority: arena_info.clone(),
-                    },
-                    signer_seeds,
-                ),
-                winner_amount,
+    /// `distribute_reward` for up to `MAX_REWARD_BATCH` completed rumbles in
+    /// one transaction. `remaining_accounts` carries the winner token accounts
+    /// in the order of `winner_token_accounts`, one per rumble id. The vault
+    /// must cover the whole batch; nothing is paid otherwise.
+    pub fn batch_distribute_reward<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchDistributeReward<'info>>,
+        rumble_ids: Vec<u64>,
+        winner_token_accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        validate_reward_batch(&rumble_ids, &winner_token_accounts)?;
+        require!(
+            ctx.remaining_accounts.len() == winner_token_accounts.len(),
+            IchorError::InvalidRewardBatch
+        );
+
+        let arena_info = ctx.accounts.arena_config.to_account_info();
+        let arena = &mut ctx.accounts.arena_config;
+
+        let mut emissions = Vec::with_capacity(rumble_ids.len());
+        let mut total_emission: u64 = 0;
+        for (i, (info, expected)) in ctx
+            .remaining_accounts
+            .iter()
+            .zip(winner_token_accounts.iter())
+            .enumerate()
+        {
+            require!(
+                info.key() == *expected && info.is_writable,
+                IchorError::InvalidWinnerTokenAccount
+            );
+            let winner_token_account = Account::<TokenAccount>::try_from(info)
+                .map_err(|_| error!(IchorError::InvalidWinnerTokenAccount))?;
+            require!(
+                winner_token_account.mint == arena.ichor_mint,
+                IchorError::InvalidMint
+            );
+
+            let rumbles_completed = arena
+                .total_rumbles_completed
+                .checked_add(i as u64)
+                .ok_or(IchorError::MathOverflow)?;
+            let shares = season_shares(
+                rumbles_completed,
+                &ctx.accounts.season_config,
+                ctx.accounts.next_season_config.as_deref(),
             )?;
+            let (winner_amount, shower_addition) = core_reward_emission(
+                arena.base_reward,
+                rumbles_completed,
+                arena.season_reward,
+                &shares,
+            )?;
+            total_emission = total_emission
+                .checked_add(winner_amount)
+                .and_then(|v| v.checked_add(shower_addition))
+                .ok_or(IchorError::MathOverflow)?;
+            emissions.push((winner_amount, shower_addition));
         }
 
-        // Transfer shower pool portion from vault to the shower vault
-        if shower_addition > 0 {
-            token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.distribution_vault.to_account_info(),
-                        to: ctx.accounts.shower_vault.to_account_info(),
-                        authority: arena_info.clone(),
-                    },
-                    signer_seeds,
-                ),
+        require!(
+            ctx.accounts.distribution_vault.amount >= total_emission,
+            IchorError::VaultInsufficientBalance
+        );
+
+        for ((rumble_id, info), (winner_amount, shower_addition)) in rumble_ids
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .zip(emissions)
+        {
+            pay_core_reward(
+                arena,
+                arena_info.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.distribution_vault.to_account_info(),
+                info.clone(),
+                ctx.accounts.shower_vault.to_account_info(),
+                Some(*rumble_id),
+                winner_amount,
                 shower_addition,
             )?;
         }
 
-        // Update state
-        let new_total = arena
-            .total_distributed
-            .checked_add(total_emission)
-            .ok_or(IchorError::MathOverflow)?;
-        arena.total_distributed = new_total;
-        arena.total_rumbles_completed = arena
-            .total_rumbles_completed
-            .checked_add(1)
-            .ok_or(IchorError::MathOverflow)?;
-        arena.ichor_shower_pool = arena
-            .ichor_shower_pool
-            .checked_add(shower_addition)
-            .ok_or(IchorError::MathOverflow)?;
-
         msg!(
-            "Rumble #{} on-chain core emission: {} to 1st fighter, {} to shower pool. Total distributed: {}",
-            arena.total_rumbles_completed,
-            winner_amount,
-            shower_addition,
-            arena.total_distributed
+            "Batch reward: {} rumbles, {} ICHOR distributed",
+            rumble_ids.len(),
+            total_emission
         );
 
         Ok(())
@@ -249,9 +324,13 @@ pub mod ichor_token {
         let arena_info = ctx.accounts.arena_config.to_account_info();
         let arena = &mut ctx.accounts.arena_config;
         let request = &mut ctx.accounts.shower_request;
+        let history = &mut ctx.accounts.shower_history;
+        let cap = &mut ctx.accounts.shower_cap;
         let clock = Clock::get()?;
         let slot = clock.slot;
         let is_admin = ctx.accounts.authority.key() == arena.admin;
+        history.bump = ctx.bumps.shower_history;
+        cap.bump = ctx.bumps.shower_cap;
 
         // Initialize request metadata once.
         if !request.initialized {
@@ -271,6 +350,10 @@ pub mod ichor_token {
             // Only admin can open a new request/recipient pair.
             require!(is_admin, IchorError::Unauthorized);
             require!(arena.ichor_shower_pool > 0, IchorError::EmptyShowerPool);
+            require!(
+                shower_cooldown_elapsed(arena, slot),
+                IchorError::ShowerCooldownActive
+            );
 
             request.request_nonce = request
                 .request_nonce
@@ -446,22 +529,27 @@ pub mod ichor_token {
                 &request.recipient_token_account,
             )
         };
-        let triggered = rng_value % SHOWER_CHANCE == 0;
+        let triggered = shower_triggered(rng_value, arena.shower_chance);
+        let mut paid_amount = 0;
 
         if triggered {
             // Use the smaller of the bookkeeping counter and actual vault balance
             // to prevent desync from causing a revert (H-2 fix).
             let vault_balance = ctx.accounts.shower_vault.amount;
             let pool_amount = arena.ichor_shower_pool.min(vault_balance);
-
-            // 90% to recipient, 10% burned
-            let recipient_amount = pool_amount
-                .checked_mul(90)
-                .ok_or(IchorError::MathOverflow)?
-                .checked_div(100)
-                .ok_or(IchorError::MathOverflow)?;
-            let burn_amount = pool_amount
-                .checked_sub(recipient_amount)
+            paid_amount = pool_amount;
+
+            // `shower_burn_bps` burned, the rest to the recipient up to their daily cap
+            let (recipient_amount, burn_amount) =
+                split_shower_payout(pool_amount, arena.shower_burn_bps)?;
+            let (recipient_amount, over_cap) = cap_shower_payout(
+                cap,
+                clock.unix_timestamp,
+                arena.max_shower_per_day,
+                recipient_amount,
+            )?;
+            let burn_amount = burn_amount
+                .checked_add(over_cap)
                 .ok_or(IchorError::MathOverflow)?;
 
             // The shower vault's authority is the arena_config PDA.
@@ -469,7 +557,7 @@ pub mod ichor_token {
             let seeds: &[&[u8]] = &[ARENA_SEED, bump];
             let signer_seeds = &[seeds];
 
-            // Transfer 90% to recipient
+            // Transfer the recipient's share
             if recipient_amount > 0 {
                 token::transfer(
                     CpiContext::new_with_signer(
@@ -485,7 +573,7 @@ pub mod ichor_token {
                 )?;
             }
 
-            // Burn 10%
+            // Burn the rest
             if burn_amount > 0 {
                 token::burn(
                     CpiContext::new_with_signer(
@@ -527,7 +615,17 @@ pub mod ichor_token {
             );
         }
 
-        reset_shower_request(request);
+        record_shower_outcome(
+            history,
+            ShowerEntry {
+                slot,
+                triggered,
+                recipient: request.recipient_token_account,
+                amount: paid_amount,
+                rng_value,
+            },
+        );
+        settle_shower_request(arena, request, slot);
         Ok(())
     }
 
@@ -551,6 +649,159 @@ pub mod ichor_token {
         Ok(())
     }
 
+    /// Donate ICHOR to the shower pool. Permissionless, so sponsors and the
+    /// community can grow the jackpot between rumbles.
+    pub fn top_up_shower_pool(ctx: Context<TopUpShowerPool>, amount: u64) -> Result<()> {
+        require!(amount > 0, IchorError::ZeroTopUpAmount);
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.contributor_token_account.to_account_info(),
+                    to: ctx.accounts.shower_vault.to_account_info(),
+                    authority: ctx.accounts.contributor.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let arena = &mut ctx.accounts.arena_config;
+        arena.ichor_shower_pool = arena
+            .ichor_shower_pool
+            .checked_add(amount)
+            .ok_or(IchorError::MathOverflow)?;
+
+        emit!(ShowerPoolToppedUpEvent {
+            contributor: ctx.accounts.contributor.key(),
+            amount,
+            new_pool: arena.ichor_shower_pool,
+        });
+
+        msg!(
+            "Shower pool topped up by {} ICHOR, now {}",
+            amount,
+            arena.ichor_shower_pool
+        );
+        Ok(())
+    }
+
+    /// Transfer ICHOR from the funder's token account into the distribution
+    /// vault, e.g. to fund a vault created by `initialize_with_mint`. Anyone
+    /// can refill unless `refill_require_admin` is set. `total_distributed` is
+    /// left untouched.
+    pub fn fund_distribution_vault(ctx: Context<FundDistributionVault>, amount: u64) -> Result<()> {
+        require!(amount > 0, IchorError::ZeroTopUpAmount);
+        require_refill_authorized(&ctx.accounts.arena_config, &ctx.accounts.funder.key())?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source_token_account.to_account_info(),
+                    to: ctx.accounts.distribution_vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        ctx.accounts.distribution_vault.reload()?;
+        let new_balance = ctx.accounts.distribution_vault.amount;
+
+        emit!(VaultRefillEvent {
+            amount,
+            funder: ctx.accounts.funder.key(),
+            new_balance,
+        });
+
+        msg!(
+            "Distribution vault refilled with {} ICHOR, now {}",
+            amount,
+            new_balance
+        );
+        Ok(())
+    }
+
+    /// Lock `amount` ICHOR in the stake vault for `lock_period` seconds. The
+    /// lock tier sets `yield_bps`, the betting payout bonus rumble-engine
+    /// grants while the stake is locked. One stake per wallet at a time.
+    pub fn stake_ichor(ctx: Context<StakeIchor>, amount: u64, lock_period: i64) -> Result<()> {
+        require!(amount > 0, IchorError::ZeroStakeAmount);
+        require!(
+            ctx.accounts.ichor_stake.staked_amount == 0,
+            IchorError::StakeAlreadyActive
+        );
+        let yield_bps = stake_yield_bps(lock_period)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.staker_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.staker.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let staked_at = Clock::get()?.unix_timestamp;
+        let stake = &mut ctx.accounts.ichor_stake;
+        stake.staker = ctx.accounts.staker.key();
+        stake.staked_amount = amount;
+        stake.staked_at = staked_at;
+        stake.lock_period_seconds = lock_period;
+        stake.yield_bps = yield_bps;
+        stake.bump = ctx.bumps.ichor_stake;
+
+        msg!(
+            "Staked {} ICHOR for {}s ({} bps yield)",
+            amount,
+            lock_period,
+            yield_bps
+        );
+        emit!(IchorStakedEvent {
+            staker: stake.staker,
+            amount,
+            staked_at,
+            lock_period_seconds: lock_period,
+            yield_bps,
+        });
+        Ok(())
+    }
+
+    /// Return a stake's ICHOR once its lock period has elapsed. Closes the
+    /// IchorStake PDA.
+    pub fn unstake_ichor(ctx: Context<UnstakeIchor>) -> Result<()> {
+        let stake = &ctx.accounts.ichor_stake;
+        require_stake_unlocked(stake, Clock::get()?.unix_timestamp)?;
+        let amount = stake.staked_amount;
+
+        let bump = &[ctx.accounts.arena_config.bump];
+        let seeds: &[&[u8]] = &[ARENA_SEED, bump];
+        let signer_seeds = &[seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.staker_token_account.to_account_info(),
+                    authority: ctx.accounts.arena_config.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        msg!("Unstaked {} ICHOR", amount);
+        emit!(IchorUnstakedEvent {
+            staker: ctx.accounts.staker.key(),
+            amount,
+        });
+        Ok(())
+    }
+
     /// Admin: update the base reward amount (legacy).
     /// Bounded: must be >= SHOWER_POOL_CUT (to avoid C-1 at era 0) and <= 2,000 ICHOR.
     pub fn update_base_reward(ctx: Context<AdminOnly>, new_base_reward: u64) -> Result<()> {
@@ -586,8 +837,116 @@ pub mod ichor_token {
         Ok(())
     }
 
-    /// One-time migration helper for legacy ArenaConfig accounts that predate
-    /// `season_reward`. Reallocates the PDA and writes an explicit season reward.
+    /// Admin: create the SeasonConfig for `season_id`. Season ids start at 1;
+    /// every later season must start at or after the previous one's end, so
+    /// at most one season covers any rumble.
+    pub fn start_new_season(
+        ctx: Context<StartNewSeason>,
+        season_id: u64,
+        params: SeasonParams,
+    ) -> Result<()> {
+        validate_season_params(season_id, &params)?;
+        if season_id > 1 {
+            let previous = ctx
+                .accounts
+                .previous_season_config
+                .as_ref()
+                .ok_or(IchorError::InvalidSeasonParams)?;
+            require!(
+                params.start_rumble >= previous.end_rumble,
+                IchorError::InvalidSeasonParams
+            );
+        }
+
+        let season = &mut ctx.accounts.season_config;
+        season.season_id = season_id;
+        season.start_rumble = params.start_rumble;
+        season.end_rumble = params.end_rumble;
+        season.bettor_share_bps = params.bettor_share_bps;
+        season.fighter_share_bps = params.fighter_share_bps;
+        season.shower_share_bps = params.shower_share_bps;
+        season.fighter_first_share_bps = params.fighter_first_share_bps;
+        season.bump = ctx.bumps.season_config;
+
+        msg!(
+            "Season {} configured for rumbles {}..{}",
+            season_id,
+            params.start_rumble,
+            params.end_rumble
+        );
+
+        emit!(SeasonStartedEvent {
+            season_id,
+            start_rumble: params.start_rumble,
+            end_rumble: params.end_rumble,
+            bettor_share_bps: params.bettor_share_bps,
+            fighter_share_bps: params.fighter_share_bps,
+            shower_share_bps: params.shower_share_bps,
+            fighter_first_share_bps: params.fighter_first_share_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Admin: set the Ichor Shower trigger chance (1 in `new_chance`).
+    /// Bounded: must be between MIN_SHOWER_CHANCE and MAX_SHOWER_CHANCE.
+    pub fn update_shower_chance(ctx: Context<AdminOnly>, new_chance: u64) -> Result<()> {
+        require!(
+            (MIN_SHOWER_CHANCE..=MAX_SHOWER_CHANCE).contains(&new_chance),
+            IchorError::InvalidShowerChance
+        );
+        let arena = &mut ctx.accounts.arena_config;
+        arena.shower_chance = new_chance;
+        msg!("Shower chance updated to 1 in {}", new_chance);
+        Ok(())
+    }
+
+    /// Admin: set the share of a triggered shower that is burned, in bps.
+    /// Bounded: at most MAX_SHOWER_BURN_BPS (50%).
+    pub fn update_shower_burn_bps(ctx: Context<AdminOnly>, new_burn_bps: u16) -> Result<()> {
+        require!(
+            new_burn_bps <= MAX_SHOWER_BURN_BPS,
+            IchorError::InvalidShowerBurnBps
+        );
+        let arena = &mut ctx.accounts.arena_config;
+        arena.shower_burn_bps = new_burn_bps;
+        msg!("Shower burn updated to {} bps", new_burn_bps);
+        Ok(())
+    }
+
+    /// Admin: set how many slots after a settled shower a new one can be requested.
+    /// Bounded: at most MAX_SHOWER_COOLDOWN_SLOTS.
+    pub fn update_shower_cooldown(ctx: Context<AdminOnly>, new_cooldown_slots: u64) -> Result<()> {
+        require!(
+            new_cooldown_slots <= MAX_SHOWER_COOLDOWN_SLOTS,
+            IchorError::InvalidShowerCooldown
+        );
+        let arena = &mut ctx.accounts.arena_config;
+        arena.shower_cooldown_slots = new_cooldown_slots;
+        msg!("Shower cooldown updated to {} slots", new_cooldown_slots);
+        Ok(())
+    }
+
+    /// Admin: set the most shower payout one recipient can receive per day.
+    pub fn update_max_shower_per_day(ctx: Context<AdminOnly>, new_max: u64) -> Result<()> {
+        require!(new_max > 0, IchorError::InvalidShowerDailyCap);
+        let arena = &mut ctx.accounts.arena_config;
+        arena.max_shower_per_day = new_max;
+        msg!("Max shower payout per recipient updated to {} per day", new_max);
+        Ok(())
+    }
+
+    /// Admin: restrict `fund_distribution_vault` to the admin, or open it to anyone.
+    pub fn update_refill_require_admin(ctx: Context<AdminOnly>, require_admin: bool) -> Result<()> {
+        let arena = &mut ctx.accounts.arena_config;
+        arena.refill_require_admin = require_admin;
+        msg!("Distribution vault refill admin-only: {}", require_admin);
+        Ok(())
+    }
+
+    /// Migration helper for legacy ArenaConfig accounts that predate
+    /// `season_reward` or the later shower settings. Reallocates the PDA,
+    /// writes an explicit season reward and defaults the newer fields.
     pub fn migrate_arena_config_v2(
         ctx: Context<MigrateArenaConfigV2>,
         season_reward: u64,
@@ -598,7 +957,12 @@ pub mod ichor_token {
         );
 
         const ARENA_V1_LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1; // 145
-        const ARENA_V2_LEN: usize = 8 + ArenaConfig::INIT_SPACE; // 153
+        const ARENA_V2_LEN: usize = ARENA_V1_LEN + 8; // 153
+        const ARENA_V3_LEN: usize = ARENA_V2_LEN + 8; // 161
+        const ARENA_V4_LEN: usize = ARENA_V3_LEN + 2; // 163
+        const ARENA_V5_LEN: usize = ARENA_V4_LEN + 16; // 179
+        const ARENA_V6_LEN: usize = ARENA_V5_LEN + 8; // 187
+        const ARENA_LEN: usize = 8 + ArenaConfig::INIT_SPACE; // 188
 
         let arena_info = ctx.accounts.arena_config.to_account_info();
         require!(
@@ -623,9 +987,14 @@ pub mod ichor_token {
             );
         }
 
-        if arena_info.data_len() < ARENA_V2_LEN {
+        let needs_shower_chance = arena_info.data_len() < ARENA_V3_LEN;
+        let needs_shower_burn_bps = arena_info.data_len() < ARENA_V4_LEN;
+        let needs_shower_cooldown = arena_info.data_len() < ARENA_V5_LEN;
+        let needs_max_shower_per_day = arena_info.data_len() < ARENA_V6_LEN;
+        let needs_refill_require_admin = arena_info.data_len() < ARENA_LEN;
+        if arena_info.data_len() < ARENA_LEN {
             let rent = Rent::get()?;
-            let min_balance = rent.minimum_balance(ARENA_V2_LEN);
+            let min_balance = rent.minimum_balance(ARENA_LEN);
             let current = arena_info.lamports();
             if min_balance > current {
                 let topup = min_balance
@@ -642,13 +1011,33 @@ pub mod ichor_token {
                     topup,
                 )?;
             }
-            arena_info.realloc(ARENA_V2_LEN, false)?;
+            arena_info.realloc(ARENA_LEN, false)?;
         }
 
         {
             let mut data = arena_info.try_borrow_mut_data()?;
             let season_offset = ARENA_V1_LEN;
             data[season_offset..season_offset + 8].copy_from_slice(&season_reward.to_le_bytes());
+            if needs_shower_chance {
+                data[ARENA_V2_LEN..ARENA_V2_LEN + 8]
+                    .copy_from_slice(&DEFAULT_SHOWER_CHANCE.to_le_bytes());
+            }
+            if needs_shower_burn_bps {
+                data[ARENA_V3_LEN..ARENA_V3_LEN + 2]
+                    .copy_from_slice(&DEFAULT_SHOWER_BURN_BPS.to_le_bytes());
+            }
+            if needs_shower_cooldown {
+                data[ARENA_V4_LEN..ARENA_V4_LEN + 8].copy_from_slice(&0u64.to_le_bytes());
+                data[ARENA_V4_LEN + 8..ARENA_V4_LEN + 16]
+                    .copy_from_slice(&DEFAULT_SHOWER_COOLDOWN_SLOTS.to_le_bytes());
+            }
+            if needs_max_shower_per_day {
+                data[ARENA_V5_LEN..ARENA_V5_LEN + 8]
+                    .copy_from_slice(&DEFAULT_MAX_SHOWER_PER_DAY.to_le_bytes());
+            }
+            if needs_refill_require_admin {
+                data[ARENA_V6_LEN] = 0;
+            }
         }
 
         msg!(
@@ -729,9 +1118,10 @@ pub mod ichor_token {
             IchorError::InvalidNewAdmin
         );
 
+        let proposed_at = Clock::get()?.slot;
         let pending = &mut ctx.accounts.pending_admin;
         pending.proposed_admin = new_admin;
-        pending.proposed_at = Clock::get()?.slot;
+        pending.proposed_at = proposed_at;
         pending.bump = ctx.bumps.pending_admin;
 
         msg!(
@@ -739,10 +1129,16 @@ pub mod ichor_token {
             ctx.accounts.arena_config.admin,
             new_admin
         );
+        emit!(AdminTransferProposedEvent {
+            current_admin: ctx.accounts.arena_config.admin,
+            proposed_admin: new_admin,
+            proposed_at,
+        });
         Ok(())
     }
 
-    /// Accept a pending admin transfer. Must be signed by the proposed admin.
+    /// Accept a pending admin transfer. Must be signed by the proposed admin
+    /// within `PENDING_ADMIN_EXPIRY_SLOTS` of the proposal.
     pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
         let arena = &mut ctx.accounts.arena_config;
         let pending = &ctx.accounts.pending_admin;
@@ -752,11 +1148,28 @@ pub mod ichor_token {
             new_admin == pending.proposed_admin,
             IchorError::Unauthorized
         );
+        require_pending_admin_live(pending, Clock::get()?.slot)?;
 
         let old_admin = arena.admin;
         arena.admin = new_admin;
 
         msg!("Admin transferred: {} -> {}", old_admin, new_admin);
+        emit!(AdminTransferredEvent {
+            old_admin,
+            new_admin,
+        });
+        Ok(())
+    }
+
+    /// Admin: withdraw a pending admin transfer. Closes the PendingAdmin PDA.
+    pub fn revoke_pending_admin(ctx: Context<RevokePendingAdmin>) -> Result<()> {
+        let proposed_admin = ctx.accounts.pending_admin.proposed_admin;
+
+        msg!("Admin transfer to {} revoked", proposed_admin);
+        emit!(AdminTransferRevokedEvent {
+            admin: ctx.accounts.authority.key(),
+            proposed_admin,
+        });
         Ok(())
     }
 
@@ -829,6 +1242,12 @@ pub mod ichor_token {
         arena.treasury_vault = 0;
         arena.bump = bump;
         arena.season_reward = default_season_reward;
+        arena.shower_chance = DEFAULT_SHOWER_CHANCE;
+        arena.shower_burn_bps = DEFAULT_SHOWER_BURN_BPS;
+        arena.shower_last_settled_slot = 0;
+        arena.shower_cooldown_slots = DEFAULT_SHOWER_COOLDOWN_SLOTS;
+        arena.max_shower_per_day = DEFAULT_MAX_SHOWER_PER_DAY;
+        arena.refill_require_admin = false;
 
         // No minting — vault starts empty.
         // Admin will fund by transferring tokens purchased from bonding curve / DEX.
@@ -884,17 +1303,25 @@ pub mod ichor_token {
             IchorError::Unauthorized
         );
         require!(arena.ichor_shower_pool > 0, IchorError::EmptyShowerPool);
+        require!(
+            shower_cooldown_elapsed(arena, Clock::get()?.slot),
+            IchorError::ShowerCooldownActive
+        );
 
         // Capture keys before mutable borrow
         let payer_key = ctx.accounts.payer.key();
         let oracle_queue_key = ctx.accounts.oracle_queue.key();
         let arena_config_key = ctx.accounts.arena_config.key();
         let shower_request_key = ctx.accounts.shower_request.key();
+        let shower_history_key = ctx.accounts.shower_history.key();
+        let shower_cap_key = ctx.accounts.shower_cap.key();
         let ichor_mint_key = ctx.accounts.ichor_mint.key();
         let recipient_key = ctx.accounts.recipient_token_account.key();
         let shower_vault_key = ctx.accounts.shower_vault.key();
         let token_program_key = ctx.accounts.token_program.key();
 
+        ctx.accounts.shower_history.bump = ctx.bumps.shower_history;
+        ctx.accounts.shower_cap.bump = ctx.bumps.shower_cap;
         let request = &mut ctx.accounts.shower_request;
 
         // Initialize or validate shower_request PDA
@@ -964,6 +1391,16 @@ pub mod ichor_token {
                         is_signer: false,
                         is_writable: false,
                     },
+                    SerializableAccountMeta {
+                        pubkey: shower_history_key,
+                        is_signer: false,
+                        is_writable: true,
+                    },
+                    SerializableAccountMeta {
+                        pubkey: shower_cap_key,
+                        is_signer: false,
+                        is_writable: true,
+                    },
                 ]),
                 ..Default::default()
             },
@@ -1000,19 +1437,25 @@ pub mod ichor_token {
         );
 
         let rng_value = random_u64(&randomness);
-        let triggered = rng_value % SHOWER_CHANCE == 0;
+        let triggered = shower_triggered(rng_value, arena.shower_chance);
+        let slot = Clock::get()?.slot;
+        let mut paid_amount = 0;
 
         if triggered {
             let vault_balance = ctx.accounts.shower_vault.amount;
             let pool_amount = arena.ichor_shower_pool.min(vault_balance);
-
-            let recipient_amount = pool_amount
-                .checked_mul(90)
-                .ok_or(IchorError::MathOverflow)?
-                .checked_div(100)
-                .ok_or(IchorError::MathOverflow)?;
-            let burn_amount = pool_amount
-                .checked_sub(recipient_amount)
+            paid_amount = pool_amount;
+
+            let (recipient_amount, burn_amount) =
+                split_shower_payout(pool_amount, arena.shower_burn_bps)?;
+            let (recipient_amount, over_cap) = cap_shower_payout(
+                &mut ctx.accounts.shower_cap,
+                Clock::get()?.unix_timestamp,
+                arena.max_shower_per_day,
+                recipient_amount,
+            )?;
+            let burn_amount = burn_amount
+                .checked_add(over_cap)
                 .ok_or(IchorError::MathOverflow)?;
 
             let arena_info = arena.to_account_info();
@@ -1053,28 +1496,84 @@ pub mod ichor_token {
             arena.ichor_shower_pool = 0;
 
             emit!(IchorShowerEvent {
-                slot: Clock::get()?.slot,
+                slot,
                 amount: pool_amount,
                 recipient: request.recipient_token_account,
             });
         }
 
-        // Reset request
-        request.active = false;
-        request.recipient_token_account = Pubkey::default();
-        request.requested_slot = 0;
-        request.target_slot_a = 0;
-        request.target_slot_b = 0;
+        record_shower_outcome(
+            &mut ctx.accounts.shower_history,
+            ShowerEntry {
+                slot,
+                triggered,
+                recipient: request.recipient_token_account,
+                amount: paid_amount,
+                rng_value,
+            },
+        );
+        settle_shower_request(arena, request, slot);
 
         Ok(())
     }
-}
 
-// ---------------------------------------------------------------------------
-// Helpers
-// ---------------------------------------------------------------------------
+    /// Admin: cancel a VRF shower request the oracle never answered, once
+    /// SHOWER_VRF_EXPIRY_SLOTS have passed since it was made. The shower pool
+    /// is left untouched, so the next request pays out the same pool.
+    pub fn cancel_vrf_shower_request(ctx: Context<CancelVrfShowerRequest>) -> Result<()> {
+        let request = &mut ctx.accounts.shower_request;
+        let slot = Clock::get()?.slot;
+
+        require!(request.active, IchorError::NoActiveShowerRequest);
+        require!(
+            vrf_shower_request_expired(request, slot),
+            IchorError::ShowerRequestNotExpired
+        );
+
+        let nonce = request.request_nonce;
+        reset_shower_request(request);
+
+        msg!("VRF shower request cancelled. nonce={}, slot={}", nonce, slot);
+        emit!(ShowerVrfRequestCancelledEvent {
+            nonce,
+            cancel_slot: slot,
+        });
+
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Calculate the reward for a rumble.
+/// Whether a shower roll hits: 1 in `shower_chance`, or 1 in
+/// DEFAULT_SHOWER_CHANCE if the field was never set.
+fn shower_triggered(rng_value: u64, shower_chance: u64) -> bool {
+    let chance = if shower_chance > 0 {
+        shower_chance
+    } else {
+        DEFAULT_SHOWER_CHANCE
+    };
+    rng_value % chance == 0
+}
+
+/// Split a triggered shower into (recipient, burned): `burn_bps` of the pool
+/// is burned and the recipient takes the rest.
+fn split_shower_payout(pool_amount: u64, burn_bps: u16) -> Result<(u64, u64)> {
+    let burn_bps = burn_bps.min(MAX_SHOWER_BURN_BPS) as u128;
+    let recipient_amount = (pool_amount as u128)
+        .checked_mul(10_000 - burn_bps)
+        .ok_or(IchorError::MathOverflow)?
+        / 10_000;
+    let recipient_amount = recipient_amount as u64;
+    let burn_amount = pool_amount
+        .checked_sub(recipient_amount)
+        .ok_or(IchorError::MathOverflow)?;
+    Ok((recipient_amount, burn_amount))
+}
 
-/// Calculate the reward for a rumble.
 /// Season-based: returns the configured season_reward (flat, no halving).
 /// Falls back to base_reward if season_reward is 0 (for backwards compatibility
 /// with existing on-chain state that predates the season_reward field).
@@ -1085,6 +1584,228 @@ pub mod ichor_token {
 ///   rumbles < 12,600,000 → base_reward / 4
 ///   rumbles < 21,000,000 → base_reward / 8
 ///   rumbles >= 21,000,000 → base_reward / 16
+/// Core on-chain emission for one completed rumble: (1st fighter share,
+/// shower pool addition). The rest of the seasonal reward goes out through
+/// `admin_distribute`.
+fn core_reward_emission(
+    base_reward: u64,
+    rumbles_completed: u64,
+    season_reward: u64,
+    shares: &SeasonShares,
+) -> Result<(u64, u64)> {
+    // Season-based flat reward, no halving
+    let reward = calculate_reward(base_reward, rumbles_completed, season_reward);
+
+    let _bettor_pool = reward
+        .checked_mul(shares.bettor_bps)
+        .ok_or(IchorError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(IchorError::MathOverflow)?;
+
+    let fighter_pool = reward
+        .checked_mul(shares.fighter_bps)
+        .ok_or(IchorError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(IchorError::MathOverflow)?;
+
+    let winner_amount = fighter_pool
+        .checked_mul(shares.fighter_first_bps)
+        .ok_or(IchorError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(IchorError::MathOverflow)?;
+
+    let shower_from_reward = reward
+        .checked_mul(shares.shower_bps)
+        .ok_or(IchorError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(IchorError::MathOverflow)?;
+
+    let shower_addition = shower_from_reward
+        .checked_add(SHOWER_BONUS_EMISSION)
+        .ok_or(IchorError::MathOverflow)?;
+
+    Ok((winner_amount, shower_addition))
+}
+
+/// Reward split for one rumble, in bps. `fighter_first_bps` is a share of
+/// the fighter pool; the others are shares of the whole reward.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SeasonShares {
+    bettor_bps: u64,
+    fighter_bps: u64,
+    shower_bps: u64,
+    fighter_first_bps: u64,
+}
+
+const DEFAULT_SEASON_SHARES: SeasonShares = SeasonShares {
+    bettor_bps: BETTOR_SHARE_BPS,
+    fighter_bps: FIGHTER_SHARE_BPS,
+    shower_bps: SHOWER_SHARE_BPS,
+    fighter_first_bps: FIGHTER_FIRST_SHARE_BPS,
+};
+
+impl From<&SeasonConfig> for SeasonShares {
+    fn from(season: &SeasonConfig) -> Self {
+        SeasonShares {
+            bettor_bps: season.bettor_share_bps as u64,
+            fighter_bps: season.fighter_share_bps as u64,
+            shower_bps: season.shower_share_bps as u64,
+            fighter_first_bps: season.fighter_first_share_bps as u64,
+        }
+    }
+}
+
+/// Split for the `rumbles_completed`-th rumble. `current` must have started;
+/// once it has ended, `next` (season id + 1) must cover the rumble, and with
+/// no `next` the default split applies.
+fn season_shares(
+    rumbles_completed: u64,
+    current: &SeasonConfig,
+    next: Option<&SeasonConfig>,
+) -> Result<SeasonShares> {
+    require!(
+        rumbles_completed >= current.start_rumble,
+        IchorError::SeasonConfigMismatch
+    );
+    if rumbles_completed < current.end_rumble {
+        return Ok(current.into());
+    }
+    let Some(next) = next else {
+        return Ok(DEFAULT_SEASON_SHARES);
+    };
+    require!(
+        Some(next.season_id) == current.season_id.checked_add(1)
+            && rumbles_completed >= next.start_rumble
+            && rumbles_completed < next.end_rumble,
+        IchorError::SeasonConfigMismatch
+    );
+    Ok(next.into())
+}
+
+/// Season ids start at 1, the season must span at least one rumble, the
+/// three reward shares must add up to the whole reward and the 1st fighter's
+/// cut can't exceed the fighter pool.
+fn validate_season_params(season_id: u64, params: &SeasonParams) -> Result<()> {
+    let total_bps = params.bettor_share_bps as u32
+        + params.fighter_share_bps as u32
+        + params.shower_share_bps as u32;
+    require!(
+        season_id >= 1
+            && params.end_rumble > params.start_rumble
+            && total_bps == 10_000
+            && params.fighter_first_share_bps <= 10_000,
+        IchorError::InvalidSeasonParams
+    );
+    Ok(())
+}
+
+/// A batch needs 1..=`MAX_REWARD_BATCH` distinct rumble ids, each with a
+/// winner token account.
+fn validate_reward_batch(rumble_ids: &[u64], winner_token_accounts: &[Pubkey]) -> Result<()> {
+    require!(
+        !rumble_ids.is_empty()
+            && rumble_ids.len() <= MAX_REWARD_BATCH
+            && rumble_ids.len() == winner_token_accounts.len(),
+        IchorError::InvalidRewardBatch
+    );
+    for (i, rumble_id) in rumble_ids.iter().enumerate() {
+        require!(
+            !rumble_ids[..i].contains(rumble_id),
+            IchorError::InvalidRewardBatch
+        );
+    }
+    Ok(())
+}
+
+/// Pay one rumble's core emission out of the distribution vault and record
+/// it on the arena. The caller has already checked the vault balance.
+#[allow(clippy::too_many_arguments)]
+fn pay_core_reward<'info>(
+    arena: &mut ArenaConfig,
+    arena_info: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    distribution_vault: AccountInfo<'info>,
+    winner_token_account: AccountInfo<'info>,
+    shower_vault: AccountInfo<'info>,
+    rumble_id: Option<u64>,
+    winner_amount: u64,
+    shower_addition: u64,
+) -> Result<()> {
+    let total_emission = winner_amount
+        .checked_add(shower_addition)
+        .ok_or(IchorError::MathOverflow)?;
+
+    // Build PDA signer seeds
+    let bump = &[arena.bump];
+    let seeds: &[&[u8]] = &[ARENA_SEED, bump];
+    let signer_seeds = &[seeds];
+
+    // Transfer winner's share from vault to their token account
+    if winner_amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: distribution_vault.clone(),
+                    to: winner_token_account.clone(),
+                    authority: arena_info.clone(),
+                },
+                signer_seeds,
+            ),
+            winner_amount,
+        )?;
+    }
+
+    // Transfer shower pool portion from vault to the shower vault
+    if shower_addition > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program,
+                Transfer {
+                    from: distribution_vault,
+                    to: shower_vault,
+                    authority: arena_info,
+                },
+                signer_seeds,
+            ),
+            shower_addition,
+        )?;
+    }
+
+    // Update state
+    arena.total_distributed = arena
+        .total_distributed
+        .checked_add(total_emission)
+        .ok_or(IchorError::MathOverflow)?;
+    arena.total_rumbles_completed = arena
+        .total_rumbles_completed
+        .checked_add(1)
+        .ok_or(IchorError::MathOverflow)?;
+    arena.ichor_shower_pool = arena
+        .ichor_shower_pool
+        .checked_add(shower_addition)
+        .ok_or(IchorError::MathOverflow)?;
+
+    msg!(
+        "Rumble #{} on-chain core emission: {} to 1st fighter, {} to shower pool. Total distributed: {}",
+        arena.total_rumbles_completed,
+        winner_amount,
+        shower_addition,
+        arena.total_distributed
+    );
+
+    emit!(RewardDistributedEvent {
+        rumble_id,
+        rumbles_completed: arena.total_rumbles_completed,
+        winner_token_account: winner_token_account.key(),
+        winner_amount,
+        shower_amount: shower_addition,
+        total_distributed: arena.total_distributed,
+    });
+
+    Ok(())
+}
+
 fn calculate_reward(base_reward: u64, _rumbles_completed: u64, season_reward: u64) -> u64 {
     if season_reward > 0 {
         season_reward
@@ -1249,6 +1970,98 @@ fn reset_shower_request(request: &mut ShowerRequest) {
     request.target_slot_b = 0;
 }
 
+/// Whether a pending VRF shower request has gone unanswered for longer than
+/// SHOWER_VRF_EXPIRY_SLOTS.
+fn vrf_shower_request_expired(request: &ShowerRequest, slot: u64) -> bool {
+    slot > request
+        .requested_slot
+        .saturating_add(SHOWER_VRF_EXPIRY_SLOTS)
+}
+
+/// Append a settlement to the shower history ring buffer.
+fn record_shower_outcome(history: &mut ShowerHistory, entry: ShowerEntry) {
+    let head = history.head as usize % SHOWER_HISTORY_LEN;
+    history.entries[head] = entry;
+    history.head = ((head + 1) % SHOWER_HISTORY_LEN) as u8;
+}
+
+/// Close out a settled request (triggered or not) and start the cooldown.
+fn settle_shower_request(arena: &mut ArenaConfig, request: &mut ShowerRequest, slot: u64) {
+    reset_shower_request(request);
+    arena.shower_last_settled_slot = slot;
+}
+
+/// Clamp a recipient's shower share to what is left of their daily allowance,
+/// starting a new window once SHOWER_CAP_WINDOW_SECONDS have passed. Records
+/// the payout and returns (paid to the recipient, excess to burn).
+fn cap_shower_payout(
+    cap: &mut ShowerCap,
+    now: i64,
+    max_per_day: u64,
+    recipient_amount: u64,
+) -> Result<(u64, u64)> {
+    if now.saturating_sub(cap.last_reset_ts) >= SHOWER_CAP_WINDOW_SECONDS {
+        cap.total_received_today = 0;
+        cap.last_reset_ts = now;
+    }
+    let allowance = max_per_day.saturating_sub(cap.total_received_today);
+    let paid = recipient_amount.min(allowance);
+    cap.total_received_today = cap
+        .total_received_today
+        .checked_add(paid)
+        .ok_or(IchorError::MathOverflow)?;
+    Ok((paid, recipient_amount - paid))
+}
+
+/// With `refill_require_admin` set, only the admin may fund the distribution vault.
+fn require_refill_authorized(arena: &ArenaConfig, funder: &Pubkey) -> Result<()> {
+    require!(
+        !arena.refill_require_admin || *funder == arena.admin,
+        IchorError::Unauthorized
+    );
+    Ok(())
+}
+
+/// Whether `shower_cooldown_slots` have passed since the last settled shower.
+fn shower_cooldown_elapsed(arena: &ArenaConfig, slot: u64) -> bool {
+    slot >= arena
+        .shower_last_settled_slot
+        .saturating_add(arena.shower_cooldown_slots)
+}
+
+/// Reject an admin proposal older than `PENDING_ADMIN_EXPIRY_SLOTS`. A lapsed
+/// proposal can only be replaced (`transfer_admin`) or revoked.
+fn require_pending_admin_live(pending: &PendingAdmin, slot: u64) -> Result<()> {
+    require!(
+        slot.saturating_sub(pending.proposed_at) <= PENDING_ADMIN_EXPIRY_SLOTS,
+        IchorError::PendingAdminExpired
+    );
+    Ok(())
+}
+
+/// Yield tier for a stake lock period; rejects periods outside
+/// `MIN_STAKE_LOCK_SECONDS..=MAX_STAKE_LOCK_SECONDS`.
+fn stake_yield_bps(lock_period: i64) -> Result<u16> {
+    require!(
+        (MIN_STAKE_LOCK_SECONDS..=MAX_STAKE_LOCK_SECONDS).contains(&lock_period),
+        IchorError::InvalidLockPeriod
+    );
+    STAKE_YIELD_TIERS
+        .iter()
+        .find(|(min_lock, _)| lock_period >= *min_lock)
+        .map(|(_, bps)| *bps)
+        .ok_or_else(|| error!(IchorError::InvalidLockPeriod))
+}
+
+fn require_stake_unlocked(stake: &IchorStake, now: i64) -> Result<()> {
+    let unlock_at = stake
+        .staked_at
+        .checked_add(stake.lock_period_seconds)
+        .ok_or(IchorError::MathOverflow)?;
+    require!(now >= unlock_at, IchorError::StakeLocked);
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Accounts
 // ---------------------------------------------------------------------------
@@ -1372,6 +2185,56 @@ pub struct DistributeReward<'info> {
     pub shower_vault: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
+
+    /// Season config covering the current rumble.
+    pub season_config: Account<'info, SeasonConfig>,
+
+    /// The following season's config, read once `season_config` has ended.
+    pub next_season_config: Option<Account<'info, SeasonConfig>>,
+}
+
+/// Same accounts as `DistributeReward`, minus the winner token account:
+/// those come in `remaining_accounts`, one per rumble.
+#[derive(Accounts)]
+pub struct BatchDistributeReward<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == arena_config.admin @ IchorError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        mut,
+        address = arena_config.distribution_vault @ IchorError::InvalidVault,
+        token::mint = ichor_mint,
+        token::authority = arena_config,
+    )]
+    pub distribution_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        address = arena_config.ichor_mint @ IchorError::InvalidMint,
+    )]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = arena_config,
+    )]
+    pub shower_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub season_config: Account<'info, SeasonConfig>,
+
+    pub next_season_config: Option<Account<'info, SeasonConfig>>,
 }
 
 #[derive(Accounts)]
@@ -1396,6 +2259,16 @@ pub struct CheckIchorShower<'info> {
     )]
     pub shower_request: Account<'info, ShowerRequest>,
 
+    /// Last settled showers; created by the first call that needs it.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ShowerHistory::INIT_SPACE,
+        seeds = [SHOWER_HISTORY_SEED],
+        bump
+    )]
+    pub shower_history: Box<Account<'info, ShowerHistory>>,
+
     #[account(
         mut,
         address = arena_config.ichor_mint @ IchorError::InvalidMint,
@@ -1409,6 +2282,16 @@ pub struct CheckIchorShower<'info> {
     )]
     pub recipient_token_account: Account<'info, TokenAccount>,
 
+    /// The recipient's daily payout tally; created by the first call that needs it.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ShowerCap::INIT_SPACE,
+        seeds = [SHOWER_CAP_SEED, recipient_token_account.key().as_ref()],
+        bump
+    )]
+    pub shower_cap: Box<Account<'info, ShowerCap>>,
+
     /// Shower vault (holds pool tokens). Authority must be the arena_config PDA.
     #[account(
         mut,
@@ -1461,6 +2344,164 @@ pub struct BurnIchor<'info> {
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct TopUpShowerPool<'info> {
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        address = arena_config.ichor_mint @ IchorError::InvalidMint,
+    )]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = contributor,
+    )]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    /// Shower vault token account (holds the shower pool).
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = arena_config,
+    )]
+    pub shower_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundDistributionVault<'info> {
+    pub funder: Signer<'info>,
+
+    #[account(
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        address = arena_config.ichor_mint @ IchorError::InvalidMint,
+    )]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = funder,
+    )]
+    pub source_token_account: Account<'info, TokenAccount>,
+
+    /// Distribution vault (holds undistributed supply).
+    #[account(
+        mut,
+        address = arena_config.distribution_vault @ IchorError::InvalidVault,
+        token::mint = ichor_mint,
+        token::authority = arena_config,
+    )]
+    pub distribution_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct StakeIchor<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        address = arena_config.ichor_mint @ IchorError::InvalidMint,
+    )]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = staker,
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + IchorStake::INIT_SPACE,
+        seeds = [STAKE_SEED, staker.key().as_ref()],
+        bump
+    )]
+    pub ichor_stake: Account<'info, IchorStake>,
+
+    /// Stake vault: holds all locked ICHOR. Created by the first staker.
+    #[account(
+        init_if_needed,
+        payer = staker,
+        token::mint = ichor_mint,
+        token::authority = arena_config,
+        seeds = [STAKE_VAULT_SEED],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeIchor<'info> {
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    #[account(
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        address = arena_config.ichor_mint @ IchorError::InvalidMint,
+    )]
+    pub ichor_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = ichor_mint,
+        token::authority = staker,
+    )]
+    pub staker_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = staker,
+        seeds = [STAKE_SEED, staker.key().as_ref()],
+        bump = ichor_stake.bump,
+    )]
+    pub ichor_stake: Account<'info, IchorStake>,
+
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED],
+        bump,
+        token::mint = ichor_mint,
+        token::authority = arena_config,
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct AdminOnly<'info> {
     #[account(
@@ -1477,6 +2518,40 @@ pub struct AdminOnly<'info> {
     pub arena_config: Account<'info, ArenaConfig>,
 }
 
+#[derive(Accounts)]
+#[instruction(season_id: u64)]
+pub struct StartNewSeason<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == arena_config.admin @ IchorError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SeasonConfig::INIT_SPACE,
+        seeds = [SEASON_CONFIG_SEED, season_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub season_config: Account<'info, SeasonConfig>,
+
+    /// Config of season `season_id - 1`; required unless this is season 1.
+    #[account(
+        seeds = [SEASON_CONFIG_SEED, season_id.saturating_sub(1).to_le_bytes().as_ref()],
+        bump = previous_season_config.bump,
+    )]
+    pub previous_season_config: Option<Account<'info, SeasonConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct MigrateArenaConfigV2<'info> {
     #[account(mut)]
@@ -1568,6 +2643,29 @@ pub struct AcceptAdmin<'info> {
     pub pending_admin: Account<'info, PendingAdmin>,
 }
 
+#[derive(Accounts)]
+pub struct RevokePendingAdmin<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == arena_config.admin @ IchorError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PENDING_ADMIN_SEED],
+        bump = pending_admin.bump,
+    )]
+    pub pending_admin: Account<'info, PendingAdmin>,
+}
+
 #[derive(Accounts)]
 pub struct AdminDistribute<'info> {
     #[account(
@@ -1644,12 +2742,32 @@ pub struct RequestIchorShowerVrf<'info> {
     )]
     pub shower_request: Account<'info, ShowerRequest>,
 
+    /// Created here so the oracle callback can record the outcome.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ShowerHistory::INIT_SPACE,
+        seeds = [SHOWER_HISTORY_SEED],
+        bump
+    )]
+    pub shower_history: Box<Account<'info, ShowerHistory>>,
+
     #[account(address = arena_config.ichor_mint @ IchorError::InvalidMint)]
     pub ichor_mint: Account<'info, Mint>,
 
     #[account(mut, token::mint = ichor_mint)]
     pub recipient_token_account: Account<'info, TokenAccount>,
 
+    /// Created here so the oracle callback can apply the recipient's daily cap.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ShowerCap::INIT_SPACE,
+        seeds = [SHOWER_CAP_SEED, recipient_token_account.key().as_ref()],
+        bump
+    )]
+    pub shower_cap: Box<Account<'info, ShowerCap>>,
+
     #[account(mut, token::mint = ichor_mint, token::authority = arena_config)]
     pub shower_vault: Account<'info, TokenAccount>,
 
@@ -1691,6 +2809,43 @@ pub struct CallbackIchorShowerVrf<'info> {
     pub shower_vault: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
+
+    /// Last in the callback account list, matching `request_ichor_shower_vrf`.
+    #[account(
+        mut,
+        seeds = [SHOWER_HISTORY_SEED],
+        bump = shower_history.bump,
+    )]
+    pub shower_history: Box<Account<'info, ShowerHistory>>,
+
+    #[account(
+        mut,
+        seeds = [SHOWER_CAP_SEED, recipient_token_account.key().as_ref()],
+        bump = shower_cap.bump,
+    )]
+    pub shower_cap: Box<Account<'info, ShowerCap>>,
+}
+
+/// Accounts for cancelling an expired VRF shower request (admin only).
+#[derive(Accounts)]
+pub struct CancelVrfShowerRequest<'info> {
+    #[account(
+        constraint = authority.key() == arena_config.admin @ IchorError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [ARENA_SEED],
+        bump = arena_config.bump,
+    )]
+    pub arena_config: Account<'info, ArenaConfig>,
+
+    #[account(
+        mut,
+        seeds = [SHOWER_REQUEST_SEED],
+        bump = shower_request.bump,
+    )]
+    pub shower_request: Account<'info, ShowerRequest>,
 }
 
 // ---------------------------------------------------------------------------
@@ -1710,6 +2865,12 @@ pub struct ArenaConfig {
     pub treasury_vault: u64,          // 8
     pub bump: u8,                     // 1
     pub season_reward: u64,           // 8   season-based flat reward per rumble
+    pub shower_chance: u64,           // 8   shower triggers 1 in `shower_chance`
+    pub shower_burn_bps: u16,         // 2   share of a triggered shower burned
+    pub shower_last_settled_slot: u64, // 8   slot the last shower request settled
+    pub shower_cooldown_slots: u64,   // 8   slots before the next request may open
+    pub max_shower_per_day: u64,      // 8   per-recipient shower payout cap per day
+    pub refill_require_admin: bool,   // 1   fund_distribution_vault is admin-only
 }
 
 #[account]
@@ -1737,6 +2898,36 @@ pub struct ShowerRequest {
     pub recipient_token_account: Pubkey, // 32
 }
 
+/// One settled shower, triggered or not.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct ShowerEntry {
+    pub slot: u64,          // 8
+    pub triggered: bool,    // 1
+    pub recipient: Pubkey,  // 32  recipient token account
+    pub amount: u64,        // 8   pool paid out (incl. burn); 0 if not triggered
+    pub rng_value: u64,     // 8
+}
+
+/// Ring buffer of the last SHOWER_HISTORY_LEN settlements; `head` is the slot
+/// the next one is written to (so the newest entry sits just before it).
+#[account]
+#[derive(InitSpace)]
+pub struct ShowerHistory {
+    pub head: u8,                                    // 1
+    pub bump: u8,                                    // 1
+    pub entries: [ShowerEntry; SHOWER_HISTORY_LEN],  // 57 * 16
+}
+
+/// Shower payouts one recipient token account has received in the current
+/// SHOWER_CAP_WINDOW_SECONDS window.
+#[account]
+#[derive(InitSpace)]
+pub struct ShowerCap {
+    pub total_received_today: u64, // 8
+    pub last_reset_ts: i64,        // 8
+    pub bump: u8,                  // 1
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct PendingAdmin {
@@ -1745,6 +2936,45 @@ pub struct PendingAdmin {
     pub bump: u8,               // 1
 }
 
+/// A wallet's locked ICHOR, at [STAKE_SEED, staker]. Read by rumble-engine's
+/// `claim_payout` to boost the staker's winnings.
+#[account]
+#[derive(InitSpace)]
+pub struct IchorStake {
+    pub staker: Pubkey,           // 32
+    pub staked_amount: u64,       // 8
+    pub staked_at: i64,           // 8
+    pub lock_period_seconds: i64, // 8
+    pub yield_bps: u16,           // 2
+    pub bump: u8,                 // 1
+}
+
+/// Reward split for rumbles `start_rumble..end_rumble` (by
+/// `total_rumbles_completed`), at [SEASON_CONFIG_SEED, season_id].
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonConfig {
+    pub season_id: u64,               // 8
+    pub start_rumble: u64,            // 8
+    pub end_rumble: u64,              // 8   exclusive
+    pub bettor_share_bps: u16,        // 2
+    pub fighter_share_bps: u16,       // 2
+    pub shower_share_bps: u16,        // 2
+    pub fighter_first_share_bps: u16, // 2   share of the fighter pool
+    pub bump: u8,                     // 1
+}
+
+/// `start_new_season` arguments.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SeasonParams {
+    pub start_rumble: u64,
+    pub end_rumble: u64,
+    pub bettor_share_bps: u16,
+    pub fighter_share_bps: u16,
+    pub shower_share_bps: u16,
+    pub fighter_first_share_bps: u16,
+}
+
 // ---------------------------------------------------------------------------
 // Events
 // ---------------------------------------------------------------------------
@@ -1781,6 +3011,82 @@ pub struct IchorShowerVrfRequestedEvent {
     pub requested_slot: u64,
 }
 
+#[event]
+pub struct ShowerVrfRequestCancelledEvent {
+    pub nonce: u64,
+    pub cancel_slot: u64,
+}
+
+#[event]
+pub struct AdminTransferProposedEvent {
+    pub current_admin: Pubkey,
+    pub proposed_admin: Pubkey,
+    pub proposed_at: u64,
+}
+
+#[event]
+pub struct AdminTransferRevokedEvent {
+    pub admin: Pubkey,
+    pub proposed_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferredEvent {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct IchorStakedEvent {
+    pub staker: Pubkey,
+    pub amount: u64,
+    pub staked_at: i64,
+    pub lock_period_seconds: i64,
+    pub yield_bps: u16,
+}
+
+#[event]
+pub struct IchorUnstakedEvent {
+    pub staker: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ShowerPoolToppedUpEvent {
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub new_pool: u64,
+}
+
+#[event]
+pub struct RewardDistributedEvent {
+    /// Set by `batch_distribute_reward`; `distribute_reward` isn't told the id.
+    pub rumble_id: Option<u64>,
+    pub rumbles_completed: u64,
+    pub winner_token_account: Pubkey,
+    pub winner_amount: u64,
+    pub shower_amount: u64,
+    pub total_distributed: u64,
+}
+
+#[event]
+pub struct SeasonStartedEvent {
+    pub season_id: u64,
+    pub start_rumble: u64,
+    pub end_rumble: u64,
+    pub bettor_share_bps: u16,
+    pub fighter_share_bps: u16,
+    pub shower_share_bps: u16,
+    pub fighter_first_share_bps: u16,
+}
+
+#[event]
+pub struct VaultRefillEvent {
+    pub amount: u64,
+    pub funder: Pubkey,
+    pub new_balance: u64,
+}
+
 // ---------------------------------------------------------------------------
 // Errors
 // ---------------------------------------------------------------------------
@@ -1861,6 +3167,54 @@ pub enum IchorError {
 
     #[msg("No active shower request to settle")]
     NoActiveShowerRequest,
+
+    #[msg("Pending admin transfer has expired")]
+    PendingAdminExpired,
+
+    #[msg("Stake amount must be greater than zero")]
+    ZeroStakeAmount,
+
+    #[msg("Invalid lock period: must be between 7 and 365 days")]
+    InvalidLockPeriod,
+
+    #[msg("Wallet already has an active stake")]
+    StakeAlreadyActive,
+
+    #[msg("Stake is still locked")]
+    StakeLocked,
+
+    #[msg("Top-up amount must be greater than zero")]
+    ZeroTopUpAmount,
+
+    #[msg("Invalid shower chance: must be between 1 in 50 and 1 in 5,000")]
+    InvalidShowerChance,
+
+    #[msg("Invalid shower burn: must be at most 5,000 bps")]
+    InvalidShowerBurnBps,
+
+    #[msg("Ichor Shower is cooling down after the last settlement")]
+    ShowerCooldownActive,
+
+    #[msg("Invalid shower cooldown: must be at most 216,000 slots")]
+    InvalidShowerCooldown,
+
+    #[msg("Shower request has not expired yet")]
+    ShowerRequestNotExpired,
+
+    #[msg("Max shower payout per day must be greater than zero")]
+    InvalidShowerDailyCap,
+
+    #[msg("Reward batch must list 1 to 8 distinct rumbles, each with a winner token account")]
+    InvalidRewardBatch,
+
+    #[msg("Winner token account does not match the reward batch")]
+    InvalidWinnerTokenAccount,
+
+    #[msg("Invalid season: shares must sum to 10,000 bps and it must start after the previous season ends")]
+    InvalidSeasonParams,
+
+    #[msg("Season config does not cover the current rumble")]
+    SeasonConfigMismatch,
 }
 
 #[cfg(test)]
@@ -1966,6 +3320,183 @@ mod tests {
         assert_eq!(reward, ONE_ICHOR);
     }
 
+    #[test]
+    fn shower_triggers_on_configured_chance_with_default_fallback() {
+        assert!(shower_triggered(0, MIN_SHOWER_CHANCE));
+        assert!(shower_triggered(MIN_SHOWER_CHANCE * 3, MIN_SHOWER_CHANCE));
+        assert!(!shower_triggered(MIN_SHOWER_CHANCE * 3, DEFAULT_SHOWER_CHANCE));
+
+        // Unset chance behaves like the old 1-in-500 constant.
+        assert!(shower_triggered(DEFAULT_SHOWER_CHANCE * 2, 0));
+        assert!(!shower_triggered(MIN_SHOWER_CHANCE, 0));
+    }
+
+    #[test]
+    fn shower_split_follows_burn_bps() {
+        // The default matches the old hardcoded 90/10 split.
+        assert_eq!(
+            split_shower_payout(1_000 * ONE_ICHOR, DEFAULT_SHOWER_BURN_BPS).unwrap(),
+            (900 * ONE_ICHOR, 100 * ONE_ICHOR)
+        );
+        assert_eq!(split_shower_payout(999, 0).unwrap(), (999, 0));
+        assert_eq!(split_shower_payout(1_000, MAX_SHOWER_BURN_BPS).unwrap(), (500, 500));
+        assert_eq!(split_shower_payout(u64::MAX, 2_500).unwrap().1, u64::MAX / 4 + 1);
+    }
+
+    #[test]
+    fn shower_cooldown_starts_at_settlement() {
+        let mut arena = ArenaConfig {
+            admin: Pubkey::new_unique(),
+            ichor_mint: Pubkey::new_unique(),
+            distribution_vault: Pubkey::new_unique(),
+            total_distributed: 0,
+            total_rumbles_completed: 0,
+            base_reward: ONE_ICHOR,
+            ichor_shower_pool: 0,
+            treasury_vault: 0,
+            bump: 255,
+            season_reward: 2_500 * ONE_ICHOR,
+            shower_chance: DEFAULT_SHOWER_CHANCE,
+            shower_burn_bps: DEFAULT_SHOWER_BURN_BPS,
+            shower_last_settled_slot: 0,
+            shower_cooldown_slots: DEFAULT_SHOWER_COOLDOWN_SLOTS,
+            max_shower_per_day: DEFAULT_MAX_SHOWER_PER_DAY,
+            refill_require_admin: false,
+        };
+        let mut request = ShowerRequest {
+            initialized: true,
+            active: true,
+            bump: 254,
+            request_nonce: 3,
+            requested_slot: 4_000,
+            target_slot_a: 4_002,
+            target_slot_b: 4_004,
+            recipient_token_account: Pubkey::new_unique(),
+        };
+        // Nothing settled yet: only the first DEFAULT_SHOWER_COOLDOWN_SLOTS are blocked.
+        assert!(shower_cooldown_elapsed(&arena, DEFAULT_SHOWER_COOLDOWN_SLOTS));
+
+        settle_shower_request(&mut arena, &mut request, 5_000);
+        assert!(!request.active);
+        assert_eq!(arena.shower_last_settled_slot, 5_000);
+        assert!(!shower_cooldown_elapsed(&arena, 5_999));
+        assert!(shower_cooldown_elapsed(&arena, 6_000));
+
+        arena.shower_cooldown_slots = 0;
+        assert!(shower_cooldown_elapsed(&arena, 5_000));
+        arena.shower_cooldown_slots = u64::MAX;
+        assert!(!shower_cooldown_elapsed(&arena, u64::MAX - 1));
+    }
+
+    #[test]
+    fn distribution_vault_refill_can_be_made_admin_only() {
+        let mut arena = ArenaConfig {
+            admin: Pubkey::new_unique(),
+            ichor_mint: Pubkey::new_unique(),
+            distribution_vault: Pubkey::new_unique(),
+            total_distributed: 0,
+            total_rumbles_completed: 0,
+            base_reward: ONE_ICHOR,
+            ichor_shower_pool: 0,
+            treasury_vault: 0,
+            bump: 255,
+            season_reward: 2_500 * ONE_ICHOR,
+            shower_chance: DEFAULT_SHOWER_CHANCE,
+            shower_burn_bps: DEFAULT_SHOWER_BURN_BPS,
+            shower_last_settled_slot: 0,
+            shower_cooldown_slots: DEFAULT_SHOWER_COOLDOWN_SLOTS,
+            max_shower_per_day: DEFAULT_MAX_SHOWER_PER_DAY,
+            refill_require_admin: false,
+        };
+        let stranger = Pubkey::new_unique();
+        assert!(require_refill_authorized(&arena, &stranger).is_ok());
+
+        arena.refill_require_admin = true;
+        assert_eq!(
+            require_refill_authorized(&arena, &stranger).err(),
+            Some(error!(IchorError::Unauthorized))
+        );
+        assert!(require_refill_authorized(&arena, &arena.admin).is_ok());
+    }
+
+    #[test]
+    fn shower_cap_limits_a_recipient_per_day() {
+        let mut cap = ShowerCap {
+            total_received_today: 0,
+            last_reset_ts: 0,
+            bump: 255,
+        };
+        let day = SHOWER_CAP_WINDOW_SECONDS;
+        let max = DEFAULT_MAX_SHOWER_PER_DAY;
+
+        // A fresh cap opens its window at the first payout.
+        assert_eq!(
+            cap_shower_payout(&mut cap, 1_000_000, max, 60_000 * ONE_ICHOR).unwrap(),
+            (60_000 * ONE_ICHOR, 0)
+        );
+        assert_eq!(cap.last_reset_ts, 1_000_000);
+        // The second shower the same day is clamped; the excess is burned.
+        assert_eq!(
+            cap_shower_payout(&mut cap, 1_000_000 + day - 1, max, 70_000 * ONE_ICHOR).unwrap(),
+            (40_000 * ONE_ICHOR, 30_000 * ONE_ICHOR)
+        );
+        assert_eq!(
+            cap_shower_payout(&mut cap, 1_000_000 + day - 1, max, ONE_ICHOR).unwrap(),
+            (0, ONE_ICHOR)
+        );
+        // A day after the window opened, the allowance is back in full.
+        assert_eq!(
+            cap_shower_payout(&mut cap, 1_000_000 + day, max, 70_000 * ONE_ICHOR).unwrap(),
+            (70_000 * ONE_ICHOR, 0)
+        );
+        assert_eq!(cap.total_received_today, 70_000 * ONE_ICHOR);
+    }
+
+    #[test]
+    fn vrf_shower_request_expires_after_expiry_window() {
+        let request = ShowerRequest {
+            initialized: true,
+            active: true,
+            bump: 254,
+            request_nonce: 7,
+            requested_slot: 20_000,
+            target_slot_a: 0,
+            target_slot_b: 0,
+            recipient_token_account: Pubkey::new_unique(),
+        };
+        assert!(!vrf_shower_request_expired(&request, 20_000));
+        assert!(!vrf_shower_request_expired(&request, 20_000 + SHOWER_VRF_EXPIRY_SLOTS));
+        assert!(vrf_shower_request_expired(&request, 20_001 + SHOWER_VRF_EXPIRY_SLOTS));
+    }
+
+    #[test]
+    fn shower_history_keeps_the_last_sixteen_settlements() {
+        let mut history = ShowerHistory {
+            head: 0,
+            bump: 255,
+            entries: [ShowerEntry::default(); SHOWER_HISTORY_LEN],
+        };
+        let recipient = Pubkey::new_unique();
+        for slot in 1..=20u64 {
+            record_shower_outcome(
+                &mut history,
+                ShowerEntry {
+                    slot,
+                    triggered: slot == 20,
+                    recipient,
+                    amount: if slot == 20 { 50 * ONE_ICHOR } else { 0 },
+                    rng_value: slot * 7,
+                },
+            );
+        }
+        // 20 writes wrap the buffer: slots 17..=20 overwrote the oldest four.
+        assert_eq!(history.head, 4);
+        let newest = history.entries[history.head as usize - 1];
+        assert_eq!((newest.slot, newest.triggered, newest.amount), (20, true, 50 * ONE_ICHOR));
+        assert_eq!(history.entries[history.head as usize].slot, 5);
+        assert_eq!(history.entries.iter().map(|e| e.slot).min(), Some(5));
+    }
+
     #[test]
     fn season_split_matches_betting_model() {
         let reward = 2_500 * ONE_ICHOR;
@@ -2000,6 +3531,124 @@ mod tests {
         assert_eq!(shower_addition, 250 * ONE_ICHOR + SHOWER_BONUS_EMISSION); // 10% + 0.2
     }
 
+    #[test]
+    fn core_reward_emission_pays_first_fighter_and_shower_pool() {
+        let season = 2_500 * ONE_ICHOR;
+        let shares = &DEFAULT_SEASON_SHARES;
+        assert_eq!(
+            core_reward_emission(ONE_ICHOR, 0, season, shares).unwrap(),
+            (800 * ONE_ICHOR, 250 * ONE_ICHOR + SHOWER_BONUS_EMISSION)
+        );
+        // Flat per rumble, so a batch owes the single-rumble amount per entry.
+        assert_eq!(
+            core_reward_emission(ONE_ICHOR, 7, season, shares).unwrap(),
+            core_reward_emission(ONE_ICHOR, 0, season, shares).unwrap()
+        );
+    }
+
+    fn sample_season(season_id: u64, start_rumble: u64, end_rumble: u64) -> SeasonConfig {
+        SeasonConfig {
+            season_id,
+            start_rumble,
+            end_rumble,
+            bettor_share_bps: 2_000,
+            fighter_share_bps: 7_000,
+            shower_share_bps: 1_000,
+            fighter_first_share_bps: 5_000,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn core_reward_emission_uses_season_shares() {
+        let season = sample_season(1, 0, 100);
+        // 70% fighter pool, half of it to 1st => 875 of 2,500.
+        assert_eq!(
+            core_reward_emission(ONE_ICHOR, 0, 2_500 * ONE_ICHOR, &(&season).into()).unwrap(),
+            (875 * ONE_ICHOR, 250 * ONE_ICHOR + SHOWER_BONUS_EMISSION)
+        );
+    }
+
+    #[test]
+    fn season_shares_roll_to_next_season_or_defaults() {
+        let current = sample_season(1, 10, 100);
+        let mut next = sample_season(2, 100, 200);
+        next.fighter_first_share_bps = 6_000;
+
+        assert_eq!(
+            season_shares(10, &current, None).unwrap(),
+            SeasonShares::from(&current)
+        );
+        assert_eq!(
+            season_shares(9, &current, None).unwrap_err(),
+            error!(IchorError::SeasonConfigMismatch)
+        );
+        assert_eq!(
+            season_shares(100, &current, None).unwrap(),
+            DEFAULT_SEASON_SHARES
+        );
+        assert_eq!(
+            season_shares(100, &current, Some(&next)).unwrap(),
+            SeasonShares::from(&next)
+        );
+        // The next season must follow on directly and cover the rumble.
+        assert!(season_shares(200, &current, Some(&next)).is_err());
+        assert!(season_shares(100, &current, Some(&sample_season(3, 100, 200))).is_err());
+    }
+
+    #[test]
+    fn season_params_need_full_split_and_nonempty_range() {
+        let params = SeasonParams {
+            start_rumble: 0,
+            end_rumble: 100,
+            bettor_share_bps: 1_000,
+            fighter_share_bps: 8_000,
+            shower_share_bps: 1_000,
+            fighter_first_share_bps: 4_000,
+        };
+        assert!(validate_season_params(1, &params).is_ok());
+        assert!(validate_season_params(0, &params).is_err());
+        for bad in [
+            SeasonParams {
+                end_rumble: 0,
+                ..params
+            },
+            SeasonParams {
+                shower_share_bps: 900,
+                ..params
+            },
+            SeasonParams {
+                fighter_first_share_bps: 10_001,
+                ..params
+            },
+        ] {
+            assert_eq!(
+                validate_season_params(1, &bad).unwrap_err(),
+                error!(IchorError::InvalidSeasonParams)
+            );
+        }
+    }
+
+    #[test]
+    fn reward_batch_needs_one_to_eight_distinct_rumbles() {
+        let accounts: Vec<Pubkey> = (0..9).map(|_| Pubkey::new_unique()).collect();
+        let ids: Vec<u64> = (1..=9).collect();
+
+        assert!(validate_reward_batch(&ids[..1], &accounts[..1]).is_ok());
+        assert!(validate_reward_batch(&ids[..8], &accounts[..8]).is_ok());
+        for (ids, accounts) in [
+            (&ids[..0], &accounts[..0]),
+            (&ids[..9], &accounts[..9]),
+            (&ids[..3], &accounts[..2]),
+            (&[4, 5, 4][..], &accounts[..3]),
+        ] {
+            assert_eq!(
+                validate_reward_batch(ids, accounts).unwrap_err(),
+                error!(IchorError::InvalidRewardBatch)
+            );
+        }
+    }
+
     #[test]
     fn calculate_reward_never_underflows_pool_cut() {
         // C-1 regression: even with a small season_reward, pool_cut should not underflow.
@@ -2031,4 +3680,37 @@ mod tests {
 
         assert!(load_slot_hash_by_slot(&data, 43).is_err());
     }
+
+    #[test]
+    fn pending_admin_expires_after_window() {
+        let pending = PendingAdmin {
+            proposed_admin: Pubkey::new_unique(),
+            proposed_at: 100,
+            bump: 255,
+        };
+
+        assert!(require_pending_admin_live(&pending, 100 + PENDING_ADMIN_EXPIRY_SLOTS).is_ok());
+        assert!(require_pending_admin_live(&pending, 101 + PENDING_ADMIN_EXPIRY_SLOTS).is_err());
+    }
+
+    #[test]
+    fn stake_yield_follows_lock_tiers_and_unlocks_after_period() {
+        assert!(stake_yield_bps(MIN_STAKE_LOCK_SECONDS - 1).is_err());
+        assert!(stake_yield_bps(MAX_STAKE_LOCK_SECONDS + 1).is_err());
+        assert_eq!(stake_yield_bps(MIN_STAKE_LOCK_SECONDS).unwrap(), 50);
+        assert_eq!(stake_yield_bps(30 * 86_400).unwrap(), 100);
+        assert_eq!(stake_yield_bps(MAX_STAKE_LOCK_SECONDS).unwrap(), 200);
+
+        let stake = IchorStake {
+            staker: Pubkey::new_unique(),
+            staked_amount: ONE_ICHOR,
+            staked_at: 1_000,
+            lock_period_seconds: MIN_STAKE_LOCK_SECONDS,
+            yield_bps: 50,
+            bump: 255,
+        };
+        let unlock_at = 1_000 + MIN_STAKE_LOCK_SECONDS;
+        assert!(require_stake_unlocked(&stake, unlock_at - 1).is_err());
+        assert!(require_stake_unlocked(&stake, unlock_at).is_ok());
+    }
 }
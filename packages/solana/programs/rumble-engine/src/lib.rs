@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+#[cfg(feature = "combat")]
+use anchor_lang::solana_program::sysvar::slot_hashes;
 use anchor_lang::system_program;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 #[cfg(feature = "combat")]
 use ephemeral_rollups_sdk::anchor::{commit, delegate, ephemeral};
 #[cfg(feature = "combat")]
@@ -16,37 +21,124 @@ use ephemeral_vrf_sdk::instructions::create_request_randomness_ix;
 use ephemeral_vrf_sdk::types::SerializableAccountMeta;
 #[cfg(feature = "combat")]
 use sha2::{Digest, Sha256};
+#[cfg(feature = "combat")]
+use std::cell::{Ref, RefMut};
 
 #[cfg(not(feature = "mainnet"))]
 declare_id!("638DcfW6NaBweznnzmJe4PyxCw51s3CTkykUNskWnxTU");
 #[cfg(feature = "mainnet")]
 declare_id!("2TvW4EfbmMe566ZQWZWd8kX34iFR2DM3oBUpjwpRJcqC");
 
-/// Maximum fighters per rumble
-const MAX_FIGHTERS: usize = 16;
+/// Maximum fighters per rumble (rumbles created on the v2 layout)
+const MAX_FIGHTERS: usize = 32;
+/// Maximum fighters per rumble created on the original layout
+const MAX_FIGHTERS_V1: usize = 16;
+/// Bettors an invite-only rumble's whitelist can hold.
+const MAX_WHITELIST_ENTRIES: usize = 64;
 
 /// PDA seeds
 const RUMBLE_SEED: &[u8] = b"rumble";
 const VAULT_SEED: &[u8] = b"vault";
 const BETTOR_SEED: &[u8] = b"bettor";
+const BET_RECORD_SEED: &[u8] = b"bet_record";
+const EXACTA_TICKET_SEED: &[u8] = b"exacta_ticket";
+const EXACTA_COMBO_SEED: &[u8] = b"exacta_combo";
+const BETTOR_INDEX_SEED: &[u8] = b"bettor_index";
+/// Rumbles a wallet's BettorIndex remembers.
+const BETTOR_INDEX_LEN: usize = 32;
+const GLOBAL_STATS_SEED: &[u8] = b"global_stats";
+const BETTOR_STATS_SEED: &[u8] = b"bettor_stats";
+const FEE_EXEMPT_SEED: &[u8] = b"fee_exempt";
+const RATE_LIMIT_SEED: &[u8] = b"rate_limit";
+/// `place_bet` calls one bettor may land on one rumble within a single slot.
+const MAX_BETS_PER_SLOT: u8 = 3;
+const DISPUTE_SEED: &[u8] = b"dispute";
+const ADMIN_LOG_SEED: &[u8] = b"admin_log";
+
+/// Entries the AdminLog ring buffer keeps before overwriting the oldest.
+const ADMIN_LOG_CAPACITY: usize = 128;
+const SPONSORSHIP_LEDGER_SEED: &[u8] = b"sponsorship_ledger";
 const CONFIG_SEED: &[u8] = b"rumble_config";
 const SPONSORSHIP_SEED: &[u8] = b"sponsorship";
+const REFERRAL_SEED: &[u8] = b"referral";
+const WHITELIST_SEED: &[u8] = b"whitelist";
+#[cfg(feature = "combat")]
+const COMBAT_RULESET_SEED: &[u8] = b"combat_ruleset";
 #[cfg(feature = "combat")]
 const MOVE_COMMIT_SEED: &[u8] = b"move_commit";
 #[cfg(feature = "combat")]
 const MOVE_COMMIT_DOMAIN: &[u8] = b"rumble:v1";
 #[cfg(feature = "combat")]
 const FIGHTER_DELEGATE_SEED: &[u8] = b"fighter_delegate";
+/// FighterDelegate size before `expires_at_slot` was added.
 #[cfg(feature = "combat")]
+const FIGHTER_DELEGATE_V1_LEN: usize = 8 + 32 + 32 + 8 + 1 + 1; // 82
+/// `Rumble` size before any field past `bump` was added (see `migrate_rumble`).
+const RUMBLE_V1_LEN: usize =
+    8 + 8 + 1 + 32 * 16 + 1 + 8 * 16 + 8 + 8 + 8 + 16 + 1 + 8 + 8 + 8 + 1; // 724
+/// Bytes each fighter slot takes in a `Rumble` (fighters, betting_pools,
+/// placements, sponsorship_by_fighter, elimination_rank, insured_stake).
+const RUMBLE_FIGHTER_SLOT_LEN: usize = 32 + 8 + 1 + 8 + 1 + 8; // 58
+/// `Rumble` with `MAX_FIGHTERS` slots, for rumbles created on the v2 layout.
+const RUMBLE_V2_LEN: usize = 8 + Rumble::INIT_SPACE;
+/// `Rumble` with `MAX_FIGHTERS_V1` slots, which has no `fighter_slots` byte.
+const RUMBLE_LEN: usize =
+    RUMBLE_V2_LEN - (MAX_FIGHTERS - MAX_FIGHTERS_V1) * RUMBLE_FIGHTER_SLOT_LEN - 1;
 const COMBAT_STATE_SEED: &[u8] = b"combat_state";
+/// Zero-copy `RumbleCombatState` size; borsh-era combat states are shorter.
+#[cfg(feature = "combat")]
+const COMBAT_STATE_LEN: usize = 8 + std::mem::size_of::<RumbleCombatState>(); // 1872
+#[cfg(feature = "combat")]
+const COMBAT_LOG_SEED: &[u8] = b"combat_log";
 const PENDING_ADMIN_SEED: &[u8] = b"pending_admin_re";
+const PENDING_TREASURY_SEED: &[u8] = b"pending_treasury";
+const RUMBLE_META_SEED: &[u8] = b"rumble_meta";
+const LEADERBOARD_SEED: &[u8] = b"leaderboard";
+/// Number of fighters kept on the `GlobalLeaderboard`.
+const LEADERBOARD_SIZE: usize = 16;
 const FIGHTER_REGISTRY_PROGRAM_ID: Pubkey = pubkey!("2hA6Jvj1yjP2Uj3qrJcsBeYA2R9xPM95mDKw1ncKVExa");
 const FIGHTER_ACCOUNT_DISCRIMINATOR: [u8; 8] = [24, 221, 27, 113, 60, 210, 101, 211];
-
-/// Fee basis points (out of 10_000)
-const ADMIN_FEE_BPS: u64 = 100; // 1%
-const SPONSORSHIP_FEE_BPS: u64 = 100; // 1%
-
+/// Seed of fighter-registry's `Fighter` PDA: `[FIGHTER_SEED, authority, fighter_index]`.
+const FIGHTER_SEED: &[u8] = b"fighter";
+/// Offset of `Fighter.wins`: after discriminator, authority, name and created_at.
+const FIGHTER_WINS_OFFSET: usize = 8 + 32 + 32 + 8;
+/// Offset of `Fighter.current_streak`: after wins, losses, damage dealt/taken
+/// and total_rumbles.
+#[cfg(feature = "combat")]
+const FIGHTER_CURRENT_STREAK_OFFSET: usize = FIGHTER_WINS_OFFSET + 5 * 8;
+/// Seed of fighter-registry's `RegistryConfig` PDA.
+const FIGHTER_REGISTRY_CONFIG_SEED: &[u8] = b"registry_config";
+/// Seed of this program's PDA that signs CPIs into fighter-registry; the
+/// registry trusts it once `engine_program` is set to this program.
+const REGISTRY_AUTHORITY_SEED: &[u8] = b"registry_authority";
+/// Anchor discriminator of fighter-registry's `set_in_rumble` instruction.
+const SET_IN_RUMBLE_DISCRIMINATOR: [u8; 8] = [206, 237, 179, 221, 84, 204, 123, 248];
+/// Anchor discriminator of fighter-registry's `update_record` instruction.
+#[cfg(feature = "combat")]
+const UPDATE_RECORD_DISCRIMINATOR: [u8; 8] = [54, 194, 108, 162, 199, 12, 5, 60];
+/// Offset of `Fighter.queue_position` (an `Option<u64>`) in the registry account.
+/// Everything before it is fixed-size: discriminator, authority, name, created_at,
+/// seven record fields and three economy fields.
+const FIGHTER_QUEUE_POSITION_OFFSET: usize = 8 + 32 + 32 + 8 + 7 * 8 + 3 * 8;
+/// Bytes between `queue_position` and `strength`: auto_requeue, in_rumble,
+/// last_rumble_id, last_rumble_at, fighter_index, bump.
+const FIGHTER_ATTRIBUTES_GAP: usize = 1 + 1 + 8 + 8 + 1 + 1;
+/// Bytes between `queue_position` and `fighter_index`: auto_requeue, in_rumble,
+/// last_rumble_id, last_rumble_at. `bump` follows `fighter_index`.
+const FIGHTER_INDEX_GAP: usize = 1 + 1 + 8 + 8;
+/// Bytes between the end of `specialization` and `level`: injured_until_rumble, xp.
+const FIGHTER_LEVEL_GAP: usize = 8 + 4;
+
+/// Default fee basis points (out of 10_000), stored in RumbleConfig at init/migration
+const ADMIN_FEE_BPS: u16 = 100; // 1%
+const SPONSORSHIP_FEE_BPS: u16 = 100; // 1%
+/// Share of the admin fee paid to a bet's referrer, not an extra charge.
+const REFERRAL_FEE_BPS: u16 = 50; // 0.5%
+const _: () = assert!(REFERRAL_FEE_BPS <= ADMIN_FEE_BPS);
+/// Upper bound for each configurable fee
+const MAX_FEE_BPS: u16 = 1_000; // 10%
+
+/// Default split when a rumble is created without a `PayoutSplit`.
 /// Winner-takes-all: 100% of losers' pool (after treasury cut) goes to 1st place bettors
 const FIRST_PLACE_BPS: u64 = 10_000; // 100%
 const SECOND_PLACE_BPS: u64 = 0; // 0% — winner-takes-all
@@ -55,18 +147,95 @@ const THIRD_PLACE_BPS: u64 = 0; // 0% — winner-takes-all
 /// Treasury cut from losers' pool before payout distribution
 const TREASURY_CUT_BPS: u64 = 300; // 3%
 
+/// Ceiling on the ICHOR staking bonus, in bps of a bettor's winnings. The same
+/// share of the distributable pool is held back from the treasury cut at result
+/// time to fund it.
+const MAX_STAKE_BONUS_BPS: u16 = 200; // 2%
+/// (minimum lock seconds, bonus bps) tiers for ICHOR stakes, longest lock first.
+const STAKE_BONUS_TIERS: [(i64, u16); 3] = [
+    (90 * 86_400, 200),
+    (30 * 86_400, 100),
+    (7 * 86_400, 50),
+];
+const ICHOR_TOKEN_PROGRAM_ID: Pubkey = pubkey!("925GAeqjKMX4B5MDANB91SZCvrx8HpEgmPJwHJzxKJx1");
+/// ICHOR mint burned by `place_bet` (same mint fighter-registry burns).
+const EXPECTED_ICHOR_MINT: Pubkey = pubkey!("4amdLk5Ue4pbM1CXRZeUn3ZBAf8QTXXGu4HqH5dQv3qM");
+/// Upper bound for `ichor_burn_per_bet`: 10 ICHOR.
+const MAX_ICHOR_BURN_PER_BET: u64 = 10_000_000_000;
+const ICHOR_STAKE_DISCRIMINATOR: [u8; 8] = [106, 49, 165, 76, 246, 29, 3, 57];
+/// Seed of ichor-token's per-staker `IchorStake` PDA.
+const ICHOR_STAKE_SEED: &[u8] = b"stake";
+
 /// Post-result buffer before admin can mark payout phase complete (24 hours).
+/// Default for `create_rumble`, and the window of migrated rumbles that store 0.
 const PAYOUT_CLAIM_WINDOW_SECONDS: i64 = 86_400;
+/// Shortest per-rumble claim window `create_rumble` accepts (10 minutes).
+const MIN_CLAIM_WINDOW_SECONDS: i64 = 600;
+/// Longest per-rumble claim window `create_rumble` accepts (30 days).
+const MAX_CLAIM_WINDOW_SECONDS: i64 = 30 * 86_400;
 
-/// On-chain turn timing windows (slots).
-#[cfg(feature = "combat")]
-const COMMIT_WINDOW_SLOTS: u64 = 30;
+/// Post-cancellation buffer before a cancelled rumble's vault can be swept (7 days).
+const REFUND_CLAIM_WINDOW_SECONDS: i64 = 7 * 86_400;
+
+/// How long after an admin-set result a bettor may dispute it (12 hours).
+const DISPUTE_WINDOW_SECONDS: i64 = 12 * 60 * 60;
+
+/// How long after on-chain combat starts before `admin_set_result` may
+/// override it with `force` (1 hour).
+const FORCE_RESULT_TIMEOUT_SECONDS: i64 = 60 * 60;
+
+/// `Rumble.result_source` values.
+const RESULT_SOURCE_UNSET: u8 = 0;
 #[cfg(feature = "combat")]
-const REVEAL_WINDOW_SLOTS: u64 = 30;
+const RESULT_SOURCE_ONCHAIN: u8 = 1;
+const RESULT_SOURCE_ADMIN: u8 = 2;
+
+/// Grace after the betting deadline before anyone may cancel an underfunded rumble (~1 hour),
+/// in the unit of the rumble's `DeadlineKind`.
+const UNDERFUNDED_CANCEL_GRACE_SLOTS: i64 = 9_000;
+const UNDERFUNDED_CANCEL_GRACE_SECONDS: i64 = 3_600;
+
+/// Default on-chain turn timing windows (slots), stored in RumbleConfig at init/migration
+const COMMIT_WINDOW_SLOTS: u32 = 30;
+const REVEAL_WINDOW_SLOTS: u32 = 30;
+/// Bounds for admin-configured commit/reveal windows (slots)
+const MIN_COMBAT_WINDOW_SLOTS: u32 = 5;
+const MAX_COMBAT_WINDOW_SLOTS: u32 = 300;
+/// Default rate for estimating a slot from a unix timestamp, in slots per second
+/// × 1000 (2.5 slots/s, i.e. 400ms slots). Stored in RumbleConfig at init/migration.
+const DEFAULT_SLOTS_PER_SECOND_MILLI: u32 = 2_500;
+/// Upper bound for the admin-configured slot rate (10 slots/s).
+const MAX_SLOTS_PER_SECOND_MILLI: u32 = 10_000;
+/// Delay between proposing and applying a treasury change (~24 hours of slots).
+const TREASURY_UPDATE_DELAY_SLOTS: u64 = 216_000;
+/// How long a proposed admin has to accept before the proposal lapses (~7 days of slots).
+const PENDING_ADMIN_EXPIRY_SLOTS: u64 = 1_512_000;
+/// Upper bound on the cranker's cut of a cranked claim (1%).
+const MAX_CRANK_TIP_BPS: u16 = 100;
 #[cfg(feature = "combat")]
 const MAX_ONCHAIN_COMBAT_TURNS: u32 = 120;
+/// Keeper calls a rumble's keeper budget is sized for: a resolve and an
+/// advance per turn, plus `finalize_rumble`.
+const KEEPER_BUDGET_CALLS: u64 = 241;
+#[cfg(feature = "combat")]
+const _: () = assert!(KEEPER_BUDGET_CALLS == 2 * MAX_ONCHAIN_COMBAT_TURNS as u64 + 1);
+/// Upper bound for `keeper_fee_lamports` (0.001 SOL per call).
+const MAX_KEEPER_FEE_LAMPORTS: u64 = 1_000_000;
+/// Default `combat_timeout_slots`: slots since combat started after which
+/// `finalize_rumble` may end a fight that is still going (~1 hour). A full
+/// `MAX_ONCHAIN_COMBAT_TURNS` fight at the default windows takes 7_200 slots.
+const DEFAULT_COMBAT_TIMEOUT_SLOTS: u64 = 9_000;
+/// Bounds for `combat_timeout_slots`. The floor is the old per-turn timeout;
+/// the ceiling (~2 hours) caps how long a stuck rumble holds bettor funds.
+const MIN_COMBAT_TIMEOUT_SLOTS: u64 = 5_000;
+const MAX_COMBAT_TIMEOUT_SLOTS: u64 = 18_000;
+/// Slots past a turn's reveal window after which anyone can
+/// `force_resolve_stale_turn` it.
+#[cfg(feature = "combat")]
+const STALE_TURN_GRACE_SLOTS: u64 = 300;
+/// Max MoveCommitment PDAs closed per `close_move_commitments_batch` call (compute budget).
 #[cfg(feature = "combat")]
-const COMBAT_TIMEOUT_SLOTS: u64 = 5000; // ~33 minutes; prevents stuck rumbles
+const MAX_MOVE_COMMIT_CLOSE_BATCH: usize = 16;
 
 #[cfg(feature = "combat")]
 const MOVE_HIGH_STRIKE: u8 = 0;
@@ -86,6 +255,12 @@ const MOVE_DODGE: u8 = 6;
 const MOVE_CATCH: u8 = 7;
 #[cfg(feature = "combat")]
 const MOVE_SPECIAL: u8 = 8;
+/// Reads the opponent's previous move instead of meeting the current one.
+#[cfg(feature = "combat")]
+const MOVE_FEINT: u8 = 9;
+/// `last_move` value before a fighter has resolved a move.
+#[cfg(feature = "combat")]
+const NO_LAST_MOVE: u8 = u8::MAX;
 
 #[cfg(feature = "combat")]
 const STRIKE_DAMAGE_HIGH: u16 = 39;
@@ -97,18 +272,108 @@ const STRIKE_DAMAGE_LOW: u16 = 23;
 const CATCH_DAMAGE: u16 = 45;
 #[cfg(feature = "combat")]
 const COUNTER_DAMAGE: u16 = 18;
+/// Extra damage a Feint deals over a counter when it reads a striker.
+#[cfg(feature = "combat")]
+const FEINT_COUNTER_BONUS: u16 = 6;
+/// Blocked strikes in a row after which a fighter's guard breaks.
+#[cfg(feature = "combat")]
+const GUARD_BREAK_THRESHOLD: u8 = 3;
+/// Percent chance a strike crits on a `crits_enabled` rumble (1 in 10).
+#[cfg(feature = "combat")]
+const CRIT_CHANCE_PERCENT: u64 = 10;
+/// A crit deals 1.5x the landed strike's damage.
+#[cfg(feature = "combat")]
+const CRIT_DAMAGE_PERCENT: u32 = 150;
+/// Lamports backing a fighter per point of audience boost.
+#[cfg(feature = "combat")]
+const AUDIENCE_BOOST_LAMPORTS_PER_POINT: u64 = 1_000_000_000;
+/// Cap on the bonus damage a fighter's backers can buy.
+#[cfg(feature = "combat")]
+const MAX_AUDIENCE_BOOST: u64 = 5;
+/// Low-strike hits taken before a fighter is poisoned.
+#[cfg(feature = "combat")]
+const POISON_STACK_THRESHOLD: u8 = 3;
+/// Damage a poisoned fighter takes at the start of each resolved turn.
+#[cfg(feature = "combat")]
+const POISON_TICK_DAMAGE: u16 = 5;
+/// HP restored when a low guard cures poison.
+#[cfg(feature = "combat")]
+const POISON_CURE_HEAL: u16 = 5;
 #[cfg(feature = "combat")]
 const SPECIAL_DAMAGE: u16 = 52;
 #[cfg(feature = "combat")]
+const BRAWLER_STRIKE_BONUS: u16 = 4;
+/// Chip damage for committing a move and then not revealing it.
+#[cfg(feature = "combat")]
+const MISSED_REVEAL_CHIP_DAMAGE: u16 = 5;
+#[cfg(feature = "combat")]
 const FINAL_DUEL_SUDDEN_DEATH_BONUS: u16 = 20;
 #[cfg(feature = "combat")]
 const FINAL_DUEL_SUDDEN_DEATH_CHIP: u16 = 20;
+/// HP the last fighters standing come back with after knocking each other out
+/// in the same turn; see `revive_double_knockout`.
+#[cfg(feature = "combat")]
+const DOUBLE_KO_REVIVE_HP: u16 = 10;
 #[cfg(feature = "combat")]
 const METER_PER_TURN: u8 = 20;
 #[cfg(feature = "combat")]
 const SPECIAL_METER_COST: u8 = 100;
 #[cfg(feature = "combat")]
 const START_HP: u16 = 100;
+/// Combat numbers for rumbles created without a `CombatRuleset`.
+#[cfg(feature = "combat")]
+const DEFAULT_COMBAT_RULESET: CombatRuleset = CombatRuleset {
+    ruleset_id: 0,
+    strike_damage_high: STRIKE_DAMAGE_HIGH,
+    strike_damage_mid: STRIKE_DAMAGE_MID,
+    strike_damage_low: STRIKE_DAMAGE_LOW,
+    catch_damage: CATCH_DAMAGE,
+    counter_damage: COUNTER_DAMAGE,
+    special_damage: SPECIAL_DAMAGE,
+    meter_per_turn: METER_PER_TURN,
+    special_meter_cost: SPECIAL_METER_COST,
+    start_hp: START_HP,
+    bump: 0,
+};
+/// Bounds for per-fighter starting HP set by `set_fighter_hp_overrides`.
+#[cfg(feature = "combat")]
+const MIN_HP_OVERRIDE: u16 = 80;
+#[cfg(feature = "combat")]
+const MAX_HP_OVERRIDE: u16 = 120;
+/// Cap on the starting-HP bonus from a fighter's registry level; mirrors
+/// fighter-registry's `level_up_bonus`.
+#[cfg(feature = "combat")]
+const MAX_LEVEL_HP_BONUS: u16 = 10;
+/// Cap on a fighter's total registry starting-HP bonus (+1 per win, plus level).
+#[cfg(feature = "combat")]
+const MAX_REGISTRY_HP_BONUS: u16 = 20;
+/// Starting meter per consecutive win, capped at one turn's worth.
+#[cfg(feature = "combat")]
+const WIN_STREAK_METER_PER_WIN: u8 = 5;
+#[cfg(feature = "combat")]
+const MAX_WIN_STREAK_METER: u8 = 20;
+/// `turn_pairings` entry for a fighter with no opponent this turn (bye or eliminated).
+#[cfg(feature = "combat")]
+const PAIRING_BYE: u8 = u8::MAX;
+/// Consecutive fallback-move turns after which anyone may eliminate a fighter.
+#[cfg(feature = "combat")]
+const INACTIVITY_TIMEOUT_TURNS: u8 = 3;
+/// Duels a CombatLog can hold; later duels set `truncated` instead.
+#[cfg(feature = "combat")]
+const COMBAT_LOG_CAPACITY: usize = 256;
+
+/// ichor-token's `IchorStake` account, read (never written) to size a bettor's
+/// payout bonus.
+#[derive(AnchorDeserialize)]
+struct IchorStake {
+    staker: Pubkey,
+    staked_amount: u64,
+    staked_at: i64,
+    lock_period_seconds: i64,
+    #[allow(dead_code)]
+    yield_bps: u16,
+    bump: u8,
+}
 
 struct ParsedBettorAccount {
     authority: Pubkey,
@@ -121,6 +386,56 @@ struct ParsedBettorAccount {
     claimed: bool,
     bump: u8,
     fighter_deployments: [u64; MAX_FIGHTERS],
+    bet_sequence: u32,
+    insured_deployments: [u64; MAX_FIGHTERS],
+    insurance_claimed: bool,
+    insurance_premium_paid: u64,
+}
+
+impl From<&BettorAccount> for ParsedBettorAccount {
+    fn from(bettor: &BettorAccount) -> Self {
+        Self {
+            authority: bettor.authority,
+            rumble_id: bettor.rumble_id,
+            fighter_index: bettor.fighter_index,
+            sol_deployed: bettor.sol_deployed,
+            claimable_lamports: bettor.claimable_lamports,
+            total_claimed_lamports: bettor.total_claimed_lamports,
+            last_claim_ts: bettor.last_claim_ts,
+            claimed: bettor.claimed,
+            bump: bettor.bump,
+            fighter_deployments: bettor.fighter_deployments,
+            bet_sequence: bettor.bet_sequence,
+            insured_deployments: bettor.insured_deployments,
+            insurance_claimed: bettor.insurance_claimed,
+            insurance_premium_paid: bettor.insurance_premium_paid,
+        }
+    }
+}
+
+fn read_u32_le(data: &[u8], offset: &mut usize) -> Result<u32> {
+    let end = offset
+        .checked_add(4)
+        .ok_or(RumbleError::InvalidBettorAccount)?;
+    let bytes: [u8; 4] = data
+        .get(*offset..end)
+        .ok_or(RumbleError::InvalidBettorAccount)?
+        .try_into()
+        .map_err(|_| error!(RumbleError::InvalidBettorAccount))?;
+    *offset = end;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn write_u32_le(data: &mut [u8], offset: &mut usize, value: u32) -> Result<()> {
+    let end = offset
+        .checked_add(4)
+        .ok_or(RumbleError::InvalidBettorAccount)?;
+    let slice = data
+        .get_mut(*offset..end)
+        .ok_or(RumbleError::InvalidBettorAccount)?;
+    slice.copy_from_slice(&value.to_le_bytes());
+    *offset = end;
+    Ok(())
 }
 
 fn read_u64_le(data: &[u8], offset: &mut usize) -> Result<u64> {
@@ -173,20 +488,49 @@ fn write_i64_le(data: &mut [u8], offset: &mut usize, value: i64) -> Result<()> {
     Ok(())
 }
 
+/// `BettorAccount` size with `slots` entries per fighter array; `place_bet`
+/// sizes it to the rumble's slots.
+fn bettor_account_len(slots: usize) -> usize {
+    if slots == MAX_FIGHTERS {
+        8 + BettorAccount::INIT_SPACE
+    } else {
+        // No trailing `fighter_slots` byte on the 16-slot layout.
+        8 + BettorAccount::INIT_SPACE - 2 * 8 * (MAX_FIGHTERS - MAX_FIGHTERS_V1) - 1
+    }
+}
+
+/// Fighter slots of a bettor account `len` bytes long. Anything short of the
+/// v2 size is a 16-slot layout.
+fn bettor_account_slots(len: usize) -> usize {
+    if len >= bettor_account_len(MAX_FIGHTERS) {
+        MAX_FIGHTERS
+    } else {
+        MAX_FIGHTERS_V1
+    }
+}
+
 fn parse_bettor_account_data(data: &[u8]) -> Result<ParsedBettorAccount> {
+    require!(
+        data.len() >= 8 && &data[..8] == BettorAccount::DISCRIMINATOR,
+        RumbleError::InvalidBettorAccount
+    );
+    parse_bettor_account_fields(data)
+}
+
+/// `parse_bettor_account_data` without the discriminator check.
+fn parse_bettor_account_fields(data: &[u8]) -> Result<ParsedBettorAccount> {
     // Legacy V2 minimum: discriminator + authority + rumble_id + fighter_index + sol_deployed
     // + claimable + total_claimed + last_claim_ts + claimed + bump
     const LEGACY_V2_LEN: usize = 8 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 1 + 1; // 83
-    const CURRENT_LEN: usize = 8 + BettorAccount::INIT_SPACE; // 211
+    const DEPLOYMENTS_LEN: usize = LEGACY_V2_LEN + 8 * MAX_FIGHTERS_V1; // 211
+    const BET_SEQUENCE_LEN: usize = DEPLOYMENTS_LEN + 4; // 215
+    let slots = bettor_account_slots(data.len());
+    let current_len = bettor_account_len(slots); // 352, or 609 on v2 rumbles
 
     require!(
         data.len() >= LEGACY_V2_LEN,
         RumbleError::InvalidBettorAccount
     );
-    require!(
-        &data[..8] == BettorAccount::DISCRIMINATOR,
-        RumbleError::InvalidBettorAccount
-    );
 
     let mut offset = 8usize;
     let authority_bytes: [u8; 32] = data[offset..offset + 32]
@@ -209,15 +553,31 @@ fn parse_bettor_account_data(data: &[u8]) -> Result<ParsedBettorAccount> {
     offset += 1;
 
     let mut fighter_deployments = [0u64; MAX_FIGHTERS];
-    if data.len() >= CURRENT_LEN {
-        for i in 0..MAX_FIGHTERS {
-            fighter_deployments[i] = read_u64_le(data, &mut offset)?;
+    if data.len() >= DEPLOYMENTS_LEN {
+        for value in fighter_deployments.iter_mut().take(slots) {
+            *value = read_u64_le(data, &mut offset)?;
         }
     } else {
         if (fighter_index as usize) < MAX_FIGHTERS {
             fighter_deployments[fighter_index as usize] = sol_deployed;
         }
     }
+    let bet_sequence = if data.len() >= BET_SEQUENCE_LEN {
+        read_u32_le(data, &mut offset)?
+    } else {
+        0
+    };
+    let mut insured_deployments = [0u64; MAX_FIGHTERS];
+    let mut insurance_claimed = false;
+    let mut insurance_premium_paid = 0;
+    if data.len() >= current_len {
+        for value in insured_deployments.iter_mut().take(slots) {
+            *value = read_u64_le(data, &mut offset)?;
+        }
+        insurance_claimed = *data.get(offset).ok_or(RumbleError::InvalidBettorAccount)? == 1;
+        offset += 1;
+        insurance_premium_paid = read_u64_le(data, &mut offset)?;
+    }
 
     Ok(ParsedBettorAccount {
         authority,
@@ -230,6 +590,10 @@ fn parse_bettor_account_data(data: &[u8]) -> Result<ParsedBettorAccount> {
         claimed,
         bump,
         fighter_deployments,
+        bet_sequence,
+        insured_deployments,
+        insurance_claimed,
+        insurance_premium_paid,
     })
 }
 
@@ -237,7 +601,10 @@ fn write_bettor_account_data(data: &mut [u8], bettor: &ParsedBettorAccount) -> R
     // Legacy V2 minimum: discriminator + authority + rumble_id + fighter_index + sol_deployed
     // + claimable + total_claimed + last_claim_ts + claimed + bump
     const LEGACY_V2_LEN: usize = 8 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 1 + 1; // 83
-    const CURRENT_LEN: usize = 8 + BettorAccount::INIT_SPACE; // 211
+    const DEPLOYMENTS_LEN: usize = LEGACY_V2_LEN + 8 * MAX_FIGHTERS_V1; // 211
+    const BET_SEQUENCE_LEN: usize = DEPLOYMENTS_LEN + 4; // 215
+    let slots = bettor_account_slots(data.len());
+    let current_len = bettor_account_len(slots); // 352, or 609 on v2 rumbles
 
     require!(
         data.len() >= LEGACY_V2_LEN,
@@ -264,12 +631,219 @@ fn write_bettor_account_data(data: &mut [u8], bettor: &ParsedBettorAccount) -> R
     data[offset] = bettor.bump;
     offset += 1;
 
-    if data.len() >= CURRENT_LEN {
-        for value in bettor.fighter_deployments {
-            write_u64_le(data, &mut offset, value)?;
+    if data.len() >= DEPLOYMENTS_LEN {
+        for value in &bettor.fighter_deployments[..slots] {
+            write_u64_le(data, &mut offset, *value)?;
+        }
+    }
+    if data.len() >= BET_SEQUENCE_LEN {
+        write_u32_le(data, &mut offset, bettor.bet_sequence)?;
+    }
+    if data.len() >= current_len {
+        for value in &bettor.insured_deployments[..slots] {
+            write_u64_le(data, &mut offset, *value)?;
+        }
+        data[offset] = if bettor.insurance_claimed { 1 } else { 0 };
+        offset += 1;
+        write_u64_le(data, &mut offset, bettor.insurance_premium_paid)?;
+        if slots == MAX_FIGHTERS {
+            data[offset] = MAX_FIGHTERS as u8;
+        }
+    }
+
+    Ok(())
+}
+
+/// Per-fighter stake for a bettor, backfilling legacy single-fighter accounts
+/// that only tracked `fighter_index` + `sol_deployed`.
+fn bettor_deployments(bettor: &ParsedBettorAccount) -> [u64; MAX_FIGHTERS] {
+    let mut deployments = bettor.fighter_deployments;
+    if deployments.iter().all(|x| *x == 0) {
+        let legacy_idx = bettor.fighter_index as usize;
+        if legacy_idx < MAX_FIGHTERS {
+            deployments[legacy_idx] = bettor.sol_deployed;
         }
     }
+    deployments
+}
+
+/// Move a pending treasury into the config once `slot` reaches its timelock.
+/// Until then the config (and so every `treasury` constraint) is unchanged.
+/// Returns the replaced treasury.
+fn apply_pending_treasury(
+    config: &mut RumbleConfig,
+    pending: &PendingTreasury,
+    slot: u64,
+) -> Result<Pubkey> {
+    require!(
+        slot >= pending.effective_after_slot,
+        RumbleError::TreasuryUpdateTimelocked
+    );
+    Ok(std::mem::replace(&mut config.treasury, pending.proposed_treasury))
+}
+
+/// Reject an admin proposal older than `PENDING_ADMIN_EXPIRY_SLOTS`. A lapsed
+/// proposal can only be replaced (`transfer_admin`) or revoked.
+fn require_pending_admin_live(pending: &PendingAdminRE, slot: u64) -> Result<()> {
+    require!(
+        slot.saturating_sub(pending.proposed_at) <= PENDING_ADMIN_EXPIRY_SLOTS,
+        RumbleError::PendingAdminExpired
+    );
+    Ok(())
+}
+
+/// Reject instructions gated by the global pause switch (see `set_paused`).
+fn require_not_paused(config: &RumbleConfig) -> Result<()> {
+    require!(!config.paused, RumbleError::ProgramPaused);
+    Ok(())
+}
+
+/// Close a program-owned account held as a raw `AccountInfo` (e.g. a legacy
+/// bettor layout that cannot be loaded as `Account<T>`), returning rent to `destination`.
+fn close_program_account(info: &AccountInfo<'_>, destination: &AccountInfo<'_>) -> Result<()> {
+    let lamports = info.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(RumbleError::MathOverflow)?;
+    **info.try_borrow_mut_lamports()? = 0;
+
+    info.assign(&system_program::ID);
+    info.resize(0)?;
+    Ok(())
+}
+
+/// Take `amount` (or the whole stake when `None`) off a bettor's stake on one
+/// fighter while betting is open. Returns `(withdrawn, refund, penalty)`.
+/// Withdrawing the bettor's last stake also refunds their insurance premiums,
+/// since the account that recorded them may be closed.
+fn apply_stake_withdrawal(
+    rumble: &mut Rumble,
+    bettor: &mut ParsedBettorAccount,
+    fighter_index: u8,
+    amount: Option<u64>,
+    penalty_bps: u16,
+    clock: &Clock,
+) -> Result<(u64, u64, u64)> {
+    require!(
+        rumble.state == RumbleState::Betting,
+        RumbleError::BettingClosed
+    );
+    require_betting_open(rumble, clock)?;
+    let idx = fighter_index as usize;
+    require!(
+        idx < rumble.fighter_count as usize,
+        RumbleError::InvalidFighterIndex
+    );
+    require!(!bettor.claimed, RumbleError::AlreadyClaimed);
+
+    let mut deployments = bettor_deployments(bettor);
+    let stake = deployments[idx];
+    require!(stake > 0, RumbleError::NoBetToCancel);
+    let withdrawn = amount.unwrap_or(stake);
+    require!(
+        withdrawn > 0 && withdrawn <= stake,
+        RumbleError::InvalidWithdrawAmount
+    );
+
+    let penalty = fee_lamports(withdrawn, penalty_bps)?;
+    let mut refund = withdrawn
+        .checked_sub(penalty)
+        .ok_or(RumbleError::MathOverflow)?;
+
+    rumble.betting_pools[idx] = rumble.betting_pools[idx]
+        .checked_sub(withdrawn)
+        .ok_or(RumbleError::MathOverflow)?;
+    rumble.total_deployed = rumble
+        .total_deployed
+        .checked_sub(withdrawn)
+        .ok_or(RumbleError::MathOverflow)?;
+    rumble.admin_fee_collected = rumble
+        .admin_fee_collected
+        .checked_add(penalty)
+        .ok_or(RumbleError::MathOverflow)?;
+
+    deployments[idx] = stake - withdrawn;
+    bettor.fighter_deployments = deployments;
+    // Insurance covers no more than what is still staked; premiums stay paid
+    // while any stake remains.
+    let uninsured = bettor.insured_deployments[idx].saturating_sub(deployments[idx]);
+    bettor.insured_deployments[idx] -= uninsured;
+    rumble.insured_stake[idx] = rumble.insured_stake[idx].saturating_sub(uninsured);
+    bettor.sol_deployed = bettor
+        .sol_deployed
+        .checked_sub(withdrawn)
+        .ok_or(RumbleError::MathOverflow)?;
+    if bettor.sol_deployed == 0 {
+        release_refunded_premium(rumble, bettor)?;
+        refund = refund
+            .checked_add(bettor.insurance_premium_paid)
+            .ok_or(RumbleError::MathOverflow)?;
+        bettor.insurance_premium_paid = 0;
+    }
+
+    Ok((withdrawn, refund, penalty))
+}
+
+/// Total net stake a bettor has deployed across every fighter in the rumble,
+/// plus any insurance premiums paid (the cover never came into play).
+/// Falls back to `sol_deployed` for legacy accounts with no per-fighter breakdown.
+fn bettor_refund_lamports(bettor: &ParsedBettorAccount) -> Result<u64> {
+    let mut total: u64 = 0;
+    for value in bettor.fighter_deployments.iter() {
+        total = total
+            .checked_add(*value)
+            .ok_or(RumbleError::MathOverflow)?;
+    }
+    if total == 0 {
+        total = bettor.sol_deployed;
+    }
+    total
+        .checked_add(bettor.insurance_premium_paid)
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// Shared body of `forfeit` and `surrender`: the fighter's HP drops to 0 and
+/// it takes the next elimination rank. Only between turns, so an open turn's
+/// pairings are never disturbed. Returns the fighter's index.
+#[cfg(feature = "combat")]
+fn concede_fighter(accounts: &mut Forfeit<'_>) -> Result<usize> {
+    let rumble = &accounts.rumble;
+    let mut combat_state = load_combat_mut(&accounts.combat_state)?;
+    let combat = &mut *combat_state;
+
+    require!(
+        rumble.state == RumbleState::Combat,
+        RumbleError::InvalidStateTransition
+    );
+    let fighter_idx = fighter_in_rumble(rumble, &accounts.fighter.key())
+        .ok_or(error!(RumbleError::Unauthorized))?;
+    assert_move_authority(
+        &accounts.fighter.key(),
+        &accounts.authority.key(),
+        &accounts.fighter_delegate,
+    )?;
+    concede_combat_slot(combat, fighter_idx, rumble.fighter_count as usize)?;
+    Ok(fighter_idx)
+}
+
+/// Take a live fighter out of combat: HP to 0, next elimination rank, and the
+/// winner recorded if that leaves one fighter standing.
+#[cfg(feature = "combat")]
+fn concede_combat_slot(
+    combat: &mut RumbleCombatState,
+    fighter_idx: usize,
+    fighter_count: usize,
+) -> Result<()> {
+    require!(
+        combat.hp[fighter_idx] > 0 && combat.elimination_rank[fighter_idx] == 0,
+        RumbleError::FighterEliminated
+    );
+    require!(combat.turn_resolved != 0, RumbleError::TurnNotResolved);
 
+    combat.hp[fighter_idx] = 0;
+    assign_elimination_rank(combat, fighter_idx)?;
+    record_last_fighter_standing(combat, fighter_count);
     Ok(())
 }
 
@@ -283,7 +857,7 @@ fn fighter_in_rumble(rumble: &Rumble, fighter: &Pubkey) -> Option<usize> {
 
 #[cfg(feature = "combat")]
 fn is_valid_move_code(move_code: u8) -> bool {
-    move_code <= 8
+    move_code <= MOVE_FEINT
 }
 
 #[cfg(feature = "combat")]
@@ -322,6 +896,70 @@ fn hash_u64(parts: &[&[u8]]) -> u64 {
     u64::from_le_bytes(bytes)
 }
 
+/// Pair the fighters alive at turn open. Order comes from
+/// `hash_u64("pair-order", [vrf_seed,] rumble_id, turn, fighter)`, with ties
+/// going to the faster fighter, and consecutive fighters duel; an odd one out
+/// gets a bye.
+#[cfg(feature = "combat")]
+fn compute_turn_pairings(
+    rumble: &Rumble,
+    combat: &RumbleCombatState,
+    attributes: &[FighterAttributes; MAX_FIGHTERS],
+    turn: u32,
+) -> [u8; MAX_FIGHTERS] {
+    let rumble_id_bytes = rumble.id.to_le_bytes();
+    let turn_bytes = turn.to_le_bytes();
+    let vrf_seed_ref = &combat.vrf_seed;
+    let mut alive_order_keys: Vec<(usize, u64, [u8; 32])> = (0..combat.fighter_count as usize)
+        .filter(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
+        .map(|idx| {
+            let fighter_bytes = rumble.fighters[idx].to_bytes();
+            let pair_key = if *vrf_seed_ref != [0u8; 32] {
+                hash_u64(&[
+                    b"pair-order",
+                    vrf_seed_ref.as_ref(),
+                    rumble_id_bytes.as_ref(),
+                    turn_bytes.as_ref(),
+                    fighter_bytes.as_ref(),
+                ])
+            } else {
+                hash_u64(&[
+                    b"pair-order",
+                    rumble_id_bytes.as_ref(),
+                    turn_bytes.as_ref(),
+                    fighter_bytes.as_ref(),
+                ])
+            };
+            (idx, pair_key, fighter_bytes)
+        })
+        .collect();
+    alive_order_keys.sort_by(|a, b| {
+        a.1.cmp(&b.1)
+            .then_with(|| attributes[b.0].speed.cmp(&attributes[a.0].speed))
+            .then_with(|| a.2.cmp(&b.2))
+    });
+
+    let mut pairings = [PAIRING_BYE; MAX_FIGHTERS];
+    for chunk in alive_order_keys.chunks(2) {
+        if let [(idx_a, _, _), (idx_b, _, _)] = chunk {
+            pairings[*idx_a] = *idx_b as u8;
+            pairings[*idx_b] = *idx_a as u8;
+        }
+    }
+    pairings
+}
+
+/// A posted duel must match the pairing stored when the turn opened.
+#[cfg(feature = "combat")]
+fn validate_duel_pairing(combat: &RumbleCombatState, idx_a: usize, idx_b: usize) -> Result<()> {
+    require!(
+        combat.turn_pairings[idx_a] as usize == idx_b
+            && combat.turn_pairings[idx_b] as usize == idx_a,
+        RumbleError::PairingMismatch
+    );
+    Ok(())
+}
+
 #[cfg(feature = "combat")]
 fn is_strike(move_code: u8) -> bool {
     move_code == MOVE_HIGH_STRIKE || move_code == MOVE_MID_STRIKE || move_code == MOVE_LOW_STRIKE
@@ -343,17 +981,217 @@ fn guard_for_strike(move_code: u8) -> Option<u8> {
 }
 
 #[cfg(feature = "combat")]
-fn strike_damage(move_code: u8) -> u16 {
+fn strike_damage(ruleset: &CombatRuleset, move_code: u8) -> u16 {
     match move_code {
-        MOVE_HIGH_STRIKE => STRIKE_DAMAGE_HIGH,
-        MOVE_MID_STRIKE => STRIKE_DAMAGE_MID,
-        MOVE_LOW_STRIKE => STRIKE_DAMAGE_LOW,
+        MOVE_HIGH_STRIKE => ruleset.strike_damage_high,
+        MOVE_MID_STRIKE => ruleset.strike_damage_mid,
+        MOVE_LOW_STRIKE => ruleset.strike_damage_low,
         _ => 0,
     }
 }
 
+/// Strike damage scaled by the attacker's strength (+5% per point) and reduced
+/// by the defender's defense (-2 per point), never below 1. Brawlers add a
+/// flat bonus on top.
+#[cfg(feature = "combat")]
+fn attribute_strike_damage(
+    ruleset: &CombatRuleset,
+    move_code: u8,
+    attacker: &FighterAttributes,
+    defender: &FighterAttributes,
+) -> u16 {
+    let boosted =
+        strike_damage(ruleset, move_code) as u32 * (100 + attacker.strength as u32 * 5) / 100;
+    let damage = boosted
+        .saturating_sub(defender.defense as u32 * 2)
+        .clamp(1, u16::MAX as u32) as u16;
+    if attacker.specialization == Some(Specialization::Brawler) {
+        damage.saturating_add(BRAWLER_STRIKE_BONUS)
+    } else {
+        damage
+    }
+}
+
+/// Counter damage dealt by a correct guard; Duelists counter for double.
+#[cfg(feature = "combat")]
+fn counter_damage(ruleset: &CombatRuleset, counterer: &FighterAttributes) -> u16 {
+    if counterer.specialization == Some(Specialization::Duelist) {
+        ruleset.counter_damage.saturating_mul(2)
+    } else {
+        ruleset.counter_damage
+    }
+}
+
+/// Special damage taken; Guardians take half.
+#[cfg(feature = "combat")]
+fn special_damage(ruleset: &CombatRuleset, defender: &FighterAttributes) -> u16 {
+    if defender.specialization == Some(Specialization::Guardian) {
+        ruleset.special_damage / 2
+    } else {
+        ruleset.special_damage
+    }
+}
+
+/// Damage a Feint deals, keyed on the opponent's previous move: it punishes a
+/// striker and slips past a guard, but a dodger or catcher is not fooled.
+#[cfg(feature = "combat")]
+fn feint_damage(ruleset: &CombatRuleset, opponent_last_move: u8) -> u16 {
+    if is_strike(opponent_last_move) {
+        ruleset.counter_damage.saturating_add(FEINT_COUNTER_BONUS)
+    } else if is_guard(opponent_last_move) {
+        ruleset.strike_damage_mid
+    } else {
+        0
+    }
+}
+
+/// Whether `attacker`'s strike on `defender` this turn is a critical hit.
 #[cfg(feature = "combat")]
-fn fallback_move_code(rumble_id: u64, turn: u32, fighter: &Pubkey, meter: u8) -> u8 {
+fn strike_is_crit(
+    rumble_id: u64,
+    turn: u32,
+    attacker: &Pubkey,
+    defender: &Pubkey,
+    seed: u64,
+) -> bool {
+    let rumble_id_bytes = rumble_id.to_le_bytes();
+    let turn_bytes = turn.to_le_bytes();
+    let seed_bytes = seed.to_le_bytes();
+    hash_u64(&[
+        b"crit",
+        rumble_id_bytes.as_ref(),
+        turn_bytes.as_ref(),
+        attacker.as_ref(),
+        defender.as_ref(),
+        seed_bytes.as_ref(),
+    ]) % 100
+        < CRIT_CHANCE_PERCENT
+}
+
+/// Crit flags for both sides of a duel. Only strikes crit, and only in rumbles
+/// created with `crits_enabled`; the rolls use `combat.crit_seed`, fixed when
+/// the turn opened, so `resolve_turn` and `post_turn_result` always agree.
+#[cfg(feature = "combat")]
+fn duel_crits(
+    rumble: &Rumble,
+    combat: &RumbleCombatState,
+    turn: u32,
+    (idx_a, move_a): (usize, u8),
+    (idx_b, move_b): (usize, u8),
+) -> (bool, bool) {
+    if !rumble.crits_enabled {
+        return (false, false);
+    }
+    let fighter_a = &rumble.fighters[idx_a];
+    let fighter_b = &rumble.fighters[idx_b];
+    let seed = combat.crit_seed;
+    (
+        is_strike(move_a) && strike_is_crit(rumble.id, turn, fighter_a, fighter_b, seed),
+        is_strike(move_b) && strike_is_crit(rumble.id, turn, fighter_b, fighter_a, seed),
+    )
+}
+
+/// Crit seed from raw SlotHashes sysvar data: a u64 entry count followed by
+/// `(slot: u64, hash: [u8; 32])` entries, newest first.
+#[cfg(feature = "combat")]
+fn slot_hash_crit_seed(data: &[u8]) -> Result<u64> {
+    require!(data.len() >= 48, RumbleError::MissingSlotHashes);
+    let mut count = [0u8; 8];
+    count.copy_from_slice(&data[..8]);
+    require!(u64::from_le_bytes(count) > 0, RumbleError::MissingSlotHashes);
+    Ok(hash_u64(&[b"crit-seed", &data[16..48]]))
+}
+
+/// Crit seed for a turn being opened: the most recent slot hash, which nobody
+/// knew before this transaction landed. 0 for rumbles without crits.
+#[cfg(feature = "combat")]
+fn turn_crit_seed(rumble: &Rumble, slot_hashes: Option<&UncheckedAccount>) -> Result<u64> {
+    if !rumble.crits_enabled {
+        return Ok(0);
+    }
+    let slot_hashes = slot_hashes.ok_or(RumbleError::MissingSlotHashes)?;
+    let data = slot_hashes.try_borrow_data()?;
+    slot_hash_crit_seed(&data)
+}
+
+/// Bonus damage on a fighter's landed strikes: +1 per SOL in its betting pool,
+/// capped at `MAX_AUDIENCE_BOOST`.
+#[cfg(feature = "combat")]
+fn audience_boost(rumble: &Rumble, fighter_idx: usize) -> u16 {
+    (rumble.betting_pools[fighter_idx] / AUDIENCE_BOOST_LAMPORTS_PER_POINT).min(MAX_AUDIENCE_BOOST)
+        as u16
+}
+
+/// Damage from a strike that got through: doubled against a broken guard and
+/// raised by half on a crit.
+#[cfg(feature = "combat")]
+fn landed_strike_damage(
+    ruleset: &CombatRuleset,
+    move_code: u8,
+    attacker: &FighterAttributes,
+    defender: &FighterAttributes,
+    guard_broken: bool,
+    crit: bool,
+) -> u16 {
+    let mut damage = attribute_strike_damage(ruleset, move_code, attacker, defender);
+    if guard_broken {
+        damage = damage.saturating_mul(2);
+    }
+    if crit {
+        damage = (damage as u32 * CRIT_DAMAGE_PERCENT / 100).min(u16::MAX as u32) as u16;
+    }
+    damage
+}
+
+/// Guard streak after a duel: a guard that blocks a strike extends it, taking
+/// damage or playing anything but a guard resets it.
+#[cfg(feature = "combat")]
+fn next_consecutive_guards(current: u8, own_move: u8, opponent_move: u8, damage_taken: u16) -> u8 {
+    if damage_taken > 0 || !is_guard(own_move) {
+        0
+    } else if guard_for_strike(opponent_move) == Some(own_move) {
+        current.saturating_add(1)
+    } else {
+        current
+    }
+}
+
+/// Whether an opponent's low strike connects with a fighter, given the
+/// fighter's guard streak before the duel.
+#[cfg(feature = "combat")]
+fn low_strike_lands(opponent_move: u8, own_move: u8, consecutive_guards: u8) -> bool {
+    opponent_move == MOVE_LOW_STRIKE
+        && own_move != MOVE_DODGE
+        && (own_move != MOVE_GUARD_LOW || consecutive_guards >= GUARD_BREAK_THRESHOLD)
+}
+
+/// Moves that come at the opponent, as opposed to guards and dodges.
+#[cfg(feature = "combat")]
+fn is_attack(move_code: u8) -> bool {
+    is_strike(move_code)
+        || move_code == MOVE_SPECIAL
+        || move_code == MOVE_CATCH
+        || move_code == MOVE_FEINT
+}
+
+/// Damage a dodger deals back to the attacker it evaded; only Tricksters do.
+#[cfg(feature = "combat")]
+fn dodge_damage(ruleset: &CombatRuleset, dodger: &FighterAttributes) -> u16 {
+    if dodger.specialization == Some(Specialization::Trickster) {
+        ruleset.counter_damage
+    } else {
+        0
+    }
+}
+
+#[cfg(feature = "combat")]
+fn fallback_move_code(
+    rumble_id: u64,
+    turn: u32,
+    fighter: &Pubkey,
+    meter: u8,
+    special_meter_cost: u8,
+) -> u8 {
     let rumble_id_bytes = rumble_id.to_le_bytes();
     let turn_bytes = turn.to_le_bytes();
     let roll = hash_u64(&[
@@ -363,7 +1201,7 @@ fn fallback_move_code(rumble_id: u64, turn: u32, fighter: &Pubkey, meter: u8) ->
         fighter.as_ref(),
     ]) % 100;
 
-    if meter >= SPECIAL_METER_COST && roll < 15 {
+    if meter >= special_meter_cost && roll < 15 {
         return MOVE_SPECIAL;
     }
 
@@ -412,26 +1250,44 @@ fn apply_final_duel_sudden_death(damage_to_a: &mut u16, damage_to_b: &mut u16) {
     }
 }
 
+/// `last_move_a`/`last_move_b` are each fighter's move from their previous
+/// duel (`NO_LAST_MOVE` if none) and only matter to a Feint. A fighter whose
+/// `consecutive_guards` has reached `GUARD_BREAK_THRESHOLD` takes double damage
+/// from the next strike, whatever they guard. `crit_a`/`crit_b` add half again
+/// to a fighter's strike if it lands, and `audience_boost_a`/`audience_boost_b` are
+/// then added to it. Damage and meter cost come from the rumble's `ruleset`.
 #[cfg(feature = "combat")]
+#[allow(clippy::too_many_arguments)]
 fn resolve_duel(
     move_a: u8,
     move_b: u8,
     meter_a: u8,
     meter_b: u8,
+    last_move_a: u8,
+    last_move_b: u8,
+    consecutive_guards_a: u8,
+    consecutive_guards_b: u8,
+    crit_a: bool,
+    crit_b: bool,
+    audience_boost_a: u16,
+    audience_boost_b: u16,
+    attributes_a: &FighterAttributes,
+    attributes_b: &FighterAttributes,
     sudden_death_active: bool,
+    ruleset: &CombatRuleset,
 ) -> (u16, u16, u8, u8) {
     let mut damage_to_a: u16 = 0;
     let mut damage_to_b: u16 = 0;
     let mut meter_used_a: u8 = 0;
     let mut meter_used_b: u8 = 0;
 
-    let a_special = move_a == MOVE_SPECIAL && meter_a >= SPECIAL_METER_COST;
-    let b_special = move_b == MOVE_SPECIAL && meter_b >= SPECIAL_METER_COST;
+    let a_special = move_a == MOVE_SPECIAL && meter_a >= ruleset.special_meter_cost;
+    let b_special = move_b == MOVE_SPECIAL && meter_b >= ruleset.special_meter_cost;
     if a_special {
-        meter_used_a = SPECIAL_METER_COST;
+        meter_used_a = ruleset.special_meter_cost;
     }
     if b_special {
-        meter_used_b = SPECIAL_METER_COST;
+        meter_used_b = ruleset.special_meter_cost;
     }
 
     let effective_a = if move_a == MOVE_SPECIAL && !a_special {
@@ -448,38 +1304,64 @@ fn resolve_duel(
     // A attacks B
     if effective_a == MOVE_SPECIAL {
         if effective_b != MOVE_DODGE {
-            damage_to_b = SPECIAL_DAMAGE;
+            damage_to_b = special_damage(ruleset, attributes_b);
+        } else {
+            damage_to_a = dodge_damage(ruleset, attributes_b);
         }
     } else if effective_a == MOVE_CATCH {
         if effective_b == MOVE_DODGE {
-            damage_to_b = CATCH_DAMAGE;
+            damage_to_b = ruleset.catch_damage;
         }
+    } else if effective_a == MOVE_FEINT {
+        damage_to_b = feint_damage(ruleset, last_move_b);
     } else if is_strike(effective_a) {
+        let guard_broken = consecutive_guards_b >= GUARD_BREAK_THRESHOLD;
         if effective_b == MOVE_DODGE {
-            // dodged
-        } else if guard_for_strike(effective_a) == Some(effective_b) {
-            damage_to_a = COUNTER_DAMAGE;
+            damage_to_a = dodge_damage(ruleset, attributes_b);
+        } else if !guard_broken && guard_for_strike(effective_a) == Some(effective_b) {
+            damage_to_a = counter_damage(ruleset, attributes_b);
         } else {
-            damage_to_b = strike_damage(effective_a);
+            damage_to_b = landed_strike_damage(
+                ruleset,
+                effective_a,
+                attributes_a,
+                attributes_b,
+                guard_broken,
+                crit_a,
+            )
+            .saturating_add(audience_boost_a);
         }
     }
 
     // B attacks A
     if effective_b == MOVE_SPECIAL {
         if effective_a != MOVE_DODGE {
-            damage_to_a = SPECIAL_DAMAGE;
+            damage_to_a = special_damage(ruleset, attributes_a);
+        } else {
+            damage_to_b = dodge_damage(ruleset, attributes_a);
         }
     } else if effective_b == MOVE_CATCH {
         if effective_a == MOVE_DODGE {
-            damage_to_a = CATCH_DAMAGE;
+            damage_to_a = ruleset.catch_damage;
         }
+    } else if effective_b == MOVE_FEINT {
+        damage_to_a = feint_damage(ruleset, last_move_a);
     } else if is_strike(effective_b) {
+        let guard_broken = consecutive_guards_a >= GUARD_BREAK_THRESHOLD;
         if effective_a == MOVE_DODGE {
-            // dodged
-        } else if guard_for_strike(effective_b) == Some(effective_a) {
-            damage_to_b = COUNTER_DAMAGE;
+            damage_to_b = dodge_damage(ruleset, attributes_a);
+        } else if !guard_broken && guard_for_strike(effective_b) == Some(effective_a) {
+            damage_to_b = counter_damage(ruleset, attributes_a);
         } else {
-            damage_to_a = strike_damage(effective_b);
+            damage_to_a = landed_strike_damage(
+                ruleset,
+                effective_b,
+                attributes_b,
+                attributes_a,
+                guard_broken,
+                crit_b,
+            )
+            .saturating_add(audience_boost_b);
         }
     }
 
@@ -491,7 +1373,18 @@ fn resolve_duel(
 }
 
 #[cfg(feature = "combat")]
-fn expected_move_commitment_pda(rumble_id: u64, fighter: &Pubkey, turn: u32) -> Pubkey {
+fn expected_move_commitment_pda(rumble_id: u64, fighter: &Pubkey) -> Pubkey {
+    let rumble_id_bytes = rumble_id.to_le_bytes();
+    let (pda, _bump) = Pubkey::find_program_address(
+        &[MOVE_COMMIT_SEED, rumble_id_bytes.as_ref(), fighter.as_ref()],
+        &crate::ID,
+    );
+    pda
+}
+
+/// Per-turn MoveCommitment PDA used before commitments were reused across turns.
+#[cfg(feature = "combat")]
+fn expected_legacy_move_commitment_pda(rumble_id: u64, fighter: &Pubkey, turn: u32) -> Pubkey {
     let rumble_id_bytes = rumble_id.to_le_bytes();
     let turn_bytes = turn.to_le_bytes();
     let (pda, _bump) = Pubkey::find_program_address(
@@ -520,11 +1413,33 @@ fn validate_fighter_delegate_authority(
     delegate: &FighterDelegate,
     fighter: &Pubkey,
     authority: &Pubkey,
+    slot: u64,
 ) -> Result<()> {
     require!(delegate.fighter == *fighter, RumbleError::Unauthorized);
     require!(delegate.authority == *authority, RumbleError::Unauthorized);
     require!(!delegate.revoked, RumbleError::FighterDelegateRevoked);
-    Ok(())
+    require!(
+        delegate.expires_at_slot == 0 || slot <= delegate.expires_at_slot,
+        RumbleError::FighterDelegateExpired
+    );
+    Ok(())
+}
+
+/// Parse a FighterDelegate, reading pre-expiry (`FIGHTER_DELEGATE_V1_LEN`)
+/// accounts as never expiring.
+#[cfg(feature = "combat")]
+fn parse_fighter_delegate(data: &[u8]) -> Result<FighterDelegate> {
+    require!(
+        data.len() >= FIGHTER_DELEGATE_V1_LEN,
+        RumbleError::InvalidFighterDelegate
+    );
+    let mut padded = data.to_vec();
+    if padded.len() < 8 + FighterDelegate::INIT_SPACE {
+        padded.resize(8 + FighterDelegate::INIT_SPACE, 0);
+    }
+    let mut slice: &[u8] = &padded;
+    FighterDelegate::try_deserialize(&mut slice)
+        .map_err(|_| error!(RumbleError::InvalidFighterDelegate))
 }
 
 #[cfg(feature = "combat")]
@@ -547,23 +1462,517 @@ fn assert_move_authority(
         return err!(RumbleError::InvalidFighterDelegate);
     }
 
-    let mut slice: &[u8] = &data;
-    let parsed = FighterDelegate::try_deserialize(&mut slice)
-        .map_err(|_| error!(RumbleError::InvalidFighterDelegate))?;
-    validate_fighter_delegate_authority(&parsed, fighter, authority)
+    let parsed = parse_fighter_delegate(&data)?;
+    validate_fighter_delegate_authority(&parsed, fighter, authority, Clock::get()?.slot)
+}
+
+/// Mirror of fighter-registry's `Specialization`; variant order matches its
+/// Borsh encoding.
+#[cfg(feature = "combat")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Specialization {
+    Brawler,
+    Duelist,
+    Guardian,
+    Trickster,
+}
+
+/// Combat attributes from the fighter-registry `Fighter` account.
+#[cfg(feature = "combat")]
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct FighterAttributes {
+    pub strength: u8,
+    pub speed: u8,
+    pub defense: u8,
+    pub specialization: Option<Specialization>,
+    pub level: u8,
+    pub wins: u64,
+    /// `current_streak` when positive; 0 on a loss streak.
+    pub win_streak: u64,
+}
+
+/// `FighterAttributes` as stored in the zero-copy `RumbleCombatState`.
+#[cfg(feature = "combat")]
+#[zero_copy]
+pub struct PackedFighterAttributes {
+    pub wins: u64,          // 8
+    pub win_streak: u64,    // 8
+    pub strength: u8,       // 1
+    pub speed: u8,          // 1
+    pub defense: u8,        // 1
+    pub specialization: u8, // 1 (0 = none, else `Specialization` + 1)
+    pub level: u8,          // 1
+    pub _padding: [u8; 3],  // 3
+}
+
+#[cfg(feature = "combat")]
+impl From<FighterAttributes> for PackedFighterAttributes {
+    fn from(attrs: FighterAttributes) -> Self {
+        Self {
+            wins: attrs.wins,
+            win_streak: attrs.win_streak,
+            strength: attrs.strength,
+            speed: attrs.speed,
+            defense: attrs.defense,
+            specialization: attrs.specialization.map_or(0, |class| class as u8 + 1),
+            level: attrs.level,
+            _padding: [0; 3],
+        }
+    }
+}
+
+#[cfg(feature = "combat")]
+impl From<PackedFighterAttributes> for FighterAttributes {
+    fn from(packed: PackedFighterAttributes) -> Self {
+        let specialization = match packed.specialization {
+            1 => Some(Specialization::Brawler),
+            2 => Some(Specialization::Duelist),
+            3 => Some(Specialization::Guardian),
+            4 => Some(Specialization::Trickster),
+            _ => None,
+        };
+        Self {
+            strength: packed.strength,
+            speed: packed.speed,
+            defense: packed.defense,
+            specialization,
+            level: packed.level,
+            wins: packed.wins,
+            win_streak: packed.win_streak,
+        }
+    }
+}
+
+/// Parse attributes from raw `Fighter` account data. Accounts registered before
+/// attributes (or levels) existed are too short and read those as zero.
+#[cfg(feature = "combat")]
+fn parse_fighter_attributes(data: &[u8]) -> Option<FighterAttributes> {
+    if data.get(..8) != Some(FIGHTER_ACCOUNT_DISCRIMINATOR.as_ref()) {
+        return None;
+    }
+    let read_u64 = |offset: usize| {
+        data.get(offset..offset + 8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+    };
+    let wins = read_u64(FIGHTER_WINS_OFFSET)?;
+    let win_streak = (read_u64(FIGHTER_CURRENT_STREAK_OFFSET)? as i64).max(0) as u64;
+    let option_len = match *data.get(FIGHTER_QUEUE_POSITION_OFFSET)? {
+        0 => 1,
+        1 => 9,
+        _ => return None,
+    };
+    let offset = FIGHTER_QUEUE_POSITION_OFFSET + option_len + FIGHTER_ATTRIBUTES_GAP;
+    let Some(&[strength, speed, defense]) = data.get(offset..offset + 3) else {
+        return Some(FighterAttributes {
+            wins,
+            win_streak,
+            ..FighterAttributes::default()
+        });
+    };
+    let specialization = match data.get(offset + 3..offset + 5) {
+        Some(&[1, 0]) => Some(Specialization::Brawler),
+        Some(&[1, 1]) => Some(Specialization::Duelist),
+        Some(&[1, 2]) => Some(Specialization::Guardian),
+        Some(&[1, 3]) => Some(Specialization::Trickster),
+        _ => None,
+    };
+    let specialization_len = if specialization.is_some() { 2 } else { 1 };
+    let level = data
+        .get(offset + 3 + specialization_len + FIGHTER_LEVEL_GAP)
+        .copied()
+        .unwrap_or(0);
+    Some(FighterAttributes {
+        strength,
+        speed,
+        defense,
+        specialization,
+        level,
+        wins,
+        win_streak,
+    })
+}
+
+/// Read the rumble fighters' registry accounts from `remaining_accounts` for
+/// the combat-start snapshot. A fighter with no account passed (e.g. one only
+/// registered in Supabase) fights on base stats, unless the rumble was created
+/// with `require_registered` so a keeper can't strip stats by leaving accounts
+/// out. A passed account must be a registry-owned `Fighter`.
+#[cfg(feature = "combat")]
+fn snapshot_fighter_attributes(
+    remaining_accounts: &[AccountInfo<'_>],
+    rumble: &Rumble,
+) -> Result<[FighterAttributes; MAX_FIGHTERS]> {
+    let mut attributes = [FighterAttributes::default(); MAX_FIGHTERS];
+    for (idx, fighter) in rumble.fighters[..rumble.fighter_count as usize]
+        .iter()
+        .enumerate()
+    {
+        let Some(info) = remaining_accounts.iter().find(|acc| acc.key == fighter) else {
+            require!(
+                !rumble.registration_required,
+                RumbleError::FighterNotRegistered
+            );
+            continue;
+        };
+        require!(
+            *info.owner == FIGHTER_REGISTRY_PROGRAM_ID,
+            RumbleError::InvalidFighterAccount
+        );
+        let data = info.try_borrow_data()?;
+        attributes[idx] =
+            parse_fighter_attributes(&data).ok_or(RumbleError::InvalidFighterAccount)?;
+    }
+    Ok(attributes)
+}
+
+/// Whether raw `Fighter` account data has `retired` set. It follows `level`;
+/// accounts from before retirement existed are too short and read as active.
+fn fighter_is_retired(data: &[u8]) -> bool {
+    let option_len = match data.get(FIGHTER_QUEUE_POSITION_OFFSET) {
+        Some(0) => 1,
+        Some(1) => 9,
+        _ => return false,
+    };
+    let specialization_offset =
+        FIGHTER_QUEUE_POSITION_OFFSET + option_len + FIGHTER_ATTRIBUTES_GAP + 3;
+    let specialization_len = match data.get(specialization_offset) {
+        Some(1) => 2,
+        _ => 1,
+    };
+    let retired_offset = specialization_offset + specialization_len + FIGHTER_LEVEL_GAP + 1;
+    data.get(retired_offset) == Some(&1)
+}
+
+/// Check the fighter-registry accounts passed to `create_rumble`. Each entry in
+/// `fighters` is the fighter's registry `Fighter` PDA (not its authority), so
+/// the matching remaining account is the one with that key. A passed account
+/// must be a registry-owned, unretired `Fighter`; a fighter with no account
+/// passed is only an error when `require_registered` is set.
+fn validate_fighter_registrations(
+    remaining_accounts: &[AccountInfo<'_>],
+    fighters: &[Pubkey],
+    require_registered: bool,
+) -> Result<()> {
+    for fighter in fighters {
+        let Some(info) = remaining_accounts.iter().find(|acc| acc.key == fighter) else {
+            require!(!require_registered, RumbleError::FighterNotRegistered);
+            continue;
+        };
+        require!(
+            *info.owner == FIGHTER_REGISTRY_PROGRAM_ID,
+            RumbleError::InvalidFighterAccount
+        );
+        let data = info.try_borrow_data()?;
+        require!(
+            data.get(..8) == Some(FIGHTER_ACCOUNT_DISCRIMINATOR.as_ref()),
+            RumbleError::InvalidFighterAccount
+        );
+        require!(!fighter_is_retired(&data), RumbleError::FighterRetired);
+    }
+    Ok(())
+}
+
+/// Parse `(authority, wins, fighter_index, bump)` from raw `Fighter` account data.
+fn parse_fighter_wins_and_seeds(data: &[u8]) -> Option<(Pubkey, u64, u8, u8)> {
+    if data.get(..8) != Some(FIGHTER_ACCOUNT_DISCRIMINATOR.as_ref()) {
+        return None;
+    }
+    let authority = Pubkey::try_from(data.get(8..40)?).ok()?;
+    let wins = u64::from_le_bytes(
+        data.get(FIGHTER_WINS_OFFSET..FIGHTER_WINS_OFFSET + 8)?
+            .try_into()
+            .ok()?,
+    );
+    let option_len = match *data.get(FIGHTER_QUEUE_POSITION_OFFSET)? {
+        0 => 1,
+        1 => 9,
+        _ => return None,
+    };
+    let offset = FIGHTER_QUEUE_POSITION_OFFSET + option_len + FIGHTER_INDEX_GAP;
+    let &[fighter_index, bump] = data.get(offset..offset + 2)? else {
+        return None;
+    };
+    Some((authority, wins, fighter_index, bump))
+}
+
+/// Wins on a fighter-registry `Fighter` account, after checking the account is
+/// registry-owned and sits at the PDA its own authority, index and bump derive.
+fn registry_fighter_wins(info: &AccountInfo<'_>) -> Result<u64> {
+    require!(
+        *info.owner == FIGHTER_REGISTRY_PROGRAM_ID,
+        RumbleError::InvalidFighterAccount
+    );
+    let data = info.try_borrow_data()?;
+    let (authority, wins, fighter_index, bump) =
+        parse_fighter_wins_and_seeds(&data).ok_or(RumbleError::InvalidFighterAccount)?;
+    let expected = Pubkey::create_program_address(
+        &[FIGHTER_SEED, authority.as_ref(), &[fighter_index], &[bump]],
+        &FIGHTER_REGISTRY_PROGRAM_ID,
+    )
+    .map_err(|_| error!(RumbleError::InvalidFighterAccount))?;
+    require_keys_eq!(expected, *info.key, RumbleError::InvalidFighterAccount);
+    Ok(wins)
+}
+
+/// Record `wins` for `fighter` on a leaderboard sorted by wins, highest first.
+/// A listed fighter is updated in place; a new one replaces the last entry only
+/// with strictly more wins. Returns the fighter's 0-based rank if it placed.
+fn insert_leaderboard_entry(
+    entries: &mut [LeaderboardEntry; LEADERBOARD_SIZE],
+    fighter: Pubkey,
+    wins: u64,
+) -> Option<usize> {
+    let mut idx = match entries.iter().position(|entry| entry.fighter == fighter) {
+        Some(idx) => idx,
+        None if wins > entries[LEADERBOARD_SIZE - 1].wins => LEADERBOARD_SIZE - 1,
+        None => return None,
+    };
+    entries[idx] = LeaderboardEntry { fighter, wins };
+    while idx > 0 && entries[idx - 1].wins < wins {
+        entries.swap(idx - 1, idx);
+        idx -= 1;
+    }
+    while idx + 1 < LEADERBOARD_SIZE && entries[idx + 1].wins > wins {
+        entries.swap(idx, idx + 1);
+        idx += 1;
+    }
+    Some(idx)
+}
+
+/// fighter-registry accounts needed to CPI into it as our `registry_authority`
+/// PDA, taken from `remaining_accounts`.
+struct RegistryCpi<'a, 'info> {
+    program: &'a AccountInfo<'info>,
+    authority: &'a AccountInfo<'info>,
+    config: &'a AccountInfo<'info>,
+    authority_bump: u8,
+}
+
+impl<'a, 'info> RegistryCpi<'a, 'info> {
+    /// Opt-in: `None` unless the registry program is passed, in which case its
+    /// config PDA and our authority PDA must be passed as well.
+    fn from_remaining_accounts(
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Option<Self>> {
+        let find = |key: &Pubkey| remaining_accounts.iter().find(|acc| acc.key == key);
+        let Some(program) = find(&FIGHTER_REGISTRY_PROGRAM_ID) else {
+            return Ok(None);
+        };
+        let (authority_key, authority_bump) =
+            Pubkey::find_program_address(&[REGISTRY_AUTHORITY_SEED], &crate::ID);
+        let (config_key, _bump) = Pubkey::find_program_address(
+            &[FIGHTER_REGISTRY_CONFIG_SEED],
+            &FIGHTER_REGISTRY_PROGRAM_ID,
+        );
+        Ok(Some(Self {
+            program,
+            authority: find(&authority_key).ok_or(RumbleError::RegistryAccountsMissing)?,
+            config: find(&config_key).ok_or(RumbleError::RegistryAccountsMissing)?,
+            authority_bump,
+        }))
+    }
+
+    /// Call a registry instruction whose accounts are
+    /// `(authority, registry_config, fighter)`.
+    fn invoke(&self, fighter: &AccountInfo<'info>, data: Vec<u8>) -> Result<()> {
+        let ix = Instruction {
+            program_id: FIGHTER_REGISTRY_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(*self.authority.key, true),
+                AccountMeta::new_readonly(*self.config.key, false),
+                AccountMeta::new(*fighter.key, false),
+            ],
+            data,
+        };
+        invoke_signed(
+            &ix,
+            &[
+                self.authority.clone(),
+                self.config.clone(),
+                fighter.clone(),
+                self.program.clone(),
+            ],
+            &[&[REGISTRY_AUTHORITY_SEED, &[self.authority_bump]]],
+        )?;
+        Ok(())
+    }
+}
+
+/// A fighter's registry `Fighter` account in `remaining_accounts`, if passed.
+fn registry_fighter_account<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    fighter: &Pubkey,
+) -> Option<&'a AccountInfo<'info>> {
+    remaining_accounts
+        .iter()
+        .find(|acc| acc.key == fighter && *acc.owner == FIGHTER_REGISTRY_PROGRAM_ID)
+}
+
+/// Set or clear fighter-registry's `Fighter.in_rumble` for each fighter whose
+/// registry account is in `remaining_accounts` (see `RegistryCpi`). Fighters
+/// without a registry account are skipped.
+fn set_fighters_in_rumble<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    fighters: &[Pubkey],
+    in_rumble: bool,
+) -> Result<()> {
+    let Some(registry) = RegistryCpi::from_remaining_accounts(remaining_accounts)? else {
+        return Ok(());
+    };
+    for fighter in fighters {
+        let Some(fighter_info) = registry_fighter_account(remaining_accounts, fighter) else {
+            continue;
+        };
+        let mut data = SET_IN_RUMBLE_DISCRIMINATOR.to_vec();
+        data.push(in_rumble as u8);
+        registry.invoke(fighter_info, data)?;
+    }
+    Ok(())
+}
+
+/// Push each registered fighter's result into fighter-registry's
+/// `update_record`: a win for the winner, a loss for everyone else, and the
+/// damage totals from combat. Same opt-in accounts as `set_fighters_in_rumble`.
+#[cfg(feature = "combat")]
+fn update_fighter_records<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    rumble: &Rumble,
+    combat: &RumbleCombatState,
+) -> Result<()> {
+    let Some(registry) = RegistryCpi::from_remaining_accounts(remaining_accounts)? else {
+        return Ok(());
+    };
+    for (idx, fighter) in rumble.fighters[..rumble.fighter_count as usize].iter().enumerate() {
+        let Some(fighter_info) = registry_fighter_account(remaining_accounts, fighter) else {
+            continue;
+        };
+        let won = idx == rumble.winner_index as usize;
+        registry.invoke(
+            fighter_info,
+            update_record_ix_data(
+                won,
+                combat.total_damage_dealt[idx],
+                combat.total_damage_taken[idx],
+                rumble.id,
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+/// Instruction data for `update_record(wins, losses, damage_dealt,
+/// damage_taken, ichor_mined, rumble_id, damage_taken_this_rumble)`. ICHOR is
+/// left to the backend.
+#[cfg(feature = "combat")]
+fn update_record_ix_data(
+    won: bool,
+    damage_dealt: u64,
+    damage_taken: u64,
+    rumble_id: u64,
+) -> Vec<u8> {
+    let mut data = UPDATE_RECORD_DISCRIMINATOR.to_vec();
+    let args = [won as u64, !won as u64, damage_dealt, damage_taken, 0, rumble_id, damage_taken];
+    for arg in args {
+        data.extend_from_slice(&arg.to_le_bytes());
+    }
+    data
+}
+
+/// Current turn of the rumble's on-chain combat, or 0 if its combat state was
+/// never created. The PDA address is fixed, so the owner is not checked: a
+/// combat state delegated to the Ephemeral Rollup still counts.
+#[cfg(feature = "combat")]
+fn onchain_combat_turn(combat_state: &AccountInfo<'_>) -> Result<u32> {
+    let data = combat_state.try_borrow_data()?;
+    if data.get(..8) != Some(RumbleCombatState::DISCRIMINATOR) {
+        return Ok(0);
+    }
+    if data.len() < COMBAT_STATE_LEN {
+        let legacy = LegacyRumbleCombatState::deserialize(&mut &data[8..])?;
+        return Ok(legacy.current_turn);
+    }
+    let combat: RumbleCombatState = bytemuck::pod_read_unaligned(&data[8..COMBAT_STATE_LEN]);
+    Ok(combat.current_turn)
+}
+
+/// Borrow a combat state, failing cleanly on one still in the borsh layout
+/// (which `AccountLoader` would slice past the end of) until
+/// `migrate_combat_state` converts it.
+#[cfg(feature = "combat")]
+fn load_combat<'a>(
+    combat_state: &'a AccountLoader<'_, RumbleCombatState>,
+) -> Result<Ref<'a, RumbleCombatState>> {
+    require!(
+        combat_state.to_account_info().data_len() >= COMBAT_STATE_LEN,
+        RumbleError::CombatStateNotMigrated
+    );
+    combat_state.load()
+}
+
+/// Mutable `load_combat`.
+#[cfg(feature = "combat")]
+fn load_combat_mut<'a>(
+    combat_state: &'a AccountLoader<'_, RumbleCombatState>,
+) -> Result<RefMut<'a, RumbleCombatState>> {
+    require!(
+        combat_state.to_account_info().data_len() >= COMBAT_STATE_LEN,
+        RumbleError::CombatStateNotMigrated
+    );
+    combat_state.load_mut()
 }
 
+/// `load_combat_mut` for an `init_if_needed` combat state, which the context
+/// may have just created with no discriminator yet.
 #[cfg(feature = "combat")]
-fn read_revealed_move_from_remaining_accounts(
+fn load_or_init_combat<'a>(
+    combat_state: &'a AccountLoader<'_, RumbleCombatState>,
+) -> Result<RefMut<'a, RumbleCombatState>> {
+    let fresh = combat_state.to_account_info().try_borrow_data()?[..8] == [0u8; 8];
+    if fresh {
+        combat_state.load_init()
+    } else {
+        load_combat_mut(combat_state)
+    }
+}
+
+/// Without the combat feature no combat state can exist.
+#[cfg(not(feature = "combat"))]
+fn onchain_combat_turn(_combat_state: &AccountInfo<'_>) -> Result<u32> {
+    Ok(0)
+}
+
+/// Load a fighter's MoveCommitment for `turn` from `remaining_accounts`. The
+/// PDA must be passed even if the fighter never committed (it is then empty),
+/// so a keeper can't dodge the missed-reveal penalty by leaving it out.
+#[cfg(feature = "combat")]
+fn read_move_commitment_from_remaining_accounts(
     remaining_accounts: &[AccountInfo<'_>],
     rumble_id: u64,
     turn: u32,
     fighter: &Pubkey,
-) -> Option<u8> {
-    let expected_pda = expected_move_commitment_pda(rumble_id, fighter, turn);
+) -> Result<Option<MoveCommitment>> {
+    let expected_pda = expected_move_commitment_pda(rumble_id, fighter);
     let info = remaining_accounts
         .iter()
-        .find(|acc| *acc.key == expected_pda)?;
+        .find(|acc| *acc.key == expected_pda)
+        .ok_or(RumbleError::MoveCommitmentMissing)?;
+    Ok(parse_move_commitment_for_turn(
+        info, rumble_id, turn, fighter,
+    ))
+}
+
+/// Parse a passed MoveCommitment PDA; none if it was never created or holds
+/// another turn's commit.
+#[cfg(feature = "combat")]
+fn parse_move_commitment_for_turn(
+    info: &AccountInfo<'_>,
+    rumble_id: u64,
+    turn: u32,
+    fighter: &Pubkey,
+) -> Option<MoveCommitment> {
     if *info.owner != crate::ID || info.data_is_empty() {
         return None;
     }
@@ -577,10 +1986,105 @@ fn read_revealed_move_from_remaining_accounts(
     if parsed.rumble_id != rumble_id || parsed.turn != turn || parsed.fighter != *fighter {
         return None;
     }
-    if !parsed.revealed {
-        return None;
+    Some(parsed)
+}
+
+/// Reset meter and apply chip damage to a fighter that hid its reveal.
+/// Returns the chip damage actually taken.
+#[cfg(feature = "combat")]
+fn apply_missed_reveal_penalty(combat: &mut RumbleCombatState, idx: usize) -> Result<u16> {
+    let chip_damage = MISSED_REVEAL_CHIP_DAMAGE.min(combat.hp[idx]);
+    combat.meter[idx] = 0;
+    combat.hp[idx] -= chip_damage;
+    combat.total_damage_taken[idx] = combat.total_damage_taken[idx]
+        .checked_add(chip_damage as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.missed_reveals[idx] = combat.missed_reveals[idx].saturating_add(1);
+    Ok(chip_damage)
+}
+
+/// Penalize a fighter that committed this turn but never revealed, before its
+/// duel runs. Fighters that never committed just get the fallback move.
+#[cfg(feature = "combat")]
+fn penalize_missed_reveal(
+    rumble: &Rumble,
+    combat: &mut RumbleCombatState,
+    idx: usize,
+    commitment: Option<&MoveCommitment>,
+) -> Result<()> {
+    if !matches!(commitment, Some(c) if !c.revealed) {
+        return Ok(());
     }
-    Some(parsed.revealed_move)
+    let chip_damage = apply_missed_reveal_penalty(combat, idx)?;
+    emit!(RevealMissedEvent {
+        rumble_id: rumble.id,
+        turn: combat.current_turn,
+        fighter: rumble.fighters[idx],
+        chip_damage,
+        missed_reveals: combat.missed_reveals[idx],
+    });
+    Ok(())
+}
+
+/// Find the MoveCommitment PDA for `entry` in `remaining_accounts`: the
+/// fighter's PDA or its legacy per-turn PDA, owned by this program and
+/// storing `entry`'s rumble, fighter and turn.
+#[cfg(feature = "combat")]
+fn find_closable_move_commitment<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    rumble_id: u64,
+    entry: &MoveCommitmentKey,
+) -> Result<&'a AccountInfo<'info>> {
+    let expected_pda = expected_move_commitment_pda(rumble_id, &entry.fighter);
+    let legacy_pda = expected_legacy_move_commitment_pda(rumble_id, &entry.fighter, entry.turn);
+    let info = remaining_accounts
+        .iter()
+        .find(|acc| *acc.key == expected_pda || *acc.key == legacy_pda)
+        .ok_or(RumbleError::InvalidMoveCommitment)?;
+    require!(*info.owner == crate::ID, RumbleError::InvalidMoveCommitment);
+
+    let data = info.try_borrow_data()?;
+    let mut slice: &[u8] = &data;
+    let parsed = MoveCommitment::try_deserialize(&mut slice)
+        .map_err(|_| error!(RumbleError::InvalidMoveCommitment))?;
+    require!(
+        parsed.rumble_id == rumble_id
+            && parsed.fighter == entry.fighter
+            && parsed.turn == entry.turn,
+        RumbleError::InvalidMoveCommitment
+    );
+    Ok(info)
+}
+
+/// Identifies one MoveCommitment PDA for `close_move_commitments_batch`.
+#[cfg(feature = "combat")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MoveCommitmentKey {
+    pub fighter: Pubkey,
+    pub turn: u32,
+}
+
+/// Share of the distributable losers' pool paid to each of the top three places.
+/// Must sum to 10_000 bps. Places with a zero share are treated as losers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PayoutSplit {
+    pub first_bps: u64,
+    pub second_bps: u64,
+    pub third_bps: u64,
+}
+
+/// Tunable combat numbers for a `CombatRuleset`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct CombatRulesetParams {
+    pub strike_damage_high: u16,
+    pub strike_damage_mid: u16,
+    pub strike_damage_low: u16,
+    pub catch_damage: u16,
+    pub counter_damage: u16,
+    pub special_damage: u16,
+    pub meter_per_turn: u8,
+    pub special_meter_cost: u8,
+    pub start_hp: u16,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -591,6 +2095,10 @@ pub struct DuelResult {
     pub move_b: u8,
     pub damage_to_a: u16,
     pub damage_to_b: u16,
+    /// Must match the rolls from the turn's stored `crit_seed` (always false
+    /// unless the rumble has `crits_enabled`).
+    pub crit_a: bool,
+    pub crit_b: bool,
 }
 
 #[cfg_attr(feature = "combat", ephemeral)]
@@ -606,39 +2114,154 @@ pub mod rumble_engine {
         config.treasury = ctx.accounts.treasury.key();
         config.total_rumbles = 0;
         config.bump = ctx.bumps.config;
+        config.admin_fee_bps = ADMIN_FEE_BPS;
+        config.sponsorship_fee_bps = SPONSORSHIP_FEE_BPS;
+        config.paused = false;
+        config.commit_window_slots = COMMIT_WINDOW_SLOTS;
+        config.reveal_window_slots = REVEAL_WINDOW_SLOTS;
+        config.crank_tip_bps = 0;
+        config.slots_per_second_milli = DEFAULT_SLOTS_PER_SECOND_MILLI;
+        config.min_bettors = 0;
+        config.referral_fee_bps = REFERRAL_FEE_BPS;
+        config.ichor_burn_per_bet = 0;
+        config.keeper_fee_lamports = 0;
+        config.insurance_premium_bps = 0;
+        config.insurance_payout_bps = 0;
+        config.combat_timeout_slots = DEFAULT_COMBAT_TIMEOUT_SLOTS;
+
+        ctx.accounts.global_stats.bump = ctx.bumps.global_stats;
 
         msg!("Rumble engine initialized. Admin: {}", config.admin);
         Ok(())
     }
 
-    /// Create a new rumble with a list of fighters and an on-chain betting close slot.
-    /// `betting_deadline` is interpreted as a slot number for backward compatibility.
+    /// Admin: create the GlobalStats PDA on a deployment initialized before it
+    /// existed. Its counters start from zero.
+    pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::InitializeGlobalStats::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        ctx.accounts.global_stats.bump = ctx.bumps.global_stats;
+        msg!("Global stats initialized");
+        Ok(())
+    }
+
+    /// Admin: create the AdminLog PDA. Privileged calls require it, so they
+    /// fail until it exists; once created it is permanent (there is no close).
+    pub fn initialize_admin_log(ctx: Context<InitializeAdminLog>) -> Result<()> {
+        let clock = Clock::get()?;
+        let mut log = ctx.accounts.admin_log.load_init()?;
+        log.config = ctx.accounts.config.key();
+        log.bump = ctx.bumps.admin_log;
+        push_admin_log_entry(
+            &mut log,
+            admin_log_entry(
+                crate::instruction::InitializeAdminLog::DISCRIMINATOR,
+                ctx.accounts.admin.key(),
+                &clock,
+            ),
+        );
+        msg!("Admin log initialized");
+        Ok(())
+    }
+
+    /// Create a new rumble with a list of fighters and an on-chain betting deadline.
+    /// `betting_deadline` is a slot number unless `deadline_kind` is `UnixTimestamp`
+    /// (omitted = `Slot`, for backward compatibility). Either way it is stored as
+    /// `betting_close_slot`; timestamps are converted with the config's slot rate.
+    /// `payout_split` defaults to winner-takes-all when omitted.
+    /// `max_bet_per_bettor` caps each bettor's net stake in this rumble (0 = uncapped).
+    /// `min_pool_lamports` is the pool required to leave Betting (0 = no minimum).
+    /// The config's `min_bettors` is snapshotted as the distinct-bettor minimum.
+    /// `whitelist_enabled` makes the rumble invite-only: the `whitelist` PDA
+    /// must then be passed and is created empty for `add_to_whitelist`.
+    /// Fighters' registry `Fighter` PDAs may be passed as remaining accounts and
+    /// are validated if present; `require_registered` makes them mandatory,
+    /// here and again at `start_combat`.
+    /// Passing the registry program, its config and our `registry_authority`
+    /// PDA as well marks those fighters `in_rumble` (writable fighter accounts).
+    /// A non-zero `token_gate` requires `token_account` to hold at least that
+    /// much ICHOR; it is checked, not spent, and stored on the rumble.
+    /// `fighter_slots` = 32 creates the rumble on the v2 layout, which takes up
+    /// to `MAX_FIGHTERS`; omitted (or 16) keeps the original 16-slot layout.
+    /// Fighters that don't fit in one transaction go in with
+    /// `append_rumble_fighters`.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_rumble(
         ctx: Context<CreateRumble>,
         rumble_id: u64,
         fighters: Vec<Pubkey>,
         betting_deadline: i64,
+        payout_split: Option<PayoutSplit>,
+        max_bet_per_bettor: u64,
+        min_pool_lamports: u64,
+        deadline_kind: Option<DeadlineKind>,
+        record_bets: bool,
+        require_registered: bool,
+        whitelist_enabled: bool,
+        crits_enabled: bool,
+        token_gate: Option<u64>,
+        claim_window_seconds: Option<i64>,
+        fighter_slots: Option<u8>,
     ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CreateRumble::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        require_not_paused(&ctx.accounts.config)?;
+        let token_gate = token_gate.unwrap_or(0);
+        let claim_window_seconds = claim_window_seconds.unwrap_or(PAYOUT_CLAIM_WINDOW_SECONDS);
+        validate_claim_window(claim_window_seconds)?;
+        check_token_gate(
+            token_gate,
+            ctx.accounts.token_account.as_ref().map(|account| account.amount),
+        )?;
+        require!(
+            ctx.accounts.whitelist.is_some() == whitelist_enabled,
+            RumbleError::WhitelistMismatch
+        );
+        let fighter_slots = requested_fighter_slots(fighter_slots)?;
         require!(
-            fighters.len() >= 2 && fighters.len() <= MAX_FIGHTERS,
+            fighters.len() >= 2 && fighters.len() <= fighter_slots,
             RumbleError::InvalidFighterCount
         );
 
+        let payout_split = payout_split.unwrap_or(PayoutSplit {
+            first_bps: FIRST_PLACE_BPS,
+            second_bps: SECOND_PLACE_BPS,
+            third_bps: THIRD_PLACE_BPS,
+        });
+        validate_payout_split(&payout_split)?;
+
         // Check for duplicate fighters
         let mut seen = std::collections::BTreeSet::new();
         for f in fighters.iter() {
             require!(seen.insert(f), RumbleError::DuplicateFighter);
         }
 
-        // NOTE: Fighter registry validation removed — fighters are registered
-        // in Supabase, not all have on-chain fighter_registry PDAs yet.
-        // TODO: Re-add once all fighters are registered on-chain.
+        // Fighters registered only in Supabase have no fighter_registry PDA yet,
+        // so registration is opt-in until the migration finishes.
+        validate_fighter_registrations(ctx.remaining_accounts, &fighters, require_registered)?;
+        set_fighters_in_rumble(ctx.remaining_accounts, &fighters, true)?;
 
         let clock = Clock::get()?;
+        let deadline_kind = deadline_kind.unwrap_or_default();
         require!(betting_deadline > 0, RumbleError::DeadlineInPast);
-        let betting_close_slot =
-            u64::try_from(betting_deadline).map_err(|_| error!(RumbleError::DeadlineInPast))?;
-        require!(betting_close_slot > clock.slot, RumbleError::DeadlineInPast);
+        require!(
+            betting_deadline > deadline_clock_value(deadline_kind, &clock)?,
+            RumbleError::DeadlineInPast
+        );
+        let betting_close_slot = estimate_close_slot(
+            deadline_kind,
+            betting_deadline,
+            &clock,
+            ctx.accounts.config.slots_per_second_milli,
+        )?;
 
         let rumble = &mut ctx.accounts.rumble;
         rumble.id = rumble_id;
@@ -656,33 +2279,177 @@ pub mod rumble_engine {
         rumble.total_deployed = 0;
         rumble.admin_fee_collected = 0;
         rumble.sponsorship_paid = 0;
+        rumble.sponsorship_by_fighter = [0u64; MAX_FIGHTERS];
         rumble.placements = [0u8; MAX_FIGHTERS];
         rumble.winner_index = 0;
         rumble.betting_deadline = betting_deadline;
         rumble.combat_started_at = 0;
         rumble.completed_at = 0;
         rumble.bump = ctx.bumps.rumble;
+        rumble.cancelled_at = 0;
+        rumble.first_place_bps = payout_split.first_bps;
+        rumble.second_place_bps = payout_split.second_bps;
+        rumble.third_place_bps = payout_split.third_bps;
+        rumble.max_bet_per_bettor = max_bet_per_bettor;
+        rumble.min_pool_lamports = min_pool_lamports;
+        rumble.underfunded = false;
+        rumble.deadline_kind = deadline_kind;
+        rumble.vault_rent_reserve = 0;
+        rumble.result_source = RESULT_SOURCE_UNSET;
+        rumble.record_bets = record_bets;
+        rumble.betting_close_slot = betting_close_slot;
+        rumble.unique_bettor_count = 0;
+        rumble.min_bettors = ctx.accounts.config.min_bettors;
+        rumble.prize_pool = 0;
+        rumble.prize_depositor = Pubkey::default();
+        rumble.registration_required = require_registered;
+        rumble.dispute_resolved = false;
+        rumble.fighter_slots = fighter_slots as u8;
+        rumble.whitelist_enabled = whitelist_enabled;
+        rumble.stake_bonus_reserve = 0;
+        rumble.combat_history_hash = [0u8; 32];
+        rumble.keeper_budget = 0;
+        rumble.ruleset = ctx
+            .accounts
+            .ruleset
+            .as_ref()
+            .map(|ruleset| ruleset.key())
+            .unwrap_or_default();
+        rumble.crits_enabled = crits_enabled;
+        rumble.token_gate = token_gate;
+        rumble.claim_window_seconds = claim_window_seconds;
+        rumble.insurance_payout_bps = ctx.accounts.config.insurance_payout_bps;
+
+        if let Some(whitelist) = ctx.accounts.whitelist.as_mut() {
+            whitelist.rumble_id = rumble_id;
+            whitelist.count = 0;
+            whitelist.bump = ctx.bumps.whitelist.ok_or(RumbleError::WhitelistMismatch)?;
+        }
+
+        if let Some(stats) = ctx.accounts.global_stats.as_mut() {
+            record_global_rumble(stats, fighters.len())?;
+        }
+
+        // Admin funds the keeper bounties up front, on top of the vault's rent reserve.
+        let keeper_budget = ctx
+            .accounts
+            .config
+            .keeper_fee_lamports
+            .checked_mul(KEEPER_BUDGET_CALLS)
+            .ok_or(RumbleError::MathOverflow)?;
+        if keeper_budget > 0 {
+            fund_vault_rent_reserve(
+                rumble,
+                &ctx.accounts.vault,
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            )?;
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                keeper_budget,
+            )?;
+            rumble.keeper_budget = keeper_budget;
+        }
+
+        msg!(
+            "Rumble {} created with {} fighters, betting closes at slot {}",
+            rumble_id,
+            fighters.len(),
+            betting_close_slot
+        );
+
+        emit!(RumbleCreatedEvent {
+            rumble_id,
+            fighter_count: rumble.fighter_count,
+            fighters,
+            betting_deadline,
+            betting_close_slot,
+        });
+        Ok(())
+    }
+
+    /// Read-only: confirm `token_account` still holds the ICHOR a token-gated
+    /// rumble was created against. Fails for rumbles created without a gate.
+    pub fn verify_rumble_gate(ctx: Context<VerifyRumbleGate>, _rumble_id: u64) -> Result<()> {
+        let token_gate = ctx.accounts.rumble.token_gate;
+        require!(token_gate > 0, RumbleError::RumbleNotTokenGated);
+        check_token_gate(token_gate, Some(ctx.accounts.token_account.amount))
+    }
+
+    /// Admin: add fighters to a rumble still in Betting with nothing staked
+    /// yet, up to its fighter slots. Lets a 32-slot rumble take fighters past
+    /// what fits in the `create_rumble` transaction. Registry accounts are
+    /// handled as in `create_rumble`, under the rumble's `require_registered`.
+    pub fn append_rumble_fighters(
+        ctx: Context<AppendRumbleFighters>,
+        rumble_id: u64,
+        fighters: Vec<Pubkey>,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::AppendRumbleFighters::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        require_not_paused(&ctx.accounts.config)?;
+        let rumble = &mut ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Betting,
+            RumbleError::BettingClosed
+        );
+        require_betting_open(rumble, &Clock::get()?)?;
+
+        validate_fighter_registrations(
+            ctx.remaining_accounts,
+            &fighters,
+            rumble.registration_required,
+        )?;
+        append_fighters(rumble, &fighters)?;
+        set_fighters_in_rumble(ctx.remaining_accounts, &fighters, true)?;
+
+        if let Some(stats) = ctx.accounts.global_stats.as_mut() {
+            record_global_fighters(stats, fighters.len())?;
+        }
 
         msg!(
-            "Rumble {} created with {} fighters",
+            "Rumble {} now has {} fighters",
             rumble_id,
-            fighters.len()
+            rumble.fighter_count
         );
+
+        emit!(RumbleFightersAppendedEvent {
+            rumble_id,
+            fighter_count: rumble.fighter_count,
+            fighters,
+        });
         Ok(())
     }
 
     /// Place a bet on a fighter in a rumble.
     /// Transfers SOL from bettor to treasury, sponsorship PDA, and vault.
-    /// Current upfront economics:
-    /// - 1% platform fee to treasury
-    /// - 1% fighter sponsorship to the selected fighter PDA
-    /// - 98% to the rumble betting pool
+    /// Upfront economics (rates from RumbleConfig, default 1% / 1%):
+    /// - `admin_fee_bps` platform fee to treasury, waived while the bettor holds
+    ///   an unexpired FeeExemption
+    /// - `sponsorship_fee_bps` fighter sponsorship to the selected fighter PDA
+    /// - remainder to the rumble betting pool
+    ///
+    /// An `insured` bet also pays `insurance_premium_bps` of `amount` on top,
+    /// into the rumble's insurance pool; it comes back with the stake if the
+    /// rumble refunds its bettors.
     pub fn place_bet(
         ctx: Context<PlaceBet>,
         rumble_id: u64,
         fighter_index: u8,
         amount: u64,
+        insured: bool,
     ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
         let rumble = &mut ctx.accounts.rumble;
 
         // Validate state
@@ -691,11 +2458,9 @@ pub mod rumble_engine {
             RumbleError::BettingClosed
         );
 
-        // Validate on-chain slot deadline
+        // Validate on-chain deadline
         let clock = Clock::get()?;
-        let betting_close_slot = u64::try_from(rumble.betting_deadline)
-            .map_err(|_| error!(RumbleError::BettingClosed))?;
-        require!(clock.slot < betting_close_slot, RumbleError::BettingClosed);
+        require_betting_open(rumble, &clock)?;
 
         // Validate fighter index
         require!(
@@ -706,27 +2471,120 @@ pub mod rumble_engine {
         // Validate amount
         require!(amount > 0, RumbleError::ZeroBetAmount);
 
-        // Calculate fees
-        let admin_fee = amount
-            .checked_mul(ADMIN_FEE_BPS)
-            .ok_or(RumbleError::MathOverflow)?
-            .checked_div(10_000)
-            .ok_or(RumbleError::MathOverflow)?;
+        // Per-slot bet cap, against bots flooding one slot with dust bets
+        let rate_limit = &mut ctx.accounts.rate_limit;
+        record_slot_bet(rate_limit, clock.slot)?;
+        rate_limit.bump = ctx.bumps.rate_limit;
+
+        // Invite-only rumbles
+        if rumble.whitelist_enabled {
+            let whitelist = ctx
+                .accounts
+                .whitelist
+                .as_ref()
+                .ok_or(RumbleError::BettorNotWhitelisted)?;
+            require!(
+                whitelist_contains(whitelist, &ctx.accounts.bettor.key()),
+                RumbleError::BettorNotWhitelisted
+            );
+        }
 
-        let sponsorship_fee = amount
-            .checked_mul(SPONSORSHIP_FEE_BPS)
-            .ok_or(RumbleError::MathOverflow)?
-            .checked_div(10_000)
+        // Calculate fees
+        let config = &ctx.accounts.config;
+        let fee_exempt = ctx
+            .accounts
+            .fee_exemption
+            .as_ref()
+            .is_some_and(|exemption| fee_exemption_active(exemption, clock.slot));
+        let admin_fee = if fee_exempt {
+            0
+        } else {
+            fee_lamports(amount, config.admin_fee_bps)?
+        };
+        let sponsorship_fee = fee_lamports(amount, config.sponsorship_fee_bps)?;
+        let insurance_premium = if insured {
+            require!(
+                config.insurance_premium_bps > 0 && rumble.insurance_payout_bps > 0,
+                RumbleError::InsuranceUnavailable
+            );
+            fee_lamports(amount, config.insurance_premium_bps)?
+        } else {
+            0
+        };
+        let total_fees = admin_fee
+            .checked_add(sponsorship_fee)
             .ok_or(RumbleError::MathOverflow)?;
+        require!(total_fees < amount, RumbleError::BetTooSmall);
 
         let net_bet = amount
-            .checked_sub(admin_fee)
-            .ok_or(RumbleError::MathOverflow)?
-            .checked_sub(sponsorship_fee)
+            .checked_sub(total_fees)
             .ok_or(RumbleError::MathOverflow)?;
 
-        // Transfer admin fee to treasury
-        if admin_fee > 0 {
+        let referrer = ctx.accounts.referrer.as_ref().map(|r| r.key());
+        validate_referral_accounts(
+            &ctx.accounts.bettor.key(),
+            referrer.as_ref(),
+            ctx.accounts.referral_account.as_ref().map(|a| a.key()).as_ref(),
+        )?;
+        let (treasury_fee, referral_fee) = if referrer.is_some() {
+            split_referral_fee(amount, admin_fee, config.referral_fee_bps)?
+        } else {
+            (admin_fee, 0)
+        };
+
+        let bettor_deployed = if ctx.accounts.bettor_account.authority == Pubkey::default() {
+            0
+        } else {
+            ctx.accounts.bettor_account.sol_deployed
+        };
+        validate_bet_limits(
+            config,
+            amount,
+            net_bet,
+            bettor_deployed,
+            rumble.total_deployed,
+        )?;
+        validate_rumble_bettor_cap(rumble, bettor_deployed, net_bet)?;
+
+        // ICHOR burn gate, ahead of any SOL moving
+        let ichor_burned = config.ichor_burn_per_bet;
+        if ichor_burned > 0 {
+            let ichor_token_account = ctx
+                .accounts
+                .ichor_token_account
+                .as_ref()
+                .ok_or(RumbleError::IchorAccountRequired)?;
+            let ichor_mint = ctx
+                .accounts
+                .ichor_mint
+                .as_ref()
+                .ok_or(RumbleError::IchorAccountRequired)?;
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(RumbleError::IchorAccountRequired)?;
+
+            require!(
+                ichor_token_account.amount >= ichor_burned,
+                RumbleError::InsufficientIchor
+            );
+
+            token::burn(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    Burn {
+                        mint: ichor_mint.to_account_info(),
+                        from: ichor_token_account.to_account_info(),
+                        authority: ctx.accounts.bettor.to_account_info(),
+                    },
+                ),
+                ichor_burned,
+            )?;
+        }
+
+        // Transfer admin fee to treasury, less the referrer's share
+        if treasury_fee > 0 {
             system_program::transfer(
                 CpiContext::new(
                     ctx.accounts.system_program.to_account_info(),
@@ -735,11 +2593,57 @@ pub mod rumble_engine {
                         to: ctx.accounts.treasury.to_account_info(),
                     },
                 ),
-                admin_fee,
+                treasury_fee,
+            )?;
+        }
+
+        // Referral share goes to the referrer's PDA, topped up to rent-exempt
+        // by the bettor like the sponsorship PDA below.
+        if let (Some(referral_account), true) =
+            (ctx.accounts.referral_account.as_ref(), referral_fee > 0)
+        {
+            let referral_top_up = Rent::get()?
+                .minimum_balance(0)
+                .saturating_sub(referral_account.lamports());
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bettor.to_account_info(),
+                        to: referral_account.to_account_info(),
+                    },
+                ),
+                referral_fee
+                    .checked_add(referral_top_up)
+                    .ok_or(RumbleError::MathOverflow)?,
             )?;
         }
 
-        // Transfer sponsorship fee to fighter owner's sponsorship account
+        // Transfer sponsorship fee to fighter owner's sponsorship account. The
+        // bettor tops the PDA up to its rent-exempt minimum first, so the fee
+        // (however small) is always claimable in full.
+        let sponsorship_rent = Rent::get()?.minimum_balance(0);
+        open_sponsorship_ledger(
+            &mut ctx.accounts.sponsorship_ledger,
+            rumble.fighters[fighter_index as usize],
+            ctx.bumps.sponsorship_ledger,
+            ctx.accounts.sponsorship_account.lamports(),
+            sponsorship_rent,
+        );
+        let sponsorship_top_up =
+            sponsorship_rent.saturating_sub(ctx.accounts.sponsorship_account.lamports());
+        if sponsorship_fee > 0 && sponsorship_top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bettor.to_account_info(),
+                        to: ctx.accounts.sponsorship_account.to_account_info(),
+                    },
+                ),
+                sponsorship_top_up,
+            )?;
+        }
         if sponsorship_fee > 0 {
             system_program::transfer(
                 CpiContext::new(
@@ -753,6 +2657,13 @@ pub mod rumble_engine {
             )?;
         }
 
+        fund_vault_rent_reserve(
+            rumble,
+            &ctx.accounts.vault,
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+
         // Transfer net bet to vault PDA
         if net_bet > 0 {
             system_program::transfer(
@@ -767,6 +2678,20 @@ pub mod rumble_engine {
             )?;
         }
 
+        // Insurance premium joins the vault, held in the rumble's insurance pool
+        if insurance_premium > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bettor.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                insurance_premium,
+            )?;
+        }
+
         // Update rumble state
         rumble.betting_pools[fighter_index as usize] = rumble.betting_pools[fighter_index as usize]
             .checked_add(net_bet)
@@ -777,16 +2702,50 @@ pub mod rumble_engine {
             .ok_or(RumbleError::MathOverflow)?;
         rumble.admin_fee_collected = rumble
             .admin_fee_collected
-            .checked_add(admin_fee)
+            .checked_add(treasury_fee)
             .ok_or(RumbleError::MathOverflow)?;
         rumble.sponsorship_paid = rumble
             .sponsorship_paid
             .checked_add(sponsorship_fee)
             .ok_or(RumbleError::MathOverflow)?;
+        rumble.sponsorship_by_fighter[fighter_index as usize] = rumble.sponsorship_by_fighter
+            [fighter_index as usize]
+            .checked_add(sponsorship_fee)
+            .ok_or(RumbleError::MathOverflow)?;
+        if insured {
+            rumble.insurance_pool = rumble
+                .insurance_pool
+                .checked_add(insurance_premium)
+                .ok_or(RumbleError::MathOverflow)?;
+            rumble.insured_stake[fighter_index as usize] = rumble.insured_stake
+                [fighter_index as usize]
+                .checked_add(net_bet)
+                .ok_or(RumbleError::MathOverflow)?;
+        }
+        if sponsorship_fee > 0 {
+            let ledger = &mut ctx.accounts.sponsorship_ledger;
+            ledger.total_accrued = ledger
+                .total_accrued
+                .checked_add(sponsorship_fee)
+                .ok_or(RumbleError::MathOverflow)?;
+            ledger.last_rumble_id = rumble_id;
+            emit!(SponsorshipAccruedEvent {
+                rumble_id,
+                fighter: ledger.fighter,
+                amount: sponsorship_fee,
+                total_accrued: ledger.total_accrued,
+            });
+        }
+
+        if let Some(stats) = ctx.accounts.global_stats.as_mut() {
+            let new_bettor = ctx.accounts.bettor_account.authority == Pubkey::default();
+            record_global_bet(stats, amount, sponsorship_fee, new_bettor)?;
+        }
 
         // Initialize or accumulate bettor account
         let bettor_account = &mut ctx.accounts.bettor_account;
-        if bettor_account.authority == Pubkey::default() {
+        let first_bet_on_rumble = bettor_account.authority == Pubkey::default();
+        if first_bet_on_rumble {
             // First bet: initialize the account
             bettor_account.authority = ctx.accounts.bettor.key();
             bettor_account.rumble_id = rumble_id;
@@ -800,6 +2759,14 @@ pub mod rumble_engine {
             bettor_account.last_claim_ts = 0;
             bettor_account.claimed = false;
             bettor_account.bump = ctx.bumps.bettor_account;
+            bettor_account.bet_sequence = 0;
+            bettor_account.insured_deployments = [0u64; MAX_FIGHTERS];
+            bettor_account.insurance_claimed = false;
+            bettor_account.insurance_premium_paid = 0;
+            rumble.unique_bettor_count = rumble
+                .unique_bettor_count
+                .checked_add(1)
+                .ok_or(RumbleError::MathOverflow)?;
         } else {
             require!(
                 bettor_account.authority == ctx.accounts.bettor.key(),
@@ -828,6 +2795,54 @@ pub mod rumble_engine {
                 .checked_add(net_bet)
                 .ok_or(RumbleError::MathOverflow)?;
         }
+        if insured {
+            bettor_account.insured_deployments[fighter_index as usize] = bettor_account
+                .insured_deployments[fighter_index as usize]
+                .checked_add(net_bet)
+                .ok_or(RumbleError::MathOverflow)?;
+            bettor_account.insurance_premium_paid = bettor_account
+                .insurance_premium_paid
+                .checked_add(insurance_premium)
+                .ok_or(RumbleError::MathOverflow)?;
+        }
+
+        require!(
+            ctx.accounts.bet_record.is_some() == rumble.record_bets,
+            RumbleError::BetRecordMismatch
+        );
+        if let Some(bet_record) = ctx.accounts.bet_record.as_mut() {
+            bet_record.rumble_id = rumble_id;
+            bet_record.bettor = ctx.accounts.bettor.key();
+            bet_record.sequence = bettor_account.bet_sequence;
+            bet_record.fighter_index = fighter_index;
+            bet_record.gross_amount = amount;
+            bet_record.net_amount = net_bet;
+            bet_record.admin_fee = admin_fee;
+            bet_record.sponsorship_fee = sponsorship_fee;
+            bet_record.slot = clock.slot;
+            bet_record.timestamp = clock.unix_timestamp;
+            bet_record.bump = ctx.bumps.bet_record.ok_or(RumbleError::BetRecordMismatch)?;
+            bettor_account.bet_sequence = bettor_account
+                .bet_sequence
+                .checked_add(1)
+                .ok_or(RumbleError::MathOverflow)?;
+        }
+
+        if let Some(bettor_index) = ctx.accounts.bettor_index.as_mut() {
+            if bettor_index.wallet == Pubkey::default() {
+                bettor_index.wallet = ctx.accounts.bettor.key();
+                bettor_index.bump = ctx.bumps.bettor_index.unwrap_or_default();
+            }
+            record_bettor_index(bettor_index, rumble_id);
+        }
+
+        if let Some(stats) = ctx.accounts.bettor_stats.as_mut() {
+            if stats.bettor == Pubkey::default() {
+                stats.bettor = ctx.accounts.bettor.key();
+                stats.bump = ctx.bumps.bettor_stats.unwrap_or_default();
+            }
+            record_bettor_stats_bet(stats, net_bet, first_bet_on_rumble)?;
+        }
 
         msg!(
             "Bet placed: {} lamports on fighter #{} in rumble {}. Net: {}, fee: {}, sponsor: {}",
@@ -845,15 +2860,186 @@ pub mod rumble_engine {
             fighter_index,
             amount,
             net_amount: net_bet,
+            sponsorship_fee,
+            fighter_sponsorship: rumble.sponsorship_by_fighter[fighter_index as usize],
+            referrer,
+            referral_fee,
+            ichor_burned,
+            insurance_premium,
+        });
+
+        Ok(())
+    }
+
+    /// Bettor withdraws their stake on one fighter before the betting deadline.
+    /// A cancellation penalty at the platform fee rate is sent to treasury.
+    /// The bettor account is closed once no stake remains on any fighter, unless
+    /// the rumble records bets.
+    pub fn cancel_bet(ctx: Context<CancelBet>, rumble_id: u64, fighter_index: u8) -> Result<()> {
+        withdraw_stake(ctx, rumble_id, fighter_index, None)
+    }
+
+    /// Bettor pulls back part (or all) of their stake on one fighter before the
+    /// betting deadline. Admin and sponsorship fees paid at `place_bet` are not
+    /// refunded, and the same penalty as `cancel_bet` applies to `amount` to
+    /// discourage deposit/withdraw cycling to move the odds.
+    pub fn withdraw_bet(
+        ctx: Context<CancelBet>,
+        rumble_id: u64,
+        fighter_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        withdraw_stake(ctx, rumble_id, fighter_index, Some(amount))
+    }
+
+    /// Exacta bet: predict the exact winner and runner-up of a rumble. The
+    /// platform fee goes to the treasury as in `place_bet` (no sponsorship or
+    /// referral share); the rest joins `rumble.exacta_pool`, kept apart from
+    /// `betting_pools`. Stakes accumulate on the bettor's `ExactaTicket` for
+    /// the ordered pair and on the pair's `ExactaCombo` total. Exacta stakes
+    /// cannot be withdrawn and don't count towards `min_bettors`.
+    pub fn place_exacta_bet(
+        ctx: Context<PlaceExactaBet>,
+        rumble_id: u64,
+        first_index: u8,
+        second_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        let rumble = &mut ctx.accounts.rumble;
+
+        require!(
+            rumble.state == RumbleState::Betting,
+            RumbleError::BettingClosed
+        );
+        let clock = Clock::get()?;
+        require_betting_open(rumble, &clock)?;
+        validate_exacta_pick(rumble, first_index, second_index)?;
+        require!(amount > 0, RumbleError::ZeroBetAmount);
+
+        if rumble.whitelist_enabled {
+            let whitelist = ctx
+                .accounts
+                .whitelist
+                .as_ref()
+                .ok_or(RumbleError::BettorNotWhitelisted)?;
+            require!(
+                whitelist_contains(whitelist, &ctx.accounts.bettor.key()),
+                RumbleError::BettorNotWhitelisted
+            );
+        }
+
+        let config = &ctx.accounts.config;
+        let admin_fee = fee_lamports(amount, config.admin_fee_bps)?;
+        require!(admin_fee < amount, RumbleError::BetTooSmall);
+        let net_bet = amount
+            .checked_sub(admin_fee)
+            .ok_or(RumbleError::MathOverflow)?;
+        let pool_deployed = rumble
+            .total_deployed
+            .checked_add(rumble.exacta_pool)
+            .ok_or(RumbleError::MathOverflow)?;
+        validate_bet_limits(
+            config,
+            amount,
+            net_bet,
+            ctx.accounts.exacta_ticket.amount,
+            pool_deployed,
+        )?;
+
+        if admin_fee > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bettor.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                ),
+                admin_fee,
+            )?;
+        }
+
+        fund_vault_rent_reserve(
+            rumble,
+            &ctx.accounts.vault,
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bettor.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            net_bet,
+        )?;
+
+        rumble.exacta_pool = rumble
+            .exacta_pool
+            .checked_add(net_bet)
+            .ok_or(RumbleError::MathOverflow)?;
+        rumble.admin_fee_collected = rumble
+            .admin_fee_collected
+            .checked_add(admin_fee)
+            .ok_or(RumbleError::MathOverflow)?;
+
+        let ticket = &mut ctx.accounts.exacta_ticket;
+        if ticket.bettor == Pubkey::default() {
+            ticket.rumble_id = rumble_id;
+            ticket.bettor = ctx.accounts.bettor.key();
+            ticket.first_index = first_index;
+            ticket.second_index = second_index;
+            ticket.bump = ctx.bumps.exacta_ticket;
+        }
+        ticket.amount = ticket
+            .amount
+            .checked_add(net_bet)
+            .ok_or(RumbleError::MathOverflow)?;
+
+        let combo = &mut ctx.accounts.exacta_combo;
+        if combo.total_staked == 0 {
+            combo.rumble_id = rumble_id;
+            combo.first_index = first_index;
+            combo.second_index = second_index;
+            combo.bump = ctx.bumps.exacta_combo;
+        }
+        combo.total_staked = combo
+            .total_staked
+            .checked_add(net_bet)
+            .ok_or(RumbleError::MathOverflow)?;
+
+        msg!(
+            "Exacta bet placed: {} lamports on #{} then #{} in rumble {}. Net: {}, fee: {}",
+            amount,
+            first_index,
+            second_index,
+            rumble_id,
+            net_bet,
+            admin_fee
+        );
+
+        emit!(ExactaBetPlacedEvent {
+            rumble_id,
+            bettor: ctx.accounts.bettor.key(),
+            first_index,
+            second_index,
+            amount,
+            net_amount: net_bet,
+            exacta_pool: rumble.exacta_pool,
         });
 
         Ok(())
     }
 
     /// Transition rumble from Betting to Combat and initialize on-chain combat state.
-    /// Callable by admin after betting deadline.
+    /// Permissionless once the betting deadline has passed; the caller pays
+    /// for the combat state account.
     #[cfg(feature = "combat")]
     pub fn start_combat(ctx: Context<StartCombat>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
         let rumble = &mut ctx.accounts.rumble;
 
         require!(
@@ -862,63 +3048,141 @@ pub mod rumble_engine {
         );
 
         let clock = Clock::get()?;
-        let betting_close_slot = u64::try_from(rumble.betting_deadline)
-            .map_err(|_| error!(RumbleError::BettingNotEnded))?;
+        require_betting_ended(rumble, &clock)?;
+
+        let ruleset = rumble_ruleset(rumble, ctx.accounts.ruleset.as_deref())?;
+        begin_combat(
+            rumble,
+            &ruleset,
+            &mut *load_or_init_combat(&ctx.accounts.combat_state)?,
+            ctx.bumps.combat_state,
+            &clock,
+            ctx.remaining_accounts,
+        )
+    }
+
+    /// Admin-only early start that skips the betting deadline (testing/ops).
+    #[cfg(feature = "combat")]
+    pub fn force_start_combat(ctx: Context<ForceStartCombat>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::ForceStartCombat::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let rumble = &mut ctx.accounts.rumble;
+
         require!(
-            clock.slot >= betting_close_slot,
-            RumbleError::BettingNotEnded
+            rumble.state == RumbleState::Betting,
+            RumbleError::InvalidStateTransition
         );
 
-        rumble.state = RumbleState::Combat;
-        rumble.combat_started_at = clock.unix_timestamp;
+        let clock = Clock::get()?;
+        let ruleset = rumble_ruleset(rumble, ctx.accounts.ruleset.as_deref())?;
+        begin_combat(
+            rumble,
+            &ruleset,
+            &mut *load_or_init_combat(&ctx.accounts.combat_state)?,
+            ctx.bumps.combat_state,
+            &clock,
+            ctx.remaining_accounts,
+        )
+    }
+
+    /// Create a `CombatRuleset` that rumbles can be created under. Admin-only.
+    #[cfg(feature = "combat")]
+    pub fn create_combat_ruleset(
+        ctx: Context<CreateCombatRuleset>,
+        ruleset_id: u32,
+        params: CombatRulesetParams,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CreateCombatRuleset::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        validate_ruleset_params(&params)?;
+        let ruleset = &mut ctx.accounts.ruleset;
+        ruleset.ruleset_id = ruleset_id;
+        ruleset.bump = ctx.bumps.ruleset;
+        write_ruleset_params(ruleset, &params);
+
+        msg!("Combat ruleset {} created", ruleset_id);
+        Ok(())
+    }
+
+    /// Rebalance an existing `CombatRuleset`. Admin-only; applies from the next
+    /// turn of every rumble referencing it, including ones already in combat.
+    #[cfg(feature = "combat")]
+    pub fn update_combat_ruleset(
+        ctx: Context<UpdateCombatRuleset>,
+        params: CombatRulesetParams,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateCombatRuleset::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        validate_ruleset_params(&params)?;
+        let ruleset = &mut ctx.accounts.ruleset;
+        write_ruleset_params(ruleset, &params);
+
+        msg!("Combat ruleset {} updated", ruleset.ruleset_id);
+        Ok(())
+    }
+
+    /// Admin sets each fighter's starting HP (e.g. scaled by registry win rate)
+    /// while the rumble is still in Betting. Picked up by `start_combat`;
+    /// without it every fighter starts at the rumble ruleset's `start_hp`.
+    #[cfg(feature = "combat")]
+    pub fn set_fighter_hp_overrides(
+        ctx: Context<SetFighterHpOverrides>,
+        hp_overrides: [u16; MAX_FIGHTERS],
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::SetFighterHpOverrides::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let rumble = &ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Betting,
+            RumbleError::InvalidStateTransition
+        );
+        validate_hp_overrides(&hp_overrides, rumble.fighter_count as usize)?;
 
-        let combat = &mut ctx.accounts.combat_state;
+        let mut combat_state = load_or_init_combat(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
         if combat.rumble_id != 0 {
             require!(combat.rumble_id == rumble.id, RumbleError::InvalidRumble);
         }
         combat.rumble_id = rumble.id;
-        combat.fighter_count = rumble.fighter_count;
-        combat.current_turn = 0;
-        combat.turn_open_slot = clock.slot;
-        combat.commit_close_slot = clock.slot;
-        combat.reveal_close_slot = clock.slot;
-        combat.turn_resolved = true;
-        combat.remaining_fighters = rumble.fighter_count;
-        combat.winner_index = u8::MAX;
-        combat.hp = [0u16; MAX_FIGHTERS];
-        combat.meter = [0u8; MAX_FIGHTERS];
-        combat.elimination_rank = [0u8; MAX_FIGHTERS];
-        combat.total_damage_dealt = [0u64; MAX_FIGHTERS];
-        combat.total_damage_taken = [0u64; MAX_FIGHTERS];
-        combat.vrf_seed = [0u8; 32];
-        for i in 0..rumble.fighter_count as usize {
-            combat.hp[i] = START_HP;
-        }
+        combat.hp_overrides = hp_overrides;
         combat.bump = ctx.bumps.combat_state;
 
-        msg!(
-            "Rumble {} combat started at {}",
-            rumble.id,
-            clock.unix_timestamp
-        );
-
-        emit!(CombatStartedEvent {
-            rumble_id: rumble.id,
-            timestamp: clock.unix_timestamp,
-        });
-
+        msg!("Rumble {} fighter HP overrides set", rumble.id);
         Ok(())
     }
 
     /// Fighter authorizes a persistent delegate authority to submit move commits/reveals.
     /// This removes the need for the owner wallet to sign every combat turn or every rumble.
+    /// `expires_at_slot` is the last slot the delegate may act (0 = until revoked).
+    /// Pre-expiry delegate accounts must go through `migrate_fighter_delegate` first.
     #[cfg(feature = "combat")]
     pub fn authorize_fighter_delegate(
         ctx: Context<AuthorizeFighterDelegate>,
         authority: Pubkey,
+        expires_at_slot: u64,
     ) -> Result<()> {
         let clock = Clock::get()?;
         require!(authority != Pubkey::default(), RumbleError::InvalidFighterDelegate);
+        require!(
+            expires_at_slot == 0 || expires_at_slot > clock.slot,
+            RumbleError::FighterDelegateExpired
+        );
 
         let fighter_delegate = &mut ctx.accounts.fighter_delegate;
         fighter_delegate.fighter = ctx.accounts.fighter.key();
@@ -926,20 +3190,63 @@ pub mod rumble_engine {
         fighter_delegate.authorized_slot = clock.slot;
         fighter_delegate.revoked = false;
         fighter_delegate.bump = ctx.bumps.fighter_delegate;
+        fighter_delegate.expires_at_slot = expires_at_slot;
 
         emit!(FighterDelegateAuthorizedEvent {
             fighter: ctx.accounts.fighter.key(),
             authority,
             authorized_slot: clock.slot,
+            expires_at_slot,
         });
 
         Ok(())
     }
 
-    /// Fighter revokes an existing persistent delegate.
+    /// Grow a FighterDelegate created before `expires_at_slot` existed, so
+    /// `authorize_fighter_delegate`/`revoke_fighter_delegate` can load it.
+    /// Permissionless; the payer tops up rent. The delegate keeps no expiry.
     #[cfg(feature = "combat")]
-    pub fn revoke_fighter_delegate(ctx: Context<RevokeFighterDelegate>) -> Result<()> {
-        let fighter_delegate = &mut ctx.accounts.fighter_delegate;
+    pub fn migrate_fighter_delegate(ctx: Context<MigrateFighterDelegate>) -> Result<()> {
+        const DELEGATE_LEN: usize = 8 + FighterDelegate::INIT_SPACE; // 90
+
+        let info = ctx.accounts.fighter_delegate.to_account_info();
+        {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() == FIGHTER_DELEGATE_V1_LEN
+                    && &data[..8] == FighterDelegate::DISCRIMINATOR,
+                RumbleError::InvalidFighterDelegate
+            );
+        }
+
+        let min_balance = Rent::get()?.minimum_balance(DELEGATE_LEN);
+        let topup = min_balance.saturating_sub(info.lamports());
+        if topup > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                topup,
+            )?;
+        }
+        info.resize(DELEGATE_LEN)?;
+        {
+            let mut data = info.try_borrow_mut_data()?;
+            data[FIGHTER_DELEGATE_V1_LEN..DELEGATE_LEN].copy_from_slice(&0u64.to_le_bytes());
+        }
+
+        msg!("FighterDelegate migrated for {}", ctx.accounts.fighter.key());
+        Ok(())
+    }
+
+    /// Fighter revokes an existing persistent delegate.
+    #[cfg(feature = "combat")]
+    pub fn revoke_fighter_delegate(ctx: Context<RevokeFighterDelegate>) -> Result<()> {
+        let fighter_delegate = &mut ctx.accounts.fighter_delegate;
         require!(fighter_delegate.fighter == ctx.accounts.fighter.key(), RumbleError::Unauthorized);
 
         fighter_delegate.revoked = true;
@@ -952,7 +3259,9 @@ pub mod rumble_engine {
         Ok(())
     }
 
-    /// Fighter commits a move hash for the active rumble turn.
+    /// Fighter commits a move hash for the active rumble turn. The fighter's
+    /// MoveCommitment PDA is created on its first commit and overwritten on
+    /// later turns; one commit per turn.
     /// Hash format: sha256("rumble:v1", rumble_id, turn, fighter_pubkey, move_code, salt)
     #[cfg(feature = "combat")]
     pub fn commit_move(
@@ -961,9 +3270,11 @@ pub mod rumble_engine {
         turn: u32,
         move_hash: [u8; 32],
     ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
         let clock = Clock::get()?;
         let rumble = &ctx.accounts.rumble;
-        let combat = &ctx.accounts.combat_state;
+        let combat_state = load_combat(&ctx.accounts.combat_state)?;
+        let combat = &*combat_state;
 
         require!(
             rumble.state == RumbleState::Combat,
@@ -980,7 +3291,7 @@ pub mod rumble_engine {
         // Check fighter is still alive
         require!(combat.hp[fighter_idx] > 0, RumbleError::FighterEliminated);
         require!(turn == combat.current_turn, RumbleError::InvalidTurn);
-        require!(!combat.turn_resolved, RumbleError::TurnAlreadyResolved);
+        require!(combat.turn_resolved == 0, RumbleError::TurnAlreadyResolved);
         require!(
             clock.slot >= combat.turn_open_slot && clock.slot <= combat.commit_close_slot,
             RumbleError::CommitWindowClosed
@@ -988,6 +3299,10 @@ pub mod rumble_engine {
         require!(move_hash != [0u8; 32], RumbleError::InvalidMoveCommitment);
 
         let move_commitment = &mut ctx.accounts.move_commitment;
+        require!(
+            move_commitment.turn != turn,
+            RumbleError::MoveAlreadyCommitted
+        );
         move_commitment.rumble_id = rumble_id;
         move_commitment.fighter = ctx.accounts.fighter.key();
         move_commitment.turn = turn;
@@ -1017,9 +3332,11 @@ pub mod rumble_engine {
         move_code: u8,
         salt: [u8; 32],
     ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
         let clock = Clock::get()?;
         let rumble = &ctx.accounts.rumble;
-        let combat = &ctx.accounts.combat_state;
+        let combat_state = load_combat(&ctx.accounts.combat_state)?;
+        let combat = &*combat_state;
 
         require!(
             rumble.state == RumbleState::Combat,
@@ -1036,7 +3353,7 @@ pub mod rumble_engine {
             &ctx.accounts.fighter_delegate,
         )?;
         require!(turn == combat.current_turn, RumbleError::InvalidTurn);
-        require!(!combat.turn_resolved, RumbleError::TurnAlreadyResolved);
+        require!(combat.turn_resolved == 0, RumbleError::TurnAlreadyResolved);
         require!(
             clock.slot > combat.commit_close_slot && clock.slot <= combat.reveal_close_slot,
             RumbleError::RevealWindowClosed
@@ -1078,31 +3395,40 @@ pub mod rumble_engine {
     #[cfg(feature = "combat")]
     pub fn open_turn(ctx: Context<CombatAction>) -> Result<()> {
         let clock = Clock::get()?;
+        let config = &ctx.accounts.config;
         let rumble = &ctx.accounts.rumble;
-        let combat = &mut ctx.accounts.combat_state;
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
+        stamp_combat_log(&mut ctx.accounts.combat_log, rumble.id, ctx.bumps.combat_log);
 
         require!(
             rumble.state == RumbleState::Combat,
             RumbleError::InvalidStateTransition
         );
         require!(combat.current_turn == 0, RumbleError::TurnAlreadyOpen);
-        require!(combat.turn_resolved, RumbleError::TurnNotResolved);
+        require!(combat.turn_resolved != 0, RumbleError::TurnNotResolved);
         require!(
             combat.remaining_fighters > 1,
             RumbleError::CombatAlreadyFinished
         );
 
+        combat.crit_seed = turn_crit_seed(rumble, ctx.accounts.slot_hashes.as_ref())?;
         combat.current_turn = 1;
+        let attributes = combat.attributes();
+        combat.turn_pairings =
+            compute_turn_pairings(rumble, combat, &attributes, combat.current_turn);
         combat.turn_open_slot = clock.slot;
         combat.commit_close_slot = clock
             .slot
-            .checked_add(COMMIT_WINDOW_SLOTS)
+            .checked_add(config.commit_window_slots as u64)
             .ok_or(RumbleError::MathOverflow)?;
         combat.reveal_close_slot = combat
             .commit_close_slot
-            .checked_add(REVEAL_WINDOW_SLOTS)
+            .checked_add(config.reveal_window_slots as u64)
             .ok_or(RumbleError::MathOverflow)?;
-        combat.turn_resolved = false;
+        combat.turn_resolved = 0;
+        combat.pairs_resolved = 0;
+        combat.turn_chunked = 0;
 
         emit!(TurnOpenedEvent {
             rumble_id: rumble.id,
@@ -1110,237 +3436,156 @@ pub mod rumble_engine {
             turn_open_slot: combat.turn_open_slot,
             commit_close_slot: combat.commit_close_slot,
             reveal_close_slot: combat.reveal_close_slot,
+            crit_seed: combat.crit_seed,
         });
 
         Ok(())
     }
 
     /// Resolve the active turn from revealed move commitments.
-    /// If a fighter didn't reveal, deterministic fallback move is used.
+    /// If a fighter didn't reveal, deterministic fallback move is used; one that
+    /// committed but hid its reveal also loses its meter and takes chip damage.
     #[cfg(feature = "combat")]
     pub fn resolve_turn(ctx: Context<CombatAction>) -> Result<()> {
         let clock = Clock::get()?;
-        let rumble = &ctx.accounts.rumble;
-        let combat = &mut ctx.accounts.combat_state;
+        let combat_log = &mut ctx.accounts.combat_log;
+        stamp_combat_log(combat_log, ctx.accounts.rumble.id, ctx.bumps.combat_log);
+        let ruleset = rumble_ruleset(&ctx.accounts.rumble, ctx.accounts.ruleset.as_deref())?;
+        let mut combat = load_combat_mut(&ctx.accounts.combat_state)?;
+        resolve_current_turn(
+            &ctx.accounts.rumble,
+            &ruleset,
+            &mut combat,
+            combat_log,
+            ctx.remaining_accounts,
+            &clock,
+        )?;
+        pay_keeper_bounty(
+            &ctx.accounts.rumble,
+            &mut combat,
+            ctx.accounts.config.keeper_fee_lamports,
+            ctx.accounts.vault.as_ref().zip(ctx.bumps.vault),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )
+    }
 
+    /// Recovery for a turn no keeper resolved: once `STALE_TURN_GRACE_SLOTS`
+    /// have passed since its reveal window closed, anyone can resolve it
+    /// exactly like `resolve_turn`, falling back for moves never revealed.
+    #[cfg(feature = "combat")]
+    pub fn force_resolve_stale_turn(ctx: Context<CombatAction>) -> Result<()> {
+        let clock = Clock::get()?;
         require!(
-            rumble.state == RumbleState::Combat,
-            RumbleError::InvalidStateTransition
-        );
-        require!(combat.current_turn > 0, RumbleError::TurnNotOpen);
-        require!(!combat.turn_resolved, RumbleError::TurnAlreadyResolved);
-        require!(
-            clock.slot >= combat.reveal_close_slot,
-            RumbleError::RevealWindowActive
+            turn_is_stale(&*load_combat(&ctx.accounts.combat_state)?, clock.slot),
+            RumbleError::TurnNotStale
         );
-
-        let fighter_count = combat.fighter_count as usize;
-        let turn = combat.current_turn;
-
-        let alive_indices: Vec<usize> = (0..fighter_count)
-            .filter(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
-            .collect();
-
-        if alive_indices.len() <= 1 {
-            combat.turn_resolved = true;
-            if let Some(idx) = alive_indices.first() {
-                combat.winner_index = *idx as u8;
-            }
-            emit!(TurnResolvedEvent {
-                rumble_id: rumble.id,
-                turn,
-                remaining_fighters: combat.remaining_fighters,
-            });
-            return Ok(());
-        }
-
-        let rumble_id_bytes = rumble.id.to_le_bytes();
-        let turn_bytes = turn.to_le_bytes();
-        let vrf_seed_ref = &combat.vrf_seed;
-        let mut alive_order_keys: Vec<(usize, u64, [u8; 32])> = alive_indices
-            .iter()
-            .map(|idx| {
-                let fighter_bytes = rumble.fighters[*idx].to_bytes();
-                let pair_key = if *vrf_seed_ref != [0u8; 32] {
-                    hash_u64(&[
-                        b"pair-order",
-                        vrf_seed_ref.as_ref(),
-                        rumble_id_bytes.as_ref(),
-                        turn_bytes.as_ref(),
-                        fighter_bytes.as_ref(),
-                    ])
-                } else {
-                    hash_u64(&[
-                        b"pair-order",
-                        rumble_id_bytes.as_ref(),
-                        turn_bytes.as_ref(),
-                        fighter_bytes.as_ref(),
-                    ])
-                };
-                (*idx, pair_key, fighter_bytes)
-            })
-            .collect();
-        alive_order_keys.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.2.cmp(&b.2)));
-        let alive_indices: Vec<usize> = alive_order_keys
-            .into_iter()
-            .map(|(idx, _, _)| idx)
-            .collect();
-        let sudden_death_active = alive_indices.len() == 2;
-
-        let mut paired_indices: Vec<usize> = Vec::with_capacity(alive_indices.len());
-        let mut eliminated_this_turn: Vec<usize> = Vec::new();
-
-        for chunk in alive_indices.chunks(2) {
-            if chunk.len() < 2 {
-                // bye
-                continue;
-            }
-
-            let idx_a = chunk[0];
-            let idx_b = chunk[1];
-            let fighter_a = rumble.fighters[idx_a];
-            let fighter_b = rumble.fighters[idx_b];
-
-            let move_a = read_revealed_move_from_remaining_accounts(
-                ctx.remaining_accounts,
-                rumble.id,
-                turn,
-                &fighter_a,
-            )
-            .filter(|m| is_valid_move_code(*m))
-            .unwrap_or_else(|| {
-                fallback_move_code(rumble.id, turn, &fighter_a, combat.meter[idx_a])
-            });
-            let move_b = read_revealed_move_from_remaining_accounts(
-                ctx.remaining_accounts,
-                rumble.id,
-                turn,
-                &fighter_b,
-            )
-            .filter(|m| is_valid_move_code(*m))
-            .unwrap_or_else(|| {
-                fallback_move_code(rumble.id, turn, &fighter_b, combat.meter[idx_b])
-            });
-
-            let (damage_to_a, damage_to_b, meter_used_a, meter_used_b) =
-                resolve_duel(
-                    move_a,
-                    move_b,
-                    combat.meter[idx_a],
-                    combat.meter[idx_b],
-                    sudden_death_active,
-                );
-
-            combat.meter[idx_a] = combat.meter[idx_a].saturating_sub(meter_used_a);
-            combat.meter[idx_b] = combat.meter[idx_b].saturating_sub(meter_used_b);
-
-            combat.hp[idx_a] = combat.hp[idx_a].saturating_sub(damage_to_a);
-            combat.hp[idx_b] = combat.hp[idx_b].saturating_sub(damage_to_b);
-
-            combat.total_damage_dealt[idx_a] = combat.total_damage_dealt[idx_a]
-                .checked_add(damage_to_b as u64)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.total_damage_dealt[idx_b] = combat.total_damage_dealt[idx_b]
-                .checked_add(damage_to_a as u64)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.total_damage_taken[idx_a] = combat.total_damage_taken[idx_a]
-                .checked_add(damage_to_a as u64)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.total_damage_taken[idx_b] = combat.total_damage_taken[idx_b]
-                .checked_add(damage_to_b as u64)
-                .ok_or(RumbleError::MathOverflow)?;
-
-            paired_indices.push(idx_a);
-            paired_indices.push(idx_b);
-
-            if combat.hp[idx_a] == 0 && combat.elimination_rank[idx_a] == 0 {
-                eliminated_this_turn.push(idx_a);
-            }
-            if combat.hp[idx_b] == 0 && combat.elimination_rank[idx_b] == 0 {
-                eliminated_this_turn.push(idx_b);
-            }
-        }
-
-        for idx in paired_indices {
-            if combat.hp[idx] > 0 {
-                let next_meter = combat.meter[idx].saturating_add(METER_PER_TURN);
-                combat.meter[idx] = next_meter.min(SPECIAL_METER_COST);
-            }
-        }
-
-        // Give bye fighter meter if odd count
-        if alive_indices.len() % 2 == 1 {
-            let bye_idx = alive_indices[alive_indices.len() - 1];
-            let next_meter = combat.meter[bye_idx].saturating_add(METER_PER_TURN);
-            combat.meter[bye_idx] = next_meter.min(SPECIAL_METER_COST);
-        }
-
-        // Deterministic elimination ordering: sort by damage dealt descending,
-        // then by fighter index ascending as tiebreaker.
-        eliminated_this_turn.sort_by(|a, b| {
-            combat.total_damage_dealt[*b]
-                .cmp(&combat.total_damage_dealt[*a])
-                .then_with(|| a.cmp(b))
+        let combat_log = &mut ctx.accounts.combat_log;
+        stamp_combat_log(combat_log, ctx.accounts.rumble.id, ctx.bumps.combat_log);
+        let ruleset = rumble_ruleset(&ctx.accounts.rumble, ctx.accounts.ruleset.as_deref())?;
+        let mut combat = load_combat_mut(&ctx.accounts.combat_state)?;
+        resolve_current_turn(
+            &ctx.accounts.rumble,
+            &ruleset,
+            &mut combat,
+            combat_log,
+            ctx.remaining_accounts,
+            &clock,
+        )?;
+        emit!(StaleTurnResolvedEvent {
+            rumble_id: ctx.accounts.rumble.id,
+            turn: combat.current_turn,
+            reveal_close_slot: combat.reveal_close_slot,
+            resolved_slot: clock.slot,
         });
+        pay_keeper_bounty(
+            &ctx.accounts.rumble,
+            &mut combat,
+            ctx.accounts.config.keeper_fee_lamports,
+            ctx.accounts.vault.as_ref().zip(ctx.bumps.vault),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )
+    }
 
-        for idx in eliminated_this_turn {
-            if combat.elimination_rank[idx] > 0 {
-                continue;
-            }
-            let eliminated_so_far = combat
-                .fighter_count
-                .checked_sub(combat.remaining_fighters)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.elimination_rank[idx] = eliminated_so_far
-                .checked_add(1)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.remaining_fighters = combat
-                .remaining_fighters
-                .checked_sub(1)
-                .ok_or(RumbleError::MathOverflow)?;
-        }
-
-        if combat.remaining_fighters == 1 {
-            if let Some((idx, _)) = (0..fighter_count)
-                .filter(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
-                .map(|i| (i, combat.hp[i]))
-                .next()
-            {
-                combat.winner_index = idx as u8;
-            }
-        }
-
-        combat.turn_resolved = true;
-
-        emit!(TurnResolvedEvent {
-            rumble_id: rumble.id,
-            turn,
-            remaining_fighters: combat.remaining_fighters,
-        });
+    /// Resolve a single pair of the open turn, so turns with many fighters
+    /// fit the compute budget. Pairs go in order (`pair_index` 0, 1, ...), by
+    /// the lower fighter index of each stored pairing; MoveCommitment PDAs for
+    /// the pair go in remaining accounts. `seal_turn` finishes the turn.
+    #[cfg(feature = "combat")]
+    pub fn resolve_turn_pair(ctx: Context<CombatAction>, pair_index: u8) -> Result<()> {
+        let clock = Clock::get()?;
+        let combat_log = &mut ctx.accounts.combat_log;
+        stamp_combat_log(combat_log, ctx.accounts.rumble.id, ctx.bumps.combat_log);
+        let ruleset = rumble_ruleset(&ctx.accounts.rumble, ctx.accounts.ruleset.as_deref())?;
+        resolve_chunked_pair(
+            &ctx.accounts.rumble,
+            &ruleset,
+            &mut *load_combat_mut(&ctx.accounts.combat_state)?,
+            combat_log,
+            ctx.remaining_accounts,
+            &clock,
+            pair_index,
+        )
+    }
 
-        Ok(())
+    /// Finish a turn resolved with `resolve_turn_pair`: survivor meter,
+    /// eliminations and the history hash, as at the end of `resolve_turn`.
+    #[cfg(feature = "combat")]
+    pub fn seal_turn(ctx: Context<CombatAction>) -> Result<()> {
+        let clock = Clock::get()?;
+        stamp_combat_log(
+            &mut ctx.accounts.combat_log,
+            ctx.accounts.rumble.id,
+            ctx.bumps.combat_log,
+        );
+        let ruleset = rumble_ruleset(&ctx.accounts.rumble, ctx.accounts.ruleset.as_deref())?;
+        let mut combat = load_combat_mut(&ctx.accounts.combat_state)?;
+        seal_chunked_turn(
+            &ctx.accounts.rumble,
+            &ruleset,
+            &mut combat,
+            &clock,
+        )?;
+        pay_keeper_bounty(
+            &ctx.accounts.rumble,
+            &mut combat,
+            ctx.accounts.config.keeper_fee_lamports,
+            ctx.accounts.vault.as_ref().zip(ctx.bumps.vault),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )
     }
 
     /// Accept pre-computed turn results from the admin/keeper.
     /// Validates damage by re-running resolve_duel internally.
     /// This is the "Option D hybrid" path — combat math runs off-chain,
     /// but on-chain program validates correctness.
+    /// MoveCommitment PDAs passed as remaining accounts apply missed-reveal
+    /// penalties before damage is checked. `audience_boosts` must match
+    /// `audience_boost` for every fighter in the rumble.
     #[cfg(feature = "combat")]
     pub fn post_turn_result(
         ctx: Context<AdminCombatAction>,
         duel_results: Vec<DuelResult>,
         bye_fighter_idx: Option<u8>,
+        audience_boosts: [u16; MAX_FIGHTERS],
     ) -> Result<()> {
         let clock = Clock::get()?;
         let rumble = &ctx.accounts.rumble;
-        let combat = &mut ctx.accounts.combat_state;
+        let ruleset = rumble_ruleset(rumble, ctx.accounts.ruleset.as_deref())?;
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
+        let combat_log = &mut ctx.accounts.combat_log;
+        stamp_combat_log(combat_log, rumble.id, ctx.bumps.combat_log);
 
         require!(
             rumble.state == RumbleState::Combat,
             RumbleError::InvalidStateTransition
         );
         require!(combat.current_turn > 0, RumbleError::TurnNotOpen);
-        require!(!combat.turn_resolved, RumbleError::TurnAlreadyResolved);
+        require!(combat.turn_resolved == 0, RumbleError::TurnAlreadyResolved);
+        require!(combat.turn_chunked == 0, RumbleError::ChunkedTurnInProgress);
         require!(
             clock.slot >= combat.reveal_close_slot,
             RumbleError::RevealWindowActive
@@ -1348,6 +3593,8 @@ pub mod rumble_engine {
 
         let fighter_count = combat.fighter_count as usize;
         let turn = combat.current_turn;
+        let hp_before = combat.hp;
+        let mut moves = [NO_LAST_MOVE; MAX_FIGHTERS];
 
         // Track which fighters were paired to give them meter later
         let mut paired_indices: Vec<usize> = Vec::new();
@@ -1361,6 +3608,13 @@ pub mod rumble_engine {
             .filter(|&i| combat.hp[i] > 0 && combat.elimination_rank[i] == 0)
             .count();
         let sudden_death_active = alive_count == 2;
+        let attributes = combat.attributes();
+        for (idx, boost) in audience_boosts.iter().enumerate().take(fighter_count) {
+            require!(
+                *boost == audience_boost(rumble, idx),
+                RumbleError::AudienceBoostMismatch
+            );
+        }
         let expected_duels = alive_count / 2;
         let expected_bye = if alive_count % 2 == 1 { 1usize } else { 0usize };
         require!(
@@ -1391,10 +3645,30 @@ pub mod rumble_engine {
                 combat.hp[idx_b] > 0 && combat.elimination_rank[idx_b] == 0,
                 RumbleError::FighterEliminated
             );
+            validate_duel_pairing(combat, idx_a, idx_b)?;
             // Validate moves
             require!(is_valid_move_code(dr.move_a), RumbleError::InvalidState);
             require!(is_valid_move_code(dr.move_b), RumbleError::InvalidState);
 
+            for idx in [idx_a, idx_b] {
+                let commitment = read_move_commitment_from_remaining_accounts(
+                    ctx.remaining_accounts,
+                    rumble.id,
+                    turn,
+                    &rumble.fighters[idx],
+                )?;
+                penalize_missed_reveal(rumble, combat, idx, commitment.as_ref())?;
+            }
+
+            let fighter_a = rumble.fighters[idx_a];
+            let fighter_b = rumble.fighters[idx_b];
+            let (expected_crit_a, expected_crit_b) =
+                duel_crits(rumble, combat, turn, (idx_a, dr.move_a), (idx_b, dr.move_b));
+            require!(
+                dr.crit_a == expected_crit_a && dr.crit_b == expected_crit_b,
+                RumbleError::DamageMismatch
+            );
+
             // RE-VALIDATE damage by running resolve_duel
             let (expected_dmg_a, expected_dmg_b, expected_meter_a, expected_meter_b) =
                 resolve_duel(
@@ -1402,16 +3676,68 @@ pub mod rumble_engine {
                     dr.move_b,
                     combat.meter[idx_a],
                     combat.meter[idx_b],
+                    combat.last_move[idx_a],
+                    combat.last_move[idx_b],
+                    combat.consecutive_guards[idx_a],
+                    combat.consecutive_guards[idx_b],
+                    dr.crit_a,
+                    dr.crit_b,
+                    audience_boosts[idx_a],
+                    audience_boosts[idx_b],
+                    &attributes[idx_a],
+                    &attributes[idx_b],
                     sudden_death_active,
+                    &ruleset,
                 );
             require!(
                 dr.damage_to_a == expected_dmg_a && dr.damage_to_b == expected_dmg_b,
                 RumbleError::DamageMismatch
             );
+            emit!(TurnPairResolvedEvent {
+                rumble_id: rumble.id,
+                turn,
+                fighter_a,
+                fighter_b,
+                move_a: dr.move_a,
+                move_b: dr.move_b,
+                damage_to_a: dr.damage_to_a,
+                damage_to_b: dr.damage_to_b,
+                crit_a: dr.crit_a,
+                crit_b: dr.crit_b,
+            });
+
+            append_duel_log(
+                combat_log,
+                DuelLogEntry {
+                    turn,
+                    fighter_a_idx: dr.fighter_a_idx,
+                    fighter_b_idx: dr.fighter_b_idx,
+                    move_a: dr.move_a,
+                    move_b: dr.move_b,
+                    damage_to_a: dr.damage_to_a,
+                    damage_to_b: dr.damage_to_b,
+                },
+            )?;
 
             // Apply damage
             combat.meter[idx_a] = combat.meter[idx_a].saturating_sub(expected_meter_a);
             combat.meter[idx_b] = combat.meter[idx_b].saturating_sub(expected_meter_b);
+            combat.last_move[idx_a] = dr.move_a;
+            combat.last_move[idx_b] = dr.move_b;
+            moves[idx_a] = dr.move_a;
+            moves[idx_b] = dr.move_b;
+            combat.consecutive_guards[idx_a] = next_consecutive_guards(
+                combat.consecutive_guards[idx_a],
+                dr.move_a,
+                dr.move_b,
+                dr.damage_to_a,
+            );
+            combat.consecutive_guards[idx_b] = next_consecutive_guards(
+                combat.consecutive_guards[idx_b],
+                dr.move_b,
+                dr.move_a,
+                dr.damage_to_b,
+            );
 
             combat.hp[idx_a] = combat.hp[idx_a].saturating_sub(dr.damage_to_a);
             combat.hp[idx_b] = combat.hp[idx_b].saturating_sub(dr.damage_to_b);
@@ -1443,8 +3769,8 @@ pub mod rumble_engine {
         // Give meter to paired survivors
         for idx in paired_indices {
             if combat.hp[idx] > 0 {
-                let next_meter = combat.meter[idx].saturating_add(METER_PER_TURN);
-                combat.meter[idx] = next_meter.min(SPECIAL_METER_COST);
+                let next_meter = combat.meter[idx].saturating_add(ruleset.meter_per_turn);
+                combat.meter[idx] = next_meter.min(ruleset.special_meter_cost);
             }
         }
 
@@ -1465,72 +3791,156 @@ pub mod rumble_engine {
             );
             // M2 fix: bye fighter must not also appear in a duel
             require!(!seen[bye], RumbleError::DuplicateFighter);
-            let next_meter = combat.meter[bye].saturating_add(METER_PER_TURN);
-            combat.meter[bye] = next_meter.min(SPECIAL_METER_COST);
+            require!(
+                combat.turn_pairings[bye] == PAIRING_BYE,
+                RumbleError::PairingMismatch
+            );
+            let next_meter = combat.meter[bye].saturating_add(ruleset.meter_per_turn);
+            combat.meter[bye] = next_meter.min(ruleset.special_meter_cost);
         }
 
-        // Deterministic elimination ordering: sort by damage dealt descending,
-        // then by fighter index ascending as tiebreaker.
-        eliminated_this_turn.sort_by(|a, b| {
-            combat.total_damage_dealt[*b]
-                .cmp(&combat.total_damage_dealt[*a])
-                .then_with(|| a.cmp(b))
-        });
+        revive_double_knockout(rumble, combat, turn, &mut eliminated_this_turn);
 
         // Handle eliminations (same logic as resolve_turn)
-        for idx in eliminated_this_turn {
-            if combat.elimination_rank[idx] > 0 {
-                continue;
-            }
-            let eliminated_so_far = combat
-                .fighter_count
-                .checked_sub(combat.remaining_fighters)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.elimination_rank[idx] = eliminated_so_far
-                .checked_add(1)
-                .ok_or(RumbleError::MathOverflow)?;
-            combat.remaining_fighters = combat
-                .remaining_fighters
-                .checked_sub(1)
-                .ok_or(RumbleError::MathOverflow)?;
+        for event in rank_turn_eliminations(rumble, combat, eliminated_this_turn)? {
+            emit!(event);
         }
 
         // Check for winner
-        if combat.remaining_fighters == 1 {
-            if let Some((idx, _)) = (0..fighter_count)
-                .filter(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
-                .map(|i| (i, combat.hp[i]))
-                .next()
-            {
-                combat.winner_index = idx as u8;
-            }
-        }
+        record_last_fighter_standing(combat, fighter_count);
 
-        combat.turn_resolved = true;
+        combat.turn_resolved = 1;
 
-        emit!(TurnResolvedEvent {
+        emit!(chain_turn_history(rumble.id, combat, turn, moves, &hp_before));
+
+        Ok(())
+    }
+
+    /// Fighter concedes between turns. Their HP drops to 0 and they take the
+    /// next elimination rank, so `finalize_rumble` places them like any other
+    /// eliminated fighter.
+    #[cfg(feature = "combat")]
+    pub fn forfeit(ctx: Context<Forfeit>, _rumble_id: u64) -> Result<()> {
+        let fighter_idx = concede_fighter(ctx.accounts)?;
+        let rumble = &ctx.accounts.rumble;
+        let combat_state = load_combat(&ctx.accounts.combat_state)?;
+        let combat = &*combat_state;
+
+        emit!(FighterForfeitedEvent {
             rumble_id: rumble.id,
-            turn,
+            fighter: ctx.accounts.fighter.key(),
+            turn: combat.current_turn,
+            elimination_rank: combat.elimination_rank[fighter_idx],
+            remaining_fighters: combat.remaining_fighters,
+        });
+
+        Ok(())
+    }
+
+    /// `forfeit` under the name fighter UIs offer when an owner can't play
+    /// out the rest of a rumble; same accounts and rules, but emits
+    /// `FighterSurrenderedEvent`.
+    #[cfg(feature = "combat")]
+    pub fn surrender(ctx: Context<Forfeit>, _rumble_id: u64) -> Result<()> {
+        let fighter_idx = concede_fighter(ctx.accounts)?;
+        let rumble = &ctx.accounts.rumble;
+        let combat_state = load_combat(&ctx.accounts.combat_state)?;
+        let combat = &*combat_state;
+
+        emit!(FighterSurrenderedEvent {
+            rumble_id: rumble.id,
+            fighter: ctx.accounts.fighter.key(),
+            turn: combat.current_turn,
+            elimination_rank: combat.elimination_rank[fighter_idx],
+            remaining_fighters: combat.remaining_fighters,
+        });
+
+        Ok(())
+    }
+
+    /// Eliminate a fighter that missed `INACTIVITY_TIMEOUT_TURNS` consecutive
+    /// turns (fallback move every time), so the rumble need not wait for
+    /// `COMBAT_TIMEOUT_SLOTS`. Permissionless; only between turns.
+    #[cfg(feature = "combat")]
+    pub fn declare_inactivity_timeout(ctx: Context<CombatAction>, fighter_idx: u8) -> Result<()> {
+        let rumble = &ctx.accounts.rumble;
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
+        stamp_combat_log(&mut ctx.accounts.combat_log, rumble.id, ctx.bumps.combat_log);
+
+        require!(
+            rumble.state == RumbleState::Combat,
+            RumbleError::InvalidStateTransition
+        );
+        require!(combat.turn_resolved != 0, RumbleError::TurnNotResolved);
+
+        let idx = fighter_idx as usize;
+        eliminate_inactive_fighter(combat, idx, rumble.fighter_count as usize)?;
+
+        emit!(FighterInactivityTimeoutEvent {
+            rumble_id: rumble.id,
+            fighter: rumble.fighters[idx],
+            turn: combat.current_turn,
+            missed_turns: combat.missed_turns[idx],
+            elimination_rank: combat.elimination_rank[idx],
             remaining_fighters: combat.remaining_fighters,
         });
 
         Ok(())
     }
 
+    /// Resolve the active turn and, if the fight goes on, open the next one in
+    /// the same transaction. Takes the same remaining accounts as `resolve_turn`.
+    /// Permissionless keeper call.
+    #[cfg(feature = "combat")]
+    pub fn resolve_and_advance(ctx: Context<CombatAction>) -> Result<()> {
+        let clock = Clock::get()?;
+        let rumble = &ctx.accounts.rumble;
+        let ruleset = rumble_ruleset(rumble, ctx.accounts.ruleset.as_deref())?;
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
+        let combat_log = &mut ctx.accounts.combat_log;
+        stamp_combat_log(combat_log, rumble.id, ctx.bumps.combat_log);
+
+        resolve_current_turn(
+            rumble,
+            &ruleset,
+            combat,
+            combat_log,
+            ctx.remaining_accounts,
+            &clock,
+        )?;
+
+        if combat.remaining_fighters > 1 && combat.current_turn < MAX_ONCHAIN_COMBAT_TURNS {
+            let crit_seed = turn_crit_seed(rumble, ctx.accounts.slot_hashes.as_ref())?;
+            open_next_turn(&ctx.accounts.config, rumble, combat, &clock, crit_seed)?;
+        }
+        pay_keeper_bounty(
+            rumble,
+            combat,
+            ctx.accounts.config.keeper_fee_lamports,
+            ctx.accounts.vault.as_ref().zip(ctx.bumps.vault),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )
+    }
+
     /// Advance to next turn after a resolved turn.
     /// Permissionless keeper call.
     #[cfg(feature = "combat")]
     pub fn advance_turn(ctx: Context<CombatAction>) -> Result<()> {
         let clock = Clock::get()?;
         let rumble = &ctx.accounts.rumble;
-        let combat = &mut ctx.accounts.combat_state;
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
+        stamp_combat_log(&mut ctx.accounts.combat_log, rumble.id, ctx.bumps.combat_log);
 
         require!(
             rumble.state == RumbleState::Combat,
             RumbleError::InvalidStateTransition
         );
         require!(combat.current_turn > 0, RumbleError::TurnNotOpen);
-        require!(combat.turn_resolved, RumbleError::TurnNotResolved);
+        require!(combat.turn_resolved != 0, RumbleError::TurnNotResolved);
         require!(
             combat.remaining_fighters > 1,
             RumbleError::CombatAlreadyFinished
@@ -1544,38 +3954,27 @@ pub mod rumble_engine {
             RumbleError::RevealWindowActive
         );
 
-        combat.current_turn = combat
-            .current_turn
-            .checked_add(1)
-            .ok_or(RumbleError::MathOverflow)?;
-        combat.turn_open_slot = clock.slot;
-        combat.commit_close_slot = clock
-            .slot
-            .checked_add(COMMIT_WINDOW_SLOTS)
-            .ok_or(RumbleError::MathOverflow)?;
-        combat.reveal_close_slot = combat
-            .commit_close_slot
-            .checked_add(REVEAL_WINDOW_SLOTS)
-            .ok_or(RumbleError::MathOverflow)?;
-        combat.turn_resolved = false;
-
-        emit!(TurnOpenedEvent {
-            rumble_id: rumble.id,
-            turn: combat.current_turn,
-            turn_open_slot: combat.turn_open_slot,
-            commit_close_slot: combat.commit_close_slot,
-            reveal_close_slot: combat.reveal_close_slot,
-        });
-
-        Ok(())
+        let crit_seed = turn_crit_seed(rumble, ctx.accounts.slot_hashes.as_ref())?;
+        open_next_turn(&ctx.accounts.config, rumble, combat, &clock, crit_seed)?;
+        pay_keeper_bounty(
+            rumble,
+            combat,
+            ctx.accounts.config.keeper_fee_lamports,
+            ctx.accounts.vault.as_ref().zip(ctx.bumps.vault),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )
     }
 
     /// Permissionless deterministic finalization from on-chain combat state.
+    /// Registry accounts passed as in `create_rumble` clear `in_rumble` and
+    /// record each fighter's result through `update_record`.
     #[cfg(feature = "combat")]
     pub fn finalize_rumble(ctx: Context<FinalizeRumble>) -> Result<()> {
         let clock = Clock::get()?;
         let rumble = &mut ctx.accounts.rumble;
-        let combat = &mut ctx.accounts.combat_state;
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
 
         require!(
             rumble.state == RumbleState::Combat,
@@ -1583,16 +3982,17 @@ pub mod rumble_engine {
         );
         require!(combat.current_turn > 0, RumbleError::TurnNotOpen);
 
-        // Check for combat timeout: if current slot is >5000 past the turn_open_slot,
-        // allow finalization even if combat hasn't naturally ended (prevents stuck rumbles).
-        let timed_out = clock.slot
-            > combat
-                .turn_open_slot
-                .checked_add(COMBAT_TIMEOUT_SLOTS)
-                .ok_or(RumbleError::MathOverflow)?;
+        // Combat timeout: past `combat_timeout_slots` since combat started, allow
+        // finalization even if combat hasn't naturally ended (prevents stuck rumbles).
+        // A single stale turn is recovered by `force_resolve_stale_turn` instead.
+        let timed_out = combat_timed_out(
+            combat,
+            ctx.accounts.config.combat_timeout_slots,
+            clock.slot,
+        );
 
         if !timed_out {
-            require!(combat.turn_resolved, RumbleError::TurnNotResolved);
+            require!(combat.turn_resolved != 0, RumbleError::TurnNotResolved);
         }
 
         if combat.remaining_fighters > 1 {
@@ -1679,9 +4079,20 @@ pub mod rumble_engine {
 
         rumble.placements = placements;
         rumble.winner_index = winner_idx as u8;
+        rumble.combat_history_hash = combat.history_hash;
+        rumble.elimination_rank = combat.elimination_rank;
         rumble.state = RumbleState::Payout;
         rumble.completed_at = clock.unix_timestamp;
+        rumble.result_source = RESULT_SOURCE_ONCHAIN;
 
+        pay_keeper_bounty(
+            rumble,
+            combat,
+            ctx.accounts.config.keeper_fee_lamports,
+            Some((&ctx.accounts.vault, ctx.bumps.vault)),
+            ctx.accounts.keeper.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
         extract_result_treasury_cut(
             rumble,
             ctx.accounts.vault.to_account_info(),
@@ -1690,10 +4101,21 @@ pub mod rumble_engine {
             ctx.bumps.vault,
         )?;
 
-        emit!(OnchainResultFinalizedEvent {
-            rumble_id: rumble.id,
+        if let Some(combat_log) = ctx.accounts.combat_log.as_mut() {
+            combat_log.sealed = true;
+        }
+        set_fighters_in_rumble(
+            ctx.remaining_accounts,
+            &rumble.fighters[..fighter_count],
+            false,
+        )?;
+        update_fighter_records(ctx.remaining_accounts, rumble, combat)?;
+
+        emit!(OnchainResultFinalizedEvent {
+            rumble_id: rumble.id,
             winner_index: rumble.winner_index,
             timestamp: clock.unix_timestamp,
+            result_source: rumble.result_source,
         });
 
         Ok(())
@@ -1711,18 +4133,44 @@ pub mod rumble_engine {
 
     /// Admin override to set rumble result directly.
     /// Bypasses combat state machine for off-chain resolution (mainnet betting).
+    /// Only allowed once the betting deadline has passed, and `placements` must
+    /// rank every fighter exactly once (a permutation of 1..=fighter_count).
+    /// Once on-chain combat has opened a turn, the override also needs `force`
+    /// and `FORCE_RESULT_TIMEOUT_SECONDS` since combat started.
+    /// Registry accounts passed as in `create_rumble` clear `in_rumble`.
     pub fn admin_set_result(
         ctx: Context<AdminSetResultAction>,
         placements: Vec<u8>,
         winner_index: u8,
+        force: bool,
     ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::AdminSetResult::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
         let rumble = &mut ctx.accounts.rumble;
         let fighter_count = rumble.fighter_count as usize;
+        let clock = Clock::get()?;
 
         require!(
             rumble.state == RumbleState::Betting || rumble.state == RumbleState::Combat,
             RumbleError::InvalidStateTransition
         );
+        require_betting_ended(rumble, &clock)?;
+        if onchain_combat_turn(&ctx.accounts.combat_state.to_account_info())? > 0 {
+            require!(force, RumbleError::CombatStillActive);
+            let force_after = rumble
+                .combat_started_at
+                .checked_add(FORCE_RESULT_TIMEOUT_SECONDS)
+                .ok_or(RumbleError::MathOverflow)?;
+            require!(
+                clock.unix_timestamp >= force_after,
+                RumbleError::ForceResultTimeoutActive
+            );
+        }
+        require!(pool_meets_minimum(rumble), RumbleError::PoolBelowMinimum);
         validate_result_placements(&placements, fighter_count, winner_index)?;
 
         let mut placement_arr = [0u8; MAX_FIGHTERS];
@@ -1730,11 +4178,11 @@ pub mod rumble_engine {
             placement_arr[i] = p;
         }
 
-        let clock = Clock::get()?;
         rumble.placements = placement_arr;
         rumble.winner_index = winner_index;
         rumble.state = RumbleState::Payout;
         rumble.completed_at = clock.unix_timestamp;
+        rumble.result_source = RESULT_SOURCE_ADMIN;
 
         extract_result_treasury_cut(
             rumble,
@@ -1743,6 +4191,11 @@ pub mod rumble_engine {
             ctx.accounts.system_program.to_account_info(),
             ctx.bumps.vault,
         )?;
+        set_fighters_in_rumble(
+            ctx.remaining_accounts,
+            &rumble.fighters[..fighter_count],
+            false,
+        )?;
 
         msg!(
             "Admin set result for rumble {}: winner_index={}",
@@ -1750,34 +4203,33 @@ pub mod rumble_engine {
             winner_index
         );
 
+        emit!(ResultSetEvent {
+            rumble_id: rumble.id,
+            winner_index,
+            placements,
+            result_source: rumble.result_source,
+        });
+
         Ok(())
     }
 
-    /// Bettor claims their payout if their fighter placed 1st (winner-takes-all).
-    ///
-    /// Payout logic:
-    /// 1. Sum all pools for fighters that did NOT place 1st = losers_pool
-    /// 2. Treasury cut = 3% of losers_pool
-    /// 3. Distributable = losers_pool - treasury_cut
-    /// 4. 1st place bettors split 100% of distributable (winner-takes-all)
-    /// 5. Each winning bettor gets their original bet back + proportional share
-    pub fn claim_payout(ctx: Context<ClaimPayout>) -> Result<()> {
-        let rumble = &ctx.accounts.rumble;
+    /// Challenge an admin-set result. Any wallet with a bettor account on the
+    /// rumble, while it is in Payout and within `DISPUTE_WINDOW_SECONDS` of
+    /// the result. One dispute per rumble. Claims and exacta settlement on an
+    /// admin-set result wait out the window, or until the admin resolves the
+    /// dispute; `complete_rumble` waits for any open dispute.
+    /// `evidence_uri` is stored UTF-8, zero-padded to 128 bytes.
+    pub fn file_dispute(ctx: Context<FileDispute>, evidence_uri: String) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
         let clock = Clock::get()?;
-        let mut bettor_account = {
+        require_dispute_window(rumble, clock.unix_timestamp)?;
+
+        let bettor_account = {
             let data = ctx.accounts.bettor_account.try_borrow_data()?;
             parse_bettor_account_data(&data)?
         };
-
-        require!(
-            rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete,
-            RumbleError::PayoutNotReady
-        );
-
-        require!(!bettor_account.claimed, RumbleError::AlreadyClaimed);
-
         require!(
-            bettor_account.authority == ctx.accounts.bettor.key(),
+            bettor_account.authority == ctx.accounts.disputer.key(),
             RumbleError::Unauthorized
         );
         require!(
@@ -1785,2048 +4237,13206 @@ pub mod rumble_engine {
             RumbleError::InvalidRumble
         );
 
-        let winner_idx = rumble.winner_index as usize;
-        require!(
-            winner_idx < rumble.fighter_count as usize,
-            RumbleError::InvalidFighterIndex
+        let dispute = &mut ctx.accounts.dispute_record;
+        dispute.rumble_id = rumble.id;
+        dispute.disputer = ctx.accounts.disputer.key();
+        dispute.evidence_uri = pad_metadata_field(&evidence_uri)?;
+        dispute.filed_at = clock.unix_timestamp;
+        dispute.resolved = false;
+        dispute.bump = ctx.bumps.dispute_record;
+        rumble.dispute_open = true;
+
+        msg!(
+            "Dispute filed on rumble {} by {}",
+            rumble.id,
+            dispute.disputer
         );
-        let placement = rumble.placements[winner_idx];
+        emit!(DisputeFiledEvent {
+            rumble_id: rumble.id,
+            disputer: dispute.disputer,
+            filed_at: dispute.filed_at,
+        });
+        Ok(())
+    }
 
-        // Lazy accrual model:
-        // If claimable is empty, compute and store this bettor's payout once.
-        if bettor_account.claimable_lamports == 0 {
-            // Winner-takes-all: only 1st place gets a payout
-            require!(placement == 1, RumbleError::NotInPayoutRange);
+    /// Admin closes a rumble's open dispute. `uphold` keeps the result;
+    /// otherwise `placements` / `winner_index` replace it, validated as in
+    /// `admin_set_result`. A correction needs a non-refund result with no
+    /// winning claim paid and the exacta pool unsettled. Any change in the
+    /// treasury cut is settled on the spot: an increase goes from the vault
+    /// to the treasury, a decrease is topped up into the vault by the admin.
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        uphold: bool,
+        placements: Vec<u8>,
+        winner_index: u8,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::ResolveDispute::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-            // Account can hold stakes across multiple fighters.
-            // Only stake deployed on the winning fighter is eligible for payout.
-            let mut winning_deployed = bettor_account.fighter_deployments[winner_idx];
+        let dispute = &mut ctx.accounts.dispute_record;
+        require!(!dispute.resolved, RumbleError::DisputeAlreadyResolved);
+        dispute.resolved = true;
 
-            // Legacy fallback: older accounts only tracked one fighter_index + sol_deployed.
-            if winning_deployed == 0 && bettor_account.fighter_index as usize == winner_idx {
-                winning_deployed = bettor_account.sol_deployed;
-            }
-            require!(winning_deployed > 0, RumbleError::NotInPayoutRange);
-
-            let (first_pool, _losers_pool, _treasury_cut, distributable) =
-                calculate_payout_breakdown(rumble)?;
-
-            // Winner-takes-all: 100% of distributable goes to 1st place bettors
-            let place_allocation = distributable;
-
-            // Bettor's proportional share of the allocation
-            // share = (bettor_winning_deployed / first_pool) * place_allocation
-            // Use u128 intermediate math to prevent overflow when pools exceed ~4 SOL
-            // (u64 overflows at ~1.8×10^19, but lamport products easily reach that)
-            let winnings = if first_pool > 0 {
-                (place_allocation as u128)
-                    .checked_mul(winning_deployed as u128)
-                    .ok_or(RumbleError::MathOverflow)?
-                    .checked_div(first_pool as u128)
-                    .ok_or(RumbleError::MathOverflow)? as u64
-            } else {
-                0
-            };
+        let rumble = &mut ctx.accounts.rumble;
+        rumble.dispute_open = false;
+        rumble.dispute_resolved = true;
 
-            // Total payout = original winning stake + winnings from losers' pool
-            let total_payout = winning_deployed
-                .checked_add(winnings)
-                .ok_or(RumbleError::MathOverflow)?;
+        if !uphold {
+            require!(
+                rumble.state == RumbleState::Payout,
+                RumbleError::InvalidStateTransition
+            );
+            require!(
+                rumble.winner_stake_claimed == 0
+                    && rumble.insurance_paid == 0
+                    && !rumble.exacta_settled
+                    && !refunds_all_bettors(rumble)?,
+                RumbleError::DisputeNotCorrectable
+            );
+            let fighter_count = rumble.fighter_count as usize;
+            validate_result_placements(&placements, fighter_count, winner_index)?;
+
+            let old_transfer = result_treasury_transfer(rumble)?;
+            let mut placement_arr = [0u8; MAX_FIGHTERS];
+            placement_arr[..fighter_count].copy_from_slice(&placements);
+            rumble.placements = placement_arr;
+            rumble.winner_index = winner_index;
+            rumble.stake_bonus_reserve = result_stake_bonus_reserve(rumble)?;
+            let new_transfer = result_treasury_transfer(rumble)?;
+
+            if new_transfer > old_transfer {
+                let owed = new_transfer - old_transfer;
+                let available = vault_available_lamports(ctx.accounts.vault.lamports(), rumble)?;
+                require!(available >= owed, RumbleError::InsufficientVaultFunds);
+                transfer_from_vault(
+                    ctx.accounts.vault.to_account_info(),
+                    ctx.accounts.treasury.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    rumble.id,
+                    ctx.bumps.vault,
+                    owed,
+                )?;
+            } else if old_transfer > new_transfer {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.admin.to_account_info(),
+                            to: ctx.accounts.vault.to_account_info(),
+                        },
+                    ),
+                    old_transfer - new_transfer,
+                )?;
+            }
 
-            bettor_account.claimable_lamports = total_payout;
+            msg!(
+                "Dispute corrected rumble {}: winner_index={}, treasury cut {} -> {}",
+                rumble.id,
+                winner_index,
+                old_transfer,
+                new_transfer
+            );
+            emit!(ResultSetEvent {
+                rumble_id: rumble.id,
+                winner_index,
+                placements,
+                result_source: rumble.result_source,
+            });
         }
 
-        let claimable = bettor_account.claimable_lamports;
-        require!(claimable > 0, RumbleError::NothingToClaim);
-
-        // State update BEFORE CPI transfer (checks-effects-interactions pattern)
-        bettor_account.claimable_lamports = 0;
-        bettor_account.total_claimed_lamports = bettor_account
-            .total_claimed_lamports
-            .checked_add(claimable)
-            .ok_or(RumbleError::MathOverflow)?;
-        bettor_account.last_claim_ts = clock.unix_timestamp;
-        bettor_account.claimed = true;
+        msg!(
+            "Dispute on rumble {} resolved (upheld: {})",
+            rumble.id,
+            uphold
+        );
+        emit!(DisputeResolvedEvent {
+            rumble_id: rumble.id,
+            disputer: dispute.disputer,
+            upheld: uphold,
+        });
+        Ok(())
+    }
 
-        {
-            let mut data = ctx.accounts.bettor_account.try_borrow_mut_data()?;
-            write_bettor_account_data(&mut data, &bettor_account)?;
-        }
+    /// Add bettors to an invite-only rumble's whitelist. Admin-only; keys
+    /// already listed are skipped.
+    pub fn add_to_whitelist(
+        ctx: Context<UpdateWhitelist>,
+        rumble_id: u64,
+        pubkeys: Vec<Pubkey>,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::AddToWhitelist::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-        // Transfer SOL from vault PDA to bettor via System Program CPI signed
-        // by the vault PDA seeds.
-        let vault_info = ctx.accounts.vault.to_account_info();
-        let bettor_info = ctx.accounts.bettor.to_account_info();
-        // Vault PDAs are ephemeral wager buckets; claims must be able to drain
-        // the full balance, otherwise exact-match pools fail due rent reserve.
-        let available = vault_info.lamports();
-        require!(available >= claimable, RumbleError::InsufficientVaultFunds);
+        let whitelist = &mut ctx.accounts.whitelist;
+        add_whitelist_entries(whitelist, &pubkeys)?;
 
-        let rumble_id_bytes = rumble.id.to_le_bytes();
-        let vault_seeds: &[&[u8]] = &[VAULT_SEED, rumble_id_bytes.as_ref(), &[ctx.bumps.vault]];
-        let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+        msg!("Whitelist for rumble {} now has {} bettors", rumble_id, whitelist.count);
+        emit!(WhitelistUpdatedEvent {
+            rumble_id,
+            count: whitelist.count,
+        });
+        Ok(())
+    }
 
-        system_program::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: vault_info,
-                    to: bettor_info,
-                },
-                signer_seeds,
-            ),
-            claimable,
+    /// Remove bettors from an invite-only rumble's whitelist. Admin-only; keys
+    /// not listed are ignored. Bets already placed are unaffected.
+    pub fn remove_from_whitelist(
+        ctx: Context<UpdateWhitelist>,
+        rumble_id: u64,
+        pubkeys: Vec<Pubkey>,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::RemoveFromWhitelist::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
         )?;
 
-        msg!(
-            "Payout claimed: {} lamports (deployed: {}) for rumble {}",
-            claimable,
-            bettor_account.sol_deployed,
-            rumble.id
-        );
+        let whitelist = &mut ctx.accounts.whitelist;
+        remove_whitelist_entries(whitelist, &pubkeys);
 
-        emit!(PayoutClaimedEvent {
-            rumble_id: rumble.id,
-            bettor: ctx.accounts.bettor.key(),
-            fighter_index: rumble.winner_index,
-            placement,
-            amount: claimable,
+        msg!("Whitelist for rumble {} now has {} bettors", rumble_id, whitelist.count);
+        emit!(WhitelistUpdatedEvent {
+            rumble_id,
+            count: whitelist.count,
         });
-
         Ok(())
     }
 
-    /// Fighter owner claims accumulated sponsorship revenue.
-    /// Drains the sponsorship PDA balance to the fighter owner.
-    pub fn claim_sponsorship_revenue(ctx: Context<ClaimSponsorship>) -> Result<()> {
-        // Verify that fighter_owner is the authority of the fighter account.
-        // The authority pubkey is stored at bytes 8..40 (after Anchor's 8-byte discriminator).
-        {
-            let fighter_data = ctx.accounts.fighter.try_borrow_data()?;
-            // NOTE: This discriminator is tied to the fighter_registry program's FighterAccount struct.
-            // If that program is upgraded and changes its account layout, this must be updated.
-            require!(fighter_data.len() >= 40, RumbleError::InvalidFighterAccount);
-            require!(
-                fighter_data[..8] == FIGHTER_ACCOUNT_DISCRIMINATOR,
-                RumbleError::InvalidFighterAccount
-            );
-            let authority_bytes: [u8; 32] = fighter_data[8..40]
-                .try_into()
-                .map_err(|_| error!(RumbleError::InvalidFighterAccount))?;
-            let fighter_authority = Pubkey::new_from_array(authority_bytes);
-            require!(
-                fighter_authority == ctx.accounts.fighter_owner.key(),
-                RumbleError::Unauthorized
-            );
-        }
-
-        let sponsorship_info = ctx.accounts.sponsorship_account.to_account_info();
-        let owner_info = ctx.accounts.fighter_owner.to_account_info();
+    /// Exempt `wallet` from the `place_bet` admin fee until `expires_slot`
+    /// (inclusive). Admin-only; granting again replaces the expiry.
+    pub fn grant_fee_exemption(
+        ctx: Context<GrantFeeExemption>,
+        wallet: Pubkey,
+        expires_slot: u64,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::GrantFeeExemption::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-        // Keep rent-exempt minimum in the sponsorship account
-        let rent = Rent::get()?;
-        let min_balance = rent.minimum_balance(0);
-        let available = sponsorship_info
-            .lamports()
-            .checked_sub(min_balance)
-            .ok_or(RumbleError::InsufficientVaultFunds)?;
+        let clock = Clock::get()?;
+        validate_fee_exemption_grant(
+            &ctx.accounts.config,
+            &ctx.accounts.admin.key(),
+            expires_slot,
+            clock.slot,
+        )?;
 
-        require!(available > 0, RumbleError::NothingToClaim);
+        let exemption = &mut ctx.accounts.fee_exemption;
+        exemption.wallet = wallet;
+        exemption.expires_slot = expires_slot;
+        exemption.bump = ctx.bumps.fee_exemption;
 
-        let fighter_key = ctx.accounts.fighter.key();
-        let sponsorship_seeds: &[&[u8]] = &[
-            SPONSORSHIP_SEED,
-            fighter_key.as_ref(),
-            &[ctx.bumps.sponsorship_account],
-        ];
-        let signer_seeds: &[&[&[u8]]] = &[sponsorship_seeds];
+        msg!("Fee exemption for {} until slot {}", wallet, expires_slot);
+        emit!(FeeExemptionUpdatedEvent {
+            wallet,
+            expires_slot,
+        });
+        Ok(())
+    }
 
-        system_program::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: sponsorship_info,
-                    to: owner_info,
-                },
-                signer_seeds,
-            ),
-            available,
+    /// Close `wallet`'s FeeExemption, returning its rent to the admin.
+    pub fn revoke_fee_exemption(ctx: Context<RevokeFeeExemption>, wallet: Pubkey) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::RevokeFeeExemption::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
         )?;
 
-        msg!(
-            "Sponsorship claimed: {} lamports by {}",
-            available,
-            ctx.accounts.fighter_owner.key()
-        );
-
-        emit!(SponsorshipClaimedEvent {
-            fighter_owner: ctx.accounts.fighter_owner.key(),
-            fighter: ctx.accounts.fighter.key(),
-            amount: available,
+        msg!("Fee exemption revoked for {}", wallet);
+        emit!(FeeExemptionUpdatedEvent {
+            wallet,
+            expires_slot: 0,
         });
-
         Ok(())
     }
 
-    /// Admin transitions rumble to Complete state after all payouts processed.
-    pub fn complete_rumble(ctx: Context<AdminAction>) -> Result<()> {
-        let rumble = &mut ctx.accounts.rumble;
+    /// Seed a rumble's prize pool while betting is open. Any signer for the
+    /// first deposit, which records them as `prize_depositor`; only they can
+    /// add more. The SOL goes to the vault without touching `betting_pools`
+    /// and is paid out on top of the losers' pool in `claim_payout`.
+    pub fn deposit_prize_pool(
+        ctx: Context<DepositPrizePool>,
+        rumble_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        require!(amount > 0, RumbleError::ZeroBetAmount);
 
+        let rumble = &mut ctx.accounts.rumble;
         require!(
-            rumble.state == RumbleState::Payout,
-            RumbleError::InvalidStateTransition
+            rumble.state == RumbleState::Betting,
+            RumbleError::BettingClosed
         );
-
-        let clock = Clock::get()?;
-        let claim_window_end = rumble
-            .completed_at
-            .checked_add(PAYOUT_CLAIM_WINDOW_SECONDS)
-            .ok_or(RumbleError::MathOverflow)?;
+        let depositor = ctx.accounts.depositor.key();
+        if rumble.prize_depositor == Pubkey::default() {
+            rumble.prize_depositor = depositor;
+        }
         require!(
-            clock.unix_timestamp >= claim_window_end,
-            RumbleError::ClaimWindowActive
+            rumble.prize_depositor == depositor,
+            RumbleError::PrizeDepositorMismatch
         );
 
-        rumble.state = RumbleState::Complete;
+        fund_vault_rent_reserve(
+            rumble,
+            &ctx.accounts.vault,
+            ctx.accounts.depositor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.depositor.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
 
-        let config = &mut ctx.accounts.config;
-        config.total_rumbles = config
-            .total_rumbles
-            .checked_add(1)
+        rumble.prize_pool = rumble
+            .prize_pool
+            .checked_add(amount)
             .ok_or(RumbleError::MathOverflow)?;
 
-        msg!("Rumble {} completed", rumble.id);
+        msg!(
+            "Prize pool for rumble {}: +{} lamports ({} total)",
+            rumble_id,
+            amount,
+            rumble.prize_pool
+        );
+
+        emit!(PrizePoolDepositedEvent {
+            rumble_id,
+            depositor: ctx.accounts.depositor.key(),
+            amount,
+            prize_pool: rumble.prize_pool,
+        });
+
         Ok(())
     }
 
-    /// Sweep remaining SOL from a completed Rumble's vault to the treasury.
-    /// Only valid for no-winner-bet rumbles. If anyone bet on the winner,
-    /// payout funds remain claimable indefinitely and the vault must not be
-    /// swept by treasury.
-    pub fn sweep_treasury(ctx: Context<SweepTreasury>) -> Result<()> {
-        let rumble = &ctx.accounts.rumble;
-
+    /// Permissionless: return the prize pool to its depositor once nobody can
+    /// win it, i.e. the rumble was cancelled or its result refunds every
+    /// bettor. Must run before `close_rumble`, which won't drain an
+    /// unrefunded prize pool to the treasury.
+    pub fn refund_prize_pool(ctx: Context<RefundPrizePool>) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
         require!(
-            rumble.state == RumbleState::Complete,
-            RumbleError::InvalidStateTransition
+            prize_pool_refundable(rumble)?,
+            RumbleError::PrizePoolNotRefundable
         );
+        let amount = rumble.prize_pool;
+        require!(amount > 0, RumbleError::NothingToClaim);
 
-        // No-winner-bet rumbles are pure house money and can be swept.
-        // Winner rumbles remain claimable indefinitely, so treasury sweeping is
-        // blocked entirely to avoid draining bettor funds.
-        let winner_pool = winner_pool_lamports(rumble)?;
-        require!(winner_pool == 0, RumbleError::OutstandingWinnerClaims);
+        // State update BEFORE CPI transfer (checks-effects-interactions pattern)
+        rumble.prize_pool = 0;
 
         let vault_info = ctx.accounts.vault.to_account_info();
-        let treasury_info = ctx.accounts.treasury.to_account_info();
-
-        // Keep rent-exempt minimum in the vault
-        let rent = Rent::get()?;
-        let min_balance = rent.minimum_balance(0);
-        let available = vault_info
-            .lamports()
-            .checked_sub(min_balance)
-            .ok_or(RumbleError::InsufficientVaultFunds)?;
-
-        require!(available > 0, RumbleError::NothingToClaim);
+        require!(
+            vault_available_lamports(vault_info.lamports(), rumble)? >= amount,
+            RumbleError::InsufficientVaultFunds
+        );
         transfer_from_vault(
             vault_info,
-            treasury_info,
+            ctx.accounts.depositor.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
             rumble.id,
             ctx.bumps.vault,
-            available,
+            amount,
         )?;
 
         msg!(
-            "Treasury sweep: {} lamports from rumble {} vault to treasury",
-            available,
+            "Prize pool refunded: {} lamports to {} from rumble {}",
+            amount,
+            rumble.prize_depositor,
             rumble.id
         );
-
+        emit!(PrizePoolRefundedEvent {
+            rumble_id: rumble.id,
+            depositor: rumble.prize_depositor,
+            amount,
+        });
         Ok(())
     }
 
-    /// Close a MoveCommitment PDA and return rent to a destination.
-    /// Admin-only. Only allowed when rumble is in Payout or Complete state.
-    #[cfg(feature = "combat")]
-    pub fn close_move_commitment(
-        _ctx: Context<CloseMoveCommitment>,
-        _rumble_id: u64,
-        _turn: u32,
-    ) -> Result<()> {
-        // Anchor's `close = destination` handles the lamport transfer
+    /// Bettor claims their payout if any of their fighters finished in a paying place.
+    ///
+    /// Payout logic:
+    /// 1. Sum all pools for fighters outside the paying places = losers_pool
+    /// 2. Treasury cut = 3% of losers_pool
+    /// 3. Distributable = losers_pool - treasury_cut + prize_pool
+    /// 4. Each paying place receives its split of distributable (default: 100% to 1st)
+    /// 5. Each paying bettor gets their original bet back + proportional share
+    ///
+    /// If nobody backed a paying place, or the rumble was closed as underfunded,
+    /// every bettor is refunded their full net stake instead.
+    ///
+    /// Bettors with a live ICHOR stake (`ichor_stake`) get their winnings raised
+    /// by `bettor_stake_multiplier`, paid from the rumble's stake bonus reserve.
+    pub fn claim_payout(ctx: Context<ClaimPayout>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        let stake_bonus_bps = load_stake_bonus_bps(
+            ctx.accounts.ichor_stake.as_deref(),
+            &ctx.accounts.bettor.key(),
+            &Clock::get()?,
+        )?;
+        let (paid, won) = settle_payout_claim(
+            &mut ctx.accounts.rumble,
+            &ctx.accounts.bettor_account,
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.bumps.vault,
+            stake_bonus_bps,
+            None,
+        )?;
+        if let Some(bettor_index) = ctx.accounts.bettor_index.as_mut() {
+            mark_bettor_index_claimed(bettor_index, ctx.accounts.rumble.id);
+        }
+        if let Some(stats) = ctx.accounts.bettor_stats.as_mut() {
+            record_bettor_stats_claim(stats, paid, won)?;
+        }
         Ok(())
     }
 
-    /// Propose a new admin (two-step transfer).
-    /// Creates/overwrites PendingAdminRE PDA. New admin must call accept_admin.
-    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
-        require!(new_admin != Pubkey::default(), RumbleError::InvalidNewAdmin);
-        require!(
-            new_admin != ctx.accounts.config.admin,
-            RumbleError::InvalidNewAdmin
-        );
-
-        let pending = &mut ctx.accounts.pending_admin;
-        pending.proposed_admin = new_admin;
-        pending.proposed_at = Clock::get()?.slot;
-        pending.bump = ctx.bumps.pending_admin;
-
-        msg!(
-            "Admin transfer proposed: {} -> {}",
-            ctx.accounts.config.admin,
-            new_admin
-        );
+    /// Anyone can crank a bettor's `claim_payout`, e.g. for bettors with no SOL
+    /// left for fees. Same checks as `claim_payout`; the payout still goes to
+    /// `bettor` (the bettor account's authority), minus `config.crank_tip_bps`
+    /// paid to the cranker.
+    pub fn crank_claim_payout(ctx: Context<CrankClaimPayout>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        let stake_bonus_bps = load_stake_bonus_bps(
+            ctx.accounts.ichor_stake.as_deref(),
+            &ctx.accounts.bettor.key(),
+            &Clock::get()?,
+        )?;
+        let (paid, won) = settle_payout_claim(
+            &mut ctx.accounts.rumble,
+            &ctx.accounts.bettor_account,
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.bumps.vault,
+            stake_bonus_bps,
+            Some((
+                ctx.accounts.cranker.to_account_info(),
+                ctx.accounts.config.crank_tip_bps,
+            )),
+        )?;
+        if let Some(bettor_index) = ctx.accounts.bettor_index.as_mut() {
+            mark_bettor_index_claimed(bettor_index, ctx.accounts.rumble.id);
+        }
+        if let Some(stats) = ctx.accounts.bettor_stats.as_mut() {
+            record_bettor_stats_claim(stats, paid, won)?;
+        }
         Ok(())
     }
 
-    /// Accept a pending admin transfer. Must be signed by the proposed admin.
-    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        let pending = &ctx.accounts.pending_admin;
-        let new_admin = ctx.accounts.new_admin.key();
+    /// Insured bettor whose fighters all missed the money recovers
+    /// `insurance_payout_bps` of the insured net stake from the rumble's
+    /// insurance pool, pro-rated when the pool can't cover every insured
+    /// loser (see `insurance_claim_lamports`). Exclusive with winner payouts
+    /// and open only while the rumble is in Payout; `sweep_treasury` takes
+    /// what is left of the pool.
+    pub fn claim_insurance(ctx: Context<ClaimInsurance>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        let clock = Clock::get()?;
+        let rumble = &mut ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Payout,
+            RumbleError::InsuranceNotClaimable
+        );
+        require_claims_open(rumble, clock.unix_timestamp)?;
+        require!(!rumble.vault_halted, RumbleError::VaultHalted);
 
+        let mut bettor_account = {
+            let data = ctx.accounts.bettor_account.try_borrow_data()?;
+            parse_bettor_account_data(&data)?
+        };
         require!(
-            new_admin == pending.proposed_admin,
+            bettor_account.authority == ctx.accounts.bettor.key(),
             RumbleError::Unauthorized
         );
-
-        let old_admin = config.admin;
-        config.admin = new_admin;
-
-        msg!("Admin transferred: {} -> {}", old_admin, new_admin);
-        Ok(())
-    }
-
-    /// Update the treasury address. Admin-only, immediate (lower risk than admin transfer).
-    pub fn update_treasury(ctx: Context<UpdateTreasury>, new_treasury: Pubkey) -> Result<()> {
-        ctx.accounts.config.treasury = new_treasury;
-        msg!("Treasury updated to {}", new_treasury);
-        Ok(())
-    }
-
-    /// Close a completed Rumble PDA to reclaim rent. Admin-only.
-    /// Requires Complete state. Closable only when there are no possible winner
-    /// claims left on-chain:
-    /// - No bets were placed, OR
-    /// - No one bet on the winner
-    /// In both cases any remaining vault balance is drained to treasury first.
-    /// Winner rumbles are only closable after claims have fully drained the
-    /// vault to zero, so bettor claims are never invalidated by a rent-floor
-    /// heuristic or premature sweep.
-    pub fn close_rumble(ctx: Context<CloseRumble>) -> Result<()> {
-        let rumble = &ctx.accounts.rumble;
         require!(
-            rumble.state == RumbleState::Complete,
-            RumbleError::InvalidStateTransition
+            bettor_account.rumble_id == rumble.id,
+            RumbleError::InvalidRumble
+        );
+        require!(
+            !bettor_account.insurance_claimed,
+            RumbleError::InsuranceAlreadyClaimed
         );
 
-        let total_bets: u64 = rumble.betting_pools.iter().sum();
-        let vault_balance = ctx.accounts.vault.lamports();
-        if total_bets == 0 {
-            transfer_from_vault(
-                ctx.accounts.vault.to_account_info(),
-                ctx.accounts.treasury.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-                rumble.id,
-                ctx.bumps.vault,
-                vault_balance,
-            )?;
-            msg!("Rumble {} closed after draining no-bet vault funds", rumble.id);
-            return Ok(());
-        }
+        let payout = insurance_claim_lamports(rumble, &bettor_account)?;
+        require!(payout > 0, RumbleError::NothingToClaim);
 
-        let winner_pool = winner_pool_lamports(rumble)?;
-        if winner_pool > 0 {
-            require!(vault_balance == 0, RumbleError::OutstandingWinnerClaims);
-            msg!(
-                "Rumble {} closed after winner claims fully drained the vault",
-                rumble.id
-            );
-            return Ok(());
+        // State update BEFORE CPI transfer (checks-effects-interactions pattern)
+        bettor_account.insurance_claimed = true;
+        bettor_account.total_claimed_lamports = bettor_account
+            .total_claimed_lamports
+            .checked_add(payout)
+            .ok_or(RumbleError::MathOverflow)?;
+        bettor_account.last_claim_ts = clock.unix_timestamp;
+        {
+            let mut data = ctx.accounts.bettor_account.try_borrow_mut_data()?;
+            write_bettor_account_data(&mut data, &bettor_account)?;
         }
+        rumble.insurance_paid = rumble
+            .insurance_paid
+            .checked_add(payout)
+            .ok_or(RumbleError::MathOverflow)?;
 
+        let available = vault_available_lamports(ctx.accounts.vault.lamports(), rumble)?;
+        require!(available >= payout, RumbleError::InsufficientVaultFunds);
         transfer_from_vault(
             ctx.accounts.vault.to_account_info(),
-            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.bettor.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
             rumble.id,
             ctx.bumps.vault,
-            vault_balance,
+            payout,
         )?;
 
-        msg!("Rumble {} closed after draining no-winner vault funds", rumble.id);
+        msg!(
+            "Insurance claimed: {} lamports to {} from rumble {}",
+            payout,
+            ctx.accounts.bettor.key(),
+            rumble.id
+        );
+        emit!(InsuranceClaimedEvent {
+            rumble_id: rumble.id,
+            bettor: ctx.accounts.bettor.key(),
+            amount: payout,
+        });
         Ok(())
     }
 
-    /// Close a RumbleCombatState PDA to reclaim rent. Admin-only.
-    /// Requires the associated rumble is Complete.
-    #[cfg(feature = "combat")]
-    pub fn close_combat_state(ctx: Context<CloseCombatState>) -> Result<()> {
-        let rumble = &ctx.accounts.rumble;
+    /// Permissionless: settle the exacta pool once the result is in. The
+    /// treasury takes `TREASURY_CUT_BPS` of the stakes that missed; the rest
+    /// is shared by tickets on the exact 1-2 finish. If nobody hit it, the
+    /// whole pool rolls to the treasury. `exacta_combo` is the `ExactaCombo`
+    /// address for the winning pair, whether or not anyone opened it.
+    pub fn settle_exacta_pool(ctx: Context<SettleExactaPool>) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
         require!(
-            rumble.state == RumbleState::Complete,
-            RumbleError::InvalidStateTransition
+            rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete,
+            RumbleError::PayoutNotReady
+        );
+        require!(!rumble.underfunded, RumbleError::PayoutNotReady);
+        require_claims_open(rumble, Clock::get()?.unix_timestamp)?;
+        require!(!rumble.exacta_settled, RumbleError::ExactaAlreadySettled);
+        require!(rumble.exacta_pool > 0, RumbleError::NothingToClaim);
+
+        let (first_index, second_index) = exacta_result(rumble)?;
+        let winning_stake = read_exacta_combo_stake(
+            &ctx.accounts.exacta_combo.to_account_info(),
+            rumble.id,
+            first_index,
+            second_index,
+        )?;
+        let (treasury_cut, distributable) = exacta_settlement(rumble.exacta_pool, winning_stake)?;
+
+        // State update BEFORE CPI transfer (checks-effects-interactions pattern)
+        rumble.exacta_settled = true;
+        rumble.exacta_winning_stake = winning_stake;
+        rumble.exacta_distributable = distributable;
+
+        let vault_info = ctx.accounts.vault.to_account_info();
+        require!(
+            vault_available_lamports(vault_info.lamports(), rumble)? >= treasury_cut,
+            RumbleError::InsufficientVaultFunds
         );
+        transfer_from_vault(
+            vault_info,
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            rumble.id,
+            ctx.bumps.vault,
+            treasury_cut,
+        )?;
 
         msg!(
-            "Combat state for rumble {} closed, rent reclaimed",
-            rumble.id
+            "Exacta settled for rumble {}: #{} then #{}, winning stake {}, treasury {}",
+            rumble.id,
+            first_index,
+            second_index,
+            winning_stake,
+            treasury_cut
         );
+
+        emit!(ExactaSettledEvent {
+            rumble_id: rumble.id,
+            first_index,
+            second_index,
+            winning_stake,
+            treasury_cut,
+            distributable,
+        });
+
         Ok(())
     }
 
-    // -----------------------------------------------------------------------
-    // Ephemeral Rollup delegation (MagicBlock ER)
-    // -----------------------------------------------------------------------
+    /// Bettor claims an exacta ticket and closes it. A ticket on the exact 1-2
+    /// finish gets its share of the settled pool; a missed ticket just returns
+    /// its rent. Cancelled and underfunded rumbles refund the net stake.
+    pub fn claim_exacta_payout(ctx: Context<ClaimExactaPayout>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+        let rumble = &mut ctx.accounts.rumble;
+        let ticket = &ctx.accounts.exacta_ticket;
 
-    /// Delegate a combat state PDA to a MagicBlock Ephemeral Rollup.
-    /// Admin-only. Called after matchmaking, before combat starts on ER.
-    #[cfg(feature = "combat")]
-    pub fn delegate_combat(ctx: Context<DelegateCombat>, rumble_id: u64) -> Result<()> {
+        let is_refund = rumble.state == RumbleState::Cancelled
+            || (rumble.state == RumbleState::Complete && rumble.underfunded);
+        let payout = if is_refund {
+            ticket.amount
+        } else {
+            require!(
+                rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete,
+                RumbleError::PayoutNotReady
+            );
+            require!(rumble.exacta_settled, RumbleError::ExactaNotSettled);
+            let payout = exacta_ticket_payout(rumble, ticket)?;
+            if payout > 0 {
+                rumble.exacta_claimed_stake = rumble
+                    .exacta_claimed_stake
+                    .checked_add(ticket.amount)
+                    .ok_or(RumbleError::MathOverflow)?;
+            }
+            payout
+        };
+
+        let vault_info = ctx.accounts.vault.to_account_info();
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.config.admin,
-            RumbleError::Unauthorized
+            vault_available_lamports(vault_info.lamports(), rumble)? >= payout,
+            RumbleError::InsufficientVaultFunds
         );
-
-        ctx.accounts.delegate_pda(
-            &ctx.accounts.authority,
-            &[COMBAT_STATE_SEED, &rumble_id.to_le_bytes()],
-            DelegateConfig {
-                commit_frequency_ms: 3_000,
-                validator: ctx.remaining_accounts.first().map(|acc| acc.key()),
-                ..Default::default()
-            },
+        transfer_from_vault(
+            vault_info,
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            rumble.id,
+            ctx.bumps.vault,
+            payout,
         )?;
 
         msg!(
-            "Combat state delegated to Ephemeral Rollup for rumble {}",
-            rumble_id
+            "Exacta ticket claimed: {} lamports (staked: {}) for rumble {}",
+            payout,
+            ticket.amount,
+            rumble.id
         );
+
+        if is_refund {
+            emit!(RefundClaimedEvent {
+                rumble_id: rumble.id,
+                bettor: ctx.accounts.bettor.key(),
+                amount: payout,
+            });
+        } else if payout > 0 {
+            emit!(ExactaPayoutClaimedEvent {
+                rumble_id: rumble.id,
+                bettor: ctx.accounts.bettor.key(),
+                first_index: ticket.first_index,
+                second_index: ticket.second_index,
+                amount: payout,
+            });
+        }
+
         Ok(())
     }
 
-    /// Commit combat state from ER back to Solana L1 (periodic sync for spectators).
-    /// Admin-only to prevent unauthorized commits.
-    #[cfg(feature = "combat")]
-    pub fn commit_combat(ctx: Context<CommitCombatSecure>) -> Result<()> {
+    /// Close a BettorAccount PDA and return its rent to the bettor.
+    /// Allowed once the bettor has claimed, or once the rumble has a result (or
+    /// was cancelled) and the bettor is owed nothing. The rumble account is only
+    /// needed for the ineligible path, so claimed bettors can still close after
+    /// the rumble PDA itself has been closed.
+    /// Closed manually rather than via `close = bettor` so legacy 83-byte
+    /// layouts, too short for any `BettorAccount` layout, are reclaimable too.
+    pub fn close_bettor_account(ctx: Context<CloseBettorAccount>, rumble_id: u64) -> Result<()> {
+        let bettor_account = {
+            let data = ctx.accounts.bettor_account.try_borrow_data()?;
+            parse_bettor_account_data(&data)?
+        };
+
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.config.admin,
+            bettor_account.authority == ctx.accounts.bettor.key(),
             RumbleError::Unauthorized
         );
-        // Flush in-memory account mutations before commit CPI so L1 gets
-        // the latest combat state during periodic ER syncs.
-        ctx.accounts.combat_state.exit(&crate::ID)?;
-        commit_accounts(
-            &ctx.accounts.authority,
-            vec![&ctx.accounts.combat_state.to_account_info()],
-            &ctx.accounts.magic_context,
-            &ctx.accounts.magic_program,
-        )?;
-        msg!("Combat state committed to L1");
-        Ok(())
-    }
-
-    /// Commit final combat state and undelegate back to Solana L1.
-    /// Admin-only to prevent adversaries from yanking accounts mid-combat.
-    #[cfg(feature = "combat")]
-    pub fn undelegate_combat(ctx: Context<UndelegateCombat>) -> Result<()> {
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.config.admin,
-            RumbleError::Unauthorized
+            bettor_account.rumble_id == rumble_id,
+            RumbleError::InvalidRumble
         );
-        ctx.accounts.combat_state.exit(&crate::ID)?;
+        require!(
+            bettor_account.claimable_lamports == 0,
+            RumbleError::ClaimableBalanceOutstanding
+        );
+        if !bettor_account.claimed {
+            let rumble = ctx
+                .accounts
+                .rumble
+                .as_ref()
+                .ok_or(RumbleError::BettorNotClaimed)?;
+            require!(
+                bettor_claim_entitlement(rumble, &bettor_account)? == 0,
+                RumbleError::BettorNotClaimed
+            );
+            // Owed nothing after a result: every backed fighter missed the money.
+            if rumble.state != RumbleState::Cancelled {
+                if let Some(stats) = ctx.accounts.bettor_stats.as_mut() {
+                    stats.losses = stats
+                        .losses
+                        .checked_add(1)
+                        .ok_or(RumbleError::MathOverflow)?;
+                }
+            }
+        }
 
-        commit_and_undelegate_accounts(
-            &ctx.accounts.authority,
-            vec![&ctx.accounts.combat_state.to_account_info()],
-            &ctx.accounts.magic_context,
-            &ctx.accounts.magic_program,
+        let rent_lamports = ctx.accounts.bettor_account.lamports();
+        close_program_account(
+            &ctx.accounts.bettor_account,
+            &ctx.accounts.bettor.to_account_info(),
         )?;
-        msg!("Combat state undelegated back to L1");
+
+        msg!(
+            "Bettor account closed for rumble {}: {} lamports returned",
+            rumble_id,
+            rent_lamports
+        );
         Ok(())
     }
 
-    /// Request provably-fair matchup seed via MagicBlock VRF.
-    ///
-    /// Admin calls this after combat starts to get a VRF-derived seed
-    /// for fair fighter pairing. The VRF oracle will automatically call
-    /// `callback_matchup_seed` with the randomness result.
-    #[cfg(feature = "combat")]
-    pub fn request_matchup_seed(
-        ctx: Context<RequestMatchupSeed>,
+    /// Close a BetRecord PDA and return its rent to the bettor once the rumble
+    /// is Complete or Cancelled, or has been closed altogether.
+    pub fn close_bet_record(
+        ctx: Context<CloseBetRecord>,
         rumble_id: u64,
-        client_seed: u8,
+        _sequence: u32,
     ) -> Result<()> {
-        let config = &ctx.accounts.config;
-        require!(
-            ctx.accounts.payer.key() == config.admin,
-            RumbleError::Unauthorized
-        );
-
-        let combat = &ctx.accounts.combat_state;
-        require!(combat.rumble_id == rumble_id, RumbleError::InvalidRumble);
-        require!(combat.vrf_seed == [0u8; 32], RumbleError::VrfSeedAlreadySet);
+        let rumble_info = ctx.accounts.rumble.to_account_info();
+        if !rumble_info.data_is_empty() {
+            require!(*rumble_info.owner == crate::ID, RumbleError::InvalidRumble);
+            let data = rumble_info.try_borrow_data()?;
+            let mut slice: &[u8] = &data;
+            let rumble = Rumble::try_deserialize(&mut slice)?;
+            require!(
+                rumble.state == RumbleState::Complete || rumble.state == RumbleState::Cancelled,
+                RumbleError::InvalidStateTransition
+            );
+        }
 
-        // Capture keys before CPI
-        let payer_key = ctx.accounts.payer.key();
-        let oracle_queue_key = ctx.accounts.oracle_queue.key();
-        let combat_state_key = ctx.accounts.combat_state.key();
+        msg!(
+            "Bet record {} closed for rumble {}",
+            ctx.accounts.bet_record.sequence,
+            rumble_id
+        );
+        Ok(())
+    }
 
-        let ix = create_request_randomness_ix(
-            ephemeral_vrf_sdk::instructions::RequestRandomnessParams {
-                payer: payer_key,
-                oracle_queue: oracle_queue_key,
-                callback_program_id: crate::ID,
-                callback_discriminator: instruction::CallbackMatchupSeed::DISCRIMINATOR.to_vec(),
-                caller_seed: [client_seed; 32],
-                accounts_metas: Some(vec![SerializableAccountMeta {
-                    pubkey: combat_state_key,
-                    is_signer: false,
-                    is_writable: true,
-                }]),
-                ..Default::default()
-            },
+    /// Fighter owner claims accumulated sponsorship revenue: exactly the
+    /// ledger's `total_accrued - total_claimed`. The PDA keeps its rent reserve.
+    pub fn claim_sponsorship_revenue(ctx: Context<ClaimSponsorship>) -> Result<()> {
+        // Verify that fighter_owner is the authority of the fighter account.
+        // The authority pubkey is stored at bytes 8..40 (after Anchor's 8-byte discriminator).
+        {
+            let fighter_data = ctx.accounts.fighter.try_borrow_data()?;
+            // NOTE: This discriminator is tied to the fighter_registry program's FighterAccount struct.
+            // If that program is upgraded and changes its account layout, this must be updated.
+            require!(fighter_data.len() >= 40, RumbleError::InvalidFighterAccount);
+            require!(
+                fighter_data[..8] == FIGHTER_ACCOUNT_DISCRIMINATOR,
+                RumbleError::InvalidFighterAccount
+            );
+            let authority_bytes: [u8; 32] = fighter_data[8..40]
+                .try_into()
+                .map_err(|_| error!(RumbleError::InvalidFighterAccount))?;
+            let fighter_authority = Pubkey::new_from_array(authority_bytes);
+            require!(
+                fighter_authority == ctx.accounts.fighter_owner.key(),
+                RumbleError::Unauthorized
+            );
+        }
+
+        let sponsorship_info = ctx.accounts.sponsorship_account.to_account_info();
+        let owner_info = ctx.accounts.fighter_owner.to_account_info();
+
+        let rent_minimum = Rent::get()?.minimum_balance(0);
+        let ledger = &mut ctx.accounts.sponsorship_ledger;
+        open_sponsorship_ledger(
+            ledger,
+            ctx.accounts.fighter.key(),
+            ctx.bumps.sponsorship_ledger,
+            sponsorship_info.lamports(),
+            rent_minimum,
         );
-        ctx.accounts
-            .invoke_signed_vrf(&ctx.accounts.payer.to_account_info(), &ix)?;
+        let available = claimable_sponsorship(ledger, sponsorship_info.lamports(), rent_minimum)?;
 
-        msg!("VRF matchup seed requested for rumble {}", rumble_id);
-        Ok(())
-    }
+        require!(available > 0, RumbleError::NothingToClaim);
+        ledger.total_claimed = ledger
+            .total_claimed
+            .checked_add(available)
+            .ok_or(RumbleError::MathOverflow)?;
 
-    /// Callback from MagicBlock VRF oracle with matchup randomness.
-    ///
-    /// Only the VRF oracle (VRF_PROGRAM_IDENTITY signer) can call this.
-    /// Stores the randomness in RumbleCombatState.vrf_seed for fair pairing.
-    #[cfg(feature = "combat")]
-    pub fn callback_matchup_seed(
-        ctx: Context<CallbackMatchupSeed>,
-        randomness: [u8; 32],
-    ) -> Result<()> {
-        let combat = &mut ctx.accounts.combat_state;
-        require!(combat.vrf_seed == [0u8; 32], RumbleError::VrfSeedAlreadySet);
+        let fighter_key = ctx.accounts.fighter.key();
+        let sponsorship_seeds: &[&[u8]] = &[
+            SPONSORSHIP_SEED,
+            fighter_key.as_ref(),
+            &[ctx.bumps.sponsorship_account],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[sponsorship_seeds];
 
-        combat.vrf_seed = randomness;
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: sponsorship_info,
+                    to: owner_info,
+                },
+                signer_seeds,
+            ),
+            available,
+        )?;
+
+        msg!(
+            "Sponsorship claimed: {} lamports by {}",
+            available,
+            ctx.accounts.fighter_owner.key()
+        );
+
+        emit!(SponsorshipClaimedEvent {
+            fighter_owner: ctx.accounts.fighter_owner.key(),
+            fighter: ctx.accounts.fighter.key(),
+            amount: available,
+            total_claimed: ledger.total_claimed,
+        });
 
-        msg!("VRF matchup seed stored for rumble {}", combat.rumble_id);
         Ok(())
     }
-}
 
-// ---------------------------------------------------------------------------
-// Accounts
-// ---------------------------------------------------------------------------
+    /// Referrer claims the referral fees `place_bet` paid into their PDA.
+    /// The PDA keeps its rent reserve.
+    pub fn claim_referral_revenue(ctx: Context<ClaimReferral>) -> Result<()> {
+        let referral_info = ctx.accounts.referral_account.to_account_info();
+        let referrer_info = ctx.accounts.referrer.to_account_info();
 
-#[derive(Accounts)]
-pub struct InitializeConfig<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+        let rent_minimum = Rent::get()?.minimum_balance(0);
+        let available = referral_info.lamports().saturating_sub(rent_minimum);
+        require!(available > 0, RumbleError::NothingToClaim);
 
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + RumbleConfig::INIT_SPACE,
-        seeds = [CONFIG_SEED],
-        bump
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        let referrer_key = ctx.accounts.referrer.key();
+        let referral_seeds: &[&[u8]] = &[
+            REFERRAL_SEED,
+            referrer_key.as_ref(),
+            &[ctx.bumps.referral_account],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[referral_seeds];
 
-    /// CHECK: Treasury wallet address, validated by admin at init time.
-    pub treasury: AccountInfo<'info>,
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: referral_info,
+                    to: referrer_info,
+                },
+                signer_seeds,
+            ),
+            available,
+        )?;
 
-    pub system_program: Program<'info, System>,
-}
+        msg!("Referral revenue claimed: {} lamports by {}", available, referrer_key);
 
-#[derive(Accounts)]
-#[instruction(rumble_id: u64, fighters: Vec<Pubkey>, betting_deadline: i64)]
-pub struct CreateRumble<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        emit!(ReferralClaimedEvent {
+            referrer: referrer_key,
+            amount: available,
+        });
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        Ok(())
+    }
 
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + Rumble::INIT_SPACE,
-        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub rumble: Account<'info, Rumble>,
+    /// Admin transitions rumble to Complete state after all payouts processed.
+    pub fn complete_rumble(ctx: Context<AdminAction>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CompleteRumble::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    pub system_program: Program<'info, System>,
-}
+        let clock = Clock::get()?;
+        let rumble = &mut ctx.accounts.rumble;
+        mark_rumble_complete(
+            rumble,
+            &mut ctx.accounts.config,
+            ctx.accounts
+                .global_stats
+                .as_deref_mut()
+                .map(|stats| &mut **stats),
+            clock.unix_timestamp,
+        )?;
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct AuthorizeFighterDelegate<'info> {
-    #[account(mut)]
-    pub fighter: Signer<'info>,
+        msg!("Rumble {} completed", rumble.id);
 
-    #[account(
-        init_if_needed,
-        payer = sponsor,
-        space = 8 + FighterDelegate::INIT_SPACE,
-        seeds = [FIGHTER_DELEGATE_SEED, fighter.key().as_ref()],
-        bump
-    )]
-    pub fighter_delegate: Account<'info, FighterDelegate>,
+        emit!(RumbleCompletedEvent {
+            rumble_id: rumble.id,
+            total_deployed: rumble.total_deployed,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub sponsor: Signer<'info>,
+    /// Permissionless `complete_rumble`, under the same claim-window gate, so
+    /// `sweep_treasury` and `close_rumble` don't wait on the admin. Whichever
+    /// of the two lands first completes the rumble; the other fails with
+    /// `InvalidStateTransition`, so `total_rumbles` moves once.
+    pub fn finalize_complete(ctx: Context<FinalizeComplete>) -> Result<()> {
+        let clock = Clock::get()?;
+        let rumble = &mut ctx.accounts.rumble;
+        mark_rumble_complete(
+            rumble,
+            &mut ctx.accounts.config,
+            ctx.accounts
+                .global_stats
+                .as_deref_mut()
+                .map(|stats| &mut **stats),
+            clock.unix_timestamp,
+        )?;
 
-    pub system_program: Program<'info, System>,
-}
+        msg!(
+            "Rumble {} completed by keeper {}",
+            rumble.id,
+            ctx.accounts.caller.key()
+        );
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct RevokeFighterDelegate<'info> {
-    #[account(mut)]
-    pub fighter: Signer<'info>,
+        emit!(RumbleCompletedEvent {
+            rumble_id: rumble.id,
+            total_deployed: rumble.total_deployed,
+            timestamp: clock.unix_timestamp,
+        });
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [FIGHTER_DELEGATE_SEED, fighter.key().as_ref()],
-        bump = fighter_delegate.bump,
-        constraint = fighter_delegate.fighter == fighter.key() @ RumbleError::Unauthorized,
-    )]
-    pub fighter_delegate: Account<'info, FighterDelegate>,
-}
+    /// Permissionless circuit breaker: halts claims on a rumble whose vault
+    /// holds less than it still owes bettors (see `expected_vault_minimum`).
+    /// A healthy vault leaves the rumble untouched; only the admin lifts a halt.
+    pub fn verify_vault_integrity(ctx: Context<VerifyVaultIntegrity>) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete,
+            RumbleError::PayoutNotReady
+        );
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-#[instruction(rumble_id: u64, turn: u32)]
-pub struct CommitMove<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        let vault_lamports = ctx.accounts.vault.lamports();
+        let expected_minimum = expected_vault_minimum(rumble)?;
+        if vault_lamports >= expected_minimum {
+            msg!(
+                "Rumble {} vault holds {} lamports, {} expected",
+                rumble.id,
+                vault_lamports,
+                expected_minimum
+            );
+            return Ok(());
+        }
 
-    /// CHECK: Fighter wallet identity. Must match either the authority signer
-    /// or an active persistent fighter delegate PDA.
-    pub fighter: UncheckedAccount<'info>,
+        rumble.vault_halted = true;
+        msg!(
+            "Rumble {} vault halted: {} lamports, {} expected",
+            rumble.id,
+            vault_lamports,
+            expected_minimum
+        );
+        emit!(VaultHaltedEvent {
+            rumble_id: rumble.id,
+            vault_lamports,
+            expected_minimum,
+            caller: ctx.accounts.caller.key(),
+        });
+        Ok(())
+    }
 
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    /// Admin resumes claims on a rumble halted by `verify_vault_integrity`,
+    /// once the shortfall has been investigated.
+    pub fn admin_lift_vault_halt(ctx: Context<AdminAction>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::AdminLiftVaultHalt::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        let rumble = &mut ctx.accounts.rumble;
+        require!(rumble.vault_halted, RumbleError::VaultNotHalted);
+        rumble.vault_halted = false;
 
-    #[account(
-        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble_id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
+        msg!("Rumble {} vault halt lifted", rumble.id);
+        emit!(VaultHaltLiftedEvent {
+            rumble_id: rumble.id,
+            admin: ctx.accounts.admin.key(),
+        });
+        Ok(())
+    }
 
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + MoveCommitment::INIT_SPACE,
-        seeds = [
-            MOVE_COMMIT_SEED,
-            rumble_id.to_le_bytes().as_ref(),
-            fighter.key().as_ref(),
-            turn.to_le_bytes().as_ref(),
-        ],
-        bump
-    )]
-    pub move_commitment: Account<'info, MoveCommitment>,
+    /// Admin aborts a rumble that has not produced a result yet.
+    /// Bettors recover their net stake through `claim_refund`.
+    /// Registry accounts passed as in `create_rumble` clear `in_rumble`.
+    pub fn cancel_rumble(ctx: Context<AdminAction>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CancelRumble::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    /// CHECK: Optional persistent fighter delegate PDA, validated manually when authority != fighter.
-    pub fighter_delegate: UncheckedAccount<'info>,
+        let rumble = &mut ctx.accounts.rumble;
 
-    pub system_program: Program<'info, System>,
-}
+        require!(
+            rumble.state == RumbleState::Betting || rumble.state == RumbleState::Combat,
+            RumbleError::InvalidStateTransition
+        );
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-#[instruction(rumble_id: u64, turn: u32)]
-pub struct RevealMove<'info> {
-    pub authority: Signer<'info>,
+        let clock = Clock::get()?;
+        rumble.state = RumbleState::Cancelled;
+        rumble.cancelled_at = clock.unix_timestamp;
+        set_fighters_in_rumble(
+            ctx.remaining_accounts,
+            &rumble.fighters[..rumble.fighter_count as usize],
+            false,
+        )?;
 
-    /// CHECK: Fighter wallet identity. Must match either the authority signer
-    /// or an active persistent fighter delegate PDA.
-    pub fighter: UncheckedAccount<'info>,
+        msg!(
+            "Rumble {} cancelled. {} lamports refundable to bettors",
+            rumble.id,
+            rumble.total_deployed
+        );
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        emit!(RumbleCancelledEvent {
+            rumble_id: rumble.id,
+            total_refundable: rumble.total_deployed,
+            timestamp: clock.unix_timestamp,
+        });
 
-    #[account(
-        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble_id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [
-            MOVE_COMMIT_SEED,
-            rumble_id.to_le_bytes().as_ref(),
-            fighter.key().as_ref(),
-            turn.to_le_bytes().as_ref(),
-        ],
-        bump = move_commitment.bump,
-        constraint = move_commitment.fighter == fighter.key() @ RumbleError::Unauthorized,
-        constraint = move_commitment.rumble_id == rumble_id @ RumbleError::InvalidRumble,
-        constraint = move_commitment.turn == turn @ RumbleError::InvalidTurn,
-    )]
-    pub move_commitment: Account<'info, MoveCommitment>,
+    /// Close a rumble whose pool missed `min_pool_lamports` by the betting deadline.
+    /// Admin can call this as soon as the deadline passes; anyone can after
+    /// the underfunded-cancel grace period. The rumble skips combat and payout math
+    /// and moves straight to Complete, with `claim_payout` refunding each bettor's
+    /// net stake (platform and sponsorship fees are not returned).
+    /// Registry accounts passed as in `create_rumble` clear `in_rumble`.
+    pub fn cancel_underfunded_rumble(ctx: Context<CancelUnderfundedRumble>) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
 
-    /// CHECK: Optional persistent fighter delegate PDA, validated manually when authority != fighter.
-    pub fighter_delegate: UncheckedAccount<'info>,
-}
+        require!(
+            rumble.state == RumbleState::Betting,
+            RumbleError::InvalidStateTransition
+        );
+        require!(!pool_meets_minimum(rumble), RumbleError::PoolMeetsMinimum);
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct StartCombat<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        let clock = Clock::get()?;
+        require_betting_ended(rumble, &clock)?;
+        if ctx.accounts.caller.key() != ctx.accounts.config.admin {
+            let grace_elapsed = if rumble.betting_close_slot != 0 {
+                let grace_end = rumble
+                    .betting_close_slot
+                    .checked_add(UNDERFUNDED_CANCEL_GRACE_SLOTS.unsigned_abs())
+                    .ok_or(RumbleError::MathOverflow)?;
+                clock.slot >= grace_end
+            } else {
+                let grace = match rumble.deadline_kind {
+                    DeadlineKind::Slot => UNDERFUNDED_CANCEL_GRACE_SLOTS,
+                    DeadlineKind::UnixTimestamp => UNDERFUNDED_CANCEL_GRACE_SECONDS,
+                };
+                let grace_end = rumble
+                    .betting_deadline
+                    .checked_add(grace)
+                    .ok_or(RumbleError::MathOverflow)?;
+                deadline_clock_value(rumble.deadline_kind, &clock)? >= grace_end
+            };
+            require!(grace_elapsed, RumbleError::Unauthorized);
+        }
 
-    #[account(
-        mut,
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        rumble.state = RumbleState::Complete;
+        rumble.underfunded = true;
+        rumble.completed_at = clock.unix_timestamp;
+        set_fighters_in_rumble(
+            ctx.remaining_accounts,
+            &rumble.fighters[..rumble.fighter_count as usize],
+            false,
+        )?;
 
-    #[account(
-        mut,
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        msg!(
+            "Rumble {} closed as underfunded: pool {} below minimum {}",
+            rumble.id,
+            rumble.total_deployed,
+            rumble.min_pool_lamports
+        );
 
-    #[account(
-        init_if_needed,
-        payer = admin,
-        space = 8 + RumbleCombatState::INIT_SPACE,
-        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
+        emit!(UnderfundedRumbleCancelledEvent {
+            rumble_id: rumble.id,
+            total_deployed: rumble.total_deployed,
+            min_pool_lamports: rumble.min_pool_lamports,
+            timestamp: clock.unix_timestamp,
+        });
 
-    pub system_program: Program<'info, System>,
-}
+        Ok(())
+    }
 
-/// Permissionless combat action — open_turn, resolve_turn, advance_turn.
-/// Anyone can call these; correctness is enforced by on-chain state machine.
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct CombatAction<'info> {
-    #[account(mut)]
-    pub keeper: Signer<'info>,
+    /// Cancel a rumble that reached its betting deadline with fewer distinct
+    /// bettors than its `min_bettors`. Permissionless; bettors recover their
+    /// net stake through `claim_refund`.
+    /// Registry accounts passed as in `create_rumble` clear `in_rumble`.
+    pub fn cancel_underfilled_rumble(ctx: Context<CancelUnderfilledRumble>) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
+        let clock = Clock::get()?;
+        cancel_underfilled(rumble, &clock)?;
+        set_fighters_in_rumble(
+            ctx.remaining_accounts,
+            &rumble.fighters[..rumble.fighter_count as usize],
+            false,
+        )?;
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        msg!(
+            "Rumble {} cancelled as underfilled: {} bettors, minimum {}",
+            rumble.id,
+            rumble.unique_bettor_count,
+            rumble.min_bettors
+        );
 
-    #[account(
-        mut,
-        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble.id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
-}
+        emit!(UnderfilledRumbleCancelledEvent {
+            rumble_id: rumble.id,
+            unique_bettor_count: rumble.unique_bettor_count,
+            min_bettors: rumble.min_bettors,
+            timestamp: clock.unix_timestamp,
+        });
 
-/// Admin-gated combat action — post_turn_result (hybrid mode).
-/// Admin posts move results; damage is validated on-chain.
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct AdminCombatAction<'info> {
-    #[account(mut)]
-    pub keeper: Signer<'info>,
+        Ok(())
+    }
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-        constraint = keeper.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+    /// Bettor reclaims their full net stake from a cancelled rumble.
+    /// Refunds every per-fighter deployment and any insurance premiums;
+    /// platform and sponsorship fees are not returned.
+    pub fn claim_refund(ctx: Context<ClaimPayout>) -> Result<()> {
+        let rumble = &mut ctx.accounts.rumble;
+        let clock = Clock::get()?;
+        let mut bettor_account = {
+            let data = ctx.accounts.bettor_account.try_borrow_data()?;
+            parse_bettor_account_data(&data)?
+        };
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        require!(
+            rumble.state == RumbleState::Cancelled,
+            RumbleError::RumbleNotCancelled
+        );
+        require!(!bettor_account.claimed, RumbleError::AlreadyClaimed);
+        require!(
+            bettor_account.authority == ctx.accounts.bettor.key(),
+            RumbleError::Unauthorized
+        );
+        require!(
+            bettor_account.rumble_id == rumble.id,
+            RumbleError::InvalidRumble
+        );
 
-    #[account(
-        mut,
-        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble.id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
-}
+        let refund = bettor_refund_lamports(&bettor_account)?;
+        require!(refund > 0, RumbleError::NothingToClaim);
 
-/// Permissionless finalization — anyone can finalize when state machine allows it.
-/// Correctness is enforced by on-chain combat state (winner, placements, timeouts).
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct FinalizeRumble<'info> {
-    #[account(mut)]
-    pub keeper: Signer<'info>,
+        // State update BEFORE CPI transfer (checks-effects-interactions pattern)
+        bettor_account.claimable_lamports = 0;
+        bettor_account.total_claimed_lamports = bettor_account
+            .total_claimed_lamports
+            .checked_add(refund)
+            .ok_or(RumbleError::MathOverflow)?;
+        bettor_account.last_claim_ts = clock.unix_timestamp;
+        bettor_account.claimed = true;
+        release_refunded_premium(rumble, &bettor_account)?;
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        {
+            let mut data = ctx.accounts.bettor_account.try_borrow_mut_data()?;
+            write_bettor_account_data(&mut data, &bettor_account)?;
+        }
 
-    #[account(
-        mut,
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        let vault_info = ctx.accounts.vault.to_account_info();
+        require!(
+            vault_available_lamports(vault_info.lamports(), rumble)? >= refund,
+            RumbleError::InsufficientVaultFunds
+        );
+        transfer_from_vault(
+            vault_info,
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            rumble.id,
+            ctx.bumps.vault,
+            refund,
+        )?;
 
-    #[account(
-        mut,
-        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble.id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
+        msg!("Refund claimed: {} lamports for rumble {}", refund, rumble.id);
 
-    /// CHECK: Vault PDA holding payout SOL for this rumble.
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+        emit!(RefundClaimedEvent {
+            rumble_id: rumble.id,
+            bettor: ctx.accounts.bettor.key(),
+            amount: refund,
+        });
 
-    /// CHECK: Treasury address, must match config.
-    #[account(
-        mut,
-        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
-    )]
-    pub treasury: AccountInfo<'info>,
+        if let Some(bettor_index) = ctx.accounts.bettor_index.as_mut() {
+            mark_bettor_index_claimed(bettor_index, ctx.accounts.rumble.id);
+        }
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Sweep remaining SOL from a completed Rumble's vault to the treasury.
+    /// No-winner-bet and cancelled rumbles can be swept only after the refund
+    /// window has elapsed. Neither pays anyone the prize pool, so it stays in
+    /// the vault for `refund_prize_pool`.
+    /// Winner rumbles can be swept once every winning bettor has claimed. While
+    /// any winner is unclaimed the sweep fails with `UnclaimedPayoutsRemain`,
+    /// unless `force` is set and the payout claim window has passed; a forced
+    /// sweep strands the outstanding winners, whose liability is logged.
+    pub fn sweep_treasury(ctx: Context<SweepTreasury>, force: bool) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::SweepTreasury::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-#[derive(Accounts)]
-#[instruction(rumble_id: u64, fighter_index: u8, amount: u64)]
-pub struct PlaceBet<'info> {
-    #[account(mut)]
-    pub bettor: Signer<'info>,
+        let rumble = &mut ctx.accounts.rumble;
 
-    #[account(
-        mut,
-        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        let mut held_prize_pool = rumble.prize_pool;
+        if rumble.state == RumbleState::Cancelled {
+            require_refund_window_elapsed(rumble.cancelled_at)?;
+        } else {
+            require!(
+                rumble.state == RumbleState::Complete,
+                RumbleError::InvalidStateTransition
+            );
 
-    /// Vault PDA that holds all bet SOL for this rumble.
-    /// CHECK: PDA derived from vault seed + rumble_id. Just holds lamports.
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+            // An unsettled exacta pool or unclaimed winning exacta tickets
+            // block the sweep outright.
+            require!(
+                !exacta_claims_outstanding(rumble),
+                RumbleError::OutstandingWinnerClaims
+            );
 
-    /// CHECK: Treasury address, must match config.
-    #[account(
-        mut,
-        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
-    )]
-    pub treasury: AccountInfo<'info>,
+            // No-winner-bet and underfunded rumbles refund every stake, so the
+            // leftovers can only be swept once the refund window has closed.
+            if refunds_all_bettors(rumble)? {
+                require_refund_window_elapsed(rumble.completed_at)?;
+            } else {
+                let liability =
+                    require_winner_sweep_allowed(rumble, force, Clock::get()?.unix_timestamp)?;
+                msg!(
+                    "Rumble {} outstanding winner liability: {} lamports ({} of {} claimed)",
+                    rumble.id,
+                    liability,
+                    rumble.total_claimed_lamports,
+                    rumble.winner_pool_snapshot
+                );
+                // Winners shared the prize pool; none of it is held back.
+                held_prize_pool = 0;
+            }
+        }
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let treasury_info = ctx.accounts.treasury.to_account_info();
 
-    /// Sponsorship account PDA for the fighter being bet on.
-    /// CHECK: PDA derived from sponsorship seed + fighter pubkey. Holds lamports.
-    #[account(
-        mut,
-        seeds = [SPONSORSHIP_SEED, rumble.fighters[fighter_index as usize].as_ref()],
-        bump
-    )]
-    pub sponsorship_account: SystemAccount<'info>,
+        // Keep rent-exempt minimum in the vault
+        let rent = Rent::get()?;
+        let min_balance = rent.minimum_balance(0);
+        let available = sweepable_lamports(vault_info.lamports(), min_balance, held_prize_pool)?;
 
-    #[account(
-        init_if_needed,
-        payer = bettor,
-        space = 8 + BettorAccount::INIT_SPACE,
-        seeds = [BETTOR_SEED, rumble_id.to_le_bytes().as_ref(), bettor.key().as_ref()],
-        bump
-    )]
-    pub bettor_account: Account<'info, BettorAccount>,
+        require!(available > 0, RumbleError::NothingToClaim);
+        transfer_from_vault(
+            vault_info.clone(),
+            treasury_info,
+            ctx.accounts.system_program.to_account_info(),
+            rumble.id,
+            ctx.bumps.vault,
+            available,
+        )?;
+        rumble.swept_lamports = rumble
+            .swept_lamports
+            .checked_add(available)
+            .ok_or(RumbleError::MathOverflow)?;
 
-    pub system_program: Program<'info, System>,
-}
+        msg!(
+            "Treasury sweep: {} lamports from rumble {} vault to treasury ({} unclaimed insurance)",
+            available,
+            rumble.id,
+            rumble.insurance_pool.saturating_sub(rumble.insurance_paid)
+        );
 
-#[derive(Accounts)]
-pub struct AdminAction<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        emit!(TreasurySweptEvent {
+            rumble_id: rumble.id,
+            amount: available,
+            vault_remaining: vault_info.lamports(),
+        });
 
-    #[account(
-        mut,
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        Ok(())
+    }
 
-    #[account(
-        mut,
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
-}
+    /// Close a fighter's MoveCommitment PDA and return rent to a destination.
+    /// Admin-only. Only allowed when rumble is in Combat, Payout or Complete state.
+    #[cfg(feature = "combat")]
+    pub fn close_move_commitment(ctx: Context<CloseMoveCommitment>, _rumble_id: u64) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseMoveCommitment::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-#[derive(Accounts)]
-pub struct AdminSetResultAction<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        // Anchor's `close = destination` handles the lamport transfer
+        Ok(())
+    }
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+    /// Close a legacy per-turn MoveCommitment PDA (seeded with the turn
+    /// number) left over from before commitments were reused across turns.
+    /// Admin-only, same rumble state rules as `close_move_commitment`.
+    #[cfg(feature = "combat")]
+    pub fn close_legacy_move_commitment(
+        ctx: Context<CloseLegacyMoveCommitment>,
+        _rumble_id: u64,
+        _turn: u32,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseLegacyMoveCommitment::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        mut,
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        Ok(())
+    }
 
-    /// CHECK: Vault PDA holding payout SOL for this rumble.
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+    /// Close up to `MAX_MOVE_COMMIT_CLOSE_BATCH` MoveCommitment PDAs in one call.
+    /// Admin-only, once the rumble is in Payout or Complete. Each PDA
+    /// must be passed (writable) in `remaining_accounts`; rent goes to `destination`.
+    /// An entry matches either the fighter's PDA or its legacy per-turn PDA,
+    /// as long as the stored turn equals `entry.turn`.
+    #[cfg(feature = "combat")]
+    pub fn close_move_commitments_batch(
+        ctx: Context<CloseMoveCommitmentsBatch>,
+        rumble_id: u64,
+        commitments: Vec<MoveCommitmentKey>,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseMoveCommitmentsBatch::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    /// CHECK: Treasury address, must match config.
-    #[account(
-        mut,
-        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
-    )]
-    pub treasury: AccountInfo<'info>,
+        require!(
+            !commitments.is_empty() && commitments.len() <= MAX_MOVE_COMMIT_CLOSE_BATCH,
+            RumbleError::BatchTooLarge
+        );
 
-    pub system_program: Program<'info, System>,
-}
+        let destination = ctx.accounts.destination.to_account_info();
+        for entry in commitments.iter() {
+            let info = find_closable_move_commitment(ctx.remaining_accounts, rumble_id, entry)?;
+            close_program_account(info, &destination)?;
+        }
 
-#[derive(Accounts)]
-pub struct ClaimPayout<'info> {
-    #[account(mut)]
-    pub bettor: Signer<'info>,
+        msg!(
+            "Closed {} move commitments for rumble {}",
+            commitments.len(),
+            rumble_id
+        );
+        Ok(())
+    }
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+    /// Permissionless `close_move_commitments_batch` for keepers, once the
+    /// rumble is in Payout or Complete. Rent goes back to each commitment's
+    /// fighter, so `remaining_accounts` must hold every commitment PDA and
+    /// every fighter wallet (all writable).
+    #[cfg(feature = "combat")]
+    pub fn crank_close_move_commitments(
+        ctx: Context<CrankCloseMoveCommitments>,
+        rumble_id: u64,
+        commitments: Vec<MoveCommitmentKey>,
+    ) -> Result<()> {
+        require!(
+            !commitments.is_empty() && commitments.len() <= MAX_MOVE_COMMIT_CLOSE_BATCH,
+            RumbleError::BatchTooLarge
+        );
 
-    /// CHECK: Vault PDA holding SOL for this rumble.
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+        for entry in commitments.iter() {
+            let info = find_closable_move_commitment(ctx.remaining_accounts, rumble_id, entry)?;
+            let fighter = ctx
+                .remaining_accounts
+                .iter()
+                .find(|acc| *acc.key == entry.fighter)
+                .ok_or(RumbleError::InvalidFighterAccount)?;
+            close_program_account(info, fighter)?;
+        }
 
-    #[account(
-        mut,
-        seeds = [BETTOR_SEED, rumble.id.to_le_bytes().as_ref(), bettor.key().as_ref()],
-        bump,
-        owner = crate::ID,
-    )]
-    /// CHECK: Parsed manually to support legacy bettor layouts.
-    pub bettor_account: AccountInfo<'info>,
+        msg!(
+            "Keeper {} closed {} move commitments for rumble {} (rent to fighters)",
+            ctx.accounts.caller.key(),
+            commitments.len(),
+            rumble_id
+        );
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Propose a new admin (two-step transfer).
+    /// Creates/overwrites PendingAdminRE PDA. New admin must call accept_admin.
+    pub fn transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::TransferAdmin::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-#[derive(Accounts)]
-pub struct ClaimSponsorship<'info> {
-    #[account(mut)]
-    pub fighter_owner: Signer<'info>,
+        require!(new_admin != Pubkey::default(), RumbleError::InvalidNewAdmin);
+        require!(
+            new_admin != ctx.accounts.config.admin,
+            RumbleError::InvalidNewAdmin
+        );
 
-    /// CHECK: The fighter account. Authority is verified in the instruction handler
-    /// by reading bytes 8..40 (the authority pubkey after Anchor's 8-byte discriminator).
-    #[account(
-        constraint = fighter.owner == &FIGHTER_REGISTRY_PROGRAM_ID @ RumbleError::InvalidFighterAccount,
-    )]
-    pub fighter: AccountInfo<'info>,
+        let proposed_at = Clock::get()?.slot;
+        let pending = &mut ctx.accounts.pending_admin;
+        pending.proposed_admin = new_admin;
+        pending.proposed_at = proposed_at;
+        pending.bump = ctx.bumps.pending_admin;
 
-    /// CHECK: Sponsorship PDA holding accumulated SOL.
-    #[account(
-        mut,
-        seeds = [SPONSORSHIP_SEED, fighter.key().as_ref()],
-        bump
-    )]
-    pub sponsorship_account: SystemAccount<'info>,
+        msg!(
+            "Admin transfer proposed: {} -> {}",
+            ctx.accounts.config.admin,
+            new_admin
+        );
+        emit!(AdminTransferProposedEvent {
+            current_admin: ctx.accounts.config.admin,
+            proposed_admin: new_admin,
+            proposed_at,
+        });
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Accept a pending admin transfer. Must be signed by the proposed admin
+    /// within `PENDING_ADMIN_EXPIRY_SLOTS` of the proposal.
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::AcceptAdmin::DISCRIMINATOR,
+            ctx.accounts.new_admin.key(),
+        )?;
 
-#[derive(Accounts)]
-pub struct SweepTreasury<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        let config = &mut ctx.accounts.config;
+        let pending = &ctx.accounts.pending_admin;
+        let new_admin = ctx.accounts.new_admin.key();
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        require!(
+            new_admin == pending.proposed_admin,
+            RumbleError::Unauthorized
+        );
+        require_pending_admin_live(pending, Clock::get()?.slot)?;
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        let old_admin = config.admin;
+        config.admin = new_admin;
 
-    /// CHECK: Vault PDA holding remaining SOL for this rumble.
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+        msg!("Admin transferred: {} -> {}", old_admin, new_admin);
+        emit!(AdminTransferredEvent {
+            old_admin,
+            new_admin,
+        });
+        Ok(())
+    }
 
-    /// CHECK: Treasury address, must match config.
-    #[account(
-        mut,
-        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
-    )]
-    pub treasury: AccountInfo<'info>,
+    /// Withdraw a pending admin transfer. Admin-only; closes the PendingAdminRE PDA.
+    pub fn revoke_pending_admin(ctx: Context<RevokePendingAdmin>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::RevokePendingAdmin::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    pub system_program: Program<'info, System>,
-}
+        let proposed_admin = ctx.accounts.pending_admin.proposed_admin;
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-#[instruction(rumble_id: u64, turn: u32)]
-pub struct CloseMoveCommitment<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+        msg!("Admin transfer to {} revoked", proposed_admin);
+        emit!(AdminTransferRevokedEvent {
+            admin: ctx.accounts.admin.key(),
+            proposed_admin,
+        });
+        Ok(())
+    }
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+    /// Propose a new treasury address (timelocked, two-step). Creates/overwrites
+    /// the PendingTreasury PDA; `apply_treasury_update` can land it once
+    /// `TREASURY_UPDATE_DELAY_SLOTS` have passed. Admin-only.
+    pub fn propose_treasury_update(
+        ctx: Context<ProposeTreasuryUpdate>,
+        new_treasury: Pubkey,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::ProposeTreasuryUpdate::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-        constraint = (rumble.state == RumbleState::Combat || rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete) @ RumbleError::InvalidState,
-    )]
-    pub rumble: Account<'info, Rumble>,
-
-    #[account(
-        mut,
-        close = destination,
-        seeds = [
-            MOVE_COMMIT_SEED,
-            rumble_id.to_le_bytes().as_ref(),
-            fighter.key().as_ref(),
-            turn.to_le_bytes().as_ref(),
-        ],
-        bump = move_commitment.bump,
-    )]
-    pub move_commitment: Account<'info, MoveCommitment>,
+        require!(new_treasury != Pubkey::default(), RumbleError::InvalidTreasury);
+        require!(
+            new_treasury != ctx.accounts.config.treasury,
+            RumbleError::InvalidTreasury
+        );
 
-    /// CHECK: Fighter pubkey used for PDA derivation.
-    pub fighter: UncheckedAccount<'info>,
+        let effective_after_slot = Clock::get()?
+            .slot
+            .checked_add(TREASURY_UPDATE_DELAY_SLOTS)
+            .ok_or(RumbleError::MathOverflow)?;
+        let pending = &mut ctx.accounts.pending_treasury;
+        pending.proposed_treasury = new_treasury;
+        pending.effective_after_slot = effective_after_slot;
+        pending.bump = ctx.bumps.pending_treasury;
 
-    /// CHECK: Destination for rent refund.
-    #[account(mut)]
-    pub destination: UncheckedAccount<'info>,
-}
+        msg!(
+            "Treasury update proposed: {} -> {}, effective after slot {}",
+            ctx.accounts.config.treasury,
+            new_treasury,
+            effective_after_slot
+        );
+        emit!(TreasuryUpdateProposedEvent {
+            current_treasury: ctx.accounts.config.treasury,
+            proposed_treasury: new_treasury,
+            effective_after_slot,
+        });
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct TransferAdmin<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+    /// Apply a proposed treasury update once its timelock has passed. Admin-only;
+    /// closes the PendingTreasury PDA.
+    pub fn apply_treasury_update(ctx: Context<ApplyTreasuryUpdate>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::ApplyTreasuryUpdate::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        let old_treasury = apply_pending_treasury(
+            &mut ctx.accounts.config,
+            &ctx.accounts.pending_treasury,
+            Clock::get()?.slot,
+        )?;
+        let new_treasury = ctx.accounts.config.treasury;
 
-    #[account(
-        init_if_needed,
-        payer = admin,
-        space = 8 + PendingAdminRE::INIT_SPACE,
-        seeds = [PENDING_ADMIN_SEED],
-        bump
-    )]
-    pub pending_admin: Account<'info, PendingAdminRE>,
+        msg!("Treasury updated: {} -> {}", old_treasury, new_treasury);
+        emit!(TreasuryUpdateAppliedEvent {
+            old_treasury,
+            new_treasury,
+        });
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Drop a proposed treasury update. Admin-only; closes the PendingTreasury PDA.
+    pub fn cancel_treasury_update(ctx: Context<CancelTreasuryUpdate>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CancelTreasuryUpdate::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-#[derive(Accounts)]
-pub struct AcceptAdmin<'info> {
-    /// The proposed new admin must sign this transaction.
-    #[account(mut)]
-    pub new_admin: Signer<'info>,
+        let proposed_treasury = ctx.accounts.pending_treasury.proposed_treasury;
 
-    #[account(
-        mut,
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        msg!("Treasury update to {} cancelled", proposed_treasury);
+        emit!(TreasuryUpdateCancelledEvent { proposed_treasury });
+        Ok(())
+    }
 
-    #[account(
-        seeds = [PENDING_ADMIN_SEED],
-        bump = pending_admin.bump,
-        constraint = pending_admin.proposed_admin == new_admin.key() @ RumbleError::Unauthorized,
-    )]
-    pub pending_admin: Account<'info, PendingAdminRE>,
-}
+    /// Update the admin and sponsorship fee rates charged by `place_bet`.
+    /// Admin-only. Each rate is capped at `MAX_FEE_BPS`, and the admin fee
+    /// can't drop below the referral fee carved out of it.
+    pub fn update_fees(
+        ctx: Context<UpdateFees>,
+        admin_fee_bps: u16,
+        sponsorship_fee_bps: u16,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateFees::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-#[derive(Accounts)]
-pub struct UpdateTreasury<'info> {
-    pub admin: Signer<'info>,
+        validate_fee_bps(admin_fee_bps)?;
+        validate_fee_bps(sponsorship_fee_bps)?;
 
-    #[account(
-        mut,
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub config: Account<'info, RumbleConfig>,
-}
+        let config = &mut ctx.accounts.config;
+        validate_referral_fee_bps(config.referral_fee_bps, admin_fee_bps)?;
+        config.admin_fee_bps = admin_fee_bps;
+        config.sponsorship_fee_bps = sponsorship_fee_bps;
 
-#[derive(Accounts)]
-pub struct CloseRumble<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        msg!(
+            "Fees updated: admin={} bps, sponsorship={} bps",
+            admin_fee_bps,
+            sponsorship_fee_bps
+        );
+        Ok(())
+    }
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+    /// Update the referrer's share of the admin fee on referred bets.
+    /// Admin-only; at most the current `admin_fee_bps`.
+    pub fn update_referral_fee(ctx: Context<UpdateFees>, referral_fee_bps: u16) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateReferralFee::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        mut,
-        close = admin,
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        let config = &mut ctx.accounts.config;
+        validate_referral_fee_bps(referral_fee_bps, config.admin_fee_bps)?;
+        config.referral_fee_bps = referral_fee_bps;
 
-    /// CHECK: Vault PDA — checked to see if winners have claimed.
-    #[account(
-        mut,
-        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+        msg!("Referral fee updated: {} bps", referral_fee_bps);
+        Ok(())
+    }
 
-    /// CHECK: Treasury address, must match config.
-    #[account(
-        mut,
-        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
-    )]
-    pub treasury: AccountInfo<'info>,
+    /// Set the ICHOR burned from the bettor on every `place_bet` (0 = off).
+    /// Admin-only; at most `MAX_ICHOR_BURN_PER_BET`.
+    pub fn update_ichor_burn_per_bet(
+        ctx: Context<UpdateFees>,
+        ichor_burn_per_bet: u64,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateIchorBurnPerBet::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    pub system_program: Program<'info, System>,
-}
+        require!(
+            ichor_burn_per_bet <= MAX_ICHOR_BURN_PER_BET,
+            RumbleError::IchorBurnTooHigh
+        );
+        ctx.accounts.config.ichor_burn_per_bet = ichor_burn_per_bet;
 
-#[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct CloseCombatState<'info> {
-    #[account(
-        mut,
-        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
-    )]
-    pub admin: Signer<'info>,
+        msg!("ICHOR burn per bet updated: {}", ichor_burn_per_bet);
+        Ok(())
+    }
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+    /// Set the insurance premium `place_bet` charges on insured bets and the
+    /// share of insured net stake `claim_insurance` refunds on a loss. Admin-only;
+    /// the premium is capped at `MAX_FEE_BPS`, the payout at 100%. A zero
+    /// premium turns insurance off; rumbles keep the payout rate they were
+    /// created with.
+    pub fn update_insurance(
+        ctx: Context<UpdateFees>,
+        insurance_premium_bps: u16,
+        insurance_payout_bps: u16,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateInsurance::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = rumble.bump,
-    )]
-    pub rumble: Account<'info, Rumble>,
+        validate_fee_bps(insurance_premium_bps)?;
+        require!(
+            insurance_payout_bps <= 10_000,
+            RumbleError::InvalidInsuranceBps
+        );
+        let config = &mut ctx.accounts.config;
+        config.insurance_premium_bps = insurance_premium_bps;
+        config.insurance_payout_bps = insurance_payout_bps;
 
-    #[account(
-        mut,
-        close = admin,
-        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble.id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
-}
+        msg!(
+            "Insurance updated: premium={} bps, payout={} bps",
+            insurance_premium_bps,
+            insurance_payout_bps
+        );
+        Ok(())
+    }
 
-#[cfg(feature = "combat")]
-#[delegate]
-#[derive(Accounts)]
-pub struct DelegateCombat<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Set the bounty paid to keepers per `resolve_turn`, `resolve_and_advance`,
+    /// `advance_turn` and `finalize_rumble` call (0 = off). Admin-only; at most
+    /// `MAX_KEEPER_FEE_LAMPORTS`. Rumbles created afterwards are funded for it.
+    pub fn update_keeper_fee(ctx: Context<UpdateFees>, keeper_fee_lamports: u64) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateKeeperFee::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        require!(
+            keeper_fee_lamports <= MAX_KEEPER_FEE_LAMPORTS,
+            RumbleError::KeeperFeeTooHigh
+        );
+        ctx.accounts.config.keeper_fee_lamports = keeper_fee_lamports;
 
-    /// CHECK: The combat state PDA to delegate to the Ephemeral Rollup.
-    #[account(mut, del)]
-    pub pda: AccountInfo<'info>,
-}
+        msg!("Keeper fee updated: {} lamports", keeper_fee_lamports);
+        Ok(())
+    }
 
-#[cfg(feature = "combat")]
-#[commit]
-#[derive(Accounts)]
-pub struct CommitCombatSecure<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Global emergency switch. While paused, `create_rumble`, `place_bet`,
+    /// `start_combat`, `commit_move`, `reveal_move` and payout claims are
+    /// rejected for every rumble. Refund, cancel and withdraw paths stay open
+    /// so users can always exit. Admin-only.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::SetPaused::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        ctx.accounts.config.paused = paused;
+        msg!("Rumble engine paused: {}", paused);
 
-    #[account(mut)]
-    pub combat_state: Account<'info, RumbleCombatState>,
-}
+        let admin = ctx.accounts.admin.key();
+        if paused {
+            emit!(ProgramPausedEvent { admin });
+        } else {
+            emit!(ProgramUnpausedEvent { admin });
+        }
+        Ok(())
+    }
 
-#[cfg(feature = "combat")]
-#[commit]
-#[derive(Accounts)]
-pub struct UndelegateCombat<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Update bet size limits enforced by `place_bet`. Admin-only.
+    /// A value of 0 disables the corresponding limit.
+    pub fn update_bet_limits(
+        ctx: Context<UpdateBetLimits>,
+        min_bet_lamports: u64,
+        max_bet_per_bettor_lamports: u64,
+        max_total_pool_lamports: u64,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateBetLimits::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        let config = &mut ctx.accounts.config;
+        config.min_bet_lamports = min_bet_lamports;
+        config.max_bet_per_bettor_lamports = max_bet_per_bettor_lamports;
+        config.max_total_pool_lamports = max_total_pool_lamports;
 
-    #[account(mut)]
-    pub combat_state: Account<'info, RumbleCombatState>,
-}
+        msg!(
+            "Bet limits updated: min={}, per_bettor={}, pool={}",
+            min_bet_lamports,
+            max_bet_per_bettor_lamports,
+            max_total_pool_lamports
+        );
+        Ok(())
+    }
 
-/// Accounts for requesting VRF-based matchup seed.
-/// The `#[vrf]` macro auto-injects: program_identity, vrf_program, slot_hashes, system_program.
-#[cfg(feature = "combat")]
-#[vrf]
-#[derive(Accounts)]
-#[instruction(rumble_id: u64)]
-pub struct RequestMatchupSeed<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    /// Update the commit and reveal window lengths used when turns open.
+    /// Admin-only; each must be within 5..=300 slots.
+    pub fn update_combat_windows(
+        ctx: Context<UpdateCombatWindows>,
+        commit_window_slots: u32,
+        reveal_window_slots: u32,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateCombatWindows::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
 
-    #[account(
-        seeds = [CONFIG_SEED],
-        bump = config.bump,
-    )]
-    pub config: Account<'info, RumbleConfig>,
+        validate_combat_window(commit_window_slots)?;
+        validate_combat_window(reveal_window_slots)?;
 
-    #[account(
-        mut,
-        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
-        bump = combat_state.bump,
-        constraint = combat_state.rumble_id == rumble_id @ RumbleError::InvalidRumble,
-    )]
-    pub combat_state: Account<'info, RumbleCombatState>,
+        let config = &mut ctx.accounts.config;
+        config.commit_window_slots = commit_window_slots;
+        config.reveal_window_slots = reveal_window_slots;
 
-    /// CHECK: The MagicBlock VRF oracle queue
-    #[account(mut, address = DEFAULT_QUEUE)]
-    pub oracle_queue: AccountInfo<'info>,
-}
+        msg!(
+            "Combat windows updated: commit={} slots, reveal={} slots",
+            commit_window_slots,
+            reveal_window_slots
+        );
+        Ok(())
+    }
+
+    /// Set how many slots after combat starts `finalize_rumble` may end a fight
+    /// that is still going. Admin-only; within 5_000..=18_000 slots.
+    pub fn update_combat_timeout(
+        ctx: Context<UpdateCombatWindows>,
+        combat_timeout_slots: u64,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateCombatTimeout::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        validate_combat_timeout(combat_timeout_slots)?;
+        ctx.accounts.config.combat_timeout_slots = combat_timeout_slots;
+
+        msg!("Combat timeout updated: {} slots", combat_timeout_slots);
+        Ok(())
+    }
+
+    /// Set the tip paid to whoever cranks `crank_claim_payout`. Admin-only;
+    /// at most `MAX_CRANK_TIP_BPS`.
+    pub fn update_crank_tip(ctx: Context<UpdateCrankTip>, crank_tip_bps: u16) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateCrankTip::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        require!(
+            crank_tip_bps <= MAX_CRANK_TIP_BPS,
+            RumbleError::CrankTipTooHigh
+        );
+        ctx.accounts.config.crank_tip_bps = crank_tip_bps;
+
+        msg!("Crank tip updated: {} bps", crank_tip_bps);
+        Ok(())
+    }
+
+    /// Set the slot rate `create_rumble` uses to turn unix-timestamp deadlines
+    /// into a `betting_close_slot`, in slots per second × 1000. Admin-only.
+    pub fn update_slot_rate(
+        ctx: Context<UpdateSlotRate>,
+        slots_per_second_milli: u32,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateSlotRate::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        require!(
+            slots_per_second_milli > 0 && slots_per_second_milli <= MAX_SLOTS_PER_SECOND_MILLI,
+            RumbleError::InvalidSlotRate
+        );
+        ctx.accounts.config.slots_per_second_milli = slots_per_second_milli;
+
+        msg!("Slot rate updated: {} slots/s x1000", slots_per_second_milli);
+        Ok(())
+    }
+
+    /// Set the distinct-bettor minimum new rumbles need before combat can
+    /// start (0 = no minimum). Admin-only; existing rumbles keep their snapshot.
+    pub fn update_min_bettors(ctx: Context<UpdateMinBettors>, min_bettors: u16) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::UpdateMinBettors::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        ctx.accounts.config.min_bettors = min_bettors;
+
+        msg!("Minimum bettors updated: {}", min_bettors);
+        Ok(())
+    }
+
+    /// Migrate a legacy RumbleConfig to the current layout.
+    /// Admin-only. Reallocs the account and tops up rent from the admin.
+    /// V1 layouts (no fee fields) get the previous compile-time fee defaults;
+    /// bet limits start zeroed, i.e. unlimited, the config starts unpaused,
+    /// combat windows get the previous compile-time defaults, the crank
+    /// tip starts at 0, the slot rate gets `DEFAULT_SLOTS_PER_SECOND_MILLI`,
+    /// `min_bettors` starts at 0 (no minimum), the referral fee gets
+    /// `REFERRAL_FEE_BPS`, capped at the config's admin fee, and the ICHOR
+    /// burn per bet, keeper fee and insurance rates start at 0 (off), and the
+    /// combat timeout gets `DEFAULT_COMBAT_TIMEOUT_SLOTS`.
+    pub fn migrate_rumble_config_v2(ctx: Context<MigrateRumbleConfigV2>) -> Result<()> {
+        const CONFIG_V1_LEN: usize = 8 + 32 + 32 + 8 + 1; // 81
+        const CONFIG_V2_LEN: usize = CONFIG_V1_LEN + 2 + 2; // 85
+        const CONFIG_V3_LEN: usize = CONFIG_V2_LEN + 8 + 8 + 8 + 1; // 110
+        const CONFIG_V4_LEN: usize = CONFIG_V3_LEN + 4 + 4; // 118
+        const CONFIG_V5_LEN: usize = CONFIG_V4_LEN + 2; // 120
+        const CONFIG_V6_LEN: usize = CONFIG_V5_LEN + 4; // 124
+        const CONFIG_V7_LEN: usize = CONFIG_V6_LEN + 2; // 126
+        const CONFIG_V8_LEN: usize = CONFIG_V7_LEN + 2; // 128
+        const CONFIG_V9_LEN: usize = CONFIG_V8_LEN + 8; // 136
+        const CONFIG_V10_LEN: usize = CONFIG_V9_LEN + 8; // 144
+        const CONFIG_V11_LEN: usize = CONFIG_V10_LEN + 4; // 148
+        const CONFIG_LEN: usize = 8 + RumbleConfig::INIT_SPACE; // 156
+
+        let config_info = ctx.accounts.config.to_account_info();
+        require!(
+            config_info.owner == ctx.program_id,
+            RumbleError::InvalidRumbleConfig
+        );
+
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(data.len() >= CONFIG_V1_LEN, RumbleError::InvalidRumbleConfig);
+            require!(
+                &data[..8] == RumbleConfig::DISCRIMINATOR,
+                RumbleError::InvalidRumbleConfig
+            );
+            let admin_bytes: [u8; 32] = data[8..40]
+                .try_into()
+                .map_err(|_| error!(RumbleError::InvalidRumbleConfig))?;
+            let admin = Pubkey::new_from_array(admin_bytes);
+            require!(
+                admin == ctx.accounts.admin.key(),
+                RumbleError::Unauthorized
+            );
+            // Already migrated; leave any admin-set values untouched.
+            require!(
+                data.len() < CONFIG_LEN,
+                RumbleError::InvalidRumbleConfig
+            );
+        }
+        let needs_fees = config_info.data_len() < CONFIG_V2_LEN;
+        let needs_windows = config_info.data_len() < CONFIG_V4_LEN;
+        let needs_crank_tip = config_info.data_len() < CONFIG_V5_LEN;
+        let needs_slot_rate = config_info.data_len() < CONFIG_V6_LEN;
+        let needs_min_bettors = config_info.data_len() < CONFIG_V7_LEN;
+        let needs_referral_fee = config_info.data_len() < CONFIG_V8_LEN;
+        let needs_ichor_burn = config_info.data_len() < CONFIG_V9_LEN;
+        let needs_keeper_fee = config_info.data_len() < CONFIG_V10_LEN;
+        let needs_insurance = config_info.data_len() < CONFIG_V11_LEN;
+        let needs_combat_timeout = config_info.data_len() < CONFIG_LEN;
+
+        let rent = Rent::get()?;
+        let min_balance = rent.minimum_balance(CONFIG_LEN);
+        let current = config_info.lamports();
+        if min_balance > current {
+            let topup = min_balance
+                .checked_sub(current)
+                .ok_or(RumbleError::MathOverflow)?;
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: config_info.clone(),
+                    },
+                ),
+                topup,
+            )?;
+        }
+        config_info.resize(CONFIG_LEN)?;
+
+        if needs_fees {
+            let mut data = config_info.try_borrow_mut_data()?;
+            let fee_offset = CONFIG_V1_LEN;
+            data[fee_offset..fee_offset + 2].copy_from_slice(&ADMIN_FEE_BPS.to_le_bytes());
+            data[fee_offset + 2..fee_offset + 4]
+                .copy_from_slice(&SPONSORSHIP_FEE_BPS.to_le_bytes());
+        }
+        if needs_windows {
+            let mut data = config_info.try_borrow_mut_data()?;
+            let window_offset = CONFIG_V3_LEN;
+            data[window_offset..window_offset + 4]
+                .copy_from_slice(&COMMIT_WINDOW_SLOTS.to_le_bytes());
+            data[window_offset + 4..window_offset + 8]
+                .copy_from_slice(&REVEAL_WINDOW_SLOTS.to_le_bytes());
+        }
+        if needs_crank_tip {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V4_LEN..CONFIG_V4_LEN + 2].copy_from_slice(&0u16.to_le_bytes());
+        }
+        if needs_slot_rate {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V5_LEN..CONFIG_V5_LEN + 4]
+                .copy_from_slice(&DEFAULT_SLOTS_PER_SECOND_MILLI.to_le_bytes());
+        }
+        if needs_min_bettors {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V6_LEN..CONFIG_V6_LEN + 2].copy_from_slice(&0u16.to_le_bytes());
+        }
+        if needs_referral_fee {
+            let mut data = config_info.try_borrow_mut_data()?;
+            let admin_fee_bps = u16::from_le_bytes([data[CONFIG_V1_LEN], data[CONFIG_V1_LEN + 1]]);
+            data[CONFIG_V7_LEN..CONFIG_V7_LEN + 2]
+                .copy_from_slice(&REFERRAL_FEE_BPS.min(admin_fee_bps).to_le_bytes());
+        }
+        if needs_ichor_burn {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V8_LEN..CONFIG_V8_LEN + 8].copy_from_slice(&0u64.to_le_bytes());
+        }
+        if needs_keeper_fee {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V9_LEN..CONFIG_V9_LEN + 8].copy_from_slice(&0u64.to_le_bytes());
+        }
+        if needs_insurance {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V10_LEN..CONFIG_V10_LEN + 4].copy_from_slice(&[0u8; 4]);
+        }
+        if needs_combat_timeout {
+            let mut data = config_info.try_borrow_mut_data()?;
+            data[CONFIG_V11_LEN..CONFIG_V11_LEN + 8]
+                .copy_from_slice(&DEFAULT_COMBAT_TIMEOUT_SLOTS.to_le_bytes());
+        }
+
+        msg!(
+            "RumbleConfig migrated. account_len={}, fees_seeded={}",
+            config_info.data_len(),
+            needs_fees
+        );
+        Ok(())
+    }
+
+    /// Grow a `Rumble` created under an older, shorter layout to the current
+    /// one. Admin-only; the admin tops up rent and funds the vault's rent
+    /// reserve, which older rumbles never set aside. Every other field past
+    /// the old end starts zeroed, which the program reads as its pre-field
+    /// behaviour (default payout split, `betting_deadline`, 24-hour claim
+    /// window, no minimums, no insurance). Payout rumbles can be migrated too:
+    /// claims recompute from the placements and pools, and the zeroed claim
+    /// tallies only leave the sweep waiting for a forced sweep. Combat rumbles
+    /// cannot, since their combat bookkeeping does not carry over.
+    pub fn migrate_rumble(ctx: Context<MigrateRumble>, rumble_id: u64) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::MigrateRumble::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let rumble_info = ctx.accounts.rumble.to_account_info();
+        let old_len = rumble_info.data_len();
+        {
+            let data = rumble_info.try_borrow_data()?;
+            require_migratable_rumble(&data, rumble_id)?;
+        }
+
+        let rent = Rent::get()?;
+        let min_balance = rent.minimum_balance(RUMBLE_LEN);
+        let current = rumble_info.lamports();
+        if min_balance > current {
+            let topup = min_balance
+                .checked_sub(current)
+                .ok_or(RumbleError::MathOverflow)?;
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: rumble_info.clone(),
+                    },
+                ),
+                topup,
+            )?;
+        }
+        rumble_info.resize(RUMBLE_LEN)?;
+        rumble_info.try_borrow_mut_data()?[old_len..].fill(0);
+
+        // The vault's balance is already owed to bettors, so the reserve is
+        // funded in full on top of it.
+        let reserve = rent.minimum_balance(0);
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            reserve,
+        )?;
+        {
+            let mut data = rumble_info.try_borrow_mut_data()?;
+            set_migrated_vault_rent_reserve(&mut data, reserve)?;
+        }
+
+        msg!(
+            "Rumble {} migrated. account_len={} (was {}), vault_rent_reserve={}",
+            rumble_id,
+            RUMBLE_LEN,
+            old_len,
+            reserve
+        );
+        Ok(())
+    }
+
+    /// Create or update the optional metadata PDA for a rumble. Admin-only.
+    /// Strings are stored UTF-8, zero-padded to their fixed field sizes.
+    pub fn set_rumble_metadata(
+        ctx: Context<SetRumbleMetadata>,
+        name: String,
+        description: String,
+        image_uri: String,
+    ) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::SetRumbleMetadata::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let metadata = &mut ctx.accounts.metadata;
+        metadata.rumble_id = ctx.accounts.rumble.id;
+        metadata.name = pad_metadata_field(&name)?;
+        metadata.description = pad_metadata_field(&description)?;
+        metadata.image_uri = pad_metadata_field(&image_uri)?;
+        metadata.bump = ctx.bumps.metadata;
+
+        msg!("Metadata set for rumble {}: {}", metadata.rumble_id, name);
+        Ok(())
+    }
+
+    /// Close a rumble's metadata PDA once the rumble is Complete and the
+    /// payout claim window has passed. Admin-only; rent goes to the admin.
+    /// Must run before `close_rumble`, which removes the rumble account.
+    pub fn close_rumble_metadata(ctx: Context<CloseRumbleMetadata>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseRumbleMetadata::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let rumble = &ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Complete,
+            RumbleError::InvalidStateTransition
+        );
+        require_claim_window_elapsed(rumble, Clock::get()?.unix_timestamp)?;
+
+        msg!("Metadata for rumble {} closed, rent reclaimed", rumble.id);
+        Ok(())
+    }
+
+    /// Permissionless: refresh a fighter's spot on the top-16 leaderboard from
+    /// its fighter-registry win count. The caller pays rent if the leaderboard
+    /// does not exist yet.
+    pub fn update_leaderboard(ctx: Context<UpdateLeaderboard>, fighter_key: Pubkey) -> Result<()> {
+        let wins = registry_fighter_wins(&ctx.accounts.fighter)?;
+
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.bump = ctx.bumps.leaderboard;
+        let Some(rank) = insert_leaderboard_entry(&mut leaderboard.entries, fighter_key, wins)
+        else {
+            msg!("Fighter {} with {} wins did not place", fighter_key, wins);
+            return Ok(());
+        };
+
+        msg!("Fighter {} ranked #{} with {} wins", fighter_key, rank + 1, wins);
+        emit!(LeaderboardUpdatedEvent {
+            fighter: fighter_key,
+            wins,
+            rank: rank as u8,
+        });
+        Ok(())
+    }
+
+    /// Close the caller's BettorStats PDA, returning its rent and wiping
+    /// their record; the next `place_bet` that passes it starts from zero.
+    pub fn close_bettor_stats(ctx: Context<CloseBettorStats>) -> Result<()> {
+        msg!("Bettor stats closed for {}", ctx.accounts.bettor.key());
+        Ok(())
+    }
+
+    /// Close the leaderboard to reset it for a new season. Admin-only; rent
+    /// goes to the admin and the next `update_leaderboard` starts a fresh one.
+    pub fn close_leaderboard(ctx: Context<CloseLeaderboard>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseLeaderboard::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        msg!("Leaderboard closed, rent reclaimed");
+        Ok(())
+    }
+
+    /// Close a completed Rumble PDA to reclaim rent. Admin-only.
+    /// Requires Complete state. Closable only when there are no possible winner
+    /// claims left on-chain:
+    /// - No bets were placed, OR
+    /// - No one bet on the winner, once the refund window has elapsed
+    /// In both cases any remaining vault balance is drained to treasury first,
+    /// so a prize pool must have gone back to its depositor (`refund_prize_pool`).
+    /// Winner rumbles are only closable after claims have drained the vault
+    /// down to its rent reserve, which then goes to treasury, so bettor claims
+    /// are never invalidated by a premature sweep.
+    pub fn close_rumble(ctx: Context<CloseRumble>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseRumble::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let rumble = &ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Complete,
+            RumbleError::InvalidStateTransition
+        );
+
+        // Unsettled exacta pools and unclaimed winning tickets keep it open.
+        require!(
+            !exacta_claims_outstanding(rumble),
+            RumbleError::OutstandingWinnerClaims
+        );
+
+        let total_bets: u64 = rumble.betting_pools.iter().sum();
+        let vault_balance = ctx.accounts.vault.lamports();
+        if prize_pool_refundable(rumble)? {
+            require!(rumble.prize_pool == 0, RumbleError::PrizePoolNotRefunded);
+        }
+        if total_bets == 0 {
+            transfer_from_vault(
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                rumble.id,
+                ctx.bumps.vault,
+                vault_balance,
+            )?;
+            msg!("Rumble {} closed after draining no-bet vault funds", rumble.id);
+            return Ok(());
+        }
+
+        if !refunds_all_bettors(rumble)? {
+            // Unused stake bonus reserve is the treasury's and leaves with the rest.
+            let drained_floor = rumble
+                .vault_rent_reserve
+                .checked_add(rumble.stake_bonus_reserve)
+                .ok_or(RumbleError::MathOverflow)?;
+            require!(
+                vault_balance <= drained_floor,
+                RumbleError::OutstandingWinnerClaims
+            );
+            if vault_balance > 0 {
+                transfer_from_vault(
+                    ctx.accounts.vault.to_account_info(),
+                    ctx.accounts.treasury.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    rumble.id,
+                    ctx.bumps.vault,
+                    vault_balance,
+                )?;
+            }
+            msg!(
+                "Rumble {} closed after winner claims fully drained the vault",
+                rumble.id
+            );
+            return Ok(());
+        }
+
+        // No-winner and underfunded rumbles refund every stake; keep them open
+        // until the refund window has closed.
+        require_refund_window_elapsed(rumble.completed_at)?;
+
+        transfer_from_vault(
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            rumble.id,
+            ctx.bumps.vault,
+            vault_balance,
+        )?;
+
+        msg!("Rumble {} closed after draining no-winner vault funds", rumble.id);
+        Ok(())
+    }
+
+    /// Move a combat state created before `RumbleCombatState` went zero-copy
+    /// onto the current layout. Permissionless; the payer covers the extra rent.
+    #[cfg(feature = "combat")]
+    pub fn migrate_combat_state(ctx: Context<MigrateCombatState>, rumble_id: u64) -> Result<()> {
+        let info = ctx.accounts.combat_state.to_account_info();
+        let combat = {
+            let data = info.try_borrow_data()?;
+            migrated_combat_state(&data, rumble_id)?
+        };
+
+        let min_balance = Rent::get()?.minimum_balance(COMBAT_STATE_LEN);
+        let topup = min_balance.saturating_sub(info.lamports());
+        if topup > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                topup,
+            )?;
+        }
+        info.resize(COMBAT_STATE_LEN)?;
+        {
+            let mut data = info.try_borrow_mut_data()?;
+            data[8..COMBAT_STATE_LEN].copy_from_slice(bytemuck::bytes_of(&combat));
+        }
+
+        msg!("Combat state for rumble {} migrated", rumble_id);
+        Ok(())
+    }
+
+    /// Close a RumbleCombatState PDA to reclaim rent. Admin-only.
+    /// Requires the associated rumble is Complete or Cancelled.
+    /// Also closes the rumble's CombatLog when it is passed.
+    #[cfg(feature = "combat")]
+    pub fn close_combat_state(ctx: Context<CloseCombatState>) -> Result<()> {
+        record_admin_action(
+            &ctx.accounts.admin_log,
+            crate::instruction::CloseCombatState::DISCRIMINATOR,
+            ctx.accounts.admin.key(),
+        )?;
+
+        let rumble = &ctx.accounts.rumble;
+        require!(
+            rumble.state == RumbleState::Complete || rumble.state == RumbleState::Cancelled,
+            RumbleError::InvalidStateTransition
+        );
+
+        msg!(
+            "Combat state for rumble {} closed, rent reclaimed",
+            rumble.id
+        );
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Ephemeral Rollup delegation (MagicBlock ER)
+    // -----------------------------------------------------------------------
+
+    /// Delegate a combat state PDA to a MagicBlock Ephemeral Rollup.
+    /// Admin-only. Called after matchmaking, before combat starts on ER.
+    #[cfg(feature = "combat")]
+    pub fn delegate_combat(ctx: Context<DelegateCombat>, rumble_id: u64) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.config.admin,
+            RumbleError::Unauthorized
+        );
+
+        ctx.accounts.delegate_pda(
+            &ctx.accounts.authority,
+            &[COMBAT_STATE_SEED, &rumble_id.to_le_bytes()],
+            DelegateConfig {
+                commit_frequency_ms: 3_000,
+                validator: ctx.remaining_accounts.first().map(|acc| acc.key()),
+                ..Default::default()
+            },
+        )?;
+
+        msg!(
+            "Combat state delegated to Ephemeral Rollup for rumble {}",
+            rumble_id
+        );
+        Ok(())
+    }
+
+    /// Commit combat state from ER back to Solana L1 (periodic sync for spectators).
+    /// Admin-only to prevent unauthorized commits.
+    #[cfg(feature = "combat")]
+    pub fn commit_combat(ctx: Context<CommitCombatSecure>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.config.admin,
+            RumbleError::Unauthorized
+        );
+        // Flush in-memory account mutations before commit CPI so L1 gets
+        // the latest combat state during periodic ER syncs.
+        ctx.accounts.combat_state.exit(&crate::ID)?;
+        commit_accounts(
+            &ctx.accounts.authority,
+            vec![&ctx.accounts.combat_state.to_account_info()],
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+        )?;
+        msg!("Combat state committed to L1");
+        Ok(())
+    }
+
+    /// Commit final combat state and undelegate back to Solana L1.
+    /// Admin-only to prevent adversaries from yanking accounts mid-combat.
+    #[cfg(feature = "combat")]
+    pub fn undelegate_combat(ctx: Context<UndelegateCombat>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.config.admin,
+            RumbleError::Unauthorized
+        );
+        ctx.accounts.combat_state.exit(&crate::ID)?;
+
+        commit_and_undelegate_accounts(
+            &ctx.accounts.authority,
+            vec![&ctx.accounts.combat_state.to_account_info()],
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+        )?;
+        msg!("Combat state undelegated back to L1");
+        Ok(())
+    }
+
+    /// Request provably-fair matchup seed via MagicBlock VRF.
+    ///
+    /// Admin calls this after combat starts to get a VRF-derived seed
+    /// for fair fighter pairing. The VRF oracle will automatically call
+    /// `callback_matchup_seed` with the randomness result.
+    #[cfg(feature = "combat")]
+    pub fn request_matchup_seed(
+        ctx: Context<RequestMatchupSeed>,
+        rumble_id: u64,
+        client_seed: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(
+            ctx.accounts.payer.key() == config.admin,
+            RumbleError::Unauthorized
+        );
+
+        let combat_state = load_combat(&ctx.accounts.combat_state)?;
+        let combat = &*combat_state;
+        require!(combat.rumble_id == rumble_id, RumbleError::InvalidRumble);
+        require!(combat.vrf_seed == [0u8; 32], RumbleError::VrfSeedAlreadySet);
+
+        // Capture keys before CPI
+        let payer_key = ctx.accounts.payer.key();
+        let oracle_queue_key = ctx.accounts.oracle_queue.key();
+        let combat_state_key = ctx.accounts.combat_state.key();
+
+        let ix = create_request_randomness_ix(
+            ephemeral_vrf_sdk::instructions::RequestRandomnessParams {
+                payer: payer_key,
+                oracle_queue: oracle_queue_key,
+                callback_program_id: crate::ID,
+                callback_discriminator: instruction::CallbackMatchupSeed::DISCRIMINATOR.to_vec(),
+                caller_seed: [client_seed; 32],
+                accounts_metas: Some(vec![SerializableAccountMeta {
+                    pubkey: combat_state_key,
+                    is_signer: false,
+                    is_writable: true,
+                }]),
+                ..Default::default()
+            },
+        );
+        ctx.accounts
+            .invoke_signed_vrf(&ctx.accounts.payer.to_account_info(), &ix)?;
+
+        msg!("VRF matchup seed requested for rumble {}", rumble_id);
+        Ok(())
+    }
+
+    /// Callback from MagicBlock VRF oracle with matchup randomness.
+    ///
+    /// Only the VRF oracle (VRF_PROGRAM_IDENTITY signer) can call this.
+    /// Stores the randomness in RumbleCombatState.vrf_seed for fair pairing.
+    #[cfg(feature = "combat")]
+    pub fn callback_matchup_seed(
+        ctx: Context<CallbackMatchupSeed>,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let mut combat_state = load_combat_mut(&ctx.accounts.combat_state)?;
+        let combat = &mut *combat_state;
+        require!(combat.vrf_seed == [0u8; 32], RumbleError::VrfSeedAlreadySet);
+
+        combat.vrf_seed = randomness;
+
+        msg!("VRF matchup seed stored for rumble {}", combat.rumble_id);
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Accounts
+// ---------------------------------------------------------------------------
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RumbleConfig::INIT_SPACE,
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    /// CHECK: Treasury wallet address, validated by admin at init time.
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GlobalStats::INIT_SPACE,
+        seeds = [GLOBAL_STATS_SEED],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalStats<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GlobalStats::INIT_SPACE,
+        seeds = [GLOBAL_STATS_SEED],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAdminLog<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<AdminLog>(),
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    rumble_id: u64,
+    fighters: Vec<Pubkey>,
+    betting_deadline: i64,
+    payout_split: Option<PayoutSplit>,
+    max_bet_per_bettor: u64,
+    min_pool_lamports: u64,
+    deadline_kind: Option<DeadlineKind>,
+    record_bets: bool,
+    require_registered: bool,
+    whitelist_enabled: bool,
+    crits_enabled: bool,
+    token_gate: Option<u64>,
+    claim_window_seconds: Option<i64>,
+    fighter_slots: Option<u8>,
+)]
+pub struct CreateRumble<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = rumble_account_len(fighter_slots),
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// Passed only for invite-only rumbles (`whitelist_enabled`).
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + WhitelistAccount::INIT_SPACE,
+        seeds = [WHITELIST_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub whitelist: Option<Box<Account<'info, WhitelistAccount>>>,
+
+    /// Combat rules for this rumble; omitted = the built-in constants.
+    pub ruleset: Option<Box<Account<'info, CombatRuleset>>>,
+
+    /// ICHOR balance checked against a non-zero `token_gate`.
+    #[account(
+        constraint = token_account.mint == EXPECTED_ICHOR_MINT @ RumbleError::InvalidTokenGateAccount,
+    )]
+    pub token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: Vault PDA; receives the keeper budget when `keeper_fee_lamports` is set.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Optional: lifetime totals for dashboards.
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATS_SEED],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Option<Box<Account<'info, GlobalStats>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct AppendRumbleFighters<'info> {
+    #[account(
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// Optional: lifetime totals for dashboards.
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATS_SEED],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Option<Box<Account<'info, GlobalStats>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct VerifyRumbleGate<'info> {
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        constraint = token_account.mint == EXPECTED_ICHOR_MINT @ RumbleError::InvalidTokenGateAccount,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct AuthorizeFighterDelegate<'info> {
+    #[account(mut)]
+    pub fighter: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + FighterDelegate::INIT_SPACE,
+        seeds = [FIGHTER_DELEGATE_SEED, fighter.key().as_ref()],
+        bump
+    )]
+    pub fighter_delegate: Account<'info, FighterDelegate>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct MigrateFighterDelegate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Fighter wallet the delegate PDA is seeded by.
+    pub fighter: UncheckedAccount<'info>,
+
+    /// CHECK: Legacy FighterDelegate PDA; seeds + owner checked here,
+    /// discriminator and length in the handler.
+    #[account(
+        mut,
+        seeds = [FIGHTER_DELEGATE_SEED, fighter.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub fighter_delegate: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct MigrateCombatState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Legacy RumbleCombatState PDA; seeds + owner checked here,
+    /// discriminator and length in the handler.
+    #[account(
+        mut,
+        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub combat_state: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct RevokeFighterDelegate<'info> {
+    #[account(mut)]
+    pub fighter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [FIGHTER_DELEGATE_SEED, fighter.key().as_ref()],
+        bump = fighter_delegate.bump,
+        constraint = fighter_delegate.fighter == fighter.key() @ RumbleError::Unauthorized,
+    )]
+    pub fighter_delegate: Account<'info, FighterDelegate>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, turn: u32)]
+pub struct CommitMove<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Fighter wallet identity. Must match either the authority signer
+    /// or an active persistent fighter delegate PDA.
+    pub fighter: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble_id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MoveCommitment::INIT_SPACE,
+        seeds = [
+            MOVE_COMMIT_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            fighter.key().as_ref(),
+        ],
+        bump
+    )]
+    pub move_commitment: Account<'info, MoveCommitment>,
+
+    /// CHECK: Optional persistent fighter delegate PDA, validated manually when authority != fighter.
+    pub fighter_delegate: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct Forfeit<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Fighter wallet identity. Must match either the authority signer
+    /// or an active persistent fighter delegate PDA.
+    pub fighter: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble_id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    /// CHECK: Optional persistent fighter delegate PDA, validated manually when authority != fighter.
+    pub fighter_delegate: UncheckedAccount<'info>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, turn: u32)]
+pub struct RevealMove<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Fighter wallet identity. Must match either the authority signer
+    /// or an active persistent fighter delegate PDA.
+    pub fighter: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble_id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    #[account(
+        mut,
+        seeds = [
+            MOVE_COMMIT_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            fighter.key().as_ref(),
+        ],
+        bump = move_commitment.bump,
+        constraint = move_commitment.fighter == fighter.key() @ RumbleError::Unauthorized,
+        constraint = move_commitment.rumble_id == rumble_id @ RumbleError::InvalidRumble,
+        constraint = move_commitment.turn == turn @ RumbleError::InvalidTurn,
+    )]
+    pub move_commitment: Account<'info, MoveCommitment>,
+
+    /// CHECK: Optional persistent fighter delegate PDA, validated manually when authority != fighter.
+    pub fighter_delegate: UncheckedAccount<'info>,
+}
+
+/// Permissionless: anyone can start combat once betting has closed.
+/// The keeper pays for the combat state account.
+/// Remaining accounts: each fighter's registry `Fighter` account for its
+/// wins/level HP bonus and win-streak meter (omitted fighters get base stats).
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct StartCombat<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = COMBAT_STATE_LEN,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    /// The rumble's `CombatRuleset`; required when `rumble.ruleset` is set.
+    #[account(address = rumble.ruleset @ RumbleError::RulesetMismatch)]
+    pub ruleset: Option<Box<Account<'info, CombatRuleset>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct ForceStartCombat<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = COMBAT_STATE_LEN,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    /// The rumble's `CombatRuleset`; required when `rumble.ruleset` is set.
+    #[account(address = rumble.ruleset @ RumbleError::RulesetMismatch)]
+    pub ruleset: Option<Box<Account<'info, CombatRuleset>>>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+/// Admin-only: creates the combat state early if needed to hold HP overrides.
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct SetFighterHpOverrides<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = COMBAT_STATE_LEN,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(ruleset_id: u32)]
+pub struct CreateCombatRuleset<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + CombatRuleset::INIT_SPACE,
+        seeds = [COMBAT_RULESET_SEED, ruleset_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub ruleset: Account<'info, CombatRuleset>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct UpdateCombatRuleset<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [COMBAT_RULESET_SEED, ruleset.ruleset_id.to_le_bytes().as_ref()],
+        bump = ruleset.bump,
+    )]
+    pub ruleset: Account<'info, CombatRuleset>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+/// Permissionless combat action — open_turn, resolve_turn, advance_turn.
+/// Anyone can call these; correctness is enforced by on-chain state machine.
+/// Remaining accounts: MoveCommitment PDAs plus each fighter's registry
+/// `Fighter` account for attributes (omitted fighters use zero attributes).
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct CombatAction<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble.id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    /// Duel history; created by the first call that needs it (normally `open_turn`).
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + CombatLog::INIT_SPACE,
+        seeds = [COMBAT_LOG_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub combat_log: Box<Account<'info, CombatLog>>,
+
+    /// The rumble's `CombatRuleset`; required when `rumble.ruleset` is set.
+    #[account(address = rumble.ruleset @ RumbleError::RulesetMismatch)]
+    pub ruleset: Option<Box<Account<'info, CombatRuleset>>>,
+
+    /// CHECK: Vault PDA paying the keeper bounty. Optional: calls run on the
+    /// Ephemeral Rollup, where the vault isn't delegated, go unpaid.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: Option<SystemAccount<'info>>,
+
+    /// CHECK: SlotHashes sysvar, read when a turn opens to seed its crits.
+    /// Required by `open_turn`/`advance_turn`/`resolve_and_advance` when
+    /// `rumble.crits_enabled`.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Admin-gated combat action — post_turn_result (hybrid mode).
+/// Admin posts move results; damage is validated on-chain.
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct AdminCombatAction<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = keeper.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble.id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = 8 + CombatLog::INIT_SPACE,
+        seeds = [COMBAT_LOG_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub combat_log: Box<Account<'info, CombatLog>>,
+
+    /// The rumble's `CombatRuleset`; required when `rumble.ruleset` is set.
+    #[account(address = rumble.ruleset @ RumbleError::RulesetMismatch)]
+    pub ruleset: Option<Box<Account<'info, CombatRuleset>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless finalization — anyone can finalize when state machine allows it.
+/// Correctness is enforced by on-chain combat state (winner, placements, timeouts).
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct FinalizeRumble<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble.id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    /// Optional: sealed against further appends when passed.
+    #[account(
+        mut,
+        seeds = [COMBAT_LOG_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = combat_log.bump,
+    )]
+    pub combat_log: Option<Box<Account<'info, CombatLog>>>,
+
+    /// CHECK: Vault PDA holding payout SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, fighter_index: u8, amount: u64)]
+pub struct PlaceBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// Vault PDA that holds all bet SOL for this rumble.
+    /// CHECK: PDA derived from vault seed + rumble_id. Just holds lamports.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    /// Sponsorship account PDA for the fighter being bet on.
+    /// CHECK: PDA derived from sponsorship seed + fighter pubkey. Holds lamports.
+    #[account(
+        mut,
+        seeds = [SPONSORSHIP_SEED, rumble.fighters[fighter_index as usize].as_ref()],
+        bump
+    )]
+    pub sponsorship_account: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + SponsorshipLedger::INIT_SPACE,
+        seeds = [SPONSORSHIP_LEDGER_SEED, rumble.fighters[fighter_index as usize].as_ref()],
+        bump
+    )]
+    pub sponsorship_ledger: Account<'info, SponsorshipLedger>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = bettor_account_len(rumble.slots()),
+        seeds = [BETTOR_SEED, rumble_id.to_le_bytes().as_ref(), bettor.key().as_ref()],
+        bump
+    )]
+    pub bettor_account: Account<'info, BettorAccount>,
+
+    /// Required exactly when the rumble has `record_bets` set.
+    #[account(
+        init,
+        payer = bettor,
+        space = 8 + BetRecord::INIT_SPACE,
+        seeds = [
+            BET_RECORD_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            bettor.key().as_ref(),
+            bettor_account.bet_sequence.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub bet_record: Option<Account<'info, BetRecord>>,
+
+    /// CHECK: Optional wallet that referred the bettor; must differ from the bettor.
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Referral PDA for `referrer`, required exactly when `referrer` is passed.
+    /// CHECK: Address checked against [REFERRAL_SEED, referrer] in the handler.
+    #[account(mut)]
+    pub referral_account: Option<UncheckedAccount<'info>>,
+
+    /// Required when the rumble is invite-only.
+    #[account(
+        seeds = [WHITELIST_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = whitelist.bump,
+    )]
+    pub whitelist: Option<Box<Account<'info, WhitelistAccount>>>,
+
+    // Optional: required while `config.ichor_burn_per_bet` is non-zero
+    #[account(
+        mut,
+        token::authority = bettor,
+    )]
+    pub ichor_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(mut, address = EXPECTED_ICHOR_MINT)]
+    pub ichor_mint: Option<Box<Account<'info, Mint>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Optional: the wallet's BettorIndex, opened on first use.
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + BettorIndex::INIT_SPACE,
+        seeds = [BETTOR_INDEX_SEED, bettor.key().as_ref()],
+        bump
+    )]
+    pub bettor_index: Option<Box<Account<'info, BettorIndex>>>,
+
+    /// Optional: lifetime totals for dashboards.
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATS_SEED],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Option<Box<Account<'info, GlobalStats>>>,
+
+    /// Optional: the wallet's BettorStats, opened on first use.
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + BettorStats::INIT_SPACE,
+        seeds = [BETTOR_STATS_SEED, bettor.key().as_ref()],
+        bump
+    )]
+    pub bettor_stats: Option<Box<Account<'info, BettorStats>>>,
+
+    /// Optional: the bettor's FeeExemption; waives the admin fee until it expires.
+    #[account(
+        seeds = [FEE_EXEMPT_SEED, bettor.key().as_ref()],
+        bump = fee_exemption.bump,
+    )]
+    pub fee_exemption: Option<Box<Account<'info, FeeExemption>>>,
+
+    /// The bettor's per-slot bet counter for this rumble, opened on first bet.
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + BettorRateLimit::INIT_SPACE,
+        seeds = [RATE_LIMIT_SEED, bettor.key().as_ref(), rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub rate_limit: Box<Account<'info, BettorRateLimit>>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, first_index: u8, second_index: u8)]
+pub struct PlaceExactaBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA that holds all bet SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + ExactaTicket::INIT_SPACE,
+        seeds = [
+            EXACTA_TICKET_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            bettor.key().as_ref(),
+            &[first_index],
+            &[second_index],
+        ],
+        bump
+    )]
+    pub exacta_ticket: Account<'info, ExactaTicket>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + ExactaCombo::INIT_SPACE,
+        seeds = [
+            EXACTA_COMBO_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            &[first_index],
+            &[second_index],
+        ],
+        bump
+    )]
+    pub exacta_combo: Account<'info, ExactaCombo>,
+
+    /// Required when the rumble is invite-only.
+    #[account(
+        seeds = [WHITELIST_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = whitelist.bump,
+    )]
+    pub whitelist: Option<Box<Account<'info, WhitelistAccount>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct CancelBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding bet SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [BETTOR_SEED, rumble_id.to_le_bytes().as_ref(), bettor.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Parsed manually to support legacy bettor layouts.
+    pub bettor_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// Optional: lifetime totals, bumped by `complete_rumble`.
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATS_SEED],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Option<Box<Account<'info, GlobalStats>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct FileDispute<'info> {
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        seeds = [BETTOR_SEED, rumble.id.to_le_bytes().as_ref(), disputer.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Parsed manually to support legacy bettor layouts.
+    pub bettor_account: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = disputer,
+        space = 8 + DisputeRecord::INIT_SPACE,
+        seeds = [DISPUTE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute_record: Account<'info, DisputeRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = dispute_record.bump,
+    )]
+    pub dispute_record: Account<'info, DisputeRecord>,
+
+    /// CHECK: Vault PDA holding payout SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeComplete<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// Optional: lifetime totals, bumped on completion.
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATS_SEED],
+        bump = global_stats.bump,
+    )]
+    pub global_stats: Option<Box<Account<'info, GlobalStats>>>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyVaultIntegrity<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: PDA derived from vault seed + rumble_id. Just holds lamports.
+    #[account(
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+}
+
+/// Admin, or any keeper once the grace period has passed.
+#[derive(Accounts)]
+pub struct CancelUnderfundedRumble<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct DepositPrizePool<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding this rumble's SOL.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundPrizePool<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Refund recipient; must be the rumble's recorded prize depositor.
+    #[account(
+        mut,
+        constraint = depositor.key() == rumble.prize_depositor @ RumbleError::PrizeDepositorMismatch,
+    )]
+    pub depositor: UncheckedAccount<'info>,
+
+    /// CHECK: Vault PDA holding this rumble's SOL.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelUnderfilledRumble<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+}
+
+#[derive(Accounts)]
+pub struct AdminSetResultAction<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding payout SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Combat state PDA for this rumble. Usually uninitialized; only read
+    /// to tell whether on-chain combat has started.
+    #[account(
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub combat_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPayout<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [BETTOR_SEED, rumble.id.to_le_bytes().as_ref(), bettor.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Parsed manually to support legacy bettor layouts.
+    pub bettor_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Optional ichor-token `IchorStake` PDA of `bettor`; owner, seeds
+    /// and discriminator are verified in `load_stake_bonus_bps`.
+    pub ichor_stake: Option<UncheckedAccount<'info>>,
+
+    /// Optional: the bettor's BettorIndex, to mark this rumble claimed.
+    #[account(
+        mut,
+        seeds = [BETTOR_INDEX_SEED, bettor.key().as_ref()],
+        bump = bettor_index.bump,
+    )]
+    pub bettor_index: Option<Box<Account<'info, BettorIndex>>>,
+
+    /// Optional: the bettor's BettorStats, credited with the claim.
+    #[account(
+        mut,
+        seeds = [BETTOR_STATS_SEED, bettor.key().as_ref()],
+        bump = bettor_stats.bump,
+    )]
+    pub bettor_stats: Option<Box<Account<'info, BettorStats>>>,
+
+    /// Optional: the bettor's BettorRateLimit for this rumble, closed back to
+    /// the bettor now that betting is over.
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [RATE_LIMIT_SEED, bettor.key().as_ref(), rumble.id.to_le_bytes().as_ref()],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Option<Box<Account<'info, BettorRateLimit>>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [BETTOR_SEED, rumble.id.to_le_bytes().as_ref(), bettor.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Parsed manually to support legacy bettor layouts.
+    pub bettor_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Optional: the bettor's BettorRateLimit for this rumble, closed back to
+    /// the bettor now that betting is over.
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [RATE_LIMIT_SEED, bettor.key().as_ref(), rumble.id.to_le_bytes().as_ref()],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Option<Box<Account<'info, BettorRateLimit>>>,
+}
+
+#[derive(Accounts)]
+pub struct CrankClaimPayout<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    /// CHECK: Payout recipient; must be the bettor account's authority, which
+    /// the bettor PDA seeds and the handler both enforce.
+    #[account(mut)]
+    pub bettor: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [BETTOR_SEED, rumble.id.to_le_bytes().as_ref(), bettor.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Parsed manually to support legacy bettor layouts.
+    pub bettor_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Optional ichor-token `IchorStake` PDA of `bettor`; owner, seeds
+    /// and discriminator are verified in `load_stake_bonus_bps`.
+    pub ichor_stake: Option<UncheckedAccount<'info>>,
+
+    /// Optional: the bettor's BettorIndex, to mark this rumble claimed.
+    #[account(
+        mut,
+        seeds = [BETTOR_INDEX_SEED, bettor.key().as_ref()],
+        bump = bettor_index.bump,
+    )]
+    pub bettor_index: Option<Box<Account<'info, BettorIndex>>>,
+
+    /// Optional: the bettor's BettorStats, credited with the claim.
+    #[account(
+        mut,
+        seeds = [BETTOR_STATS_SEED, bettor.key().as_ref()],
+        bump = bettor_stats.bump,
+    )]
+    pub bettor_stats: Option<Box<Account<'info, BettorStats>>>,
+
+    /// Optional: the bettor's BettorRateLimit for this rumble, closed back to
+    /// the bettor now that betting is over.
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [RATE_LIMIT_SEED, bettor.key().as_ref(), rumble.id.to_le_bytes().as_ref()],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Option<Box<Account<'info, BettorRateLimit>>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleExactaPool<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: `ExactaCombo` PDA for the rumble's 1-2 finish, possibly never
+    /// created; address checked in `read_exacta_combo_stake`.
+    pub exacta_combo: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimExactaPayout<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [
+            EXACTA_TICKET_SEED,
+            rumble.id.to_le_bytes().as_ref(),
+            bettor.key().as_ref(),
+            &[exacta_ticket.first_index],
+            &[exacta_ticket.second_index],
+        ],
+        bump = exacta_ticket.bump,
+        constraint = exacta_ticket.bettor == bettor.key() @ RumbleError::Unauthorized,
+    )]
+    pub exacta_ticket: Account<'info, ExactaTicket>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct CloseBettorAccount<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BETTOR_SEED, rumble_id.to_le_bytes().as_ref(), bettor.key().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    /// CHECK: Parsed manually to support legacy bettor layouts.
+    pub bettor_account: AccountInfo<'info>,
+
+    /// Required only when the bettor has not claimed.
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Option<Box<Account<'info, Rumble>>>,
+
+    /// Optional: the bettor's BettorStats, charged a loss when closing a
+    /// losing bettor account.
+    #[account(
+        mut,
+        seeds = [BETTOR_STATS_SEED, bettor.key().as_ref()],
+        bump = bettor_stats.bump,
+    )]
+    pub bettor_stats: Option<Box<Account<'info, BettorStats>>>,
+
+    /// Optional: the bettor's BettorRateLimit for this rumble, closed alongside.
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [RATE_LIMIT_SEED, bettor.key().as_ref(), rumble_id.to_le_bytes().as_ref()],
+        bump = rate_limit.bump,
+    )]
+    pub rate_limit: Option<Box<Account<'info, BettorRateLimit>>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBettorStats<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [BETTOR_STATS_SEED, bettor.key().as_ref()],
+        bump = bettor_stats.bump,
+    )]
+    pub bettor_stats: Account<'info, BettorStats>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, sequence: u32)]
+pub struct CloseBetRecord<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [
+            BET_RECORD_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            bettor.key().as_ref(),
+            sequence.to_le_bytes().as_ref(),
+        ],
+        bump = bet_record.bump,
+        has_one = bettor @ RumbleError::Unauthorized,
+    )]
+    pub bet_record: Account<'info, BetRecord>,
+
+    /// CHECK: Rumble PDA; may already be closed, otherwise its state is checked.
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub rumble: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSponsorship<'info> {
+    #[account(mut)]
+    pub fighter_owner: Signer<'info>,
+
+    /// CHECK: The fighter account. Authority is verified in the instruction handler
+    /// by reading bytes 8..40 (the authority pubkey after Anchor's 8-byte discriminator).
+    #[account(
+        constraint = fighter.owner == &FIGHTER_REGISTRY_PROGRAM_ID @ RumbleError::InvalidFighterAccount,
+    )]
+    pub fighter: AccountInfo<'info>,
+
+    /// CHECK: Sponsorship PDA holding accumulated SOL.
+    #[account(
+        mut,
+        seeds = [SPONSORSHIP_SEED, fighter.key().as_ref()],
+        bump
+    )]
+    pub sponsorship_account: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = fighter_owner,
+        space = 8 + SponsorshipLedger::INIT_SPACE,
+        seeds = [SPONSORSHIP_LEDGER_SEED, fighter.key().as_ref()],
+        bump
+    )]
+    pub sponsorship_ledger: Account<'info, SponsorshipLedger>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    /// CHECK: Referral PDA holding accumulated SOL.
+    #[account(
+        mut,
+        seeds = [REFERRAL_SEED, referrer.key().as_ref()],
+        bump
+    )]
+    pub referral_account: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepTreasury<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA holding remaining SOL for this rumble.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct CloseMoveCommitment<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+        constraint = (rumble.state == RumbleState::Combat || rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete) @ RumbleError::InvalidState,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        close = destination,
+        seeds = [
+            MOVE_COMMIT_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            fighter.key().as_ref(),
+        ],
+        bump = move_commitment.bump,
+    )]
+    pub move_commitment: Account<'info, MoveCommitment>,
+
+    /// CHECK: Fighter pubkey used for PDA derivation.
+    pub fighter: UncheckedAccount<'info>,
+
+    /// CHECK: Destination for rent refund.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64, turn: u32)]
+pub struct CloseLegacyMoveCommitment<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+        constraint = (rumble.state == RumbleState::Combat || rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete) @ RumbleError::InvalidState,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        close = destination,
+        seeds = [
+            MOVE_COMMIT_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            fighter.key().as_ref(),
+            turn.to_le_bytes().as_ref(),
+        ],
+        bump = move_commitment.bump,
+    )]
+    pub move_commitment: Account<'info, MoveCommitment>,
+
+    /// CHECK: Fighter pubkey used for PDA derivation.
+    pub fighter: UncheckedAccount<'info>,
+
+    /// CHECK: Destination for rent refund.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct CloseMoveCommitmentsBatch<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+        constraint = (rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete) @ RumbleError::InvalidState,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Destination for rent refund.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct CrankCloseMoveCommitments<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+        constraint = (rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete) @ RumbleError::InvalidState,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + PendingAdminRE::INIT_SPACE,
+        seeds = [PENDING_ADMIN_SEED],
+        bump
+    )]
+    pub pending_admin: Account<'info, PendingAdminRE>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed new admin must sign this transaction.
+    #[account(mut)]
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [PENDING_ADMIN_SEED],
+        bump = pending_admin.bump,
+        constraint = pending_admin.proposed_admin == new_admin.key() @ RumbleError::Unauthorized,
+    )]
+    pub pending_admin: Account<'info, PendingAdminRE>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct RevokePendingAdmin<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [PENDING_ADMIN_SEED],
+        bump = pending_admin.bump,
+    )]
+    pub pending_admin: Account<'info, PendingAdminRE>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeTreasuryUpdate<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + PendingTreasury::INIT_SPACE,
+        seeds = [PENDING_TREASURY_SEED],
+        bump
+    )]
+    pub pending_treasury: Account<'info, PendingTreasury>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyTreasuryUpdate<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [PENDING_TREASURY_SEED],
+        bump = pending_treasury.bump,
+    )]
+    pub pending_treasury: Account<'info, PendingTreasury>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTreasuryUpdate<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [PENDING_TREASURY_SEED],
+        bump = pending_treasury.bump,
+    )]
+    pub pending_treasury: Account<'info, PendingTreasury>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCombatWindows<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCrankTip<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSlotRate<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+/// Admin is checked in `grant_fee_exemption` via `validate_fee_exemption_grant`.
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct GrantFeeExemption<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + FeeExemption::INIT_SPACE,
+        seeds = [FEE_EXEMPT_SEED, wallet.as_ref()],
+        bump
+    )]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RevokeFeeExemption<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [FEE_EXEMPT_SEED, wallet.as_ref()],
+        bump = fee_exemption.bump,
+    )]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct UpdateWhitelist<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [WHITELIST_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = whitelist.bump,
+    )]
+    pub whitelist: Box<Account<'info, WhitelistAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMinBettors<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBetLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRumbleConfigV2<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Legacy RumbleConfig PDA (possibly old layout). Seeds + owner are verified
+    /// in constraints/handler before migration write.
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        owner = crate::ID,
+    )]
+    pub config: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct MigrateRumble<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    /// CHECK: Rumble PDA in an older layout, which no longer deserializes.
+    /// Seeds + owner are verified here; discriminator, id and state in the handler.
+    #[account(
+        mut,
+        seeds = [RUMBLE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub rumble: AccountInfo<'info>,
+
+    /// CHECK: Vault PDA; receives the rent reserve the old layout never held.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct SetRumbleMetadata<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + RumbleMetadata::INIT_SPACE,
+        seeds = [RUMBLE_META_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, RumbleMetadata>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRumbleMetadata<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [RUMBLE_META_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = metadata.bump,
+    )]
+    pub metadata: Account<'info, RumbleMetadata>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+#[instruction(fighter_key: Pubkey)]
+pub struct UpdateLeaderboard<'info> {
+    /// Anyone; pays rent the first time the leaderboard is created.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + GlobalLeaderboard::INIT_SPACE,
+        seeds = [LEADERBOARD_SEED],
+        bump
+    )]
+    pub leaderboard: Account<'info, GlobalLeaderboard>,
+
+    /// CHECK: fighter-registry `Fighter` PDA; owner and seeds are verified in the handler.
+    #[account(address = fighter_key @ RumbleError::InvalidFighterAccount)]
+    pub fighter: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLeaderboard<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [LEADERBOARD_SEED],
+        bump = leaderboard.bump,
+    )]
+    pub leaderboard: Account<'info, GlobalLeaderboard>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRumble<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    /// CHECK: Vault PDA — checked to see if winners have claimed.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury address, must match config.
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury @ RumbleError::InvalidTreasury,
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[derive(Accounts)]
+pub struct CloseCombatState<'info> {
+    #[account(
+        mut,
+        constraint = admin.key() == config.admin @ RumbleError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        seeds = [RUMBLE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = rumble.bump,
+    )]
+    pub rumble: Box<Account<'info, Rumble>>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [COMBAT_STATE_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    /// Not loaded, so pre-zero-copy combat states close without migrating.
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [COMBAT_LOG_SEED, rumble.id.to_le_bytes().as_ref()],
+        bump = combat_log.bump,
+    )]
+    pub combat_log: Option<Box<Account<'info, CombatLog>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_LOG_SEED, config.key().as_ref()],
+        bump = admin_log.load()?.bump,
+    )]
+    pub admin_log: AccountLoader<'info, AdminLog>,
+}
+
+#[cfg(feature = "combat")]
+#[delegate]
+#[derive(Accounts)]
+pub struct DelegateCombat<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    /// CHECK: The combat state PDA to delegate to the Ephemeral Rollup.
+    #[account(mut, del)]
+    pub pda: AccountInfo<'info>,
+}
+
+#[cfg(feature = "combat")]
+#[commit]
+#[derive(Accounts)]
+pub struct CommitCombatSecure<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(mut)]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+}
+
+#[cfg(feature = "combat")]
+#[commit]
+#[derive(Accounts)]
+pub struct UndelegateCombat<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(mut)]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+}
+
+/// Accounts for requesting VRF-based matchup seed.
+/// The `#[vrf]` macro auto-injects: program_identity, vrf_program, slot_hashes, system_program.
+#[cfg(feature = "combat")]
+#[vrf]
+#[derive(Accounts)]
+#[instruction(rumble_id: u64)]
+pub struct RequestMatchupSeed<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, RumbleConfig>,
+
+    #[account(
+        mut,
+        seeds = [COMBAT_STATE_SEED, rumble_id.to_le_bytes().as_ref()],
+        bump = load_combat(&combat_state)?.bump,
+        constraint = load_combat(&combat_state)?.rumble_id == rumble_id @ RumbleError::InvalidRumble,
+    )]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+
+    /// CHECK: The MagicBlock VRF oracle queue
+    #[account(mut, address = DEFAULT_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
 
 /// Accounts for the VRF callback (called by the MagicBlock oracle).
 #[cfg(feature = "combat")]
-#[derive(Accounts)]
-pub struct CallbackMatchupSeed<'info> {
-    /// The VRF program identity — only the oracle can call this
-    #[account(address = VRF_PROGRAM_IDENTITY)]
-    pub vrf_program_identity: Signer<'info>,
+#[derive(Accounts)]
+pub struct CallbackMatchupSeed<'info> {
+    /// The VRF program identity — only the oracle can call this
+    #[account(address = VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    #[account(mut)]
+    pub combat_state: AccountLoader<'info, RumbleCombatState>,
+}
+
+// ---------------------------------------------------------------------------
+// State
+// ---------------------------------------------------------------------------
+
+#[account]
+#[derive(InitSpace)]
+pub struct RumbleConfig {
+    pub admin: Pubkey,                    // 32
+    pub treasury: Pubkey,                 // 32
+    pub total_rumbles: u64,               // 8
+    pub bump: u8,                         // 1
+    pub admin_fee_bps: u16,               // 2
+    pub sponsorship_fee_bps: u16,         // 2
+    pub min_bet_lamports: u64,            // 8 (0 = no limit)
+    pub max_bet_per_bettor_lamports: u64, // 8 (0 = no limit)
+    pub max_total_pool_lamports: u64,     // 8 (0 = no limit)
+    pub paused: bool,                     // 1
+    pub commit_window_slots: u32,         // 4
+    pub reveal_window_slots: u32,         // 4
+    pub crank_tip_bps: u16,               // 2 (cranker's cut of crank_claim_payout)
+    pub slots_per_second_milli: u32,      // 4 (slots/s x1000, for timestamp deadlines)
+    pub min_bettors: u16,                 // 2 (0 = no minimum; copied into new rumbles)
+    pub referral_fee_bps: u16,            // 2 (share of admin_fee_bps, <= admin_fee_bps)
+    pub ichor_burn_per_bet: u64,          // 8 (ICHOR base units burned per bet; 0 = off)
+    pub keeper_fee_lamports: u64,         // 8 (bounty per keeper call; 0 = off)
+    pub insurance_premium_bps: u16,       // 2 (charged on insured bets; 0 = off)
+    pub insurance_payout_bps: u16,        // 2 (of insured stake, refunded on a loss)
+    pub combat_timeout_slots: u64,        // 8 (finalize_rumble timeout from combat start)
+}
+
+/// Stored with `fighter_slots` entries per fighter array (see
+/// `AccountSerialize for Rumble`); the arrays here are always full width.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Rumble {
+    pub id: u64,                            // 8
+    pub state: RumbleState,                 // 1
+    pub fighters: [Pubkey; MAX_FIGHTERS],   // 32 * 32 = 1024
+    pub fighter_count: u8,                  // 1
+    pub betting_pools: [u64; MAX_FIGHTERS], // 8 * 32 = 256
+    pub total_deployed: u64,                // 8
+    pub admin_fee_collected: u64,           // 8
+    pub sponsorship_paid: u64,              // 8
+    pub placements: [u8; MAX_FIGHTERS],     // 32
+    pub winner_index: u8,         // 1
+    pub betting_deadline: i64,    // 8
+    pub combat_started_at: i64,   // 8
+    pub completed_at: i64,        // 8
+    pub bump: u8,                 // 1
+    pub cancelled_at: i64,        // 8
+    pub first_place_bps: u64,     // 8
+    pub second_place_bps: u64,    // 8
+    pub third_place_bps: u64,     // 8
+    pub max_bet_per_bettor: u64,  // 8 (0 = uncapped)
+    pub min_pool_lamports: u64,   // 8 (0 = no minimum)
+    pub underfunded: bool,        // 1
+    pub deadline_kind: DeadlineKind, // 1 (legacy = Slot)
+    pub vault_rent_reserve: u64,  // 8 (0 = none, vault holds stakes only)
+    pub result_source: u8,        // 1 (RESULT_SOURCE_*)
+    pub record_bets: bool,        // 1 (place_bet writes a BetRecord per bet)
+    /// Sponsorship fees `place_bet` routed to each fighter in this rumble.
+    pub sponsorship_by_fighter: [u64; MAX_FIGHTERS], // 256
+    /// Slot at which betting closes, whatever unit `betting_deadline` was given in.
+    /// 0 (only after `migrate_rumble`) falls back to `betting_deadline`.
+    pub betting_close_slot: u64, // 8
+    /// Bettor accounts opened by `place_bet` and still open.
+    pub unique_bettor_count: u16, // 2
+    /// Distinct bettors required to start combat (0 = no minimum).
+    pub min_bettors: u16, // 2
+    /// Organizer-funded bonus held in the vault, added to the distributable pool.
+    pub prize_pool: u64, // 8
+    /// Invite-only: `place_bet` requires the bettor in the rumble's WhitelistAccount.
+    pub whitelist_enabled: bool, // 1
+    /// Part of the treasury cut left in the vault for ICHOR staking bonuses;
+    /// drawn down by claims, whatever remains goes to the treasury on close.
+    pub stake_bonus_reserve: u64, // 8
+    /// Final `RumbleCombatState.history_hash`, copied by `finalize_rumble`
+    /// (all zero for admin-set results).
+    pub combat_history_hash: [u8; 32], // 32
+    /// Keeper bounties funded into the vault by the admin at creation, kept
+    /// apart from the betting pools. Spending is tracked in
+    /// `RumbleCombatState.keeper_paid`; the rest goes to the treasury at result time.
+    pub keeper_budget: u64, // 8
+    /// `CombatRuleset` this rumble fights under; default = built-in constants.
+    pub ruleset: Pubkey, // 32
+    /// Strikes can crit, seeded per turn from a slot hash taken at turn open.
+    pub crits_enabled: bool, // 1
+    /// ICHOR the creator's token account had to hold at creation (0 = ungated).
+    pub token_gate: u64, // 8
+    /// Net exacta stakes from `place_exacta_bet`, held in the vault apart
+    /// from `betting_pools` and `total_deployed`.
+    pub exacta_pool: u64, // 8
+    /// `settle_exacta_pool` has run; the fields below are fixed from then on.
+    pub exacta_settled: bool, // 1
+    /// Stake on the exact 1-2 finish (0 = nobody hit; the pool went to treasury).
+    pub exacta_winning_stake: u64, // 8
+    /// Exacta pool shared by winning tickets after the treasury cut.
+    pub exacta_distributable: u64, // 8
+    /// Winning-ticket stake already paid by `claim_exacta_payout`.
+    pub exacta_claimed_stake: u64, // 8
+    /// Lamports owed to bettors on paying places (stakes plus allocations),
+    /// fixed by the first winning claim. 0 until then or for refund rumbles.
+    pub winner_pool_snapshot: u64, // 8
+    /// Lamports `claim_payout` has paid winning bettors, stake bonuses excluded.
+    pub total_claimed_lamports: u64, // 8
+    /// Stake on paying places whose payout has been claimed; claims are
+    /// settled once this reaches `winner_pool_lamports`.
+    pub winner_stake_claimed: u64, // 8
+    /// A DisputeRecord is open on this result; claims and completion wait.
+    pub dispute_open: bool, // 1
+    /// Lamports `sweep_treasury` has moved to the treasury, across calls.
+    pub swept_lamports: u64, // 8
+    /// Seconds after `completed_at` before the rumble can be completed or
+    /// force-swept. 0 (only after `migrate_rumble`) means the 24-hour default.
+    pub claim_window_seconds: i64, // 8
+    /// `verify_vault_integrity` found the vault short; claims wait until the
+    /// admin lifts the halt.
+    pub vault_halted: bool, // 1
+    /// Final elimination ranks copied from the combat state by
+    /// `finalize_rumble` (1 = first out, 0 = never eliminated).
+    pub elimination_rank: [u8; MAX_FIGHTERS], // 32
+    /// Insurance premiums `place_bet` has put in the vault.
+    pub insurance_pool: u64, // 8
+    /// Lamports `claim_insurance` has paid out of `insurance_pool`.
+    pub insurance_paid: u64, // 8
+    /// Insured net stake per fighter, across bettors.
+    pub insured_stake: [u64; MAX_FIGHTERS], // 256
+    /// `config.insurance_payout_bps` when the rumble was created.
+    pub insurance_payout_bps: u16, // 2
+    /// First `deposit_prize_pool` signer; later deposits must come from it, and
+    /// `refund_prize_pool` returns the pool to it when nobody can win it.
+    pub prize_depositor: Pubkey, // 32
+    /// Created with `require_registered`: every fighter's registry account was
+    /// checked, so `start_combat` requires them all again for the snapshot.
+    pub registration_required: bool, // 1
+    /// `resolve_dispute` has closed this rumble's dispute, so claims on an
+    /// admin-set result no longer wait out the dispute window.
+    pub dispute_resolved: bool, // 1
+    /// Fighter slots the account is sized for: `MAX_FIGHTERS_V1`, or
+    /// `MAX_FIGHTERS` for rumbles created on the v2 layout. Only stored on the
+    /// v2 layout; either way it is read back from the account length.
+    pub fighter_slots: u8, // 1 (v2 only)
+}
+
+impl Rumble {
+    /// Fighter slots this rumble is stored with.
+    fn slots(&self) -> usize {
+        if self.fighter_slots as usize == MAX_FIGHTERS {
+            MAX_FIGHTERS
+        } else {
+            MAX_FIGHTERS_V1
+        }
+    }
+
+    /// Borsh layout of the fields with every fighter array cut to `slots`.
+    fn write_fields<W: std::io::Write>(&self, writer: &mut W, slots: usize) -> std::io::Result<()> {
+        self.id.serialize(writer)?;
+        self.state.serialize(writer)?;
+        write_fighter_slots(&self.fighters, slots, writer)?;
+        self.fighter_count.serialize(writer)?;
+        write_fighter_slots(&self.betting_pools, slots, writer)?;
+        self.total_deployed.serialize(writer)?;
+        self.admin_fee_collected.serialize(writer)?;
+        self.sponsorship_paid.serialize(writer)?;
+        write_fighter_slots(&self.placements, slots, writer)?;
+        self.winner_index.serialize(writer)?;
+        self.betting_deadline.serialize(writer)?;
+        self.combat_started_at.serialize(writer)?;
+        self.completed_at.serialize(writer)?;
+        self.bump.serialize(writer)?;
+        self.cancelled_at.serialize(writer)?;
+        self.first_place_bps.serialize(writer)?;
+        self.second_place_bps.serialize(writer)?;
+        self.third_place_bps.serialize(writer)?;
+        self.max_bet_per_bettor.serialize(writer)?;
+        self.min_pool_lamports.serialize(writer)?;
+        self.underfunded.serialize(writer)?;
+        self.deadline_kind.serialize(writer)?;
+        self.vault_rent_reserve.serialize(writer)?;
+        self.result_source.serialize(writer)?;
+        self.record_bets.serialize(writer)?;
+        write_fighter_slots(&self.sponsorship_by_fighter, slots, writer)?;
+        self.betting_close_slot.serialize(writer)?;
+        self.unique_bettor_count.serialize(writer)?;
+        self.min_bettors.serialize(writer)?;
+        self.prize_pool.serialize(writer)?;
+        self.whitelist_enabled.serialize(writer)?;
+        self.stake_bonus_reserve.serialize(writer)?;
+        self.combat_history_hash.serialize(writer)?;
+        self.keeper_budget.serialize(writer)?;
+        self.ruleset.serialize(writer)?;
+        self.crits_enabled.serialize(writer)?;
+        self.token_gate.serialize(writer)?;
+        self.exacta_pool.serialize(writer)?;
+        self.exacta_settled.serialize(writer)?;
+        self.exacta_winning_stake.serialize(writer)?;
+        self.exacta_distributable.serialize(writer)?;
+        self.exacta_claimed_stake.serialize(writer)?;
+        self.winner_pool_snapshot.serialize(writer)?;
+        self.total_claimed_lamports.serialize(writer)?;
+        self.winner_stake_claimed.serialize(writer)?;
+        self.dispute_open.serialize(writer)?;
+        self.swept_lamports.serialize(writer)?;
+        self.claim_window_seconds.serialize(writer)?;
+        self.vault_halted.serialize(writer)?;
+        write_fighter_slots(&self.elimination_rank, slots, writer)?;
+        self.insurance_pool.serialize(writer)?;
+        self.insurance_paid.serialize(writer)?;
+        write_fighter_slots(&self.insured_stake, slots, writer)?;
+        self.insurance_payout_bps.serialize(writer)?;
+        self.prize_depositor.serialize(writer)?;
+        self.registration_required.serialize(writer)?;
+        self.dispute_resolved.serialize(writer)?;
+        if slots == MAX_FIGHTERS {
+            self.fighter_slots.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    fn read_fields(data: &mut &[u8], slots: usize) -> std::io::Result<Self> {
+        Ok(Self {
+            id: AnchorDeserialize::deserialize(data)?,
+            state: AnchorDeserialize::deserialize(data)?,
+            fighters: read_fighter_slots(data, slots)?,
+            fighter_count: AnchorDeserialize::deserialize(data)?,
+            betting_pools: read_fighter_slots(data, slots)?,
+            total_deployed: AnchorDeserialize::deserialize(data)?,
+            admin_fee_collected: AnchorDeserialize::deserialize(data)?,
+            sponsorship_paid: AnchorDeserialize::deserialize(data)?,
+            placements: read_fighter_slots(data, slots)?,
+            winner_index: AnchorDeserialize::deserialize(data)?,
+            betting_deadline: AnchorDeserialize::deserialize(data)?,
+            combat_started_at: AnchorDeserialize::deserialize(data)?,
+            completed_at: AnchorDeserialize::deserialize(data)?,
+            bump: AnchorDeserialize::deserialize(data)?,
+            cancelled_at: AnchorDeserialize::deserialize(data)?,
+            first_place_bps: AnchorDeserialize::deserialize(data)?,
+            second_place_bps: AnchorDeserialize::deserialize(data)?,
+            third_place_bps: AnchorDeserialize::deserialize(data)?,
+            max_bet_per_bettor: AnchorDeserialize::deserialize(data)?,
+            min_pool_lamports: AnchorDeserialize::deserialize(data)?,
+            underfunded: AnchorDeserialize::deserialize(data)?,
+            deadline_kind: AnchorDeserialize::deserialize(data)?,
+            vault_rent_reserve: AnchorDeserialize::deserialize(data)?,
+            result_source: AnchorDeserialize::deserialize(data)?,
+            record_bets: AnchorDeserialize::deserialize(data)?,
+            sponsorship_by_fighter: read_fighter_slots(data, slots)?,
+            betting_close_slot: AnchorDeserialize::deserialize(data)?,
+            unique_bettor_count: AnchorDeserialize::deserialize(data)?,
+            min_bettors: AnchorDeserialize::deserialize(data)?,
+            prize_pool: AnchorDeserialize::deserialize(data)?,
+            whitelist_enabled: AnchorDeserialize::deserialize(data)?,
+            stake_bonus_reserve: AnchorDeserialize::deserialize(data)?,
+            combat_history_hash: AnchorDeserialize::deserialize(data)?,
+            keeper_budget: AnchorDeserialize::deserialize(data)?,
+            ruleset: AnchorDeserialize::deserialize(data)?,
+            crits_enabled: AnchorDeserialize::deserialize(data)?,
+            token_gate: AnchorDeserialize::deserialize(data)?,
+            exacta_pool: AnchorDeserialize::deserialize(data)?,
+            exacta_settled: AnchorDeserialize::deserialize(data)?,
+            exacta_winning_stake: AnchorDeserialize::deserialize(data)?,
+            exacta_distributable: AnchorDeserialize::deserialize(data)?,
+            exacta_claimed_stake: AnchorDeserialize::deserialize(data)?,
+            winner_pool_snapshot: AnchorDeserialize::deserialize(data)?,
+            total_claimed_lamports: AnchorDeserialize::deserialize(data)?,
+            winner_stake_claimed: AnchorDeserialize::deserialize(data)?,
+            dispute_open: AnchorDeserialize::deserialize(data)?,
+            swept_lamports: AnchorDeserialize::deserialize(data)?,
+            claim_window_seconds: AnchorDeserialize::deserialize(data)?,
+            vault_halted: AnchorDeserialize::deserialize(data)?,
+            elimination_rank: read_fighter_slots(data, slots)?,
+            insurance_pool: AnchorDeserialize::deserialize(data)?,
+            insurance_paid: AnchorDeserialize::deserialize(data)?,
+            insured_stake: read_fighter_slots(data, slots)?,
+            insurance_payout_bps: AnchorDeserialize::deserialize(data)?,
+            prize_depositor: AnchorDeserialize::deserialize(data)?,
+            registration_required: AnchorDeserialize::deserialize(data)?,
+            dispute_resolved: AnchorDeserialize::deserialize(data)?,
+            // The v2 layout's trailing byte always matches the account length.
+            fighter_slots: slots as u8,
+        })
+    }
+}
+
+fn write_fighter_slots<T: AnchorSerialize, W: std::io::Write>(
+    values: &[T; MAX_FIGHTERS],
+    slots: usize,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    values[..slots]
+        .iter()
+        .try_for_each(|value| value.serialize(writer))
+}
+
+fn read_fighter_slots<T: AnchorDeserialize + Copy + Default>(
+    data: &mut &[u8],
+    slots: usize,
+) -> std::io::Result<[T; MAX_FIGHTERS]> {
+    let mut values = [T::default(); MAX_FIGHTERS];
+    for value in values.iter_mut().take(slots) {
+        *value = T::deserialize(data)?;
+    }
+    Ok(values)
+}
+
+impl anchor_lang::Discriminator for Rumble {
+    // sha256("account:Rumble")[..8], as `#[account]` derived it
+    const DISCRIMINATOR: &'static [u8] = &[121, 136, 74, 188, 164, 146, 171, 5];
+}
+
+impl anchor_lang::Owner for Rumble {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl anchor_lang::AccountSerialize for Rumble {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(Rumble::DISCRIMINATOR)
+            .and_then(|()| self.write_fields(writer, self.slots()))
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize.into())
+    }
+}
+
+/// The account length picks the width: `RUMBLE_V2_LEN` and up is a v2 rumble.
+impl anchor_lang::AccountDeserialize for Rumble {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < Rumble::DISCRIMINATOR.len() {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if &buf[..Rumble::DISCRIMINATOR.len()] != Rumble::DISCRIMINATOR {
+            return Err(
+                error!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch)
+                    .with_account_name("Rumble"),
+            );
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let slots = if buf.len() >= RUMBLE_V2_LEN {
+            MAX_FIGHTERS
+        } else {
+            MAX_FIGHTERS_V1
+        };
+        let mut data: &[u8] = &buf[Rumble::DISCRIMINATOR.len()..];
+        Rumble::read_fields(&mut data, slots)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+/// Bettors allowed into an invite-only rumble, at [WHITELIST_SEED, rumble_id].
+#[account]
+#[derive(InitSpace)]
+pub struct WhitelistAccount {
+    pub rumble_id: u64,                           // 8
+    pub entries: [Pubkey; MAX_WHITELIST_ENTRIES], // 2048 (first `count` used)
+    pub count: u8,                                // 1
+    pub bump: u8,                                 // 1
+}
+
+/// Combat numbers at [COMBAT_RULESET_SEED, ruleset_id], so balance patches
+/// don't need a program upgrade. Referenced by `Rumble.ruleset`.
+#[account]
+#[derive(InitSpace)]
+pub struct CombatRuleset {
+    pub ruleset_id: u32,         // 4
+    pub strike_damage_high: u16, // 2
+    pub strike_damage_mid: u16,  // 2
+    pub strike_damage_low: u16,  // 2
+    pub catch_damage: u16,       // 2
+    pub counter_damage: u16,     // 2 (feints add FEINT_COUNTER_BONUS)
+    pub special_damage: u16,     // 2
+    pub meter_per_turn: u8,      // 1
+    pub special_meter_cost: u8,  // 1 (also the meter cap)
+    pub start_hp: u16,           // 2 (before HP overrides and level bonus)
+    pub bump: u8,                // 1
+}
+
+/// Stored with the rumble's fighter slots per array (see `bettor_account_len`);
+/// the arrays here are always full width.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct BettorAccount {
+    pub authority: Pubkey,                        // 32
+    pub rumble_id: u64,                           // 8
+    pub fighter_index: u8,                        // 1 (legacy compatibility)
+    pub sol_deployed: u64,                        // 8 (total deployed across all fighters)
+    pub claimable_lamports: u64,                  // 8
+    pub total_claimed_lamports: u64,              // 8
+    pub last_claim_ts: i64,                       // 8
+    pub claimed: bool,                            // 1
+    pub bump: u8,                                 // 1
+    pub fighter_deployments: [u64; MAX_FIGHTERS], // 256 (128 on 16-slot rumbles)
+    pub bet_sequence: u32,                        // 4 (next BetRecord sequence)
+    pub insured_deployments: [u64; MAX_FIGHTERS], // 256 (net stake bought insurance)
+    pub insurance_claimed: bool,                  // 1
+    pub insurance_premium_paid: u64,              // 8 (refunded with the stake)
+    /// Only stored on the v2 layout; either way it is read back from the
+    /// account length.
+    pub fighter_slots: u8, // 1 (v2 only)
+}
+
+impl BettorAccount {
+    /// Fighter slots this account is stored with: 16, or 32 on v2 rumbles.
+    fn slots(&self) -> usize {
+        if self.fighter_slots as usize == MAX_FIGHTERS {
+            MAX_FIGHTERS
+        } else {
+            MAX_FIGHTERS_V1
+        }
+    }
+}
+
+impl anchor_lang::Discriminator for BettorAccount {
+    // sha256("account:BettorAccount")[..8], as `#[account]` derived it
+    const DISCRIMINATOR: &'static [u8] = &[122, 110, 158, 151, 236, 225, 6, 38];
+}
+
+impl anchor_lang::Owner for BettorAccount {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl anchor_lang::AccountSerialize for BettorAccount {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        let slots = self.slots();
+        let mut data = vec![0u8; bettor_account_len(slots)];
+        data[..8].copy_from_slice(BettorAccount::DISCRIMINATOR);
+        write_bettor_account_data(&mut data, &ParsedBettorAccount::from(self))?;
+        writer
+            .write_all(&data)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize.into())
+    }
+}
+
+/// Older, shorter bettor layouts load too (see `parse_bettor_account_data`).
+impl anchor_lang::AccountDeserialize for BettorAccount {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < BettorAccount::DISCRIMINATOR.len() {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if &buf[..BettorAccount::DISCRIMINATOR.len()] != BettorAccount::DISCRIMINATOR {
+            return Err(
+                error!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch)
+                    .with_account_name("BettorAccount"),
+            );
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let bettor = parse_bettor_account_fields(buf)?;
+        Ok(Self {
+            authority: bettor.authority,
+            rumble_id: bettor.rumble_id,
+            fighter_index: bettor.fighter_index,
+            sol_deployed: bettor.sol_deployed,
+            claimable_lamports: bettor.claimable_lamports,
+            total_claimed_lamports: bettor.total_claimed_lamports,
+            last_claim_ts: bettor.last_claim_ts,
+            claimed: bettor.claimed,
+            bump: bettor.bump,
+            fighter_deployments: bettor.fighter_deployments,
+            bet_sequence: bettor.bet_sequence,
+            insured_deployments: bettor.insured_deployments,
+            insurance_claimed: bettor.insurance_claimed,
+            insurance_premium_paid: bettor.insurance_premium_paid,
+            fighter_slots: bettor_account_slots(buf.len()) as u8,
+        })
+    }
+}
+
+/// A bettor's exacta stake on one ordered (first, second) pair, at
+/// [EXACTA_TICKET_SEED, rumble_id, bettor, first_index, second_index].
+/// Closed by `claim_exacta_payout`.
+#[account]
+#[derive(InitSpace)]
+pub struct ExactaTicket {
+    pub rumble_id: u64,   // 8
+    pub bettor: Pubkey,   // 32
+    pub first_index: u8,  // 1
+    pub second_index: u8, // 1
+    pub amount: u64,      // 8 (net stake)
+    pub bump: u8,         // 1
+}
+
+/// Net exacta stake across all bettors on one ordered pair, at
+/// [EXACTA_COMBO_SEED, rumble_id, first_index, second_index].
+#[account]
+#[derive(InitSpace)]
+pub struct ExactaCombo {
+    pub rumble_id: u64,    // 8
+    pub first_index: u8,   // 1
+    pub second_index: u8,  // 1
+    pub total_staked: u64, // 8
+    pub bump: u8,          // 1
+}
+
+/// Ring buffer of the last BETTOR_INDEX_LEN rumbles a wallet bet on, at
+/// [BETTOR_INDEX_SEED, wallet], so wallet UIs can list open positions without
+/// scanning every BettorAccount. `head` is where the next rumble goes; the
+/// program itself never reads it.
+#[account]
+#[derive(InitSpace)]
+pub struct BettorIndex {
+    pub wallet: Pubkey,                      // 32
+    pub head: u8,                            // 1
+    pub count: u8,                           // 1 (filled entries, up to BETTOR_INDEX_LEN)
+    pub bump: u8,                            // 1
+    pub rumble_ids: [u64; BETTOR_INDEX_LEN], // 8 * 32
+    pub claimed: [bool; BETTOR_INDEX_LEN],   // 32 (claimed[i] goes with rumble_ids[i])
+}
+
+/// Lifetime totals across every rumble, at [GLOBAL_STATS_SEED]. Written by
+/// `create_rumble`, `place_bet` and `complete_rumble` when passed; the
+/// program never reads it back.
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalStats {
+    pub total_rumbles: u64,              // 8 created
+    pub total_bets_lamports: u64,        // 8 gross, fees included
+    pub total_bettors: u64,              // 8 bettor accounts opened (one per wallet per rumble)
+    pub total_fighters_entered: u64,     // 8
+    pub total_sponsorship_lamports: u64, // 8
+    pub completed_rumbles: u64,          // 8
+    pub bump: u8,                        // 1
+}
+
+/// A wallet's record across every rumble, at [BETTOR_STATS_SEED, bettor].
+/// Separate from the per-rumble BettorAccount and only kept up to date by
+/// the instructions it is passed to; `close_bettor_stats` wipes it.
+#[account]
+#[derive(InitSpace)]
+pub struct BettorStats {
+    pub bettor: Pubkey,               // 32
+    pub total_bets: u64,              // 8 every place_bet
+    pub total_deployed_lamports: u64, // 8 net of fees
+    pub total_claimed_lamports: u64,  // 8 paid by claim_payout, refunds included
+    pub wins: u32,                    // 4 winning claims
+    pub losses: u32,                  // 4 losing bettor accounts closed
+    pub rumbles_entered: u32,         // 4
+    pub bump: u8,                     // 1
+}
+
+/// How many bets a wallet has landed on one rumble in its latest slot, at
+/// [RATE_LIMIT_SEED, bettor, rumble_id]. The claim, refund and
+/// `close_bettor_account` paths close it back to the bettor.
+#[account]
+#[derive(InitSpace)]
+pub struct BettorRateLimit {
+    pub last_bet_slot: u64, // 8
+    pub bets_this_slot: u8, // 1
+    pub bump: u8,           // 1
+}
+
+/// A promo wallet that bets without the admin fee, at [FEE_EXEMPT_SEED, wallet].
+/// Ignored by `place_bet` once `expires_slot` has passed.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeExemption {
+    pub wallet: Pubkey,    // 32
+    pub expires_slot: u64, // 8 last slot the exemption applies
+    pub bump: u8,          // 1
+}
+
+/// A bettor's challenge to an admin-set result, at [DISPUTE_SEED, rumble_id].
+#[account]
+#[derive(InitSpace)]
+pub struct DisputeRecord {
+    pub rumble_id: u64,          // 8
+    pub disputer: Pubkey,        // 32
+    pub evidence_uri: [u8; 128], // 128
+    pub filed_at: i64,           // 8
+    pub resolved: bool,          // 1
+    pub bump: u8,                // 1
+}
+
+/// One bet on a `record_bets` rumble, kept for auditors and indexers.
+#[account]
+#[derive(InitSpace)]
+pub struct BetRecord {
+    pub rumble_id: u64,       // 8
+    pub bettor: Pubkey,       // 32
+    pub sequence: u32,        // 4
+    pub fighter_index: u8,    // 1
+    pub gross_amount: u64,    // 8
+    pub net_amount: u64,      // 8
+    pub admin_fee: u64,       // 8
+    pub sponsorship_fee: u64, // 8
+    pub slot: u64,            // 8
+    pub timestamp: i64,       // 8
+    pub bump: u8,             // 1
+}
+
+/// Sponsorship owed to a fighter's owner, tracked next to the lamport-holding
+/// sponsorship PDA so claims pay exactly what bettors contributed.
+#[account]
+#[derive(InitSpace)]
+pub struct SponsorshipLedger {
+    pub fighter: Pubkey,      // 32
+    pub total_accrued: u64,   // 8
+    pub total_claimed: u64,   // 8
+    /// Rumble of the latest contribution.
+    pub last_rumble_id: u64,  // 8
+    pub bump: u8,             // 1
+}
+
+#[cfg(feature = "combat")]
+#[account]
+#[derive(InitSpace)]
+pub struct FighterDelegate {
+    pub fighter: Pubkey,      // 32
+    pub authority: Pubkey,    // 32
+    pub authorized_slot: u64, // 8
+    pub revoked: bool,        // 1
+    pub bump: u8,             // 1
+    pub expires_at_slot: u64, // 8 (0 = until revoked)
+}
+
+/// A fighter's move for its latest committed turn, one PDA per fighter per
+/// rumble at [MOVE_COMMIT_SEED, rumble_id, fighter], overwritten each turn.
+///
+/// Migration: commitments used to be one PDA per turn, with the turn number as
+/// a fourth seed. Upgrade while no rumble is in Combat; the old per-turn PDAs
+/// are then inert and are reclaimed with `close_legacy_move_commitment` or
+/// `close_move_commitments_batch`.
+#[cfg(feature = "combat")]
+#[account]
+#[derive(InitSpace)]
+pub struct MoveCommitment {
+    pub rumble_id: u64,      // 8
+    pub fighter: Pubkey,     // 32
+    pub turn: u32,           // 4
+    pub move_hash: [u8; 32], // 32
+    pub revealed_move: u8,   // 1
+    pub revealed: bool,      // 1
+    pub committed_slot: u64, // 8
+    pub revealed_slot: u64,  // 8
+    pub bump: u8,            // 1
+}
+
+/// One resolved duel, as recorded in a CombatLog.
+#[cfg(feature = "combat")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct DuelLogEntry {
+    pub turn: u32,         // 4
+    pub fighter_a_idx: u8, // 1
+    pub fighter_b_idx: u8, // 1
+    pub move_a: u8,        // 1
+    pub move_b: u8,        // 1
+    pub damage_to_a: u16,  // 2
+    pub damage_to_b: u16,  // 2
+}
+
+/// On-chain duel history for replay verification. Sealed by `finalize_rumble`.
+#[cfg(feature = "combat")]
+#[account]
+#[derive(InitSpace)]
+pub struct CombatLog {
+    pub rumble_id: u64,                                  // 8
+    pub count: u16,                                      // 2
+    pub truncated: bool,                                 // 1 (duels past capacity were dropped)
+    pub sealed: bool,                                    // 1
+    pub bump: u8,                                        // 1
+    pub entries: [DuelLogEntry; COMBAT_LOG_CAPACITY],    // 12 * 256 = 3072
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RumbleMetadata {
+    pub rumble_id: u64,         // 8
+    pub name: [u8; 64],         // 64
+    pub description: [u8; 256], // 256
+    pub image_uri: [u8; 128],   // 128
+    pub bump: u8,               // 1
+}
+
+/// Privileged calls (results, disputes, sweeps, admin and treasury handover,
+/// config and whitelist updates) at [ADMIN_LOG_SEED, config], newest at
+/// `head - 1`. Only the program writes it and it cannot be closed. Zero-copy:
+/// at ~7 KB it is too large to deserialize on the stack.
+#[account(zero_copy)]
+pub struct AdminLog {
+    pub config: Pubkey,                               // 32
+    pub total_entries: u64,                           // 8 appended ever, overwritten included
+    pub entries: [AdminLogEntry; ADMIN_LOG_CAPACITY], // 56 * 128 = 7168
+    pub head: u8,                                     // 1 next entry to write
+    pub count: u8,                                    // 1 (<= ADMIN_LOG_CAPACITY)
+    pub bump: u8,                                     // 1
+    pub _padding: [u8; 5],                            // 5
+}
+
+#[zero_copy]
+pub struct AdminLogEntry {
+    pub instruction_tag: [u8; 8], // 8 Anchor discriminator of the instruction
+    pub actor: Pubkey,            // 32
+    pub at_slot: u64,             // 8
+    pub at_ts: i64,               // 8
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct LeaderboardEntry {
+    pub fighter: Pubkey, // 32
+    pub wins: u64,       // 8
+}
+
+/// Top fighters by registry win count, highest first; empty slots are zeroed.
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalLeaderboard {
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE], // 40 * 16 = 640
+    pub bump: u8,                                      // 1
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingAdminRE {
+    pub proposed_admin: Pubkey, // 32
+    pub proposed_at: u64,       // 8
+    pub bump: u8,               // 1
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingTreasury {
+    pub proposed_treasury: Pubkey, // 32
+    pub effective_after_slot: u64, // 8
+    pub bump: u8,                  // 1
+}
+
+/// Per-rumble combat state at [COMBAT_STATE_SEED, rumble_id]. Zero-copy, so a
+/// turn doesn't deserialize every per-fighter array: fields are ordered by
+/// alignment and flags are `u8` (0/1). Combat states from the earlier borsh
+/// layout load only after `migrate_combat_state`.
+#[cfg(feature = "combat")]
+#[account(zero_copy)]
+pub struct RumbleCombatState {
+    pub rumble_id: u64,                          // 8
+    pub turn_open_slot: u64,                     // 8
+    pub commit_close_slot: u64,                  // 8
+    pub reveal_close_slot: u64,                  // 8
+    /// Keeper bounties paid out of `Rumble.keeper_budget` so far.
+    pub keeper_paid: u64,                        // 8
+    /// Crit entropy for the open turn, fixed at turn open before any move is
+    /// revealed (0 unless `Rumble.crits_enabled`).
+    pub crit_seed: u64,                          // 8
+    /// Slot `start_combat` ran; `combat_timeout_slots` counts from here.
+    pub combat_start_slot: u64,                  // 8
+    pub total_damage_dealt: [u64; MAX_FIGHTERS], // 256
+    pub total_damage_taken: [u64; MAX_FIGHTERS], // 256
+    /// Each fighter's registry attributes, snapshotted at combat start; every
+    /// later turn reads these instead of keeper-passed accounts.
+    pub fighter_attributes: [PackedFighterAttributes; MAX_FIGHTERS], // 24 * 32 = 768
+    pub current_turn: u32,                       // 4
+    pub hp: [u16; MAX_FIGHTERS],                 // 64
+    /// Starting HP per fighter: the admin override before combat, then the HP
+    /// each fighter actually started with (incl. level bonus); 0 = `START_HP`.
+    pub hp_overrides: [u16; MAX_FIGHTERS],       // 64
+    /// HP at the start of a chunked turn, before poison, for the history hash.
+    pub turn_start_hp: [u16; MAX_FIGHTERS],      // 64
+    /// Bitmask of the open turn's pairs `resolve_turn_pair` has resolved.
+    pub pairs_resolved: u16,                     // 2
+    pub vrf_seed: [u8; 32],                      // 32
+    /// Hash chain over every resolved turn; see `turn_history_hash`.
+    pub history_hash: [u8; 32],                  // 32
+    pub fighter_count: u8,                       // 1
+    pub turn_resolved: u8,                       // 1
+    pub remaining_fighters: u8,                  // 1
+    pub winner_index: u8,                        // 1 (255 until known)
+    pub bump: u8,                                // 1
+    /// Whether the open turn is being resolved pair by pair; `resolve_turn`
+    /// and `post_turn_result` refuse it until `seal_turn` finishes it.
+    pub turn_chunked: u8,                        // 1
+    /// `Rumble.fighter_slots`: per-turn history hashes cover this many fighters.
+    pub fighter_slots: u8,                       // 1
+    pub meter: [u8; MAX_FIGHTERS],               // 32
+    pub elimination_rank: [u8; MAX_FIGHTERS],    // 32
+    /// Opponent index per fighter for the open turn, set at open_turn/advance_turn
+    /// (`PAIRING_BYE` = no opponent).
+    pub turn_pairings: [u8; MAX_FIGHTERS],       // 32
+    /// Turns each fighter committed a move but did not reveal it.
+    pub missed_reveals: [u8; MAX_FIGHTERS],      // 32
+    /// Consecutive turns each fighter fell back to a default move in `resolve_turn`.
+    pub missed_turns: [u8; MAX_FIGHTERS],        // 32
+    /// Move each fighter resolved in its last duel (`NO_LAST_MOVE` before the first).
+    pub last_move: [u8; MAX_FIGHTERS],           // 32
+    /// Strikes each fighter has blocked in a row; see `GUARD_BREAK_THRESHOLD`.
+    pub consecutive_guards: [u8; MAX_FIGHTERS],  // 32
+    /// Low-strike hits taken; poisoned at `POISON_STACK_THRESHOLD`.
+    pub poison_stacks: [u8; MAX_FIGHTERS],       // 32
+    pub _padding: [u8; 3],                       // 3
+}
+
+#[cfg(feature = "combat")]
+impl RumbleCombatState {
+    /// Registry attributes snapshotted at combat start, unpacked.
+    fn attributes(&self) -> [FighterAttributes; MAX_FIGHTERS] {
+        self.fighter_attributes.map(FighterAttributes::from)
+    }
+
+    /// Fighter slots of the rumble this combat belongs to.
+    fn slots(&self) -> usize {
+        if self.fighter_slots as usize == MAX_FIGHTERS {
+            MAX_FIGHTERS
+        } else {
+            MAX_FIGHTERS_V1
+        }
+    }
+}
+
+/// `RumbleCombatState` as it was laid out before going zero-copy; only read by
+/// `migrate_combat_state`.
+#[cfg(feature = "combat")]
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyRumbleCombatState {
+    rumble_id: u64,
+    fighter_count: u8,
+    current_turn: u32,
+    turn_open_slot: u64,
+    commit_close_slot: u64,
+    reveal_close_slot: u64,
+    turn_resolved: bool,
+    remaining_fighters: u8,
+    winner_index: u8,
+    hp: [u16; MAX_FIGHTERS_V1],
+    meter: [u8; MAX_FIGHTERS_V1],
+    elimination_rank: [u8; MAX_FIGHTERS_V1],
+    total_damage_dealt: [u64; MAX_FIGHTERS_V1],
+    total_damage_taken: [u64; MAX_FIGHTERS_V1],
+    vrf_seed: [u8; 32],
+    bump: u8,
+    turn_pairings: [u8; MAX_FIGHTERS_V1],
+    fighter_attributes: [FighterAttributes; MAX_FIGHTERS_V1],
+    missed_reveals: [u8; MAX_FIGHTERS_V1],
+    missed_turns: [u8; MAX_FIGHTERS_V1],
+    last_move: [u8; MAX_FIGHTERS_V1],
+    consecutive_guards: [u8; MAX_FIGHTERS_V1],
+    poison_stacks: [u8; MAX_FIGHTERS_V1],
+    hp_overrides: [u16; MAX_FIGHTERS_V1],
+    history_hash: [u8; 32],
+    keeper_paid: u64,
+    crit_seed: u64,
+    combat_start_slot: u64,
+    pairs_resolved: u8,
+    turn_chunked: bool,
+    turn_start_hp: [u16; MAX_FIGHTERS_V1],
+}
+
+#[cfg(feature = "combat")]
+impl From<LegacyRumbleCombatState> for RumbleCombatState {
+    fn from(legacy: LegacyRumbleCombatState) -> Self {
+        Self {
+            rumble_id: legacy.rumble_id,
+            turn_open_slot: legacy.turn_open_slot,
+            commit_close_slot: legacy.commit_close_slot,
+            reveal_close_slot: legacy.reveal_close_slot,
+            keeper_paid: legacy.keeper_paid,
+            crit_seed: legacy.crit_seed,
+            combat_start_slot: legacy.combat_start_slot,
+            total_damage_dealt: widen_fighter_array(legacy.total_damage_dealt, 0),
+            total_damage_taken: widen_fighter_array(legacy.total_damage_taken, 0),
+            fighter_attributes: widen_fighter_array(
+                legacy.fighter_attributes.map(PackedFighterAttributes::from),
+                FighterAttributes::default().into(),
+            ),
+            current_turn: legacy.current_turn,
+            hp: widen_fighter_array(legacy.hp, 0),
+            hp_overrides: widen_fighter_array(legacy.hp_overrides, 0),
+            turn_start_hp: widen_fighter_array(legacy.turn_start_hp, 0),
+            pairs_resolved: legacy.pairs_resolved as u16,
+            vrf_seed: legacy.vrf_seed,
+            history_hash: legacy.history_hash,
+            fighter_count: legacy.fighter_count,
+            turn_resolved: legacy.turn_resolved as u8,
+            remaining_fighters: legacy.remaining_fighters,
+            winner_index: legacy.winner_index,
+            bump: legacy.bump,
+            turn_chunked: legacy.turn_chunked as u8,
+            fighter_slots: MAX_FIGHTERS_V1 as u8,
+            meter: widen_fighter_array(legacy.meter, 0),
+            elimination_rank: widen_fighter_array(legacy.elimination_rank, 0),
+            turn_pairings: widen_fighter_array(legacy.turn_pairings, PAIRING_BYE),
+            missed_reveals: widen_fighter_array(legacy.missed_reveals, 0),
+            missed_turns: widen_fighter_array(legacy.missed_turns, 0),
+            last_move: widen_fighter_array(legacy.last_move, NO_LAST_MOVE),
+            consecutive_guards: widen_fighter_array(legacy.consecutive_guards, 0),
+            poison_stacks: widen_fighter_array(legacy.poison_stacks, 0),
+            _padding: [0; 3],
+        }
+    }
+}
+
+/// A 16-slot per-fighter array padded out to `MAX_FIGHTERS` with `fill`.
+#[cfg(feature = "combat")]
+fn widen_fighter_array<T: Copy>(values: [T; MAX_FIGHTERS_V1], fill: T) -> [T; MAX_FIGHTERS] {
+    let mut wide = [fill; MAX_FIGHTERS];
+    wide[..MAX_FIGHTERS_V1].copy_from_slice(&values);
+    wide
+}
+
+// ---------------------------------------------------------------------------
+// Enums
+// ---------------------------------------------------------------------------
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RumbleState {
+    Betting,
+    Combat,
+    Payout,
+    Complete,
+    Cancelled,
+}
+
+impl Default for RumbleState {
+    fn default() -> Self {
+        RumbleState::Betting
+    }
+}
+
+/// Unit of `Rumble::betting_deadline`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Default)]
+pub enum DeadlineKind {
+    #[default]
+    Slot,
+    UnixTimestamp,
+}
+
+fn validate_result_placements(
+    placements: &[u8],
+    fighter_count: usize,
+    winner_index: u8,
+) -> Result<()> {
+    require!(
+        fighter_count > 0 && fighter_count <= MAX_FIGHTERS,
+        RumbleError::InvalidPlacement
+    );
+    require!(placements.len() == fighter_count, RumbleError::InvalidPlacement);
+    require!(
+        (winner_index as usize) < fighter_count,
+        RumbleError::InvalidFighterIndex
+    );
+
+    let mut seen = [false; MAX_FIGHTERS + 1];
+    let mut first_place_count = 0usize;
+
+    for (idx, &placement) in placements.iter().enumerate() {
+        require!(
+            placement > 0 && (placement as usize) <= fighter_count,
+            RumbleError::InvalidPlacement
+        );
+        require!(
+            !seen[placement as usize],
+            RumbleError::InvalidPlacement
+        );
+        seen[placement as usize] = true;
+
+        if placement == 1 {
+            first_place_count += 1;
+            require!(idx == winner_index as usize, RumbleError::InvalidPlacement);
+        }
+    }
+
+    require!(first_place_count == 1, RumbleError::InvalidPlacement);
+    Ok(())
+}
+
+fn validate_stored_result_placements(rumble: &Rumble) -> Result<()> {
+    let fighter_count = rumble.fighter_count as usize;
+    validate_result_placements(
+        &rumble.placements[..fighter_count],
+        fighter_count,
+        rumble.winner_index,
+    )
+}
+
+fn validate_fee_bps(fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= MAX_FEE_BPS, RumbleError::InvalidFeeBps);
+    Ok(())
+}
+
+fn validate_referral_fee_bps(referral_fee_bps: u16, admin_fee_bps: u16) -> Result<()> {
+    require!(
+        referral_fee_bps <= admin_fee_bps,
+        RumbleError::ReferralFeeExceedsAdminFee
+    );
+    Ok(())
+}
+
+/// `place_bet`'s referrer accounts: both or neither, the PDA at
+/// [REFERRAL_SEED, referrer], and no self-referral.
+fn validate_referral_accounts(
+    bettor: &Pubkey,
+    referrer: Option<&Pubkey>,
+    referral_account: Option<&Pubkey>,
+) -> Result<()> {
+    match (referrer, referral_account) {
+        (None, None) => Ok(()),
+        (Some(referrer), Some(referral_account)) => {
+            require!(referrer != bettor, RumbleError::InvalidReferrer);
+            let (expected, _) =
+                Pubkey::find_program_address(&[REFERRAL_SEED, referrer.as_ref()], &crate::ID);
+            require!(*referral_account == expected, RumbleError::InvalidReferrer);
+            Ok(())
+        }
+        _ => err!(RumbleError::InvalidReferrer),
+    }
+}
+
+/// Split a referred bet's admin fee into (treasury, referrer) shares.
+fn split_referral_fee(amount: u64, admin_fee: u64, referral_fee_bps: u16) -> Result<(u64, u64)> {
+    let referral_fee = fee_lamports(amount, referral_fee_bps)?.min(admin_fee);
+    let treasury_fee = admin_fee
+        .checked_sub(referral_fee)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok((treasury_fee, referral_fee))
+}
+
+fn validate_combat_window(slots: u32) -> Result<()> {
+    require!(
+        (MIN_COMBAT_WINDOW_SLOTS..=MAX_COMBAT_WINDOW_SLOTS).contains(&slots),
+        RumbleError::InvalidCombatWindow
+    );
+    Ok(())
+}
+
+fn validate_combat_timeout(slots: u64) -> Result<()> {
+    require!(
+        (MIN_COMBAT_TIMEOUT_SLOTS..=MAX_COMBAT_TIMEOUT_SLOTS).contains(&slots),
+        RumbleError::InvalidCombatTimeout
+    );
+    Ok(())
+}
+
+fn fee_lamports(amount: u64, fee_bps: u16) -> Result<u64> {
+    amount
+        .checked_mul(fee_bps as u64)
+        .ok_or(RumbleError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// Whether a bettor's FeeExemption still waives the admin fee at `slot`.
+fn fee_exemption_active(exemption: &FeeExemption, slot: u64) -> bool {
+    slot <= exemption.expires_slot
+}
+
+/// Count a `place_bet` at `slot`; the counter restarts on each new slot and
+/// caps out at `MAX_BETS_PER_SLOT`.
+fn record_slot_bet(rate_limit: &mut BettorRateLimit, slot: u64) -> Result<()> {
+    if rate_limit.last_bet_slot == slot {
+        require!(
+            rate_limit.bets_this_slot < MAX_BETS_PER_SLOT,
+            RumbleError::BetRateLimited
+        );
+        rate_limit.bets_this_slot = rate_limit
+            .bets_this_slot
+            .checked_add(1)
+            .ok_or(RumbleError::MathOverflow)?;
+    } else {
+        rate_limit.last_bet_slot = slot;
+        rate_limit.bets_this_slot = 1;
+    }
+    Ok(())
+}
+
+fn validate_fee_exemption_grant(
+    config: &RumbleConfig,
+    admin: &Pubkey,
+    expires_slot: u64,
+    slot: u64,
+) -> Result<()> {
+    require!(*admin == config.admin, RumbleError::Unauthorized);
+    require!(expires_slot > slot, RumbleError::InvalidFeeExemption);
+    Ok(())
+}
+
+/// Enforce config bet limits. `amount` is the gross bet; caps apply to the
+/// net stake that lands in the pool. Zero-valued limits are disabled.
+fn validate_bet_limits(
+    config: &RumbleConfig,
+    amount: u64,
+    net_bet: u64,
+    bettor_deployed: u64,
+    pool_deployed: u64,
+) -> Result<()> {
+    if config.min_bet_lamports > 0 {
+        require!(
+            amount >= config.min_bet_lamports,
+            RumbleError::BetBelowMinimum
+        );
+    }
+    if config.max_bet_per_bettor_lamports > 0 {
+        let bettor_total = bettor_deployed
+            .checked_add(net_bet)
+            .ok_or(RumbleError::MathOverflow)?;
+        require!(
+            bettor_total <= config.max_bet_per_bettor_lamports,
+            RumbleError::BettorCapExceeded
+        );
+    }
+    if config.max_total_pool_lamports > 0 {
+        let pool_total = pool_deployed
+            .checked_add(net_bet)
+            .ok_or(RumbleError::MathOverflow)?;
+        require!(
+            pool_total <= config.max_total_pool_lamports,
+            RumbleError::PoolCapExceeded
+        );
+    }
+    Ok(())
+}
+
+/// Enforce the rumble's own per-bettor cap on the bettor's cumulative net stake.
+fn validate_rumble_bettor_cap(rumble: &Rumble, bettor_deployed: u64, net_bet: u64) -> Result<()> {
+    if rumble.max_bet_per_bettor == 0 {
+        return Ok(());
+    }
+    let bettor_total = bettor_deployed
+        .checked_add(net_bet)
+        .ok_or(RumbleError::MathOverflow)?;
+    require!(
+        bettor_total <= rumble.max_bet_per_bettor,
+        RumbleError::BettorCapExceeded
+    );
+    Ok(())
+}
+
+/// Copy a UTF-8 string into a zero-padded fixed-size metadata field.
+fn pad_metadata_field<const N: usize>(value: &str) -> Result<[u8; N]> {
+    let bytes = value.as_bytes();
+    require!(bytes.len() <= N, RumbleError::MetadataTooLong);
+    let mut field = [0u8; N];
+    field[..bytes.len()].copy_from_slice(bytes);
+    Ok(field)
+}
+
+/// A non-zero `token_gate` needs an ICHOR token account holding at least that much.
+fn check_token_gate(token_gate: u64, balance: Option<u64>) -> Result<()> {
+    if token_gate == 0 {
+        return Ok(());
+    }
+    let balance = balance.ok_or(RumbleError::TokenGateAccountRequired)?;
+    require!(balance >= token_gate, RumbleError::TokenGateNotMet);
+    Ok(())
+}
+
+fn validate_payout_split(split: &PayoutSplit) -> Result<()> {
+    require!(split.first_bps > 0, RumbleError::InvalidPayoutSplit);
+    let total = split
+        .first_bps
+        .checked_add(split.second_bps)
+        .and_then(|v| v.checked_add(split.third_bps))
+        .ok_or(RumbleError::MathOverflow)?;
+    require!(total == 10_000, RumbleError::InvalidPayoutSplit);
+    Ok(())
+}
+
+/// Stored split for places 1..=3. Rumbles migrated from before per-rumble
+/// splits carry all-zero fields and fall back to the compile-time defaults.
+fn payout_split_bps(rumble: &Rumble) -> [u64; 3] {
+    if rumble.first_place_bps == 0 && rumble.second_place_bps == 0 && rumble.third_place_bps == 0 {
+        [FIRST_PLACE_BPS, SECOND_PLACE_BPS, THIRD_PLACE_BPS]
+    } else {
+        [
+            rumble.first_place_bps,
+            rumble.second_place_bps,
+            rumble.third_place_bps,
+        ]
+    }
+}
+
+/// Whether fighter `idx` finished in a place with a share of the split.
+fn fighter_in_paying_place(rumble: &Rumble, idx: usize) -> bool {
+    let placement = rumble.placements[idx] as usize;
+    (1..=3).contains(&placement) && payout_split_bps(rumble)[placement - 1] > 0
+}
+
+/// Betting pool backing each paying place (1st..3rd). Places without a share
+/// in the split, or that do not exist in a small rumble, report zero.
+fn paying_place_pools(rumble: &Rumble) -> Result<[u64; 3]> {
+    validate_stored_result_placements(rumble)?;
+
+    let mut pools = [0u64; 3];
+    for i in 0..rumble.fighter_count as usize {
+        if fighter_in_paying_place(rumble, i) {
+            let place = rumble.placements[i] as usize - 1;
+            pools[place] = pools[place]
+                .checked_add(rumble.betting_pools[i])
+                .ok_or(RumbleError::MathOverflow)?;
+        }
+    }
+    Ok(pools)
+}
+
+/// Insured net stake in `insured` (per fighter) on fighters that missed the
+/// money.
+fn insured_losing_stake(rumble: &Rumble, insured: &[u64; MAX_FIGHTERS]) -> Result<u64> {
+    (0..rumble.fighter_count as usize)
+        .filter(|i| !fighter_in_paying_place(rumble, *i))
+        .try_fold(0u64, |acc, i| acc.checked_add(insured[i]))
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// What `claim_insurance` pays a bettor: `insurance_payout_bps` of their
+/// insured stake on losing fighters. When the pool can't cover that rate for
+/// every insured loser, each claim is scaled by pool / total cover, so the
+/// claims together never exceed the pool. Bettors owed a winner payout, and
+/// refund results, get nothing.
+fn insurance_claim_lamports(rumble: &Rumble, bettor: &ParsedBettorAccount) -> Result<u64> {
+    require!(
+        !refunds_all_bettors(rumble)?
+            && calculate_bettor_payout(rumble, &bettor_deployments(bettor))? == 0,
+        RumbleError::InsuranceNotClaimable
+    );
+    let cover = fee_lamports(
+        insured_losing_stake(rumble, &bettor.insured_deployments)?,
+        rumble.insurance_payout_bps,
+    )?;
+    let total_cover = fee_lamports(
+        insured_losing_stake(rumble, &rumble.insured_stake)?,
+        rumble.insurance_payout_bps,
+    )?;
+    if total_cover <= rumble.insurance_pool {
+        return Ok(cover);
+    }
+    let pro_rated = (cover as u128)
+        .checked_mul(rumble.insurance_pool as u128)
+        .ok_or(RumbleError::MathOverflow)?
+        .checked_div(total_cover as u128)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok(pro_rated as u64)
+}
+
+/// Lamports that remain claimable by bettors on paying places.
+fn winner_pool_lamports(rumble: &Rumble) -> Result<u64> {
+    let pools = paying_place_pools(rumble)?;
+    pools
+        .iter()
+        .try_fold(0u64, |acc, pool| acc.checked_add(*pool))
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+fn calculate_payout_breakdown(rumble: &Rumble) -> Result<(u64, u64, u64, u64)> {
+    let pools = paying_place_pools(rumble)?;
+
+    let mut total_pool: u64 = 0;
+    for i in 0..rumble.fighter_count as usize {
+        total_pool = total_pool
+            .checked_add(rumble.betting_pools[i])
+            .ok_or(RumbleError::MathOverflow)?;
+    }
+    let first_pool = pools[0];
+    let losers_pool = total_pool
+        .checked_sub(winner_pool_lamports(rumble)?)
+        .ok_or(RumbleError::MathOverflow)?;
+
+    let treasury_cut = losers_pool
+        .checked_mul(TREASURY_CUT_BPS)
+        .ok_or(RumbleError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(RumbleError::MathOverflow)?;
+    let distributable = losers_pool
+        .checked_sub(treasury_cut)
+        .and_then(|v| v.checked_add(rumble.prize_pool))
+        .ok_or(RumbleError::MathOverflow)?;
+
+    Ok((first_pool, losers_pool, treasury_cut, distributable))
+}
+
+/// Split the distributable pool across paying places. A paying place nobody
+/// bet on (e.g. a short rumble or an unbacked runner-up) collapses its share
+/// into the best-placed paying place that does have bettors.
+fn calculate_place_allocations(rumble: &Rumble) -> Result<([u64; 3], [u64; 3])> {
+    let pools = paying_place_pools(rumble)?;
+    let (_, _, _, distributable) = calculate_payout_breakdown(rumble)?;
+
+    let mut split = payout_split_bps(rumble);
+    if let Some(recipient) = pools.iter().position(|pool| *pool > 0) {
+        for place in 0..3 {
+            if pools[place] == 0 && split[place] > 0 {
+                split[recipient] = split[recipient]
+                    .checked_add(split[place])
+                    .ok_or(RumbleError::MathOverflow)?;
+                split[place] = 0;
+            }
+        }
+    }
+
+    let mut allocations = [0u64; 3];
+    for place in 0..3 {
+        allocations[place] = (distributable as u128)
+            .checked_mul(split[place] as u128)
+            .ok_or(RumbleError::MathOverflow)?
+            .checked_div(10_000)
+            .ok_or(RumbleError::MathOverflow)? as u64;
+    }
+    Ok((pools, allocations))
+}
+
+/// Total lamports owed to a bettor: every stake on a paying place is returned
+/// along with its proportional share of that place's allocation.
+fn calculate_bettor_payout(rumble: &Rumble, deployments: &[u64; MAX_FIGHTERS]) -> Result<u64> {
+    let (returned_stake, winnings) = calculate_bettor_winnings(rumble, deployments)?;
+    returned_stake
+        .checked_add(winnings)
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// `calculate_bettor_payout` split into (stakes returned, winnings on top).
+fn calculate_bettor_winnings(
+    rumble: &Rumble,
+    deployments: &[u64; MAX_FIGHTERS],
+) -> Result<(u64, u64)> {
+    let (pools, allocations) = calculate_place_allocations(rumble)?;
+
+    let mut returned_stake: u64 = 0;
+    let mut total_winnings: u64 = 0;
+    let fighter_count = rumble.fighter_count as usize;
+    for (&stake, &placement) in deployments[..fighter_count]
+        .iter()
+        .zip(rumble.placements[..fighter_count].iter())
+    {
+        let placement = placement as usize;
+        if stake == 0 || !(1..=3).contains(&placement) {
+            continue;
+        }
+        let pool = pools[placement - 1];
+        if pool == 0 {
+            continue;
+        }
+
+        // share = (stake / place_pool) * place_allocation
+        // Use u128 intermediate math to prevent overflow when pools exceed ~4 SOL
+        // (u64 overflows at ~1.8×10^19, but lamport products easily reach that)
+        let winnings = (allocations[placement - 1] as u128)
+            .checked_mul(stake as u128)
+            .ok_or(RumbleError::MathOverflow)?
+            .checked_div(pool as u128)
+            .ok_or(RumbleError::MathOverflow)? as u64;
+
+        returned_stake = returned_stake
+            .checked_add(stake)
+            .ok_or(RumbleError::MathOverflow)?;
+        total_winnings = total_winnings
+            .checked_add(winnings)
+            .ok_or(RumbleError::MathOverflow)?;
+    }
+    Ok((returned_stake, total_winnings))
+}
+
+/// Exacta picks must be two different fighters in the rumble.
+fn validate_exacta_pick(rumble: &Rumble, first_index: u8, second_index: u8) -> Result<()> {
+    let fighter_count = rumble.fighter_count as usize;
+    require!(
+        (first_index as usize) < fighter_count
+            && (second_index as usize) < fighter_count
+            && first_index != second_index,
+        RumbleError::InvalidExactaPick
+    );
+    Ok(())
+}
+
+/// Fighter indices that finished first and second.
+fn exacta_result(rumble: &Rumble) -> Result<(u8, u8)> {
+    validate_stored_result_placements(rumble)?;
+    let placements = &rumble.placements[..rumble.fighter_count as usize];
+    let index_of = |place: u8| {
+        placements
+            .iter()
+            .position(|p| *p == place)
+            .map(|i| i as u8)
+            .ok_or_else(|| error!(RumbleError::InvalidPlacement))
+    };
+    Ok((index_of(1)?, index_of(2)?))
+}
+
+/// Split the exacta pool into (treasury, winning tickets). The treasury cut
+/// is taken from the stakes that missed, as in `calculate_payout_breakdown`;
+/// with no winning stake the whole pool goes to the treasury.
+fn exacta_settlement(exacta_pool: u64, winning_stake: u64) -> Result<(u64, u64)> {
+    if winning_stake == 0 {
+        return Ok((exacta_pool, 0));
+    }
+    let losing_stake = exacta_pool
+        .checked_sub(winning_stake)
+        .ok_or(RumbleError::MathOverflow)?;
+    let treasury_cut = losing_stake
+        .checked_mul(TREASURY_CUT_BPS)
+        .ok_or(RumbleError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(RumbleError::MathOverflow)?;
+    let distributable = exacta_pool
+        .checked_sub(treasury_cut)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok((treasury_cut, distributable))
+}
+
+/// What a ticket is paid from a settled exacta pool: its share of the
+/// distributable pool if it called the exact 1-2 finish, else nothing.
+fn exacta_ticket_payout(rumble: &Rumble, ticket: &ExactaTicket) -> Result<u64> {
+    if rumble.exacta_winning_stake == 0 {
+        return Ok(0);
+    }
+    if exacta_result(rumble)? != (ticket.first_index, ticket.second_index) {
+        return Ok(0);
+    }
+    let payout = (rumble.exacta_distributable as u128)
+        .checked_mul(ticket.amount as u128)
+        .ok_or(RumbleError::MathOverflow)?
+        .checked_div(rumble.exacta_winning_stake as u128)
+        .ok_or(RumbleError::MathOverflow)?;
+    u64::try_from(payout).map_err(|_| error!(RumbleError::MathOverflow))
+}
+
+/// Total stake on a pair from its `ExactaCombo` PDA; an account nobody
+/// opened reads as zero.
+fn read_exacta_combo_stake(
+    info: &AccountInfo,
+    rumble_id: u64,
+    first_index: u8,
+    second_index: u8,
+) -> Result<u64> {
+    let (expected, _) = Pubkey::find_program_address(
+        &[
+            EXACTA_COMBO_SEED,
+            rumble_id.to_le_bytes().as_ref(),
+            &[first_index],
+            &[second_index],
+        ],
+        &crate::ID,
+    );
+    require!(info.key() == expected, RumbleError::InvalidExactaCombo);
+    if *info.owner != crate::ID || info.data_is_empty() {
+        return Ok(0);
+    }
+    let data = info.try_borrow_data()?;
+    let mut slice: &[u8] = &data;
+    Ok(ExactaCombo::try_deserialize(&mut slice)?.total_staked)
+}
+
+/// Exacta funds the vault still owes: the pool is unsettled, or some winning
+/// tickets are unclaimed. Underfunded rumbles refund tickets like any stake.
+fn exacta_claims_outstanding(rumble: &Rumble) -> bool {
+    if rumble.underfunded || rumble.exacta_pool == 0 {
+        return false;
+    }
+    !rumble.exacta_settled || rumble.exacta_claimed_stake < rumble.exacta_winning_stake
+}
+
+/// Slot at which a deadline passed to `create_rumble` falls. Unix timestamps
+/// are projected from the current slot at `slots_per_second_milli` / 1000.
+fn estimate_close_slot(
+    kind: DeadlineKind,
+    betting_deadline: i64,
+    clock: &Clock,
+    slots_per_second_milli: u32,
+) -> Result<u64> {
+    match kind {
+        DeadlineKind::Slot => {
+            u64::try_from(betting_deadline).map_err(|_| error!(RumbleError::DeadlineInPast))
+        }
+        DeadlineKind::UnixTimestamp => {
+            let seconds = betting_deadline
+                .checked_sub(clock.unix_timestamp)
+                .and_then(|s| u64::try_from(s).ok())
+                .ok_or(RumbleError::DeadlineInPast)?;
+            let rate = if slots_per_second_milli == 0 {
+                DEFAULT_SLOTS_PER_SECOND_MILLI
+            } else {
+                slots_per_second_milli
+            };
+            let slots = (seconds as u128)
+                .checked_mul(rate as u128)
+                .map(|v| v / 1_000)
+                .and_then(|v| u64::try_from(v).ok())
+                .ok_or(RumbleError::MathOverflow)?;
+            clock
+                .slot
+                .checked_add(slots)
+                .ok_or(error!(RumbleError::MathOverflow))
+        }
+    }
+}
+
+/// Whether betting has closed. Reads `betting_close_slot`, falling back to
+/// the `betting_deadline`/`deadline_kind` pair for migrated rumbles.
+fn betting_closed(rumble: &Rumble, clock: &Clock) -> Result<bool> {
+    if rumble.betting_close_slot != 0 {
+        return Ok(clock.slot >= rumble.betting_close_slot);
+    }
+    Ok(deadline_clock_value(rumble.deadline_kind, clock)? >= rumble.betting_deadline)
+}
+
+/// Current clock reading in the unit of a rumble's betting deadline.
+fn deadline_clock_value(kind: DeadlineKind, clock: &Clock) -> Result<i64> {
+    match kind {
+        DeadlineKind::Slot => {
+            i64::try_from(clock.slot).map_err(|_| error!(RumbleError::MathOverflow))
+        }
+        DeadlineKind::UnixTimestamp => Ok(clock.unix_timestamp),
+    }
+}
+
+fn require_betting_open(rumble: &Rumble, clock: &Clock) -> Result<()> {
+    require!(!betting_closed(rumble, clock)?, RumbleError::BettingClosed);
+    Ok(())
+}
+
+fn require_betting_ended(rumble: &Rumble, clock: &Clock) -> Result<()> {
+    require!(betting_closed(rumble, clock)?, RumbleError::BettingNotEnded);
+    Ok(())
+}
+
+/// Record the owning rumble on a CombatLog, which may have just been created
+/// by `init_if_needed`.
+#[cfg(feature = "combat")]
+fn stamp_combat_log(combat_log: &mut CombatLog, rumble_id: u64, bump: u8) {
+    combat_log.rumble_id = rumble_id;
+    combat_log.bump = bump;
+}
+
+/// Append a duel to the log. Once full, further duels only mark it truncated.
+#[cfg(feature = "combat")]
+fn append_duel_log(combat_log: &mut CombatLog, entry: DuelLogEntry) -> Result<()> {
+    require!(!combat_log.sealed, RumbleError::CombatLogSealed);
+    let count = combat_log.count as usize;
+    if count < COMBAT_LOG_CAPACITY {
+        combat_log.entries[count] = entry;
+        combat_log.count += 1;
+    } else {
+        combat_log.truncated = true;
+    }
+    Ok(())
+}
+
+/// Shared turn resolution for `resolve_turn` and `resolve_and_advance`.
+/// Missing or unrevealed moves fall back to a deterministic move; committed but
+/// unrevealed ones are penalized first.
+#[cfg(feature = "combat")]
+fn resolve_current_turn(
+    rumble: &Rumble,
+    ruleset: &CombatRuleset,
+    combat: &mut RumbleCombatState,
+    combat_log: &mut CombatLog,
+    remaining_accounts: &[AccountInfo<'_>],
+    clock: &Clock,
+) -> Result<()> {
+    require_turn_resolvable(rumble, combat, clock)?;
+    require!(combat.turn_chunked == 0, RumbleError::ChunkedTurnInProgress);
+
+    let fighter_count = combat.fighter_count as usize;
+    let turn = combat.current_turn;
+    let hp_before = combat.hp;
+    let mut moves = [NO_LAST_MOVE; MAX_FIGHTERS];
+
+    apply_poison_ticks(rumble, combat, turn)?;
+
+    let alive_indices: Vec<usize> = (0..fighter_count)
+        .filter(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
+        .collect();
+
+    if alive_indices.len() <= 1 {
+        combat.turn_resolved = 1;
+        if let Some(idx) = alive_indices.first() {
+            combat.winner_index = *idx as u8;
+        }
+        emit!(chain_turn_history(rumble.id, combat, turn, moves, &hp_before));
+        return Ok(());
+    }
+
+    let sudden_death_active = alive_indices.len() == 2;
+    let pairings = combat.turn_pairings;
+    let attributes = combat.attributes();
+
+    for &idx_a in alive_indices.iter() {
+        let opponent = pairings[idx_a];
+        if opponent == PAIRING_BYE || !alive_indices.contains(&(opponent as usize)) {
+            continue;
+        }
+        let idx_b = opponent as usize;
+        if idx_b < idx_a {
+            // Duel already resolved from the opponent's side.
+            continue;
+        }
+
+        let (move_a, move_b) = resolve_turn_duel(
+            rumble,
+            ruleset,
+            combat,
+            combat_log,
+            remaining_accounts,
+            &attributes,
+            (idx_a, idx_b),
+            sudden_death_active,
+        )?;
+        moves[idx_a] = move_a;
+        moves[idx_b] = move_b;
+    }
+
+    seal_resolved_turn(rumble, ruleset, combat, moves, &hp_before)
+}
+
+/// Preconditions shared by every path that resolves the open turn.
+#[cfg(feature = "combat")]
+fn require_turn_resolvable(
+    rumble: &Rumble,
+    combat: &RumbleCombatState,
+    clock: &Clock,
+) -> Result<()> {
+    require!(
+        rumble.state == RumbleState::Combat,
+        RumbleError::InvalidStateTransition
+    );
+    require!(combat.current_turn > 0, RumbleError::TurnNotOpen);
+    require!(combat.turn_resolved == 0, RumbleError::TurnAlreadyResolved);
+    require!(
+        clock.slot >= combat.reveal_close_slot,
+        RumbleError::RevealWindowActive
+    );
+    Ok(())
+}
+
+/// Fight one duel of the open turn: penalize missed reveals, pick fallback
+/// moves, apply damage, poison and guards, and log it. Returns the moves used.
+#[cfg(feature = "combat")]
+#[allow(clippy::too_many_arguments)]
+fn resolve_turn_duel(
+    rumble: &Rumble,
+    ruleset: &CombatRuleset,
+    combat: &mut RumbleCombatState,
+    combat_log: &mut CombatLog,
+    remaining_accounts: &[AccountInfo<'_>],
+    attributes: &[FighterAttributes; MAX_FIGHTERS],
+    (idx_a, idx_b): (usize, usize),
+    sudden_death_active: bool,
+) -> Result<(u8, u8)> {
+    let turn = combat.current_turn;
+    let fighter_a = rumble.fighters[idx_a];
+    let fighter_b = rumble.fighters[idx_b];
+
+    let commitment_a = read_move_commitment_from_remaining_accounts(
+        remaining_accounts,
+        rumble.id,
+        turn,
+        &fighter_a,
+    )?;
+    let commitment_b = read_move_commitment_from_remaining_accounts(
+        remaining_accounts,
+        rumble.id,
+        turn,
+        &fighter_b,
+    )?;
+    penalize_missed_reveal(rumble, combat, idx_a, commitment_a.as_ref())?;
+    penalize_missed_reveal(rumble, combat, idx_b, commitment_b.as_ref())?;
+
+    let revealed_a = commitment_a
+        .filter(|c| c.revealed)
+        .map(|c| c.revealed_move)
+        .filter(|m| is_valid_move_code(*m));
+    let revealed_b = commitment_b
+        .filter(|c| c.revealed)
+        .map(|c| c.revealed_move)
+        .filter(|m| is_valid_move_code(*m));
+    record_move_source(combat, idx_a, revealed_a.is_some());
+    record_move_source(combat, idx_b, revealed_b.is_some());
+
+    let move_a = revealed_a.unwrap_or_else(|| {
+        fallback_move_code(
+            rumble.id,
+            turn,
+            &fighter_a,
+            combat.meter[idx_a],
+            ruleset.special_meter_cost,
+        )
+    });
+    let move_b = revealed_b.unwrap_or_else(|| {
+        fallback_move_code(
+            rumble.id,
+            turn,
+            &fighter_b,
+            combat.meter[idx_b],
+            ruleset.special_meter_cost,
+        )
+    });
+
+    let (crit_a, crit_b) = duel_crits(rumble, combat, turn, (idx_a, move_a), (idx_b, move_b));
+    let (damage_to_a, damage_to_b, meter_used_a, meter_used_b) =
+        resolve_duel(
+            move_a,
+            move_b,
+            combat.meter[idx_a],
+            combat.meter[idx_b],
+            combat.last_move[idx_a],
+            combat.last_move[idx_b],
+            combat.consecutive_guards[idx_a],
+            combat.consecutive_guards[idx_b],
+            crit_a,
+            crit_b,
+            audience_boost(rumble, idx_a),
+            audience_boost(rumble, idx_b),
+            &attributes[idx_a],
+            &attributes[idx_b],
+            sudden_death_active,
+            ruleset,
+        );
+
+    combat.meter[idx_a] = combat.meter[idx_a].saturating_sub(meter_used_a);
+    combat.meter[idx_b] = combat.meter[idx_b].saturating_sub(meter_used_b);
+
+    emit!(TurnPairResolvedEvent {
+        rumble_id: rumble.id,
+        turn,
+        fighter_a,
+        fighter_b,
+        move_a,
+        move_b,
+        damage_to_a,
+        damage_to_b,
+        crit_a,
+        crit_b,
+    });
+
+    let low_hit_a = low_strike_lands(move_b, move_a, combat.consecutive_guards[idx_a]);
+    let low_hit_b = low_strike_lands(move_a, move_b, combat.consecutive_guards[idx_b]);
+    combat.last_move[idx_a] = move_a;
+    combat.last_move[idx_b] = move_b;
+    combat.consecutive_guards[idx_a] = next_consecutive_guards(
+        combat.consecutive_guards[idx_a],
+        move_a,
+        move_b,
+        damage_to_a,
+    );
+    combat.consecutive_guards[idx_b] = next_consecutive_guards(
+        combat.consecutive_guards[idx_b],
+        move_b,
+        move_a,
+        damage_to_b,
+    );
+
+    append_duel_log(
+        combat_log,
+        DuelLogEntry {
+            turn,
+            fighter_a_idx: idx_a as u8,
+            fighter_b_idx: idx_b as u8,
+            move_a,
+            move_b,
+            damage_to_a,
+            damage_to_b,
+        },
+    )?;
+
+    combat.hp[idx_a] = combat.hp[idx_a].saturating_sub(damage_to_a);
+    combat.hp[idx_b] = combat.hp[idx_b].saturating_sub(damage_to_b);
+    apply_duel_poison(rumble, combat, turn, idx_a, move_a, move_b, low_hit_a);
+    apply_duel_poison(rumble, combat, turn, idx_b, move_b, move_a, low_hit_b);
+
+    combat.total_damage_dealt[idx_a] = combat.total_damage_dealt[idx_a]
+        .checked_add(damage_to_b as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.total_damage_dealt[idx_b] = combat.total_damage_dealt[idx_b]
+        .checked_add(damage_to_a as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.total_damage_taken[idx_a] = combat.total_damage_taken[idx_a]
+        .checked_add(damage_to_a as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.total_damage_taken[idx_b] = combat.total_damage_taken[idx_b]
+        .checked_add(damage_to_b as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+
+    Ok((move_a, move_b))
+}
+
+/// Close out a turn whose duels have all been fought: meter for every
+/// survivor, eliminations in deterministic order, and the history hash.
+#[cfg(feature = "combat")]
+fn seal_resolved_turn(
+    rumble: &Rumble,
+    ruleset: &CombatRuleset,
+    combat: &mut RumbleCombatState,
+    moves: [u8; MAX_FIGHTERS],
+    hp_before: &[u16; MAX_FIGHTERS],
+) -> Result<()> {
+    let fighter_count = combat.fighter_count as usize;
+    let turn = combat.current_turn;
+    let mut eliminated_this_turn: Vec<usize> = Vec::new();
+
+    // Survivors (dueled or on a bye) gain meter; anyone left at 0 HP with no
+    // rank yet was knocked out in a duel this turn.
+    for idx in 0..fighter_count {
+        if combat.elimination_rank[idx] != 0 {
+            continue;
+        }
+        if combat.hp[idx] > 0 {
+            let next_meter = combat.meter[idx].saturating_add(ruleset.meter_per_turn);
+            combat.meter[idx] = next_meter.min(ruleset.special_meter_cost);
+        } else {
+            eliminated_this_turn.push(idx);
+        }
+    }
+
+    revive_double_knockout(rumble, combat, turn, &mut eliminated_this_turn);
+
+    for event in rank_turn_eliminations(rumble, combat, eliminated_this_turn)? {
+        emit!(event);
+    }
+
+    record_last_fighter_standing(combat, fighter_count);
+
+    combat.turn_resolved = 1;
+
+    emit!(chain_turn_history(rumble.id, combat, turn, moves, hp_before));
+
+    Ok(())
+}
+
+/// Duels of the open turn in `resolve_turn_pair` order: each stored pairing
+/// once, by the lower fighter index.
+#[cfg(feature = "combat")]
+fn turn_pair_list(combat: &RumbleCombatState) -> Vec<(usize, usize)> {
+    (0..combat.fighter_count as usize)
+        .filter_map(|idx_a| {
+            let opponent = combat.turn_pairings[idx_a];
+            (opponent != PAIRING_BYE && (opponent as usize) > idx_a)
+                .then_some((idx_a, opponent as usize))
+        })
+        .collect()
+}
+
+/// First step of a chunked turn: snapshot HP for the history hash, tick
+/// poison, and turn fighters whose opponent poison knocked out into byes, so
+/// the stored pairings list exactly the duels left to fight.
+#[cfg(feature = "combat")]
+fn start_chunked_turn(rumble: &Rumble, combat: &mut RumbleCombatState) -> Result<()> {
+    combat.turn_start_hp = combat.hp;
+    apply_poison_ticks(rumble, combat, combat.current_turn)?;
+
+    let alive = |combat: &RumbleCombatState, idx: usize| {
+        combat.hp[idx] > 0 && combat.elimination_rank[idx] == 0
+    };
+    for idx in 0..combat.fighter_count as usize {
+        let opponent = combat.turn_pairings[idx];
+        if opponent != PAIRING_BYE && (!alive(combat, idx) || !alive(combat, opponent as usize)) {
+            combat.turn_pairings[idx] = PAIRING_BYE;
+        }
+    }
+
+    combat.pairs_resolved = 0;
+    combat.turn_chunked = 1;
+    Ok(())
+}
+
+/// Resolve pair `pair_index` of the open turn (see `turn_pair_list`). Pairs
+/// must be resolved in order, each exactly once; `seal_chunked_turn` finishes
+/// the turn.
+#[cfg(feature = "combat")]
+fn resolve_chunked_pair(
+    rumble: &Rumble,
+    ruleset: &CombatRuleset,
+    combat: &mut RumbleCombatState,
+    combat_log: &mut CombatLog,
+    remaining_accounts: &[AccountInfo<'_>],
+    clock: &Clock,
+    pair_index: u8,
+) -> Result<()> {
+    require_turn_resolvable(rumble, combat, clock)?;
+    if combat.turn_chunked == 0 {
+        start_chunked_turn(rumble, combat)?;
+    }
+
+    let pairs = turn_pair_list(combat);
+    let index = pair_index as usize;
+    require!(index < pairs.len(), RumbleError::InvalidPairIndex);
+    require!(
+        combat.pairs_resolved & (1u16 << index) == 0,
+        RumbleError::PairAlreadyResolved
+    );
+    require!(
+        combat.pairs_resolved.count_ones() as usize == index,
+        RumbleError::PairOutOfOrder
+    );
+
+    // Fighters still standing at the start of the turn: everyone unranked
+    // with HP left, plus paired fighters earlier duels knocked out.
+    let standing = (0..combat.fighter_count as usize)
+        .filter(|i| {
+            combat.elimination_rank[*i] == 0
+                && (combat.hp[*i] > 0 || combat.turn_pairings[*i] != PAIRING_BYE)
+        })
+        .count();
+    let attributes = combat.attributes();
+    resolve_turn_duel(
+        rumble,
+        ruleset,
+        combat,
+        combat_log,
+        remaining_accounts,
+        &attributes,
+        pairs[index],
+        standing == 2,
+    )?;
+
+    combat.pairs_resolved |= 1u16 << index;
+    Ok(())
+}
+
+/// Finish a chunked turn once every pair has been resolved.
+#[cfg(feature = "combat")]
+fn seal_chunked_turn(
+    rumble: &Rumble,
+    ruleset: &CombatRuleset,
+    combat: &mut RumbleCombatState,
+    clock: &Clock,
+) -> Result<()> {
+    require_turn_resolvable(rumble, combat, clock)?;
+    if combat.turn_chunked == 0 {
+        // Nothing resolved yet: a turn with no duels left still ticks poison.
+        start_chunked_turn(rumble, combat)?;
+    }
+
+    let pairs = turn_pair_list(combat);
+    require!(
+        combat.pairs_resolved.count_ones() as usize == pairs.len(),
+        RumbleError::TurnPairsUnresolved
+    );
+
+    let hp_before = combat.turn_start_hp;
+    let mut moves = [NO_LAST_MOVE; MAX_FIGHTERS];
+    if pairs.is_empty() {
+        let alive_indices: Vec<usize> = (0..combat.fighter_count as usize)
+            .filter(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
+            .collect();
+        if alive_indices.len() <= 1 {
+            combat.turn_resolved = 1;
+            if let Some(idx) = alive_indices.first() {
+                combat.winner_index = *idx as u8;
+            }
+            emit!(chain_turn_history(rumble.id, combat, combat.current_turn, moves, &hp_before));
+            return Ok(());
+        }
+    }
+    for (idx_a, idx_b) in pairs {
+        moves[idx_a] = combat.last_move[idx_a];
+        moves[idx_b] = combat.last_move[idx_b];
+    }
+
+    seal_resolved_turn(rumble, ruleset, combat, moves, &hp_before)
+}
+
+/// Open the next turn's commit/reveal windows and store its pairings and
+/// `crit_seed` (see `turn_crit_seed`).
+#[cfg(feature = "combat")]
+fn open_next_turn(
+    config: &RumbleConfig,
+    rumble: &Rumble,
+    combat: &mut RumbleCombatState,
+    clock: &Clock,
+    crit_seed: u64,
+) -> Result<()> {
+    combat.current_turn = combat
+        .current_turn
+        .checked_add(1)
+        .ok_or(RumbleError::MathOverflow)?;
+    let attributes = combat.attributes();
+    combat.turn_pairings = compute_turn_pairings(rumble, combat, &attributes, combat.current_turn);
+    combat.turn_open_slot = clock.slot;
+    combat.commit_close_slot = clock
+        .slot
+        .checked_add(config.commit_window_slots as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.reveal_close_slot = combat
+        .commit_close_slot
+        .checked_add(config.reveal_window_slots as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.turn_resolved = 0;
+    combat.pairs_resolved = 0;
+    combat.turn_chunked = 0;
+    combat.crit_seed = crit_seed;
+
+    emit!(TurnOpenedEvent {
+        rumble_id: rumble.id,
+        turn: combat.current_turn,
+        turn_open_slot: combat.turn_open_slot,
+        commit_close_slot: combat.commit_close_slot,
+        reveal_close_slot: combat.reveal_close_slot,
+        crit_seed: combat.crit_seed,
+    });
+
+    Ok(())
+}
+
+/// Whether the open turn has sat unresolved for `STALE_TURN_GRACE_SLOTS` past
+/// its reveal window.
+#[cfg(feature = "combat")]
+fn turn_is_stale(combat: &RumbleCombatState, slot: u64) -> bool {
+    combat.current_turn > 0
+        && combat.turn_resolved == 0
+        && slot >= combat.reveal_close_slot.saturating_add(STALE_TURN_GRACE_SLOTS)
+}
+
+/// Whether `timeout_slots` have passed since combat started.
+#[cfg(feature = "combat")]
+fn combat_timed_out(combat: &RumbleCombatState, timeout_slots: u64, slot: u64) -> bool {
+    slot > combat.combat_start_slot.saturating_add(timeout_slots)
+}
+
+/// Give a newly eliminated fighter the next elimination rank (1 = first out).
+#[cfg(feature = "combat")]
+fn assign_elimination_rank(combat: &mut RumbleCombatState, idx: usize) -> Result<()> {
+    if combat.elimination_rank[idx] > 0 {
+        return Ok(());
+    }
+    let eliminated_so_far = combat
+        .fighter_count
+        .checked_sub(combat.remaining_fighters)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.elimination_rank[idx] = eliminated_so_far
+        .checked_add(1)
+        .ok_or(RumbleError::MathOverflow)?;
+    combat.remaining_fighters = combat
+        .remaining_fighters
+        .checked_sub(1)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok(())
+}
+
+/// Rank a turn's knockouts in deterministic order, damage dealt descending
+/// and then fighter index, returning a `FighterEliminatedEvent` for each in
+/// the order their ranks were assigned.
+#[cfg(feature = "combat")]
+fn rank_turn_eliminations(
+    rumble: &Rumble,
+    combat: &mut RumbleCombatState,
+    mut eliminated: Vec<usize>,
+) -> Result<Vec<FighterEliminatedEvent>> {
+    eliminated.sort_by(|a, b| {
+        combat.total_damage_dealt[*b]
+            .cmp(&combat.total_damage_dealt[*a])
+            .then_with(|| a.cmp(b))
+    });
+    let mut events = Vec::with_capacity(eliminated.len());
+    for idx in eliminated {
+        assign_elimination_rank(combat, idx)?;
+        events.push(fighter_eliminated_event(rumble, combat, idx));
+    }
+    Ok(events)
+}
+
+#[cfg(feature = "combat")]
+fn fighter_eliminated_event(
+    rumble: &Rumble,
+    combat: &RumbleCombatState,
+    idx: usize,
+) -> FighterEliminatedEvent {
+    FighterEliminatedEvent {
+        rumble_id: rumble.id,
+        turn: combat.current_turn,
+        fighter: rumble.fighters[idx],
+        elimination_rank: combat.elimination_rank[idx],
+        damage_dealt_total: combat.total_damage_dealt[idx],
+    }
+}
+
+/// Poison damage at the top of turn resolution. Fighters it knocks out are
+/// eliminated before any duel is fought.
+#[cfg(feature = "combat")]
+fn apply_poison_ticks(rumble: &Rumble, combat: &mut RumbleCombatState, turn: u32) -> Result<()> {
+    let fighter_count = combat.fighter_count as usize;
+    let mut knocked_out: Vec<usize> = Vec::new();
+    for idx in 0..fighter_count {
+        if combat.poison_stacks[idx] < POISON_STACK_THRESHOLD
+            || combat.hp[idx] == 0
+            || combat.elimination_rank[idx] != 0
+        {
+            continue;
+        }
+        combat.hp[idx] = combat.hp[idx].saturating_sub(POISON_TICK_DAMAGE);
+        combat.total_damage_taken[idx] = combat.total_damage_taken[idx]
+            .checked_add(POISON_TICK_DAMAGE as u64)
+            .ok_or(RumbleError::MathOverflow)?;
+        emit!(PoisonTickEvent {
+            rumble_id: rumble.id,
+            turn,
+            fighter: rumble.fighters[idx],
+            damage: POISON_TICK_DAMAGE,
+            hp: combat.hp[idx],
+        });
+        if combat.hp[idx] == 0 {
+            knocked_out.push(idx);
+        }
+    }
+    revive_double_knockout(rumble, combat, turn, &mut knocked_out);
+    for idx in knocked_out {
+        assign_elimination_rank(combat, idx)?;
+        emit!(fighter_eliminated_event(rumble, combat, idx));
+    }
+    record_last_fighter_standing(combat, fighter_count);
+    Ok(())
+}
+
+/// Update a fighter's poison after its duel: a landed low strike adds a stack,
+/// and a poisoned fighter that low-guards with no attack incoming is cured.
+#[cfg(feature = "combat")]
+fn apply_duel_poison(
+    rumble: &Rumble,
+    combat: &mut RumbleCombatState,
+    turn: u32,
+    idx: usize,
+    own_move: u8,
+    opponent_move: u8,
+    hit_by_low_strike: bool,
+) {
+    let poisoned = combat.poison_stacks[idx] >= POISON_STACK_THRESHOLD;
+    if hit_by_low_strike {
+        combat.poison_stacks[idx] = combat.poison_stacks[idx].saturating_add(1);
+        if !poisoned && combat.poison_stacks[idx] >= POISON_STACK_THRESHOLD {
+            emit!(PoisonedEvent {
+                rumble_id: rumble.id,
+                turn,
+                fighter: rumble.fighters[idx],
+            });
+        }
+    } else if poisoned
+        && own_move == MOVE_GUARD_LOW
+        && !is_attack(opponent_move)
+        && combat.hp[idx] > 0
+    {
+        combat.poison_stacks[idx] = 0;
+        // `begin_combat` records every fighter's real starting HP; only
+        // combat states from before that fall back to `START_HP`.
+        combat.hp[idx] = combat.hp[idx]
+            .saturating_add(POISON_CURE_HEAL)
+            .min(starting_hp(combat, idx, START_HP));
+    }
+}
+
+/// Track consecutive fallback-move turns: a real reveal resets the count.
+#[cfg(feature = "combat")]
+fn record_move_source(combat: &mut RumbleCombatState, idx: usize, revealed: bool) {
+    combat.missed_turns[idx] = if revealed {
+        0
+    } else {
+        combat.missed_turns[idx].saturating_add(1)
+    };
+}
+
+/// Eliminate a fighter that has fallen back to a default move for
+/// `INACTIVITY_TIMEOUT_TURNS` turns in a row.
+#[cfg(feature = "combat")]
+fn eliminate_inactive_fighter(
+    combat: &mut RumbleCombatState,
+    idx: usize,
+    fighter_count: usize,
+) -> Result<()> {
+    require!(idx < fighter_count, RumbleError::InvalidFighterIndex);
+    require!(
+        combat.hp[idx] > 0 && combat.elimination_rank[idx] == 0,
+        RumbleError::FighterEliminated
+    );
+    require!(
+        combat.missed_turns[idx] >= INACTIVITY_TIMEOUT_TURNS,
+        RumbleError::FighterStillActive
+    );
+
+    combat.hp[idx] = 0;
+    assign_elimination_rank(combat, idx)?;
+    record_last_fighter_standing(combat, fighter_count);
+    Ok(())
+}
+
+/// Next link of the combat history chain:
+/// `sha256(prev || turn || moves || damages || eliminations)`, integers
+/// little-endian. `moves` holds each fighter's duel move (`NO_LAST_MOVE` if it
+/// didn't duel), `damages` the HP each lost this turn and `eliminations` the
+/// elimination ranks after the turn, each over the rumble's fighter slots.
+#[cfg(feature = "combat")]
+fn turn_history_hash(
+    prev: &[u8; 32],
+    turn: u32,
+    moves: &[u8],
+    damages: &[u16],
+    eliminations: &[u8],
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(prev);
+    hasher.update(turn.to_le_bytes());
+    hasher.update(moves);
+    for damage in damages {
+        hasher.update(damage.to_le_bytes());
+    }
+    hasher.update(eliminations);
+    hasher.finalize().into()
+}
+
+/// Fold a resolved turn into `combat.history_hash` and build the
+/// `TurnResolvedEvent` carrying the leaf data needed to recompute it.
+#[cfg(feature = "combat")]
+fn chain_turn_history(
+    rumble_id: u64,
+    combat: &mut RumbleCombatState,
+    turn: u32,
+    moves: [u8; MAX_FIGHTERS],
+    hp_before: &[u16; MAX_FIGHTERS],
+) -> TurnResolvedEvent {
+    let mut damages = [0u16; MAX_FIGHTERS];
+    for (damage, (before, after)) in damages.iter_mut().zip(hp_before.iter().zip(combat.hp)) {
+        *damage = before.saturating_sub(after);
+    }
+    let slots = combat.slots();
+    let eliminations = combat.elimination_rank;
+    combat.history_hash = turn_history_hash(
+        &combat.history_hash,
+        turn,
+        &moves[..slots],
+        &damages[..slots],
+        &eliminations[..slots],
+    );
+    TurnResolvedEvent {
+        rumble_id,
+        turn,
+        remaining_fighters: combat.remaining_fighters,
+        moves: moves[..slots].to_vec(),
+        damages: damages[..slots].to_vec(),
+        eliminations: eliminations[..slots].to_vec(),
+        history_hash: combat.history_hash,
+    }
+}
+
+/// Draw rule: when a turn knocks out every fighter still standing (typically
+/// the last two trading final blows), nobody is eliminated. They are revived
+/// at `DOUBLE_KO_REVIVE_HP` and fight on in sudden death, so the winner is
+/// settled in the ring rather than by a tiebreak over fighters all at 0 HP.
+/// Clears `knocked_out` when it applies.
+#[cfg(feature = "combat")]
+fn revive_double_knockout(
+    rumble: &Rumble,
+    combat: &mut RumbleCombatState,
+    turn: u32,
+    knocked_out: &mut Vec<usize>,
+) {
+    if knocked_out.is_empty() || knocked_out.len() < combat.remaining_fighters as usize {
+        return;
+    }
+    for idx in knocked_out.drain(..) {
+        combat.hp[idx] = DOUBLE_KO_REVIVE_HP;
+        emit!(DoubleKnockoutRevivedEvent {
+            rumble_id: rumble.id,
+            turn,
+            fighter: rumble.fighters[idx],
+            hp: DOUBLE_KO_REVIVE_HP,
+        });
+    }
+}
+
+/// Record the winner once a single fighter is left alive.
+#[cfg(feature = "combat")]
+fn record_last_fighter_standing(combat: &mut RumbleCombatState, fighter_count: usize) {
+    if combat.remaining_fighters != 1 {
+        return;
+    }
+    if let Some(idx) = (0..fighter_count)
+        .find(|i| combat.hp[*i] > 0 && combat.elimination_rank[*i] == 0)
+    {
+        combat.winner_index = idx as u8;
+    }
+}
+
+/// Extra starting HP from a fighter's registry level.
+#[cfg(feature = "combat")]
+fn level_hp_bonus(level: u8) -> u16 {
+    (level as u16).min(MAX_LEVEL_HP_BONUS)
+}
+
+/// Extra starting HP from a fighter's registry record: +1 per win plus its
+/// level bonus, at most `MAX_REGISTRY_HP_BONUS` so veterans can't snowball.
+#[cfg(feature = "combat")]
+fn registry_hp_bonus(attrs: &FighterAttributes) -> u16 {
+    let wins_bonus = attrs.wins.min(MAX_REGISTRY_HP_BONUS as u64) as u16;
+    wins_bonus
+        .saturating_add(level_hp_bonus(attrs.level))
+        .min(MAX_REGISTRY_HP_BONUS)
+}
+
+/// Meter a fighter on a win streak starts combat with. Always short of
+/// `special_meter_cost`, so nobody opens with a special.
+#[cfg(feature = "combat")]
+fn win_streak_meter(attrs: &FighterAttributes, special_meter_cost: u8) -> u8 {
+    let streak = attrs.win_streak.min(u8::MAX as u64) as u8;
+    streak
+        .saturating_mul(WIN_STREAK_METER_PER_WIN)
+        .min(MAX_WIN_STREAK_METER)
+        .min(special_meter_cost.saturating_sub(1))
+}
+
+/// Combat numbers for a rumble: its `CombatRuleset` if it was created with
+/// one, else `DEFAULT_COMBAT_RULESET`.
+#[cfg(feature = "combat")]
+fn rumble_ruleset(
+    rumble: &Rumble,
+    ruleset: Option<&Account<CombatRuleset>>,
+) -> Result<CombatRuleset> {
+    if rumble.ruleset == Pubkey::default() {
+        return Ok(DEFAULT_COMBAT_RULESET);
+    }
+    ruleset
+        .map(|ruleset| CombatRuleset::clone(ruleset))
+        .ok_or_else(|| error!(RumbleError::RulesetMismatch))
+}
+
+/// Starting HP must be positive, and a free special would fire every turn.
+#[cfg(feature = "combat")]
+fn validate_ruleset_params(params: &CombatRulesetParams) -> Result<()> {
+    require!(
+        params.start_hp > 0 && params.special_meter_cost > 0,
+        RumbleError::InvalidRuleset
+    );
+    Ok(())
+}
+
+#[cfg(feature = "combat")]
+fn write_ruleset_params(ruleset: &mut CombatRuleset, params: &CombatRulesetParams) {
+    ruleset.strike_damage_high = params.strike_damage_high;
+    ruleset.strike_damage_mid = params.strike_damage_mid;
+    ruleset.strike_damage_low = params.strike_damage_low;
+    ruleset.catch_damage = params.catch_damage;
+    ruleset.counter_damage = params.counter_damage;
+    ruleset.special_damage = params.special_damage;
+    ruleset.meter_per_turn = params.meter_per_turn;
+    ruleset.special_meter_cost = params.special_meter_cost;
+    ruleset.start_hp = params.start_hp;
+}
+
+/// HP a fighter starts combat with: its override if one was set, else the
+/// ruleset's `default_hp`.
+#[cfg(feature = "combat")]
+fn starting_hp(combat: &RumbleCombatState, idx: usize, default_hp: u16) -> u16 {
+    match combat.hp_overrides[idx] {
+        0 => default_hp,
+        hp => hp,
+    }
+}
+
+/// Every fighter in the rumble needs an override within the HP bounds; slots
+/// past `fighter_count` must be 0.
+#[cfg(feature = "combat")]
+fn validate_hp_overrides(hp_overrides: &[u16; MAX_FIGHTERS], fighter_count: usize) -> Result<()> {
+    for (idx, hp) in hp_overrides.iter().enumerate() {
+        let valid = if idx < fighter_count {
+            (MIN_HP_OVERRIDE..=MAX_HP_OVERRIDE).contains(hp)
+        } else {
+            *hp == 0
+        };
+        require!(valid, RumbleError::InvalidHpOverride);
+    }
+    Ok(())
+}
+
+/// Shared Betting -> Combat transition for `start_combat` and `force_start_combat`.
+#[cfg(feature = "combat")]
+fn begin_combat(
+    rumble: &mut Rumble,
+    ruleset: &CombatRuleset,
+    combat: &mut RumbleCombatState,
+    combat_bump: u8,
+    clock: &Clock,
+    remaining_accounts: &[AccountInfo<'_>],
+) -> Result<()> {
+    require!(pool_meets_minimum(rumble), RumbleError::PoolBelowMinimum);
+    require!(bettors_meet_minimum(rumble), RumbleError::NotEnoughBettors);
+
+    rumble.state = RumbleState::Combat;
+    rumble.combat_started_at = clock.unix_timestamp;
+
+    if combat.rumble_id != 0 {
+        require!(combat.rumble_id == rumble.id, RumbleError::InvalidRumble);
+    }
+    combat.rumble_id = rumble.id;
+    combat.fighter_count = rumble.fighter_count;
+    combat.fighter_slots = rumble.slots() as u8;
+    combat.current_turn = 0;
+    combat.turn_open_slot = clock.slot;
+    combat.commit_close_slot = clock.slot;
+    combat.reveal_close_slot = clock.slot;
+    combat.turn_resolved = 1;
+    combat.remaining_fighters = rumble.fighter_count;
+    combat.winner_index = u8::MAX;
+    combat.hp = [0u16; MAX_FIGHTERS];
+    combat.meter = [0u8; MAX_FIGHTERS];
+    combat.elimination_rank = [0u8; MAX_FIGHTERS];
+    combat.total_damage_dealt = [0u64; MAX_FIGHTERS];
+    combat.total_damage_taken = [0u64; MAX_FIGHTERS];
+    combat.vrf_seed = [0u8; 32];
+    combat.turn_pairings = [PAIRING_BYE; MAX_FIGHTERS];
+    combat.missed_reveals = [0u8; MAX_FIGHTERS];
+    combat.missed_turns = [0u8; MAX_FIGHTERS];
+    combat.last_move = [NO_LAST_MOVE; MAX_FIGHTERS];
+    combat.consecutive_guards = [0u8; MAX_FIGHTERS];
+    combat.poison_stacks = [0u8; MAX_FIGHTERS];
+    combat.history_hash = [0u8; 32];
+    combat.keeper_paid = 0;
+    combat.crit_seed = 0;
+    combat.combat_start_slot = clock.slot;
+    combat.pairs_resolved = 0;
+    combat.turn_chunked = 0;
+    combat.turn_start_hp = [0u16; MAX_FIGHTERS];
+    let attributes = snapshot_fighter_attributes(remaining_accounts, rumble)?;
+    combat.fighter_attributes = attributes.map(PackedFighterAttributes::from);
+    for (i, attrs) in attributes.iter().enumerate().take(rumble.fighter_count as usize) {
+        let hp = starting_hp(combat, i, ruleset.start_hp).saturating_add(registry_hp_bonus(attrs));
+        // Record the real starting HP so later heals cap against it.
+        combat.hp_overrides[i] = hp;
+        combat.hp[i] = hp;
+        combat.meter[i] = win_streak_meter(attrs, ruleset.special_meter_cost);
+    }
+    combat.bump = combat_bump;
+
+    msg!(
+        "Rumble {} combat started at {}",
+        rumble.id,
+        clock.unix_timestamp
+    );
+
+    emit!(CombatStartedEvent {
+        rumble_id: rumble.id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Whether the rumble's pool cleared its minimum (0 = no minimum).
+fn pool_meets_minimum(rumble: &Rumble) -> bool {
+    rumble.min_pool_lamports == 0 || rumble.total_deployed >= rumble.min_pool_lamports
+}
+
+/// Whether enough distinct bettors joined the rumble (0 = no minimum).
+fn bettors_meet_minimum(rumble: &Rumble) -> bool {
+    rumble.unique_bettor_count >= rumble.min_bettors
+}
+
+/// Betting -> Cancelled for a rumble that closed betting short of `min_bettors`.
+fn cancel_underfilled(rumble: &mut Rumble, clock: &Clock) -> Result<()> {
+    require!(
+        rumble.state == RumbleState::Betting,
+        RumbleError::InvalidStateTransition
+    );
+    require_betting_ended(rumble, clock)?;
+    require!(!bettors_meet_minimum(rumble), RumbleError::BettorMinimumMet);
+
+    rumble.state = RumbleState::Cancelled;
+    rumble.cancelled_at = clock.unix_timestamp;
+    Ok(())
+}
+
+/// Whether nobody can win the prize pool, so `refund_prize_pool` may return
+/// it: the rumble was cancelled, or its result refunds every bettor.
+fn prize_pool_refundable(rumble: &Rumble) -> Result<bool> {
+    match rumble.state {
+        RumbleState::Cancelled => Ok(true),
+        RumbleState::Payout | RumbleState::Complete => refunds_all_bettors(rumble),
+        _ => Ok(false),
+    }
+}
+
+/// Whether every bettor gets their stake back instead of a payout: the rumble
+/// was closed as underfunded, or nobody backed a paying place.
+fn refunds_all_bettors(rumble: &Rumble) -> Result<bool> {
+    if rumble.underfunded {
+        return Ok(true);
+    }
+    Ok(winner_pool_lamports(rumble)? == 0)
+}
+
+/// Lamports owed to bettors on paying places: every such stake comes back
+/// along with all of the place allocations.
+fn winner_payout_total(rumble: &Rumble) -> Result<u64> {
+    let (_, allocations) = calculate_place_allocations(rumble)?;
+    allocations
+        .iter()
+        .try_fold(winner_pool_lamports(rumble)?, |acc, allocation| {
+            acc.checked_add(*allocation)
+        })
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// Book a paid winning claim on the rumble. The first one snapshots what
+/// winners are owed in total.
+fn record_winner_claim(rumble: &mut Rumble, returned_stake: u64, paid: u64) -> Result<()> {
+    if rumble.winner_pool_snapshot == 0 {
+        rumble.winner_pool_snapshot = winner_payout_total(rumble)?;
+    }
+    rumble.winner_stake_claimed = rumble
+        .winner_stake_claimed
+        .checked_add(returned_stake)
+        .ok_or(RumbleError::MathOverflow)?;
+    rumble.total_claimed_lamports = rumble
+        .total_claimed_lamports
+        .checked_add(paid)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok(())
+}
+
+/// Winner payout not yet claimed. Each claim rounds down, so a few lamports
+/// of dust remain here after everyone has claimed; whether claims are still
+/// open is decided by stake instead (`require_winner_sweep_allowed`).
+fn outstanding_winner_liability(rumble: &Rumble) -> Result<u64> {
+    let owed = if rumble.winner_pool_snapshot > 0 {
+        rumble.winner_pool_snapshot
+    } else {
+        winner_payout_total(rumble)?
+    };
+    Ok(owed.saturating_sub(rumble.total_claimed_lamports))
+}
+
+/// Least a settled rumble's vault should hold: its rent reserve plus, for a
+/// result with winners, the unclaimed winner payout (stakes less the treasury
+/// cut, less claims), the stake-bonus hold and the insurance pool not yet
+/// paid out. Refund claims aren't tallied on
+/// the rumble, and a forced sweep forfeits unclaimed winnings, so those
+/// vaults are only held to the rent reserve.
+fn expected_vault_minimum(rumble: &Rumble) -> Result<u64> {
+    if rumble.swept_lamports > 0 || refunds_all_bettors(rumble)? {
+        return Ok(rumble.vault_rent_reserve);
+    }
+    rumble
+        .vault_rent_reserve
+        .checked_add(outstanding_winner_liability(rumble)?)
+        .and_then(|v| v.checked_add(rumble.stake_bonus_reserve))
+        .and_then(|v| v.checked_add(rumble.insurance_pool.saturating_sub(rumble.insurance_paid)))
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// `create_rumble`'s claim window must fall within 10 minutes and 30 days.
+fn validate_claim_window(seconds: i64) -> Result<()> {
+    require!(
+        (MIN_CLAIM_WINDOW_SECONDS..=MAX_CLAIM_WINDOW_SECONDS).contains(&seconds),
+        RumbleError::InvalidClaimWindow
+    );
+    Ok(())
+}
+
+/// The rumble's payout claim window; migrated rumbles store 0 and get the
+/// 24-hour default.
+fn claim_window_seconds(rumble: &Rumble) -> i64 {
+    if rumble.claim_window_seconds > 0 {
+        rumble.claim_window_seconds
+    } else {
+        PAYOUT_CLAIM_WINDOW_SECONDS
+    }
+}
+
+/// Fails until the rumble's claim window has run from `completed_at`.
+fn require_claim_window_elapsed(rumble: &Rumble, now: i64) -> Result<()> {
+    let claim_window_end = claims_open_at(rumble)?
+        .checked_add(claim_window_seconds(rumble))
+        .ok_or(RumbleError::MathOverflow)?;
+    require!(now >= claim_window_end, RumbleError::ClaimWindowActive);
+    Ok(())
+}
+
+/// `sweep_treasury` on a rumble with winners: fails while any winning stake
+/// is unclaimed, unless `force` is set and the payout claim window has
+/// passed. Returns the outstanding winner liability.
+fn require_winner_sweep_allowed(rumble: &Rumble, force: bool, now: i64) -> Result<u64> {
+    if rumble.winner_stake_claimed < winner_pool_lamports(rumble)? {
+        require!(force, RumbleError::UnclaimedPayoutsRemain);
+        require_claim_window_elapsed(rumble, now)?;
+    }
+    outstanding_winner_liability(rumble)
+}
+
+/// Refunds stay claimable for `REFUND_CLAIM_WINDOW_SECONDS` after `started_at`.
+fn require_refund_window_elapsed(started_at: i64) -> Result<()> {
+    let refund_window_end = started_at
+        .checked_add(REFUND_CLAIM_WINDOW_SECONDS)
+        .ok_or(RumbleError::MathOverflow)?;
+    require!(
+        Clock::get()?.unix_timestamp >= refund_window_end,
+        RumbleError::RefundWindowActive
+    );
+    Ok(())
+}
+
+/// Take a refunded bettor's insurance premiums back out of the rumble's
+/// insurance pool, which then holds only premiums for live cover.
+fn release_refunded_premium(rumble: &mut Rumble, bettor: &ParsedBettorAccount) -> Result<()> {
+    rumble.insurance_pool = rumble
+        .insurance_pool
+        .checked_sub(bettor.insurance_premium_paid)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok(())
+}
+
+/// Lamports a bettor can claim once the result is set. When nobody backed a
+/// paying place there is no one to pay, so every stake is refunded in full.
+fn calculate_claim_lamports(rumble: &Rumble, bettor: &ParsedBettorAccount) -> Result<u64> {
+    if refunds_all_bettors(rumble)? {
+        return bettor_refund_lamports(bettor);
+    }
+    calculate_bettor_payout(rumble, &bettor_deployments(bettor))
+}
+
+/// Lamports an unclaimed bettor is still owed. Errors while the rumble has
+/// no result yet, since eligibility is unknown until then.
+fn bettor_claim_entitlement(rumble: &Rumble, bettor: &ParsedBettorAccount) -> Result<u64> {
+    match rumble.state {
+        RumbleState::Cancelled => bettor_refund_lamports(bettor),
+        RumbleState::Payout | RumbleState::Complete => calculate_claim_lamports(rumble, bettor),
+        _ => err!(RumbleError::PayoutNotReady),
+    }
+}
+
+/// Keeper budget still in the vault: what's left above the rent reserve, the
+/// betting, prize, exacta and insurance pools, capped at `keeper_budget`. Only
+/// meaningful before any claim is paid, i.e. up to result time.
+fn unspent_keeper_budget(rumble: &Rumble, vault_lamports: u64) -> Result<u64> {
+    let committed = rumble
+        .total_deployed
+        .checked_add(rumble.prize_pool)
+        .and_then(|v| v.checked_add(rumble.exacta_pool))
+        .and_then(|v| v.checked_add(rumble.insurance_pool))
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok(vault_available_lamports(vault_lamports, rumble)?
+        .saturating_sub(committed)
+        .min(rumble.keeper_budget))
+}
+
+/// Bounty for one keeper call: `keeper_fee`, capped by what this rumble's
+/// keeper budget has left.
+#[cfg(feature = "combat")]
+fn keeper_bounty(rumble: &Rumble, combat: &RumbleCombatState, keeper_fee: u64) -> u64 {
+    rumble
+        .keeper_budget
+        .saturating_sub(combat.keeper_paid)
+        .min(keeper_fee)
+}
+
+/// Pay the keeper's bounty from the vault. Also capped by the budget actually
+/// in the vault, so it can never touch bettor funds. No vault, no bounty.
+#[cfg(feature = "combat")]
+fn pay_keeper_bounty<'info>(
+    rumble: &Rumble,
+    combat: &mut RumbleCombatState,
+    keeper_fee: u64,
+    vault: Option<(&SystemAccount<'info>, u8)>,
+    keeper: AccountInfo<'info>,
+    system_program_info: AccountInfo<'info>,
+) -> Result<()> {
+    let Some((vault, vault_bump)) = vault else {
+        return Ok(());
+    };
+    let bounty = keeper_bounty(rumble, combat, keeper_fee)
+        .min(unspent_keeper_budget(rumble, vault.lamports())?);
+    if bounty == 0 {
+        return Ok(());
+    }
+    combat.keeper_paid = combat
+        .keeper_paid
+        .checked_add(bounty)
+        .ok_or(RumbleError::MathOverflow)?;
+    let keeper_key = keeper.key();
+    transfer_from_vault(
+        vault.to_account_info(),
+        keeper,
+        system_program_info,
+        rumble.id,
+        vault_bump,
+        bounty,
+    )?;
+    emit!(KeeperPaidEvent {
+        rumble_id: rumble.id,
+        keeper: keeper_key,
+        amount: bounty,
+    });
+    Ok(())
+}
+
+/// Decode a pre-zero-copy combat state for `rumble_id` into the current layout.
+#[cfg(feature = "combat")]
+fn migrated_combat_state(data: &[u8], rumble_id: u64) -> Result<RumbleCombatState> {
+    require!(
+        data.len() < COMBAT_STATE_LEN,
+        RumbleError::CombatStateAlreadyMigrated
+    );
+    require!(
+        data.get(..8) == Some(RumbleCombatState::DISCRIMINATOR),
+        RumbleError::InvalidRumble
+    );
+    let legacy = LegacyRumbleCombatState::deserialize(&mut &data[8..])
+        .map_err(|_| error!(RumbleError::InvalidRumble))?;
+    require!(legacy.rumble_id == rumble_id, RumbleError::InvalidRumble);
+    Ok(legacy.into())
+}
+
+/// Add `fighters` after the rumble's current ones. Only before anything is
+/// staked, so no bettor picked from a shorter field, and only up to the
+/// rumble's fighter slots.
+fn append_fighters(rumble: &mut Rumble, fighters: &[Pubkey]) -> Result<()> {
+    require!(
+        rumble.total_deployed == 0 && rumble.exacta_pool == 0,
+        RumbleError::FightersLocked
+    );
+    let count = rumble.fighter_count as usize;
+    let new_count = count + fighters.len();
+    require!(
+        !fighters.is_empty() && new_count <= rumble.slots(),
+        RumbleError::InvalidFighterCount
+    );
+    let mut seen: std::collections::BTreeSet<&Pubkey> =
+        rumble.fighters[..count].iter().collect();
+    for f in fighters.iter() {
+        require!(seen.insert(f), RumbleError::DuplicateFighter);
+    }
+    rumble.fighters[count..new_count].copy_from_slice(fighters);
+    rumble.fighter_count = new_count as u8;
+    Ok(())
+}
+
+/// Fighter slots `create_rumble` lays a rumble out with: `MAX_FIGHTERS` on
+/// the v2 layout, `MAX_FIGHTERS_V1` otherwise.
+fn requested_fighter_slots(fighter_slots: Option<u8>) -> Result<usize> {
+    match fighter_slots.map(usize::from) {
+        None | Some(MAX_FIGHTERS_V1) => Ok(MAX_FIGHTERS_V1),
+        Some(MAX_FIGHTERS) => Ok(MAX_FIGHTERS),
+        Some(_) => err!(RumbleError::InvalidFighterSlots),
+    }
+}
+
+/// Account size for `create_rumble`'s `fighter_slots`; invalid values are
+/// rejected by the handler.
+fn rumble_account_len(fighter_slots: Option<u8>) -> usize {
+    match requested_fighter_slots(fighter_slots) {
+        Ok(MAX_FIGHTERS) => RUMBLE_V2_LEN,
+        _ => RUMBLE_LEN,
+    }
+}
+
+/// Check a `migrate_rumble` target before it grows: an older-layout `Rumble`
+/// for `rumble_id` that is not mid-combat.
+fn require_migratable_rumble(data: &[u8], rumble_id: u64) -> Result<()> {
+    const STATE_OFFSET: usize = 8 + 8;
+
+    require!(
+        (RUMBLE_V1_LEN..RUMBLE_LEN).contains(&data.len()),
+        RumbleError::RumbleAlreadyMigrated
+    );
+    require!(
+        &data[..8] == Rumble::DISCRIMINATOR,
+        RumbleError::InvalidRumble
+    );
+    let stored_id = u64::from_le_bytes(
+        data[8..16]
+            .try_into()
+            .map_err(|_| error!(RumbleError::InvalidRumble))?,
+    );
+    require!(stored_id == rumble_id, RumbleError::InvalidRumble);
+    let state = RumbleState::try_from_slice(&data[STATE_OFFSET..STATE_OFFSET + 1])
+        .map_err(|_| error!(RumbleError::InvalidRumble))?;
+    require!(state != RumbleState::Combat, RumbleError::RumbleNotMigratable);
+    Ok(())
+}
+
+/// Record the vault rent reserve on a rumble `migrate_rumble` has just grown
+/// to `RUMBLE_LEN`. `fund_vault_rent_reserve` only sets one up on a rumble's
+/// first deposit, which migrated rumbles are long past.
+fn set_migrated_vault_rent_reserve(data: &mut [u8], reserve: u64) -> Result<Rumble> {
+    let mut rumble = Rumble::try_deserialize(&mut &data[..])?;
+    rumble.vault_rent_reserve = reserve;
+    rumble.try_serialize(&mut &mut data[..])?;
+    Ok(rumble)
+}
+
+/// The first deposit into a rumble's vault (bet or prize pool) tops it up to
+/// its rent-exempt minimum. That reserve is never paid out, so claims can't
+/// strand the vault below the rent floor.
+fn fund_vault_rent_reserve<'info>(
+    rumble: &mut Rumble,
+    vault: &SystemAccount<'info>,
+    payer: AccountInfo<'info>,
+    system_program_info: AccountInfo<'info>,
+) -> Result<()> {
+    if rumble.vault_rent_reserve != 0 || rumble.total_deployed != 0 {
+        return Ok(());
+    }
+    let reserve = Rent::get()?.minimum_balance(0);
+    let top_up = reserve.saturating_sub(vault.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program_info,
+                system_program::Transfer {
+                    from: payer,
+                    to: vault.to_account_info(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    rumble.vault_rent_reserve = reserve;
+    Ok(())
+}
+
+fn whitelist_contains(whitelist: &WhitelistAccount, key: &Pubkey) -> bool {
+    whitelist.entries[..whitelist.count as usize].contains(key)
+}
+
+/// Append `keys` not already listed; fails without changes if they don't fit.
+fn add_whitelist_entries(whitelist: &mut WhitelistAccount, keys: &[Pubkey]) -> Result<()> {
+    let mut count = whitelist.count as usize;
+    let mut entries = whitelist.entries;
+    for key in keys {
+        if entries[..count].contains(key) {
+            continue;
+        }
+        require!(count < MAX_WHITELIST_ENTRIES, RumbleError::WhitelistFull);
+        entries[count] = *key;
+        count += 1;
+    }
+    whitelist.entries = entries;
+    whitelist.count = count as u8;
+    Ok(())
+}
+
+/// Drop `keys` from the list, moving the last entry into each freed slot.
+fn remove_whitelist_entries(whitelist: &mut WhitelistAccount, keys: &[Pubkey]) {
+    let mut count = whitelist.count as usize;
+    for key in keys {
+        if let Some(pos) = whitelist.entries[..count].iter().position(|e| e == key) {
+            count -= 1;
+            whitelist.entries[pos] = whitelist.entries[count];
+            whitelist.entries[count] = Pubkey::default();
+        }
+    }
+    whitelist.count = count as u8;
+}
+
+/// Add a rumble to the wallet's index unless it is already listed, replacing
+/// the oldest entry once the ring is full.
+fn record_bettor_index(index: &mut BettorIndex, rumble_id: u64) {
+    let count = index.count as usize;
+    if index.rumble_ids[..count].contains(&rumble_id) {
+        return;
+    }
+    let head = index.head as usize % BETTOR_INDEX_LEN;
+    index.rumble_ids[head] = rumble_id;
+    index.claimed[head] = false;
+    index.head = ((head + 1) % BETTOR_INDEX_LEN) as u8;
+    index.count = (count + 1).min(BETTOR_INDEX_LEN) as u8;
+}
+
+/// Flag a listed rumble as claimed; rumbles that fell out of the ring are ignored.
+fn mark_bettor_index_claimed(index: &mut BettorIndex, rumble_id: u64) {
+    let count = index.count as usize;
+    let listed = &index.rumble_ids[..count];
+    if let Some(pos) = listed.iter().position(|id| *id == rumble_id) {
+        index.claimed[pos] = true;
+    }
+}
+
+/// Count a new rumble and its fighters in the lifetime totals.
+fn record_global_rumble(stats: &mut GlobalStats, fighter_count: usize) -> Result<()> {
+    stats.total_rumbles = stats
+        .total_rumbles
+        .checked_add(1)
+        .ok_or(RumbleError::MathOverflow)?;
+    record_global_fighters(stats, fighter_count)
+}
+
+/// Count fighters entered into a rumble in the lifetime totals.
+fn record_global_fighters(stats: &mut GlobalStats, fighter_count: usize) -> Result<()> {
+    stats.total_fighters_entered = stats
+        .total_fighters_entered
+        .checked_add(fighter_count as u64)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok(())
+}
+
+/// Count a bet in the lifetime totals; `new_bettor` when it opened the
+/// bettor's account for this rumble.
+fn record_global_bet(
+    stats: &mut GlobalStats,
+    amount: u64,
+    sponsorship_fee: u64,
+    new_bettor: bool,
+) -> Result<()> {
+    stats.total_bets_lamports = stats
+        .total_bets_lamports
+        .checked_add(amount)
+        .ok_or(RumbleError::MathOverflow)?;
+    stats.total_sponsorship_lamports = stats
+        .total_sponsorship_lamports
+        .checked_add(sponsorship_fee)
+        .ok_or(RumbleError::MathOverflow)?;
+    if new_bettor {
+        stats.total_bettors = stats
+            .total_bettors
+            .checked_add(1)
+            .ok_or(RumbleError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Count a bet on the wallet's stats; `first_bet_on_rumble` when it opened
+/// the bettor account for this rumble.
+fn record_bettor_stats_bet(
+    stats: &mut BettorStats,
+    net_bet: u64,
+    first_bet_on_rumble: bool,
+) -> Result<()> {
+    stats.total_bets = stats
+        .total_bets
+        .checked_add(1)
+        .ok_or(RumbleError::MathOverflow)?;
+    stats.total_deployed_lamports = stats
+        .total_deployed_lamports
+        .checked_add(net_bet)
+        .ok_or(RumbleError::MathOverflow)?;
+    if first_bet_on_rumble {
+        stats.rumbles_entered = stats
+            .rumbles_entered
+            .checked_add(1)
+            .ok_or(RumbleError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Book a `claim_payout` on the wallet's stats; refunds pay out but aren't wins.
+fn record_bettor_stats_claim(stats: &mut BettorStats, paid: u64, won: bool) -> Result<()> {
+    stats.total_claimed_lamports = stats
+        .total_claimed_lamports
+        .checked_add(paid)
+        .ok_or(RumbleError::MathOverflow)?;
+    if won {
+        stats.wins = stats.wins.checked_add(1).ok_or(RumbleError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Vault lamports that can be paid out, i.e. everything above the rent reserve
+/// funded by the first deposit.
+fn vault_available_lamports(vault_lamports: u64, rumble: &Rumble) -> Result<u64> {
+    vault_lamports
+        .checked_sub(rumble.vault_rent_reserve)
+        .ok_or_else(|| error!(RumbleError::InsufficientVaultFunds))
+}
+
+/// Set up a fresh sponsorship ledger. Sponsorship PDAs funded before ledgers
+/// existed carry their surplus over the rent minimum in as already accrued.
+fn open_sponsorship_ledger(
+    ledger: &mut SponsorshipLedger,
+    fighter: Pubkey,
+    bump: u8,
+    sponsorship_lamports: u64,
+    rent_minimum: u64,
+) {
+    if ledger.fighter != Pubkey::default() {
+        return;
+    }
+    ledger.fighter = fighter;
+    ledger.total_accrued = sponsorship_lamports.saturating_sub(rent_minimum);
+    ledger.total_claimed = 0;
+    ledger.last_rumble_id = 0;
+    ledger.bump = bump;
+}
+
+/// Sponsorship owed to the fighter owner: accrued minus already claimed. The
+/// PDA must still hold it on top of its rent reserve.
+fn claimable_sponsorship(
+    ledger: &SponsorshipLedger,
+    sponsorship_lamports: u64,
+    rent_minimum: u64,
+) -> Result<u64> {
+    let claimable = ledger
+        .total_accrued
+        .checked_sub(ledger.total_claimed)
+        .ok_or(RumbleError::MathOverflow)?;
+    require!(
+        sponsorship_lamports.saturating_sub(rent_minimum) >= claimable,
+        RumbleError::InsufficientVaultFunds
+    );
+    Ok(claimable)
+}
+
+/// Treasury cut taken at result time. Skipped when nobody backed a paying
+/// place, since the whole vault is owed back to bettors as refunds.
+fn result_treasury_cut(rumble: &Rumble) -> Result<u64> {
+    if refunds_all_bettors(rumble)? {
+        return Ok(0);
+    }
+    let (_, _losers_pool, treasury_cut, _) = calculate_payout_breakdown(rumble)?;
+    Ok(treasury_cut)
+}
+
+/// Share of the treasury cut held back to pay ICHOR staking bonuses: enough
+/// for every winner at `MAX_STAKE_BONUS_BPS` when the cut covers it.
+fn result_stake_bonus_reserve(rumble: &Rumble) -> Result<u64> {
+    let treasury_cut = result_treasury_cut(rumble)?;
+    if treasury_cut == 0 {
+        return Ok(0);
+    }
+    let (_, _, _, distributable) = calculate_payout_breakdown(rumble)?;
+    Ok(fee_lamports(distributable, MAX_STAKE_BONUS_BPS)?.min(treasury_cut))
+}
+
+fn admin_log_entry(discriminator: &[u8], actor: Pubkey, clock: &Clock) -> AdminLogEntry {
+    let mut instruction_tag = [0u8; 8];
+    instruction_tag.copy_from_slice(&discriminator[..8]);
+    AdminLogEntry {
+        instruction_tag,
+        actor,
+        at_slot: clock.slot,
+        at_ts: clock.unix_timestamp,
+    }
+}
+
+/// Write `entry` at the ring buffer's head, overwriting the oldest once full.
+fn push_admin_log_entry(log: &mut AdminLog, entry: AdminLogEntry) {
+    log.entries[log.head as usize] = entry;
+    log.head = ((log.head as usize + 1) % ADMIN_LOG_CAPACITY) as u8;
+    log.count = (log.count as usize + 1).min(ADMIN_LOG_CAPACITY) as u8;
+    log.total_entries = log.total_entries.saturating_add(1);
+}
+
+/// Append a privileged call to the AdminLog.
+fn record_admin_action(
+    admin_log: &AccountLoader<'_, AdminLog>,
+    discriminator: &[u8],
+    actor: Pubkey,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    push_admin_log_entry(
+        &mut *admin_log.load_mut()?,
+        admin_log_entry(discriminator, actor, &clock),
+    );
+    Ok(())
+}
+
+/// Payout -> Complete once the claim window has passed and no dispute is
+/// open, counting the rumble once. Shared by `complete_rumble` and
+/// `finalize_complete`; the state check makes a second call fail.
+fn mark_rumble_complete(
+    rumble: &mut Rumble,
+    config: &mut RumbleConfig,
+    global_stats: Option<&mut GlobalStats>,
+    now: i64,
+) -> Result<()> {
+    require!(
+        rumble.state == RumbleState::Payout,
+        RumbleError::InvalidStateTransition
+    );
+    require!(!rumble.dispute_open, RumbleError::DisputeUnresolved);
+    require_claim_window_elapsed(rumble, now)?;
+
+    rumble.state = RumbleState::Complete;
+    config.total_rumbles = config
+        .total_rumbles
+        .checked_add(1)
+        .ok_or(RumbleError::MathOverflow)?;
+    if let Some(stats) = global_stats {
+        stats.completed_rumbles = stats
+            .completed_rumbles
+            .checked_add(1)
+            .ok_or(RumbleError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// What `sweep_treasury` can take from a vault: everything above its rent
+/// reserve and any prize pool still held for bettors.
+fn sweepable_lamports(vault_lamports: u64, rent_reserve: u64, held_prize_pool: u64) -> Result<u64> {
+    vault_lamports
+        .checked_sub(rent_reserve)
+        .and_then(|v| v.checked_sub(held_prize_pool))
+        .ok_or_else(|| error!(RumbleError::InsufficientVaultFunds))
+}
+
+/// What `extract_result_treasury_cut` sends to the treasury for the current
+/// result, keeper refund aside: the cut less the stake-bonus hold.
+fn result_treasury_transfer(rumble: &Rumble) -> Result<u64> {
+    result_treasury_cut(rumble)?
+        .checked_sub(result_stake_bonus_reserve(rumble)?)
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// When claims on a result open. An admin-set result can be disputed for
+/// `DISPUTE_WINDOW_SECONDS`, so its claims wait for that unless the admin
+/// has already resolved a dispute on it.
+fn claims_open_at(rumble: &Rumble) -> Result<i64> {
+    if rumble.result_source != RESULT_SOURCE_ADMIN || rumble.dispute_resolved {
+        return Ok(rumble.completed_at);
+    }
+    rumble
+        .completed_at
+        .checked_add(DISPUTE_WINDOW_SECONDS)
+        .ok_or_else(|| error!(RumbleError::MathOverflow))
+}
+
+/// Claims, insurance and exacta settlement: no open dispute, and for an
+/// admin-set result, past its dispute window.
+fn require_claims_open(rumble: &Rumble, now: i64) -> Result<()> {
+    require!(!rumble.dispute_open, RumbleError::DisputeUnresolved);
+    require!(now >= claims_open_at(rumble)?, RumbleError::DisputeWindowOpen);
+    Ok(())
+}
+
+/// `file_dispute` is open on admin-set results in Payout, for
+/// `DISPUTE_WINDOW_SECONDS` after `completed_at`.
+fn require_dispute_window(rumble: &Rumble, now: i64) -> Result<()> {
+    require!(
+        rumble.state == RumbleState::Payout && rumble.result_source == RESULT_SOURCE_ADMIN,
+        RumbleError::ResultNotDisputable
+    );
+    let window_end = rumble
+        .completed_at
+        .checked_add(DISPUTE_WINDOW_SECONDS)
+        .ok_or(RumbleError::MathOverflow)?;
+    require!(now < window_end, RumbleError::DisputeWindowClosed);
+    Ok(())
+}
+
+fn extract_result_treasury_cut<'info>(
+    rumble: &mut Rumble,
+    vault_info: AccountInfo<'info>,
+    treasury_info: AccountInfo<'info>,
+    system_program_info: AccountInfo<'info>,
+    vault_bump: u8,
+) -> Result<()> {
+    // Keeper budget left after combat is the admin's; return it with the cut.
+    let keeper_refund = unspent_keeper_budget(rumble, vault_info.lamports())?;
+    rumble.keeper_budget = 0;
+
+    let reserve = result_stake_bonus_reserve(rumble)?;
+    rumble.stake_bonus_reserve = reserve;
+    let treasury_cut = result_treasury_cut(rumble)?
+        .checked_sub(reserve)
+        .ok_or(RumbleError::MathOverflow)?;
+    let to_treasury = treasury_cut
+        .checked_add(keeper_refund)
+        .ok_or(RumbleError::MathOverflow)?;
+    if to_treasury == 0 {
+        return Ok(());
+    }
+
+    // Result finalization happens before any bettor claims, so the vault still
+    // holds every stake on top of its rent reserve.
+    let available = vault_available_lamports(vault_info.lamports(), rumble)?;
+    require!(available >= to_treasury, RumbleError::InsufficientVaultFunds);
+
+    let rumble_id_bytes = rumble.id.to_le_bytes();
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, rumble_id_bytes.as_ref(), &[vault_bump]];
+    let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program_info,
+            system_program::Transfer {
+                from: vault_info,
+                to: treasury_info,
+            },
+            signer_seeds,
+        ),
+        to_treasury,
+    )?;
+
+    msg!(
+        "Treasury cut extracted: {} lamports from rumble {} ({} bonus hold, {} keeper refund)",
+        treasury_cut,
+        rumble.id,
+        reserve,
+        keeper_refund
+    );
+
+    Ok(())
+}
+
+/// Shared body of `claim_payout` and `crank_claim_payout`. `crank` carries the
+/// cranker and tip rate when someone else is claiming for `bettor`. Returns the
+/// lamports paid out (crank tip included) and whether this was a winning
+/// claim rather than a refund.
+#[allow(clippy::too_many_arguments)]
+fn settle_payout_claim<'info>(
+    rumble: &mut Rumble,
+    bettor_account_info: &AccountInfo<'info>,
+    bettor: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    vault_bump: u8,
+    stake_bonus_bps: u16,
+    crank: Option<(AccountInfo<'info>, u16)>,
+) -> Result<(u64, bool)> {
+    let clock = Clock::get()?;
+    let mut bettor_account = {
+        let data = bettor_account_info.try_borrow_data()?;
+        parse_bettor_account_data(&data)?
+    };
+
+    require!(
+        rumble.state == RumbleState::Payout || rumble.state == RumbleState::Complete,
+        RumbleError::PayoutNotReady
+    );
+    require_claims_open(rumble, clock.unix_timestamp)?;
+    require!(!rumble.vault_halted, RumbleError::VaultHalted);
+
+    require!(!bettor_account.claimed, RumbleError::AlreadyClaimed);
+    require!(
+        !bettor_account.insurance_claimed,
+        RumbleError::InsuranceAlreadyClaimed
+    );
+
+    require!(
+        bettor_account.authority == bettor.key(),
+        RumbleError::Unauthorized
+    );
+    require!(
+        bettor_account.rumble_id == rumble.id,
+        RumbleError::InvalidRumble
+    );
+
+    let winner_idx = rumble.winner_index as usize;
+    require!(
+        winner_idx < rumble.fighter_count as usize,
+        RumbleError::InvalidFighterIndex
+    );
+
+    // Account can hold stakes across multiple fighters.
+    let deployments = bettor_deployments(&bettor_account);
+
+    // Report the best-placed fighter this bettor backed.
+    let (paid_fighter_index, placement) = (0..rumble.fighter_count as usize)
+        .filter(|i| deployments[*i] > 0 && rumble.placements[*i] > 0)
+        .map(|i| (i as u8, rumble.placements[i]))
+        .min_by_key(|(_, placement)| *placement)
+        .unwrap_or((rumble.winner_index, rumble.placements[winner_idx]));
+
+    let is_refund = refunds_all_bettors(rumble)?;
+
+    // Lazy accrual model:
+    // If claimable is empty, compute and store this bettor's payout once.
+    if bettor_account.claimable_lamports == 0 {
+        let total_payout = calculate_claim_lamports(rumble, &bettor_account)?;
+        require!(total_payout > 0, RumbleError::NotInPayoutRange);
+
+        bettor_account.claimable_lamports = total_payout;
+    }
+
+    require!(bettor_account.claimable_lamports > 0, RumbleError::NothingToClaim);
+
+    let stake_bonus = if is_refund {
+        release_refunded_premium(rumble, &bettor_account)?;
+        0
+    } else {
+        let (returned_stake, winnings) = calculate_bettor_winnings(rumble, &deployments)?;
+        record_winner_claim(rumble, returned_stake, bettor_account.claimable_lamports)?;
+        stake_bonus_lamports(winnings, stake_bonus_bps, rumble.stake_bonus_reserve)?
+    };
+    rumble.stake_bonus_reserve = rumble
+        .stake_bonus_reserve
+        .checked_sub(stake_bonus)
+        .ok_or(RumbleError::MathOverflow)?;
+    let claimable = bettor_account
+        .claimable_lamports
+        .checked_add(stake_bonus)
+        .ok_or(RumbleError::MathOverflow)?;
+
+    // State update BEFORE CPI transfer (checks-effects-interactions pattern)
+    bettor_account.claimable_lamports = 0;
+    bettor_account.total_claimed_lamports = bettor_account
+        .total_claimed_lamports
+        .checked_add(claimable)
+        .ok_or(RumbleError::MathOverflow)?;
+    bettor_account.last_claim_ts = clock.unix_timestamp;
+    bettor_account.claimed = true;
+
+    {
+        let mut data = bettor_account_info.try_borrow_mut_data()?;
+        write_bettor_account_data(&mut data, &bettor_account)?;
+    }
+
+    let available = vault_available_lamports(vault.lamports(), rumble)?;
+    require!(available >= claimable, RumbleError::InsufficientVaultFunds);
+
+    let (to_bettor, tip) = match &crank {
+        Some((_, tip_bps)) => split_crank_tip(claimable, *tip_bps)?,
+        None => (claimable, 0),
+    };
+    transfer_from_vault(
+        vault.clone(),
+        bettor.clone(),
+        system_program.clone(),
+        rumble.id,
+        vault_bump,
+        to_bettor,
+    )?;
+    if let Some((cranker, _)) = crank {
+        if tip > 0 {
+            transfer_from_vault(
+                vault,
+                cranker.clone(),
+                system_program,
+                rumble.id,
+                vault_bump,
+                tip,
+            )?;
+        }
+        emit!(CrankClaimEvent {
+            rumble_id: rumble.id,
+            bettor: bettor.key(),
+            cranker: cranker.key(),
+            tip,
+        });
+    }
+
+    msg!(
+        "Payout claimed: {} lamports (deployed: {}, stake bonus: {}, crank tip: {}) for rumble {}",
+        to_bettor,
+        bettor_account.sol_deployed,
+        stake_bonus,
+        tip,
+        rumble.id
+    );
+
+    if is_refund {
+        emit!(RefundClaimedEvent {
+            rumble_id: rumble.id,
+            bettor: bettor.key(),
+            amount: to_bettor,
+        });
+    } else {
+        emit!(PayoutClaimedEvent {
+            rumble_id: rumble.id,
+            bettor: bettor.key(),
+            fighter_index: paid_fighter_index,
+            placement,
+            amount: to_bettor,
+            stake_bonus,
+        });
+    }
+
+    Ok((claimable, !is_refund))
+}
+
+/// Extra payout bps a bettor earns from their ICHOR stake: picked by the
+/// stake's lock-period tier and only while the lock is still running, capped
+/// at `MAX_STAKE_BONUS_BPS`.
+fn bettor_stake_multiplier(stake: &IchorStake, clock: &Clock) -> u16 {
+    if stake.staked_amount == 0 {
+        return 0;
+    }
+    let locked = stake
+        .staked_at
+        .checked_add(stake.lock_period_seconds)
+        .is_some_and(|unlock_at| clock.unix_timestamp < unlock_at);
+    if !locked {
+        return 0;
+    }
+    STAKE_BONUS_TIERS
+        .iter()
+        .find(|(min_lock, _)| stake.lock_period_seconds >= *min_lock)
+        .map_or(0, |(_, bps)| (*bps).min(MAX_STAKE_BONUS_BPS))
+}
+
+/// Read the bettor's optional `IchorStake` and return its bonus bps. No
+/// account means no bonus; a mismatched one is rejected rather than ignored.
+fn load_stake_bonus_bps(
+    info: Option<&AccountInfo>,
+    bettor: &Pubkey,
+    clock: &Clock,
+) -> Result<u16> {
+    let Some(info) = info else {
+        return Ok(0);
+    };
+    require!(
+        *info.owner == ICHOR_TOKEN_PROGRAM_ID,
+        RumbleError::InvalidStakeAccount
+    );
+    let data = info.try_borrow_data()?;
+    require!(
+        data.get(..8) == Some(ICHOR_STAKE_DISCRIMINATOR.as_ref()),
+        RumbleError::InvalidStakeAccount
+    );
+    let stake = IchorStake::deserialize(&mut &data[8..])
+        .map_err(|_| error!(RumbleError::InvalidStakeAccount))?;
+    let expected = Pubkey::create_program_address(
+        &[ICHOR_STAKE_SEED, bettor.as_ref(), &[stake.bump]],
+        &ICHOR_TOKEN_PROGRAM_ID,
+    )
+    .map_err(|_| error!(RumbleError::InvalidStakeAccount))?;
+    require!(
+        stake.staker == *bettor && info.key() == expected,
+        RumbleError::InvalidStakeAccount
+    );
+    Ok(bettor_stake_multiplier(&stake, clock))
+}
+
+/// Stake bonus on `winnings` at `bonus_bps`, limited to what is left of the
+/// rumble's reserve.
+fn stake_bonus_lamports(winnings: u64, bonus_bps: u16, reserve: u64) -> Result<u64> {
+    Ok(fee_lamports(winnings, bonus_bps)?.min(reserve))
+}
+
+/// Split a cranked claim into the bettor's share and the cranker's tip.
+fn split_crank_tip(claimable: u64, tip_bps: u16) -> Result<(u64, u64)> {
+    require!(tip_bps <= MAX_CRANK_TIP_BPS, RumbleError::CrankTipTooHigh);
+    let tip = fee_lamports(claimable, tip_bps)?;
+    let to_bettor = claimable
+        .checked_sub(tip)
+        .ok_or(RumbleError::MathOverflow)?;
+    Ok((to_bettor, tip))
+}
+
+/// Shared body of `cancel_bet` (`amount = None`) and `withdraw_bet`.
+fn withdraw_stake(
+    ctx: Context<CancelBet>,
+    rumble_id: u64,
+    fighter_index: u8,
+    amount: Option<u64>,
+) -> Result<()> {
+    let rumble = &mut ctx.accounts.rumble;
+
+    let mut bettor_account = {
+        let data = ctx.accounts.bettor_account.try_borrow_data()?;
+        parse_bettor_account_data(&data)?
+    };
+    require!(
+        bettor_account.authority == ctx.accounts.bettor.key(),
+        RumbleError::Unauthorized
+    );
+    require!(
+        bettor_account.rumble_id == rumble_id,
+        RumbleError::InvalidRumble
+    );
+
+    // State update BEFORE CPI transfers (checks-effects-interactions pattern)
+    let clock = Clock::get()?;
+    let (withdrawn, refund, penalty) = apply_stake_withdrawal(
+        rumble,
+        &mut bettor_account,
+        fighter_index,
+        amount,
+        ctx.accounts.config.admin_fee_bps,
+        &clock,
+    )?;
+    // Rumbles that record bets keep the account so `bet_sequence` never repeats.
+    let close_bettor_account = bettor_account.sol_deployed == 0 && !rumble.record_bets;
+    if close_bettor_account {
+        // A later bet re-initializes the account and counts the bettor again.
+        rumble.unique_bettor_count = rumble.unique_bettor_count.saturating_sub(1);
+    } else {
+        let mut data = ctx.accounts.bettor_account.try_borrow_mut_data()?;
+        write_bettor_account_data(&mut data, &bettor_account)?;
+    }
+
+    transfer_from_vault(
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        rumble_id,
+        ctx.bumps.vault,
+        penalty,
+    )?;
+    transfer_from_vault(
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.bettor.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        rumble_id,
+        ctx.bumps.vault,
+        refund,
+    )?;
+
+    if close_bettor_account {
+        close_program_account(
+            &ctx.accounts.bettor_account,
+            &ctx.accounts.bettor.to_account_info(),
+        )?;
+    }
+
+    msg!(
+        "Bet withdrawn: {} lamports on fighter #{} in rumble {}. Refund: {}, penalty: {}",
+        withdrawn,
+        fighter_index,
+        rumble_id,
+        refund,
+        penalty
+    );
+
+    if amount.is_none() {
+        emit!(BetCancelledEvent {
+            rumble_id,
+            bettor: ctx.accounts.bettor.key(),
+            fighter_index,
+            refund,
+            penalty,
+        });
+    } else {
+        emit!(BetWithdrawnEvent {
+            rumble_id,
+            bettor: ctx.accounts.bettor.key(),
+            fighter_index,
+            amount: withdrawn,
+            refund,
+            penalty,
+            remaining_stake: bettor_account.fighter_deployments[fighter_index as usize],
+        });
+    }
+
+    Ok(())
+}
+
+fn transfer_from_vault<'info>(
+    vault_info: AccountInfo<'info>,
+    recipient_info: AccountInfo<'info>,
+    system_program_info: AccountInfo<'info>,
+    rumble_id: u64,
+    vault_bump: u8,
+    lamports: u64,
+) -> Result<()> {
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    let rumble_id_bytes = rumble_id.to_le_bytes();
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, rumble_id_bytes.as_ref(), &[vault_bump]];
+    let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program_info,
+            system_program::Transfer {
+                from: vault_info,
+                to: recipient_info,
+            },
+            signer_seeds,
+        ),
+        lamports,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+#[event]
+pub struct RumbleCreatedEvent {
+    pub rumble_id: u64,
+    pub fighter_count: u8,
+    pub fighters: Vec<Pubkey>,
+    pub betting_deadline: i64,
+    pub betting_close_slot: u64,
+}
+
+#[event]
+pub struct RumbleFightersAppendedEvent {
+    pub rumble_id: u64,
+    pub fighter_count: u8,
+    pub fighters: Vec<Pubkey>,
+}
+
+#[event]
+pub struct BetPlacedEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub fighter_index: u8,
+    pub amount: u64,
+    pub net_amount: u64,
+    pub sponsorship_fee: u64,
+    /// Sponsorship this fighter has earned in the rumble so far, this bet included.
+    pub fighter_sponsorship: u64,
+    pub referrer: Option<Pubkey>,
+    /// Part of the admin fee paid to `referrer` (0 without one).
+    pub referral_fee: u64,
+    /// ICHOR burned from the bettor (`ichor_burn_per_bet` at the time).
+    pub ichor_burned: u64,
+    /// Premium paid into the rumble's insurance pool (0 for uninsured bets).
+    pub insurance_premium: u64,
+}
+
+#[event]
+pub struct BetCancelledEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub fighter_index: u8,
+    pub refund: u64,
+    pub penalty: u64,
+}
+
+#[event]
+pub struct BetWithdrawnEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub fighter_index: u8,
+    pub amount: u64,
+    pub refund: u64,
+    pub penalty: u64,
+    pub remaining_stake: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct CombatStartedEvent {
+    pub rumble_id: u64,
+    pub timestamp: i64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct ResultReportedEvent {
+    pub rumble_id: u64,
+    pub winner_index: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ResultSetEvent {
+    pub rumble_id: u64,
+    pub winner_index: u8,
+    pub placements: Vec<u8>,
+    pub result_source: u8,
+}
+
+#[event]
+pub struct DisputeFiledEvent {
+    pub rumble_id: u64,
+    pub disputer: Pubkey,
+    pub filed_at: i64,
+}
+
+#[event]
+pub struct DisputeResolvedEvent {
+    pub rumble_id: u64,
+    pub disputer: Pubkey,
+    pub upheld: bool,
+}
+
+#[event]
+pub struct RumbleCompletedEvent {
+    pub rumble_id: u64,
+    pub total_deployed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PrizePoolDepositedEvent {
+    pub rumble_id: u64,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub prize_pool: u64,
+}
+
+#[event]
+pub struct PrizePoolRefundedEvent {
+    pub rumble_id: u64,
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VaultHaltedEvent {
+    pub rumble_id: u64,
+    pub vault_lamports: u64,
+    pub expected_minimum: u64,
+    pub caller: Pubkey,
+}
+
+#[event]
+pub struct VaultHaltLiftedEvent {
+    pub rumble_id: u64,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct TreasurySweptEvent {
+    pub rumble_id: u64,
+    pub amount: u64,
+    /// Vault balance after the sweep: its rent reserve plus any held prize pool.
+    pub vault_remaining: u64,
+}
+
+#[event]
+pub struct PayoutClaimedEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub fighter_index: u8,
+    pub placement: u8,
+    pub amount: u64,
+    pub stake_bonus: u64,
+}
+
+#[event]
+pub struct ExactaBetPlacedEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub first_index: u8,
+    pub second_index: u8,
+    pub amount: u64,
+    pub net_amount: u64,
+    pub exacta_pool: u64,
+}
+
+#[event]
+pub struct ExactaSettledEvent {
+    pub rumble_id: u64,
+    pub first_index: u8,
+    pub second_index: u8,
+    pub winning_stake: u64,
+    pub treasury_cut: u64,
+    pub distributable: u64,
+}
+
+#[event]
+pub struct ExactaPayoutClaimedEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub first_index: u8,
+    pub second_index: u8,
+    pub amount: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct MoveCommittedEvent {
+    pub rumble_id: u64,
+    pub fighter: Pubkey,
+    pub turn: u32,
+    pub committed_slot: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct FighterDelegateAuthorizedEvent {
+    pub fighter: Pubkey,
+    pub authority: Pubkey,
+    pub authorized_slot: u64,
+    pub expires_at_slot: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct FighterDelegateRevokedEvent {
+    pub fighter: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct MoveRevealedEvent {
+    pub rumble_id: u64,
+    pub fighter: Pubkey,
+    pub turn: u32,
+    pub move_code: u8,
+    pub revealed_slot: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct TurnOpenedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub turn_open_slot: u64,
+    pub commit_close_slot: u64,
+    pub reveal_close_slot: u64,
+    pub crit_seed: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct StaleTurnResolvedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub reveal_close_slot: u64,
+    pub resolved_slot: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct DoubleKnockoutRevivedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub fighter: Pubkey,
+    pub hp: u16,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct TurnPairResolvedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub fighter_a: Pubkey,
+    pub fighter_b: Pubkey,
+    pub move_a: u8,
+    pub move_b: u8,
+    pub damage_to_a: u16,
+    pub damage_to_b: u16,
+    pub crit_a: bool,
+    pub crit_b: bool,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct PoisonedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub fighter: Pubkey,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct PoisonTickEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub fighter: Pubkey,
+    pub damage: u16,
+    pub hp: u16,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct RevealMissedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub fighter: Pubkey,
+    pub chip_damage: u16,
+    pub missed_reveals: u8,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct KeeperPaidEvent {
+    pub rumble_id: u64,
+    pub keeper: Pubkey,
+    pub amount: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct TurnResolvedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub remaining_fighters: u8,
+    /// Leaf data chained into `history_hash` (see `turn_history_hash`), one
+    /// entry per fighter slot of the rumble (16, or 32 on the v2 layout).
+    pub moves: Vec<u8>,
+    pub damages: Vec<u16>,
+    pub eliminations: Vec<u8>,
+    pub history_hash: [u8; 32],
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct OnchainResultFinalizedEvent {
+    pub rumble_id: u64,
+    pub winner_index: u8,
+    pub timestamp: i64,
+    pub result_source: u8,
+}
+
+#[event]
+pub struct RumbleCancelledEvent {
+    pub rumble_id: u64,
+    pub total_refundable: u64,
+    pub timestamp: i64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct FighterEliminatedEvent {
+    pub rumble_id: u64,
+    pub turn: u32,
+    pub fighter: Pubkey,
+    pub elimination_rank: u8,
+    pub damage_dealt_total: u64,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct FighterForfeitedEvent {
+    pub rumble_id: u64,
+    pub fighter: Pubkey,
+    pub turn: u32,
+    pub elimination_rank: u8,
+    pub remaining_fighters: u8,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct FighterSurrenderedEvent {
+    pub rumble_id: u64,
+    pub fighter: Pubkey,
+    pub turn: u32,
+    pub elimination_rank: u8,
+    pub remaining_fighters: u8,
+}
+
+#[cfg(feature = "combat")]
+#[event]
+pub struct FighterInactivityTimeoutEvent {
+    pub rumble_id: u64,
+    pub fighter: Pubkey,
+    pub turn: u32,
+    pub missed_turns: u8,
+    pub elimination_rank: u8,
+    pub remaining_fighters: u8,
+}
+
+#[event]
+pub struct UnderfundedRumbleCancelledEvent {
+    pub rumble_id: u64,
+    pub total_deployed: u64,
+    pub min_pool_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// `expires_slot` is 0 when the exemption was revoked.
+#[event]
+pub struct FeeExemptionUpdatedEvent {
+    pub wallet: Pubkey,
+    pub expires_slot: u64,
+}
+
+#[event]
+pub struct WhitelistUpdatedEvent {
+    pub rumble_id: u64,
+    pub count: u8,
+}
+
+#[event]
+pub struct UnderfilledRumbleCancelledEvent {
+    pub rumble_id: u64,
+    pub unique_bettor_count: u16,
+    pub min_bettors: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceClaimedEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CrankClaimEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub cranker: Pubkey,
+    pub tip: u64,
+}
+
+#[event]
+pub struct RefundClaimedEvent {
+    pub rumble_id: u64,
+    pub bettor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SponsorshipAccruedEvent {
+    pub rumble_id: u64,
+    pub fighter: Pubkey,
+    pub amount: u64,
+    pub total_accrued: u64,
+}
+
+#[event]
+pub struct ReferralClaimedEvent {
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SponsorshipClaimedEvent {
+    pub fighter_owner: Pubkey,
+    pub fighter: Pubkey,
+    pub amount: u64,
+    /// Lifetime sponsorship claimed for the fighter, this claim included.
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct TreasuryUpdateProposedEvent {
+    pub current_treasury: Pubkey,
+    pub proposed_treasury: Pubkey,
+    pub effective_after_slot: u64,
+}
+
+#[event]
+pub struct TreasuryUpdateAppliedEvent {
+    pub old_treasury: Pubkey,
+    pub new_treasury: Pubkey,
+}
+
+#[event]
+pub struct TreasuryUpdateCancelledEvent {
+    pub proposed_treasury: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferProposedEvent {
+    pub current_admin: Pubkey,
+    pub proposed_admin: Pubkey,
+    pub proposed_at: u64,
+}
+
+#[event]
+pub struct AdminTransferRevokedEvent {
+    pub admin: Pubkey,
+    pub proposed_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferredEvent {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct ProgramPausedEvent {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct ProgramUnpausedEvent {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct LeaderboardUpdatedEvent {
+    pub fighter: Pubkey,
+    pub wins: u64,
+    /// 0-based position on the leaderboard.
+    pub rank: u8,
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[error_code]
+pub enum RumbleError {
+    #[msg("Unauthorized: only admin can perform this action")]
+    Unauthorized,
+
+    #[msg("Betting is closed for this rumble")]
+    BettingClosed,
+
+    #[msg("Betting period has not ended yet")]
+    BettingNotEnded,
+
+    #[msg("Invalid state transition")]
+    InvalidStateTransition,
+
+    #[msg("Invalid fighter index")]
+    InvalidFighterIndex,
+
+    #[msg("Invalid fighter count: must be between 2 and the rumble's fighter slots")]
+    InvalidFighterCount,
+
+    #[msg("Invalid placement data")]
+    InvalidPlacement,
+
+    #[msg("Bet amount must be greater than zero")]
+    ZeroBetAmount,
+
+    #[msg("Payout already claimed")]
+    AlreadyClaimed,
+
+    #[msg("Payout is not ready yet")]
+    PayoutNotReady,
+
+    #[msg("Fighter did not win (winner-takes-all)")]
+    NotInPayoutRange,
+
+    #[msg("Math overflow")]
+    MathOverflow,
+
+    #[msg("Insufficient funds in vault")]
+    InsufficientVaultFunds,
+
+    #[msg("Invalid treasury address")]
+    InvalidTreasury,
+
+    #[msg("Invalid rumble ID mismatch")]
+    InvalidRumble,
+
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("Betting deadline must be in the future")]
+    DeadlineInPast,
+
+    #[msg("Invalid fighter account data")]
+    InvalidFighterAccount,
+
+    #[msg("Payout claim window is still active")]
+    ClaimWindowActive,
+
+    #[msg("Invalid bettor account data")]
+    InvalidBettorAccount,
+
+    #[msg("Invalid turn index")]
+    InvalidTurn,
+
+    #[msg("Invalid move commitment")]
+    InvalidMoveCommitment,
+
+    #[msg("Batch must contain between 1 and 16 entries")]
+    BatchTooLarge,
+
+    #[msg("Invalid fighter delegate account")]
+    InvalidFighterDelegate,
+
+    #[msg("Fighter delegate has been revoked")]
+    FighterDelegateRevoked,
+
+    #[msg("Invalid move code")]
+    InvalidMoveCode,
+
+    #[msg("Move already revealed")]
+    AlreadyRevealedMove,
+
+    #[msg("Turn is already open")]
+    TurnAlreadyOpen,
+
+    #[msg("Turn is not open")]
+    TurnNotOpen,
+
+    #[msg("Turn already resolved")]
+    TurnAlreadyResolved,
+
+    #[msg("Turn is not resolved yet")]
+    TurnNotResolved,
+
+    #[msg("Commit window is closed")]
+    CommitWindowClosed,
+
+    #[msg("Reveal window is closed")]
+    RevealWindowClosed,
+
+    #[msg("Reveal window is still active")]
+    RevealWindowActive,
+
+    #[msg("Combat already finished")]
+    CombatAlreadyFinished,
+
+    #[msg("Combat is still active")]
+    CombatStillActive,
+
+    #[msg("Max combat turns reached")]
+    MaxTurnsReached,
+
+    #[msg("Instruction is deprecated")]
+    DeprecatedInstruction,
+
+    #[msg("Duplicate fighter in rumble")]
+    DuplicateFighter,
+
+    #[msg("Invalid rumble state for this operation")]
+    InvalidState,
+
+    #[msg("Fighter has been eliminated")]
+    FighterEliminated,
+
+    #[msg("Invalid fighter accounts provided")]
+    InvalidFighterAccounts,
+
+    #[msg("Posted damage does not match resolve_duel computation")]
+    DamageMismatch,
+
+    #[msg("Invalid new admin address")]
+    InvalidNewAdmin,
+
+    #[msg("VRF matchup seed already set")]
+    VrfSeedAlreadySet,
+
+    #[msg("Winner claims are still outstanding")]
+    OutstandingWinnerClaims,
+
+    #[msg("Rumble has not been cancelled")]
+    RumbleNotCancelled,
+
+    #[msg("Refund claim window is still active")]
+    RefundWindowActive,
+
+    #[msg("Invalid payout split: shares must sum to 10000 bps with a non-zero first place")]
+    InvalidPayoutSplit,
+
+    #[msg("No stake on this fighter to cancel")]
+    NoBetToCancel,
+
+    #[msg("Bettor account has not been claimed yet")]
+    BettorNotClaimed,
+
+    #[msg("Invalid fee: each rate must be at most 1000 bps")]
+    InvalidFeeBps,
+
+    #[msg("Bet amount too small to cover fees")]
+    BetTooSmall,
+
+    #[msg("Invalid rumble config account")]
+    InvalidRumbleConfig,
+
+    #[msg("Bet amount is below the configured minimum")]
+    BetBelowMinimum,
+
+    #[msg("Bet would exceed the per-bettor cap")]
+    BettorCapExceeded,
+
+    #[msg("Bet would exceed the rumble pool cap")]
+    PoolCapExceeded,
+
+    #[msg("Rumble pool is below its minimum")]
+    PoolBelowMinimum,
+
+    #[msg("Rumble pool already meets its minimum")]
+    PoolMeetsMinimum,
+
+    #[msg("Bettor account still holds a claimable balance")]
+    ClaimableBalanceOutstanding,
+
+    #[msg("Rumble engine is paused")]
+    ProgramPaused,
+
+    #[msg("Duel does not match the pairing stored at turn open")]
+    PairingMismatch,
+
+    #[msg("Metadata field exceeds its maximum length")]
+    MetadataTooLong,
+
+    #[msg("Each dueling fighter's MoveCommitment PDA must be passed, even if never created")]
+    MoveCommitmentMissing,
+
+    #[msg("Combat window must be between 5 and 300 slots")]
+    InvalidCombatWindow,
+
+    #[msg("Combat log is sealed")]
+    CombatLogSealed,
+
+    #[msg("Withdraw amount must be positive and at most the current stake")]
+    InvalidWithdrawAmount,
+
+    #[msg("Fighter has not missed enough consecutive turns to time out")]
+    FighterStillActive,
+
+    #[msg("On-chain combat can only be overridden with force after the timeout")]
+    ForceResultTimeoutActive,
+
+    #[msg("A bet record must be passed exactly when the rumble records bets")]
+    BetRecordMismatch,
+
+    #[msg("Crank tip exceeds the maximum")]
+    CrankTipTooHigh,
+
+    #[msg("Audience boosts do not match the betting pools")]
+    AudienceBoostMismatch,
+
+    #[msg("Fighter has no fighter registry account")]
+    FighterNotRegistered,
+
+    #[msg("Fighter HP override out of range")]
+    InvalidHpOverride,
+
+    #[msg("Fighter registry config or registry authority account missing")]
+    RegistryAccountsMissing,
+
+    #[msg("Retired fighters cannot enter a rumble")]
+    FighterRetired,
+
+    #[msg("Treasury update timelock has not elapsed")]
+    TreasuryUpdateTimelocked,
+
+    #[msg("Pending admin transfer has expired")]
+    PendingAdminExpired,
+
+    #[msg("Slot rate must be between 1 and 10,000 (slots per second x1000)")]
+    InvalidSlotRate,
+
+    #[msg("Rumble has fewer distinct bettors than its minimum")]
+    NotEnoughBettors,
+
+    #[msg("Rumble has enough distinct bettors; it cannot be cancelled as underfilled")]
+    BettorMinimumMet,
+
+    #[msg("Fighter delegate has expired")]
+    FighterDelegateExpired,
+
+    #[msg("Referral fee cannot exceed the admin fee")]
+    ReferralFeeExceedsAdminFee,
+
+    #[msg("Referrer must differ from the bettor and come with its referral PDA")]
+    InvalidReferrer,
+
+    #[msg("Move already committed for this turn")]
+    MoveAlreadyCommitted,
+
+    #[msg("Whitelist account must be passed exactly when whitelist_enabled is set")]
+    WhitelistMismatch,
+
+    #[msg("Whitelist is full")]
+    WhitelistFull,
+
+    #[msg("Bettor is not on this rumble's whitelist")]
+    BettorNotWhitelisted,
+
+    #[msg("Invalid ICHOR stake account for this bettor")]
+    InvalidStakeAccount,
+
+    #[msg("ICHOR token account, mint and token program are required to bet")]
+    IchorAccountRequired,
+
+    #[msg("Insufficient ICHOR balance for the bet burn")]
+    InsufficientIchor,
+
+    #[msg("ICHOR burn per bet exceeds the maximum")]
+    IchorBurnTooHigh,
+
+    #[msg("Keeper fee exceeds the maximum")]
+    KeeperFeeTooHigh,
+
+    #[msg("Combat ruleset account does not match the rumble")]
+    RulesetMismatch,
+
+    #[msg("Invalid combat ruleset parameters")]
+    InvalidRuleset,
+
+    #[msg("SlotHashes sysvar required to seed crits")]
+    MissingSlotHashes,
+
+    #[msg("Turn is not stale yet; wait out the grace period after the reveal window")]
+    TurnNotStale,
+
+    #[msg("Pair index is out of range for this turn")]
+    InvalidPairIndex,
+
+    #[msg("This pair has already been resolved")]
+    PairAlreadyResolved,
+
+    #[msg("Earlier pairs of this turn must be resolved first")]
+    PairOutOfOrder,
+
+    #[msg("Not every pair of this turn has been resolved")]
+    TurnPairsUnresolved,
+
+    #[msg("This turn is being resolved pair by pair")]
+    ChunkedTurnInProgress,
+
+    #[msg("Token account is not an ICHOR token account")]
+    InvalidTokenGateAccount,
+
+    #[msg("A token account is required for a token-gated rumble")]
+    TokenGateAccountRequired,
+
+    #[msg("Token account holds less ICHOR than the rumble's token gate")]
+    TokenGateNotMet,
+
+    #[msg("Rumble was created without a token gate")]
+    RumbleNotTokenGated,
+
+    #[msg("Exacta picks must be two different fighters in the rumble")]
+    InvalidExactaPick,
+
+    #[msg("Exacta pool has already been settled")]
+    ExactaAlreadySettled,
+
+    #[msg("Exacta pool has not been settled yet")]
+    ExactaNotSettled,
+
+    #[msg("Exacta combo account does not match the rumble's 1-2 finish")]
+    InvalidExactaCombo,
+
+    #[msg("Winning bettors have not all claimed; pass force after the claim window")]
+    UnclaimedPayoutsRemain,
+
+    #[msg("Fee exemption must expire after the current slot")]
+    InvalidFeeExemption,
+
+    #[msg("Only an admin-set result in Payout can be disputed")]
+    ResultNotDisputable,
+
+    #[msg("Dispute window for this result has closed")]
+    DisputeWindowClosed,
+
+    #[msg("Rumble has an unresolved dispute")]
+    DisputeUnresolved,
+
+    #[msg("Dispute already resolved")]
+    DisputeAlreadyResolved,
+
+    #[msg("Result can't be corrected: it is a refund, or winning or insurance claims or exacta settlement have started")]
+    DisputeNotCorrectable,
+
+    #[msg("Claim window must be between 10 minutes and 30 days")]
+    InvalidClaimWindow,
+
+    #[msg("Too many bets from this wallet in one slot")]
+    BetRateLimited,
+
+    #[msg("Rumble vault is below its expected balance; claims are halted")]
+    VaultHalted,
+
+    #[msg("Rumble vault is not halted")]
+    VaultNotHalted,
+
+    #[msg("Insurance is not offered on this rumble")]
+    InsuranceUnavailable,
+
+    #[msg("Insurance pays out only to losing bettors while the rumble is in Payout")]
+    InsuranceNotClaimable,
+
+    #[msg("Insurance already claimed")]
+    InsuranceAlreadyClaimed,
+
+    #[msg("Insurance payout must be at most 10000 bps")]
+    InvalidInsuranceBps,
+
+    #[msg("Rumble account already uses the current layout")]
+    RumbleAlreadyMigrated,
+
+    #[msg("Rumbles in Combat can't be migrated")]
+    RumbleNotMigratable,
+
+    #[msg("Combat timeout must be between 5000 and 18000 slots")]
+    InvalidCombatTimeout,
+
+    #[msg("Only the rumble's prize depositor can fund or receive its prize pool")]
+    PrizeDepositorMismatch,
+
+    #[msg("Prize pool is refundable only on cancelled or refund-all rumbles")]
+    PrizePoolNotRefundable,
+
+    #[msg("Prize pool must be refunded to its depositor first")]
+    PrizePoolNotRefunded,
+
+    #[msg("Claims on an admin-set result wait for its dispute window to close")]
+    DisputeWindowOpen,
+
+    #[msg("Combat state is in the pre-zero-copy layout; run migrate_combat_state")]
+    CombatStateNotMigrated,
+
+    #[msg("Combat state already uses the zero-copy layout")]
+    CombatStateAlreadyMigrated,
+
+    #[msg("fighter_slots must be 16 or 32")]
+    InvalidFighterSlots,
+
+    #[msg("Fighters can only be added before anything is staked on the rumble")]
+    FightersLocked,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_rumble() -> Rumble {
+        Rumble {
+            id: 42,
+            state: RumbleState::Complete,
+            fighters: [Pubkey::default(); MAX_FIGHTERS],
+            fighter_count: 4,
+            betting_pools: [0; MAX_FIGHTERS],
+            total_deployed: 0,
+            admin_fee_collected: 0,
+            sponsorship_paid: 0,
+            placements: [0; MAX_FIGHTERS],
+            winner_index: 0,
+            betting_deadline: 0,
+            combat_started_at: 0,
+            completed_at: 0,
+            bump: 0,
+            cancelled_at: 0,
+            first_place_bps: 0,
+            second_place_bps: 0,
+            third_place_bps: 0,
+            max_bet_per_bettor: 0,
+            min_pool_lamports: 0,
+            underfunded: false,
+            deadline_kind: DeadlineKind::Slot,
+            vault_rent_reserve: 0,
+            result_source: RESULT_SOURCE_UNSET,
+            record_bets: false,
+            sponsorship_by_fighter: [0; MAX_FIGHTERS],
+            betting_close_slot: 0,
+            unique_bettor_count: 0,
+            min_bettors: 0,
+            prize_pool: 0,
+            whitelist_enabled: false,
+            stake_bonus_reserve: 0,
+            combat_history_hash: [0; 32],
+            keeper_budget: 0,
+            ruleset: Pubkey::default(),
+            crits_enabled: false,
+            token_gate: 0,
+            exacta_pool: 0,
+            exacta_settled: false,
+            exacta_winning_stake: 0,
+            exacta_distributable: 0,
+            exacta_claimed_stake: 0,
+            winner_pool_snapshot: 0,
+            total_claimed_lamports: 0,
+            winner_stake_claimed: 0,
+            dispute_open: false,
+            swept_lamports: 0,
+            claim_window_seconds: 0,
+            vault_halted: false,
+            elimination_rank: [0; MAX_FIGHTERS],
+            insurance_pool: 0,
+            insurance_paid: 0,
+            insured_stake: [0; MAX_FIGHTERS],
+            insurance_payout_bps: 0,
+            prize_depositor: Pubkey::default(),
+            registration_required: false,
+            dispute_resolved: false,
+            fighter_slots: MAX_FIGHTERS_V1 as u8,
+        }
+    }
+
+    fn sample_bettor() -> ParsedBettorAccount {
+        ParsedBettorAccount {
+            authority: Pubkey::new_unique(),
+            rumble_id: 42,
+            fighter_index: 1,
+            sol_deployed: 0,
+            claimable_lamports: 0,
+            total_claimed_lamports: 0,
+            last_claim_ts: 0,
+            claimed: false,
+            bump: 255,
+            fighter_deployments: [0; MAX_FIGHTERS],
+            bet_sequence: 0,
+            insured_deployments: [0; MAX_FIGHTERS],
+            insurance_claimed: false,
+            insurance_premium_paid: 0,
+        }
+    }
+
+    #[test]
+    fn bettor_layout_round_trips_bet_sequence_and_reads_older_accounts() {
+        let mut bettor = sample_bettor();
+        bettor.fighter_deployments[1] = 250_000;
+        bettor.sol_deployed = 250_000;
+        bettor.bet_sequence = 7;
+        bettor.insured_deployments[1] = 100_000;
+        bettor.insurance_claimed = true;
+        bettor.insurance_premium_paid = 2_000;
+
+        let mut data = vec![0u8; bettor_account_len(MAX_FIGHTERS_V1)];
+        data[..8].copy_from_slice(BettorAccount::DISCRIMINATOR);
+        write_bettor_account_data(&mut data, &bettor).unwrap();
+        let parsed = parse_bettor_account_data(&data).unwrap();
+        assert_eq!(parsed.fighter_deployments, bettor.fighter_deployments);
+        assert_eq!(parsed.bet_sequence, 7);
+        assert_eq!(parsed.insured_deployments, bettor.insured_deployments);
+        assert!(parsed.insurance_claimed);
+        assert_eq!(parsed.insurance_premium_paid, 2_000);
+
+        // Accounts created before insurance existed read as uninsured.
+        data.truncate(data.len() - 8 * MAX_FIGHTERS_V1 - 1 - 8);
+        let parsed = parse_bettor_account_data(&data).unwrap();
+        assert_eq!(parsed.bet_sequence, 7);
+        assert_eq!(parsed.insured_deployments, [0; MAX_FIGHTERS]);
+        assert!(!parsed.insurance_claimed);
+
+        // Accounts created before `bet_sequence` existed still carry deployments.
+        data.truncate(data.len() - 4);
+        let parsed = parse_bettor_account_data(&data).unwrap();
+        assert_eq!(parsed.fighter_deployments, bettor.fighter_deployments);
+        assert_eq!(parsed.bet_sequence, 0);
+    }
+
+    #[test]
+    fn bettor_layout_width_follows_account_length() {
+        assert_eq!(bettor_account_len(MAX_FIGHTERS_V1), 352);
+        assert_eq!(bettor_account_len(MAX_FIGHTERS), 352 + 2 * 8 * 16 + 1);
+
+        let mut bettor = sample_bettor();
+        bettor.fighter_deployments[3] = 40_000;
+        bettor.fighter_deployments[27] = 60_000;
+        bettor.insured_deployments[27] = 60_000;
+        bettor.bet_sequence = 2;
+        bettor.insurance_premium_paid = 900;
+
+        let mut data = vec![0u8; bettor_account_len(MAX_FIGHTERS)];
+        data[..8].copy_from_slice(BettorAccount::DISCRIMINATOR);
+        write_bettor_account_data(&mut data, &bettor).unwrap();
+        let parsed = parse_bettor_account_data(&data).unwrap();
+        assert_eq!(parsed.fighter_deployments, bettor.fighter_deployments);
+        assert_eq!(parsed.insured_deployments, bettor.insured_deployments);
+        assert_eq!(parsed.bet_sequence, 2);
+        assert_eq!(parsed.insurance_premium_paid, 900);
+
+        // A 16-slot account never stores the upper slots.
+        let mut narrow = vec![0u8; bettor_account_len(MAX_FIGHTERS_V1)];
+        narrow[..8].copy_from_slice(BettorAccount::DISCRIMINATOR);
+        bettor.fighter_deployments[27] = 0;
+        bettor.insured_deployments[27] = 0;
+        write_bettor_account_data(&mut narrow, &bettor).unwrap();
+        let parsed = parse_bettor_account_data(&narrow).unwrap();
+        assert_eq!(parsed.fighter_deployments[3], 40_000);
+        assert_eq!(parsed.bet_sequence, 2);
+        assert_eq!(parsed.insurance_premium_paid, 900);
+
+        // `Account<BettorAccount>` round trips at the width it was loaded with.
+        for (source, slots) in [(&narrow, MAX_FIGHTERS_V1), (&data, MAX_FIGHTERS)] {
+            let account = BettorAccount::try_deserialize(&mut source.as_slice()).unwrap();
+            assert_eq!(account.slots(), slots);
+            assert_eq!(account.bet_sequence, 2);
+            let mut written = Vec::new();
+            account.try_serialize(&mut written).unwrap();
+            assert_eq!(&written, source);
+        }
+    }
+
+    /// Winner-take-all result on fighters 0..4 with 50% insurance: fighter 0
+    /// wins, 200M of insured stake sits on fighter 1 and 500M on fighter 3.
+    fn insured_rumble() -> Rumble {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Payout;
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[..4].copy_from_slice(&[300_000_000, 200_000_000, 0, 500_000_000]);
+        rumble.total_deployed = 1_000_000_000;
+        rumble.insurance_payout_bps = 5_000;
+        rumble.insured_stake[0] = 300_000_000;
+        rumble.insured_stake[1] = 200_000_000;
+        rumble.insured_stake[3] = 500_000_000;
+        rumble
+    }
+
+    fn insured_bettor(fighter: usize, stake: u64) -> ParsedBettorAccount {
+        let mut bettor = sample_bettor();
+        bettor.fighter_deployments[fighter] = stake;
+        bettor.insured_deployments[fighter] = stake;
+        bettor.sol_deployed = stake;
+        bettor
+    }
+
+    #[test]
+    fn insurance_pays_losers_only_and_in_full_when_funded() {
+        let mut rumble = insured_rumble();
+        rumble.insurance_pool = 400_000_000;
+
+        // Half of each loser's insured stake comes back.
+        let second = insured_bettor(1, 200_000_000);
+        let last = insured_bettor(3, 500_000_000);
+        assert_eq!(
+            insurance_claim_lamports(&rumble, &second).unwrap(),
+            100_000_000
+        );
+        assert_eq!(
+            insurance_claim_lamports(&rumble, &last).unwrap(),
+            250_000_000
+        );
+
+        // The winner collects a payout instead, never both.
+        let winner = insured_bettor(0, 300_000_000);
+        assert_eq!(
+            insurance_claim_lamports(&rumble, &winner).unwrap_err(),
+            error!(RumbleError::InsuranceNotClaimable)
+        );
+
+        // Refund results pay no insurance either.
+        rumble.underfunded = true;
+        assert_eq!(
+            insurance_claim_lamports(&rumble, &second).unwrap_err(),
+            error!(RumbleError::InsuranceNotClaimable)
+        );
+    }
+
+    #[test]
+    fn insurance_pro_rates_an_underfunded_pool() {
+        let mut rumble = insured_rumble();
+        // Full cover would be 350M; the pool holds a tenth of that.
+        rumble.insurance_pool = 35_000_000;
+
+        let second = insured_bettor(1, 200_000_000);
+        let last = insured_bettor(3, 500_000_000);
+        let paid_second = insurance_claim_lamports(&rumble, &second).unwrap();
+        let paid_last = insurance_claim_lamports(&rumble, &last).unwrap();
+        assert_eq!(paid_second, 10_000_000);
+        assert_eq!(paid_last, 25_000_000);
+        assert!(paid_second + paid_last <= rumble.insurance_pool);
+
+        // Claim order doesn't change anyone's share; odd sizes round down.
+        rumble.insurance_pool = 33_333_333;
+        let total = insurance_claim_lamports(&rumble, &last).unwrap()
+            + insurance_claim_lamports(&rumble, &second).unwrap();
+        assert!(total <= rumble.insurance_pool);
+        assert!(rumble.insurance_pool - total <= 2);
+    }
+
+    #[test]
+    fn refunds_return_insurance_premiums_with_the_stake() {
+        let mut rumble = insured_rumble();
+        rumble.insurance_pool = 30_000_000;
+        let mut bettor = insured_bettor(1, 200_000_000);
+        bettor.insurance_premium_paid = 10_000_000;
+
+        // Cancelled: the premium comes back on top of the net stake.
+        rumble.state = RumbleState::Cancelled;
+        assert_eq!(
+            bettor_claim_entitlement(&rumble, &bettor).unwrap(),
+            210_000_000
+        );
+
+        // Refund-all results do the same.
+        rumble.state = RumbleState::Payout;
+        rumble.underfunded = true;
+        assert_eq!(
+            calculate_claim_lamports(&rumble, &bettor).unwrap(),
+            210_000_000
+        );
+
+        // A refunded premium leaves the insurance pool.
+        release_refunded_premium(&mut rumble, &bettor).unwrap();
+        assert_eq!(rumble.insurance_pool, 20_000_000);
+    }
+
+    #[test]
+    fn crank_tip_is_the_crankers_only_cut() {
+        for claimable in [1, 99, 10_000, 1_234_567_891] {
+            for tip_bps in [0, 1, 50, MAX_CRANK_TIP_BPS] {
+                let (to_bettor, tip) = split_crank_tip(claimable, tip_bps).unwrap();
+                assert_eq!(to_bettor + tip, claimable);
+                assert!(tip <= claimable / 100);
+            }
+        }
+
+        let err = split_crank_tip(10_000, MAX_CRANK_TIP_BPS + 1).unwrap_err();
+        assert_eq!(err, error!(RumbleError::CrankTipTooHigh));
+    }
+
+    #[test]
+    fn token_gate_checks_balance_only_when_set() {
+        assert!(check_token_gate(0, None).is_ok());
+        assert_eq!(
+            check_token_gate(500, None).unwrap_err(),
+            error!(RumbleError::TokenGateAccountRequired)
+        );
+        assert_eq!(
+            check_token_gate(500, Some(499)).unwrap_err(),
+            error!(RumbleError::TokenGateNotMet)
+        );
+        assert!(check_token_gate(500, Some(500)).is_ok());
+    }
+
+    #[test]
+    fn winner_pool_reads_zero_when_no_one_backed_the_winner() {
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[2, 3, 1, 4]);
+        rumble.winner_index = 2;
+
+        assert_eq!(winner_pool_lamports(&rumble).unwrap(), 0);
+    }
+
+    #[test]
+    fn winner_pool_reads_positive_balance_when_winner_has_claims() {
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[0] = 980_000_000;
+        rumble.winner_index = 0;
+
+        assert_eq!(winner_pool_lamports(&rumble).unwrap(), 980_000_000);
+    }
+
+    #[test]
+    fn disputes_open_for_twelve_hours_on_admin_results() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Payout;
+        rumble.result_source = RESULT_SOURCE_ADMIN;
+        rumble.completed_at = 1_000;
+
+        assert!(require_dispute_window(&rumble, 1_000).is_ok());
+        assert!(require_dispute_window(&rumble, 1_000 + DISPUTE_WINDOW_SECONDS - 1).is_ok());
+        assert_eq!(
+            require_dispute_window(&rumble, 1_000 + DISPUTE_WINDOW_SECONDS).unwrap_err(),
+            error!(RumbleError::DisputeWindowClosed)
+        );
+
+        rumble.state = RumbleState::Complete;
+        assert_eq!(
+            require_dispute_window(&rumble, 1_000).unwrap_err(),
+            error!(RumbleError::ResultNotDisputable)
+        );
+        rumble.state = RumbleState::Payout;
+        rumble.result_source = RESULT_SOURCE_UNSET;
+        assert_eq!(
+            require_dispute_window(&rumble, 1_000).unwrap_err(),
+            error!(RumbleError::ResultNotDisputable)
+        );
+    }
+
+    #[test]
+    fn claims_on_admin_results_wait_out_the_dispute_window() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Payout;
+        rumble.result_source = RESULT_SOURCE_ADMIN;
+        rumble.completed_at = 1_000;
+        rumble.claim_window_seconds = PAYOUT_CLAIM_WINDOW_SECONDS;
+        let window_end = 1_000 + DISPUTE_WINDOW_SECONDS;
+
+        // A claim seconds after the result would put it beyond correction.
+        assert_eq!(
+            require_claims_open(&rumble, 1_005).unwrap_err(),
+            error!(RumbleError::DisputeWindowOpen)
+        );
+        assert_eq!(
+            require_claims_open(&rumble, window_end - 1).unwrap_err(),
+            error!(RumbleError::DisputeWindowOpen)
+        );
+        assert!(require_claims_open(&rumble, window_end).is_ok());
+
+        // The claim window starts once claims open.
+        assert_eq!(
+            require_claim_window_elapsed(&rumble, 1_000 + PAYOUT_CLAIM_WINDOW_SECONDS)
+                .unwrap_err(),
+            error!(RumbleError::ClaimWindowActive)
+        );
+        assert!(
+            require_claim_window_elapsed(&rumble, window_end + PAYOUT_CLAIM_WINDOW_SECONDS).is_ok()
+        );
+
+        // An open dispute holds claims past the window; resolving it frees them.
+        rumble.dispute_open = true;
+        assert_eq!(
+            require_claims_open(&rumble, window_end).unwrap_err(),
+            error!(RumbleError::DisputeUnresolved)
+        );
+        rumble.dispute_open = false;
+        rumble.dispute_resolved = true;
+        assert!(require_claims_open(&rumble, 1_005).is_ok());
+
+        // Only admin-set results can be disputed; others open at once.
+        rumble.dispute_resolved = false;
+        rumble.result_source = RESULT_SOURCE_UNSET;
+        assert!(require_claims_open(&rumble, 1_000).is_ok());
+    }
+
+    #[test]
+    fn corrected_result_moves_the_treasury_cut_with_it() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Payout;
+        rumble.betting_pools[..4].copy_from_slice(&[100_000_000, 300_000_000, 0, 600_000_000]);
+        rumble.total_deployed = 1_000_000_000;
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        let before = result_treasury_transfer(&rumble).unwrap();
+
+        // The heavily backed fighter actually won: less stake misses, so
+        // the cut shrinks and the admin tops the vault back up.
+        rumble.placements[..4].copy_from_slice(&[4, 2, 3, 1]);
+        rumble.winner_index = 3;
+        let after = result_treasury_transfer(&rumble).unwrap();
+        assert!(after < before);
+        assert_eq!(
+            after + result_stake_bonus_reserve(&rumble).unwrap(),
+            result_treasury_cut(&rumble).unwrap()
+        );
+    }
+
+    #[test]
+    fn vault_minimum_tracks_unclaimed_winnings() {
+        let rent = 890_880;
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[..4].copy_from_slice(&[300_000_000, 200_000_000, 0, 500_000_000]);
+        rumble.total_deployed = 1_000_000_000;
+        rumble.vault_rent_reserve = rent;
+        rumble.stake_bonus_reserve = result_stake_bonus_reserve(&rumble).unwrap();
+
+        // Straight after the result: every stake less the treasury transfer.
+        let vault = rent + rumble.total_deployed - result_treasury_transfer(&rumble).unwrap();
+        assert_eq!(expected_vault_minimum(&rumble).unwrap(), vault);
+
+        // A claim lowers the floor by exactly what it paid.
+        let mut first = [0u64; MAX_FIGHTERS];
+        first[0] = 300_000_000;
+        let paid = calculate_bettor_payout(&rumble, &first).unwrap();
+        let (returned_stake, _) = calculate_bettor_winnings(&rumble, &first).unwrap();
+        record_winner_claim(&mut rumble, returned_stake, paid).unwrap();
+        assert_eq!(expected_vault_minimum(&rumble).unwrap(), vault - paid);
+
+        // A drained vault would be caught; a forced sweep resets the floor.
+        assert!(vault - paid - 1 < expected_vault_minimum(&rumble).unwrap());
+        rumble.swept_lamports = 1;
+        assert_eq!(expected_vault_minimum(&rumble).unwrap(), rent);
+    }
+
+    #[test]
+    fn rumble_completes_once_whoever_calls_first() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Payout;
+        rumble.completed_at = 1_000;
+        let mut config = sample_config();
+        let total_before = config.total_rumbles;
+        let now = 1_000 + PAYOUT_CLAIM_WINDOW_SECONDS;
+
+        assert_eq!(
+            mark_rumble_complete(&mut rumble, &mut config, None, now - 1).unwrap_err(),
+            error!(RumbleError::ClaimWindowActive)
+        );
+
+        // Keeper lands first; the admin's complete_rumble then loses the race.
+        mark_rumble_complete(&mut rumble, &mut config, None, now).unwrap();
+        assert!(rumble.state == RumbleState::Complete);
+        assert_eq!(
+            mark_rumble_complete(&mut rumble, &mut config, None, now).unwrap_err(),
+            error!(RumbleError::InvalidStateTransition)
+        );
+        assert_eq!(config.total_rumbles, total_before + 1);
+    }
+
+    #[test]
+    fn claim_window_follows_the_rumble() {
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[..2].copy_from_slice(&[600_000_000, 400_000_000]);
+        rumble.state = RumbleState::Payout;
+        rumble.completed_at = 1_000;
+        let mut config = sample_config();
+        let ten_minutes = 1_000 + MIN_CLAIM_WINDOW_SECONDS;
+
+        // A test rumble closes after 10 minutes.
+        validate_claim_window(MIN_CLAIM_WINDOW_SECONDS).unwrap();
+        rumble.claim_window_seconds = MIN_CLAIM_WINDOW_SECONDS;
+        require_winner_sweep_allowed(&rumble, true, ten_minutes).unwrap();
+        let mut quick = rumble.clone();
+        mark_rumble_complete(&mut quick, &mut config, None, ten_minutes).unwrap();
+        assert!(quick.state == RumbleState::Complete);
+
+        // A 7-day rumble is still open to claims.
+        validate_claim_window(7 * 86_400).unwrap();
+        rumble.claim_window_seconds = 7 * 86_400;
+        assert_eq!(
+            require_winner_sweep_allowed(&rumble, true, ten_minutes).unwrap_err(),
+            error!(RumbleError::ClaimWindowActive)
+        );
+        assert_eq!(
+            mark_rumble_complete(&mut rumble, &mut config, None, ten_minutes).unwrap_err(),
+            error!(RumbleError::ClaimWindowActive)
+        );
+
+        // Legacy rumbles fall back to 24 hours.
+        rumble.claim_window_seconds = 0;
+        assert_eq!(claim_window_seconds(&rumble), PAYOUT_CLAIM_WINDOW_SECONDS);
+
+        for seconds in [
+            0,
+            MIN_CLAIM_WINDOW_SECONDS - 1,
+            MAX_CLAIM_WINDOW_SECONDS + 1,
+        ] {
+            assert_eq!(
+                validate_claim_window(seconds).unwrap_err(),
+                error!(RumbleError::InvalidClaimWindow)
+            );
+        }
+    }
+
+    #[test]
+    fn sweep_plus_claims_and_rent_accounts_for_every_deposit() {
+        let rent = 890_880;
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[..4].copy_from_slice(&[300_000_000, 200_000_000, 0, 500_000_000]);
+        rumble.total_deployed = 1_000_000_000;
+        let deposits = rent + rumble.total_deployed;
+
+        // Treasury cut leaves at result time; winners then claim.
+        let cut = result_treasury_transfer(&rumble).unwrap();
+        let mut first = [0u64; MAX_FIGHTERS];
+        first[0] = 300_000_000;
+        let mut second = [0u64; MAX_FIGHTERS];
+        second[1] = 200_000_000;
+        let claims = calculate_bettor_payout(&rumble, &first).unwrap()
+            + calculate_bettor_payout(&rumble, &second).unwrap();
+        let mut vault = deposits - cut - claims;
+
+        let swept = sweepable_lamports(vault, rent, 0).unwrap();
+        vault -= swept;
+        rumble.swept_lamports += swept;
+        assert_eq!(cut + claims + swept + rent, deposits);
+        assert_eq!(vault, rent);
+
+        // A second sweep finds nothing left.
+        assert_eq!(sweepable_lamports(vault, rent, 0).unwrap(), 0);
+        assert_eq!(rumble.swept_lamports, swept);
+    }
+
+    #[test]
+    fn sweep_waits_for_every_winner_to_claim() {
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[..2].copy_from_slice(&[600_000_000, 400_000_000]);
+        rumble.completed_at = 1_000;
+        let now = 1_000 + PAYOUT_CLAIM_WINDOW_SECONDS;
+
+        let mut first = [0u64; MAX_FIGHTERS];
+        first[0] = 200_000_000;
+        let mut second = [0u64; MAX_FIGHTERS];
+        second[0] = 400_000_000;
+
+        // One of two winners has claimed.
+        let paid = calculate_bettor_payout(&rumble, &first).unwrap();
+        let (returned_stake, _) = calculate_bettor_winnings(&rumble, &first).unwrap();
+        record_winner_claim(&mut rumble, returned_stake, paid).unwrap();
+        assert_eq!(
+            rumble.winner_pool_snapshot,
+            winner_payout_total(&rumble).unwrap()
+        );
+        assert_eq!(
+            require_winner_sweep_allowed(&rumble, false, now).unwrap_err(),
+            error!(RumbleError::UnclaimedPayoutsRemain)
+        );
+        assert_eq!(
+            require_winner_sweep_allowed(&rumble, true, now - 1).unwrap_err(),
+            error!(RumbleError::ClaimWindowActive)
+        );
+        assert_eq!(
+            require_winner_sweep_allowed(&rumble, true, now).unwrap(),
+            rumble.winner_pool_snapshot - paid
+        );
+
+        // Once the other claims, the sweep goes through without force.
+        let paid = calculate_bettor_payout(&rumble, &second).unwrap();
+        let (returned_stake, _) = calculate_bettor_winnings(&rumble, &second).unwrap();
+        record_winner_claim(&mut rumble, returned_stake, paid).unwrap();
+        assert!(require_winner_sweep_allowed(&rumble, false, now).unwrap() <= 2);
+    }
+
+    fn empty_bettor_index() -> BettorIndex {
+        BettorIndex {
+            wallet: Pubkey::new_unique(),
+            head: 0,
+            count: 0,
+            bump: 255,
+            rumble_ids: [0; BETTOR_INDEX_LEN],
+            claimed: [false; BETTOR_INDEX_LEN],
+        }
+    }
+
+    #[test]
+    fn global_stats_count_rumbles_bets_and_new_bettors() {
+        let mut stats = GlobalStats {
+            total_rumbles: 0,
+            total_bets_lamports: 0,
+            total_bettors: 0,
+            total_fighters_entered: 0,
+            total_sponsorship_lamports: 0,
+            completed_rumbles: 0,
+            bump: 255,
+        };
+        record_global_rumble(&mut stats, 8).unwrap();
+        record_global_rumble(&mut stats, 4).unwrap();
+        record_global_bet(&mut stats, 1_000_000, 50_000, true).unwrap();
+        record_global_bet(&mut stats, 2_000_000, 100_000, false).unwrap();
+
+        assert_eq!((stats.total_rumbles, stats.total_fighters_entered), (2, 12));
+        assert_eq!(stats.total_bets_lamports, 3_000_000);
+        assert_eq!(stats.total_sponsorship_lamports, 150_000);
+        assert_eq!(stats.total_bettors, 1);
+
+        stats.total_bets_lamports = u64::MAX;
+        assert_eq!(
+            record_global_bet(&mut stats, 1, 0, false).unwrap_err(),
+            error!(RumbleError::MathOverflow)
+        );
+    }
+
+    #[test]
+    fn place_bet_caps_bets_per_slot() {
+        let mut rate_limit = BettorRateLimit {
+            last_bet_slot: 0,
+            bets_this_slot: 0,
+            bump: 255,
+        };
+
+        for _ in 0..MAX_BETS_PER_SLOT {
+            record_slot_bet(&mut rate_limit, 500).unwrap();
+        }
+        assert_eq!(
+            record_slot_bet(&mut rate_limit, 500).unwrap_err(),
+            error!(RumbleError::BetRateLimited)
+        );
+        assert_eq!(rate_limit.bets_this_slot, MAX_BETS_PER_SLOT);
+
+        // The next slot starts a fresh count.
+        record_slot_bet(&mut rate_limit, 501).unwrap();
+        assert_eq!(rate_limit.last_bet_slot, 501);
+        assert_eq!(rate_limit.bets_this_slot, 1);
+    }
+
+    #[test]
+    fn bettor_stats_count_rumbles_once_and_only_winning_claims() {
+        let mut stats = BettorStats {
+            bettor: Pubkey::new_unique(),
+            total_bets: 0,
+            total_deployed_lamports: 0,
+            total_claimed_lamports: 0,
+            wins: 0,
+            losses: 0,
+            rumbles_entered: 0,
+            bump: 255,
+        };
+        record_bettor_stats_bet(&mut stats, 900_000, true).unwrap();
+        record_bettor_stats_bet(&mut stats, 450_000, false).unwrap();
+        record_bettor_stats_bet(&mut stats, 100_000, true).unwrap();
+        assert_eq!(stats.total_bets, 3);
+        assert_eq!(stats.total_deployed_lamports, 1_450_000);
+        assert_eq!(stats.rumbles_entered, 2);
+
+        record_bettor_stats_claim(&mut stats, 2_000_000, true).unwrap();
+        record_bettor_stats_claim(&mut stats, 100_000, false).unwrap();
+        assert_eq!(stats.total_claimed_lamports, 2_100_000);
+        assert_eq!(stats.wins, 1);
+    }
+
+    #[test]
+    fn bettor_index_lists_each_rumble_once() {
+        let mut index = empty_bettor_index();
+        record_bettor_index(&mut index, 7);
+        record_bettor_index(&mut index, 8);
+        record_bettor_index(&mut index, 7);
+
+        assert_eq!(index.count, 2);
+        assert_eq!(index.head, 2);
+        assert_eq!(&index.rumble_ids[..2], &[7, 8]);
+
+        mark_bettor_index_claimed(&mut index, 8);
+        mark_bettor_index_claimed(&mut index, 99);
+        assert_eq!(&index.claimed[..3], &[false, true, false]);
+    }
+
+    #[test]
+    fn bettor_index_wraps_over_the_oldest_rumble() {
+        let mut index = empty_bettor_index();
+        for rumble_id in 1..=BETTOR_INDEX_LEN as u64 {
+            record_bettor_index(&mut index, rumble_id);
+        }
+        mark_bettor_index_claimed(&mut index, 1);
+        assert_eq!(index.count as usize, BETTOR_INDEX_LEN);
+        assert_eq!(index.head, 0);
+        assert!(index.claimed[0]);
+
+        // Rumble 1 drops out; its slot (and claimed flag) go to the new rumble.
+        record_bettor_index(&mut index, 100);
+        assert_eq!(index.head, 1);
+        assert_eq!(index.count as usize, BETTOR_INDEX_LEN);
+        assert_eq!(index.rumble_ids[0], 100);
+        assert!(!index.claimed[0]);
+        assert!(!index.rumble_ids.contains(&1));
+
+        // A rumble still in the ring is not re-added after the wrap.
+        record_bettor_index(&mut index, 2);
+        assert_eq!(index.head, 1);
+    }
+
+    fn exacta_ticket(first_index: u8, second_index: u8, amount: u64) -> ExactaTicket {
+        ExactaTicket {
+            rumble_id: 1,
+            bettor: Pubkey::new_unique(),
+            first_index,
+            second_index,
+            amount,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn exacta_pool_rolls_to_treasury_when_nobody_hits() {
+        assert_eq!(exacta_settlement(750_000_000, 0).unwrap(), (750_000_000, 0));
+
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[2, 1, 3, 4]);
+        rumble.winner_index = 1;
+        rumble.exacta_pool = 750_000_000;
+        rumble.exacta_settled = true;
+        let ticket = exacta_ticket(1, 0, 750_000_000);
+        assert_eq!(exacta_ticket_payout(&rumble, &ticket).unwrap(), 0);
+        assert!(!exacta_claims_outstanding(&rumble));
+    }
+
+    #[test]
+    fn exacta_pays_exact_order_only_after_cut_on_missed_stakes() {
+        // 1 SOL missed, 0.5 SOL split 3:2 across two winning tickets.
+        let (treasury_cut, distributable) = exacta_settlement(1_500_000_000, 500_000_000).unwrap();
+        assert_eq!(treasury_cut, 30_000_000);
+        assert_eq!(distributable, 1_470_000_000);
+
+        let mut rumble = sample_rumble();
+        rumble.placements[..4].copy_from_slice(&[2, 1, 3, 4]);
+        rumble.winner_index = 1;
+        rumble.exacta_pool = 1_500_000_000;
+        rumble.exacta_settled = true;
+        rumble.exacta_winning_stake = 500_000_000;
+        rumble.exacta_distributable = distributable;
+
+        let big = exacta_ticket(1, 0, 300_000_000);
+        let small = exacta_ticket(1, 0, 200_000_000);
+        assert_eq!(exacta_ticket_payout(&rumble, &big).unwrap(), 882_000_000);
+        assert_eq!(exacta_ticket_payout(&rumble, &small).unwrap(), 588_000_000);
+        assert_eq!(
+            exacta_ticket_payout(&rumble, &exacta_ticket(0, 1, 1)).unwrap(),
+            0
+        );
+        assert_eq!(
+            exacta_ticket_payout(&rumble, &exacta_ticket(1, 2, 1)).unwrap(),
+            0
+        );
+
+        assert!(exacta_claims_outstanding(&rumble));
+        rumble.exacta_claimed_stake = 500_000_000;
+        assert!(!exacta_claims_outstanding(&rumble));
+    }
+
+    #[test]
+    fn exacta_pick_needs_two_distinct_fighters() {
+        let rumble = sample_rumble();
+        let count = rumble.fighter_count;
+        assert!(validate_exacta_pick(&rumble, 0, 1).is_ok());
+        for (first, second) in [(2, 2), (0, count), (count, 0)] {
+            assert_eq!(
+                validate_exacta_pick(&rumble, first, second).unwrap_err(),
+                error!(RumbleError::InvalidExactaPick)
+            );
+        }
+    }
+
+    #[test]
+    fn unsettled_exacta_pool_blocks_close_unless_refunded() {
+        let mut rumble = sample_rumble();
+        assert!(!exacta_claims_outstanding(&rumble));
+        rumble.exacta_pool = 100_000_000;
+        assert!(exacta_claims_outstanding(&rumble));
+        rumble.underfunded = true;
+        assert!(!exacta_claims_outstanding(&rumble));
+    }
+
+    #[test]
+    fn validate_result_rejects_duplicate_first_place() {
+        let placements = [1, 1, 3, 4];
+        let err = validate_result_placements(&placements, 4, 0).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    }
+
+    #[test]
+    fn validate_result_rejects_duplicate_rankings() {
+        let placements = [1, 2, 2, 4];
+        let err = validate_result_placements(&placements, 4, 0).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    }
+
+    #[test]
+    fn validate_result_rejects_zero_placement() {
+        let placements = [1, 2, 0, 4];
+        let err = validate_result_placements(&placements, 4, 0).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    }
+
+    #[test]
+    fn validate_result_rejects_out_of_range_placement() {
+        let placements = [1, 2, 3, 5];
+        let err = validate_result_placements(&placements, 4, 0).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    }
+
+    #[test]
+    fn payout_breakdown_requires_valid_result_shape() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..4]
+            .copy_from_slice(&[980_000_000, 490_000_000, 245_000_000, 245_000_000]);
+        rumble.placements[..4].copy_from_slice(&[1, 1, 3, 4]);
+
+        let err = calculate_payout_breakdown(&rumble).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    }
+
+    #[test]
+    fn payout_breakdown_uses_single_winner_take_all_math() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..4]
+            .copy_from_slice(&[980_000_000, 490_000_000, 245_000_000, 245_000_000]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let (first_pool, losers_pool, treasury_cut, distributable) =
+            calculate_payout_breakdown(&rumble).unwrap();
+        assert_eq!(first_pool, 980_000_000);
+        assert_eq!(losers_pool, 980_000_000);
+        assert_eq!(treasury_cut, 29_400_000);
+        assert_eq!(distributable, 950_600_000);
+    }
+
+    #[test]
+    fn payout_split_rewards_second_and_third_place_bettors() {
+        let mut rumble = sample_rumble();
+        rumble.first_place_bps = 7_000;
+        rumble.second_place_bps = 2_000;
+        rumble.third_place_bps = 1_000;
+        rumble.betting_pools[..4].copy_from_slice(&[100_000, 100_000, 100_000, 1_000_000]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let (_, losers_pool, treasury_cut, distributable) =
+            calculate_payout_breakdown(&rumble).unwrap();
+        assert_eq!(losers_pool, 1_000_000);
+        assert_eq!(treasury_cut, 30_000);
+        assert_eq!(distributable, 970_000);
+
+        let mut deployments = [0u64; MAX_FIGHTERS];
+        deployments[1] = 50_000;
+        assert_eq!(
+            calculate_bettor_payout(&rumble, &deployments).unwrap(),
+            50_000 + 97_000
+        );
+    }
+
+    #[test]
+    fn prize_pool_is_added_to_distributable() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..4].copy_from_slice(&[100_000, 0, 0, 1_000_000]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.prize_pool = 500_000;
+
+        let (_, losers_pool, treasury_cut, distributable) =
+            calculate_payout_breakdown(&rumble).unwrap();
+        assert_eq!(losers_pool, 1_000_000);
+        assert_eq!(treasury_cut, 30_000);
+        assert_eq!(distributable, 1_470_000);
+
+        let mut deployments = [0u64; MAX_FIGHTERS];
+        deployments[0] = 100_000;
+        assert_eq!(
+            calculate_bettor_payout(&rumble, &deployments).unwrap(),
+            100_000 + 1_470_000
+        );
+    }
+
+    #[test]
+    fn prize_pool_goes_back_to_its_depositor_only_when_nobody_can_win_it() {
+        let mut rumble = sample_rumble();
+        rumble.prize_pool = 500_000;
+        rumble.prize_depositor = Pubkey::new_unique();
+
+        // Still up for grabs while betting or fighting.
+        for state in [RumbleState::Betting, RumbleState::Combat] {
+            rumble.state = state;
+            assert!(!prize_pool_refundable(&rumble).unwrap());
+        }
+
+        // Cancelled, or completed with nobody on a paying place.
+        rumble.state = RumbleState::Cancelled;
+        assert!(prize_pool_refundable(&rumble).unwrap());
+        rumble.state = RumbleState::Complete;
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.betting_pools[3] = 1_000_000;
+        assert!(prize_pool_refundable(&rumble).unwrap());
+        rumble.underfunded = true;
+        rumble.betting_pools[0] = 100_000;
+        assert!(prize_pool_refundable(&rumble).unwrap());
+
+        // Winners share it instead.
+        rumble.underfunded = false;
+        assert!(!prize_pool_refundable(&rumble).unwrap());
+    }
+
+    #[test]
+    fn unbacked_second_place_share_collapses_into_first() {
+        let mut rumble = sample_rumble();
+        rumble.first_place_bps = 8_000;
+        rumble.second_place_bps = 2_000;
+        rumble.betting_pools[..4].copy_from_slice(&[100_000, 0, 500_000, 500_000]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let (_, allocations) = calculate_place_allocations(&rumble).unwrap();
+        assert_eq!(allocations, [970_000, 0, 0]);
+    }
+
+    #[test]
+    fn top3_claims_never_exceed_distributable() {
+        let splits = [
+            [10_000, 0, 0],
+            [5_000, 3_000, 2_000],
+            [3_333, 3_333, 3_334],
+            [7_000, 2_000, 1_000],
+        ];
+        let mut bettors = [[0u64; MAX_FIGHTERS]; 7];
+        for (b, deployments) in bettors.iter_mut().enumerate() {
+            for (f, stake) in deployments[..5].iter_mut().enumerate() {
+                // Awkward, uneven stakes; some bettors back several placed fighters.
+                if (b + f) % 3 != 0 {
+                    *stake = ((b as u64 + 1) * 7_919 + (f as u64 + 1) * 104_729) % 1_000_003 + 1;
+                }
+            }
+        }
+
+        for [first_bps, second_bps, third_bps] in splits {
+            let mut rumble = sample_rumble();
+            rumble.fighter_count = 5;
+            rumble.first_place_bps = first_bps;
+            rumble.second_place_bps = second_bps;
+            rumble.third_place_bps = third_bps;
+            rumble.placements[..5].copy_from_slice(&[3, 1, 5, 2, 4]);
+            rumble.winner_index = 1;
+            for deployments in bettors.iter() {
+                for (pool, stake) in rumble.betting_pools.iter_mut().zip(deployments.iter()) {
+                    *pool += stake;
+                }
+            }
+
+            let (_, _, _, distributable) = calculate_payout_breakdown(&rumble).unwrap();
+            let winner_pool = winner_pool_lamports(&rumble).unwrap();
+            let total_claimed: u64 = bettors
+                .iter()
+                .map(|deployments| calculate_bettor_payout(&rumble, deployments).unwrap())
+                .sum();
+            let total_winnings = total_claimed - winner_pool;
+
+            assert!(total_winnings <= distributable);
+            // Floor division loses at most one lamport per (bettor, place) share.
+            assert!(distributable - total_winnings <= (bettors.len() * 3 + 3) as u64);
+        }
+    }
+
+    #[test]
+    fn stake_multiplier_follows_lock_tier_while_locked() {
+        let mut stake = IchorStake {
+            staker: Pubkey::new_unique(),
+            staked_amount: 1_000,
+            staked_at: 1_000,
+            lock_period_seconds: 30 * 86_400,
+            yield_bps: 100,
+            bump: 255,
+        };
+        let mut clock = Clock {
+            unix_timestamp: 2_000,
+            ..Clock::default()
+        };
+        assert_eq!(bettor_stake_multiplier(&stake, &clock), 100);
+
+        stake.lock_period_seconds = 365 * 86_400;
+        assert_eq!(bettor_stake_multiplier(&stake, &clock), MAX_STAKE_BONUS_BPS);
+        stake.lock_period_seconds = 86_400;
+        assert_eq!(bettor_stake_multiplier(&stake, &clock), 0);
+
+        stake.lock_period_seconds = 7 * 86_400;
+        assert_eq!(bettor_stake_multiplier(&stake, &clock), 50);
+        clock.unix_timestamp = stake.staked_at + stake.lock_period_seconds;
+        assert_eq!(bettor_stake_multiplier(&stake, &clock), 0);
+
+        clock.unix_timestamp = 2_000;
+        stake.staked_amount = 0;
+        assert_eq!(bettor_stake_multiplier(&stake, &clock), 0);
+    }
+
+    #[test]
+    fn stake_bonus_reserve_covers_max_bonus_for_every_winner() {
+        let mut rumble = sample_rumble();
+        rumble.first_place_bps = 5_000;
+        rumble.second_place_bps = 3_000;
+        rumble.third_place_bps = 2_000;
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let mut bettors = Vec::new();
+        for (fighter, stake) in [1_000_000, 700_000, 300_000, 9_000_000].iter().enumerate() {
+            let mut bettor = sample_bettor();
+            bettor.fighter_deployments[fighter] = *stake;
+            bettor.sol_deployed = *stake;
+            rumble.betting_pools[fighter] = *stake;
+            bettors.push(bettor);
+        }
+
+        let reserve = result_stake_bonus_reserve(&rumble).unwrap();
+        assert!(reserve > 0 && reserve <= result_treasury_cut(&rumble).unwrap());
+
+        let mut remaining = reserve;
+        for bettor in &bettors {
+            let deployments = bettor_deployments(bettor);
+            let (_, winnings) = calculate_bettor_winnings(&rumble, &deployments).unwrap();
+            let bonus = stake_bonus_lamports(winnings, MAX_STAKE_BONUS_BPS, remaining).unwrap();
+            assert_eq!(bonus, fee_lamports(winnings, MAX_STAKE_BONUS_BPS).unwrap());
+            remaining -= bonus;
+        }
+        assert_eq!(stake_bonus_lamports(1_000_000, 200, 7).unwrap(), 7);
+
+        rumble.betting_pools = [0; MAX_FIGHTERS];
+        rumble.betting_pools[3] = 9_000_000;
+        assert_eq!(result_stake_bonus_reserve(&rumble).unwrap(), 0);
+    }
+
+    #[test]
+    fn winner_claims_leave_treasury_cut_and_rent_reserve_in_vault() {
+        let rent_reserve = 890_880;
+        let mut rumble = sample_rumble();
+        rumble.first_place_bps = 5_000;
+        rumble.second_place_bps = 3_000;
+        rumble.third_place_bps = 2_000;
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.winner_index = 0;
+        rumble.vault_rent_reserve = rent_reserve;
+
+        let mut bettors = Vec::new();
+        for (fighter, stake) in [1_000_000, 1_000_000, 1_000_000, 2_000_000].iter().enumerate() {
+            let mut bettor = sample_bettor();
+            bettor.fighter_deployments[fighter] = *stake;
+            bettor.sol_deployed = *stake;
+            rumble.betting_pools[fighter] = *stake;
+            bettors.push(bettor);
+        }
+        rumble.total_deployed = rumble.betting_pools.iter().sum();
+
+        let treasury_cut = result_treasury_cut(&rumble).unwrap();
+        let mut vault = rent_reserve + rumble.total_deployed;
+        for bettor in &bettors {
+            let claim = calculate_claim_lamports(&rumble, bettor).unwrap();
+            assert!(vault_available_lamports(vault, &rumble).unwrap() >= claim);
+            vault -= claim;
+        }
+        assert_eq!(vault, treasury_cut + rent_reserve);
+
+        vault -= treasury_cut;
+        assert_eq!(vault_available_lamports(vault, &rumble).unwrap(), 0);
+        assert!(vault_available_lamports(vault - 1, &rumble).is_err());
+    }
+
+    #[test]
+    fn rumble_layout_width_follows_account_length() {
+        assert_eq!(RUMBLE_LEN, 1_293);
+        assert_eq!(
+            RUMBLE_V2_LEN,
+            RUMBLE_LEN + (MAX_FIGHTERS - MAX_FIGHTERS_V1) * RUMBLE_FIGHTER_SLOT_LEN + 1
+        );
+
+        // 16-slot rumbles keep the original layout byte for byte.
+        let mut rumble = sample_rumble();
+        rumble.fighters[15] = Pubkey::new_unique();
+        rumble.betting_pools[15] = 9_000;
+        rumble.dispute_resolved = true;
+        let mut data = Vec::new();
+        rumble.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), RUMBLE_LEN);
+        assert_eq!(*data.last().unwrap(), 1);
+        let narrow = Rumble::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(narrow.slots(), MAX_FIGHTERS_V1);
+        assert_eq!(narrow.fighters, rumble.fighters);
+        assert_eq!(narrow.betting_pools, rumble.betting_pools);
+        assert!(narrow.dispute_resolved);
+
+        let mut rumble = sample_rumble();
+        rumble.fighter_slots = MAX_FIGHTERS as u8;
+        rumble.fighters[31] = Pubkey::new_unique();
+        rumble.insured_stake[31] = 4_000;
+        rumble.elimination_rank[20] = 3;
+        rumble.dispute_resolved = true;
+        let mut data = Vec::new();
+        rumble.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), RUMBLE_V2_LEN);
+        let wide = Rumble::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(wide.slots(), MAX_FIGHTERS);
+        assert_eq!(wide.fighters, rumble.fighters);
+        assert_eq!(wide.insured_stake, rumble.insured_stake);
+        assert_eq!(wide.elimination_rank, rumble.elimination_rank);
+        assert!(wide.dispute_resolved);
+    }
+
+    #[test]
+    fn only_the_v2_layout_takes_more_than_16_fighters() {
+        assert_eq!(requested_fighter_slots(None).unwrap(), MAX_FIGHTERS_V1);
+        assert_eq!(requested_fighter_slots(Some(16)).unwrap(), MAX_FIGHTERS_V1);
+        assert_eq!(requested_fighter_slots(Some(32)).unwrap(), MAX_FIGHTERS);
+        assert_eq!(
+            requested_fighter_slots(Some(24)).unwrap_err(),
+            error!(RumbleError::InvalidFighterSlots)
+        );
+        assert_eq!(rumble_account_len(None), RUMBLE_LEN);
+        assert_eq!(rumble_account_len(Some(32)), RUMBLE_V2_LEN);
+
+        let mut rumble = sample_rumble();
+        rumble.fighter_count = 2;
+        rumble.fighters[..2].copy_from_slice(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+        let more: Vec<Pubkey> = (0..14).map(|_| Pubkey::new_unique()).collect();
+        append_fighters(&mut rumble, &more).unwrap();
+        assert_eq!(rumble.fighter_count, 16);
+        assert_eq!(rumble.fighters[2..16], more[..]);
+        assert_eq!(
+            append_fighters(&mut rumble, &[Pubkey::new_unique()]).unwrap_err(),
+            error!(RumbleError::InvalidFighterCount)
+        );
+
+        rumble.fighter_slots = MAX_FIGHTERS as u8;
+        let existing = rumble.fighters[3];
+        assert_eq!(
+            append_fighters(&mut rumble, &[existing]).unwrap_err(),
+            error!(RumbleError::DuplicateFighter)
+        );
+        let rest: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        append_fighters(&mut rumble, &rest).unwrap();
+        assert_eq!(rumble.fighter_count as usize, MAX_FIGHTERS);
+
+        let mut rumble = sample_rumble();
+        rumble.fighter_count = 2;
+        rumble.total_deployed = 1;
+        assert_eq!(
+            append_fighters(&mut rumble, &[Pubkey::new_unique()]).unwrap_err(),
+            error!(RumbleError::FightersLocked)
+        );
+    }
+
+    #[test]
+    fn migrated_v1_payout_rumble_pays_claims_above_its_rent_reserve() {
+        let rent_reserve = 890_880;
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Payout;
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+        rumble.winner_index = 0;
+
+        let mut bettors = Vec::new();
+        for (fighter, stake) in [1_000_000, 3_000_000, 2_000_000].iter().enumerate() {
+            let mut bettor = sample_bettor();
+            bettor.fighter_deployments[fighter] = *stake;
+            bettor.sol_deployed = *stake;
+            rumble.betting_pools[fighter] = *stake;
+            bettors.push(bettor);
+        }
+        rumble.total_deployed = rumble.betting_pools.iter().sum();
+
+        // A 724-byte account from the original layout, grown and zero-filled.
+        let mut data = Vec::new();
+        rumble.try_serialize(&mut data).unwrap();
+        data.truncate(RUMBLE_V1_LEN);
+        require_migratable_rumble(&data, rumble.id).unwrap();
+        assert_eq!(
+            require_migratable_rumble(&data, rumble.id + 1).unwrap_err(),
+            error!(RumbleError::InvalidRumble)
+        );
+        data.resize(RUMBLE_LEN, 0);
+        let migrated = set_migrated_vault_rent_reserve(&mut data, rent_reserve).unwrap();
+        assert_eq!(
+            Rumble::try_deserialize(&mut &data[..]).unwrap().vault_rent_reserve,
+            rent_reserve
+        );
+        assert_eq!(migrated.placements, rumble.placements);
+        assert_eq!(migrated.betting_pools, rumble.betting_pools);
+        assert_eq!(
+            require_migratable_rumble(&data, rumble.id).unwrap_err(),
+            error!(RumbleError::RumbleAlreadyMigrated)
+        );
+
+        // The treasury cut left at result time; the reserve was funded on migration.
+        let treasury_cut = result_treasury_cut(&migrated).unwrap();
+        let mut vault = rumble.total_deployed - treasury_cut + rent_reserve;
+        for bettor in &bettors {
+            let claim = calculate_claim_lamports(&migrated, bettor).unwrap();
+            assert!(vault_available_lamports(vault, &migrated).unwrap() >= claim);
+            vault -= claim;
+        }
+        assert!(vault >= rent_reserve);
+
+        // Mid-combat rumbles can't be migrated.
+        data.truncate(RUMBLE_V1_LEN);
+        data[16] = RumbleState::Combat as u8;
+        assert_eq!(
+            require_migratable_rumble(&data, rumble.id).unwrap_err(),
+            error!(RumbleError::RumbleNotMigratable)
+        );
+    }
+
+    #[test]
+    fn validate_payout_split_requires_full_allocation() {
+        let split = PayoutSplit {
+            first_bps: 7_000,
+            second_bps: 2_000,
+            third_bps: 500,
+        };
+        let err = validate_payout_split(&split).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidPayoutSplit));
+    }
+
+    #[test]
+    fn fee_rates_are_capped_and_applied_in_bps() {
+        assert!(validate_fee_bps(MAX_FEE_BPS).is_ok());
+        let err = validate_fee_bps(MAX_FEE_BPS + 1).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFeeBps));
+
+        assert_eq!(fee_lamports(1_000_000, 250).unwrap(), 25_000);
+        assert_eq!(fee_lamports(99, ADMIN_FEE_BPS).unwrap(), 0);
+    }
+
+    #[test]
+    fn whitelist_adds_dedupes_and_removes_bettors() {
+        let mut whitelist = WhitelistAccount {
+            rumble_id: 1,
+            entries: [Pubkey::default(); MAX_WHITELIST_ENTRIES],
+            count: 0,
+            bump: 255,
+        };
+        let [a, b, c] = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        add_whitelist_entries(&mut whitelist, &[a, b, a]).unwrap();
+        assert_eq!(whitelist.count, 2);
+        assert!(whitelist_contains(&whitelist, &a) && whitelist_contains(&whitelist, &b));
+        assert!(!whitelist_contains(&whitelist, &c));
+
+        remove_whitelist_entries(&mut whitelist, &[a, c]);
+        assert_eq!(whitelist.count, 1);
+        assert!(!whitelist_contains(&whitelist, &a));
+        assert!(whitelist_contains(&whitelist, &b));
+
+        let more: Vec<Pubkey> = (0..MAX_WHITELIST_ENTRIES).map(|_| Pubkey::new_unique()).collect();
+        let err = add_whitelist_entries(&mut whitelist, &more).unwrap_err();
+        assert_eq!(err, error!(RumbleError::WhitelistFull));
+        assert_eq!(whitelist.count, 1);
+    }
+
+    #[test]
+    fn fee_exemption_waives_admin_fee_until_it_expires() {
+        let exemption = FeeExemption {
+            wallet: Pubkey::new_unique(),
+            expires_slot: 500,
+            bump: 255,
+        };
+        assert!(fee_exemption_active(&exemption, 499));
+        assert!(fee_exemption_active(&exemption, 500));
+        assert!(!fee_exemption_active(&exemption, 501));
+
+        // A referred, exempt bettor's zero admin fee leaves no referral cut.
+        let (treasury_fee, referral_fee) =
+            split_referral_fee(1_000_000, 0, REFERRAL_FEE_BPS).unwrap();
+        assert_eq!((treasury_fee, referral_fee), (0, 0));
+    }
+
+    #[test]
+    fn admin_log_wraps_after_capacity_entries() {
+        let entry = |slot: u64| AdminLogEntry {
+            instruction_tag: *b"settest!",
+            actor: Pubkey::default(),
+            at_slot: slot,
+            at_ts: 0,
+        };
+        let mut log = AdminLog {
+            config: Pubkey::new_unique(),
+            total_entries: 0,
+            entries: [entry(0); ADMIN_LOG_CAPACITY],
+            head: 0,
+            count: 0,
+            bump: 255,
+            _padding: [0; 5],
+        };
+
+        for slot in 1..=ADMIN_LOG_CAPACITY as u64 {
+            push_admin_log_entry(&mut log, entry(slot));
+        }
+        assert_eq!(log.head, 0);
+        assert_eq!(log.count as usize, ADMIN_LOG_CAPACITY);
+
+        // The 129th call overwrites the oldest entry.
+        push_admin_log_entry(&mut log, entry(129));
+        assert_eq!(log.head, 1);
+        assert_eq!(log.count as usize, ADMIN_LOG_CAPACITY);
+        assert_eq!(log.total_entries, 129);
+        assert_eq!(log.entries[0].at_slot, 129);
+        assert_eq!(log.entries[1].at_slot, 2);
+    }
+
+    #[test]
+    fn only_admin_grants_fee_exemptions() {
+        let config = sample_config();
+        assert!(validate_fee_exemption_grant(&config, &config.admin, 100, 50).is_ok());
+
+        let err =
+            validate_fee_exemption_grant(&config, &Pubkey::new_unique(), 100, 50).unwrap_err();
+        assert_eq!(err, error!(RumbleError::Unauthorized));
+        let err = validate_fee_exemption_grant(&config, &config.admin, 50, 50).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFeeExemption));
+    }
+
+    #[test]
+    fn referral_fee_is_carved_out_of_admin_fee() {
+        let amount = 1_000_000;
+        let admin_fee = fee_lamports(amount, ADMIN_FEE_BPS).unwrap();
+        let (treasury_fee, referral_fee) =
+            split_referral_fee(amount, admin_fee, REFERRAL_FEE_BPS).unwrap();
+        assert_eq!(referral_fee, 5_000);
+        assert_eq!(treasury_fee + referral_fee, admin_fee);
+
+        assert!(validate_referral_fee_bps(ADMIN_FEE_BPS, ADMIN_FEE_BPS).is_ok());
+        let err = validate_referral_fee_bps(ADMIN_FEE_BPS + 1, ADMIN_FEE_BPS).unwrap_err();
+        assert_eq!(err, error!(RumbleError::ReferralFeeExceedsAdminFee));
+    }
+
+    #[test]
+    fn referral_accounts_must_match_and_not_self_refer() {
+        let bettor = Pubkey::new_unique();
+        let referrer = Pubkey::new_unique();
+        let (pda, _) =
+            Pubkey::find_program_address(&[REFERRAL_SEED, referrer.as_ref()], &crate::ID);
+
+        assert!(validate_referral_accounts(&bettor, None, None).is_ok());
+        assert!(validate_referral_accounts(&bettor, Some(&referrer), Some(&pda)).is_ok());
+        for (referrer, account) in [
+            (Some(&referrer), None),
+            (None, Some(&pda)),
+            (Some(&referrer), Some(&bettor)),
+            (Some(&bettor), Some(&pda)),
+        ] {
+            let err = validate_referral_accounts(&bettor, referrer, account).unwrap_err();
+            assert_eq!(err, error!(RumbleError::InvalidReferrer));
+        }
+    }
+
+    #[test]
+    fn combat_timeout_is_bounded() {
+        assert!(validate_combat_timeout(MIN_COMBAT_TIMEOUT_SLOTS).is_ok());
+        assert!(validate_combat_timeout(DEFAULT_COMBAT_TIMEOUT_SLOTS).is_ok());
+        assert!(validate_combat_timeout(MAX_COMBAT_TIMEOUT_SLOTS).is_ok());
+        for slots in [0, MIN_COMBAT_TIMEOUT_SLOTS - 1, MAX_COMBAT_TIMEOUT_SLOTS + 1] {
+            assert_eq!(
+                validate_combat_timeout(slots).unwrap_err(),
+                error!(RumbleError::InvalidCombatTimeout)
+            );
+        }
+    }
 
-    #[account(mut)]
-    pub combat_state: Account<'info, RumbleCombatState>,
-}
+    #[test]
+    fn combat_windows_are_bounded() {
+        assert!(validate_combat_window(MIN_COMBAT_WINDOW_SLOTS).is_ok());
+        assert!(validate_combat_window(MAX_COMBAT_WINDOW_SLOTS).is_ok());
+        for slots in [0, MIN_COMBAT_WINDOW_SLOTS - 1, MAX_COMBAT_WINDOW_SLOTS + 1] {
+            let err = validate_combat_window(slots).unwrap_err();
+            assert_eq!(err, error!(RumbleError::InvalidCombatWindow));
+        }
+    }
 
-// ---------------------------------------------------------------------------
-// State
-// ---------------------------------------------------------------------------
+    fn sample_config() -> RumbleConfig {
+        RumbleConfig {
+            admin: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            total_rumbles: 0,
+            bump: 255,
+            admin_fee_bps: ADMIN_FEE_BPS,
+            sponsorship_fee_bps: SPONSORSHIP_FEE_BPS,
+            min_bet_lamports: 0,
+            max_bet_per_bettor_lamports: 0,
+            max_total_pool_lamports: 0,
+            paused: false,
+            commit_window_slots: COMMIT_WINDOW_SLOTS,
+            reveal_window_slots: REVEAL_WINDOW_SLOTS,
+            crank_tip_bps: 0,
+            slots_per_second_milli: DEFAULT_SLOTS_PER_SECOND_MILLI,
+            min_bettors: 0,
+            referral_fee_bps: REFERRAL_FEE_BPS,
+            ichor_burn_per_bet: 0,
+            keeper_fee_lamports: 0,
+            insurance_premium_bps: 0,
+            insurance_payout_bps: 0,
+            combat_timeout_slots: DEFAULT_COMBAT_TIMEOUT_SLOTS,
+        }
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct RumbleConfig {
-    pub admin: Pubkey,      // 32
-    pub treasury: Pubkey,   // 32
-    pub total_rumbles: u64, // 8
-    pub bump: u8,           // 1
-}
+    #[test]
+    fn zero_bet_limits_are_unlimited() {
+        let config = sample_config();
+        assert!(validate_bet_limits(&config, 1, 1, u64::MAX - 1, u64::MAX - 1).is_ok());
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Rumble {
-    pub id: u64,                  // 8
-    pub state: RumbleState,       // 1
-    pub fighters: [Pubkey; 16],   // 32 * 16 = 512
-    pub fighter_count: u8,        // 1
-    pub betting_pools: [u64; 16], // 8 * 16 = 128
-    pub total_deployed: u64,      // 8
-    pub admin_fee_collected: u64, // 8
-    pub sponsorship_paid: u64,    // 8
-    pub placements: [u8; 16],     // 16
-    pub winner_index: u8,         // 1
-    pub betting_deadline: i64,    // 8
-    pub combat_started_at: i64,   // 8
-    pub completed_at: i64,        // 8
-    pub bump: u8,                 // 1
-}
+    #[test]
+    fn bet_limits_accept_exact_boundaries() {
+        let mut config = sample_config();
+        config.min_bet_lamports = 10_000;
+        config.max_bet_per_bettor_lamports = 50_000;
+        config.max_total_pool_lamports = 100_000;
 
-#[account]
-#[derive(InitSpace)]
-pub struct BettorAccount {
-    pub authority: Pubkey,                        // 32
-    pub rumble_id: u64,                           // 8
-    pub fighter_index: u8,                        // 1 (legacy compatibility)
-    pub sol_deployed: u64,                        // 8 (total deployed across all fighters)
-    pub claimable_lamports: u64,                  // 8
-    pub total_claimed_lamports: u64,              // 8
-    pub last_claim_ts: i64,                       // 8
-    pub claimed: bool,                            // 1
-    pub bump: u8,                                 // 1
-    pub fighter_deployments: [u64; MAX_FIGHTERS], // 128
-}
+        assert!(validate_bet_limits(&config, 10_000, 9_800, 40_200, 90_200).is_ok());
 
-#[cfg(feature = "combat")]
-#[account]
-#[derive(InitSpace)]
-pub struct FighterDelegate {
-    pub fighter: Pubkey,      // 32
-    pub authority: Pubkey,    // 32
-    pub authorized_slot: u64, // 8
-    pub revoked: bool,        // 1
-    pub bump: u8,             // 1
-}
+        let err = validate_bet_limits(&config, 9_999, 9_799, 0, 0).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BetBelowMinimum));
 
-#[cfg(feature = "combat")]
-#[account]
-#[derive(InitSpace)]
-pub struct MoveCommitment {
-    pub rumble_id: u64,      // 8
-    pub fighter: Pubkey,     // 32
-    pub turn: u32,           // 4
-    pub move_hash: [u8; 32], // 32
-    pub revealed_move: u8,   // 1
-    pub revealed: bool,      // 1
-    pub committed_slot: u64, // 8
-    pub revealed_slot: u64,  // 8
-    pub bump: u8,            // 1
-}
+        let err = validate_bet_limits(&config, 10_000, 9_800, 40_201, 0).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettorCapExceeded));
 
-#[account]
-#[derive(InitSpace)]
-pub struct PendingAdminRE {
-    pub proposed_admin: Pubkey, // 32
-    pub proposed_at: u64,       // 8
-    pub bump: u8,               // 1
-}
+        let err = validate_bet_limits(&config, 10_000, 9_800, 0, 90_201).unwrap_err();
+        assert_eq!(err, error!(RumbleError::PoolCapExceeded));
+    }
 
-#[cfg(feature = "combat")]
-#[account]
-#[derive(InitSpace)]
-pub struct RumbleCombatState {
-    pub rumble_id: u64,                          // 8
-    pub fighter_count: u8,                       // 1
-    pub current_turn: u32,                       // 4
-    pub turn_open_slot: u64,                     // 8
-    pub commit_close_slot: u64,                  // 8
-    pub reveal_close_slot: u64,                  // 8
-    pub turn_resolved: bool,                     // 1
-    pub remaining_fighters: u8,                  // 1
-    pub winner_index: u8,                        // 1 (255 until known)
-    pub hp: [u16; MAX_FIGHTERS],                 // 32
-    pub meter: [u8; MAX_FIGHTERS],               // 16
-    pub elimination_rank: [u8; MAX_FIGHTERS],    // 16
-    pub total_damage_dealt: [u64; MAX_FIGHTERS], // 128
-    pub total_damage_taken: [u64; MAX_FIGHTERS], // 128
-    pub vrf_seed: [u8; 32],                      // 32
-    pub bump: u8,                                // 1
-}
+    #[test]
+    fn rumble_bettor_cap_applies_to_cumulative_stake() {
+        let mut rumble = sample_rumble();
+        assert!(validate_rumble_bettor_cap(&rumble, u64::MAX - 1, 1).is_ok());
 
-// ---------------------------------------------------------------------------
-// Enums
-// ---------------------------------------------------------------------------
+        rumble.max_bet_per_bettor = 1_000_000;
+        assert!(validate_rumble_bettor_cap(&rumble, 600_000, 400_000).is_ok());
+        let err = validate_rumble_bettor_cap(&rumble, 600_000, 400_001).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettorCapExceeded));
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
-pub enum RumbleState {
-    Betting,
-    Combat,
-    Payout,
-    Complete,
-}
+    #[test]
+    fn underfunded_rumble_refunds_net_stake_without_treasury_cut() {
+        let mut rumble = sample_rumble();
+        rumble.min_pool_lamports = 1_000_000;
+        rumble.total_deployed = 400_000;
+        rumble.betting_pools[..2].copy_from_slice(&[300_000, 100_000]);
+        assert!(!pool_meets_minimum(&rumble));
+
+        // Placements are never set for underfunded rumbles.
+        rumble.underfunded = true;
+        let mut bettor = sample_bettor();
+        bettor.sol_deployed = 300_000;
+        bettor.fighter_deployments[0] = 300_000;
+
+        assert_eq!(calculate_claim_lamports(&rumble, &bettor).unwrap(), 300_000);
+        assert_eq!(result_treasury_cut(&rumble).unwrap(), 0);
+
+        rumble.total_deployed = 1_000_000;
+        assert!(pool_meets_minimum(&rumble));
+        rumble.min_pool_lamports = 0;
+        rumble.total_deployed = 0;
+        assert!(pool_meets_minimum(&rumble));
+    }
 
-impl Default for RumbleState {
-    fn default() -> Self {
-        RumbleState::Betting
+    #[test]
+    fn losing_bettor_has_no_entitlement_once_result_is_set() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..4].copy_from_slice(&[100_000, 300_000, 0, 0]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let mut loser = sample_bettor();
+        loser.sol_deployed = 300_000;
+        loser.fighter_deployments[1] = 300_000;
+        assert_eq!(bettor_claim_entitlement(&rumble, &loser).unwrap(), 0);
+
+        rumble.state = RumbleState::Cancelled;
+        assert_eq!(bettor_claim_entitlement(&rumble, &loser).unwrap(), 300_000);
+
+        rumble.state = RumbleState::Betting;
+        let err = bettor_claim_entitlement(&rumble, &loser).unwrap_err();
+        assert_eq!(err, error!(RumbleError::PayoutNotReady));
     }
-}
 
-fn validate_result_placements(
-    placements: &[u8],
-    fighter_count: usize,
-    winner_index: u8,
-) -> Result<()> {
-    require!(
-        fighter_count > 0 && fighter_count <= MAX_FIGHTERS,
-        RumbleError::InvalidPlacement
-    );
-    require!(placements.len() == fighter_count, RumbleError::InvalidPlacement);
-    require!(
-        (winner_index as usize) < fighter_count,
-        RumbleError::InvalidFighterIndex
-    );
+    fn clock_at(slot: u64, unix_timestamp: i64) -> Clock {
+        Clock {
+            slot,
+            unix_timestamp,
+            ..Clock::default()
+        }
+    }
+
+    fn staked_rumble_and_bettor() -> (Rumble, ParsedBettorAccount) {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Betting;
+        rumble.betting_deadline = 100;
+        rumble.betting_pools[1] = 1_500_000;
+        rumble.total_deployed = 1_500_000;
+        let mut bettor = sample_bettor();
+        bettor.fighter_deployments[1] = 1_000_000;
+        bettor.sol_deployed = 1_000_000;
+        (rumble, bettor)
+    }
+
+    #[test]
+    fn partial_withdrawal_leaves_remaining_stake() {
+        let (mut rumble, mut bettor) = staked_rumble_and_bettor();
+
+        let (withdrawn, refund, penalty) = apply_stake_withdrawal(
+            &mut rumble,
+            &mut bettor,
+            1,
+            Some(400_000),
+            ADMIN_FEE_BPS,
+            &clock_at(99, 0),
+        )
+        .unwrap();
+        assert_eq!((withdrawn, refund, penalty), (400_000, 396_000, 4_000));
+        assert_eq!(bettor.fighter_deployments[1], 600_000);
+        assert_eq!(bettor.sol_deployed, 600_000);
+        assert_eq!(rumble.betting_pools[1], 1_100_000);
+        assert_eq!(rumble.total_deployed, 1_100_000);
+        assert_eq!(rumble.admin_fee_collected, 4_000);
+
+        let err = apply_stake_withdrawal(
+            &mut rumble,
+            &mut bettor,
+            1,
+            Some(600_001),
+            ADMIN_FEE_BPS,
+            &clock_at(99, 0),
+        )
+        .unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidWithdrawAmount));
+    }
+
+    #[test]
+    fn full_withdrawal_clears_stake() {
+        let (mut rumble, mut bettor) = staked_rumble_and_bettor();
+
+        let (withdrawn, _, _) = apply_stake_withdrawal(
+            &mut rumble,
+            &mut bettor,
+            1,
+            None,
+            ADMIN_FEE_BPS,
+            &clock_at(50, 0),
+        )
+        .unwrap();
+        assert_eq!(withdrawn, 1_000_000);
+        assert_eq!(bettor.fighter_deployments[1], 0);
+        assert_eq!(bettor.sol_deployed, 0);
+        assert_eq!(rumble.betting_pools[1], 500_000);
+    }
+
+    #[test]
+    fn full_withdrawal_of_an_insured_bet_refunds_the_premium() {
+        let (mut rumble, mut bettor) = staked_rumble_and_bettor();
+        rumble.insurance_pool = 30_000;
+        rumble.insured_stake[1] = 1_000_000;
+        bettor.insured_deployments[1] = 1_000_000;
+        bettor.insurance_premium_paid = 30_000;
+
+        // A partial withdrawal keeps the premium: the rest is still insured.
+        let (_, refund, penalty) = apply_stake_withdrawal(
+            &mut rumble,
+            &mut bettor,
+            1,
+            Some(400_000),
+            ADMIN_FEE_BPS,
+            &clock_at(50, 0),
+        )
+        .unwrap();
+        assert_eq!(refund + penalty, 400_000);
+        assert_eq!(bettor.insurance_premium_paid, 30_000);
+        assert_eq!(rumble.insurance_pool, 30_000);
+
+        let (withdrawn, refund, penalty) = apply_stake_withdrawal(
+            &mut rumble,
+            &mut bettor,
+            1,
+            None,
+            ADMIN_FEE_BPS,
+            &clock_at(50, 0),
+        )
+        .unwrap();
+        assert_eq!(withdrawn, 600_000);
+        assert_eq!(refund + penalty, 600_000 + 30_000);
+        assert_eq!(bettor.insurance_premium_paid, 0);
+        assert_eq!(rumble.insurance_pool, 0);
+        assert_eq!(rumble.insured_stake[1], 0);
+
+        // A later cancel owes the bettor nothing more.
+        rumble.state = RumbleState::Cancelled;
+        assert_eq!(bettor_claim_entitlement(&rumble, &bettor).unwrap(), 0);
+    }
+
+    #[test]
+    fn withdrawal_rejected_after_deadline() {
+        let (mut rumble, mut bettor) = staked_rumble_and_bettor();
+
+        let err = apply_stake_withdrawal(
+            &mut rumble,
+            &mut bettor,
+            1,
+            Some(1),
+            ADMIN_FEE_BPS,
+            &clock_at(100, 0),
+        )
+        .unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettingClosed));
+        assert_eq!(bettor.fighter_deployments[1], 1_000_000);
+    }
+
+    #[test]
+    fn betting_ends_at_the_deadline_slot() {
+        let mut rumble = sample_rumble();
+        rumble.betting_deadline = 1_000;
+
+        let err = require_betting_ended(&rumble, &clock_at(999, 5_000)).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettingNotEnded));
+        assert!(require_betting_open(&rumble, &clock_at(999, 5_000)).is_ok());
+        assert!(require_betting_ended(&rumble, &clock_at(1_000, 0)).is_ok());
+        assert!(require_betting_ended(&rumble, &clock_at(1_001, 0)).is_ok());
+        let err = require_betting_open(&rumble, &clock_at(1_000, 0)).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettingClosed));
+    }
+
+    #[test]
+    fn unix_timestamp_deadline_ignores_slot() {
+        let mut rumble = sample_rumble();
+        rumble.deadline_kind = DeadlineKind::UnixTimestamp;
+        rumble.betting_deadline = 1_700_000_000;
+
+        assert!(require_betting_open(&rumble, &clock_at(2_000_000_000, 1_699_999_999)).is_ok());
+        let err = require_betting_ended(&rumble, &clock_at(2_000_000_000, 1_699_999_999)).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettingNotEnded));
+        assert!(require_betting_ended(&rumble, &clock_at(1, 1_700_000_000)).is_ok());
+    }
+
+    #[test]
+    fn close_slot_overrides_legacy_deadline() {
+        let mut rumble = sample_rumble();
+        rumble.deadline_kind = DeadlineKind::UnixTimestamp;
+        rumble.betting_deadline = 1_700_000_000;
+        rumble.betting_close_slot = 5_000;
+
+        assert!(require_betting_open(&rumble, &clock_at(4_999, 1_800_000_000)).is_ok());
+        let err = require_betting_open(&rumble, &clock_at(5_000, 0)).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettingClosed));
+        assert!(require_betting_ended(&rumble, &clock_at(5_000, 0)).is_ok());
+    }
+
+    #[test]
+    fn timestamp_deadlines_convert_to_close_slot() {
+        let clock = clock_at(1_000, 1_700_000_000);
+
+        let slot = estimate_close_slot(DeadlineKind::Slot, 1_500, &clock, 2_500).unwrap();
+        assert_eq!(slot, 1_500);
+        // 60s at 2.5 slots/s.
+        let slot = estimate_close_slot(DeadlineKind::UnixTimestamp, 1_700_000_060, &clock, 2_500)
+            .unwrap();
+        assert_eq!(slot, 1_150);
+        // Unmigrated configs read a zero rate; fall back to the default.
+        let slot =
+            estimate_close_slot(DeadlineKind::UnixTimestamp, 1_700_000_060, &clock, 0).unwrap();
+        assert_eq!(slot, 1_150);
+
+        let err = estimate_close_slot(DeadlineKind::UnixTimestamp, 1_699_999_999, &clock, 2_500)
+            .unwrap_err();
+        assert_eq!(err, error!(RumbleError::DeadlineInPast));
+    }
+
+    #[test]
+    fn single_bettor_rumble_cannot_start_and_is_cancellable_after_close() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Betting;
+        rumble.betting_close_slot = 100;
+        rumble.min_bettors = 2;
+        rumble.unique_bettor_count = 1;
+        assert!(!bettors_meet_minimum(&rumble));
+
+        let err = cancel_underfilled(&mut rumble, &clock_at(99, 0)).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettingNotEnded));
+        assert!(rumble.state == RumbleState::Betting);
+
+        cancel_underfilled(&mut rumble, &clock_at(100, 1_234)).unwrap();
+        assert!(rumble.state == RumbleState::Cancelled);
+        assert_eq!(rumble.cancelled_at, 1_234);
+    }
+
+    #[test]
+    fn filled_rumble_cannot_be_cancelled_as_underfilled() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Betting;
+        rumble.betting_close_slot = 100;
+        rumble.min_bettors = 2;
+        rumble.unique_bettor_count = 2;
+        assert!(bettors_meet_minimum(&rumble));
+
+        let err = cancel_underfilled(&mut rumble, &clock_at(100, 0)).unwrap_err();
+        assert_eq!(err, error!(RumbleError::BettorMinimumMet));
+
+        // Legacy rumbles carry no minimum.
+        rumble.min_bettors = 0;
+        rumble.unique_bettor_count = 0;
+        assert!(bettors_meet_minimum(&rumble));
+    }
+
+    #[test]
+    fn metadata_fields_are_zero_padded_and_length_checked() {
+        let name: [u8; 64] = pad_metadata_field("Grand Finals S3").unwrap();
+        assert_eq!(&name[..15], b"Grand Finals S3");
+        assert!(name[15..].iter().all(|b| *b == 0));
+
+        let exact: [u8; 4] = pad_metadata_field("abcd").unwrap();
+        assert_eq!(&exact, b"abcd");
+        let err = pad_metadata_field::<4>("abcde").unwrap_err();
+        assert_eq!(err, error!(RumbleError::MetadataTooLong));
+    }
+
+    #[test]
+    fn zero_winner_pool_refunds_full_stake_to_losing_bettors() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..4].copy_from_slice(&[0, 300_000, 200_000, 0]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let mut bettor = sample_bettor();
+        bettor.sol_deployed = 250_000;
+        bettor.fighter_deployments[1] = 150_000;
+        bettor.fighter_deployments[2] = 100_000;
+
+        assert_eq!(calculate_claim_lamports(&rumble, &bettor).unwrap(), 250_000);
+        assert_eq!(result_treasury_cut(&rumble).unwrap(), 0);
+    }
+
+    #[test]
+    fn backed_winner_keeps_losers_out_of_payout() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..4].copy_from_slice(&[100_000, 300_000, 0, 0]);
+        rumble.placements[..4].copy_from_slice(&[1, 2, 3, 4]);
+
+        let mut bettor = sample_bettor();
+        bettor.sol_deployed = 300_000;
+        bettor.fighter_deployments[1] = 300_000;
+
+        assert_eq!(calculate_claim_lamports(&rumble, &bettor).unwrap(), 0);
+        assert_eq!(result_treasury_cut(&rumble).unwrap(), 9_000);
+    }
+
+    #[test]
+    fn refund_sums_every_fighter_deployment() {
+        let mut bettor = sample_bettor();
+        bettor.sol_deployed = 300;
+        bettor.fighter_deployments[0] = 100;
+        bettor.fighter_deployments[3] = 200;
+
+        assert_eq!(bettor_refund_lamports(&bettor).unwrap(), 300);
+    }
+
+    #[test]
+    fn refund_falls_back_to_legacy_sol_deployed() {
+        let mut bettor = sample_bettor();
+        bettor.sol_deployed = 490_000_000;
+
+        assert_eq!(bettor_refund_lamports(&bettor).unwrap(), 490_000_000);
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn final_duel_sudden_death_forces_damage_even_on_double_dodge() {
+        let none = FighterAttributes::default();
+        let (damage_to_a, damage_to_b, meter_used_a, meter_used_b) = resolve_duel(
+            MOVE_DODGE,
+            MOVE_DODGE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            true,
+            &DEFAULT_COMBAT_RULESET,
+        );
+
+        assert_eq!(damage_to_a, FINAL_DUEL_SUDDEN_DEATH_CHIP);
+        assert_eq!(damage_to_b, FINAL_DUEL_SUDDEN_DEATH_CHIP);
+        assert_eq!(meter_used_a, 0);
+        assert_eq!(meter_used_b, 0);
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn final_duel_sudden_death_boosts_real_hits() {
+        let none = FighterAttributes::default();
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_MID_STRIKE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            true,
+            &DEFAULT_COMBAT_RULESET,
+        );
+
+        assert_eq!(damage_to_a, STRIKE_DAMAGE_MID + FINAL_DUEL_SUDDEN_DEATH_BONUS);
+        assert_eq!(damage_to_b, STRIKE_DAMAGE_HIGH + FINAL_DUEL_SUDDEN_DEATH_BONUS);
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn fighter_delegate_authority_accepts_matching_delegate() {
+        let fighter = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let delegate = FighterDelegate {
+            fighter,
+            authority,
+            authorized_slot: 1,
+            revoked: false,
+            bump: 255,
+            expires_at_slot: 0,
+        };
+
+        assert!(validate_fighter_delegate_authority(&delegate, &fighter, &authority, 1).is_ok());
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn fighter_delegate_authority_rejects_wrong_authority() {
+        let fighter = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let wrong_authority = Pubkey::new_unique();
+        let delegate = FighterDelegate {
+            fighter,
+            authority,
+            authorized_slot: 1,
+            revoked: false,
+            bump: 255,
+            expires_at_slot: 0,
+        };
 
-    let mut seen = [false; MAX_FIGHTERS + 1];
-    let mut first_place_count = 0usize;
+        let err = validate_fighter_delegate_authority(&delegate, &fighter, &wrong_authority, 1)
+            .unwrap_err();
+        assert_eq!(err, error!(RumbleError::Unauthorized));
+    }
 
-    for (idx, &placement) in placements.iter().enumerate() {
-        require!(
-            placement > 0 && (placement as usize) <= fighter_count,
-            RumbleError::InvalidPlacement
-        );
-        require!(
-            !seen[placement as usize],
-            RumbleError::InvalidPlacement
-        );
-        seen[placement as usize] = true;
+    #[cfg(feature = "combat")]
+    #[test]
+    fn fighter_delegate_authority_rejects_revoked_delegate() {
+        let fighter = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let delegate = FighterDelegate {
+            fighter,
+            authority,
+            authorized_slot: 1,
+            revoked: true,
+            bump: 255,
+            expires_at_slot: 0,
+        };
 
-        if placement == 1 {
-            first_place_count += 1;
-            require!(idx == winner_index as usize, RumbleError::InvalidPlacement);
-        }
+        let err =
+            validate_fighter_delegate_authority(&delegate, &fighter, &authority, 1).unwrap_err();
+        assert_eq!(err, error!(RumbleError::FighterDelegateRevoked));
     }
 
-    require!(first_place_count == 1, RumbleError::InvalidPlacement);
-    Ok(())
-}
-
-fn validate_stored_result_placements(rumble: &Rumble) -> Result<()> {
-    let fighter_count = rumble.fighter_count as usize;
-    validate_result_placements(
-        &rumble.placements[..fighter_count],
-        fighter_count,
-        rumble.winner_index,
-    )
-}
+    #[cfg(feature = "combat")]
+    #[test]
+    fn fighter_delegate_authority_expires_after_slot() {
+        let fighter = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let delegate = FighterDelegate {
+            fighter,
+            authority,
+            authorized_slot: 1,
+            revoked: false,
+            bump: 255,
+            expires_at_slot: 500,
+        };
 
-fn winner_pool_lamports(rumble: &Rumble) -> Result<u64> {
-    validate_stored_result_placements(rumble)?;
-    let winner_idx = rumble.winner_index as usize;
-    Ok(rumble.betting_pools[winner_idx])
-}
+        assert!(validate_fighter_delegate_authority(&delegate, &fighter, &authority, 500).is_ok());
+        let err =
+            validate_fighter_delegate_authority(&delegate, &fighter, &authority, 501).unwrap_err();
+        assert_eq!(err, error!(RumbleError::FighterDelegateExpired));
+    }
 
-fn calculate_payout_breakdown(rumble: &Rumble) -> Result<(u64, u64, u64, u64)> {
-    validate_stored_result_placements(rumble)?;
+    #[cfg(feature = "combat")]
+    #[test]
+    fn move_commitment_is_read_only_for_its_stored_turn() {
+        let rumble_id = 9;
+        let fighter = Pubkey::new_unique();
+        let pda = expected_move_commitment_pda(rumble_id, &fighter);
+        assert_ne!(pda, expected_legacy_move_commitment_pda(rumble_id, &fighter, 3));
 
-    let mut losers_pool: u64 = 0;
-    let mut first_pool: u64 = 0;
+        let commitment = MoveCommitment {
+            rumble_id,
+            fighter,
+            turn: 3,
+            move_hash: [7u8; 32],
+            revealed_move: 255,
+            revealed: false,
+            committed_slot: 100,
+            revealed_slot: 0,
+            bump: 255,
+        };
+        let mut data = Vec::new();
+        commitment.try_serialize(&mut data).unwrap();
+        let mut lamports = 0u64;
+        let owner = crate::ID;
+        let info = AccountInfo::new(&pda, false, false, &mut lamports, &mut data, &owner, false, 0);
+        let accounts = [info];
+
+        let read = read_move_commitment_from_remaining_accounts(&accounts, rumble_id, 3, &fighter);
+        assert_eq!(read.unwrap().map(|c| c.move_hash), Some([7u8; 32]));
+        // The same PDA carries over to later turns, where a stale commit reads as none.
+        assert!(read_move_commitment_from_remaining_accounts(&accounts, rumble_id, 4, &fighter)
+            .unwrap()
+            .is_none());
+    }
 
-    for i in 0..rumble.fighter_count as usize {
-        let placement = rumble.placements[i];
-        let pool = rumble.betting_pools[i];
-        if placement == 1 {
-            first_pool = first_pool
-                .checked_add(pool)
-                .ok_or(RumbleError::MathOverflow)?;
-        } else {
-            losers_pool = losers_pool
-                .checked_add(pool)
-                .ok_or(RumbleError::MathOverflow)?;
+    #[cfg(feature = "combat")]
+    #[test]
+    fn closable_move_commitment_matches_current_or_legacy_pda() {
+        let rumble_id = 4;
+        let fighter = Pubkey::new_unique();
+        let commitment = MoveCommitment {
+            rumble_id,
+            fighter,
+            turn: 2,
+            move_hash: [1u8; 32],
+            revealed_move: 3,
+            revealed: true,
+            committed_slot: 10,
+            revealed_slot: 20,
+            bump: 255,
+        };
+        let owner = crate::ID;
+        for key in [
+            expected_move_commitment_pda(rumble_id, &fighter),
+            expected_legacy_move_commitment_pda(rumble_id, &fighter, 2),
+        ] {
+            let mut data = Vec::new();
+            commitment.try_serialize(&mut data).unwrap();
+            let mut lamports = 0u64;
+            let info =
+                AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+            let accounts = [info];
+
+            let entry = MoveCommitmentKey { fighter, turn: 2 };
+            let found = find_closable_move_commitment(&accounts, rumble_id, &entry).unwrap();
+            assert_eq!(*found.key, key);
+
+            let stale = MoveCommitmentKey { fighter, turn: 1 };
+            let err = find_closable_move_commitment(&accounts, rumble_id, &stale).unwrap_err();
+            assert_eq!(err, error!(RumbleError::InvalidMoveCommitment));
         }
     }
 
-    let treasury_cut = losers_pool
-        .checked_mul(TREASURY_CUT_BPS)
-        .ok_or(RumbleError::MathOverflow)?
-        .checked_div(10_000)
-        .ok_or(RumbleError::MathOverflow)?;
-    let distributable = losers_pool
-        .checked_sub(treasury_cut)
-        .ok_or(RumbleError::MathOverflow)?;
-
-    Ok((first_pool, losers_pool, treasury_cut, distributable))
-}
+    #[cfg(feature = "combat")]
+    #[test]
+    fn legacy_fighter_delegate_parses_without_expiry() {
+        let fighter = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let delegate = FighterDelegate {
+            fighter,
+            authority,
+            authorized_slot: 7,
+            revoked: false,
+            bump: 254,
+            expires_at_slot: 0,
+        };
+        let mut data = Vec::new();
+        delegate.try_serialize(&mut data).unwrap();
+        data.truncate(FIGHTER_DELEGATE_V1_LEN);
+
+        let parsed = parse_fighter_delegate(&data).unwrap();
+        assert_eq!(parsed.authority, authority);
+        assert_eq!(parsed.bump, 254);
+        assert_eq!(parsed.expires_at_slot, 0);
+        assert!(
+            validate_fighter_delegate_authority(&parsed, &fighter, &authority, u64::MAX).is_ok()
+        );
 
-fn extract_result_treasury_cut<'info>(
-    rumble: &Rumble,
-    vault_info: AccountInfo<'info>,
-    treasury_info: AccountInfo<'info>,
-    system_program_info: AccountInfo<'info>,
-    vault_bump: u8,
-) -> Result<()> {
-    let (_, _losers_pool, treasury_cut, _) = calculate_payout_breakdown(rumble)?;
-    if treasury_cut == 0 {
-        return Ok(());
+        let err = parse_fighter_delegate(&data[..FIGHTER_DELEGATE_V1_LEN - 1]).err();
+        assert_eq!(err, Some(error!(RumbleError::InvalidFighterDelegate)));
     }
 
-    // Result finalization happens before any bettor claims. Treasury extraction
-    // only needs the vault to contain the cut itself; no rent reserve is
-    // required because winner claims can fully drain the vault later.
-    let available = vault_info.lamports();
-    require!(available >= treasury_cut, RumbleError::InsufficientVaultFunds);
+    #[cfg(feature = "combat")]
+    fn sample_combat(fighter_count: u8) -> RumbleCombatState {
+        let mut combat = RumbleCombatState {
+            rumble_id: 42,
+            fighter_count,
+            current_turn: 4,
+            turn_open_slot: 0,
+            commit_close_slot: 0,
+            reveal_close_slot: 0,
+            turn_resolved: 1,
+            remaining_fighters: fighter_count,
+            winner_index: u8::MAX,
+            hp: [0; MAX_FIGHTERS],
+            meter: [0; MAX_FIGHTERS],
+            elimination_rank: [0; MAX_FIGHTERS],
+            total_damage_dealt: [0; MAX_FIGHTERS],
+            total_damage_taken: [0; MAX_FIGHTERS],
+            vrf_seed: [0; 32],
+            bump: 255,
+            turn_pairings: [PAIRING_BYE; MAX_FIGHTERS],
+            fighter_attributes: [FighterAttributes::default().into(); MAX_FIGHTERS],
+            missed_reveals: [0; MAX_FIGHTERS],
+            missed_turns: [0; MAX_FIGHTERS],
+            last_move: [NO_LAST_MOVE; MAX_FIGHTERS],
+            consecutive_guards: [0; MAX_FIGHTERS],
+            poison_stacks: [0; MAX_FIGHTERS],
+            hp_overrides: [0; MAX_FIGHTERS],
+            history_hash: [0; 32],
+            keeper_paid: 0,
+            crit_seed: 0,
+            combat_start_slot: 0,
+            pairs_resolved: 0,
+            turn_chunked: 0,
+            fighter_slots: MAX_FIGHTERS_V1 as u8,
+            turn_start_hp: [0; MAX_FIGHTERS],
+            _padding: [0; 3],
+        };
+        combat.hp[..fighter_count as usize].fill(START_HP);
+        combat
+    }
 
-    let rumble_id_bytes = rumble.id.to_le_bytes();
-    let vault_seeds: &[&[u8]] = &[VAULT_SEED, rumble_id_bytes.as_ref(), &[vault_bump]];
-    let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+    /// Empty MoveCommitment PDAs for the first `count` fighters, as passed for
+    /// fighters that never committed.
+    #[cfg(feature = "combat")]
+    fn uncommitted_pdas(rumble: &Rumble, count: usize) -> Vec<(Pubkey, u64, Vec<u8>)> {
+        rumble.fighters[..count]
+            .iter()
+            .map(|fighter| {
+                (
+                    expected_move_commitment_pda(rumble.id, fighter),
+                    0,
+                    Vec::new(),
+                )
+            })
+            .collect()
+    }
 
-    system_program::transfer(
-        CpiContext::new_with_signer(
-            system_program_info,
-            system_program::Transfer {
-                from: vault_info,
-                to: treasury_info,
-            },
-            signer_seeds,
-        ),
-        treasury_cut,
-    )?;
+    #[cfg(feature = "combat")]
+    fn system_account_infos(accounts: &mut [(Pubkey, u64, Vec<u8>)]) -> Vec<AccountInfo<'_>> {
+        accounts
+            .iter_mut()
+            .map(|(key, lamports, data)| {
+                AccountInfo::new(
+                    key,
+                    false,
+                    false,
+                    lamports,
+                    data,
+                    &system_program::ID,
+                    false,
+                    0,
+                )
+            })
+            .collect()
+    }
 
-    msg!(
-        "Treasury cut extracted: {} lamports from rumble {}",
-        treasury_cut,
-        rumble.id
-    );
+    #[cfg(feature = "combat")]
+    #[test]
+    fn history_hash_can_be_rebuilt_from_turn_events() {
+        let mut combat = sample_combat(3);
+        let mut events = Vec::new();
+
+        let hp_before = combat.hp;
+        let mut moves = [NO_LAST_MOVE; MAX_FIGHTERS];
+        moves[0] = 1;
+        moves[1] = 3;
+        combat.hp[0] -= 12;
+        combat.hp[1] -= 30;
+        events.push(chain_turn_history(42, &mut combat, 1, moves, &hp_before));
+
+        let hp_before = combat.hp;
+        moves = [NO_LAST_MOVE; MAX_FIGHTERS];
+        moves[0] = 2;
+        moves[2] = 1;
+        combat.hp[2] = 0;
+        combat.elimination_rank[2] = 1;
+        events.push(chain_turn_history(42, &mut combat, 2, moves, &hp_before));
+
+        assert_eq!(events[0].damages[..3], [12, 30, 0]);
+        assert_eq!(events[1].damages[2], START_HP);
+        let mut rebuilt = [0u8; 32];
+        for event in &events {
+            rebuilt = turn_history_hash(
+                &rebuilt,
+                event.turn,
+                &event.moves,
+                &event.damages,
+                &event.eliminations,
+            );
+            assert_eq!(rebuilt, event.history_hash);
+        }
+        assert_eq!(rebuilt, combat.history_hash);
+
+        let mut tampered = events[1].moves.clone();
+        tampered[0] = 3;
+        let forged = turn_history_hash(
+            &events[0].history_hash,
+            2,
+            &tampered,
+            &events[1].damages,
+            &events[1].eliminations,
+        );
+        assert_ne!(forged, combat.history_hash);
+    }
 
-    Ok(())
-}
+    #[cfg(feature = "combat")]
+    #[test]
+    fn turn_pairings_are_mutual_with_one_bye_for_odd_counts() {
+        let mut rumble = sample_rumble();
+        rumble.fighter_count = 5;
+        for fighter in rumble.fighters[..5].iter_mut() {
+            *fighter = Pubkey::new_unique();
+        }
+        let mut combat = sample_combat(5);
+        combat.hp[3] = 0;
+        combat.elimination_rank[3] = 1;
+        combat.remaining_fighters = 4;
+        let attributes = [FighterAttributes::default(); MAX_FIGHTERS];
+
+        let pairings = compute_turn_pairings(&rumble, &combat, &attributes, 2);
+        assert_eq!(pairings[3], PAIRING_BYE);
+        for idx in [0usize, 1, 2, 4] {
+            let opponent = pairings[idx] as usize;
+            assert_ne!(opponent, idx);
+            assert_eq!(pairings[opponent] as usize, idx);
+        }
+        assert!(pairings[5..].iter().all(|p| *p == PAIRING_BYE));
+        assert_eq!(
+            pairings,
+            compute_turn_pairings(&rumble, &combat, &attributes, 2)
+        );
 
-fn transfer_from_vault<'info>(
-    vault_info: AccountInfo<'info>,
-    recipient_info: AccountInfo<'info>,
-    system_program_info: AccountInfo<'info>,
-    rumble_id: u64,
-    vault_bump: u8,
-    lamports: u64,
-) -> Result<()> {
-    if lamports == 0 {
-        return Ok(());
+        combat.hp[3] = START_HP;
+        combat.elimination_rank[3] = 0;
+        let odd = compute_turn_pairings(&rumble, &combat, &attributes, 2);
+        assert_eq!(odd[..5].iter().filter(|p| **p == PAIRING_BYE).count(), 1);
     }
 
-    let rumble_id_bytes = rumble_id.to_le_bytes();
-    let vault_seeds: &[&[u8]] = &[VAULT_SEED, rumble_id_bytes.as_ref(), &[vault_bump]];
-    let signer_seeds: &[&[&[u8]]] = &[vault_seeds];
+    #[cfg(feature = "combat")]
+    #[test]
+    fn attributes_scale_strike_damage_with_floor_of_one() {
+        let none = FighterAttributes::default();
+        let strong = FighterAttributes {
+            strength: 10,
+            ..FighterAttributes::default()
+        };
+        let tank = FighterAttributes {
+            defense: 15,
+            ..FighterAttributes::default()
+        };
 
-    system_program::transfer(
-        CpiContext::new_with_signer(
-            system_program_info,
-            system_program::Transfer {
-                from: vault_info,
-                to: recipient_info,
-            },
-            signer_seeds,
-        ),
-        lamports,
-    )?;
+        assert_eq!(
+            attribute_strike_damage(&DEFAULT_COMBAT_RULESET, MOVE_HIGH_STRIKE, &none, &none),
+            STRIKE_DAMAGE_HIGH
+        );
+        assert_eq!(
+            attribute_strike_damage(&DEFAULT_COMBAT_RULESET, MOVE_HIGH_STRIKE, &strong, &none),
+            STRIKE_DAMAGE_HIGH * 150 / 100
+        );
+        assert_eq!(
+            attribute_strike_damage(&DEFAULT_COMBAT_RULESET, MOVE_LOW_STRIKE, &none, &tank),
+            STRIKE_DAMAGE_LOW.saturating_sub(30).max(1)
+        );
 
-    Ok(())
-}
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
 
-// ---------------------------------------------------------------------------
-// Events
-// ---------------------------------------------------------------------------
+            MOVE_HIGH_STRIKE,
 
-#[event]
-pub struct BetPlacedEvent {
-    pub rumble_id: u64,
-    pub bettor: Pubkey,
-    pub fighter_index: u8,
-    pub amount: u64,
-    pub net_amount: u64,
-}
+            MOVE_MID_STRIKE,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct CombatStartedEvent {
-    pub rumble_id: u64,
-    pub timestamp: i64,
-}
+            0,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct ResultReportedEvent {
-    pub rumble_id: u64,
-    pub winner_index: u8,
-    pub timestamp: i64,
-}
+            0,
 
-#[event]
-pub struct PayoutClaimedEvent {
-    pub rumble_id: u64,
-    pub bettor: Pubkey,
-    pub fighter_index: u8,
-    pub placement: u8,
-    pub amount: u64,
-}
+            NO_LAST_MOVE,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct MoveCommittedEvent {
-    pub rumble_id: u64,
-    pub fighter: Pubkey,
-    pub turn: u32,
-    pub committed_slot: u64,
-}
+            NO_LAST_MOVE,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct FighterDelegateAuthorizedEvent {
-    pub fighter: Pubkey,
-    pub authority: Pubkey,
-    pub authorized_slot: u64,
-}
+            0,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct FighterDelegateRevokedEvent {
-    pub fighter: Pubkey,
-    pub authority: Pubkey,
-}
+            0,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct MoveRevealedEvent {
-    pub rumble_id: u64,
-    pub fighter: Pubkey,
-    pub turn: u32,
-    pub move_code: u8,
-    pub revealed_slot: u64,
-}
+            false,
+
+            false,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct TurnOpenedEvent {
-    pub rumble_id: u64,
-    pub turn: u32,
-    pub turn_open_slot: u64,
-    pub commit_close_slot: u64,
-    pub reveal_close_slot: u64,
-}
+            0,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct TurnPairResolvedEvent {
-    pub rumble_id: u64,
-    pub turn: u32,
-    pub fighter_a: Pubkey,
-    pub fighter_b: Pubkey,
-    pub move_a: u8,
-    pub move_b: u8,
-    pub damage_to_a: u16,
-    pub damage_to_b: u16,
-}
+            0,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct TurnResolvedEvent {
-    pub rumble_id: u64,
-    pub turn: u32,
-    pub remaining_fighters: u8,
-}
+            &strong,
 
-#[cfg(feature = "combat")]
-#[event]
-pub struct OnchainResultFinalizedEvent {
-    pub rumble_id: u64,
-    pub winner_index: u8,
-    pub timestamp: i64,
-}
+            &none,
 
-#[event]
-pub struct SponsorshipClaimedEvent {
-    pub fighter_owner: Pubkey,
-    pub fighter: Pubkey,
-    pub amount: u64,
-}
+            false,
 
-// ---------------------------------------------------------------------------
-// Errors
-// ---------------------------------------------------------------------------
+&DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(damage_to_a, STRIKE_DAMAGE_MID);
+        assert_eq!(damage_to_b, STRIKE_DAMAGE_HIGH * 150 / 100);
+    }
 
-#[error_code]
-pub enum RumbleError {
-    #[msg("Unauthorized: only admin can perform this action")]
-    Unauthorized,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn specializations_apply_passive_effects() {
+        let none = FighterAttributes::default();
+        let class = |specialization| FighterAttributes {
+            specialization: Some(specialization),
+            ..FighterAttributes::default()
+        };
 
-    #[msg("Betting is closed for this rumble")]
-    BettingClosed,
+        let (_, damage_to_b, _, _) = resolve_duel(
+            MOVE_MID_STRIKE,
+            MOVE_CATCH,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &class(Specialization::Brawler),
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(damage_to_b, STRIKE_DAMAGE_MID + BRAWLER_STRIKE_BONUS);
 
-    #[msg("Betting period has not ended yet")]
-    BettingNotEnded,
+        let (damage_to_a, _, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_GUARD_HIGH,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &class(Specialization::Duelist),
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(damage_to_a, COUNTER_DAMAGE * 2);
 
-    #[msg("Invalid state transition")]
-    InvalidStateTransition,
+        let (_, damage_to_b, _, _) = resolve_duel(
+            MOVE_SPECIAL,
+            MOVE_GUARD_LOW,
+            SPECIAL_METER_COST,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &class(Specialization::Guardian),
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(damage_to_b, SPECIAL_DAMAGE / 2);
 
-    #[msg("Invalid fighter index")]
-    InvalidFighterIndex,
+        let trickster = class(Specialization::Trickster);
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_LOW_STRIKE,
+            MOVE_DODGE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &trickster,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!((damage_to_a, damage_to_b), (COUNTER_DAMAGE, 0));
+        let (damage_to_a, _, _, _) = resolve_duel(
+            MOVE_LOW_STRIKE,
+            MOVE_DODGE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(damage_to_a, 0);
+    }
 
-    #[msg("Invalid fighter count: must be between 2 and 16")]
-    InvalidFighterCount,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn feint_reads_the_opponents_last_move() {
+        let none = FighterAttributes::default();
+        let feint_against = |last_move_b| {
+            resolve_duel(
+                MOVE_FEINT,
+                MOVE_GUARD_HIGH,
+                0,
+                0,
+                NO_LAST_MOVE,
+                last_move_b,
+                0,
+                0,
+                false,
+                false,
+                0,
+                0,
+                &none,
+                &none,
+                false,
+                &DEFAULT_COMBAT_RULESET,
+            )
+        };
 
-    #[msg("Invalid placement data")]
-    InvalidPlacement,
+        assert!(is_valid_move_code(MOVE_FEINT));
+        assert!(!is_valid_move_code(MOVE_FEINT + 1));
+        assert_eq!(feint_against(MOVE_LOW_STRIKE), (0, COUNTER_DAMAGE + 6, 0, 0));
+        assert_eq!(feint_against(MOVE_GUARD_MID), (0, STRIKE_DAMAGE_MID, 0, 0));
+        assert_eq!(feint_against(MOVE_DODGE), (0, 0, 0, 0));
+        assert_eq!(feint_against(MOVE_CATCH), (0, 0, 0, 0));
+        assert_eq!(feint_against(NO_LAST_MOVE), (0, 0, 0, 0));
+
+        // A Feint does not guard, so a strike this turn still lands on the feinter.
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_FEINT,
+            0,
+            0,
+            MOVE_MID_STRIKE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(
+            (damage_to_a, damage_to_b),
+            (COUNTER_DAMAGE + FEINT_COUNTER_BONUS, STRIKE_DAMAGE_HIGH)
+        );
+    }
 
-    #[msg("Bet amount must be greater than zero")]
-    ZeroBetAmount,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn guard_breaks_after_three_blocked_strikes() {
+        let none = FighterAttributes::default();
+        let strike_into_guard = |consecutive_guards_b| {
+            resolve_duel(
+                MOVE_MID_STRIKE,
+                MOVE_GUARD_MID,
+                0,
+                0,
+                NO_LAST_MOVE,
+                NO_LAST_MOVE,
+                0,
+                consecutive_guards_b,
+                false,
+                false,
+                0,
+                0,
+                &none,
+                &none,
+                false,
+                &DEFAULT_COMBAT_RULESET,
+            )
+        };
 
-    #[msg("Payout already claimed")]
-    AlreadyClaimed,
+        let mut streak = 0;
+        for _ in 0..GUARD_BREAK_THRESHOLD {
+            let (damage_to_a, damage_to_b, _, _) = strike_into_guard(streak);
+            assert_eq!((damage_to_a, damage_to_b), (COUNTER_DAMAGE, 0));
+            streak = next_consecutive_guards(streak, MOVE_GUARD_MID, MOVE_MID_STRIKE, damage_to_b);
+        }
+        assert_eq!(streak, GUARD_BREAK_THRESHOLD);
 
-    #[msg("Payout is not ready yet")]
-    PayoutNotReady,
+        let (damage_to_a, damage_to_b, _, _) = strike_into_guard(streak);
+        assert_eq!((damage_to_a, damage_to_b), (0, STRIKE_DAMAGE_MID * 2));
+        assert_eq!(
+            next_consecutive_guards(streak, MOVE_GUARD_MID, MOVE_MID_STRIKE, damage_to_b),
+            0
+        );
 
-    #[msg("Fighter did not win (winner-takes-all)")]
-    NotInPayoutRange,
+        // Guarding against a non-strike holds the streak; any other move resets it.
+        assert_eq!(next_consecutive_guards(2, MOVE_GUARD_LOW, MOVE_DODGE, 0), 2);
+        assert_eq!(next_consecutive_guards(2, MOVE_DODGE, MOVE_HIGH_STRIKE, 0), 0);
+    }
 
-    #[msg("Math overflow")]
-    MathOverflow,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn crits_add_half_to_landed_strikes_about_one_in_ten() {
+        let none = FighterAttributes::default();
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_LOW_STRIKE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            true,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!(damage_to_b, STRIKE_DAMAGE_HIGH * 3 / 2);
+        assert_eq!(damage_to_a, STRIKE_DAMAGE_LOW);
+
+        // Against a broken guard the crit applies on top of the doubling.
+        let none = FighterAttributes::default();
+        assert_eq!(
+            landed_strike_damage(
+                &DEFAULT_COMBAT_RULESET,
+                MOVE_MID_STRIKE,
+                &none,
+                &none,
+                true,
+                true
+            ),
+            STRIKE_DAMAGE_MID * 3
+        );
 
-    #[msg("Insufficient funds in vault")]
-    InsufficientVaultFunds,
+        // A crit does nothing for a strike that is guarded.
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_GUARD_HIGH,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            true,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!((damage_to_a, damage_to_b), (COUNTER_DAMAGE, 0));
 
-    #[msg("Invalid treasury address")]
-    InvalidTreasury,
+        let attacker = Pubkey::new_unique();
+        let defender = Pubkey::new_unique();
+        let crits = (0..10_000u64)
+            .filter(|seed| strike_is_crit(42, 3, &attacker, &defender, *seed))
+            .count();
+        assert!((900..1_100).contains(&crits), "unexpected crit count {crits}");
+    }
 
-    #[msg("Invalid rumble ID mismatch")]
-    InvalidRumble,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn crit_seed_is_fixed_when_the_turn_opens() {
+        let mut rumble = sample_rumble();
+        rumble.state = RumbleState::Combat;
+        for fighter in rumble.fighters.iter_mut().take(4) {
+            *fighter = Pubkey::new_unique();
+        }
+        let mut combat = sample_combat(4);
+        combat.current_turn = 0;
+        let config = sample_config();
+
+        // SlotHashes data: one entry, newest first.
+        let mut slot_hashes = 1u64.to_le_bytes().to_vec();
+        slot_hashes.extend_from_slice(&99u64.to_le_bytes());
+        slot_hashes.extend_from_slice(&[7u8; 32]);
+        let seed = slot_hash_crit_seed(&slot_hashes).unwrap();
+        assert_eq!(
+            slot_hash_crit_seed(&0u64.to_le_bytes()).err(),
+            Some(error!(RumbleError::MissingSlotHashes))
+        );
 
-    #[msg("Nothing to claim")]
-    NothingToClaim,
+        // Rumbles without crits never need the sysvar and never crit.
+        assert_eq!(turn_crit_seed(&rumble, None).unwrap(), 0);
+        assert_eq!(
+            duel_crits(&rumble, &combat, 1, (0, MOVE_HIGH_STRIKE), (1, MOVE_LOW_STRIKE)),
+            (false, false)
+        );
+        rumble.crits_enabled = true;
+        assert_eq!(
+            turn_crit_seed(&rumble, None).err(),
+            Some(error!(RumbleError::MissingSlotHashes))
+        );
 
-    #[msg("Betting deadline must be in the future")]
-    DeadlineInPast,
+        let clock = Clock {
+            slot: 100,
+            ..Clock::default()
+        };
+        open_next_turn(&config, &rumble, &mut combat, &clock, seed).unwrap();
+        assert_eq!(combat.crit_seed, seed);
+        // Reveals only open once the commit window closes, after the seed is stored.
+        assert!(combat.commit_close_slot > combat.turn_open_slot);
+
+        // Whoever resolves the turn, and whenever, gets the same rolls.
+        let rolls = |combat: &RumbleCombatState| {
+            (0..4)
+                .flat_map(|a| (0..4).map(move |b| (a, b)))
+                .filter(|(a, b)| a != b)
+                .map(|(a, b)| {
+                    duel_crits(&rumble, combat, 1, (a, MOVE_MID_STRIKE), (b, MOVE_LOW_STRIKE))
+                })
+                .collect::<Vec<_>>()
+        };
+        let at_open = rolls(&combat);
+        combat.turn_resolved = 1;
+        assert_eq!(rolls(&combat), at_open);
+    }
 
-    #[msg("Invalid fighter account data")]
-    InvalidFighterAccount,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn audience_boost_adds_a_point_per_sol_to_landed_strikes() {
+        let mut rumble = sample_rumble();
+        rumble.betting_pools[..3].copy_from_slice(&[999_999_999, 2_500_000_000, 40_000_000_000]);
+        assert_eq!(audience_boost(&rumble, 0), 0);
+        assert_eq!(audience_boost(&rumble, 1), 2);
+        assert_eq!(audience_boost(&rumble, 2), MAX_AUDIENCE_BOOST as u16);
+
+        let none = FighterAttributes::default();
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_GUARD_HIGH,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            2,
+            5,
+            &none,
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!((damage_to_a, damage_to_b), (COUNTER_DAMAGE, 0));
 
-    #[msg("Payout claim window is still active")]
-    ClaimWindowActive,
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_LOW_STRIKE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            2,
+            5,
+            &none,
+            &none,
+            false,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        assert_eq!((damage_to_a, damage_to_b), (STRIKE_DAMAGE_LOW + 5, STRIKE_DAMAGE_HIGH + 2));
+    }
 
-    #[msg("Invalid bettor account data")]
-    InvalidBettorAccount,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn low_strikes_poison_and_a_quiet_low_guard_cures() {
+        let rumble = sample_rumble();
+        let mut combat = sample_combat(2);
+
+        assert!(!low_strike_lands(MOVE_LOW_STRIKE, MOVE_DODGE, 0));
+        assert!(!low_strike_lands(MOVE_LOW_STRIKE, MOVE_GUARD_LOW, 0));
+        assert!(low_strike_lands(MOVE_LOW_STRIKE, MOVE_GUARD_LOW, GUARD_BREAK_THRESHOLD));
+        for _ in 0..POISON_STACK_THRESHOLD {
+            let hit = low_strike_lands(MOVE_LOW_STRIKE, MOVE_GUARD_HIGH, 0);
+            apply_duel_poison(&rumble, &mut combat, 1, 0, MOVE_GUARD_HIGH, MOVE_LOW_STRIKE, hit);
+        }
+        assert_eq!(combat.poison_stacks[0], POISON_STACK_THRESHOLD);
+
+        apply_poison_ticks(&rumble, &mut combat, 2).unwrap();
+        assert_eq!(combat.hp[0], START_HP - POISON_TICK_DAMAGE);
+        assert_eq!(combat.hp[1], START_HP);
+
+        // Low-guarding into an attack does not cure; into a guard it does.
+        apply_duel_poison(&rumble, &mut combat, 2, 0, MOVE_GUARD_LOW, MOVE_HIGH_STRIKE, false);
+        assert_eq!(combat.poison_stacks[0], POISON_STACK_THRESHOLD);
+        apply_duel_poison(&rumble, &mut combat, 2, 0, MOVE_GUARD_LOW, MOVE_GUARD_MID, false);
+        assert_eq!(combat.poison_stacks[0], 0);
+        assert_eq!(combat.hp[0], START_HP);
+
+        combat.poison_stacks[1] = POISON_STACK_THRESHOLD;
+        combat.hp[1] = POISON_TICK_DAMAGE;
+        apply_poison_ticks(&rumble, &mut combat, 3).unwrap();
+        assert_eq!(combat.elimination_rank[1], 1);
+        assert_eq!(combat.winner_index, 0);
+    }
 
-    #[msg("Invalid turn index")]
-    InvalidTurn,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn elimination_events_follow_the_rank_order() {
+        let mut rumble = sample_rumble();
+        for fighter in rumble.fighters[..4].iter_mut() {
+            *fighter = Pubkey::new_unique();
+        }
+        let mut combat = sample_combat(4);
+        combat.hp[..3].copy_from_slice(&[0, 0, 0]);
+        combat.total_damage_dealt[..3].copy_from_slice(&[10, 30, 30]);
 
-    #[msg("Invalid move commitment")]
-    InvalidMoveCommitment,
+        let events = rank_turn_eliminations(&rumble, &mut combat, vec![0, 2, 1]).unwrap();
 
-    #[msg("Invalid fighter delegate account")]
-    InvalidFighterDelegate,
+        // Most damage dealt goes out first; the index breaks the 30-30 tie.
+        let order: Vec<Pubkey> = events.iter().map(|event| event.fighter).collect();
+        assert_eq!(
+            order,
+            [rumble.fighters[1], rumble.fighters[2], rumble.fighters[0]]
+        );
+        let ranks: Vec<u8> = events.iter().map(|event| event.elimination_rank).collect();
+        assert_eq!(ranks, [1, 2, 3]);
+        assert_eq!(combat.elimination_rank[..4], [3, 1, 2, 0]);
+        assert!(events
+            .iter()
+            .all(|event| event.turn == combat.current_turn && event.rumble_id == rumble.id));
+        assert_eq!(events[2].damage_dealt_total, 10);
+    }
 
-    #[msg("Fighter delegate has been revoked")]
-    FighterDelegateRevoked,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn double_knockout_in_the_final_duel_revives_both_fighters() {
+        let rumble = sample_rumble();
+        let mut combat = sample_combat(3);
+        combat.hp[2] = 0;
+        assign_elimination_rank(&mut combat, 2).unwrap();
+        combat.hp[0] = 15;
+        combat.hp[1] = 30;
+
+        // Both land a strike in the final duel; sudden death finishes them both.
+        let none = FighterAttributes::default();
+        let (damage_to_a, damage_to_b, _, _) = resolve_duel(
+            MOVE_HIGH_STRIKE,
+            MOVE_MID_STRIKE,
+            0,
+            0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
+            0,
+            0,
+            false,
+            false,
+            0,
+            0,
+            &none,
+            &none,
+            true,
+            &DEFAULT_COMBAT_RULESET,
+        );
+        combat.hp[0] = combat.hp[0].saturating_sub(damage_to_a);
+        combat.hp[1] = combat.hp[1].saturating_sub(damage_to_b);
+        assert_eq!((combat.hp[0], combat.hp[1]), (0, 0));
+
+        let mut knocked_out = vec![0, 1];
+        revive_double_knockout(&rumble, &mut combat, 5, &mut knocked_out);
+        assert!(knocked_out.is_empty());
+        assert_eq!(combat.hp[..2], [DOUBLE_KO_REVIVE_HP; 2]);
+        assert_eq!(combat.remaining_fighters, 2);
+        assert_eq!(combat.elimination_rank[..2], [0, 0]);
+        assert_eq!(combat.winner_index, u8::MAX);
+
+        // With a third fighter still up, a double KO eliminates as usual.
+        let mut combat = sample_combat(3);
+        combat.hp[0] = 0;
+        combat.hp[1] = 0;
+        let mut knocked_out = vec![0, 1];
+        revive_double_knockout(&rumble, &mut combat, 5, &mut knocked_out);
+        assert_eq!(knocked_out, vec![0, 1]);
+        assert_eq!(combat.hp[..2], [0, 0]);
+
+        // Poison finishing off the last two fighters at once counts too.
+        let mut combat = sample_combat(2);
+        combat.hp[..2].fill(POISON_TICK_DAMAGE);
+        combat.poison_stacks[..2].fill(POISON_STACK_THRESHOLD);
+        apply_poison_ticks(&rumble, &mut combat, 6).unwrap();
+        assert_eq!(combat.hp[..2], [DOUBLE_KO_REVIVE_HP; 2]);
+        assert_eq!(combat.remaining_fighters, 2);
+    }
 
-    #[msg("Invalid move code")]
-    InvalidMoveCode,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn set_in_rumble_discriminator_matches_registry_instruction() {
+        let hash = Sha256::digest(b"global:set_in_rumble");
+        assert_eq!(SET_IN_RUMBLE_DISCRIMINATOR, hash[..8]);
+    }
 
-    #[msg("Move already revealed")]
-    AlreadyRevealedMove,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn rumble_discriminator_matches_account_derivation() {
+        let hash = Sha256::digest(b"account:Rumble");
+        assert_eq!(Rumble::DISCRIMINATOR, &hash[..8]);
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn update_record_ix_data_encodes_the_fighters_result() {
+        let hash = Sha256::digest(b"global:update_record");
+        assert_eq!(UPDATE_RECORD_DISCRIMINATOR, hash[..8]);
+
+        let data = update_record_ix_data(false, 40, 100, 7);
+        assert_eq!(data.len(), 8 + 7 * 8);
+        let args: Vec<u64> = data[8..]
+            .chunks(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(args, [0, 1, 40, 100, 0, 7, 100]);
+        assert_eq!(update_record_ix_data(true, 0, 0, 7)[8..16], 1u64.to_le_bytes());
+    }
+
+    #[test]
+    fn sponsorship_claims_pay_exactly_what_accrued() {
+        let rent = 890_880;
+        let fighter = Pubkey::new_unique();
+        let mut ledger = SponsorshipLedger {
+            fighter: Pubkey::default(),
+            total_accrued: 0,
+            total_claimed: 0,
+            last_rumble_id: 0,
+            bump: 0,
+        };
+
+        // First-ever sponsorship: the PDA is empty and the bettor funds rent.
+        open_sponsorship_ledger(&mut ledger, fighter, 255, 0, rent);
+        assert_eq!(ledger.total_accrued, 0);
+        ledger.total_accrued += 1_000;
+        assert_eq!(claimable_sponsorship(&ledger, rent + 1_000, rent).unwrap(), 1_000);
+
+        // A dust fee after a claim is still owed in full.
+        ledger.total_claimed += 1_000;
+        ledger.total_accrued += 1;
+        assert_eq!(claimable_sponsorship(&ledger, rent + 1, rent).unwrap(), 1);
+        let err = claimable_sponsorship(&ledger, rent, rent).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InsufficientVaultFunds));
+
+        // Reopening never resets an existing ledger.
+        open_sponsorship_ledger(&mut ledger, Pubkey::new_unique(), 1, 0, rent);
+        assert_eq!((ledger.fighter, ledger.total_accrued), (fighter, 1_001));
+
+        // Pre-ledger balances above rent carry over as accrued.
+        let mut legacy = SponsorshipLedger {
+            fighter: Pubkey::default(),
+            ..ledger
+        };
+        open_sponsorship_ledger(&mut legacy, fighter, 255, rent + 5_000, rent);
+        assert_eq!(claimable_sponsorship(&legacy, rent + 5_000, rent).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn fighter_registrations_check_owner_and_discriminator() {
+        fn check(owner: &Pubkey, data: &mut [u8], require_registered: bool) -> Result<()> {
+            let fighter = Pubkey::new_unique();
+            let unregistered = Pubkey::new_unique();
+            let mut lamports = 0u64;
+            let info = AccountInfo::new(&fighter, false, false, &mut lamports, data, owner, false, 0);
+            validate_fighter_registrations(&[info], &[fighter, unregistered], require_registered)
+        }
+
+        let mut data = [0u8; 40];
+        data[..8].copy_from_slice(&FIGHTER_ACCOUNT_DISCRIMINATOR);
+        assert!(check(&FIGHTER_REGISTRY_PROGRAM_ID, &mut data, false).is_ok());
+        let err = check(&FIGHTER_REGISTRY_PROGRAM_ID, &mut data, true).unwrap_err();
+        assert_eq!(err, error!(RumbleError::FighterNotRegistered));
+        let err = check(&crate::ID, &mut data, false).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFighterAccount));
+
+        data[0] ^= 1;
+        let err = check(&FIGHTER_REGISTRY_PROGRAM_ID, &mut data, false).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFighterAccount));
+    }
+
+    #[test]
+    fn treasury_update_waits_for_timelock() {
+        let mut config = sample_config();
+        let old_treasury = config.treasury;
+        let pending = PendingTreasury {
+            proposed_treasury: Pubkey::new_unique(),
+            effective_after_slot: 1_000 + TREASURY_UPDATE_DELAY_SLOTS,
+            bump: 255,
+        };
 
-    #[msg("Turn is already open")]
-    TurnAlreadyOpen,
+        // place_bet and sweeps check `treasury == config.treasury`, so fees
+        // keep going to the old treasury until the update is applied.
+        let err = apply_pending_treasury(&mut config, &pending, 1_000).unwrap_err();
+        assert_eq!(err, error!(RumbleError::TreasuryUpdateTimelocked));
+        let err =
+            apply_pending_treasury(&mut config, &pending, pending.effective_after_slot - 1)
+                .unwrap_err();
+        assert_eq!(err, error!(RumbleError::TreasuryUpdateTimelocked));
+        assert_eq!(config.treasury, old_treasury);
+
+        let replaced =
+            apply_pending_treasury(&mut config, &pending, pending.effective_after_slot).unwrap();
+        assert_eq!(replaced, old_treasury);
+        assert_eq!(config.treasury, pending.proposed_treasury);
+    }
 
-    #[msg("Turn is not open")]
-    TurnNotOpen,
+    #[test]
+    fn pending_admin_expires_after_window() {
+        let pending = PendingAdminRE {
+            proposed_admin: Pubkey::new_unique(),
+            proposed_at: 5_000,
+            bump: 255,
+        };
 
-    #[msg("Turn already resolved")]
-    TurnAlreadyResolved,
+        assert!(require_pending_admin_live(&pending, 5_000).is_ok());
+        assert!(require_pending_admin_live(&pending, 5_000 + PENDING_ADMIN_EXPIRY_SLOTS).is_ok());
+        let err = require_pending_admin_live(&pending, 5_001 + PENDING_ADMIN_EXPIRY_SLOTS)
+            .unwrap_err();
+        assert_eq!(err, error!(RumbleError::PendingAdminExpired));
+    }
 
-    #[msg("Turn is not resolved yet")]
-    TurnNotResolved,
+    #[test]
+    fn pause_switch_gates_until_unpaused() {
+        let mut config = sample_config();
+        assert!(require_not_paused(&config).is_ok());
 
-    #[msg("Commit window is closed")]
-    CommitWindowClosed,
+        config.paused = true;
+        let err = require_not_paused(&config).unwrap_err();
+        assert_eq!(err, error!(RumbleError::ProgramPaused));
 
-    #[msg("Reveal window is closed")]
-    RevealWindowClosed,
+        config.paused = false;
+        assert!(require_not_paused(&config).is_ok());
+    }
 
-    #[msg("Reveal window is still active")]
-    RevealWindowActive,
+    #[test]
+    fn leaderboard_keeps_top_fighters_sorted_by_wins() {
+        let mut entries = [LeaderboardEntry::default(); LEADERBOARD_SIZE];
+        let fighters: Vec<Pubkey> = (0..=LEADERBOARD_SIZE).map(|_| Pubkey::new_unique()).collect();
+        assert_eq!(insert_leaderboard_entry(&mut entries, fighters[0], 0), None);
 
-    #[msg("Combat already finished")]
-    CombatAlreadyFinished,
+        for (i, fighter) in fighters[..LEADERBOARD_SIZE].iter().enumerate() {
+            insert_leaderboard_entry(&mut entries, *fighter, 10 + i as u64);
+        }
+        assert!(entries.windows(2).all(|pair| pair[0].wins >= pair[1].wins));
+        assert_eq!(entries[LEADERBOARD_SIZE - 1].fighter, fighters[0]);
+
+        // Ties with the lowest entry don't evict it; more wins do.
+        let newcomer = fighters[LEADERBOARD_SIZE];
+        assert_eq!(insert_leaderboard_entry(&mut entries, newcomer, 10), None);
+        assert_eq!(
+            insert_leaderboard_entry(&mut entries, newcomer, 11),
+            Some(LEADERBOARD_SIZE - 1)
+        );
+        assert!(entries.iter().all(|entry| entry.fighter != fighters[0]));
 
-    #[msg("Combat is still active")]
-    CombatStillActive,
+        // A listed fighter moves up in place instead of being added twice.
+        assert_eq!(insert_leaderboard_entry(&mut entries, newcomer, 100), Some(0));
+        assert_eq!(entries.iter().filter(|entry| entry.fighter == newcomer).count(), 1);
+    }
 
-    #[msg("Max combat turns reached")]
-    MaxTurnsReached,
+    #[test]
+    fn leaderboard_fighter_must_sit_at_its_registry_pda() {
+        fn check(key: &Pubkey, data: &mut [u8]) -> Result<u64> {
+            let mut lamports = 0u64;
+            let owner = FIGHTER_REGISTRY_PROGRAM_ID;
+            let info = AccountInfo::new(key, false, false, &mut lamports, data, &owner, false, 0);
+            registry_fighter_wins(&info)
+        }
 
-    #[msg("Instruction is deprecated")]
-    DeprecatedInstruction,
+        let authority = Pubkey::new_unique();
+        let (fighter, bump) = Pubkey::find_program_address(
+            &[FIGHTER_SEED, authority.as_ref(), &[2]],
+            &FIGHTER_REGISTRY_PROGRAM_ID,
+        );
+        let mut data = vec![0u8; FIGHTER_QUEUE_POSITION_OFFSET + 1 + FIGHTER_INDEX_GAP];
+        data[..8].copy_from_slice(&FIGHTER_ACCOUNT_DISCRIMINATOR);
+        data[8..40].copy_from_slice(authority.as_ref());
+        data[FIGHTER_WINS_OFFSET..FIGHTER_WINS_OFFSET + 8].copy_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&[2, bump]);
+
+        assert_eq!(check(&fighter, &mut data).unwrap(), 7);
+        let err = check(&Pubkey::new_unique(), &mut data).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFighterAccount));
+    }
 
-    #[msg("Duplicate fighter in rumble")]
-    DuplicateFighter,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn fighter_attributes_parse_after_variable_queue_position() {
+        let mut data = vec![0u8; FIGHTER_QUEUE_POSITION_OFFSET + 1 + FIGHTER_ATTRIBUTES_GAP];
+        data[..8].copy_from_slice(&FIGHTER_ACCOUNT_DISCRIMINATOR);
+        data[FIGHTER_WINS_OFFSET..FIGHTER_WINS_OFFSET + 8].copy_from_slice(&12u64.to_le_bytes());
+        data[FIGHTER_CURRENT_STREAK_OFFSET..FIGHTER_CURRENT_STREAK_OFFSET + 8]
+            .copy_from_slice(&(-2i64).to_le_bytes());
+        let record = FighterAttributes {
+            wins: 12,
+            ..FighterAttributes::default()
+        };
+        assert_eq!(parse_fighter_attributes(&data), Some(record));
+
+        data[FIGHTER_CURRENT_STREAK_OFFSET..FIGHTER_CURRENT_STREAK_OFFSET + 8]
+            .copy_from_slice(&3i64.to_le_bytes());
+        data.extend_from_slice(&[4, 5, 6]);
+        let expected = FighterAttributes {
+            strength: 4,
+            speed: 5,
+            defense: 6,
+            specialization: None,
+            level: 0,
+            wins: 12,
+            win_streak: 3,
+        };
+        assert_eq!(parse_fighter_attributes(&data), Some(expected));
 
-    #[msg("Invalid rumble state for this operation")]
-    InvalidState,
+        data.extend_from_slice(&[1, 2]);
+        let expected = FighterAttributes {
+            specialization: Some(Specialization::Guardian),
+            ..expected
+        };
+        assert_eq!(parse_fighter_attributes(&data), Some(expected));
+
+        data.extend_from_slice(&[0; FIGHTER_LEVEL_GAP]);
+        data.push(3);
+        let expected = FighterAttributes { level: 3, ..expected };
+        assert_eq!(parse_fighter_attributes(&data), Some(expected));
+
+        assert!(!fighter_is_retired(&data));
+        data.push(1);
+        assert!(fighter_is_retired(&data));
+
+        let mut queued = data.clone();
+        queued[FIGHTER_QUEUE_POSITION_OFFSET] = 1;
+        queued.splice(
+            FIGHTER_QUEUE_POSITION_OFFSET + 1..FIGHTER_QUEUE_POSITION_OFFSET + 1,
+            7u64.to_le_bytes(),
+        );
+        assert_eq!(parse_fighter_attributes(&queued), Some(expected));
 
-    #[msg("Fighter has been eliminated")]
-    FighterEliminated,
+        data[0] ^= 1;
+        assert_eq!(parse_fighter_attributes(&data), None);
+    }
 
-    #[msg("Invalid fighter accounts provided")]
-    InvalidFighterAccounts,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn combat_snapshot_defaults_missing_fighter_accounts_to_base_stats() {
+        let mut rumble = sample_rumble();
+        rumble.fighter_count = 2;
+        let (fighter_a, fighter_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        rumble.fighters[..2].copy_from_slice(&[fighter_a, fighter_b]);
+        let fighter_data = |strength: u8| {
+            let mut data = vec![0u8; FIGHTER_QUEUE_POSITION_OFFSET + 1 + FIGHTER_ATTRIBUTES_GAP];
+            data[..8].copy_from_slice(&FIGHTER_ACCOUNT_DISCRIMINATOR);
+            data.extend_from_slice(&[strength, 0, 0, 0]);
+            data
+        };
+        let (mut data_a, mut data_b) = (fighter_data(4), fighter_data(9));
+        let (mut lamports_a, mut lamports_b) = (0u64, 0u64);
+        let owner = FIGHTER_REGISTRY_PROGRAM_ID;
+        let info_a = AccountInfo::new(
+            &fighter_a,
+            false,
+            false,
+            &mut lamports_a,
+            &mut data_a,
+            &owner,
+            false,
+            0,
+        );
+        let info_b = AccountInfo::new(
+            &fighter_b,
+            false,
+            false,
+            &mut lamports_b,
+            &mut data_b,
+            &owner,
+            false,
+            0,
+        );
 
-    #[msg("Posted damage does not match resolve_duel computation")]
-    DamageMismatch,
+        let attributes = snapshot_fighter_attributes(std::slice::from_ref(&info_a), &rumble).unwrap();
+        assert_eq!(attributes[0].strength, 4);
+        assert_eq!(attributes[1], FighterAttributes::default());
+
+        let attributes =
+            snapshot_fighter_attributes(&[info_b.clone(), info_a.clone()], &rumble).unwrap();
+        assert_eq!(attributes[0].strength, 4);
+        assert_eq!(attributes[1].strength, 9);
+
+        rumble.registration_required = true;
+        let err = snapshot_fighter_attributes(std::slice::from_ref(&info_a), &rumble).unwrap_err();
+        assert_eq!(err, error!(RumbleError::FighterNotRegistered));
+        let attributes = snapshot_fighter_attributes(&[info_b.clone(), info_a], &rumble).unwrap();
+        assert_eq!(attributes[1].strength, 9);
+        rumble.registration_required = false;
+
+        let stranger = Pubkey::new_unique();
+        let wrong_owner = AccountInfo { owner: &stranger, ..info_b.clone() };
+        let err = snapshot_fighter_attributes(&[wrong_owner], &rumble).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFighterAccount));
+        info_b.try_borrow_mut_data().unwrap()[0] ^= 0xff;
+        let err = snapshot_fighter_attributes(&[info_b], &rumble).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidFighterAccount));
+    }
 
-    #[msg("Invalid new admin address")]
-    InvalidNewAdmin,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn hp_overrides_bound_starting_hp() {
+        let mut overrides = [0u16; MAX_FIGHTERS];
+        overrides[..3].copy_from_slice(&[MIN_HP_OVERRIDE, 100, MAX_HP_OVERRIDE]);
+        assert!(validate_hp_overrides(&overrides, 3).is_ok());
+
+        let err = validate_hp_overrides(&overrides, 4).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidHpOverride));
+        let err = validate_hp_overrides(&overrides, 2).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidHpOverride));
+        overrides[2] = MAX_HP_OVERRIDE + 1;
+        let err = validate_hp_overrides(&overrides, 3).unwrap_err();
+        assert_eq!(err, error!(RumbleError::InvalidHpOverride));
+
+        let mut combat = sample_combat(2);
+        assert_eq!(starting_hp(&combat, 0, START_HP), START_HP);
+        combat.hp_overrides[0] = MAX_HP_OVERRIDE;
+        assert_eq!(starting_hp(&combat, 0, START_HP), MAX_HP_OVERRIDE);
+
+        assert_eq!(level_hp_bonus(0), 0);
+        assert_eq!(level_hp_bonus(4), 4);
+        assert_eq!(level_hp_bonus(u8::MAX), MAX_LEVEL_HP_BONUS);
+    }
 
-    #[msg("VRF matchup seed already set")]
-    VrfSeedAlreadySet,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn registry_stats_scale_starting_hp_and_meter_within_caps() {
+        let fresh = FighterAttributes::default();
+        assert_eq!(registry_hp_bonus(&fresh), 0);
+        assert_eq!(win_streak_meter(&fresh, SPECIAL_METER_COST), 0);
+
+        let record = |wins, level, win_streak| FighterAttributes {
+            wins,
+            level,
+            win_streak,
+            ..FighterAttributes::default()
+        };
+        assert_eq!(registry_hp_bonus(&record(7, 3, 0)), 10);
+        assert_eq!(registry_hp_bonus(&record(50, 0, 0)), MAX_REGISTRY_HP_BONUS);
+        assert_eq!(registry_hp_bonus(&record(u64::MAX, u8::MAX, 0)), MAX_REGISTRY_HP_BONUS);
+
+        assert_eq!(win_streak_meter(&record(0, 0, 2), SPECIAL_METER_COST), 10);
+        assert_eq!(
+            win_streak_meter(&record(0, 0, 1_000), SPECIAL_METER_COST),
+            MAX_WIN_STREAK_METER
+        );
+        // A cheap ruleset special still can't be fired on turn one.
+        assert_eq!(win_streak_meter(&record(0, 0, 1_000), 10), 9);
+
+        let mut combat = sample_combat(2);
+        combat.hp_overrides[0] = MAX_HP_OVERRIDE;
+        let veteran = record(50, 10, 9);
+        assert_eq!(
+            starting_hp(&combat, 0, START_HP) + registry_hp_bonus(&veteran),
+            MAX_HP_OVERRIDE + MAX_REGISTRY_HP_BONUS
+        );
+        assert_eq!(
+            starting_hp(&combat, 1, START_HP) + registry_hp_bonus(&fresh),
+            START_HP
+        );
+    }
 
-    #[msg("Winner claims are still outstanding")]
-    OutstandingWinnerClaims,
-}
+    #[cfg(feature = "combat")]
+    #[test]
+    fn missed_reveal_resets_meter_and_chips_hp() {
+        let mut combat = sample_combat(2);
+        combat.meter[0] = SPECIAL_METER_COST;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert_eq!(
+            apply_missed_reveal_penalty(&mut combat, 0).unwrap(),
+            MISSED_REVEAL_CHIP_DAMAGE
+        );
+        assert_eq!(combat.meter[0], 0);
+        assert_eq!(combat.hp[0], START_HP - MISSED_REVEAL_CHIP_DAMAGE);
+        assert_eq!(combat.total_damage_taken[0], MISSED_REVEAL_CHIP_DAMAGE as u64);
+        assert_eq!(combat.missed_reveals[0], 1);
+
+        combat.hp[1] = 2;
+        assert_eq!(apply_missed_reveal_penalty(&mut combat, 1).unwrap(), 2);
+        assert_eq!(combat.hp[1], 0);
+    }
 
-    fn sample_rumble() -> Rumble {
-        Rumble {
-            id: 42,
-            state: RumbleState::Complete,
-            fighters: [Pubkey::default(); 16],
-            fighter_count: 4,
-            betting_pools: [0; 16],
-            total_deployed: 0,
-            admin_fee_collected: 0,
-            sponsorship_paid: 0,
-            placements: [0; 16],
-            winner_index: 0,
-            betting_deadline: 0,
-            combat_started_at: 0,
-            completed_at: 0,
-            bump: 0,
+    #[cfg(feature = "combat")]
+    #[test]
+    fn move_commitment_pda_must_be_passed_even_when_never_created() {
+        let rumble_id = 9;
+        let fighter = Pubkey::new_unique();
+        let err = read_move_commitment_from_remaining_accounts(&[], rumble_id, 1, &fighter).err();
+        assert_eq!(err, Some(error!(RumbleError::MoveCommitmentMissing)));
+
+        // A fighter that never committed passes its empty, system-owned PDA.
+        let pda = expected_move_commitment_pda(rumble_id, &fighter);
+        let mut lamports = 0u64;
+        let mut data = Vec::new();
+        let owner = system_program::ID;
+        let info = AccountInfo::new(
+            &pda,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let read = read_move_commitment_from_remaining_accounts(&[info], rumble_id, 1, &fighter);
+        assert!(read.unwrap().is_none());
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn combat_log_appends_until_full_then_truncates() {
+        let mut combat_log = CombatLog {
+            rumble_id: 42,
+            count: 0,
+            truncated: false,
+            sealed: false,
+            bump: 255,
+            entries: [DuelLogEntry::default(); COMBAT_LOG_CAPACITY],
+        };
+        for turn in 0..COMBAT_LOG_CAPACITY as u32 + 2 {
+            let entry = DuelLogEntry {
+                turn,
+                damage_to_b: 7,
+                ..DuelLogEntry::default()
+            };
+            append_duel_log(&mut combat_log, entry).unwrap();
         }
+        assert_eq!(combat_log.count as usize, COMBAT_LOG_CAPACITY);
+        assert!(combat_log.truncated);
+        assert_eq!(combat_log.entries[COMBAT_LOG_CAPACITY - 1].turn, 255);
+
+        combat_log.sealed = true;
+        let err = append_duel_log(&mut combat_log, DuelLogEntry::default()).unwrap_err();
+        assert_eq!(err, error!(RumbleError::CombatLogSealed));
     }
 
+    #[cfg(feature = "combat")]
     #[test]
-    fn winner_pool_reads_zero_when_no_one_backed_the_winner() {
+    fn keeper_outage_is_recovered_turn_by_turn_not_finalized() {
         let mut rumble = sample_rumble();
-        rumble.placements = [2, 3, 1, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-        rumble.winner_index = 2;
+        rumble.state = RumbleState::Combat;
+        for fighter in rumble.fighters.iter_mut().take(4) {
+            *fighter = Pubkey::new_unique();
+        }
+        let config = sample_config();
+        let mut combat = sample_combat(4);
+        combat.current_turn = 0;
+        combat.combat_start_slot = 1_000;
+        let mut combat_log = CombatLog {
+            rumble_id: 42,
+            count: 0,
+            truncated: false,
+            sealed: false,
+            bump: 255,
+            entries: [DuelLogEntry::default(); COMBAT_LOG_CAPACITY],
+        };
+        let at = |slot: u64| Clock {
+            slot,
+            ..Clock::default()
+        };
 
-        assert_eq!(winner_pool_lamports(&rumble).unwrap(), 0);
+        open_next_turn(&config, &rumble, &mut combat, &at(1_000), 0).unwrap();
+        let reveal_close = combat.reveal_close_slot;
+
+        // Keeper goes dark for several hundred slots after the reveal window.
+        for slot in [reveal_close, reveal_close + STALE_TURN_GRACE_SLOTS - 1] {
+            assert!(!turn_is_stale(&combat, slot));
+        }
+        let outage_slot = reveal_close + 800;
+        assert!(turn_is_stale(&combat, outage_slot));
+        assert!(!combat_timed_out(&combat, config.combat_timeout_slots, outage_slot));
+
+        let mut commitments = uncommitted_pdas(&rumble, 4);
+        resolve_current_turn(
+            &rumble,
+            &DEFAULT_COMBAT_RULESET,
+            &mut combat,
+            &mut combat_log,
+            &system_account_infos(&mut commitments),
+            &at(outage_slot),
+        )
+        .unwrap();
+        assert_eq!(combat.turn_resolved, 1);
+        assert!(!turn_is_stale(&combat, outage_slot));
+        assert_eq!(combat_log.count, 2);
+        assert!(combat.missed_turns[..4].iter().all(|missed| *missed == 1));
+        assert_eq!(combat.remaining_fighters, 4);
+
+        // A later turn left open past the old per-turn 5000-slot timeout still
+        // can't be finalized; only the total elapsed since combat start counts.
+        open_next_turn(&config, &rumble, &mut combat, &at(outage_slot), 0).unwrap();
+        let much_later = combat.turn_open_slot + 5_001;
+        assert!(turn_is_stale(&combat, much_later));
+        assert!(!combat_timed_out(&combat, config.combat_timeout_slots, much_later));
+        assert!(combat_timed_out(
+            &combat,
+            config.combat_timeout_slots,
+            1_000 + config.combat_timeout_slots + 1
+        ));
+    }
+
+    #[cfg(feature = "combat")]
+    #[test]
+    fn combat_timeout_boundary_counts_from_combat_start() {
+        let config = sample_config();
+        let mut combat = sample_combat(4);
+        combat.combat_start_slot = 1_000;
+        let timeout = config.combat_timeout_slots;
+        assert!(!combat_timed_out(&combat, timeout, 1_000 + timeout));
+        assert!(combat_timed_out(&combat, timeout, 1_000 + timeout + 1));
+        // A full fight at the default windows ends before the default timeout.
+        let full_fight = MAX_ONCHAIN_COMBAT_TURNS as u64
+            * (COMMIT_WINDOW_SLOTS + REVEAL_WINDOW_SLOTS) as u64;
+        assert!(!combat_timed_out(&combat, timeout, 1_000 + full_fight));
     }
 
+    #[cfg(feature = "combat")]
     #[test]
-    fn winner_pool_reads_positive_balance_when_winner_has_claims() {
+    fn chunked_turn_matches_resolve_turn_and_enforces_pair_order() {
         let mut rumble = sample_rumble();
-        rumble.placements = [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-        rumble.betting_pools[0] = 980_000_000;
-        rumble.winner_index = 0;
+        rumble.state = RumbleState::Combat;
+        for fighter in rumble.fighters.iter_mut().take(6) {
+            *fighter = Pubkey::new_unique();
+        }
+        let config = sample_config();
+        let mut combat = sample_combat(6);
+        combat.current_turn = 0;
+        let mut combat_log = CombatLog {
+            rumble_id: 42,
+            count: 0,
+            truncated: false,
+            sealed: false,
+            bump: 255,
+            entries: [DuelLogEntry::default(); COMBAT_LOG_CAPACITY],
+        };
+        open_next_turn(&config, &rumble, &mut combat, &Clock::default(), 0).unwrap();
+        let clock = Clock {
+            slot: combat.reveal_close_slot,
+            ..Clock::default()
+        };
 
-        assert_eq!(winner_pool_lamports(&rumble).unwrap(), 980_000_000);
+        // Poison knocks one fighter out before any duel, leaving a bye.
+        let poisoned = (0..6).find(|i| combat.turn_pairings[*i] != PAIRING_BYE).unwrap();
+        combat.poison_stacks[poisoned] = POISON_STACK_THRESHOLD;
+        combat.hp[poisoned] = POISON_TICK_DAMAGE;
+
+        let mut commitments = uncommitted_pdas(&rumble, 6);
+        let commitments = system_account_infos(&mut commitments);
+        let mut whole = combat;
+        let mut whole_log = combat_log.clone();
+        resolve_current_turn(
+            &rumble,
+            &DEFAULT_COMBAT_RULESET,
+            &mut whole,
+            &mut whole_log,
+            &commitments,
+            &clock,
+        )
+        .unwrap();
+
+        let ruleset = DEFAULT_COMBAT_RULESET;
+        let mut pair = |combat: &mut RumbleCombatState, index: u8| {
+            resolve_chunked_pair(
+                &rumble,
+                &ruleset,
+                combat,
+                &mut combat_log,
+                &commitments,
+                &clock,
+                index,
+            )
+        };
+        assert_eq!(pair(&mut combat, 1).err(), Some(error!(RumbleError::PairOutOfOrder)));
+        pair(&mut combat, 0).unwrap();
+        assert_eq!(pair(&mut combat, 0).err(), Some(error!(RumbleError::PairAlreadyResolved)));
+        assert_eq!(pair(&mut combat, 2).err(), Some(error!(RumbleError::InvalidPairIndex)));
+        assert_eq!(
+            seal_chunked_turn(&rumble, &ruleset, &mut combat, &clock).err(),
+            Some(error!(RumbleError::TurnPairsUnresolved))
+        );
+        let mut mixed = combat;
+        assert_eq!(
+            resolve_current_turn(&rumble, &ruleset, &mut mixed, &mut whole_log, &[], &clock).err(),
+            Some(error!(RumbleError::ChunkedTurnInProgress))
+        );
+        pair(&mut combat, 1).unwrap();
+        seal_chunked_turn(&rumble, &ruleset, &mut combat, &clock).unwrap();
+
+        assert_eq!(combat.turn_resolved, 1);
+        assert_eq!(combat.hp, whole.hp);
+        assert_eq!(combat.meter, whole.meter);
+        assert_eq!(combat.elimination_rank, whole.elimination_rank);
+        assert_eq!(combat.remaining_fighters, whole.remaining_fighters);
+        assert_eq!(combat.history_hash, whole.history_hash);
+        assert_eq!(combat_log.count, whole_log.count);
+        assert_eq!(combat.elimination_rank[poisoned], 1);
     }
 
+    #[cfg(feature = "combat")]
     #[test]
-    fn validate_result_rejects_duplicate_first_place() {
-        let placements = [1, 1, 3, 4];
-        let err = validate_result_placements(&placements, 4, 0).unwrap_err();
-        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    fn inactivity_timeout_needs_consecutive_missed_turns() {
+        let mut combat = sample_combat(2);
+        combat.remaining_fighters = 2;
+
+        record_move_source(&mut combat, 0, false);
+        record_move_source(&mut combat, 0, false);
+        record_move_source(&mut combat, 0, true);
+        record_move_source(&mut combat, 0, false);
+        record_move_source(&mut combat, 0, false);
+        assert_eq!(combat.missed_turns[0], 2);
+        let err = eliminate_inactive_fighter(&mut combat, 0, 2).unwrap_err();
+        assert_eq!(err, error!(RumbleError::FighterStillActive));
+
+        record_move_source(&mut combat, 0, false);
+        eliminate_inactive_fighter(&mut combat, 0, 2).unwrap();
+        assert_eq!(combat.hp[0], 0);
+        assert_eq!(combat.elimination_rank[0], 1);
+        assert_eq!(combat.remaining_fighters, 1);
+        assert_eq!(combat.winner_index, 1);
     }
 
+    #[cfg(feature = "combat")]
     #[test]
-    fn validate_result_rejects_duplicate_rankings() {
-        let placements = [1, 2, 2, 4];
-        let err = validate_result_placements(&placements, 4, 0).unwrap_err();
-        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    fn posted_duel_must_match_stored_pairing() {
+        let mut combat = sample_combat(4);
+        combat.turn_pairings[..4].copy_from_slice(&[2, 3, 0, 1]);
+
+        assert!(validate_duel_pairing(&combat, 0, 2).is_ok());
+        assert!(validate_duel_pairing(&combat, 3, 1).is_ok());
+        let err = validate_duel_pairing(&combat, 0, 1).unwrap_err();
+        assert_eq!(err, error!(RumbleError::PairingMismatch));
     }
 
+    #[cfg(feature = "combat")]
     #[test]
-    fn payout_breakdown_requires_valid_result_shape() {
-        let mut rumble = sample_rumble();
-        rumble.betting_pools = [
-            980_000_000,
-            490_000_000,
-            245_000_000,
-            245_000_000,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-        ];
-        rumble.placements = [1, 1, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    fn forfeit_ranking_matches_elimination_order_and_crowns_last_fighter() {
+        let mut combat = sample_combat(3);
+
+        combat.hp[2] = 0;
+        assign_elimination_rank(&mut combat, 2).unwrap();
+        record_last_fighter_standing(&mut combat, 3);
+        assert_eq!(combat.elimination_rank[2], 1);
+        assert_eq!(combat.winner_index, u8::MAX);
+
+        combat.hp[0] = 0;
+        assign_elimination_rank(&mut combat, 0).unwrap();
+        assign_elimination_rank(&mut combat, 0).unwrap();
+        record_last_fighter_standing(&mut combat, 3);
+        assert_eq!(combat.elimination_rank[0], 2);
+        assert_eq!(combat.remaining_fighters, 1);
+        assert_eq!(combat.winner_index, 1);
+    }
 
-        let err = calculate_payout_breakdown(&rumble).unwrap_err();
-        assert_eq!(err, error!(RumbleError::InvalidPlacement));
+    #[cfg(feature = "combat")]
+    #[test]
+    fn surrender_concedes_only_live_fighters_between_turns() {
+        let mut combat = sample_combat(2);
+
+        combat.turn_resolved = 0;
+        assert_eq!(
+            concede_combat_slot(&mut combat, 0, 2).unwrap_err(),
+            error!(RumbleError::TurnNotResolved)
+        );
+
+        combat.turn_resolved = 1;
+        concede_combat_slot(&mut combat, 0, 2).unwrap();
+        assert_eq!(combat.hp[0], 0);
+        assert_eq!(combat.elimination_rank[0], 1);
+        assert_eq!(combat.remaining_fighters, 1);
+        // The last fighter standing wins, so finalize_rumble can run at once.
+        assert_eq!(combat.winner_index, 1);
+
+        assert_eq!(
+            concede_combat_slot(&mut combat, 0, 2).unwrap_err(),
+            error!(RumbleError::FighterEliminated)
+        );
     }
 
+    #[cfg(feature = "combat")]
     #[test]
-    fn payout_breakdown_uses_single_winner_take_all_math() {
+    fn rumble_ruleset_drives_duel_damage_and_meter_cost() {
+        let none = FighterAttributes::default();
         let mut rumble = sample_rumble();
-        rumble.betting_pools = [
-            980_000_000,
-            490_000_000,
-            245_000_000,
-            245_000_000,
-            0,
+        assert_eq!(rumble_ruleset(&rumble, None).unwrap().strike_damage_high, STRIKE_DAMAGE_HIGH);
+
+        // A rumble created under a ruleset can't silently fall back to the constants.
+        rumble.ruleset = Pubkey::new_unique();
+        assert_eq!(
+            rumble_ruleset(&rumble, None).err(),
+            Some(error!(RumbleError::RulesetMismatch))
+        );
+
+        let mut ruleset = DEFAULT_COMBAT_RULESET;
+        ruleset.strike_damage_high = 50;
+        ruleset.catch_damage = 60;
+        ruleset.special_meter_cost = 60;
+        let (damage_to_a, damage_to_b, meter_used_a, _) = resolve_duel(
+            MOVE_SPECIAL,
+            MOVE_HIGH_STRIKE,
+            60,
             0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
             0,
             0,
+            false,
+            false,
             0,
             0,
+            &none,
+            &none,
+            false,
+            &ruleset,
+        );
+        assert_eq!((damage_to_a, damage_to_b, meter_used_a), (50, SPECIAL_DAMAGE, 60));
+
+        let (_, damage_to_b, _, _) = resolve_duel(
+            MOVE_CATCH,
+            MOVE_DODGE,
             0,
             0,
+            NO_LAST_MOVE,
+            NO_LAST_MOVE,
             0,
             0,
+            false,
+            false,
             0,
             0,
-        ];
-        rumble.placements = [1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
-
-        let (first_pool, losers_pool, treasury_cut, distributable) =
-            calculate_payout_breakdown(&rumble).unwrap();
-        assert_eq!(first_pool, 980_000_000);
-        assert_eq!(losers_pool, 980_000_000);
-        assert_eq!(treasury_cut, 29_400_000);
-        assert_eq!(distributable, 950_600_000);
+            &none,
+            &none,
+            false,
+            &ruleset,
+        );
+        assert_eq!(damage_to_b, 60);
+
+        let mut params = CombatRulesetParams {
+            strike_damage_high: 50,
+            strike_damage_mid: 30,
+            strike_damage_low: 23,
+            catch_damage: 60,
+            counter_damage: 18,
+            special_damage: 52,
+            meter_per_turn: 20,
+            special_meter_cost: 60,
+            start_hp: 120,
+        };
+        assert!(validate_ruleset_params(&params).is_ok());
+        params.special_meter_cost = 0;
+        assert_eq!(
+            validate_ruleset_params(&params).unwrap_err(),
+            error!(RumbleError::InvalidRuleset)
+        );
     }
 
     #[cfg(feature = "combat")]
     #[test]
-    fn final_duel_sudden_death_forces_damage_even_on_double_dodge() {
-        let (damage_to_a, damage_to_b, meter_used_a, meter_used_b) =
-            resolve_duel(MOVE_DODGE, MOVE_DODGE, 0, 0, true);
-
-        assert_eq!(damage_to_a, FINAL_DUEL_SUDDEN_DEATH_CHIP);
-        assert_eq!(damage_to_b, FINAL_DUEL_SUDDEN_DEATH_CHIP);
-        assert_eq!(meter_used_a, 0);
-        assert_eq!(meter_used_b, 0);
-    }
+    fn keeper_bounties_never_touch_pools_through_full_combat() {
+        let rent = 890_880;
+        let fee = 5_000;
+        let mut rumble = sample_rumble();
+        rumble.vault_rent_reserve = rent;
+        rumble.keeper_budget = fee * KEEPER_BUDGET_CALLS;
+        rumble.betting_pools[..4].copy_from_slice(&[3_000_000, 1_000_000, 0, 2_000_000]);
+        rumble.total_deployed = 6_000_000;
+        rumble.prize_pool = 1_000_000;
+        let mut combat = sample_combat(4);
+        let mut vault = rent + rumble.keeper_budget + rumble.total_deployed + rumble.prize_pool;
+
+        let invariant = |rumble: &Rumble, combat: &RumbleCombatState, vault: u64| {
+            let budget_left = rumble.keeper_budget - combat.keeper_paid;
+            assert_eq!(
+                vault,
+                rumble.total_deployed + rumble.prize_pool + budget_left + rent
+            );
+            assert_eq!(unspent_keeper_budget(rumble, vault).unwrap(), budget_left);
+        };
 
-    #[cfg(feature = "combat")]
-    #[test]
-    fn final_duel_sudden_death_boosts_real_hits() {
-        let (damage_to_a, damage_to_b, _, _) =
-            resolve_duel(MOVE_HIGH_STRIKE, MOVE_MID_STRIKE, 0, 0, true);
+        // Every turn of the longest possible combat, then finalize.
+        for _ in 0..KEEPER_BUDGET_CALLS {
+            let bounty = keeper_bounty(&rumble, &combat, fee)
+                .min(unspent_keeper_budget(&rumble, vault).unwrap());
+            assert_eq!(bounty, fee);
+            combat.keeper_paid += bounty;
+            vault -= bounty;
+            invariant(&rumble, &combat, vault);
+        }
 
-        assert_eq!(damage_to_a, STRIKE_DAMAGE_MID + FINAL_DUEL_SUDDEN_DEATH_BONUS);
-        assert_eq!(damage_to_b, STRIKE_DAMAGE_HIGH + FINAL_DUEL_SUDDEN_DEATH_BONUS);
+        // Budget is spent: further calls go unpaid instead of dipping into pools.
+        assert_eq!(keeper_bounty(&rumble, &combat, fee), 0);
+        assert_eq!(unspent_keeper_budget(&rumble, vault).unwrap(), 0);
+        assert_eq!(vault, rent + rumble.total_deployed + rumble.prize_pool);
     }
 
     #[cfg(feature = "combat")]
     #[test]
-    fn fighter_delegate_authority_accepts_matching_delegate() {
-        let fighter = Pubkey::new_unique();
-        let authority = Pubkey::new_unique();
-        let delegate = FighterDelegate {
-            fighter,
-            authority,
-            authorized_slot: 1,
-            revoked: false,
-            bump: 255,
-        };
-
-        assert!(validate_fighter_delegate_authority(&delegate, &fighter, &authority).is_ok());
-    }
+    fn keeper_bounty_is_capped_by_budget_left_in_vault() {
+        let mut rumble = sample_rumble();
+        rumble.keeper_budget = 12_000;
+        rumble.total_deployed = 1_000_000;
+        let mut combat = sample_combat(2);
 
-    #[cfg(feature = "combat")]
-    #[test]
-    fn fighter_delegate_authority_rejects_wrong_authority() {
-        let fighter = Pubkey::new_unique();
-        let authority = Pubkey::new_unique();
-        let wrong_authority = Pubkey::new_unique();
-        let delegate = FighterDelegate {
-            fighter,
-            authority,
-            authorized_slot: 1,
-            revoked: false,
-            bump: 255,
-        };
+        combat.keeper_paid = 10_000;
+        assert_eq!(keeper_bounty(&rumble, &combat, 5_000), 2_000);
 
-        let err = validate_fighter_delegate_authority(&delegate, &fighter, &wrong_authority).unwrap_err();
-        assert_eq!(err, error!(RumbleError::Unauthorized));
+        // Vault short of its books: the pools still win over the keeper.
+        let vault = rumble.total_deployed + 500;
+        assert_eq!(unspent_keeper_budget(&rumble, vault).unwrap(), 500);
+        assert_eq!(unspent_keeper_budget(&rumble, rumble.total_deployed).unwrap(), 0);
     }
 
     #[cfg(feature = "combat")]
     #[test]
-    fn fighter_delegate_authority_rejects_revoked_delegate() {
-        let fighter = Pubkey::new_unique();
-        let authority = Pubkey::new_unique();
-        let delegate = FighterDelegate {
-            fighter,
-            authority,
-            authorized_slot: 1,
-            revoked: true,
-            bump: 255,
+    fn legacy_combat_state_migrates_to_zero_copy_layout() {
+        assert_eq!(COMBAT_STATE_LEN, 1872);
+
+        let mut hp = [0; MAX_FIGHTERS_V1];
+        hp[..3].copy_from_slice(&[70, 0, 12]);
+        let mut attributes = [FighterAttributes::default(); MAX_FIGHTERS_V1];
+        attributes[1].specialization = Some(Specialization::Guardian);
+        attributes[1].level = 3;
+        let legacy = LegacyRumbleCombatState {
+            rumble_id: 42,
+            fighter_count: 3,
+            current_turn: 9,
+            turn_open_slot: 100,
+            commit_close_slot: 110,
+            reveal_close_slot: 120,
+            turn_resolved: true,
+            remaining_fighters: 2,
+            winner_index: u8::MAX,
+            hp,
+            meter: [5; MAX_FIGHTERS_V1],
+            elimination_rank: [0; MAX_FIGHTERS_V1],
+            total_damage_dealt: [7; MAX_FIGHTERS_V1],
+            total_damage_taken: [8; MAX_FIGHTERS_V1],
+            vrf_seed: [1; 32],
+            bump: 254,
+            turn_pairings: [PAIRING_BYE; MAX_FIGHTERS_V1],
+            fighter_attributes: attributes,
+            missed_reveals: [0; MAX_FIGHTERS_V1],
+            missed_turns: [0; MAX_FIGHTERS_V1],
+            last_move: [NO_LAST_MOVE; MAX_FIGHTERS_V1],
+            consecutive_guards: [0; MAX_FIGHTERS_V1],
+            poison_stacks: [2; MAX_FIGHTERS_V1],
+            hp_overrides: [0; MAX_FIGHTERS_V1],
+            history_hash: [3; 32],
+            keeper_paid: 5_000,
+            crit_seed: 77,
+            combat_start_slot: 90,
+            pairs_resolved: 0b101,
+            turn_chunked: true,
+            turn_start_hp: hp,
         };
-
-        let err = validate_fighter_delegate_authority(&delegate, &fighter, &authority).unwrap_err();
-        assert_eq!(err, error!(RumbleError::FighterDelegateRevoked));
+        let mut data = RumbleCombatState::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert!(data.len() < COMBAT_STATE_LEN);
+
+        let combat = migrated_combat_state(&data, 42).unwrap();
+        assert_eq!(combat.rumble_id, 42);
+        assert_eq!(combat.current_turn, 9);
+        assert_eq!(combat.reveal_close_slot, 120);
+        assert_eq!((combat.turn_resolved, combat.turn_chunked), (1, 1));
+        assert_eq!(combat.pairs_resolved, 0b101);
+        assert_eq!(combat.hp[..MAX_FIGHTERS_V1], hp);
+        assert_eq!(combat.hp[MAX_FIGHTERS_V1..], [0; MAX_FIGHTERS - MAX_FIGHTERS_V1]);
+        assert_eq!(combat.turn_pairings, [PAIRING_BYE; MAX_FIGHTERS]);
+        assert_eq!(combat.last_move, [NO_LAST_MOVE; MAX_FIGHTERS]);
+        assert_eq!(combat.slots(), MAX_FIGHTERS_V1);
+        assert_eq!(combat.keeper_paid, 5_000);
+        assert_eq!(combat.history_hash, [3; 32]);
+        assert_eq!(combat.attributes()[..MAX_FIGHTERS_V1], attributes);
+
+        assert_eq!(
+            migrated_combat_state(&data, 43).err(),
+            Some(error!(RumbleError::InvalidRumble))
+        );
+        data.resize(COMBAT_STATE_LEN, 0);
+        assert_eq!(
+            migrated_combat_state(&data, 42).err(),
+            Some(error!(RumbleError::CombatStateAlreadyMigrated))
+        );
     }
 
     #[cfg(feature = "mainnet")]
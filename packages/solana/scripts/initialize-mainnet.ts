@@ -86,6 +86,10 @@ async function main() {
     RUMBLE_ENGINE_ID
   );
   console.log("\nRumble Config PDA:", rumbleConfigPda.toBase58());
+  const [globalStatsPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("global_stats")],
+    RUMBLE_ENGINE_ID
+  );
   console.log("Program ID:", RUMBLE_ENGINE_ID.toBase58());
 
   // Load IDL and create program
@@ -116,6 +120,7 @@ async function main() {
           config: rumbleConfigPda,
           treasury: treasury,
           systemProgram: SystemProgram.programId,
+          globalStats: globalStatsPda,
         })
         .rpc();
       console.log("Rumble Engine initialized on MAINNET! Tx:", tx);
@@ -114,6 +114,18 @@ async function main() {
   );
   console.log("Rumble Config PDA:", rumbleConfigPda.toBase58());
 
+  const [globalStatsPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("global_stats")],
+    RUMBLE_ENGINE_ID
+  );
+  console.log("Global Stats PDA:", globalStatsPda.toBase58());
+
+  const [adminLogPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("admin_log"), rumbleConfigPda.toBuffer()],
+    RUMBLE_ENGINE_ID
+  );
+  console.log("Admin Log PDA:", adminLogPda.toBase58());
+
   // ---------------------------------------------------------------------------
   // Load IDLs and create programs
   // ---------------------------------------------------------------------------
@@ -265,10 +277,41 @@ async function main() {
           config: rumbleConfigPda,
           treasury: deployer.publicKey, // treasury = deployer for now
           systemProgram: SystemProgram.programId,
+          globalStats: globalStatsPda,
         })
         .rpc();
       console.log("Rumble Engine initialized. Tx:", tx);
     }
+
+    // The admin log is created on its own, for new and existing deployments.
+    if (!(await connection.getAccountInfo(adminLogPda))) {
+      const tx = await (rumbleEngine.methods as any)
+        .initializeAdminLog()
+        .accounts({
+          admin: deployer.publicKey,
+          config: rumbleConfigPda,
+          adminLog: adminLogPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      console.log("Admin log initialized. Tx:", tx);
+    }
+
+    // Deployments initialized before GlobalStats existed create it separately.
+    // Like every admin call, this needs the admin log created above.
+    if (acctInfo && !(await connection.getAccountInfo(globalStatsPda))) {
+      const tx = await (rumbleEngine.methods as any)
+        .initializeGlobalStats()
+        .accounts({
+          admin: deployer.publicKey,
+          config: rumbleConfigPda,
+          globalStats: globalStatsPda,
+          systemProgram: SystemProgram.programId,
+          adminLog: adminLogPda,
+        })
+        .rpc();
+      console.log("Global stats initialized. Tx:", tx);
+    }
   } catch (err: any) {
     if (err.message?.includes("already in use")) {
       console.log("Rumble Engine already initialized (account in use).");
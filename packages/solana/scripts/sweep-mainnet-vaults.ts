@@ -159,7 +159,7 @@ async function main() {
     try {
       const [rumblePda] = deriveRumblePda(rumbleId);
       const method = (program.methods as any)
-        .sweepTreasury()
+        .sweepTreasury(false)
         .accounts({
           admin: admin.publicKey,
           config: configPda,
@@ -48,6 +48,14 @@ describe("fighter-registry", () => {
   const REGISTRY_SEED = Buffer.from("registry_config");
   const WALLET_STATE_SEED = Buffer.from("wallet_state");
   const FIGHTER_SEED = Buffer.from("fighter");
+  const FIGHTER_NAME_SEED = Buffer.from("fname");
+  const HEAD_TO_HEAD_SEED = Buffer.from("h2h");
+
+  const fighterNamePda = (nameBytes: Uint8Array) =>
+    PublicKey.findProgramAddressSync(
+      [FIGHTER_NAME_SEED, Buffer.from(nameBytes)],
+      program.programId
+    )[0];
 
   let registryConfigPda: PublicKey;
   let registryConfigBump: number;
@@ -129,6 +137,7 @@ describe("fighter-registry", () => {
         authority: oldAuthority.publicKey,
         walletState: walletStatePda,
         fighter: fighterPda,
+        fighterName: fighterNamePda(nameBytes),
         registryConfig: registryConfigPda,
         ichorTokenAccount: null,
         ichorMint: null,
@@ -150,6 +159,71 @@ describe("fighter-registry", () => {
     const walletState = await program.account.walletState.fetch(walletStatePda);
     assert.ok(walletState.authority.equals(oldAuthority.publicKey));
     assert.equal(walletState.fighterCount, 1);
+
+    // Verify the name is claimed by this fighter
+    const fighterName = await program.account.fighterNameRegistry.fetch(
+      fighterNamePda(nameBytes)
+    );
+    assert.ok(fighterName.fighter.equals(fighterPda));
+  });
+
+  // -----------------------------------------------------------------------
+  // Names are unique and must not be empty
+  // -----------------------------------------------------------------------
+  it("Rejects a taken or empty fighter name", async () => {
+    const rival = Keypair.generate();
+    const airdropSig = await provider.connection.requestAirdrop(
+      rival.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+
+    const [walletStatePda] = PublicKey.findProgramAddressSync(
+      [WALLET_STATE_SEED, rival.publicKey.toBuffer()],
+      program.programId
+    );
+    const [fighterPda] = PublicKey.findProgramAddressSync(
+      [FIGHTER_SEED, rival.publicKey.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+
+    const register = (nameBytes: Uint8Array) =>
+      program.methods
+        .registerFighter(Array.from(nameBytes) as any)
+        .accounts({
+          authority: rival.publicKey,
+          walletState: walletStatePda,
+          fighter: fighterPda,
+          fighterName: fighterNamePda(nameBytes),
+          registryConfig: registryConfigPda,
+          ichorTokenAccount: null,
+          ichorMint: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .signers([rival])
+        .rpc();
+
+    // Same name as the fighter registered above
+    const takenName = new Uint8Array(32);
+    const nameStr = "TestFighter";
+    for (let i = 0; i < nameStr.length && i < 32; i++) {
+      takenName[i] = nameStr.charCodeAt(i);
+    }
+    try {
+      await register(takenName);
+      assert.fail("Duplicate name should have been rejected");
+    } catch (err) {
+      // System program rejects re-creating the name PDA (custom error 0x0)
+      expect(err.toString()).to.match(/already in use|0x0/);
+    }
+
+    try {
+      await register(new Uint8Array(32));
+      assert.fail("Empty name should have been rejected");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidName");
+    }
   });
 
   // -----------------------------------------------------------------------
@@ -319,6 +393,7 @@ describe("fighter-registry", () => {
         authority: queueAuthority.publicKey,
         walletState: walletStatePda,
         fighter: fighterPda,
+        fighterName: fighterNamePda(nameBytes),
         registryConfig: registryConfigPda,
         ichorTokenAccount: null,
         ichorMint: null,
@@ -393,4 +468,280 @@ describe("fighter-registry", () => {
       expect(err.toString()).to.include("MustLeaveQueueFirst");
     }
   });
+
+  // -----------------------------------------------------------------------
+  // in_rumble blocks queueing and transfer until it is cleared
+  // -----------------------------------------------------------------------
+  it("Rejects queue and transfer while fighter is in a rumble", async () => {
+    // Admin flips in_rumble directly here; rumble-engine does the same via
+    // CPI signed by its registry_authority PDA.
+    const rumbleAuthority = Keypair.generate();
+    const airdropSig = await provider.connection.requestAirdrop(
+      rumbleAuthority.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+
+    const [walletStatePda] = PublicKey.findProgramAddressSync(
+      [WALLET_STATE_SEED, rumbleAuthority.publicKey.toBuffer()],
+      program.programId
+    );
+    const [fighterPda] = PublicKey.findProgramAddressSync(
+      [FIGHTER_SEED, rumbleAuthority.publicKey.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+
+    const nameBytes = new Uint8Array(32);
+    const nameStr = "RumblingFighter";
+    for (let i = 0; i < nameStr.length && i < 32; i++) {
+      nameBytes[i] = nameStr.charCodeAt(i);
+    }
+
+    await program.methods
+      .registerFighter(Array.from(nameBytes) as any)
+      .accounts({
+        authority: rumbleAuthority.publicKey,
+        walletState: walletStatePda,
+        fighter: fighterPda,
+        fighterName: fighterNamePda(nameBytes),
+        registryConfig: registryConfigPda,
+        ichorTokenAccount: null,
+        ichorMint: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([rumbleAuthority])
+      .rpc();
+
+    const setInRumble = (inRumble: boolean) =>
+      program.methods
+        .setInRumble(inRumble)
+        .accounts({
+          authority: admin.publicKey,
+          registryConfig: registryConfigPda,
+          fighter: fighterPda,
+        } as any)
+        .rpc();
+
+    await setInRumble(true);
+    let fighter = await program.account.fighter.fetch(fighterPda);
+    assert.ok(fighter.inRumble, "Fighter should be flagged in_rumble");
+
+    // A fighter already in a rumble cannot be entered into a second one
+    try {
+      await setInRumble(true);
+      assert.fail("Second rumble entry should have been rejected");
+    } catch (err) {
+      expect(err.toString()).to.include("InRumble");
+    }
+
+    // Only admin or the engine authority may flip the flag
+    try {
+      await program.methods
+        .setInRumble(false)
+        .accounts({
+          authority: rumbleAuthority.publicKey,
+          registryConfig: registryConfigPda,
+          fighter: fighterPda,
+        } as any)
+        .signers([rumbleAuthority])
+        .rpc();
+      assert.fail("Fighter owner should not be able to clear in_rumble");
+    } catch (err) {
+      expect(err.toString()).to.include("Unauthorized");
+    }
+
+    try {
+      await program.methods
+        .joinQueue(new anchor.BN(1), false)
+        .accounts({
+          authority: rumbleAuthority.publicKey,
+          fighter: fighterPda,
+        } as any)
+        .signers([rumbleAuthority])
+        .rpc();
+      assert.fail("Queue join should have been rejected while in a rumble");
+    } catch (err) {
+      expect(err.toString()).to.include("InRumble");
+    }
+
+    const mintInfo = await provider.connection.getAccountInfo(
+      EXPECTED_ICHOR_MINT
+    );
+    if (!mintInfo) {
+      console.log(
+        "  SKIPPED transfer check: Canonical ICHOR mint not available."
+      );
+    } else {
+      const dest = Keypair.generate();
+      const [newWalletStatePda] = PublicKey.findProgramAddressSync(
+        [WALLET_STATE_SEED, dest.publicKey.toBuffer()],
+        program.programId
+      );
+      const ichorTokenAccount = await createAccount(
+        provider.connection,
+        (provider.wallet as any).payer || rumbleAuthority,
+        EXPECTED_ICHOR_MINT,
+        rumbleAuthority.publicKey
+      );
+      try {
+        await program.methods
+          .transferFighter()
+          .accounts({
+            oldAuthority: rumbleAuthority.publicKey,
+            newAuthority: dest.publicKey,
+            fighter: fighterPda,
+            oldWalletState: walletStatePda,
+            newWalletState: newWalletStatePda,
+            ichorMint: EXPECTED_ICHOR_MINT,
+            ichorTokenAccount: ichorTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .signers([rumbleAuthority])
+          .rpc();
+        assert.fail("Transfer should have been rejected while in a rumble");
+      } catch (err) {
+        expect(err.toString()).to.include("InRumble");
+      }
+    }
+
+    await setInRumble(false);
+    fighter = await program.account.fighter.fetch(fighterPda);
+    assert.ok(!fighter.inRumble, "in_rumble should be cleared");
+  });
+
+  // -----------------------------------------------------------------------
+  // A delegate can manage the queue until revoked
+  // -----------------------------------------------------------------------
+  it("Lets a delegate join and leave the queue until revoked", async () => {
+    const owner = Keypair.generate();
+    const delegate = Keypair.generate();
+    const airdropSig = await provider.connection.requestAirdrop(
+      owner.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdropSig);
+
+    const [walletStatePda] = PublicKey.findProgramAddressSync(
+      [WALLET_STATE_SEED, owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const [fighterPda] = PublicKey.findProgramAddressSync(
+      [FIGHTER_SEED, owner.publicKey.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
+
+    const nameBytes = new Uint8Array(32);
+    const nameStr = "DelegatedFighter";
+    for (let i = 0; i < nameStr.length && i < 32; i++) {
+      nameBytes[i] = nameStr.charCodeAt(i);
+    }
+
+    await program.methods
+      .registerFighter(Array.from(nameBytes) as any)
+      .accounts({
+        authority: owner.publicKey,
+        walletState: walletStatePda,
+        fighter: fighterPda,
+        fighterName: fighterNamePda(nameBytes),
+        registryConfig: registryConfigPda,
+        ichorTokenAccount: null,
+        ichorMint: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    await program.methods
+      .delegateFighter(delegate.publicKey)
+      .accounts({ authority: owner.publicKey, fighter: fighterPda } as any)
+      .signers([owner])
+      .rpc();
+
+    await program.methods
+      .joinQueue(new anchor.BN(1), false)
+      .accounts({ authority: delegate.publicKey, fighter: fighterPda } as any)
+      .signers([delegate])
+      .rpc();
+    let fighter = await program.account.fighter.fetch(fighterPda);
+    assert.equal(fighter.queuePosition.toNumber(), 1);
+
+    await program.methods
+      .leaveQueue()
+      .accounts({ authority: delegate.publicKey, fighter: fighterPda } as any)
+      .signers([delegate])
+      .rpc();
+    fighter = await program.account.fighter.fetch(fighterPda);
+    assert.isNull(fighter.queuePosition);
+
+    // Only the owner may change the delegate
+    try {
+      await program.methods
+        .revokeDelegation()
+        .accounts({ authority: delegate.publicKey, fighter: fighterPda } as any)
+        .signers([delegate])
+        .rpc();
+      assert.fail("Delegate should not be able to revoke itself");
+    } catch (err) {
+      expect(err.toString()).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .revokeDelegation()
+      .accounts({ authority: owner.publicKey, fighter: fighterPda } as any)
+      .signers([owner])
+      .rpc();
+
+    try {
+      await program.methods
+        .joinQueue(new anchor.BN(2), false)
+        .accounts({ authority: delegate.publicKey, fighter: fighterPda } as any)
+        .signers([delegate])
+        .rpc();
+      assert.fail("Revoked delegate should not be able to join the queue");
+    } catch (err) {
+      expect(err.toString()).to.include("Unauthorized");
+    }
+  });
+
+  it("Records head-to-head wins for a sorted fighter pair", async () => {
+    const [fighterA, fighterB] = [Keypair.generate(), Keypair.generate()]
+      .map((k) => k.publicKey)
+      .sort((a, b) => Buffer.compare(a.toBuffer(), b.toBuffer()));
+    const [h2hPda] = PublicKey.findProgramAddressSync(
+      [HEAD_TO_HEAD_SEED, fighterA.toBuffer(), fighterB.toBuffer()],
+      program.programId
+    );
+
+    const recordH2h = (rumbleId: number, a: PublicKey, b: PublicKey, winnerIdx: number) =>
+      program.methods
+        .recordH2h(new anchor.BN(rumbleId), a, b, winnerIdx)
+        .accounts({
+          admin: admin.publicKey,
+          registryConfig: registryConfigPda,
+          headToHead: h2hPda,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .rpc();
+
+    await recordH2h(1, fighterA, fighterB, 0);
+    await recordH2h(2, fighterA, fighterB, 1);
+    await recordH2h(3, fighterA, fighterB, 0);
+
+    const h2h = await program.account.headToHead.fetch(h2hPda);
+    assert.ok(h2h.fighterA.equals(fighterA));
+    assert.equal(h2h.winsA, 2);
+    assert.equal(h2h.winsB, 1);
+    assert.equal(h2h.lastRumbleId.toNumber(), 3);
+
+    // The same rumble can't be counted twice
+    try {
+      await recordH2h(3, fighterA, fighterB, 1);
+      assert.fail("Repeat rumble should have been rejected");
+    } catch (err) {
+      expect(err.toString()).to.include("RecordAlreadyUpdated");
+    }
+  });
 });
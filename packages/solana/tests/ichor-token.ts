@@ -76,6 +76,11 @@ describe("ichor-token", () => {
     assert.equal(arenaConfig.totalRumblesCompleted.toNumber(), 0);
     assert.equal(arenaConfig.baseReward.toNumber(), ONE_ICHOR.toNumber());
     assert.equal(arenaConfig.seasonReward.toNumber(), 2_500 * ONE_ICHOR.toNumber());
+    assert.equal(arenaConfig.showerChance.toNumber(), 500);
+    assert.equal(arenaConfig.showerBurnBps, 1000);
+    assert.equal(arenaConfig.showerCooldownSlots.toNumber(), 1000);
+    assert.ok(arenaConfig.maxShowerPerDay.eq(ONE_ICHOR.muln(100_000)));
+    assert.equal(arenaConfig.refillRequireAdmin, false);
     assert.equal(arenaConfig.ichorShowerPool.toNumber(), 0);
   });
 